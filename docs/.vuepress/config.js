@@ -156,6 +156,8 @@ export default defineUserConfig({
                 { text: 'reducel3', link: '/user_guide/reduce/reducel3.md' },
                 { text: 'all', link: '/user_guide/reduce/all.md' },
                 { text: 'any', link: '/user_guide/reduce/any.md' },
+                { text: 'reduce_axes', link: '/user_guide/reduce/reduce_axes.md' },
+                { text: 'accumulation', link: '/user_guide/reduce/accumulation.md' },
               ]
             },
             {
@@ -182,6 +184,7 @@ export default defineUserConfig({
                 { text: 'tensor_where', link: '/user_guide/advanced/tensor_where.md' },
                 { text: 'topk', link: '/user_guide/advanced/topk.md' },
                 { text: 'onehot', link: '/user_guide/advanced/onehot.md' },
+                { text: 'pad_with_mode', link: '/user_guide/advanced/pad_with_mode.md' },
               ]
             },
             {
@@ -280,6 +283,7 @@ export default defineUserConfig({
                 { text: 'ones_like', link: '/user_guide/creation/ones_like.md' },
                 { text: 'full', link: '/user_guide/creation/full.md' },
                 { text: 'full_like', link: '/user_guide/creation/full_like.md' },
+                { text: 'from_fn', link: '/user_guide/creation/from_fn.md' },
                 { text: 'arange', link: '/user_guide/creation/arange.md' },
                 { text: 'arange_step', link: '/user_guide/creation/arange_step.md' },
                 { text: 'eye', link: '/user_guide/creation/eye.md' },
@@ -299,6 +303,11 @@ export default defineUserConfig({
                 { text: 'hamming_window', link: '/user_guide/windows/hamming_window.md' },
                 { text: 'hann_window', link: '/user_guide/windows/hann_window.md' },
                 { text: 'blackman_window', link: '/user_guide/windows/blackman_window.md' },
+                { text: 'kaiser_window', link: '/user_guide/windows/kaiser_window.md' },
+                { text: 'bartlett_window', link: '/user_guide/windows/bartlett_window.md' },
+                { text: 'gaussian_window', link: '/user_guide/windows/gaussian_window.md' },
+                { text: 'tukey_window', link: '/user_guide/windows/tukey_window.md' },
+                { text: 'general_cosine_window', link: '/user_guide/windows/general_cosine_window.md' },
               ]
             },
             {
@@ -312,12 +321,27 @@ export default defineUserConfig({
                 { text: 'strided_map', link: '/user_guide/iterator/strided_map.md' },
                 { text: 'strided_map_simd', link: '/user_guide/iterator/strided_map_simd.md' },
                 { text: 'collect', link: '/user_guide/iterator/collect.md' },
+                { text: 'windows', link: '/user_guide/iterator/windows.md' },
+                { text: 'axis_iter', link: '/user_guide/iterator/axis_iter.md' },
+                { text: 'indexed_iter', link: '/user_guide/iterator/indexed_iter.md' },
               ]
             },
+            {
+              text: '8-bit floats',
+              link: '/user_guide/f8/f8.md'
+            },
+            {
+              text: 'quantization',
+              link: '/user_guide/quantize/quantize.md'
+            },
             {
               text: 'custom type',
               link: '/user_guide/custom_type/custom_type.md'
             },
+            {
+              text: 'custom operator',
+              link: '/user_guide/custom_op/custom_op.md'
+            },
             {
               text: 'slice',
               link: '/user_guide/slice/slice.md'
@@ -325,6 +349,34 @@ export default defineUserConfig({
             {
               text: 'save/load',
               link: '/user_guide/save_load/save_load.md'
+            },
+            {
+              text: 'kernel fusion',
+              link: '/user_guide/fusion/compile.md'
+            },
+            {
+              text: 'lazy mode',
+              link: '/user_guide/fusion/lazy.md'
+            },
+            {
+              text: 'SIMD dispatch',
+              link: '/user_guide/simd/simd_dispatch.md'
+            },
+            {
+              text: 'execution context',
+              link: '/user_guide/context/context.md'
+            },
+            {
+              text: 'DynTensor',
+              link: '/user_guide/dyn_tensor/dyn_tensor.md'
+            },
+            {
+              text: 'ONNX',
+              link: '/user_guide/onnx/onnx.md'
+            },
+            {
+              text: 'printing',
+              link: '/user_guide/display/display.md'
             }
           ]
         }
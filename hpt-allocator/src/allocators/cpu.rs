@@ -10,6 +10,9 @@ use hpt_common::error::base::TensorError;
 use lru::LruCache;
 use once_cell::sync::Lazy;
 
+/// a callback that releases memory owned by another library, see [`CpuAllocator::insert_foreign_ptr`]
+pub type ForeignDeleter = Box<dyn FnOnce() + Send>;
+
 /// `lru` cache allocator
 pub static CACHE: Lazy<Mutex<CpuAllocator>> = Lazy::new(|| Mutex::new(CpuAllocator::new()));
 
@@ -28,6 +31,7 @@ pub static CACHE: Lazy<Mutex<CpuAllocator>> = Lazy::new(|| Mutex::new(CpuAllocat
 /// developer must carefully manage the reference count of the pointer allocated
 pub struct CpuAllocator {
     allocator: HashMap<usize, _Allocator>,
+    released: Vec<ForeignDeleter>,
 }
 
 impl Allocator for CpuAllocator {
@@ -35,10 +39,7 @@ impl Allocator for CpuAllocator {
         if let Some(allocator) = self.allocator.get_mut(&device_id) {
             allocator.allocate(layout, device_id)
         } else {
-            let mut allocator = _Allocator {
-                cache: LruCache::new(NonZeroUsize::new(100).unwrap()),
-                allocated: HashSet::new(),
-            };
+            let mut allocator = _Allocator::new();
             let ptr = allocator.allocate(layout, device_id)?;
             self.allocator.insert(device_id, allocator);
             Ok(ptr)
@@ -46,7 +47,9 @@ impl Allocator for CpuAllocator {
     }
     fn deallocate(&mut self, ptr: *mut u8, layout: &Layout, device_id: usize) {
         if let Some(allocator) = self.allocator.get_mut(&device_id) {
-            allocator.deallocate(ptr, layout, device_id);
+            if let Some(deleter) = allocator.deallocate(ptr, layout, device_id) {
+                self.released.push(deleter);
+            }
         } else {
             panic!("device {} not found in allocator", device_id);
         }
@@ -55,10 +58,7 @@ impl Allocator for CpuAllocator {
         if let Some(allocator) = self.allocator.get_mut(&device_id) {
             allocator.insert_ptr(ptr, device_id);
         } else {
-            let mut allocator = _Allocator {
-                cache: LruCache::new(NonZeroUsize::new(100).unwrap()),
-                allocated: HashSet::new(),
-            };
+            let mut allocator = _Allocator::new();
             allocator.insert_ptr(ptr, device_id);
             self.allocator.insert(device_id, allocator);
         }
@@ -82,16 +82,73 @@ impl CpuAllocator {
     pub fn new() -> Self {
         CpuAllocator {
             allocator: HashMap::new(),
+            released: Vec::new(),
         }
     }
+
+    /// # Insert Foreign Pointer
+    ///
+    /// register memory owned by another library (DLPack producer, memory map, ...) with reference count 1
+    ///
+    /// `ptr` is only used as the key of the reference count, it must not be a ptr allocated by hpt
+    ///
+    /// when the reference count drops to 0, the memory won't be cached, the `deleter` will be moved to the released list instead
+    ///
+    /// # Note
+    ///
+    /// the `deleter` may need to lock `CACHE`, caller must drain the released deleters by [`CpuAllocator::take_released_foreign`] and call them after the lock is released
+    pub fn insert_foreign_ptr(&mut self, ptr: *mut u8, device_id: usize, deleter: ForeignDeleter) {
+        self.allocator
+            .entry(device_id)
+            .or_insert_with(_Allocator::new)
+            .insert_foreign_ptr(ptr, device_id, deleter);
+    }
+
+    /// # Insert Foreign Memory
+    ///
+    /// register memory owned by another library with reference count 1, the key of the reference count is allocated by the allocator and returned
+    ///
+    /// the key is a unique address owned by the allocator, it is freed together with the `deleter` once the reference count drops to 0
+    ///
+    /// # Note
+    ///
+    /// same as [`CpuAllocator::insert_foreign_ptr`], the released deleters must be called after the lock is released
+    pub fn insert_foreign(&mut self, device_id: usize, deleter: ForeignDeleter) -> *mut u8 {
+        let key = Box::into_raw(Box::new(0u8));
+        let addr = key as usize;
+        self.insert_foreign_ptr(
+            key,
+            device_id,
+            Box::new(move || {
+                deleter();
+                // the key is only released once, when its reference count reaches 0
+                drop(unsafe { Box::from_raw(addr as *mut u8) });
+            }),
+        );
+        key
+    }
+
+    /// take the deleters of the foreign memory whose reference count reached 0
+    pub fn take_released_foreign(&mut self) -> Vec<ForeignDeleter> {
+        std::mem::take(&mut self.released)
+    }
 }
 
 struct _Allocator {
     cache: LruCache<Layout, Vec<SafePtr>>,
     allocated: HashSet<SafePtr>,
+    foreign: HashMap<SafePtr, ForeignDeleter>,
 }
 
 impl _Allocator {
+    fn new() -> Self {
+        _Allocator {
+            cache: LruCache::new(NonZeroUsize::new(100).unwrap()),
+            allocated: HashSet::new(),
+            foreign: HashMap::new(),
+        }
+    }
+
     fn allocate(&mut self, layout: Layout, device_id: usize) -> Result<*mut u8, TensorError> {
         if let Ok(mut storage) = CPU_STORAGE.lock() {
             crate::utils::allocate::allocate_helper(
@@ -113,8 +170,24 @@ impl _Allocator {
     /// deallocate memory based on the ptr provided, if the ptr is found in the storage, decrement the reference count
     ///
     /// if the reference count is 0, remove the ptr from the storage, remove the ptr from the allocated set, and insert the ptr into the cache
-    fn deallocate(&mut self, ptr: *mut u8, layout: &Layout, device_id: usize) {
+    ///
+    /// if the ptr is foreign memory and the reference count is 0, the deleter is returned
+    fn deallocate(
+        &mut self,
+        ptr: *mut u8,
+        layout: &Layout,
+        device_id: usize,
+    ) -> Option<ForeignDeleter> {
         if let Ok(mut storage) = CPU_STORAGE.lock() {
+            if self.foreign.contains_key(&SafePtr { ptr }) {
+                let storage = storage
+                    .get_mut(&device_id)
+                    .unwrap_or_else(|| panic!("device {} not found in storage", device_id));
+                if storage.decrement_ref(SafePtr { ptr }) {
+                    return self.foreign.remove(&SafePtr { ptr });
+                }
+                return None;
+            }
             crate::utils::deallocate::deallocate_helper(
                 &mut self.cache,
                 &mut self.allocated,
@@ -123,9 +196,24 @@ impl _Allocator {
                 ptr,
                 device_id,
             );
+            None
+        } else {
+            panic!("Failed to lock CPU_STORAGE");
+        }
+    }
+
+    /// # Insert Foreign Pointer
+    ///
+    /// increment the reference count in the storage, and keep the deleter until the reference count reaches 0
+    fn insert_foreign_ptr(&mut self, ptr: *mut u8, device_id: usize, deleter: ForeignDeleter) {
+        if let Ok(mut map) = CPU_STORAGE.lock() {
+            map.entry(device_id)
+                .or_insert_with(CommonStorage::new)
+                .increment_ref(SafePtr { ptr });
         } else {
             panic!("Failed to lock CPU_STORAGE");
         }
+        self.foreign.insert(SafePtr { ptr }, deleter);
     }
 
     /// # Insert Pointer
@@ -12,7 +12,7 @@ pub(crate) mod utils {
 /// traits for the allocator
 pub mod traits;
 
-pub use crate::allocators::cpu::CACHE;
+pub use crate::allocators::cpu::{ForeignDeleter, CACHE};
 #[cfg(feature = "cuda")]
 pub use crate::allocators::cuda::CUDA_CACHE;
 pub use crate::storage::clone_storage;
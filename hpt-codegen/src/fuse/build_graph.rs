@@ -11,7 +11,9 @@ use super::{
     errors::Error,
     kernel_type::KernelType,
     node::{Binary, Node, Operand, Unary},
-    operator_lists::{BINARY_OPERATORS, OPAQUE_BINARY_OPERATORS, UNARY_OPERATORS},
+    operator_lists::{
+        BINARY_OPERATORS, OPAQUE_BINARY_OPERATORS, REDUCE_OPERATORS, UNARY_OPERATORS,
+    },
     ty_infer::Type,
     variable_collector::VariableCollector,
 };
@@ -28,6 +30,8 @@ pub(crate) struct CmpNode {
     pub(crate) id: NodeIndex,
     pub(crate) ident: Operand,
     pub(crate) method: Option<syn::Ident>,
+    /// the arguments of the method that are not tensors, such as the axes of a reduction
+    pub(crate) method_args: Vec<Operand>,
 }
 
 impl ToTokens for CmpNode {
@@ -155,6 +159,7 @@ impl Graph {
                     id: node_index_map[&unary.output],
                     ident: unary.output.clone(),
                     method: Some(unary.method.clone()),
+                    method_args: unary.args.clone(),
                 },
                 (Node::Binary(binary), stmt_idx, block_idx) => {
                     let mut args = vec![];
@@ -175,6 +180,7 @@ impl Graph {
                         id: node_index_map[&binary.output],
                         ident: binary.output.clone(),
                         method: Some(binary.method.clone()),
+                        method_args: vec![],
                     }
                 }
                 (Node::Input(input), stmt_idx, block_idx) => CmpNode {
@@ -188,6 +194,7 @@ impl Graph {
                     id: node_index_map[input],
                     ident: input.clone(),
                     method: None,
+                    method_args: vec![],
                 },
             };
             let idx = graph.add_node(data);
@@ -208,6 +215,7 @@ impl Graph {
                             id: node_index_map[&unary.operand],
                             ident: unary.operand.clone(),
                             method: None,
+                            method_args: vec![],
                         });
                         added_nodes.insert(index);
                     }
@@ -226,6 +234,7 @@ impl Graph {
                                 id: node_index_map[&binary.left],
                                 ident: binary.left.clone(),
                                 method: None,
+                                method_args: vec![],
                             });
                             added_nodes.insert(index);
                         }
@@ -243,6 +252,7 @@ impl Graph {
                                 id: node_index_map[&binary.right],
                                 ident: binary.right.clone(),
                                 method: None,
+                                method_args: vec![],
                             });
                             added_nodes.insert(index);
                         }
@@ -845,7 +855,8 @@ impl<'ast> syn::visit::Visit<'ast> for Graph {
         let is_binary = BINARY_OPERATORS.contains(&method_name.as_str());
         let is_opaque_binary = OPAQUE_BINARY_OPERATORS.contains(&method_name.as_str());
         let is_unary = UNARY_OPERATORS.contains(&method_name.as_str());
-        if !is_unary && !is_binary && !is_opaque_binary {
+        let is_reduce = REDUCE_OPERATORS.contains(&method_name.as_str());
+        if !is_unary && !is_binary && !is_opaque_binary && !is_reduce {
             return;
         }
         let current_assignment = if let Some(assingment) = self.current_assignment.clone() {
@@ -862,7 +873,10 @@ impl<'ast> syn::visit::Visit<'ast> for Graph {
         };
         let mut args = vec![];
         for arg in &node.args {
-            if let Some(ident) = extract_expr_ident(arg) {
+            if is_reduce {
+                // the axes and keep_dims are passed to the fused kernel as they are
+                args.push(Operand::Constant(arg.clone()));
+            } else if let Some(ident) = extract_expr_ident(arg) {
                 args.push(Operand::Variable(ident));
             } else {
                 self.errors
@@ -870,13 +884,17 @@ impl<'ast> syn::visit::Visit<'ast> for Graph {
                 return;
             }
         }
-        if is_unary {
+        if is_unary || is_reduce {
             let method = Node::Unary(Unary {
                 method: node.method.clone(),
                 operand: receiver_var.clone(),
                 args,
                 output: Operand::Variable(current_assignment.clone()),
-                kernel_type: KernelType::Unary,
+                kernel_type: if is_reduce {
+                    KernelType::Reduce
+                } else {
+                    KernelType::Unary
+                },
             });
             self.nodes
                 .push((method, self.current_idx as i64, self.current_block));
@@ -898,6 +916,8 @@ impl<'ast> syn::visit::Visit<'ast> for Graph {
                     output: Operand::Variable(current_assignment.clone()),
                     kernel_type: if is_binary {
                         KernelType::Binary
+                    } else if method_name == "matmul" {
+                        KernelType::MatmulEpilogue
                     } else {
                         KernelType::Opaque
                     },
@@ -931,7 +951,7 @@ impl<'ast> syn::visit::Visit<'ast> for Graph {
         let left = if let Some(ident) = extract_expr_ident(&node.left) {
             Operand::Variable(ident)
         } else if let syn::Expr::Lit(lit) = node.left.as_ref() {
-            Operand::Constant(syn::Expr::Lit(lit.clone()))
+            Operand::Constant(syn::Expr::Lit(typed_literal(lit)))
         } else {
             self.errors
                 .push(Error::ExpectedIdentifier(node.left.span(), "build graph"));
@@ -940,7 +960,7 @@ impl<'ast> syn::visit::Visit<'ast> for Graph {
         let right = if let Some(ident) = extract_expr_ident(&node.right) {
             Operand::Variable(ident)
         } else if let syn::Expr::Lit(lit) = node.right.as_ref() {
-            Operand::Constant(syn::Expr::Lit(lit.clone()))
+            Operand::Constant(syn::Expr::Lit(typed_literal(lit)))
         } else {
             self.errors
                 .push(Error::ExpectedIdentifier(node.right.span(), "build graph"));
@@ -1014,3 +1034,25 @@ fn extract_expr_ident(node: &syn::Expr) -> Option<syn::Ident> {
         _ => None,
     }
 }
+
+/// give unsuffixed literals the type rust would infer for them in the unfused code (`f64` and `i32`),
+/// the fused closures are generic over the operand types so the literal can't be inferred there
+fn typed_literal(lit: &syn::ExprLit) -> syn::ExprLit {
+    let mut lit = lit.clone();
+    match &lit.lit {
+        syn::Lit::Float(float) if float.suffix().is_empty() => {
+            lit.lit = syn::Lit::Float(syn::LitFloat::new(
+                &format!("{}f64", float.base10_digits()),
+                float.span(),
+            ));
+        }
+        syn::Lit::Int(int) if int.suffix().is_empty() => {
+            lit.lit = syn::Lit::Int(syn::LitInt::new(
+                &format!("{}i32", int.base10_digits()),
+                int.span(),
+            ));
+        }
+        _ => {}
+    }
+    lit
+}
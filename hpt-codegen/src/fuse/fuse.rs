@@ -118,6 +118,16 @@ pub(crate) fn cmp_fuse<'ast>(
 pub(crate) fn cmp_yield_candidate<'a>(
     unfused_candidates: &mut petgraph::stable_graph::StableGraph<CmpNode, ()>,
 ) -> Option<NodeIndex> {
+    // the matmuls and reductions are visited first, so they can grab their consumers and producers
+    // before those are fused into an elementwise kernel
+    for kernel_type in [KernelType::MatmulEpilogue, KernelType::Reduce] {
+        let anchor = unfused_candidates
+            .node_indices()
+            .find(|x| unfused_candidates[*x].kernel_type == kernel_type);
+        if anchor.is_some() {
+            return anchor;
+        }
+    }
     let unary = unfused_candidates
         .node_indices()
         .find(|x| unfused_candidates[*x].kernel_type == KernelType::Unary);
@@ -143,9 +153,18 @@ pub fn cmp_fuse_parents(
         .node_weight(pred)
         .expect(format!("node weight not found {:?}, ", pred).as_str());
     let fused_kernel_type = cmp_pred_kernel_fusable(next_kernel_type, node.kernel_type);
+    // a producer computed inside a reduction is gone once the reduction is done
+    if next_kernel_type == KernelType::Reduce && !is_private(pred, block, unfused, basic_block) {
+        return;
+    }
     if let Some(kernel_type) = fused_kernel_type {
         block.insert(pred);
-        if !unfused
+        if kernel_type == KernelType::Reduce {
+            // the whole private producer chain of a reduction is computed in its inner loop
+            for inp in unfused.neighbors_directed(pred, petgraph::Direction::Incoming) {
+                cmp_fuse_parents(inp, kernel_type, block, unfused, basic_block);
+            }
+        } else if !unfused
             .neighbors_directed(pred, petgraph::Direction::Incoming)
             .count()
             == 0
@@ -157,6 +176,21 @@ pub fn cmp_fuse_parents(
     }
 }
 
+/// check if `node` is only used by the nodes in `block`
+fn is_private(
+    node: NodeIndex,
+    block: &HashSet<NodeIndex>,
+    unfused: &petgraph::stable_graph::StableGraph<CmpNode, ()>,
+    basic_block: &crate::fuse::cfg::BasicBlock,
+) -> bool {
+    let live_out = match &unfused[node].ident {
+        super::node::Operand::Constant(_) => false,
+        super::node::Operand::Variable(ident) => basic_block.live_out.contains(ident),
+    };
+    // `outputs` still has the consumers that were already fused into other kernels
+    !live_out && unfused[node].outputs.iter().all(|x| block.contains(x))
+}
+
 pub fn cmp_fuse_children(
     succ: NodeIndex,
     prev_kernel_type: KernelType,
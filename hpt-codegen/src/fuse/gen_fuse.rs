@@ -2,28 +2,34 @@ use super::{
     build_graph::CmpNode,
     errors::Error,
     fuse::{FusionGroup, Input},
+    kernel_type::KernelType,
     node::Operand,
 };
 use petgraph::graph::NodeIndex;
 use proc_macro2::TokenStream as TokenStream2;
-use std::collections::HashSet;
+use quote::ToTokens;
+use std::collections::{HashMap, HashSet};
+use syn::{spanned::Spanned, visit_mut::VisitMut};
 
 pub(crate) fn cmp_gen_fuse(
     cfg: &mut crate::fuse::cfg::CFG,
     graph: &petgraph::stable_graph::StableGraph<CmpNode, ()>,
     groups: &FusionGroup,
+    graph_idx: usize,
 ) -> Vec<(TokenStream2, TokenStream2)> {
-    _cmp_gen_fuse(cfg, &graph, &groups)
+    _cmp_gen_fuse(cfg, &graph, &groups, graph_idx)
 }
 
+/// generate the body of the fused closure, the constants are splatted to simd vectors if `simd` is true
 fn cmp_gen_body(
-    sorted: Vec<NodeIndex>,
+    sorted: &[NodeIndex],
     inputs: &HashSet<Input>,
     graph: &petgraph::stable_graph::StableGraph<CmpNode, ()>,
     cfg: &crate::fuse::cfg::CFG,
+    simd: bool,
 ) -> proc_macro2::TokenStream {
     let mut comp_tokens = proc_macro2::TokenStream::new();
-    for idx in sorted {
+    for &idx in sorted {
         let mut node = graph[idx].clone();
         if !inputs.iter().any(|input| input.comp_graph_idx == idx) {
             if let Operand::Variable(ident) = &node.ident {
@@ -43,12 +49,19 @@ fn cmp_gen_body(
                 }
             }
             for (idx, inp) in node.args_ident.clone().into_iter().enumerate() {
-                if let Operand::Variable(inp) = &inp {
-                    let origin_inp = cfg.graph[NodeIndex::new(node.block_idx)]
-                        .origin_var_map
-                        .get(inp)
-                        .expect("gen_fuse::out");
-                    node.args_ident[idx] = Operand::Variable(origin_inp.clone());
+                match &inp {
+                    Operand::Variable(inp) => {
+                        let origin_inp = cfg.graph[NodeIndex::new(node.block_idx)]
+                            .origin_var_map
+                            .get(inp)
+                            .expect("gen_fuse::out");
+                        node.args_ident[idx] = Operand::Variable(origin_inp.clone());
+                    }
+                    Operand::Constant(constant) if simd => {
+                        node.args_ident[idx] =
+                            Operand::Constant(syn::parse_quote!(__hpt_splat(#constant)));
+                    }
+                    Operand::Constant(_) => {}
                 }
             }
             comp_tokens.extend(quote::quote!(
@@ -64,11 +77,11 @@ pub(crate) fn _cmp_gen_fuse(
     cfg: &mut crate::fuse::cfg::CFG,
     graph: &petgraph::stable_graph::StableGraph<CmpNode, ()>,
     groups: &FusionGroup,
+    graph_idx: usize,
 ) -> Vec<(TokenStream2, TokenStream2)> {
     // println!("graph: {:#?}", graph);
     let sorteds = petgraph::algo::toposort(graph, None).expect("gen_fuse::topological_sort");
     // println!("sorteds: {:#?}", sorteds);
-    let mut tuple_vec = vec![];
     let inputs = groups
         .inputs
         .iter()
@@ -85,16 +98,11 @@ pub(crate) fn _cmp_gen_fuse(
                     );
                 }
             }
+            // the order of the inputs decides the generated signature, keep it stable
+            v.sort_by_key(|x| x.to_string());
             v
         })
         .collect::<Vec<_>>();
-    for mut iters in inputs.clone().iter_mut() {
-        let tokens = gen_tuple(&mut iters);
-        tuple_vec.push(quote::quote!(
-            (res, #tokens)
-        ));
-    }
-
     let mut sorted_groups = Vec::new();
 
     for group in groups.groups.iter() {
@@ -129,184 +137,187 @@ pub(crate) fn _cmp_gen_fuse(
             return vec![];
         };
         // println!("sorted: {:#?}", sorted);
-        let tokens = cmp_gen_body(sorted, &groups.inputs[i], graph, cfg);
-        scalar_comp.extend(tokens.clone());
+        let func_name = quote::format_ident!("__fuse_group_{}_{}", graph_idx, i);
+        let anchor = sorted.iter().copied().find(|idx| {
+            matches!(
+                graph[*idx].kernel_type,
+                KernelType::Reduce | KernelType::MatmulEpilogue
+            )
+        });
+        if let Some(anchor) = anchor {
+            let body_nodes = sorted
+                .iter()
+                .copied()
+                .filter(|idx| *idx != anchor)
+                .collect::<Vec<_>>();
+            let anchor_node = &graph[anchor];
+            let origin_map = &cfg.graph[NodeIndex::new(anchor_node.block_idx)].origin_var_map;
+            let mut origin_args = vec![];
+            for arg in anchor_node.args_ident.iter() {
+                match arg {
+                    Operand::Variable(ident) => origin_args
+                        .push(origin_map.get(ident).expect("gen_fuse::anchor_arg").clone()),
+                    Operand::Constant(constant) => {
+                        cfg.errors
+                            .push(Error::ExpectedIdentifier(constant.span(), "gen_fuse"));
+                        return vec![];
+                    }
+                }
+            }
+            let body = cmp_gen_body(&body_nodes, &groups.inputs[i], graph, cfg, false);
+            let fused = if anchor_node.kernel_type == KernelType::Reduce {
+                let vec_body = cmp_gen_body(&body_nodes, &groups.inputs[i], graph, cfg, true);
+                match gen_reduce(
+                    &func_name,
+                    anchor_node,
+                    origin_map,
+                    &origin_args[0],
+                    inputs,
+                    body,
+                    vec_body,
+                ) {
+                    Ok(fused) => fused,
+                    Err(err) => {
+                        cfg.errors.push(err);
+                        return vec![];
+                    }
+                }
+            } else {
+                let mm = if let Operand::Variable(ident) = &anchor_node.ident {
+                    origin_map.get(ident).expect("gen_fuse::anchor").clone()
+                } else {
+                    cfg.errors.push(Error::ExpectedIdentifier(
+                        anchor_node.ident.span(),
+                        "gen_fuse",
+                    ));
+                    return vec![];
+                };
+                // the operands of the matmul are only passed to the closure when the epilogue uses them
+                let mut used = HashSet::new();
+                for idx in body_nodes.iter() {
+                    let node = &graph[*idx];
+                    let origin_map = &cfg.graph[NodeIndex::new(node.block_idx)].origin_var_map;
+                    for arg in node.args_ident.iter() {
+                        if let Operand::Variable(ident) = arg {
+                            used.insert(origin_map.get(ident).expect("gen_fuse::used").clone());
+                        }
+                    }
+                }
+                let epilogue_inputs = inputs
+                    .iter()
+                    .filter(|input| used.contains(*input) && **input != mm)
+                    .cloned()
+                    .collect::<Vec<_>>();
+                gen_matmul_epilogue(
+                    &func_name,
+                    &origin_args,
+                    &mm,
+                    &epilogue_inputs,
+                    body,
+                    origin_output,
+                )
+            };
+            fused_vec.push(fused);
+            continue;
+        }
+        scalar_comp.extend(cmp_gen_body(&sorted, &groups.inputs[i], graph, cfg, false));
         scalar_comp.extend(quote::quote!(
             #origin_output
         ));
-        vec_comp.extend(tokens);
+        vec_comp.extend(cmp_gen_body(&sorted, &groups.inputs[i], graph, cfg, true));
         vec_comp.extend(quote::quote!(
             #origin_output
         ));
-        let tuple = &tuple_vec[i];
-        let func_name = quote::format_ident!("__fuse_group_{}", i);
         let func_args = inputs.iter().map(|input| {
             let ident = quote::format_ident!("{}", input);
             quote::quote!(&#ident)
         });
+        let prelude = fused_prelude();
         let fused = quote::quote!(
-            #func_name(#(#func_args,)*|#(#inputs),*| {
-                #scalar_comp
-            },|#(#inputs),*| {
-                #vec_comp
-            })?
+            {
+                #prelude
+                #func_name(#(#func_args,)*|#(#inputs),*| {
+                    #scalar_comp
+                },|#(#inputs),*| {
+                    #vec_comp
+                })?
+            }
         );
         let input_generics = inputs
             .iter()
-            .map(|input| quote::format_ident!("{}", input.to_string().to_uppercase()));
-        let closure_bounds = input_generics.clone();
-        let closure_simd_bounds = inputs.iter().map(|input| {
-            let generic_ident = quote::format_ident!("{}", input.to_string().to_uppercase());
-            quote::quote!(<#generic_ident as TypeCommon>::Vec)
-        });
-        let input_args = inputs.iter().map(|input| {
-            let arg_ident = quote::format_ident!("{}_arg", input);
-            let generic_ident = quote::format_ident!("{}", input.to_string().to_uppercase());
-            quote::quote! { #arg_ident: &Tensor<#generic_ident> }
-        });
-        let input_bounds = inputs.iter().map(|input| {
-            let generic_ident = quote::format_ident!("{}", input.to_string().to_uppercase());
-            quote::quote! { #generic_ident: CommonBounds }
-        });
-        let check_contiguous = inputs.iter().map(|input| {
-            let lhs_ident = quote::format_ident!("{}_arg", input);
-            quote::quote!(#lhs_ident.is_contiguous())
-        });
-        let check_shape_eq = inputs.iter().flat_map(|lhs| {
-            inputs
-                .iter()
-                .filter(move |&rhs| lhs != rhs)
-                .map(move |rhs| {
-                    let lhs_ident = quote::format_ident!("{}_arg", lhs);
-                    let rhs_ident = quote::format_ident!("{}_arg", rhs);
-                    quote::quote!(#lhs_ident.shape() == #rhs_ident.shape())
-                })
-        });
-        let vec_size_eq = inputs
-            .iter()
-            .map(|input| {
-                let ident = quote::format_ident!("{}", input.to_string().to_uppercase());
-                quote::quote!(<#ident as TypeCommon>::Vec::SIZE == <__HPTRES as TypeCommon>::Vec::SIZE)
-            })
+            .map(|input| quote::format_ident!("{}", input.to_string().to_uppercase()))
             .collect::<Vec<_>>();
-        let zip_chunks = inputs
+        let closure_simd_bounds = input_generics
             .iter()
-            .enumerate()
-            .map(|(idx, input)| {
-                let ident = quote::format_ident!("{}_arg", input);
-                let generic_ident = quote::format_ident!("{}", input.to_string().to_uppercase());
-                if idx == 0 {
-                    quote::quote! { #ident.as_raw().par_chunks_exact(<#generic_ident as TypeCommon>::Vec::SIZE) }
-                } else {
-                    quote::quote! { .zip(#ident.as_raw().par_chunks_exact(<#generic_ident as TypeCommon>::Vec::SIZE)) }
-                }
+            .map(|generic_ident| quote::quote!(<#generic_ident as hpt::TypeCommon>::Vec));
+        let input_args = inputs
+            .iter()
+            .zip(input_generics.iter())
+            .map(|(input, generic_ident)| {
+                let arg_ident = quote::format_ident!("{}_arg", input);
+                quote::quote! { #arg_ident: &hpt::Tensor<#generic_ident> }
             });
-        let zip_remain_scalars = inputs.iter().enumerate().map(|(idx, input)| {
-            let ident = quote::format_ident!("{}_arg", input);
-            if idx == 0 {
-                quote::quote! { #ident.as_raw()[ret_size - remain..].iter() }
-            } else {
-                quote::quote! { .zip(#ident.as_raw()[ret_size - remain..].iter()) }
-            }
-        });
-        let zip_min_len = inputs.iter().enumerate().map(|(idx, input)| {
-            let ident = quote::format_ident!("{}_arg", input);
-            if idx == 0 {
-                quote::quote! { #ident.as_raw().par_iter().with_min_len(min_len) }
-            } else {
-                quote::quote! { .zip(#ident.as_raw().par_iter().with_min_len(min_len)) }
-            }
-        });
-        let zip_scalar_par = inputs.iter().enumerate().map(|(idx, input)| {
-            let ident = quote::format_ident!("{}_arg", input);
-            if idx == 0 {
-                quote::quote! { #ident.par_iter() }
-            } else {
-                quote::quote! { .zip(#ident.par_iter()) }
+        let input_bounds = input_generics.iter().map(|generic_ident| {
+            quote::quote! {
+                #generic_ident: hpt::CommonBounds,
+                <#generic_ident as hpt::TypeCommon>::Vec: Send
             }
         });
         let layout_broadcast = inputs.iter().enumerate().map(|(idx, input)| {
             let ident = quote::format_ident!("{}_arg", input);
             if idx == 0 {
-                quote::quote! { let mut layout = #ident.layout().clone(); }
+                quote::quote! { let layout = #ident.layout().clone(); }
             } else {
-                quote::quote! { layout = layout.broadcast(#ident.layout())?; }
+                quote::quote! { let layout = layout.broadcast(#ident.layout())?; }
             }
         });
-        let from_ptr = inputs.iter().map(|input| {
-            let ident = quote::format_ident!("{}", input);
-            let generic_ident = quote::format_ident!("{}", input.to_string().to_uppercase());
-            quote::quote!(let #ident = unsafe { <#generic_ident as TypeCommon>::Vec::from_ptr(#ident.as_ptr()) };)
+        let par_iters = inputs.iter().map(|input| {
+            let ident = quote::format_ident!("{}_arg", input);
+            quote::quote! { .zip(#ident.par_iter_simd()) }
         });
-        let f2_args = inputs
+        let args = inputs
             .iter()
             .map(|input| quote::format_ident!("{}", input))
             .collect::<Vec<_>>();
-        let first = quote::format_ident!("{}_arg", inputs[0]);
+        // the output and the inputs are zipped from the left, `((res, a), b)`
+        let zipped = inputs.iter().fold(quote::quote!(res), |acc, input| {
+            let ident = quote::format_ident!("{}", input);
+            quote::quote!((#acc, #ident))
+        });
         let func = quote::quote!(
+            #[allow(clippy::too_many_arguments)]
             fn #func_name<#(#input_generics),*, __HPTRES, F, F2>(
                 #(#input_args),*,
                 f: F,
                 f2: F2,
-            ) -> anyhow::Result<Tensor<__HPTRES>>
+            ) -> std::result::Result<hpt::Tensor<__HPTRES>, hpt::TensorError>
             where
                 #(#input_bounds),*,
-                __HPTRES: CommonBounds,
-                F: Fn(#(#closure_bounds),*) -> __HPTRES + Sync + Send + Copy,
-                F2: Fn(#(#closure_simd_bounds),*) -> <__HPTRES as TypeCommon>::Vec
+                __HPTRES: hpt::CommonBounds,
+                <__HPTRES as hpt::TypeCommon>::Vec: Send,
+                F: Fn(#(#input_generics),*) -> __HPTRES + Sync + Send + Copy,
+                F2: Fn(#(#closure_simd_bounds),*) -> <__HPTRES as hpt::TypeCommon>::Vec
                     + Sync
                     + Send
                     + Copy,
             {
-                if #(#check_shape_eq &&)* #(#check_contiguous)&&* {
-                    let mut ret = Tensor::<__HPTRES, Cpu>::empty(#first.shape())?;
-                    if #(#vec_size_eq) && * {
-                        let remain = ret.size() % <__HPTRES as TypeCommon>::Vec::SIZE;
-                        ret.as_raw_mut()
-                            .par_chunks_exact_mut(<__HPTRES as TypeCommon>::Vec::SIZE)
-                            .zip(#(#zip_chunks)*)
-                            .for_each(|#tuple| {
-                                #(#from_ptr)*
-                                let ret = f2(#(#f2_args),*);
-                                unsafe {
-                                    std::ptr::copy_nonoverlapping(
-                                        ret.as_ptr(),
-                                        res.as_mut_ptr(),
-                                        <__HPTRES as TypeCommon>::Vec::SIZE,
-                                    );
-                                }
-                            });
-                        if remain > 0 {
-                            let ret_size = ret.size();
-                            ret.as_raw_mut()[ret_size - remain..]
-                                .iter_mut()
-                                .zip(#(#zip_remain_scalars)*)
-                                .for_each(|#tuple| {
-                                    *res = f(#(*#f2_args),*);
-                                });
-                        }
-                    } else {
-                        let min_len: usize =
-                        ret.size() / (((rayon::current_num_threads() as f64) * 1.3) as usize);
-                        ret.as_raw_mut()
-                            .par_iter_mut()
-                            .with_min_len(min_len)
-                            .zip(#(#zip_min_len)*)
-                            .for_each(|#tuple| {
-                                *res = f(#(*#f2_args),*);
-                            });
-                    }
-                    Ok(ret)
-                } else {
-                    #(#layout_broadcast)*
-                    let ret = Tensor::<__HPTRES, Cpu>::empty(layout.shape())?;
-                    ret.par_iter_mut()
-                        .zip(#(#zip_scalar_par)*)
-                        .for_each(|#tuple| {
-                            *res = f(#(#f2_args),*);
-                        });
-                    Ok(ret)
-                }
+                use hpt::{
+                    ParStridedIteratorSimd, ParStridedIteratorSimdZip, TensorCreator, TensorInfo,
+                    TensorIterator,
+                };
+                #(#layout_broadcast)*
+                let mut ret = hpt::Tensor::<__HPTRES>::empty(layout.shape())?;
+                ret.par_iter_mut_simd()
+                    #(#par_iters)*
+                    .for_each(
+                        |#zipped| {
+                            *res = f(#(#args),*);
+                        },
+                        |#zipped| {
+                            res.write_unaligned(f2(#(#args),*));
+                        },
+                    );
+                Ok(ret)
             }
         );
         fused_vec.push((fused, func));
@@ -314,17 +325,229 @@ pub(crate) fn _cmp_gen_fuse(
     fused_vec
 }
 
-fn gen_tuple(iters: &mut Vec<syn::Ident>) -> TokenStream2 {
-    if iters.is_empty() {
-        quote::quote!()
-    } else if iters.len() == 1 {
-        let first = iters.remove(0);
-        quote::quote!(#first)
-    } else {
-        let first = iters.remove(0);
-        let rest = gen_tuple(iters);
-        quote::quote!(
-            (#first, #rest)
-        )
+/// the imports and helpers the fused closures rely on
+fn fused_prelude() -> TokenStream2 {
+    quote::quote!(
+        #[allow(unused_imports)]
+        use hpt::{
+            BitWiseOut as _, FloatOutBinary as _, FloatOutUnary as _, NormalOut as _,
+            NormalOutUnary as _,
+        };
+        #[allow(dead_code)]
+        fn __hpt_splat<T: hpt::CommonBounds>(val: T) -> <T as hpt::TypeCommon>::Vec {
+            <<T as hpt::TypeCommon>::Vec as hpt::VecTrait<T>>::splat(val)
+        }
+    )
+}
+
+/// rename the variables of an expression back to the names they have in the user's code
+struct OriginRenamer<'a> {
+    origin_var_map: &'a HashMap<syn::Ident, syn::Ident>,
+}
+
+impl<'a> VisitMut for OriginRenamer<'a> {
+    fn visit_expr_path_mut(&mut self, node: &mut syn::ExprPath) {
+        if let Some(ident) = node.path.get_ident() {
+            if let Some(origin) = self.origin_var_map.get(ident) {
+                node.path = origin.clone().into();
+            }
+        }
     }
+    fn visit_expr_closure_mut(&mut self, _: &mut syn::ExprClosure) {}
+}
+
+/// the elementwise producers are computed in the inner loop of the reduction, `src` is the reduced variable
+fn gen_reduce(
+    func_name: &syn::Ident,
+    reduce: &CmpNode,
+    origin_var_map: &HashMap<syn::Ident, syn::Ident>,
+    src: &syn::Ident,
+    inputs: &[syn::Ident],
+    body: TokenStream2,
+    vec_body: TokenStream2,
+) -> Result<(TokenStream2, TokenStream2), Error> {
+    let method_args = reduce.method_args.iter().map(|arg| {
+        let mut arg = arg.clone();
+        if let Operand::Constant(expr) = &mut arg {
+            OriginRenamer { origin_var_map }.visit_expr_mut(expr);
+        }
+        arg
+    });
+    let method = reduce
+        .method
+        .as_ref()
+        .map(|x| x.to_string())
+        .unwrap_or_default();
+    let (init, combine) = match method.as_str() {
+        "sum" => (quote::quote!(ZERO), quote::quote!(_add)),
+        "prod" => (quote::quote!(ONE), quote::quote!(_mul)),
+        "max" => (quote::quote!(NEG_INF), quote::quote!(_max)),
+        "min" => (quote::quote!(INF), quote::quote!(_min)),
+        _ => {
+            return Err(Error::Unsupported(
+                reduce.ident.span(),
+                "gen_fuse",
+                format!("`{}` can't be fused with its elementwise inputs", method),
+            ))
+        }
+    };
+    let prelude = fused_prelude();
+    let func_args = inputs.iter().map(|input| quote::quote!(&#input));
+    let fused = quote::quote!(
+        {
+            #prelude
+            #func_name(#(#func_args,)* #(#method_args),*, |#(#inputs),*| {
+                #body
+                #src
+            }, |#(#inputs),*| {
+                #vec_body
+                #src
+            })?
+        }
+    );
+    let input_generics = inputs
+        .iter()
+        .map(|input| quote::format_ident!("{}", input.to_string().to_uppercase()))
+        .collect::<Vec<_>>();
+    let input_args = inputs
+        .iter()
+        .map(|input| quote::format_ident!("{}_arg", input))
+        .collect::<Vec<_>>();
+    let input_ptrs = inputs
+        .iter()
+        .map(|input| quote::format_ident!("{}_ptr", input))
+        .collect::<Vec<_>>();
+    let offsets = (0..inputs.len()).map(syn::Index::from).collect::<Vec<_>>();
+    let func = quote::quote!(
+        #[allow(clippy::too_many_arguments)]
+        fn #func_name<#(#input_generics),*, __HPTRES, __HPTAXES, F, F2>(
+            #(#input_args: &hpt::Tensor<#input_generics>),*,
+            axes: __HPTAXES,
+            keep_dims: bool,
+            f: F,
+            f2: F2,
+        ) -> std::result::Result<hpt::Tensor<__HPTRES>, hpt::TensorError>
+        where
+            #(#input_generics: hpt::CommonBounds),*,
+            __HPTRES: hpt::CommonBounds,
+            __HPTAXES: Into<hpt::Axis>,
+            F: Fn(#(#input_generics),*) -> __HPTRES + Sync + Send + Copy,
+            F2: Fn(#(<#input_generics as hpt::TypeCommon>::Vec),*) -> <__HPTRES as hpt::TypeCommon>::Vec
+                + Sync
+                + Send
+                + Copy,
+        {
+            use hpt::{NormalOut, TensorInfo, TypeCommon, VecTrait};
+            #(let #input_ptrs = #input_args.ptr();)*
+            // the vector body can only be used when every input fills a vector of the output
+            let vectorized = true #(&& <#input_generics as TypeCommon>::Vec::SIZE == <__HPTRES as TypeCommon>::Vec::SIZE)*;
+            hpt::fused_reduce(
+                &[#(#input_args.layout()),*],
+                axes,
+                keep_dims,
+                <__HPTRES as TypeCommon>::#init,
+                |offsets| f(#(#input_ptrs[offsets[#offsets]]),*),
+                |offsets, strides| {
+                    if vectorized {
+                        return f2(#(hpt::fused_load_vec(&#input_ptrs, offsets[#offsets], strides[#offsets])),*);
+                    }
+                    let mut vec = <__HPTRES as TypeCommon>::Vec::splat(<__HPTRES as TypeCommon>::ZERO);
+                    let lanes = vec.as_mut_ptr();
+                    for lane in 0..<__HPTRES as TypeCommon>::Vec::SIZE as i64 {
+                        let res = f(#(#input_ptrs[offsets[#offsets] + lane * strides[#offsets]]),*);
+                        unsafe { *lanes.add(lane as usize) = res };
+                    }
+                    vec
+                },
+                |a, b| a.#combine(b),
+                |a, b| a.#combine(b),
+            )
+        }
+    );
+    Ok((fused, func))
+}
+
+/// the elementwise consumers are applied when the matmul output is written, `mm` is the output of the matmul
+fn gen_matmul_epilogue(
+    func_name: &syn::Ident,
+    operands: &[syn::Ident],
+    mm: &syn::Ident,
+    inputs: &[syn::Ident],
+    body: TokenStream2,
+    output: &syn::Ident,
+) -> (TokenStream2, TokenStream2) {
+    let (lhs, rhs) = (&operands[0], &operands[1]);
+    let mut params = inputs.to_vec();
+    params.push(mm.clone());
+    params.sort_by_key(|x| x.to_string());
+    let prelude = fused_prelude();
+    let fused = quote::quote!(
+        {
+            #prelude
+            #func_name(&#lhs, &#rhs, #(&#inputs,)* |#(#params),*| {
+                #body
+                #output
+            })?
+        }
+    );
+    let input_generics = inputs
+        .iter()
+        .map(|input| quote::format_ident!("{}", input.to_string().to_uppercase()))
+        .collect::<Vec<_>>();
+    let input_args = inputs
+        .iter()
+        .map(|input| quote::format_ident!("{}_arg", input))
+        .collect::<Vec<_>>();
+    let input_ptrs = inputs
+        .iter()
+        .map(|input| quote::format_ident!("{}_ptr", input))
+        .collect::<Vec<_>>();
+    let mm_ty = quote::quote!(<__HPTLHS as hpt::NormalOut<__HPTRHS>>::Output);
+    let (closure_tys, closure_args): (Vec<_>, Vec<_>) = params
+        .iter()
+        .map(|param| {
+            if param == mm {
+                (mm_ty.clone(), quote::quote!(__hpt_mm))
+            } else {
+                let idx = inputs
+                    .iter()
+                    .position(|x| x == param)
+                    .expect("gen_fuse::param");
+                let ptr = &input_ptrs[idx];
+                let offset = syn::Index::from(idx);
+                (
+                    input_generics[idx].to_token_stream(),
+                    quote::quote!(#ptr[__hpt_offsets[#offset]]),
+                )
+            }
+        })
+        .unzip();
+    let func = quote::quote!(
+        #[allow(clippy::too_many_arguments)]
+        fn #func_name<__HPTLHS, __HPTRHS, #(#input_generics,)* __HPTRES, F>(
+            __hpt_lhs: &hpt::Tensor<__HPTLHS>,
+            __hpt_rhs: &hpt::Tensor<__HPTRHS>,
+            #(#input_args: &hpt::Tensor<#input_generics>,)*
+            f: F,
+        ) -> std::result::Result<hpt::Tensor<__HPTRES>, hpt::TensorError>
+        where
+            __HPTLHS: hpt::CommonBounds + hpt::NormalOut<__HPTRHS> + hpt::Cast<#mm_ty>,
+            __HPTRHS: hpt::CommonBounds + hpt::Cast<#mm_ty>,
+            #mm_ty: hpt::CommonBounds,
+            #(#input_generics: hpt::CommonBounds,)*
+            __HPTRES: hpt::CommonBounds,
+            F: Fn(#(#closure_tys),*) -> __HPTRES + Sync + Send + Copy,
+        {
+            #[allow(unused_imports)]
+            use hpt::TensorInfo;
+            #(let #input_ptrs = #input_args.ptr();)*
+            hpt::matmul_post(
+                __hpt_lhs,
+                __hpt_rhs,
+                &[#(#input_args.layout()),*],
+                |__hpt_mm, __hpt_offsets| f(#(#closure_args),*),
+            )
+        }
+    );
+    (fused, func)
 }
@@ -2,12 +2,23 @@
 pub(crate) enum KernelType {
     Unary,
     Binary,
+    /// a reduction, elementwise producers are computed in the reduction's inner loop, nothing fuses after it
+    Reduce,
+    /// a matmul, elementwise consumers are applied when the matmul output is written, nothing fuses before it
+    MatmulEpilogue,
     Opaque,
 }
 
 impl KernelType {
     pub fn infer_suc_kernel(&self, next: &KernelType) -> Option<KernelType> {
         match (self, next) {
+            (KernelType::Reduce, _) => None,
+            (_, KernelType::Reduce) => None,
+            (KernelType::MatmulEpilogue, KernelType::Unary | KernelType::Binary) => {
+                Some(KernelType::MatmulEpilogue)
+            }
+            (KernelType::MatmulEpilogue, _) => None,
+            (_, KernelType::MatmulEpilogue) => None,
             (KernelType::Unary, KernelType::Unary) => Some(KernelType::Unary),
             (KernelType::Binary, KernelType::Binary) => Some(KernelType::Binary),
             (KernelType::Binary, KernelType::Unary) => Some(KernelType::Binary),
@@ -17,8 +28,14 @@ impl KernelType {
             (_, KernelType::Opaque) => None,
         }
     }
-    pub fn infer_pred_kernel(&self, pred: &KernelType) -> Option<KernelType> {
-        match (self, pred) {
+    pub fn infer_pred_kernel(&self, next: &KernelType) -> Option<KernelType> {
+        match (self, next) {
+            (KernelType::Unary | KernelType::Binary, KernelType::Reduce) => {
+                Some(KernelType::Reduce)
+            }
+            (_, KernelType::Reduce) => None,
+            (_, KernelType::MatmulEpilogue) => None,
+            (KernelType::Reduce | KernelType::MatmulEpilogue, _) => None,
             (KernelType::Unary, KernelType::Unary) => Some(KernelType::Unary),
             (KernelType::Binary, KernelType::Binary) => Some(KernelType::Binary),
             (KernelType::Binary, KernelType::Unary) => Some(KernelType::Binary),
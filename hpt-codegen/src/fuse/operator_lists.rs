@@ -90,3 +90,5 @@ pub(crate) const BINARY_OPERATORS: [&str; 10] = [
 ];
 
 pub(crate) const OPAQUE_BINARY_OPERATORS: [&str; 2] = ["matmul", "matmul_"];
+
+pub(crate) const REDUCE_OPERATORS: [&str; 4] = ["sum", "prod", "max", "min"];
@@ -2,8 +2,10 @@ use super::cfg::CFG;
 use crate::fuse::{
     errors::Error,
     fuse::{Input, Output},
+    kernel_type::KernelType,
     node::Operand,
     ty_infer::TyInfer,
+    unfused_collector::UnfusedCollector,
 };
 use std::collections::{HashMap, HashSet};
 use syn::{spanned::Spanned, visit::Visit};
@@ -39,10 +41,18 @@ fn build_cfg(item_fn: &syn::ItemFn) -> anyhow::Result<CFG> {
 
 pub fn fuse_impl(func: syn::ItemFn) -> anyhow::Result<proc_macro2::TokenStream> {
     let mut cfg = build_cfg(&func)?;
+    let attrs = func.attrs.clone();
     check_errors!(cfg);
     // println!("cfg: {:#?}", cfg.graph);
     let mut type_table = TyInfer::new();
     type_table.infer(&cfg)?;
+    let mut unfused_collector = UnfusedCollector::new(&type_table);
+    for block in cfg.graph.node_weights() {
+        for stmt in block.statements.iter() {
+            unfused_collector.visit_stmt(&stmt.stmt);
+        }
+    }
+    let warnings = unfused_collector.warnings();
     cfg.live_analysis(&type_table.table);
     cfg.inter_live_analysis();
     let table = core::mem::take(&mut type_table.table);
@@ -58,10 +68,23 @@ pub fn fuse_impl(func: syn::ItemFn) -> anyhow::Result<proc_macro2::TokenStream>
         let cmp_pet_graph = graph.to_cmp_pet_graph();
         if cmp_pet_graph.node_count() > 0 && !petgraph::algo::is_cyclic_directed(&cmp_pet_graph) {
             let mut fusion_group = crate::fuse::fuse::cmp_fuse(&cfg, &cmp_pet_graph);
+            // a matmul or a reduction is only worth replacing when something is fused into it
             let mask = fusion_group
                 .groups
                 .iter()
-                .map(|x| x.len() > 1)
+                .map(|x| {
+                    let anchored = x.iter().any(|idx| {
+                        matches!(
+                            cmp_pet_graph[*idx].kernel_type,
+                            KernelType::Reduce | KernelType::MatmulEpilogue
+                        )
+                    });
+                    let ops = x
+                        .iter()
+                        .filter(|idx| cmp_pet_graph[**idx].method.is_some())
+                        .count();
+                    x.len() > 1 && (!anchored || ops > 1)
+                })
                 .collect::<Vec<_>>();
             let mut mask_iter = mask.iter();
             fusion_group
@@ -141,8 +164,12 @@ pub fn fuse_impl(func: syn::ItemFn) -> anyhow::Result<proc_macro2::TokenStream>
                     }
                 }
             }
-            let genfuse =
-                crate::fuse::gen_fuse::cmp_gen_fuse(&mut cfg, &cmp_pet_graph, &fusion_group);
+            let genfuse = crate::fuse::gen_fuse::cmp_gen_fuse(
+                &mut cfg,
+                &cmp_pet_graph,
+                &fusion_group,
+                idx.index(),
+            );
             check_errors!(cfg);
             genfuse_map.insert(idx, (genfuse, fusion_group));
         }
@@ -196,9 +223,20 @@ pub fn fuse_impl(func: syn::ItemFn) -> anyhow::Result<proc_macro2::TokenStream>
     }
     cfg.replace_all_var_back();
     let code = cfg.gen_code();
-    let ret = quote::quote!(
-        #(#func_codes)*
-        #code
-    );
-    Ok(ret)
+    if func_codes.is_empty() && warnings.is_empty() && attrs.is_empty() {
+        return Ok(code);
+    }
+    // the fused kernels are declared inside the function, so they never clash with other functions
+    let mut item_fn = syn::parse2::<syn::ItemFn>(code)?;
+    if item_fn.attrs.is_empty() {
+        item_fn.attrs = attrs;
+    }
+    for func_code in func_codes.into_iter().rev() {
+        item_fn
+            .block
+            .stmts
+            .insert(0, syn::Stmt::Item(syn::parse2(func_code)?));
+    }
+    item_fn.block.stmts.splice(0..0, warnings);
+    Ok(quote::quote!(#item_fn))
 }
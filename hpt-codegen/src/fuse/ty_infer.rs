@@ -7,7 +7,9 @@ use syn::visit::Visit;
 use super::{
     cfg::CFG,
     expr_ty,
-    operator_lists::{BINARY_OPERATORS, OPAQUE_BINARY_OPERATORS, UNARY_OPERATORS},
+    operator_lists::{
+        BINARY_OPERATORS, OPAQUE_BINARY_OPERATORS, REDUCE_OPERATORS, UNARY_OPERATORS,
+    },
 };
 
 #[derive(Debug, Clone, PartialEq, Copy)]
@@ -81,6 +83,7 @@ impl TyInfer {
                     if UNARY_OPERATORS.contains(&func_name.as_str())
                         || BINARY_OPERATORS.contains(&func_name.as_str())
                         || OPAQUE_BINARY_OPERATORS.contains(&func_name.as_str())
+                        || REDUCE_OPERATORS.contains(&func_name.as_str())
                     {
                         return Type::Tensor;
                     }
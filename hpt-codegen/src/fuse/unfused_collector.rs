@@ -0,0 +1,76 @@
+use quote::ToTokens;
+use syn::visit::Visit;
+
+use super::{
+    operator_lists::{BINARY_OPERATORS, REDUCE_OPERATORS, UNARY_OPERATORS},
+    ty_infer::{TyInfer, Type},
+};
+
+/// collects the method calls on tensors that can't be fused, the fusion is split at these calls
+pub(crate) struct UnfusedCollector<'a> {
+    pub(crate) type_table: &'a TyInfer,
+    pub(crate) unfused: Vec<(proc_macro2::Span, String)>,
+}
+
+impl<'a> UnfusedCollector<'a> {
+    pub(crate) fn new(type_table: &'a TyInfer) -> Self {
+        Self {
+            type_table,
+            unfused: Vec::new(),
+        }
+    }
+
+    /// generate a warning for each unfused call, the warning is a use of a deprecated item so it works on stable
+    pub(crate) fn warnings(&self) -> Vec<syn::Stmt> {
+        self.unfused
+            .iter()
+            .map(|(span, method)| {
+                let note = format!(
+                    "`{}` can't be fused, the fused kernels are split at this statement",
+                    method
+                );
+                let usage = quote::quote_spanned!(*span=> __hpt_unfused);
+                syn::parse_quote! {
+                    const _: () = {
+                        #[deprecated(note = #note)]
+                        #[allow(non_camel_case_types)]
+                        struct __hpt_unfused;
+                        let _ = #usage;
+                    };
+                }
+            })
+            .collect()
+    }
+}
+
+impl<'ast, 'a> Visit<'ast> for UnfusedCollector<'a> {
+    fn visit_expr_method_call(&mut self, node: &'ast syn::ExprMethodCall) {
+        if is_simple(&node.receiver) && self.type_table.type_of(&node.receiver) == Type::Tensor {
+            let method = node.method.to_token_stream().to_string();
+            if !UNARY_OPERATORS.contains(&method.as_str())
+                && !BINARY_OPERATORS.contains(&method.as_str())
+                && !REDUCE_OPERATORS.contains(&method.as_str())
+                && method != "matmul"
+            {
+                self.unfused.push((node.method.span(), method));
+            }
+        }
+        syn::visit::visit_expr_method_call(self, node);
+    }
+    fn visit_item(&mut self, _: &'ast syn::Item) {}
+    fn visit_expr_closure(&mut self, _: &'ast syn::ExprClosure) {}
+}
+
+/// check if the type of the expression can be inferred by `TyInfer::type_of`
+fn is_simple(expr: &syn::Expr) -> bool {
+    match expr {
+        syn::Expr::Binary(binary) => is_simple(&binary.left) && is_simple(&binary.right),
+        syn::Expr::Reference(reference) => is_simple(&reference.expr),
+        syn::Expr::Try(try_expr) => is_simple(&try_expr.expr),
+        syn::Expr::MethodCall(method_call) => is_simple(&method_call.receiver),
+        syn::Expr::Paren(paren) => is_simple(&paren.expr),
+        syn::Expr::Unary(unary) => is_simple(&unary.expr),
+        syn::Expr::Path(_) | syn::Expr::Lit(_) | syn::Expr::Call(_) | syn::Expr::Field(_) => true,
+        _ => false,
+    }
+}
@@ -17,6 +17,7 @@ pub(crate) mod fuse {
     pub(crate) mod phi_function;
     pub mod start;
     pub(crate) mod ty_infer;
+    pub(crate) mod unfused_collector;
     pub(crate) mod use_define_visitor;
     pub(crate) mod var_coalescer;
     pub(crate) mod var_recover;
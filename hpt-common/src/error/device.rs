@@ -14,6 +14,17 @@ pub enum DeviceError {
         location: &'static Location<'static>,
     },
 
+    /// Device mismatch, for example, a cuda memory is passed to a cpu tensor
+    #[error("Device mismatch: expected {expected}, got {actual} at {location}")]
+    DeviceMismatch {
+        /// Expected device
+        expected: String,
+        /// Actual device
+        actual: String,
+        /// Location where the error occurred
+        location: &'static Location<'static>,
+    },
+
     /// CUDA driver error
     #[error("CUDA driver error: {message} at {location}")]
     #[cfg(feature = "cuda")]
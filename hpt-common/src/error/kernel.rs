@@ -48,6 +48,30 @@ pub enum KernelError {
         /// Location where the error occurred
         location: &'static Location<'static>,
     },
+
+    /// Error that occurs when an operation can't produce deterministic results while deterministic mode is enabled
+    #[error("Operation {op} can't guarantee deterministic results, disable deterministic mode to use it at {location}")]
+    NotDeterministic {
+        /// Name of the operation
+        op: &'static str,
+        /// Location where the error occurred
+        location: &'static Location<'static>,
+    },
+
+    /// Error that occurs when a backend can't accumulate a reduction with the requested policy
+    #[error(
+        "Operation {op} doesn't support the {accumulation} accumulation for {dtype} at {location}"
+    )]
+    UnsupportedAccumulation {
+        /// Name of the operation
+        op: &'static str,
+        /// The requested accumulation policy
+        accumulation: String,
+        /// The element type
+        dtype: &'static str,
+        /// Location where the error occurred
+        location: &'static Location<'static>,
+    },
 }
 
 #[cfg(feature = "cuda")]
@@ -21,6 +21,66 @@ pub enum ParamError {
         /// Location where error occurred
         location: &'static Location<'static>,
     },
+    /// Error that occurs when the chunk size is not positive
+    #[error("Chunk size must be positive, got {size} at {location}")]
+    InvalidChunkSize {
+        /// Invalid chunk size
+        size: i64,
+        /// Location where error occurred
+        location: &'static Location<'static>,
+    },
+    /// Error that occurs when the data type doesn't match the expected one
+    #[error("Dtype mismatch: expected {expected}, got {actual} at {location}")]
+    DtypeMismatch {
+        /// Expected data type
+        expected: String,
+        /// Actual data type
+        actual: String,
+        /// Location where error occurred
+        location: &'static Location<'static>,
+    },
+    /// Error that occurs when an operation is not implemented for the data type
+    #[error("Operation {op} is not supported for dtype {dtype} at {location}")]
+    UnsupportedDtype {
+        /// Name of the operation
+        op: &'static str,
+        /// Data type of the operand
+        dtype: String,
+        /// Location where error occurred
+        location: &'static Location<'static>,
+    },
+    /// Error that occurs when the scales or zero points of a quantization are invalid
+    #[error("Invalid quantization parameters: {message} at {location}")]
+    InvalidQuantParams {
+        /// What is wrong with the parameters
+        message: String,
+        /// Location where error occurred
+        location: &'static Location<'static>,
+    },
+    /// Error that occurs when the length or a parameter of a window function is invalid
+    #[error("Invalid window parameter: {message} at {location}")]
+    InvalidWindowParam {
+        /// What is wrong with the parameter
+        message: String,
+        /// Location where error occurred
+        location: &'static Location<'static>,
+    },
+    /// Error that occurs when the number of threads is invalid
+    #[error("Invalid number of threads: {num_threads}, expected at least 1 at {location}")]
+    InvalidNumThreads {
+        /// Requested number of threads
+        num_threads: usize,
+        /// Location where error occurred
+        location: &'static Location<'static>,
+    },
+    /// Error that occurs when the cores the threads are pinned to are invalid
+    #[error("Invalid cores: {message} at {location}")]
+    InvalidCores {
+        /// What is wrong with the cores
+        message: String,
+        /// Location where error occurred
+        location: &'static Location<'static>,
+    },
 }
 
 impl ParamError {
@@ -1,4 +1,7 @@
-use std::{collections::HashMap, io::Write};
+use std::{
+    collections::HashMap,
+    io::{Read, Seek, Write},
+};
 
 use hpt_common::{shape::shape::Shape, slice::Slice, strides::strides::Strides};
 use hpt_traits::{CommonBounds, TensorCreator, TensorInfo};
@@ -6,9 +9,10 @@ use num::traits::{FromBytes, ToBytes};
 use serde::{Deserialize, Serialize};
 
 use crate::{
-    data_loader::{Endian, HeaderInfo},
-    load::load_compressed_slice,
-    save::save,
+    data_loader::Endian,
+    format::read_header,
+    load::{into_io_error, load_compressed_slice},
+    save::{save, save_to},
 };
 
 pub trait CompressionTrait {
@@ -67,6 +71,28 @@ impl<T> DataLoader<T> {
             data,
         }
     }
+
+    /// the offsets (in elements, relative to `data`) of all the elements in C order
+    pub(crate) fn c_order_offsets(&self) -> impl Iterator<Item = isize> {
+        let shape = self.shape.inner().clone();
+        let strides = self.strides.inner().clone();
+        let mut prg = vec![0i64; shape.len()];
+        let mut offset = 0i64;
+        (0..self.shape.size()).map(move |_| {
+            let current = offset;
+            for j in (0..shape.len()).rev() {
+                if prg[j] < shape[j] - 1 {
+                    prg[j] += 1;
+                    offset += strides[j];
+                    break;
+                } else {
+                    prg[j] = 0;
+                    offset -= strides[j] * (shape[j] - 1);
+                }
+            }
+            current as isize
+        })
+    }
 }
 
 impl<T, const N: usize> DataLoaderTrait for DataLoader<T>
@@ -176,6 +202,11 @@ impl TensorSaver {
             self.to_saves.unwrap(),
         )
     }
+
+    /// write the tensors to `writer` instead of the file path, the writer is returned once everything is written
+    pub fn save_to<W: Write>(self, writer: W) -> std::io::Result<W> {
+        save_to(writer, self.to_saves.unwrap_or_default())
+    }
 }
 
 pub struct TensorLoader {
@@ -205,12 +236,8 @@ impl TensorLoader {
         T: CommonBounds + FromBytes<Bytes = [u8; N]>,
         B: TensorCreator<T, Output = B> + Clone + TensorInfo<T>,
     {
-        let res = load_compressed_slice::<T, B, N>(
-            self.file_path.to_str().unwrap().into(),
-            self.to_loads.expect("no tensors to load"),
-        )
-        .expect("failed to load tensor");
-        Ok(res)
+        let file = std::io::BufReader::new(std::fs::File::open(&self.file_path)?);
+        self.load_from::<T, B, _, N>(file)
     }
 
     pub fn load_all<T, B, const N: usize>(self) -> std::io::Result<HashMap<String, B>>
@@ -218,13 +245,40 @@ impl TensorLoader {
         T: CommonBounds + FromBytes<Bytes = [u8; N]>,
         B: TensorCreator<T, Output = B> + Clone + TensorInfo<T>,
     {
-        let res = HeaderInfo::parse_header_compressed(self.file_path.to_str().unwrap().into())
-            .expect("failed to parse header");
-        let res = load_compressed_slice::<T, B, N>(
-            self.file_path.to_str().unwrap().into(),
-            res.into_values().map(|x| (x.name, vec![])).collect(),
+        let file = std::io::BufReader::new(std::fs::File::open(&self.file_path)?);
+        self.load_all_from::<T, B, _, N>(file)
+    }
+
+    /// same as `load`, but the tensors are read from `reader` instead of the file path
+    pub fn load_from<T, B, R: Read + Seek, const N: usize>(
+        self,
+        mut reader: R,
+    ) -> std::io::Result<HashMap<String, B>>
+    where
+        T: CommonBounds + FromBytes<Bytes = [u8; N]>,
+        B: TensorCreator<T, Output = B> + Clone + TensorInfo<T>,
+    {
+        let header = read_header(&mut reader)?;
+        load_compressed_slice::<T, B, R, N>(
+            &mut reader,
+            &header.infos,
+            self.to_loads.unwrap_or_default(),
         )
-        .expect("failed to load tensor");
-        Ok(res)
+        .map_err(into_io_error)
+    }
+
+    /// same as `load_all`, but the tensors are read from `reader` instead of the file path
+    pub fn load_all_from<T, B, R: Read + Seek, const N: usize>(
+        self,
+        mut reader: R,
+    ) -> std::io::Result<HashMap<String, B>>
+    where
+        T: CommonBounds + FromBytes<Bytes = [u8; N]>,
+        B: TensorCreator<T, Output = B> + Clone + TensorInfo<T>,
+    {
+        let header = read_header(&mut reader)?;
+        let to_loads = header.infos.keys().map(|x| (x.clone(), vec![])).collect();
+        load_compressed_slice::<T, B, R, N>(&mut reader, &header.infos, to_loads)
+            .map_err(into_io_error)
     }
 }
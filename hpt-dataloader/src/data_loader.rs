@@ -1,16 +1,12 @@
-use anyhow::Result;
 use hpt_traits::CommonBounds;
 use hpt_traits::TensorInfo;
 use num::traits::FromBytes;
 use serde::{Deserialize, Serialize};
 use std::fmt::Display;
+use std::fs::File;
 use std::marker::PhantomData;
-use std::{
-    collections::HashMap,
-    fs::File,
-    io::{Read, Seek},
-};
 
+use crate::format::read_metadata;
 use crate::struct_save::load::load;
 use crate::struct_save::load::MetaLoad;
 use crate::struct_save::save::Save;
@@ -41,6 +37,9 @@ pub(crate) struct HeaderInfo {
     pub(crate) compress_algo: CompressionAlgo,
     pub(crate) dtype: String,
     pub(crate) endian: Endian,
+    /// crc32 of the stored bytes of each chunk, empty for version 1 files
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub(crate) checksums: Vec<u32>,
 }
 /// the meta data of the tensor
 #[derive(Serialize, Deserialize)]
@@ -57,21 +56,16 @@ where
     pub compression_algo: CompressionAlgo,
     pub endian: Endian,
     pub indices: Vec<(usize, usize, usize, usize)>,
+    /// crc32 of the stored bytes of each chunk, empty for version 1 files
+    #[serde(default)]
+    pub checksums: Vec<u32>,
     pub phantom: PhantomData<(T, B)>,
 }
 
 pub fn parse_header_compressed<M: Save>(file: &str) -> anyhow::Result<<M as Save>::Meta> {
-    let mut file = File::open(file)?;
-    file.read_exact(&mut [0u8; "FASTTENSOR".len()])?;
-    let mut header_infos = [0u8; 20];
-    file.read_exact(&mut header_infos)?;
-    let header = std::str::from_utf8(&header_infos)?;
-    let header_int = header.trim().parse::<u64>()?;
-    file.seek(std::io::SeekFrom::Start(header_int))?; // offset for header
-    let mut buffer3 = vec![];
-    file.read_to_end(&mut buffer3)?;
-    let info = std::str::from_utf8(&buffer3)?;
-    let ret = serde_json::from_str::<M::Meta>(info)?;
+    let mut file = std::io::BufReader::new(File::open(file)?);
+    let metadata = read_metadata(&mut file)?;
+    let ret = serde_json::from_value::<M::Meta>(metadata)?;
     Ok(ret)
 }
 
@@ -87,19 +81,19 @@ where
 }
 
 impl HeaderInfo {
-    pub(crate) fn parse_header_compressed(file: &str) -> Result<HashMap<String, HeaderInfo>> {
-        let mut file = File::open(file)?;
-        file.read_exact(&mut [0u8; "FASTTENSOR".len()])?;
-        let mut header_infos = [0u8; 20];
-        file.read_exact(&mut header_infos)?;
-        let header = std::str::from_utf8(&header_infos)?;
-        let header_int = header.trim().parse::<u64>()?;
-        file.seek(std::io::SeekFrom::Start(header_int))?; // offset for header
-        let mut buffer3 = vec![];
-        file.read_to_end(&mut buffer3)?;
-        let info = std::str::from_utf8(&buffer3)?;
-        let ret: HashMap<String, HeaderInfo> =
-            serde_json::from_str::<HashMap<String, Self>>(&info)?;
-        Ok(ret)
+    /// the size in bytes of the element type
+    pub(crate) fn dtype_size(&self) -> std::io::Result<usize> {
+        match self.dtype.as_str() {
+            "bool" | "i8" | "u8" | "f8e4m3" | "f8e5m2" => Ok(1),
+            "i16" | "u16" | "f16" | "bf16" => Ok(2),
+            "i32" | "u32" | "f32" => Ok(4),
+            "i64" | "u64" | "f64" | "c32" => Ok(8),
+            "isize" | "usize" => Ok(std::mem::size_of::<usize>()),
+            "c64" => Ok(16),
+            dtype => Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                format!("unknown dtype {}", dtype),
+            )),
+        }
     }
 }
@@ -0,0 +1,192 @@
+//! the FASTTENSOR file layout
+//!
+//! version 1: `FASTTENSOR` + header offset (20 bytes) + chunks + json header
+//!
+//! version 2: `FASTTENSOR` + version tag (20 bytes, `v2` right aligned) + chunks + json header + trailer,
+//! the trailer is header offset (20 bytes) + crc32 of the json header (10 bytes) + `FASTTENSOR`.
+//! since nothing has to be patched after the chunks are written, version 2 files can be written to any `Write`.
+//! each chunk of a version 2 file also has a crc32 of its stored bytes in the header.
+//!
+//! the json header maps the name of each tensor to its `HeaderInfo`. files written by `Save` also store the metadata of the
+//! struct under the `__metadata__` key, version 1 files written by `Save` store the metadata of the struct as the whole header.
+//!
+//! in both versions, the first chunk of an uncompressed tensor starts at a multiple of 64 bytes so that `MmapLoader` can
+//! view it without copy, the gap before it is filled with zeros. the chunk offsets in the header are absolute, so the
+//! padding doesn't change how the files are read, files written before the padding was added are still read the same way.
+
+use std::{
+    collections::HashMap,
+    io::{Read, Seek, SeekFrom},
+};
+
+use crate::data_loader::HeaderInfo;
+
+pub(crate) const MAGIC: &str = "FASTTENSOR";
+/// length of the magic and the version tag / header offset at the beginning of the file
+pub(crate) const PREFIX_LEN: usize = MAGIC.len() + 20;
+/// length of the trailer of version 2 files
+pub(crate) const TRAILER_LEN: usize = 20 + 10 + MAGIC.len();
+/// the version written by `TensorWriter`
+pub const FORMAT_VERSION: u32 = 2;
+/// the key of the struct metadata written by `Save`
+pub(crate) const METADATA_KEY: &str = "__metadata__";
+
+/// the parsed header of a FASTTENSOR file
+pub(crate) struct FileHeader {
+    pub(crate) version: u32,
+    pub(crate) infos: HashMap<String, HeaderInfo>,
+    /// the struct metadata written by `Save`
+    pub(crate) metadata: Option<serde_json::Value>,
+}
+
+pub(crate) fn prefix() -> String {
+    format!("{}{:>20}", MAGIC, format!("v{}", FORMAT_VERSION))
+}
+
+pub(crate) fn trailer(header_offset: usize, header_crc: u32) -> String {
+    format!("{:20}{:10}{}", header_offset, header_crc, MAGIC)
+}
+
+pub(crate) fn invalid_data<E: Into<Box<dyn std::error::Error + Send + Sync>>>(
+    err: E,
+) -> std::io::Error {
+    std::io::Error::new(std::io::ErrorKind::InvalidData, err)
+}
+
+fn truncated(message: String) -> std::io::Error {
+    std::io::Error::new(
+        std::io::ErrorKind::UnexpectedEof,
+        format!("truncated FASTTENSOR file: {}", message),
+    )
+}
+
+fn parse_int<T: std::str::FromStr>(bytes: &[u8], what: &str) -> std::io::Result<T> {
+    std::str::from_utf8(bytes)
+        .ok()
+        .and_then(|x| x.trim().parse::<T>().ok())
+        .ok_or_else(|| invalid_data(format!("corrupted FASTTENSOR file: invalid {}", what)))
+}
+
+/// read the prefix and the trailer, return the version, the offset of the json header and the json header
+fn read_raw_header<R: Read + Seek>(reader: &mut R) -> std::io::Result<(u32, usize, Vec<u8>)> {
+    let file_len = reader.seek(SeekFrom::End(0))? as usize;
+    if file_len < PREFIX_LEN {
+        return Err(truncated(format!("the file has only {} bytes", file_len)));
+    }
+    reader.seek(SeekFrom::Start(0))?;
+    let mut prefix = [0u8; PREFIX_LEN];
+    reader.read_exact(&mut prefix)?;
+    if &prefix[..MAGIC.len()] != MAGIC.as_bytes() {
+        return Err(invalid_data("not a FASTTENSOR file"));
+    }
+    let tag = std::str::from_utf8(&prefix[MAGIC.len()..])
+        .map_err(|_| invalid_data("corrupted FASTTENSOR file: invalid version tag"))?
+        .trim();
+    let (version, header_start, header_end, header_crc) =
+        if let Some(version) = tag.strip_prefix('v') {
+            let version = parse_int::<u32>(version.as_bytes(), "version tag")?;
+            if version > FORMAT_VERSION {
+                return Err(std::io::Error::new(
+                    std::io::ErrorKind::Unsupported,
+                    format!(
+                    "FASTTENSOR version {} is not supported, the latest supported version is {}",
+                    version, FORMAT_VERSION
+                ),
+                ));
+            }
+            if file_len < PREFIX_LEN + TRAILER_LEN {
+                return Err(truncated("missing trailer".to_string()));
+            }
+            reader.seek(SeekFrom::Start((file_len - TRAILER_LEN) as u64))?;
+            let mut trailer = [0u8; TRAILER_LEN];
+            reader.read_exact(&mut trailer)?;
+            if &trailer[30..] != MAGIC.as_bytes() {
+                return Err(truncated("missing trailer".to_string()));
+            }
+            let header_offset = parse_int::<usize>(&trailer[..20], "header offset")?;
+            let header_crc = parse_int::<u32>(&trailer[20..30], "header checksum")?;
+            (
+                version,
+                header_offset,
+                file_len - TRAILER_LEN,
+                Some(header_crc),
+            )
+        } else {
+            let header_offset = parse_int::<usize>(tag.as_bytes(), "header offset")?;
+            (1, header_offset, file_len, None)
+        };
+    if header_start < PREFIX_LEN || header_start > header_end {
+        return Err(truncated(format!(
+            "the header starts at {}, but the file has only {} bytes",
+            header_start, file_len
+        )));
+    }
+    reader.seek(SeekFrom::Start(header_start as u64))?;
+    let mut header = vec![0u8; header_end - header_start];
+    reader.read_exact(&mut header)?;
+    if let Some(crc) = header_crc {
+        if crc32fast::hash(&header) != crc {
+            return Err(invalid_data(
+                "corrupted FASTTENSOR file: header checksum mismatch",
+            ));
+        }
+    }
+    Ok((version, header_start, header))
+}
+
+/// read and validate the header, only the header is read, the chunks are not touched
+pub(crate) fn read_header<R: Read + Seek>(reader: &mut R) -> std::io::Result<FileHeader> {
+    let (version, header_start, header) = read_raw_header(reader)?;
+    parse_header(version, header_start, &header)
+}
+
+fn parse_header(version: u32, header_start: usize, header: &[u8]) -> std::io::Result<FileHeader> {
+    let corrupted =
+        |e: serde_json::Error| invalid_data(format!("corrupted FASTTENSOR header: {}", e));
+    let mut header = serde_json::from_slice::<serde_json::Map<String, serde_json::Value>>(header)
+        .map_err(corrupted)?;
+    let metadata = header.remove(METADATA_KEY);
+    let infos = header
+        .into_iter()
+        .map(|(name, info)| Ok((name, serde_json::from_value::<HeaderInfo>(info)?)))
+        .collect::<serde_json::Result<HashMap<String, HeaderInfo>>>()
+        .map_err(corrupted)?;
+    for (name, info) in infos.iter() {
+        if !info.checksums.is_empty() && info.checksums.len() != info.indices.len() {
+            return Err(invalid_data(format!(
+                "corrupted FASTTENSOR header: {} has {} chunks but {} checksums",
+                name,
+                info.indices.len(),
+                info.checksums.len()
+            )));
+        }
+        for (_, offset, len, _) in info.indices.iter() {
+            if offset + len > header_start {
+                return Err(invalid_data(format!(
+                    "corrupted FASTTENSOR header: the chunk of {} at {}..{} overlaps the header at {}",
+                    name,
+                    offset,
+                    offset + len,
+                    header_start
+                )));
+            }
+        }
+    }
+    Ok(FileHeader {
+        version,
+        infos,
+        metadata,
+    })
+}
+
+/// read the struct metadata written by `Save`
+pub(crate) fn read_metadata<R: Read + Seek>(reader: &mut R) -> std::io::Result<serde_json::Value> {
+    let (version, header_start, header) = read_raw_header(reader)?;
+    if version == 1 {
+        return serde_json::from_slice(&header)
+            .map_err(|e| invalid_data(format!("corrupted FASTTENSOR header: {}", e)));
+    }
+    parse_header(version, header_start, &header)?
+        .metadata
+        .ok_or_else(|| invalid_data("the FASTTENSOR file is not written by `Save`"))
+}
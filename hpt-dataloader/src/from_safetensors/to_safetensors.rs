@@ -0,0 +1,144 @@
+use std::{borrow::Cow, collections::HashMap, path::Path};
+
+use hpt_traits::CommonBounds;
+use num::traits::ToBytes;
+use safetensors::{Dtype, View};
+
+use crate::{compression_trait::DataLoaderTrait, DataLoader};
+
+/// a tensor serialized into safetensors layout: C order and little endian
+pub struct SafeTensorData {
+    dtype: Dtype,
+    shape: Vec<usize>,
+    data: Vec<u8>,
+}
+
+impl SafeTensorData {
+    /// copy the data of the tensor, non contiguous tensors are gathered in C order
+    pub fn new<T, A, const N: usize>(tensor: A) -> std::io::Result<Self>
+    where
+        T: CommonBounds + ToBytes<Bytes = [u8; N]>,
+        A: Into<DataLoader<T>>,
+    {
+        let data: DataLoader<T> = tensor.into();
+        let dtype = safetensors_dtype::<T>()?;
+        let mut bytes = vec![0u8; data.size() * N];
+        if data.strides().inner() == data.shape().to_strides().inner() {
+            for (i, chunk) in bytes.chunks_exact_mut(N).enumerate() {
+                data.fill_le_bytes_slice(i as isize, chunk);
+            }
+        } else {
+            for (offset, chunk) in data.c_order_offsets().zip(bytes.chunks_exact_mut(N)) {
+                data.fill_le_bytes_slice(offset, chunk);
+            }
+        }
+        Ok(Self {
+            dtype,
+            shape: data.shape().iter().map(|x| *x as usize).collect(),
+            data: bytes,
+        })
+    }
+}
+
+impl View for &SafeTensorData {
+    fn dtype(&self) -> Dtype {
+        self.dtype
+    }
+
+    fn shape(&self) -> &[usize] {
+        &self.shape
+    }
+
+    fn data(&self) -> Cow<'_, [u8]> {
+        Cow::Borrowed(&self.data)
+    }
+
+    fn data_len(&self) -> usize {
+        self.data.len()
+    }
+}
+
+/// get the safetensors dtype of the hpt type `T`
+fn safetensors_dtype<T: CommonBounds>() -> std::io::Result<Dtype> {
+    match T::STR {
+        "bool" => Ok(Dtype::BOOL),
+        "i8" => Ok(Dtype::I8),
+        "u8" => Ok(Dtype::U8),
+        "i16" => Ok(Dtype::I16),
+        "u16" => Ok(Dtype::U16),
+        "i32" => Ok(Dtype::I32),
+        "u32" => Ok(Dtype::U32),
+        "i64" => Ok(Dtype::I64),
+        "u64" => Ok(Dtype::U64),
+        "isize" if std::mem::size_of::<isize>() == 8 => Ok(Dtype::I64),
+        "usize" if std::mem::size_of::<usize>() == 8 => Ok(Dtype::U64),
+        "f8e4m3" => Ok(Dtype::F8_E4M3),
+        "f8e5m2" => Ok(Dtype::F8_E5M2),
+        "f16" => Ok(Dtype::F16),
+        "bf16" => Ok(Dtype::BF16),
+        "f32" => Ok(Dtype::F32),
+        "f64" => Ok(Dtype::F64),
+        _ => Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidInput,
+            format!("{} is not supported by safetensors", T::STR),
+        )),
+    }
+}
+
+#[diagnostic::on_unimplemented(
+    message = "Cannot perform operation on type `{Self}` because it doesn't implement required features"
+)]
+pub trait ToSafeTensors {
+    /// collect the tensors of `self` into `tensors`, `tensor_name` is the name of `self` in the file
+    fn to_safe_tensors(
+        &self,
+        tensor_name: &str,
+        tensors: &mut Vec<(String, SafeTensorData)>,
+    ) -> std::io::Result<()>;
+
+    /// write `self` to a `.safetensors` file
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The path of the file.
+    /// * `tensor_name` - The name of `self`, the tensors of a struct are named `{tensor_name}.{field}`.
+    /// * `metadata` - The `__metadata__` of the file.
+    fn save_safetensors<P: AsRef<Path>>(
+        &self,
+        path: P,
+        tensor_name: &str,
+        metadata: Option<HashMap<String, String>>,
+    ) -> std::io::Result<()> {
+        let mut tensors = vec![];
+        self.to_safe_tensors(tensor_name, &mut tensors)?;
+        let views = tensors.iter().map(|(name, data)| (name.as_str(), data));
+        safetensors::serialize_to_file(views, &metadata, path.as_ref())
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))
+    }
+}
+
+impl<T: ToSafeTensors> ToSafeTensors for Option<T> {
+    fn to_safe_tensors(
+        &self,
+        tensor_name: &str,
+        tensors: &mut Vec<(String, SafeTensorData)>,
+    ) -> std::io::Result<()> {
+        match self {
+            Some(x) => x.to_safe_tensors(tensor_name, tensors),
+            None => Ok(()),
+        }
+    }
+}
+
+impl<T: ToSafeTensors> ToSafeTensors for Vec<T> {
+    fn to_safe_tensors(
+        &self,
+        tensor_name: &str,
+        tensors: &mut Vec<(String, SafeTensorData)>,
+    ) -> std::io::Result<()> {
+        for (i, x) in self.iter().enumerate() {
+            x.to_safe_tensors(&format!("{}.{}", tensor_name, i), tensors)?;
+        }
+        Ok(())
+    }
+}
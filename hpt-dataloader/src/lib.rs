@@ -6,7 +6,13 @@ pub use compression_trait::Meta;
 pub use compression_trait::{DataLoader, TensorLoader, TensorSaver};
 pub use data_loader::Endian;
 pub use flate2::write::{DeflateEncoder, GzEncoder, ZlibEncoder};
+pub use format::FORMAT_VERSION;
 pub use from_safetensors::from_safetensors::FromSafeTensors;
+pub use from_safetensors::to_safetensors::{SafeTensorData, ToSafeTensors};
+pub use load::{verify, TensorReader};
+pub use mmap::{FromMapped, MappedEntry, MmapLoader};
+pub use npy::{NpyHeader, NpzReader, NpzWriter};
+pub use save::TensorWriter;
 pub use struct_save::gen_header;
 pub use struct_save::load::{Load, MetaLoad};
 pub use struct_save::save::save;
@@ -20,9 +26,13 @@ mod struct_save {
 
 pub mod from_safetensors {
     pub mod from_safetensors;
+    pub mod to_safetensors;
 }
 
+mod format;
 pub mod load;
+pub mod mmap;
+pub mod npy;
 pub mod save;
 pub mod utils;
 
@@ -1,6 +1,5 @@
 use std::{
     collections::HashMap,
-    fs::File,
     io::{Read, Seek},
 };
 
@@ -9,24 +8,28 @@ use hpt_common::slice::{slice_process, Slice};
 use hpt_traits::{CommonBounds, TensorCreator, TensorInfo};
 use num::traits::FromBytes;
 
-use crate::{data_loader::HeaderInfo, CompressionAlgo, Endian};
+use crate::{
+    data_loader::HeaderInfo,
+    format::{read_header, FileHeader},
+    CompressionAlgo, Endian,
+};
 
 pub(crate) fn load_compressed_slice<
     'a,
     T: CommonBounds + FromBytes<Bytes = [u8; N]>,
     B: TensorCreator<T, Output = B> + Clone + TensorInfo<T>,
+    R: Read + Seek,
     const N: usize,
 >(
-    file_name: &str,
+    file: &mut R,
+    res: &HashMap<String, HeaderInfo>,
     queries: Vec<(String, Vec<Slice>)>,
 ) -> anyhow::Result<HashMap<String, B>> {
-    let res = HeaderInfo::parse_header_compressed(file_name)?;
-    let mut ret = HashMap::with_capacity(res.len());
-    let mut file = File::open(file_name)?;
+    let mut ret = HashMap::with_capacity(queries.len());
     for (name, slices) in queries {
         let info = res
             .get(&name)
-            .expect(&format!("{} not found in header", name));
+            .ok_or_else(|| anyhow::anyhow!("{} not found in header", name))?;
         if info.dtype != T::STR.to_string() {
             return Err(anyhow::anyhow!(
                 "the dtype stored is {}, but the dtype requested is {}",
@@ -75,11 +78,12 @@ pub(crate) fn load_compressed_slice<
         // get buffer index, compressed data offset, compressed data size, bytes of the data in the buffer
         let (block_locate_row, idx, compressed_len, mut block_mem_size) = info.indices[block_idx];
         let mut uncompressed_data = uncompress_data(
-            &mut file,
+            file,
             idx as u64,
             compressed_len,
             block_mem_size,
             info.compress_algo,
+            info.checksums.get(block_idx).copied(),
         )?;
         // calculate row index within the chunk
         let local_row_offset = (row_idx as usize) - block_locate_row;
@@ -105,11 +109,12 @@ pub(crate) fn load_compressed_slice<
                 let (_, new_idx, new_compressed_len, new_block_mem_size) = info.indices[block_idx];
                 block_mem_size = new_block_mem_size;
                 uncompressed_data = uncompress_data(
-                    &mut file,
+                    file,
                     new_idx as u64,
                     new_compressed_len,
                     new_block_mem_size,
                     info.compress_algo,
+                    info.checksums.get(block_idx).copied(),
                 )?;
             } else if start_idx >= block_mem_size as i64 {
                 let jumped_eles = start_idx - block_mem_size as i64;
@@ -122,11 +127,12 @@ pub(crate) fn load_compressed_slice<
                 let (_, new_idx, new_compressed_len, new_block_mem_size) = info.indices[block_idx];
                 block_mem_size = new_block_mem_size;
                 uncompressed_data = uncompress_data(
-                    &mut file,
+                    file,
                     new_idx as u64,
                     new_compressed_len,
                     new_block_mem_size,
                     info.compress_algo,
+                    info.checksums.get(block_idx).copied(),
                 )?;
             }
             let val = &uncompressed_data
@@ -149,16 +155,29 @@ pub(crate) fn load_compressed_slice<
     Ok(ret)
 }
 
-fn uncompress_data(
-    file: &mut File,
+/// read the chunk at `idx` and uncompress it, the stored bytes are checked against `checksum` if there is one
+pub(crate) fn uncompress_data<R: Read + Seek>(
+    file: &mut R,
     idx: u64,
     compressed_len: usize,
     block_mem_size: usize,
     compression_type: CompressionAlgo,
+    checksum: Option<u32>,
 ) -> std::io::Result<Vec<u8>> {
     file.seek(std::io::SeekFrom::Start(idx))?;
     let mut compressed_vec = vec![0u8; compressed_len];
     file.read_exact(&mut compressed_vec)?;
+    if let Some(checksum) = checksum {
+        if crc32fast::hash(&compressed_vec) != checksum {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                format!(
+                    "corrupted FASTTENSOR file: checksum mismatch of the chunk at {}",
+                    idx
+                ),
+            ));
+        }
+    }
     let mut uncompressed_data = vec![0u8; block_mem_size];
     match compression_type {
         CompressionAlgo::Gzip => {
@@ -174,13 +193,24 @@ fn uncompress_data(
             decoder.read_exact(&mut uncompressed_data)?;
         }
         CompressionAlgo::NoCompression => {
+            if compressed_vec.len() != block_mem_size {
+                return Err(std::io::Error::new(
+                    std::io::ErrorKind::InvalidData,
+                    format!(
+                        "corrupted FASTTENSOR file: the chunk at {} has {} bytes, expected {}",
+                        idx,
+                        compressed_vec.len(),
+                        block_mem_size
+                    ),
+                ));
+            }
             uncompressed_data = compressed_vec;
         }
     }
     Ok(uncompressed_data)
 }
 
-fn get_pack_closure<T: CommonBounds + FromBytes<Bytes = [u8; N]>, const N: usize>(
+pub(crate) fn get_pack_closure<T: CommonBounds + FromBytes<Bytes = [u8; N]>, const N: usize>(
     endian: Endian,
 ) -> impl Fn(&[u8]) -> T {
     match endian {
@@ -189,3 +219,107 @@ fn get_pack_closure<T: CommonBounds + FromBytes<Bytes = [u8; N]>, const N: usize
         Endian::Native => |val: &[u8]| T::from_ne_bytes(val.try_into().unwrap()),
     }
 }
+
+/// convert the error of `load_compressed_slice` back to `std::io::Error`
+pub(crate) fn into_io_error(err: anyhow::Error) -> std::io::Error {
+    match err.downcast::<std::io::Error>() {
+        Ok(err) => err,
+        Err(err) => std::io::Error::new(std::io::ErrorKind::InvalidData, err.to_string()),
+    }
+}
+
+/// streaming reader of the FASTTENSOR format
+///
+/// only the header is read when the reader is created, tensors are read on demand from any `Read + Seek`,
+/// for example a `File` or a `std::io::Cursor<Vec<u8>>`. Both version 1 and version 2 files are supported,
+/// the chunks of version 2 files are checked against their checksums when they are read.
+pub struct TensorReader<R: Read + Seek> {
+    reader: R,
+    header: FileHeader,
+}
+
+impl<R: Read + Seek> TensorReader<R> {
+    /// read and validate the header
+    pub fn new(mut reader: R) -> std::io::Result<Self> {
+        let header = read_header(&mut reader)?;
+        Ok(Self { reader, header })
+    }
+
+    /// the format version of the file
+    pub fn version(&self) -> u32 {
+        self.header.version
+    }
+
+    /// the names of the tensors in the file
+    pub fn names(&self) -> Vec<&str> {
+        self.header.infos.keys().map(|x| x.as_str()).collect()
+    }
+
+    /// the shape and the dtype (`TypeCommon::STR`) of the tensor `name`
+    pub fn info(&self, name: &str) -> Option<(&[i64], &str)> {
+        self.header
+            .infos
+            .get(name)
+            .map(|x| (x.shape.as_slice(), x.dtype.as_str()))
+    }
+
+    /// read the tensor `name`, `slices` selects a part of the tensor, empty `slices` reads the whole tensor
+    pub fn read<T, B, const N: usize>(&mut self, name: &str, slices: &[Slice]) -> std::io::Result<B>
+    where
+        T: CommonBounds + FromBytes<Bytes = [u8; N]>,
+        B: TensorCreator<T, Output = B> + Clone + TensorInfo<T>,
+    {
+        let mut res = load_compressed_slice::<T, B, R, N>(
+            &mut self.reader,
+            &self.header.infos,
+            vec![(name.to_string(), slices.to_vec())],
+        )
+        .map_err(into_io_error)?;
+        Ok(res.remove(name).expect("the tensor is loaded"))
+    }
+
+    /// check the checksum of every chunk and that every chunk can be uncompressed
+    pub fn verify(&mut self) -> std::io::Result<()> {
+        for (name, info) in self.header.infos.iter() {
+            for (idx, (_, offset, compressed_len, mem_size)) in info.indices.iter().enumerate() {
+                uncompress_data(
+                    &mut self.reader,
+                    *offset as u64,
+                    *compressed_len,
+                    *mem_size,
+                    info.compress_algo,
+                    info.checksums.get(idx).copied(),
+                )
+                .map_err(|e| {
+                    std::io::Error::new(e.kind(), format!("{}: chunk {}: {}", name, idx, e))
+                })?;
+            }
+            let stored = info.indices.iter().map(|x| x.3).sum::<usize>();
+            let expected = info.size * info.dtype_size()?;
+            if stored != expected {
+                return Err(std::io::Error::new(
+                    std::io::ErrorKind::InvalidData,
+                    format!(
+                        "corrupted FASTTENSOR file: {} has {} bytes, expected {}",
+                        name, stored, expected
+                    ),
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// return the inner reader
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+}
+
+/// check the integrity of a FASTTENSOR file
+///
+/// the header, the checksum of every chunk (version 2 files) and the size of every tensor are checked.
+/// a truncated file returns an `UnexpectedEof` error, a corrupted file returns an `InvalidData` error.
+pub fn verify<P: AsRef<std::path::Path>>(path: P) -> std::io::Result<()> {
+    let file = std::io::BufReader::new(std::fs::File::open(path)?);
+    TensorReader::new(file)?.verify()
+}
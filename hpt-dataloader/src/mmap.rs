@@ -0,0 +1,308 @@
+//! memory mapped, lazy tensor loading
+//!
+//! `MmapLoader` only parses the header when the file is opened, tensors are created when they are requested.
+//! uncompressed entries stored in the native endianness can be viewed in the read-only mapping without copy,
+//! `load` copies or decodes the entries into freshly allocated tensors.
+
+use std::{
+    collections::HashMap,
+    fs::File,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use hpt_traits::{CommonBounds, TensorCreator, TensorInfo};
+use memmap2::{Mmap, MmapOptions};
+use num::traits::FromBytes;
+use safetensors::{Dtype, SafeTensors};
+
+use crate::{
+    data_loader::HeaderInfo,
+    format::{invalid_data, read_header},
+    load::{get_pack_closure, into_io_error, load_compressed_slice},
+    CompressionAlgo, Endian,
+};
+
+/// the closure releasing the reference of the mapping held by a tensor
+pub type MappingRelease = Box<dyn FnOnce() + Send>;
+
+/// tensors that can view memory mapped data
+pub trait FromMapped<T>: Sized {
+    /// create a contiguous tensor viewing `ptr`, `release` is called once the last tensor referencing the memory is dropped
+    ///
+    /// # Safety
+    ///
+    /// `ptr` must be aligned and valid for `shape.iter().product()` elements until `release` is called
+    unsafe fn from_mapped(
+        ptr: *mut T,
+        shape: &[i64],
+        release: MappingRelease,
+    ) -> std::io::Result<Self>;
+}
+
+/// how the entry is stored in the file
+#[derive(Debug, Clone)]
+enum Storage {
+    /// the bytes of the entry are stored contiguously in C order at `offset`
+    Raw { offset: usize },
+    /// the entry is compressed, it can only be decoded
+    Compressed,
+}
+
+/// the information of an entry in the mapped file
+#[derive(Debug, Clone)]
+pub struct MappedEntry {
+    /// the hpt type name of the entry (`TypeCommon::STR`)
+    pub dtype: String,
+    /// the shape of the entry
+    pub shape: Vec<i64>,
+    /// the endianness of the stored data
+    pub endian: Endian,
+    storage: Storage,
+}
+
+impl MappedEntry {
+    /// check if the entry can be viewed as `T` without copy
+    fn is_viewable<T: CommonBounds>(&self, base: *const u8) -> bool {
+        let native = match self.endian {
+            Endian::Native => true,
+            Endian::Little => cfg!(target_endian = "little"),
+            Endian::Big => cfg!(target_endian = "big"),
+        };
+        match self.storage {
+            Storage::Raw { offset } => {
+                native && (base as usize + offset) % std::mem::align_of::<T>() == 0
+            }
+            Storage::Compressed => false,
+        }
+    }
+}
+
+/// a read-only file mapping that lazily creates tensors
+///
+/// FASTTENSOR files written by `TensorSaver` and `.safetensors` files are supported.
+///
+/// the file is mapped read-only, so the pages are shared with the page cache and the other processes mapping the file.
+/// `load` returns tensors owning a copy of the data, `view` returns tensors viewing the mapping that must never be written.
+/// the mapping is refcounted, it stays alive until both the loader and all the tensors viewing it are dropped.
+///
+/// the checksums of FASTTENSOR files are only checked when an entry is decoded, views are not checked,
+/// use `verify` to check the whole file.
+pub struct MmapLoader {
+    path: PathBuf,
+    mmap: Arc<Mmap>,
+    entries: HashMap<String, MappedEntry>,
+    /// the header of FASTTENSOR files, used to decode the compressed entries
+    infos: HashMap<String, HeaderInfo>,
+}
+
+impl MmapLoader {
+    /// map the file and parse its header
+    pub fn open<P: AsRef<Path>>(path: P) -> std::io::Result<Self> {
+        let file = File::open(path.as_ref())?;
+        // Safety: the file must not be truncated by other processes while it is mapped, this is the same assumption made by all mmap based loaders
+        let mmap = unsafe { MmapOptions::new().map(&file)? };
+        let (entries, infos) = if mmap.starts_with(b"FASTTENSOR") {
+            let infos = read_header(&mut std::io::Cursor::new(&mmap[..]))?.infos;
+            (Self::parse_fasttensor(&infos), infos)
+        } else {
+            (Self::parse_safetensors(&mmap)?, HashMap::new())
+        };
+        Ok(Self {
+            path: path.as_ref().to_path_buf(),
+            mmap: Arc::new(mmap),
+            entries,
+            infos,
+        })
+    }
+
+    fn parse_fasttensor(infos: &HashMap<String, HeaderInfo>) -> HashMap<String, MappedEntry> {
+        infos
+            .iter()
+            .map(|(name, info)| {
+                // chunks of an uncompressed entry are written one after another
+                let contiguous = info.indices.windows(2).all(|x| x[0].1 + x[0].2 == x[1].1);
+                let storage = match info.compress_algo {
+                    CompressionAlgo::NoCompression if contiguous && !info.indices.is_empty() => {
+                        Storage::Raw {
+                            offset: info.indices[0].1,
+                        }
+                    }
+                    _ => Storage::Compressed,
+                };
+                let entry = MappedEntry {
+                    dtype: info.dtype.clone(),
+                    shape: info.shape.clone(),
+                    endian: info.endian,
+                    storage,
+                };
+                (name.clone(), entry)
+            })
+            .collect()
+    }
+
+    fn parse_safetensors(data: &[u8]) -> std::io::Result<HashMap<String, MappedEntry>> {
+        let (header_len, metadata) = SafeTensors::read_metadata(data).map_err(invalid_data)?;
+        let data_start = header_len + 8;
+        metadata
+            .tensors()
+            .into_iter()
+            .map(|(name, info)| {
+                let dtype = hpt_dtype(info.dtype).ok_or_else(|| {
+                    invalid_data(format!("{:?} of {} is not supported", info.dtype, name))
+                })?;
+                let entry = MappedEntry {
+                    dtype: dtype.to_string(),
+                    shape: info.shape.iter().map(|x| *x as i64).collect(),
+                    endian: Endian::Little,
+                    storage: Storage::Raw {
+                        offset: data_start + info.data_offsets.0,
+                    },
+                };
+                Ok((name, entry))
+            })
+            .collect()
+    }
+
+    /// the names of the entries in the file
+    pub fn names(&self) -> Vec<&str> {
+        self.entries.keys().map(|x| x.as_str()).collect()
+    }
+
+    /// the information of the entry `name`
+    pub fn entry(&self, name: &str) -> Option<&MappedEntry> {
+        self.entries.get(name)
+    }
+
+    /// check if the entry `name` can be viewed as `T` with [`MmapLoader::view`]
+    pub fn is_viewable<T: CommonBounds>(&self, name: &str) -> bool {
+        self.entries
+            .get(name)
+            .map(|x| x.dtype == T::STR && x.is_viewable::<T>(self.mmap.as_ptr()))
+            .unwrap_or(false)
+    }
+
+    /// get the tensor `name`
+    ///
+    /// the data is copied, or decoded if the entry is compressed or stored in another endianness, into a new tensor
+    pub fn load<T, B, const N: usize>(&self, name: &str) -> std::io::Result<B>
+    where
+        T: CommonBounds + FromBytes<Bytes = [u8; N]>,
+        B: TensorCreator<T, Output = B> + TensorInfo<T> + Clone,
+    {
+        let entry = self.typed_entry::<T>(name)?;
+        let size = entry.shape.iter().product::<i64>() as usize;
+        match entry.storage {
+            Storage::Raw { offset } => {
+                let bytes = self.raw_bytes(name, offset, size * N)?;
+                let tensor = B::empty(&entry.shape).map_err(invalid_data)?;
+                if size > 0 {
+                    let res: &mut [T] =
+                        unsafe { std::slice::from_raw_parts_mut(tensor.ptr().ptr, size) };
+                    let pack = get_pack_closure::<T, N>(entry.endian);
+                    for (val, bytes) in res.iter_mut().zip(bytes.chunks_exact(N)) {
+                        *val = pack(bytes);
+                    }
+                }
+                Ok(tensor)
+            }
+            Storage::Compressed => {
+                let mut res = load_compressed_slice::<T, B, _, N>(
+                    &mut std::io::Cursor::new(&self.mmap[..]),
+                    &self.infos,
+                    vec![(name.to_string(), vec![])],
+                )
+                .map_err(into_io_error)?;
+                res.remove(name)
+                    .ok_or_else(|| invalid_data(format!("failed to load {}", name)))
+            }
+        }
+    }
+
+    /// get the tensor `name` viewing the mapping without copy
+    ///
+    /// fails if the entry is compressed, stored in another endianness or not aligned for `T`, see [`MmapLoader::is_viewable`]
+    ///
+    /// # Safety
+    ///
+    /// the mapping is read-only, the returned tensor and the tensors sharing its memory must not be written to,
+    /// neither directly nor as the output of an operation
+    pub unsafe fn view<T, B>(&self, name: &str) -> std::io::Result<B>
+    where
+        T: CommonBounds,
+        B: FromMapped<T>,
+    {
+        let entry = self.typed_entry::<T>(name)?;
+        let size = entry.shape.iter().product::<i64>() as usize;
+        match entry.storage {
+            Storage::Raw { offset } if entry.is_viewable::<T>(self.mmap.as_ptr()) => {
+                let bytes = self.raw_bytes(name, offset, size * std::mem::size_of::<T>())?;
+                let mmap = self.mmap.clone();
+                B::from_mapped(
+                    bytes.as_ptr() as *mut T,
+                    &entry.shape,
+                    Box::new(move || drop(mmap)),
+                )
+            }
+            _ => Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                format!(
+                    "{} can't be viewed as {}, use load to decode it",
+                    name,
+                    T::STR
+                ),
+            )),
+        }
+    }
+
+    /// get the entry `name`, checking that it is stored as `T`
+    fn typed_entry<T: CommonBounds>(&self, name: &str) -> std::io::Result<&MappedEntry> {
+        let entry = self.entries.get(name).ok_or_else(|| {
+            std::io::Error::new(
+                std::io::ErrorKind::NotFound,
+                format!("{} not found in {}", name, self.path.display()),
+            )
+        })?;
+        if entry.dtype != T::STR {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                format!(
+                    "the dtype stored is {}, but the dtype requested is {}",
+                    entry.dtype,
+                    T::STR
+                ),
+            ));
+        }
+        Ok(entry)
+    }
+
+    /// the `len` bytes of the entry `name` stored at `offset`
+    fn raw_bytes(&self, name: &str, offset: usize, len: usize) -> std::io::Result<&[u8]> {
+        if offset + len > self.mmap.len() {
+            return Err(invalid_data(format!("{} is out of the file range", name)));
+        }
+        Ok(&self.mmap[offset..offset + len])
+    }
+}
+
+/// get the hpt type name of the safetensors dtype
+fn hpt_dtype(dtype: Dtype) -> Option<&'static str> {
+    match dtype {
+        Dtype::BOOL => Some("bool"),
+        Dtype::I8 => Some("i8"),
+        Dtype::U8 => Some("u8"),
+        Dtype::I16 => Some("i16"),
+        Dtype::U16 => Some("u16"),
+        Dtype::I32 => Some("i32"),
+        Dtype::U32 => Some("u32"),
+        Dtype::I64 => Some("i64"),
+        Dtype::U64 => Some("u64"),
+        Dtype::F8_E4M3 => Some("f8e4m3"),
+        Dtype::F8_E5M2 => Some("f8e5m2"),
+        Dtype::F16 => Some("f16"),
+        Dtype::BF16 => Some("bf16"),
+        Dtype::F32 => Some("f32"),
+        Dtype::F64 => Some("f64"),
+        _ => None,
+    }
+}
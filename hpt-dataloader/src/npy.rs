@@ -0,0 +1,493 @@
+//! NumPy `.npy` and `.npz` support
+//!
+//! format reference: <https://numpy.org/doc/stable/reference/generated/numpy.lib.format.html>
+
+use std::{
+    fs::File,
+    io::{Read, Seek, Write},
+    path::Path,
+};
+
+use hpt_common::shape::shape::Shape;
+use hpt_traits::{CommonBounds, ShapeManipulate, TensorCreator, TensorInfo};
+use num::traits::{FromBytes, ToBytes};
+use zip::{write::FileOptions, CompressionMethod, ZipArchive, ZipWriter};
+
+use crate::{
+    compression_trait::{DataLoader, DataLoaderTrait},
+    load::get_pack_closure,
+    Endian,
+};
+
+const MAGIC: &[u8] = b"\x93NUMPY";
+
+/// the parsed header of a `.npy` file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NpyHeader {
+    /// the array protocol type string, for example `<f4`
+    pub descr: String,
+    /// whether the data is stored in fortran (column major) order
+    pub fortran_order: bool,
+    /// the shape of the array
+    pub shape: Vec<i64>,
+}
+
+fn invalid_data<E: Into<Box<dyn std::error::Error + Send + Sync>>>(err: E) -> std::io::Error {
+    std::io::Error::new(std::io::ErrorKind::InvalidData, err)
+}
+
+/// get the numpy type kind and the item size of the hpt type `T`
+fn numpy_kind<T: CommonBounds>() -> std::io::Result<(char, usize)> {
+    let kind = match T::STR {
+        "bool" => 'b',
+        "i8" | "i16" | "i32" | "i64" | "isize" => 'i',
+        "u8" | "u16" | "u32" | "u64" | "usize" => 'u',
+        "f16" | "f32" | "f64" => 'f',
+        "c32" | "c64" => 'c',
+        _ => {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                format!("{} has no numpy equivalent", T::STR),
+            ))
+        }
+    };
+    Ok((kind, std::mem::size_of::<T>()))
+}
+
+impl NpyHeader {
+    /// parse the byte order of the `descr`
+    pub fn endian(&self) -> std::io::Result<Endian> {
+        match self.descr.chars().next() {
+            Some('<') => Ok(Endian::Little),
+            Some('>') => Ok(Endian::Big),
+            Some('|') | Some('=') => Ok(Endian::Native),
+            _ => Err(invalid_data(format!("invalid descr {}", self.descr))),
+        }
+    }
+
+    /// parse the type kind and the item size of the `descr`
+    pub fn kind(&self) -> std::io::Result<(char, usize)> {
+        let mut chars = self.descr.chars();
+        chars.next();
+        let kind = chars
+            .next()
+            .ok_or_else(|| invalid_data(format!("invalid descr {}", self.descr)))?;
+        let size = chars
+            .as_str()
+            .parse::<usize>()
+            .map_err(|_| invalid_data(format!("unsupported descr {}", self.descr)))?;
+        Ok((kind, size))
+    }
+
+    /// create the header of `T` with the `shape`
+    pub fn new<T: CommonBounds>(
+        shape: Vec<i64>,
+        fortran_order: bool,
+        endian: Endian,
+    ) -> std::io::Result<Self> {
+        let (kind, size) = numpy_kind::<T>()?;
+        let order = if size == 1 {
+            '|'
+        } else {
+            match endian {
+                Endian::Little => '<',
+                Endian::Big => '>',
+                Endian::Native => {
+                    if cfg!(target_endian = "little") {
+                        '<'
+                    } else {
+                        '>'
+                    }
+                }
+            }
+        };
+        Ok(NpyHeader {
+            descr: format!("{}{}{}", order, kind, size),
+            fortran_order,
+            shape,
+        })
+    }
+
+    /// read the magic string, the version and the header dictionary
+    pub fn read<R: Read>(reader: &mut R) -> std::io::Result<Self> {
+        let mut magic = [0u8; 6];
+        reader.read_exact(&mut magic)?;
+        if magic != MAGIC {
+            return Err(invalid_data("not a npy file"));
+        }
+        let mut version = [0u8; 2];
+        reader.read_exact(&mut version)?;
+        let header_len = match version[0] {
+            1 => {
+                let mut len = [0u8; 2];
+                reader.read_exact(&mut len)?;
+                u16::from_le_bytes(len) as usize
+            }
+            2 | 3 => {
+                let mut len = [0u8; 4];
+                reader.read_exact(&mut len)?;
+                u32::from_le_bytes(len) as usize
+            }
+            major => {
+                return Err(invalid_data(format!(
+                    "unsupported npy version {}.{}",
+                    major, version[1]
+                )))
+            }
+        };
+        let mut header = vec![0u8; header_len];
+        reader.read_exact(&mut header)?;
+        let header = std::str::from_utf8(&header).map_err(invalid_data)?;
+        Self::parse(header)
+    }
+
+    /// parse the header dictionary, for example `{'descr': '<f4', 'fortran_order': False, 'shape': (3, 4), }`
+    pub fn parse(header: &str) -> std::io::Result<Self> {
+        let header = header.trim();
+        let body = header
+            .strip_prefix('{')
+            .and_then(|x| x.strip_suffix('}'))
+            .ok_or_else(|| invalid_data(format!("invalid npy header {}", header)))?;
+        let mut descr = None;
+        let mut fortran_order = None;
+        let mut shape = None;
+        let mut rest = body.trim_start();
+        while !rest.is_empty() {
+            let (key, remain) = parse_str(rest)?;
+            let remain = remain
+                .trim_start()
+                .strip_prefix(':')
+                .ok_or_else(|| invalid_data(format!("expected ':' after key {}", key)))?
+                .trim_start();
+            rest = match key.as_str() {
+                "descr" => {
+                    let (value, remain) = parse_str(remain)?;
+                    descr = Some(value);
+                    remain
+                }
+                "fortran_order" => {
+                    if let Some(remain) = remain.strip_prefix("True") {
+                        fortran_order = Some(true);
+                        remain
+                    } else if let Some(remain) = remain.strip_prefix("False") {
+                        fortran_order = Some(false);
+                        remain
+                    } else {
+                        return Err(invalid_data("fortran_order must be True or False"));
+                    }
+                }
+                "shape" => {
+                    let end = remain
+                        .find(')')
+                        .ok_or_else(|| invalid_data("shape must be a tuple"))?;
+                    let tuple = remain[..end]
+                        .strip_prefix('(')
+                        .ok_or_else(|| invalid_data("shape must be a tuple"))?;
+                    shape = Some(
+                        tuple
+                            .split(',')
+                            .map(|x| x.trim().trim_end_matches('L'))
+                            .filter(|x| !x.is_empty())
+                            .map(|x| x.parse::<i64>().map_err(invalid_data))
+                            .collect::<std::io::Result<Vec<i64>>>()?,
+                    );
+                    &remain[end + 1..]
+                }
+                _ => return Err(invalid_data(format!("unknown key {} in npy header", key))),
+            };
+            rest = rest.trim_start();
+            rest = rest.strip_prefix(',').unwrap_or(rest).trim_start();
+        }
+        match (descr, fortran_order, shape) {
+            (Some(descr), Some(fortran_order), Some(shape)) => Ok(NpyHeader {
+                descr,
+                fortran_order,
+                shape,
+            }),
+            _ => Err(invalid_data(format!("incomplete npy header {}", header))),
+        }
+    }
+
+    /// write the magic string, the version and the header dictionary, the header is padded to 64 bytes alignment
+    pub fn write<W: Write>(&self, writer: &mut W) -> std::io::Result<()> {
+        let shape = match self.shape.len() {
+            1 => format!("({},)", self.shape[0]),
+            _ => format!(
+                "({})",
+                self.shape
+                    .iter()
+                    .map(|x| x.to_string())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+        };
+        let mut dict = format!(
+            "{{'descr': '{}', 'fortran_order': {}, 'shape': {}, }}",
+            self.descr,
+            if self.fortran_order { "True" } else { "False" },
+            shape
+        );
+        // magic + version + header len
+        let mut prefix_len = MAGIC.len() + 2 + 2;
+        if prefix_len + dict.len() + 1 > u16::MAX as usize {
+            prefix_len += 2;
+        }
+        let total = (prefix_len + dict.len() + 1).div_ceil(64) * 64;
+        dict.push_str(&" ".repeat(total - prefix_len - dict.len() - 1));
+        dict.push('\n');
+        writer.write_all(MAGIC)?;
+        if prefix_len == MAGIC.len() + 4 {
+            writer.write_all(&[1, 0])?;
+            writer.write_all(&(dict.len() as u16).to_le_bytes())?;
+        } else {
+            writer.write_all(&[2, 0])?;
+            writer.write_all(&(dict.len() as u32).to_le_bytes())?;
+        }
+        writer.write_all(dict.as_bytes())
+    }
+}
+
+fn parse_str(s: &str) -> std::io::Result<(String, &str)> {
+    let quote = s
+        .chars()
+        .next()
+        .filter(|x| *x == '\'' || *x == '"')
+        .ok_or_else(|| invalid_data(format!("expected string in npy header, got {}", s)))?;
+    let end = s[1..]
+        .find(quote)
+        .ok_or_else(|| invalid_data("unterminated string in npy header"))?;
+    Ok((s[1..end + 1].to_string(), &s[end + 2..]))
+}
+
+/// read a `.npy` array from the `reader`
+///
+/// fortran ordered arrays are loaded into a tensor with reversed shape and permuted back, so the result is a strided view without copy
+pub fn read_npy<T, B, R, const N: usize>(reader: &mut R) -> std::io::Result<B>
+where
+    T: CommonBounds + FromBytes<Bytes = [u8; N]>,
+    B: TensorCreator<T, Output = B> + TensorInfo<T> + ShapeManipulate<Output = B>,
+    R: Read,
+{
+    let header = NpyHeader::read(reader)?;
+    if header.kind()? != numpy_kind::<T>()? {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidInput,
+            format!(
+                "the dtype stored is {}, but the dtype requested is {}",
+                header.descr,
+                T::STR
+            ),
+        ));
+    }
+    let endian = header.endian()?;
+    let mut shape = header.shape.clone();
+    if header.fortran_order {
+        shape.reverse();
+    }
+    let tensor = B::empty(&shape).map_err(invalid_data)?;
+    if tensor.size() > 0 {
+        let res: &mut [T] =
+            unsafe { std::slice::from_raw_parts_mut(tensor.ptr().ptr, tensor.size()) };
+        let pack = get_pack_closure::<T, N>(endian);
+        let mut buffer = vec![0u8; crate::CHUNK_BUFF / N * N];
+        let mut idx = 0;
+        while idx < res.len() {
+            let len = (res.len() - idx).min(buffer.len() / N);
+            reader.read_exact(&mut buffer[..len * N])?;
+            for (val, bytes) in res[idx..idx + len]
+                .iter_mut()
+                .zip(buffer[..len * N].chunks_exact(N))
+            {
+                *val = pack(bytes);
+            }
+            idx += len;
+        }
+    }
+    if header.fortran_order && shape.len() > 1 {
+        let axes = (0..shape.len() as i64).rev().collect::<Vec<_>>();
+        tensor.permute(axes).map_err(invalid_data)
+    } else {
+        Ok(tensor)
+    }
+}
+
+/// write the tensor as a `.npy` array to the `writer`
+///
+/// tensors that are contiguous in fortran order (for example, a transposed tensor) are written in memory order with `fortran_order: True`
+pub fn write_npy<T, A, W, const N: usize>(
+    writer: &mut W,
+    tensor: A,
+    endian: Endian,
+) -> std::io::Result<()>
+where
+    T: CommonBounds + ToBytes<Bytes = [u8; N]>,
+    A: Into<DataLoader<T>>,
+    W: Write,
+{
+    let data: DataLoader<T> = tensor.into();
+    let shape = data.shape().inner().clone();
+    let strides = data.strides().inner().clone();
+    let mut reversed = shape.clone();
+    reversed.reverse();
+    let mut f_strides = Shape::from(reversed).to_strides().inner().clone();
+    f_strides.reverse();
+    let c_strides = data.shape().to_strides().inner().clone();
+    let fortran_order = shape.len() > 1 && strides == f_strides && strides != c_strides;
+    NpyHeader::new::<T>(shape.clone(), fortran_order, endian)?.write(writer)?;
+    let size = data.size();
+    if size == 0 {
+        return Ok(());
+    }
+    let fill = match endian {
+        Endian::Little => DataLoader::<T>::fill_le_bytes_slice,
+        Endian::Big => DataLoader::<T>::fill_be_bytes_slice,
+        Endian::Native => DataLoader::<T>::fill_ne_bytes_slice,
+    };
+    let offsets: Box<dyn Iterator<Item = isize>> = if fortran_order {
+        Box::new(0..size as isize)
+    } else {
+        Box::new(data.c_order_offsets())
+    };
+    let mut buffer = Vec::with_capacity((crate::CHUNK_BUFF / N).min(size) * N);
+    for offset in offsets {
+        let start = buffer.len();
+        buffer.resize(start + N, 0);
+        fill(&data, offset, &mut buffer[start..]);
+        if buffer.len() == buffer.capacity() {
+            writer.write_all(&buffer)?;
+            buffer.clear();
+        }
+    }
+    writer.write_all(&buffer)
+}
+
+/// load a `.npy` file
+pub fn load_npy<T, B, P, const N: usize>(path: P) -> std::io::Result<B>
+where
+    T: CommonBounds + FromBytes<Bytes = [u8; N]>,
+    B: TensorCreator<T, Output = B> + TensorInfo<T> + ShapeManipulate<Output = B>,
+    P: AsRef<Path>,
+{
+    let mut reader = std::io::BufReader::new(File::open(path)?);
+    read_npy::<T, B, _, N>(&mut reader)
+}
+
+/// save the tensor to a `.npy` file
+pub fn save_npy<T, A, P, const N: usize>(path: P, tensor: A, endian: Endian) -> std::io::Result<()>
+where
+    T: CommonBounds + ToBytes<Bytes = [u8; N]>,
+    A: Into<DataLoader<T>>,
+    P: AsRef<Path>,
+{
+    let mut writer = std::io::BufWriter::new(File::create(path)?);
+    write_npy::<T, A, _, N>(&mut writer, tensor, endian)?;
+    writer.flush()
+}
+
+/// reader of the `.npz` archive, both stored and deflated members are supported
+pub struct NpzReader<R: Read + Seek> {
+    archive: ZipArchive<R>,
+}
+
+impl NpzReader<File> {
+    /// open a `.npz` file
+    pub fn open<P: AsRef<Path>>(path: P) -> std::io::Result<Self> {
+        Self::new(File::open(path)?)
+    }
+}
+
+impl<R: Read + Seek> NpzReader<R> {
+    /// create a reader from any `Read + Seek`
+    pub fn new(reader: R) -> std::io::Result<Self> {
+        Ok(Self {
+            archive: ZipArchive::new(reader)?,
+        })
+    }
+
+    /// the names of the arrays in the archive, without the `.npy` extension
+    pub fn names(&self) -> Vec<String> {
+        self.archive
+            .file_names()
+            .map(|x| x.strip_suffix(".npy").unwrap_or(x).to_string())
+            .collect()
+    }
+
+    /// read the header of the array `name`
+    pub fn header(&mut self, name: &str) -> std::io::Result<NpyHeader> {
+        let mut file = self.member(name)?;
+        NpyHeader::read(&mut file)
+    }
+
+    /// load the array `name`
+    pub fn by_name<T, B, const N: usize>(&mut self, name: &str) -> std::io::Result<B>
+    where
+        T: CommonBounds + FromBytes<Bytes = [u8; N]>,
+        B: TensorCreator<T, Output = B> + TensorInfo<T> + ShapeManipulate<Output = B>,
+    {
+        let mut file = self.member(name)?;
+        read_npy::<T, B, _, N>(&mut file)
+    }
+
+    fn member(&mut self, name: &str) -> std::io::Result<zip::read::ZipFile<'_>> {
+        let member = if name.ends_with(".npy") {
+            name.to_string()
+        } else {
+            format!("{}.npy", name)
+        };
+        let has_member = self.archive.file_names().any(|x| x == member);
+        Ok(self
+            .archive
+            .by_name(if has_member { &member } else { name })?)
+    }
+}
+
+/// writer of the `.npz` archive
+pub struct NpzWriter<W: Write + Seek> {
+    zip: ZipWriter<W>,
+    compression: CompressionMethod,
+}
+
+impl NpzWriter<File> {
+    /// create a `.npz` file, members are deflated when `compressed` is true (`np.savez_compressed`), otherwise stored (`np.savez`)
+    pub fn create<P: AsRef<Path>>(path: P, compressed: bool) -> std::io::Result<Self> {
+        Ok(Self::new(File::create(path)?, compressed))
+    }
+}
+
+impl<W: Write + Seek> NpzWriter<W> {
+    /// create a writer on any `Write + Seek`
+    pub fn new(writer: W, compressed: bool) -> Self {
+        Self {
+            zip: ZipWriter::new(writer),
+            compression: if compressed {
+                CompressionMethod::Deflated
+            } else {
+                CompressionMethod::Stored
+            },
+        }
+    }
+
+    /// add the tensor to the archive as `{name}.npy`
+    pub fn add_array<T, A, const N: usize>(
+        &mut self,
+        name: &str,
+        tensor: A,
+        endian: Endian,
+    ) -> std::io::Result<()>
+    where
+        T: CommonBounds + ToBytes<Bytes = [u8; N]>,
+        A: Into<DataLoader<T>>,
+    {
+        let data: DataLoader<T> = tensor.into();
+        let options = FileOptions::default()
+            .compression_method(self.compression)
+            .large_file(data.size() * N >= u32::MAX as usize);
+        self.zip.start_file(format!("{}.npy", name), options)?;
+        write_npy::<T, _, _, N>(&mut self.zip, data, endian)
+    }
+
+    /// write the central directory and return the inner writer
+    pub fn finish(mut self) -> std::io::Result<W> {
+        Ok(self.zip.finish()?)
+    }
+}
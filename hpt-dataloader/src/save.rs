@@ -1,106 +1,124 @@
-use std::io::{Seek, Write};
+use std::collections::HashSet;
+use std::io::Write;
 
-use crate::compression_trait::{CompressionAlgo, DataLoaderTrait, Meta};
-use crate::utils::create_file;
+use crate::compression_trait::{CompressionAlgo, DataLoader, DataLoaderTrait, Meta};
+use crate::data_loader::HeaderInfo;
+use crate::format::{prefix, trailer, METADATA_KEY};
+use crate::utils::{create_file, pad_for_mmap};
 use crate::Endian;
 use crate::{compression_trait::CompressionTrait, CHUNK_BUFF};
 use flate2::write::{DeflateEncoder, GzEncoder, ZlibEncoder};
+use hpt_traits::CommonBounds;
 use indicatif::ProgressBar;
+use num::traits::ToBytes;
 
-fn generate_header_compressed(
-    tensors: &[Meta],
-) -> (Vec<String>, Vec<(String, usize, usize, usize, usize)>) {
-    let infos: Vec<String> = tensors
-        .iter()
-        .map(|meta| {
-            format!(
-                r#""{}":{{"begin":FASTTENSOR_PLACEHOLD,"name":"{}","shape":{:?},"strides":{:?},"size":{},"dtype":"{}","compress_algo":{},"endian":{},"indices":place_holder0}}"#,
-                meta.name,
-                meta.name,
-                meta.data_saver.shape().to_vec(),
-                meta.data_saver.shape().to_strides().to_vec(),
-                meta.data_saver.size(),
-                meta.data_saver.dtype(),
-                serde_json::to_string(&meta.compression_algo).expect("Failed to serialize compression_algo"),
-                serde_json::to_string(&meta.endian).expect("Failed to serialize endian")
-            )
-        })
-        .collect();
-    let res: Vec<(String, usize, usize, usize, usize)> = tensors
-        .iter()
-        .map(|meta| {
-            let x = &meta.data_saver;
-            let outer = x.size() / (*x.shape().last().unwrap() as usize);
-            let inner = (*x.shape().last().unwrap() as usize) * x.mem_size();
-            let num_chunks;
-            let mut num_lines;
-            let mut remain = 0;
-            let mut buffer_size;
-            if x.size() * x.mem_size() < CHUNK_BUFF {
-                num_chunks = 1;
-                num_lines = outer;
-                buffer_size = num_lines * inner;
-            } else {
-                buffer_size = ((CHUNK_BUFF - 1) / inner) * inner;
-                num_lines = buffer_size / inner;
-                if num_lines == 0 {
-                    num_lines = 1;
-                    buffer_size = inner;
-                }
-                remain = outer % num_lines;
-                num_chunks = outer / num_lines;
-            }
-            (
-                meta.name.clone(),
-                num_chunks,
-                num_lines,
-                remain,
-                buffer_size,
-            )
-        })
-        .collect::<Vec<(String, usize, usize, usize, usize)>>();
-
-    (infos, res)
+/// (num_chunks, num_lines of each chunk, remain lines (if it is not divisable by CHUNK_BUFF), buffer size)
+fn chunk_config(meta: &Meta) -> (usize, usize, usize, usize) {
+    let x = &meta.data_saver;
+    let outer = x.size() / (*x.shape().last().unwrap() as usize);
+    let inner = (*x.shape().last().unwrap() as usize) * x.mem_size();
+    let num_chunks;
+    let mut num_lines;
+    let mut remain = 0;
+    let mut buffer_size;
+    if x.size() * x.mem_size() < CHUNK_BUFF {
+        num_chunks = 1;
+        num_lines = outer;
+        buffer_size = num_lines * inner;
+    } else {
+        buffer_size = ((CHUNK_BUFF - 1) / inner) * inner;
+        num_lines = buffer_size / inner;
+        if num_lines == 0 {
+            num_lines = 1;
+            buffer_size = inner;
+        }
+        remain = outer % num_lines;
+        num_chunks = outer / num_lines;
+    }
+    (num_chunks, num_lines, remain, buffer_size)
 }
 
-/// method to compress the tensor and save to file
-///
-/// `file_name`: name of the file to create
+/// streaming writer of the FASTTENSOR format
 ///
-/// `tensors`: a list of tuples, [(name, tensor), ...]. Name will be used as the key to load the tensor
-pub fn save(path: std::path::PathBuf, mut to_saves: Vec<Meta>) -> std::io::Result<()> {
-    // initialize progress bar based on total element size of the data to save
-    ////////////////////////////////////////////initialize progress bar////////////////////////////////////////////
-    let total_size: usize = to_saves
-        .iter()
-        .map(|meta| meta.data_saver.size())
-        .sum::<usize>();
+/// each tensor is compressed and written as soon as it is passed to [`TensorWriter::write`],
+/// the header is written by [`TensorWriter::finish`]. Nothing is written back, so any `Write` works,
+/// for example a `TcpStream` or a `Vec<u8>`.
+pub struct TensorWriter<W: Write> {
+    writer: W,
+    len_so_far: usize,
+    infos: Vec<HeaderInfo>,
+    names: HashSet<String>,
+    pb: ProgressBar,
+}
 
-    let pb = ProgressBar::new(total_size as u64);
-    pb.set_style(
-        indicatif::ProgressStyle::default_bar()
-            .template("[{elapsed_precise}] {bar:40.cyan/blue} {pos:>7}/{len:7} {msg}")
-            .unwrap(),
-    );
-    ////////////////////////////////////////////initialize progress bar////////////////////////////////////////////
+impl<W: Write> TensorWriter<W> {
+    /// create a writer, the file prefix is written immediately
+    pub fn new(mut writer: W) -> std::io::Result<Self> {
+        let prefix = prefix();
+        writer.write_all(prefix.as_bytes())?;
+        Ok(Self {
+            writer,
+            len_so_far: prefix.len(),
+            infos: vec![],
+            names: HashSet::new(),
+            pb: ProgressBar::hidden(),
+        })
+    }
 
-    // generate compresse file header
-    // (Vec<String>, list of tensor attributes: {"begin": 0, "name": "a", "shape": [1, 2, 3], ...}, ...),
-    // will be used to deserialize and get the storing information when loading the file
-    // Vec<(String, usize, usize, usize)>:  (tensor name, num_chunks, num_lines of each chunk, remain lines(if it is not divisable by CHUNK_BUFF)))
-    let mut header = generate_header_compressed(&to_saves);
-    if let Some(parent) = path.parent() {
-        std::fs::create_dir_all(parent).expect("Failed to create directory");
+    /// compress the tensor and write it
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name used to load the tensor, must be unique in the file.
+    /// * `tensor` - The tensor to write.
+    /// * `compression_algo` - The compression algorithm of the chunks.
+    /// * `endian` - The endianness of the stored data.
+    /// * `compression_level` - The compression level, ignored for `NoCompression`.
+    pub fn write<
+        const N: usize,
+        T: ToBytes<Bytes = [u8; N]> + CommonBounds,
+        A: Into<DataLoader<T>>,
+    >(
+        &mut self,
+        name: &str,
+        tensor: A,
+        compression_algo: CompressionAlgo,
+        endian: Endian,
+        compression_level: u32,
+    ) -> std::io::Result<()> {
+        self.write_meta(Meta {
+            name: name.to_string(),
+            compression_algo,
+            endian,
+            data_saver: Box::new(tensor.into()),
+            compression_level,
+        })
     }
-    let mut file = create_file(path, "ftz")?;
-    // write FASTTENSOR Magic Header, FASTTENSORHPLACEHOLD is a place holder for the location to the real header
-    // real header is at the end of the file.
-    // For Example: FASTTENSOR210123456789{"a": {"begin": 0, "name": "a", "shape": [1, 2, 3], ...}, ...}
-    // 21 is the location of the real header, 0123456789 is the data we store.
-    file.write_all("FASTTENSORFASTTENSORHPLACEHOLD".as_bytes())?;
-    let mut len_so_far: usize = "FASTTENSORFASTTENSORHPLACEHOLD".len();
-    let mut attrs: Vec<Vec<(usize, usize, usize, usize)>> = vec![];
-    for (idx, meta) in to_saves.iter_mut().enumerate() {
+
+    /// the number of tensors written so far
+    pub(crate) fn len(&self) -> usize {
+        self.infos.len()
+    }
+
+    /// the header info of the last written tensor
+    pub(crate) fn last_info(&self) -> Option<&HeaderInfo> {
+        self.infos.last()
+    }
+
+    pub(crate) fn write_meta(&mut self, mut meta: Meta) -> std::io::Result<()> {
+        if meta.name == METADATA_KEY {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                format!("{} is reserved for the metadata of `Save`", METADATA_KEY),
+            ));
+        }
+        if !self.names.insert(meta.name.clone()) {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                format!("{} is already written", meta.name),
+            ));
+        }
+        let (num_chunks, line_num, remain_outer, buffer_size) = chunk_config(&meta);
         let last_stride: i64 = *meta.data_saver.strides().last().unwrap() as i64;
         let mut prg: Vec<i64> = vec![0; meta.data_saver.shape().len() - 1];
         let mut shape: Vec<i64> = meta.data_saver.shape().iter().map(|x| *x as i64).collect();
@@ -108,14 +126,28 @@ pub fn save(path: std::path::PathBuf, mut to_saves: Vec<Meta>) -> std::io::Resul
             *x -= 1;
         });
         let inner_loop_size: usize = *meta.data_saver.shape().last().unwrap() as usize;
-        let line_num: usize = header.1[idx].2;
-        let num_chunks: usize = header.1[idx].1;
-        let buffer_size: usize = header.1[idx].4;
         let mut attributes = vec![];
-        let mut chunk = vec![0u8; buffer_size];
+        let mut checksums = vec![];
+        pad_for_mmap(
+            &mut self.writer,
+            &mut self.len_so_far,
+            meta.compression_algo,
+        )?;
+        let begin = self.len_so_far;
         let unpack = get_unpack_closure(meta.endian);
-        for k in 0..num_chunks {
-            for j in 0..line_num {
+        // the last chunk holds the remaining lines, if there are any
+        for k in 0..num_chunks + (remain_outer > 0) as usize {
+            let lines = if k < num_chunks {
+                line_num
+            } else {
+                remain_outer
+            };
+            let mut chunk = if k < num_chunks {
+                vec![0u8; buffer_size]
+            } else {
+                vec![0u8; lines * inner_loop_size * meta.data_saver.mem_size()]
+            };
+            for j in 0..lines {
                 for i in 0..inner_loop_size {
                     let start = (j * inner_loop_size + i) * meta.data_saver.mem_size();
                     let end = (j * inner_loop_size + i + 1) * meta.data_saver.mem_size();
@@ -125,7 +157,7 @@ pub fn save(path: std::path::PathBuf, mut to_saves: Vec<Meta>) -> std::io::Resul
                         &mut chunk[start..end],
                     );
                 }
-                pb.inc(inner_loop_size as u64);
+                self.pb.inc(inner_loop_size as u64);
                 for h in (0..shape.len() - 1).rev() {
                     if prg[h] < shape[h] {
                         prg[h] += 1;
@@ -142,90 +174,103 @@ pub fn save(path: std::path::PathBuf, mut to_saves: Vec<Meta>) -> std::io::Resul
             compress_data(
                 &meta,
                 &chunk,
-                &mut file,
+                &mut self.writer,
                 &mut attributes,
-                &mut len_so_far,
+                &mut checksums,
+                &mut self.len_so_far,
                 k,
                 line_num,
             )?;
         }
-        let remain_outer: usize = header.1[idx].3;
-        let mut remain_chunk: Vec<u8> =
-            vec![0u8; remain_outer * inner_loop_size * meta.data_saver.mem_size()];
-        for j in 0..remain_outer {
-            for i in 0..inner_loop_size {
-                let start = (j * inner_loop_size + i) * meta.data_saver.mem_size();
-                let end = (j * inner_loop_size + i + 1) * meta.data_saver.mem_size();
-                unpack(
-                    &mut meta.data_saver,
-                    ((i as i64) * last_stride) as isize,
-                    &mut remain_chunk[start..end],
-                );
-            }
-            pb.inc(inner_loop_size as u64);
-            for h in (0..shape.len() - 1).rev() {
-                if prg[h] < shape[h] {
-                    prg[h] += 1;
-                    meta.data_saver
-                        .offset(meta.data_saver.strides()[h] as isize);
-                    break;
-                } else {
-                    prg[h] = 0;
-                    meta.data_saver
-                        .offset(-(meta.data_saver.strides()[h] * (shape[h] as i64)) as isize);
-                }
-            }
-        }
-        compress_data(
-            &meta,
-            &remain_chunk,
-            &mut file,
-            &mut attributes,
-            &mut len_so_far,
-            num_chunks,
-            line_num,
-        )?;
-        header.0[idx] = header.0[idx].replace("place_holder0", &format!("{:?}", attributes));
-        attrs.push(attributes);
+        self.infos.push(HeaderInfo {
+            begin: begin as u64,
+            name: meta.name.clone(),
+            shape: meta.data_saver.shape().to_vec(),
+            strides: meta.data_saver.shape().to_strides().to_vec(),
+            size: meta.data_saver.size(),
+            indices: attributes,
+            compress_algo: meta.compression_algo,
+            dtype: meta.data_saver.dtype().to_string(),
+            endian: meta.endian,
+            checksums,
+        });
+        Ok(())
     }
 
-    // record the location of the end of the file so that we can come back and write the real header
-    let current_pos = file.seek(std::io::SeekFrom::Current(0))?;
-
-    // we go to the beginning of the file and correct the header
-    file.seek(std::io::SeekFrom::Start(0))?;
-    file.write_all(format!("FASTTENSOR{:20}", current_pos).as_bytes())?;
-
-    // jump back and start writing the real header
-    file.seek(std::io::SeekFrom::Start(current_pos))?;
-    let mut header_len = "FASTTENSORFASTTENSORHPLACEHOLD".len();
-    let length: Vec<usize> = attrs
-        .iter()
-        .map(|x| {
-            let tmp = header_len;
-            header_len += x.last().unwrap().1 + x.last().unwrap().2;
-            tmp
-        })
-        .collect();
-
-    header.0.iter_mut().zip(length).for_each(|(x, y)| {
-        *x = x.replace("FASTTENSOR_PLACEHOLD", &format!("{:20}", y));
-    });
+    /// write the header and the trailer, return the inner writer
+    pub fn finish(self) -> std::io::Result<W> {
+        self.finish_with_metadata(None)
+    }
 
-    let to_write = format!(r#"{{{}}}"#, header.0.join(","));
-    let to_write = to_write.replace("(", "[");
-    let to_write = to_write.replace(")", "]");
-    let bytes = to_write.as_bytes();
-    file.write_all(bytes)?;
+    /// write the header with the struct metadata of `Save` and the trailer, return the inner writer
+    pub(crate) fn finish_with_metadata(mut self, metadata: Option<String>) -> std::io::Result<W> {
+        let mut header = self
+            .infos
+            .iter()
+            .map(|info| {
+                Ok(format!(
+                    "{}:{}",
+                    serde_json::to_string(&info.name)?,
+                    serde_json::to_string(info)?
+                ))
+            })
+            .collect::<std::io::Result<Vec<String>>>()?;
+        if let Some(metadata) = metadata {
+            header.push(format!(
+                "{}:{}",
+                serde_json::to_string(METADATA_KEY)?,
+                metadata
+            ));
+        }
+        let header = format!("{{{}}}", header.join(","));
+        self.writer.write_all(header.as_bytes())?;
+        self.writer
+            .write_all(trailer(self.len_so_far, crc32fast::hash(header.as_bytes())).as_bytes())?;
+        self.writer.flush()?;
+        Ok(self.writer)
+    }
+}
 
+/// method to compress the tensor and save to file
+///
+/// `file_name`: name of the file to create
+///
+/// `tensors`: a list of tuples, [(name, tensor), ...]. Name will be used as the key to load the tensor
+pub fn save(path: std::path::PathBuf, to_saves: Vec<Meta>) -> std::io::Result<()> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).expect("Failed to create directory");
+    }
+    let file = std::io::BufWriter::new(create_file(path, "ftz")?);
+    save_to(file, to_saves)?;
     Ok(())
 }
 
-fn compress_data(
+/// method to compress the tensors and write them to any writer
+pub(crate) fn save_to<W: Write>(writer: W, to_saves: Vec<Meta>) -> std::io::Result<W> {
+    // initialize progress bar based on total element size of the data to save
+    let total_size: usize = to_saves
+        .iter()
+        .map(|meta| meta.data_saver.size())
+        .sum::<usize>();
+    let mut tensor_writer = TensorWriter::new(writer)?;
+    tensor_writer.pb = ProgressBar::new(total_size as u64);
+    tensor_writer.pb.set_style(
+        indicatif::ProgressStyle::default_bar()
+            .template("[{elapsed_precise}] {bar:40.cyan/blue} {pos:>7}/{len:7} {msg}")
+            .unwrap(),
+    );
+    for meta in to_saves {
+        tensor_writer.write_meta(meta)?;
+    }
+    tensor_writer.finish()
+}
+
+fn compress_data<W: Write>(
     meta: &Meta,
     chunk: &[u8],
-    file: &mut std::fs::File,
+    file: &mut W,
     attributes: &mut Vec<(usize, usize, usize, usize)>,
+    checksums: &mut Vec<u32>,
     len_so_far: &mut usize,
     k: usize,
     line_num: usize,
@@ -238,6 +283,7 @@ fn compress_data(
             compressed_data.len(), /* length of the compressed data */
             chunk.len(),           /* bytes of the data in the chunk */
         ));
+        checksums.push(crc32fast::hash(compressed_data));
         *len_so_far += compressed_data.len();
         Ok(())
     };
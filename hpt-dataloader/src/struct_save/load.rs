@@ -1,18 +1,13 @@
-use std::{
-    fmt::Display,
-    fs::File,
-    io::{Read, Seek},
-    marker::PhantomData,
-};
+use std::{fmt::Display, fs::File, marker::PhantomData};
 
-use flate2::read::{DeflateDecoder, GzDecoder, ZlibDecoder};
 use hpt_common::shape::shape::Shape;
 use hpt_traits::{CommonBounds, TensorInfo};
 use num::traits::FromBytes;
 
 use crate::{
     data_loader::{parse_header_compressed, TensorMeta},
-    CPUTensorCreator, CompressionAlgo, Endian,
+    load::uncompress_data,
+    CPUTensorCreator, Endian,
 };
 
 use super::save::Save;
@@ -66,13 +61,14 @@ where
     let pack = get_pack_closure::<T, N>(meta.endian);
 
     let mut res_idx = 0;
-    for (_, idx, compressed_len, current_block_mem_size) in meta.indices.iter() {
+    for (i, (_, idx, compressed_len, current_block_mem_size)) in meta.indices.iter().enumerate() {
         let uncompressed_data = uncompress_data(
             file,
             *idx as u64,
             *compressed_len,
             *current_block_mem_size,
             meta.compression_algo,
+            meta.checksums.get(i).copied(),
         )?;
         for idx in (0..uncompressed_data.len()).step_by(std::mem::size_of::<T>()) {
             let val = &uncompressed_data[idx..idx + std::mem::size_of::<T>()];
@@ -84,37 +80,6 @@ where
     Ok(tensor)
 }
 
-fn uncompress_data(
-    file: &mut File,
-    idx: u64,
-    compressed_len: usize,
-    block_mem_size: usize,
-    compression_type: CompressionAlgo,
-) -> std::io::Result<Vec<u8>> {
-    file.seek(std::io::SeekFrom::Start(idx))?;
-    let mut compressed_vec = vec![0u8; compressed_len];
-    file.read_exact(&mut compressed_vec)?;
-    let mut uncompressed_data = vec![0u8; block_mem_size];
-    match compression_type {
-        CompressionAlgo::Gzip => {
-            let mut decoder = GzDecoder::new(compressed_vec.as_slice());
-            decoder.read_exact(&mut uncompressed_data)?;
-        }
-        CompressionAlgo::Deflate => {
-            let mut decoder = DeflateDecoder::new(compressed_vec.as_slice());
-            decoder.read_exact(&mut uncompressed_data)?;
-        }
-        CompressionAlgo::Zlib => {
-            let mut decoder = ZlibDecoder::new(compressed_vec.as_slice());
-            decoder.read_exact(&mut uncompressed_data)?;
-        }
-        CompressionAlgo::NoCompression => {
-            uncompressed_data = compressed_vec;
-        }
-    }
-    Ok(uncompressed_data)
-}
-
 fn get_pack_closure<T: CommonBounds + FromBytes<Bytes = [u8; N]>, const N: usize>(
     endian: Endian,
 ) -> impl Fn(&[u8]) -> T {
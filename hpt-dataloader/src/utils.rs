@@ -1,5 +1,26 @@
+use std::io::Write;
+
 use hpt_common::{error::base::TensorError, shape::shape::Shape};
 
+use crate::CompressionAlgo;
+
+/// alignment of the uncompressed data in the file, so that `MmapLoader` can view the data without copy
+pub(crate) const MMAP_ALIGN: usize = 64;
+
+/// pad the file with zeros so that the uncompressed data written next starts at an aligned offset
+pub(crate) fn pad_for_mmap<W: Write>(
+    file: &mut W,
+    len_so_far: &mut usize,
+    compression_algo: CompressionAlgo,
+) -> std::io::Result<()> {
+    if compression_algo == CompressionAlgo::NoCompression {
+        let padding = len_so_far.next_multiple_of(MMAP_ALIGN) - *len_so_far;
+        file.write_all(&[0u8; MMAP_ALIGN][..padding])?;
+        *len_so_far += padding;
+    }
+    Ok(())
+}
+
 pub(crate) fn create_file(path: std::path::PathBuf, ext: &str) -> std::io::Result<std::fs::File> {
     if let Some(extension) = path.extension() {
         if extension == ext {
@@ -2,9 +2,11 @@ use anyhow::Result;
 use hpt_common::utils::pointer::Pointer;
 use hpt_traits::tensor::{CommonBounds, TensorInfo};
 use hpt_types::into_scalar::Cast;
-use std::fmt::Formatter;
+use std::fmt::{Debug, Display, Formatter};
+use std::marker::PhantomData;
 
-use crate::formats::format_val;
+use crate::formats::{format_val, is_float};
+use crate::options::PrintOptions;
 
 /// # Internal Function
 /// Pushes the string representation of the tensor to the string.
@@ -14,7 +16,8 @@ fn main_loop_push_str<U, T>(
     inner_loop: usize,
     last_stride: i64,
     string: &mut String,
-    precision: usize,
+    options: &PrintOptions,
+    prefix: &str,
     col_width: &mut Vec<usize>,
     prg: &mut Vec<i64>,
     shape: &Vec<i64>,
@@ -25,7 +28,13 @@ where
     T: CommonBounds + Cast<f64>,
 {
     let print = |string: &mut String, ptr: Pointer<T>, offset: &mut i64, col: usize| {
-        let val = format_val(ptr[*offset], precision);
+        let val = format_val(ptr[*offset], options);
+        let line = &string[string.rfind('\n').map_or(0, |i| i + 1)..];
+        if !line.ends_with('[') && line.len() + col_width[col] + 1 > options.line_width {
+            string.truncate(string.trim_end().len());
+            string.push_str("\n");
+            string.push_str(&" ".repeat(prefix.len() + tensor.ndim()));
+        }
         string.push_str(&format!("{:>width$}", val, width = col_width[col]));
         if col < inner_loop - 1 {
             string.push(' ');
@@ -66,10 +75,10 @@ where
                     && prg[k] == (lr_elements_size as i64)
                 {
                     string.push_str("\n");
-                    string.push_str(&" ".repeat(k + 1 + "Tensor(".len()));
+                    string.push_str(&" ".repeat(k + 1 + prefix.len()));
                     string.push_str("...");
                     string.push_str("\n\n");
-                    string.push_str(&" ".repeat(k + 1 + "Tensor(".len()));
+                    string.push_str(&" ".repeat(k + 1 + prefix.len()));
                     string.push_str(&"[".repeat(tensor.ndim() - (k + 1)));
                     ptr.offset(
                         tensor.strides()[k] * (tensor.shape()[k] - 2 * (lr_elements_size as i64)),
@@ -80,7 +89,7 @@ where
                 }
 
                 string.push_str("\n");
-                string.push_str(&" ".repeat(k + 1 + "Tensor(".len()));
+                string.push_str(&" ".repeat(k + 1 + prefix.len()));
                 string.push_str(&"[".repeat(tensor.ndim() - (k + 1)));
                 assert!(prg[k] < tensor.shape()[k]);
                 break;
@@ -98,21 +107,21 @@ where
 }
 
 /// # Internal Function
-/// Get the width of each column in the tensor.
-fn main_loop_get_width<U, T>(
+/// Calls `visit` with the column and the value of each element to be displayed.
+fn main_loop_visit<U, T, F>(
     tensor: &U,
     lr_elements_size: usize,
     inner_loop: usize,
     last_stride: i64,
-    precision: usize,
-    col_width: &mut Vec<usize>,
     prg: &mut Vec<i64>,
     shape: &Vec<i64>,
     mut ptr: Pointer<T>,
+    mut visit: F,
 ) -> Result<()>
 where
     U: TensorInfo<T>,
     T: CommonBounds + Cast<f64>,
+    F: FnMut(usize, T),
 {
     let mut outer_loop = 1;
     for i in tensor.shape().iter().take(tensor.ndim() - 1) {
@@ -127,8 +136,7 @@ where
         if inner_loop >= 2 * lr_elements_size {
             for i in 0..2 {
                 for j in 0..lr_elements_size {
-                    let val = format_val(ptr[offset], precision);
-                    col_width[j] = std::cmp::max(col_width[j], val.len());
+                    visit(j, ptr[offset]);
                     offset += last_stride;
                 }
                 if i == 0 {
@@ -137,8 +145,7 @@ where
             }
         } else {
             for j in 0..inner_loop {
-                let val = format_val(ptr[offset], precision);
-                col_width[j] = std::cmp::max(col_width[j], val.len());
+                visit(j, ptr[offset]);
                 offset += last_stride;
             }
         }
@@ -167,36 +174,61 @@ where
     Ok(())
 }
 
+/// # Internal Function
+/// Whether the values should be printed in scientific notation when `options.scientific` is `None`, following NumPy.
+fn use_scientific(options: &PrintOptions, values: impl Iterator<Item = f64>) -> bool {
+    let (mut min, mut max) = (f64::INFINITY, 0.0f64);
+    for val in values.map(f64::abs).filter(|x| x.is_finite() && *x != 0.0) {
+        min = min.min(val);
+        max = max.max(val);
+    }
+    if max == 0.0 {
+        return false;
+    }
+    max >= 1e8 || (!options.suppress_small && (min < 1e-4 || max / min > 1e3))
+}
+
 /// Display a tensor.
 ///
+/// `{}` prints the values with the shape, strides and dtype, `{:?}` also prints whether the tensor is contiguous and `{:#}` only prints the values.
+///
 /// # Arguments
 /// - `tensor`: A reference to the tensor to be displayed.
 /// - `f`: A reference to the formatter.
-/// - `lr_elements_size`: Number of elements to display in left and right for each row and column.
-/// - `precision`: Number of decimal places to display for floating point numbers.
+/// - `options`: The options controlling the precision, summarization and layout of the output.
+/// - `debug`: A boolean indicating whether the tensor is displayed with `{:?}`.
 /// - `show_backward`: A boolean indicating whether to display the gradient function of the tensor, currently only used in DiffTensor.
 pub fn display<U, T>(
     tensor: U,
     f: &mut Formatter<'_>,
-    lr_elements_size: usize,
-    precision: usize,
+    options: &PrintOptions,
+    debug: bool,
     show_backward: bool,
 ) -> std::fmt::Result
 where
     U: TensorInfo<T>,
     T: CommonBounds + Cast<f64>,
 {
-    let mut string: String = String::new();
+    let alternate = f.alternate() && !debug;
+    let prefix = if alternate { "" } else { "Tensor(" };
+    let mut options = options.clone();
+    let mut string: String = String::from(prefix);
     if tensor.size() == 0 {
-        write!(f, "{}", "Tensor([])\n".to_string())
+        string.push_str("[]");
     } else if tensor.ndim() == 0 {
-        let val = format_val(unsafe { tensor.ptr().ptr.read() }, precision);
-        write!(f, "{}", format!("Tensor({})\n", val))
+        let val = unsafe { tensor.ptr().ptr.read() };
+        let scientific = is_float::<T>() && use_scientific(&options, std::iter::once(val.cast()));
+        options.scientific = Some(options.scientific.unwrap_or(scientific));
+        string.push_str(&format_val(val, &options));
     } else {
         let ptr: Pointer<T> = tensor.ptr();
         if !ptr.ptr.is_null() {
             let inner_loop: usize = tensor.shape()[tensor.ndim() - 1] as usize;
-            let mut prg: Vec<i64> = vec![0; tensor.ndim()];
+            let lr_elements_size = if tensor.size() > options.threshold {
+                options.edge_items.max(1)
+            } else {
+                *tensor.shape().iter().max().unwrap() as usize
+            };
             let mut shape: Vec<i64> = tensor.shape().to_vec();
             shape.iter_mut().for_each(|x: &mut i64| {
                 *x -= 1;
@@ -208,21 +240,39 @@ where
                 }
             });
             let last_stride = strides[tensor.ndim() - 1];
-            string.push_str("Tensor(");
             for _ in 0..tensor.ndim() {
                 string.push_str("[");
             }
+            if options.scientific.is_none() {
+                let mut values = vec![];
+                if is_float::<T>() {
+                    main_loop_visit(
+                        &tensor,
+                        lr_elements_size,
+                        inner_loop,
+                        last_stride,
+                        &mut vec![0; tensor.ndim()],
+                        &shape,
+                        ptr.clone(),
+                        |_, val| values.push(val.cast()),
+                    )
+                    .unwrap();
+                }
+                options.scientific = Some(use_scientific(&options, values.into_iter()));
+            }
             let mut col_width: Vec<usize> = vec![0; inner_loop];
-            main_loop_get_width(
+            main_loop_visit(
                 &tensor,
                 lr_elements_size,
                 inner_loop,
                 last_stride,
-                precision,
-                &mut col_width,
-                &mut prg,
+                &mut vec![0; tensor.ndim()],
                 &shape,
                 ptr.clone(),
+                |col, val| {
+                    let val = format_val(val, &options);
+                    col_width[col] = std::cmp::max(col_width[col], val.len());
+                },
             )
             .unwrap();
             main_loop_push_str(
@@ -231,42 +281,84 @@ where
                 inner_loop,
                 last_stride,
                 &mut string,
-                precision,
+                &options,
+                prefix,
                 &mut col_width,
-                &mut prg,
+                &mut vec![0; tensor.ndim()],
                 &shape,
                 ptr.clone(),
             )
             .unwrap();
         }
-        let shape_str = tensor
-            .shape()
-            .iter()
-            .map(|x| x.to_string())
-            .collect::<Vec<String>>()
-            .join(", ");
-        let strides_str = tensor
-            .strides()
-            .iter()
-            .map(|x| x.to_string())
-            .collect::<Vec<String>>()
-            .join(", ");
-        if !show_backward {
-            string.push_str(&format!(
-                ", shape=({}), strides=({}), dtype={})\n",
-                shape_str,
-                strides_str,
-                T::STR
-            ));
-        } else {
-            string.push_str(&format!(
-                ", shape=({}), strides=({}), dtype={}, grad_fn={})\n",
-                shape_str,
-                strides_str,
-                T::STR,
-                "None"
-            ));
+    }
+    if alternate {
+        return write!(f, "{}", string);
+    }
+    if (tensor.size() == 0 || tensor.ndim() == 0) && !debug {
+        string.push_str(")\n");
+        return write!(f, "{}", string);
+    }
+    let shape_str = tensor
+        .shape()
+        .iter()
+        .map(|x| x.to_string())
+        .collect::<Vec<String>>()
+        .join(", ");
+    let strides_str = tensor
+        .strides()
+        .iter()
+        .map(|x| x.to_string())
+        .collect::<Vec<String>>()
+        .join(", ");
+    string.push_str(&format!(
+        ", shape=({}), strides=({})",
+        shape_str, strides_str
+    ));
+    if debug {
+        string.push_str(&format!(", contiguous={}", tensor.is_contiguous()));
+    }
+    string.push_str(&format!(", dtype={}", T::STR));
+    if show_backward {
+        string.push_str(&format!(", grad_fn={}", "None"));
+    }
+    string.push_str(")\n");
+    write!(f, "{}", string)
+}
+
+/// A tensor displayed with the given [`PrintOptions`] instead of the global ones, created by `display_with`.
+pub struct DisplayWith<'a, U, T> {
+    tensor: U,
+    options: &'a PrintOptions,
+    _marker: PhantomData<T>,
+}
+
+impl<'a, U, T> DisplayWith<'a, U, T> {
+    /// Create a new `DisplayWith`, `tensor` is usually a reference to a tensor.
+    pub fn new(tensor: U, options: &'a PrintOptions) -> Self {
+        DisplayWith {
+            tensor,
+            options,
+            _marker: PhantomData,
         }
-        write!(f, "{}", format!("{}", string))
+    }
+}
+
+impl<'a, U, T> Display for DisplayWith<'a, U, T>
+where
+    U: TensorInfo<T> + Copy,
+    T: CommonBounds + Cast<f64>,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        display(self.tensor, f, self.options, false, false)
+    }
+}
+
+impl<'a, U, T> Debug for DisplayWith<'a, U, T>
+where
+    U: TensorInfo<T> + Copy,
+    T: CommonBounds + Cast<f64>,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        display(self.tensor, f, self.options, true, false)
     }
 }
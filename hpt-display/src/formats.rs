@@ -1,9 +1,39 @@
 use hpt_traits::CommonBounds;
 use hpt_types::into_scalar::Cast;
 
-pub(crate) fn format_float<T: CommonBounds + Cast<f64>>(val: T, precision: usize) -> String {
+use crate::options::{PrintOptions, PrintSign};
+
+/// `1.2340e+03`, the exponent has a sign and at least 2 digits like NumPy
+fn format_scientific(val: f64, precision: usize) -> String {
+    let val = format!("{:.prec$e}", val, prec = precision);
+    match val.split_once('e') {
+        Some((mantissa, exp)) => {
+            let exp: i32 = exp.parse().expect("Failed to parse exponent");
+            let sign = if exp < 0 { '-' } else { '+' };
+            format!("{}e{}{:02}", mantissa, sign, exp.abs())
+        }
+        None => val,
+    }
+}
+
+pub(crate) fn format_float<T: CommonBounds + Cast<f64>>(val: T, options: &PrintOptions) -> String {
+    let precision = options.precision;
+    let val = if options.scientific == Some(true) {
+        format_scientific(val.cast(), precision)
+    } else {
+        format_fixed(val, precision)
+    };
+    let signed = !val.starts_with('-') && val != "NaN";
+    match options.sign {
+        PrintSign::Plus if signed => format!("+{}", val),
+        PrintSign::Space if signed => format!(" {}", val),
+        _ => val,
+    }
+}
+
+fn format_fixed<T: CommonBounds + Cast<f64>>(val: T, precision: usize) -> String {
     match T::STR {
-        "bf16" => {
+        "bf16" | "f8e4m3" | "f8e5m2" => {
             let f64_val: f64 = val.cast();
             if f64_val - (f64_val as i64 as f64) != 0.0 {
                 format!("{:.prec$}", f64_val, prec = precision)
@@ -39,7 +69,7 @@ pub(crate) fn format_float<T: CommonBounds + Cast<f64>>(val: T, precision: usize
     }
 }
 
-pub(crate) fn format_complex<T: CommonBounds>(val: T, precision: usize) -> String {
+pub(crate) fn format_complex<T: CommonBounds>(val: T, options: &PrintOptions) -> String {
     let mut val = val.to_string();
     match T::STR {
         "c32" => {
@@ -48,8 +78,8 @@ pub(crate) fn format_complex<T: CommonBounds>(val: T, precision: usize) -> Strin
                 .expect("Failed to parse c32");
             let re = tmp_val.re;
             let im = tmp_val.im;
-            let re_str = format_float(re, precision);
-            let im_str = format_float(im, precision);
+            let re_str = format_float(re, options);
+            let im_str = format_float(im, options);
             val = format!("{} + {}i", re_str, im_str);
         }
         "c64" => {
@@ -58,8 +88,8 @@ pub(crate) fn format_complex<T: CommonBounds>(val: T, precision: usize) -> Strin
                 .expect("Failed to parse c64");
             let re = tmp_val.re;
             let im = tmp_val.im;
-            let re_str = format_float(re, precision);
-            let im_str = format_float(im, precision);
+            let re_str = format_float(re, options);
+            let im_str = format_float(im, options);
             val = format!("{} + {}i", re_str, im_str);
         }
         _ => panic!("{} is not a complex type", T::STR),
@@ -67,11 +97,17 @@ pub(crate) fn format_complex<T: CommonBounds>(val: T, precision: usize) -> Strin
     val
 }
 
-pub(crate) fn format_val<T: CommonBounds + Cast<f64>>(val: T, precision: usize) -> String {
+/// whether `T` is printed with [`format_float`]
+pub(crate) fn is_float<T: CommonBounds>() -> bool {
+    matches!(T::STR, "f8e4m3" | "f8e5m2" | "bf16" | "f16" | "f32" | "f64")
+}
+
+/// `options.scientific` must be resolved to `Some(_)`
+pub(crate) fn format_val<T: CommonBounds + Cast<f64>>(val: T, options: &PrintOptions) -> String {
     match T::STR {
-        "bf16" | "f16" | "f32" | "f64" => format_float(val, precision),
+        "f8e4m3" | "f8e5m2" | "bf16" | "f16" | "f32" | "f64" => format_float(val, options),
 
-        "c32" | "c64" => format_complex(val, precision),
+        "c32" | "c64" => format_complex(val, options),
         _ => val.to_string(),
     }
 }
@@ -79,6 +115,10 @@ pub(crate) fn format_val<T: CommonBounds + Cast<f64>>(val: T, precision: usize)
 #[test]
 fn test_complex() {
     let val = num_complex::Complex32::new(1.0, 2.0);
-    let val = format_complex(val, 2);
+    let options = PrintOptions {
+        precision: 2,
+        ..Default::default()
+    };
+    let val = format_complex(val, &options);
     println!("{}", val);
 }
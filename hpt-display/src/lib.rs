@@ -6,4 +6,7 @@
 mod display;
 /// A module contains formats
 mod formats;
-pub use display::display;
+/// A module contains print options
+mod options;
+pub use display::{display, DisplayWith};
+pub use options::{PrintOptions, PrintSign};
@@ -0,0 +1,55 @@
+/// How the sign of positive floating point values is printed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PrintSign {
+    /// only negative values are prefixed, `-1.5 2.5`
+    #[default]
+    Minus,
+    /// positive values are prefixed with `+`, `-1.5 +2.5`
+    Plus,
+    /// positive values are prefixed with a space, `-1.5  2.5`
+    Space,
+}
+
+/// Options controlling how tensors are printed, the counterpart of NumPy's `set_printoptions`.
+///
+/// The global options used by `{}` and `{:?}` are set with `set_print_options`, `display_with` uses the given options for a single call.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PrintOptions {
+    /// number of digits after the decimal point of floating point values
+    pub precision: usize,
+    /// tensors with more than `threshold` elements are summarized, only the first and last `edge_items` of each axis are printed
+    pub threshold: usize,
+    /// number of elements printed at the beginning and end of each axis of a summarized tensor
+    pub edge_items: usize,
+    /// rows longer than `line_width` characters are wrapped
+    pub line_width: usize,
+    /// `Some(true)` always prints floating point values in scientific notation, `Some(false)` never does, `None` uses it when the printed values
+    /// span more than 3 orders of magnitude, are smaller than `1e-4` or larger than `1e8`
+    pub scientific: Option<bool>,
+    /// how the sign of positive floating point values is printed
+    pub sign: PrintSign,
+    /// when `scientific` is `None`, only values of at least `1e8` switch to scientific notation, small values are printed as zero
+    pub suppress_small: bool,
+}
+
+impl PrintOptions {
+    /// the default options, `precision = 4`, `threshold = 0` (every axis longer than `2 * edge_items` is summarized),
+    /// `edge_items = 3`, `line_width = 75`, automatic scientific notation, `PrintSign::Minus` and no suppression
+    pub const fn new() -> Self {
+        PrintOptions {
+            precision: 4,
+            threshold: 0,
+            edge_items: 3,
+            line_width: 75,
+            scientific: None,
+            sign: PrintSign::Minus,
+            suppress_small: false,
+        }
+    }
+}
+
+impl Default for PrintOptions {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -1,6 +1,6 @@
 use hpt::{
-    match_selection, FloatOutUnary, Matmul, NormalBinOps, ParStridedIteratorSimdZip, Random,
-    ShapeManipulate, Slice, Tensor, TensorCreator, TensorError, TensorInfo, TensorIterator,
+    FloatOutUnary, Matmul, NormalBinOps, ParStridedIteratorSimdZip, Random, ShapeManipulate,
+    Tensor, TensorCreator, TensorError, TensorInfo, TensorIterator,
 };
 
 struct LSTM {
@@ -169,8 +169,8 @@ impl LSTMModel {
         let mut outputs = Vec::with_capacity(seq_length as usize);
 
         let mut total_time = std::time::Duration::from_secs(0);
-        for t in 0..seq_length {
-            let mut layer_input = x.slice(&match_selection![:, t:t+1, :])?.squeeze(1)?;
+        for x_t in x.axis_iter(1)? {
+            let mut layer_input = x_t;
             for layer_idx in 0..self.num_layers {
                 let lstm = &self.lstm_cells[layer_idx];
                 let now = std::time::Instant::now();
@@ -2,15 +2,12 @@
 use std::collections::HashMap;
 
 use hpt::{
-    binary_with_out, match_selection, IndexReduce, Matmul, NormalBinOps, NormalOut, NormalUaryOps,
-    ParStridedIteratorZip, Random, RandomInt, ShapeManipulate, Slice, Tensor, TensorCreator,
-    TensorError, TensorIterator, TypeCommon, VecTrait,
+    match_selection, IndexReduce, Matmul, NormalBinOps, NormalUaryOps, ParStridedIteratorZip,
+    Random, RandomInt, ShapeManipulate, Slice, Tensor, TensorCreator, TensorError, TensorIterator,
 };
 use hpt::{Eval, TensorInfo};
 use rayon::iter::ParallelIterator;
 
-type F32Vec = <f32 as TypeCommon>::Vec;
-
 struct Encoder {
     mha: MultiHeadAttention,
     layernorm: LayerNorm,
@@ -120,31 +117,15 @@ impl PositionalEncoding {
         let batch_size = shape[0];
         let seq_len = shape[1];
         let embedding_dim = shape[2];
-        let i_mat = binary_with_out(
-            &Tensor::<f32>::new([10000.0]),
-            &Tensor::<f32>::arange_step(0.0, embedding_dim as f32, 2.0)?,
-            |a, b| a._pow(b) / embedding_dim as f32,
-            |a, b| a._pow(b) / F32Vec::splat(embedding_dim as f32),
-            None::<Tensor<f32>>,
-        )?;
-        let pos = Tensor::<f32>::arange(0.0, seq_len as f32)?.unsqueeze(1)?;
-        let pe = Tensor::<f32>::zeros([seq_len, embedding_dim])?;
-        let mut pe_sin = pe.slice(&[Slice::Full, Slice::StepByRangeFrom((0, 2))])?;
-        let mut pe_cos = pe.slice(&[Slice::Full, Slice::StepByRangeFrom((1, 2))])?;
-        pe_sin
-            .par_iter_mut()
-            .zip(pos.par_iter())
-            .zip(i_mat.par_iter())
-            .for_each(|((res, pos), i_mat)| {
-                *res = (pos / i_mat).sin();
-            });
-        pe_cos
-            .par_iter_mut()
-            .zip(pos.par_iter())
-            .zip(i_mat.par_iter())
-            .for_each(|((res, pos), i_mat)| {
-                *res = (pos / i_mat).cos();
-            });
+        let pe = Tensor::<f32>::from_fn([seq_len, embedding_dim], |idx| {
+            let (pos, i) = (idx[0] as f32, idx[1]);
+            let div = 10000.0f32.powf((i - i % 2) as f32) / embedding_dim as f32;
+            if i % 2 == 0 {
+                (pos / div).sin()
+            } else {
+                (pos / div).cos()
+            }
+        })?;
         let expanded = pe
             .unsqueeze(0)?
             .expand([batch_size, seq_len, embedding_dim])?;
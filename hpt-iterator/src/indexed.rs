@@ -0,0 +1,281 @@
+use std::ops::Range;
+
+use hpt_common::{shape::shape::Shape, shape::shape_utils::mt_intervals, utils::pointer::Pointer};
+use hpt_traits::tensor::{CommonBounds, TensorAlloc, TensorInfo};
+use rayon::iter::{IntoParallelRefIterator, ParallelIterator};
+
+/// The layout of an indexed tensor, shared by all of the threads.
+///
+/// The elements are visited in row-major order, a row being the elements along the last dimension.
+struct Geometry<T> {
+    ptr: Pointer<T>,
+    shape: Vec<i64>,
+    strides: Vec<i64>,
+}
+
+impl<T: CommonBounds> Geometry<T> {
+    fn new<U: TensorInfo<T>>(tensor: U) -> Self {
+        Geometry {
+            ptr: tensor.ptr(),
+            shape: tensor.shape().to_vec(),
+            strides: tensor.strides().to_vec(),
+        }
+    }
+
+    fn rows(&self) -> usize {
+        match self.shape.split_last() {
+            Some((_, outer)) => outer.iter().product::<i64>() as usize,
+            None => 1,
+        }
+    }
+
+    fn inner(&self) -> usize {
+        self.shape.last().map_or(1, |&n| n as usize)
+    }
+
+    fn intervals(&self) -> Vec<(usize, usize)> {
+        let rows = self.rows();
+        mt_intervals(rows, rows.min(rayon::current_num_threads()))
+    }
+
+    #[inline(always)]
+    fn ptr(&self, offset: i64) -> *mut T {
+        unsafe { self.ptr.ptr.offset(offset as isize) }
+    }
+
+    /// call `f` with the row-major position, the index and the memory offset of every element of
+    /// the rows `rows`
+    fn for_rows<F>(&self, rows: Range<usize>, mut f: F)
+    where
+        F: FnMut(usize, &[i64], i64),
+    {
+        if self.shape.is_empty() {
+            if rows.contains(&0) {
+                f(0, &[], 0);
+            }
+            return;
+        }
+        let last = self.shape.len() - 1;
+        let inner = self.inner();
+        let mut index = vec![0; self.shape.len()];
+        for row in rows {
+            let mut rem = row as i64;
+            let mut base = 0;
+            for d in (0..last).rev() {
+                index[d] = rem % self.shape[d];
+                rem /= self.shape[d];
+                base += index[d] * self.strides[d];
+            }
+            for j in 0..inner {
+                index[last] = j as i64;
+                f(
+                    row * inner + j,
+                    &index,
+                    base + index[last] * self.strides[last],
+                );
+            }
+        }
+    }
+}
+
+/// A single-threaded iterator over the elements of a tensor and their indices.
+///
+/// The elements are visited in row-major order, see `TensorIterator::indexed_iter`.
+pub struct Indexed<'a, T> {
+    geom: Geometry<T>,
+    phantom: std::marker::PhantomData<&'a T>,
+}
+
+impl<'a, T: CommonBounds> Indexed<'a, T> {
+    /// Create a new indexed iterator.
+    pub fn new<U: TensorInfo<T>>(tensor: U) -> Self {
+        Indexed {
+            geom: Geometry::new(tensor),
+            phantom: std::marker::PhantomData,
+        }
+    }
+
+    /// Returns the shape of the iterated tensor.
+    pub fn shape(&self) -> Shape {
+        Shape::from(&self.geom.shape)
+    }
+
+    /// Call `f` with the index and the value of every element.
+    pub fn for_each<F>(&self, mut f: F)
+    where
+        F: FnMut((&[i64], T)),
+    {
+        let geom = &self.geom;
+        geom.for_rows(0..geom.rows(), |_, idx, offset| {
+            f((idx, unsafe { *geom.ptr(offset) }))
+        });
+    }
+
+    /// Map every element and its index to an element of the output.
+    pub fn strided_map<F, O>(self, f: F) -> IndexedMap<'a, T, F>
+    where
+        F: Fn((&[i64], T)) -> O,
+    {
+        IndexedMap {
+            geom: self.geom,
+            f,
+            parallel: false,
+            phantom: std::marker::PhantomData,
+        }
+    }
+}
+
+/// A multi-threaded iterator over the elements of a tensor and their indices.
+///
+/// The rows of the tensor are split in chunks with `mt_intervals` like `ParStrided`.
+pub struct ParIndexed<'a, T> {
+    geom: Geometry<T>,
+    phantom: std::marker::PhantomData<&'a T>,
+}
+
+impl<'a, T: CommonBounds> ParIndexed<'a, T> {
+    /// Create a new parallel indexed iterator.
+    pub fn new<U: TensorInfo<T>>(tensor: U) -> Self {
+        ParIndexed {
+            geom: Geometry::new(tensor),
+            phantom: std::marker::PhantomData,
+        }
+    }
+
+    /// Returns the shape of the iterated tensor.
+    pub fn shape(&self) -> Shape {
+        Shape::from(&self.geom.shape)
+    }
+
+    /// Call `f` with the index and the value of every element, in parallel.
+    pub fn for_each<F>(&self, f: F)
+    where
+        F: Fn((&[i64], T)) + Sync + Send,
+    {
+        let geom = &self.geom;
+        geom.intervals().par_iter().for_each(|&(start, end)| {
+            geom.for_rows(start..end, |_, idx, offset| {
+                f((idx, unsafe { *geom.ptr(offset) }))
+            })
+        });
+    }
+
+    /// Map every element and its index to an element of the output, in parallel.
+    pub fn strided_map<F, O>(self, f: F) -> IndexedMap<'a, T, F>
+    where
+        F: Fn((&[i64], T)) -> O,
+    {
+        IndexedMap {
+            geom: self.geom,
+            f,
+            parallel: true,
+            phantom: std::marker::PhantomData,
+        }
+    }
+}
+
+/// A single-threaded iterator over the mutable elements of a tensor and their indices.
+///
+/// The elements are visited in row-major order, see `TensorIterator::indexed_iter_mut`.
+pub struct IndexedMut<'a, T> {
+    geom: Geometry<T>,
+    phantom: std::marker::PhantomData<&'a mut T>,
+}
+
+impl<'a, T: CommonBounds> IndexedMut<'a, T> {
+    /// Create a new mutable indexed iterator.
+    pub fn new<U: TensorInfo<T>>(tensor: U) -> Self {
+        IndexedMut {
+            geom: Geometry::new(tensor),
+            phantom: std::marker::PhantomData,
+        }
+    }
+
+    /// Returns the shape of the iterated tensor.
+    pub fn shape(&self) -> Shape {
+        Shape::from(&self.geom.shape)
+    }
+
+    /// Call `f` with the index and a mutable reference of every element.
+    pub fn for_each<F>(self, mut f: F)
+    where
+        F: FnMut((&[i64], &mut T)),
+    {
+        let geom = &self.geom;
+        geom.for_rows(0..geom.rows(), |_, idx, offset| {
+            f((idx, unsafe { &mut *geom.ptr(offset) }))
+        });
+    }
+}
+
+/// A multi-threaded iterator over the mutable elements of a tensor and their indices.
+///
+/// The rows of the tensor are split in chunks with `mt_intervals` like `ParStridedMut`.
+pub struct ParIndexedMut<'a, T> {
+    geom: Geometry<T>,
+    phantom: std::marker::PhantomData<&'a mut T>,
+}
+
+impl<'a, T: CommonBounds> ParIndexedMut<'a, T> {
+    /// Create a new parallel mutable indexed iterator.
+    pub fn new<U: TensorInfo<T>>(tensor: U) -> Self {
+        ParIndexedMut {
+            geom: Geometry::new(tensor),
+            phantom: std::marker::PhantomData,
+        }
+    }
+
+    /// Returns the shape of the iterated tensor.
+    pub fn shape(&self) -> Shape {
+        Shape::from(&self.geom.shape)
+    }
+
+    /// Call `f` with the index and a mutable reference of every element, in parallel.
+    pub fn for_each<F>(self, f: F)
+    where
+        F: Fn((&[i64], &mut T)) + Sync + Send,
+    {
+        let geom = &self.geom;
+        geom.intervals().par_iter().for_each(|&(start, end)| {
+            geom.for_rows(start..end, |_, idx, offset| {
+                f((idx, unsafe { &mut *geom.ptr(offset) }))
+            })
+        });
+    }
+}
+
+/// A map over the elements of a tensor and their indices, collected into a tensor of the same
+/// shape.
+pub struct IndexedMap<'a, T, F> {
+    geom: Geometry<T>,
+    f: F,
+    parallel: bool,
+    phantom: std::marker::PhantomData<&'a ()>,
+}
+
+impl<'a, T: CommonBounds, F> IndexedMap<'a, T, F> {
+    /// Collects the results of the map into a new contiguous tensor.
+    pub fn collect<U>(self) -> U
+    where
+        F: Fn((&[i64], T)) -> U::Meta + Sync + Send + 'a,
+        U: Clone + TensorInfo<U::Meta> + TensorAlloc,
+        <U as TensorAlloc>::Meta: CommonBounds,
+    {
+        let res = U::_empty(self.geom.shape.clone()).unwrap();
+        let out = res.ptr();
+        let (geom, f) = (&self.geom, &self.f);
+        let write = |start: usize, end: usize| {
+            geom.for_rows(start..end, |i, idx, offset| unsafe {
+                out.get_ptr().add(i).write(f((idx, *geom.ptr(offset))))
+            })
+        };
+        if self.parallel {
+            geom.intervals()
+                .par_iter()
+                .for_each(|&(start, end)| write(start, end));
+        } else {
+            write(0, geom.rows());
+        }
+        res
+    }
+}
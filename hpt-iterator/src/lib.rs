@@ -2,12 +2,17 @@
 
 #![deny(missing_docs)]
 
+use hpt_traits::ops::advance::PadMode;
 use hpt_traits::{CommonBounds, TensorInfo};
+use indexed::{Indexed, IndexedMut, ParIndexed, ParIndexedMut};
 use par_strided::{par_strided_simd::ParStridedSimd, ParStrided};
 use par_strided_mut::{par_strided_map_mut_simd::ParStridedMutSimd, ParStridedMut};
 use strided::{strided_simd::StridedSimd, Strided};
 use strided_mut::{simd_imports::StridedMutSimd, StridedMut};
+use windows::{ParWindows, Windows};
 
+/// A module contains single-threaded/parallel iterators yielding the indices of the elements
+pub mod indexed;
 /// A module contains all the iterator traits
 pub mod iterator_traits;
 /// A module contains simd/non-simd parallel strided iterator
@@ -34,6 +39,8 @@ pub mod strided_map_mut;
 pub mod strided_mut;
 /// A module contains simd/non-simd strided zip iterator
 pub mod strided_zip;
+/// A module contains single-threaded/parallel sliding window iterators
+pub mod windows;
 
 mod with_simd;
 
@@ -99,4 +106,65 @@ where
     fn par_iter_mut(&'a mut self) -> ParStridedMut<'a, T> {
         ParStridedMut::new(self)
     }
+
+    /// Convert the tensor into an indexed iterator.
+    ///
+    /// indexed iterator is a single-threaded iterator, every item is the index of an element
+    /// along with its value
+    fn indexed_iter(&'a self) -> Indexed<'a, T> {
+        Indexed::new(self)
+    }
+
+    /// Convert the tensor into a mutable indexed iterator.
+    ///
+    /// indexed iterator is a single-threaded iterator
+    fn indexed_iter_mut(&'a mut self) -> IndexedMut<'a, T> {
+        IndexedMut::new(self)
+    }
+
+    /// Convert the tensor into a parallel indexed iterator.
+    ///
+    /// parallel indexed iterator is a multi-threaded iterator
+    fn par_indexed_iter(&'a self) -> ParIndexed<'a, T> {
+        ParIndexed::new(self)
+    }
+
+    /// Convert the tensor into a mutable parallel indexed iterator.
+    ///
+    /// parallel indexed iterator is a multi-threaded iterator
+    fn par_indexed_iter_mut(&'a mut self) -> ParIndexedMut<'a, T> {
+        ParIndexedMut::new(self)
+    }
+
+    /// Convert the tensor into a sliding window iterator.
+    ///
+    /// windows iterator is a single-threaded iterator, every window has the shape `kernel_shape`,
+    /// consecutive windows are `strides` elements apart and the elements of a window are
+    /// `dilation` elements apart. `padding` decides how the windows crossing the border read the
+    /// outside elements and the number of windows, `None` visits only the windows fully inside of
+    /// the tensor.
+    #[track_caller]
+    fn windows(
+        &'a self,
+        kernel_shape: &[i64],
+        strides: &[i64],
+        dilation: &[i64],
+        padding: Option<PadMode<T>>,
+    ) -> Windows<'a, T> {
+        Windows::new(self, kernel_shape, strides, dilation, padding)
+    }
+
+    /// Convert the tensor into a parallel sliding window iterator.
+    ///
+    /// parallel windows iterator is a multi-threaded iterator, see `windows` for the parameters
+    #[track_caller]
+    fn par_windows(
+        &'a self,
+        kernel_shape: &[i64],
+        strides: &[i64],
+        dilation: &[i64],
+        padding: Option<PadMode<T>>,
+    ) -> ParWindows<'a, T> {
+        ParWindows::new(self, kernel_shape, strides, dilation, padding)
+    }
 }
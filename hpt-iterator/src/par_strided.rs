@@ -104,6 +104,42 @@ pub mod par_strided_simd {
             }
         }
 
+        /// move the pointer to the start of the intervals this producer covers and shrink the shape for the inner loop
+        fn init_leaf(&mut self) {
+            let mut curent_shape_prg: Vec<i64> = vec![0; self.shape().len()];
+            let mut amount =
+                self.intervals[self.start_index].0 * (*self.shape().last().unwrap() as usize);
+            for j in (0..self.shape().len()).rev() {
+                curent_shape_prg[j] = (amount as i64) % self.shape()[j];
+                amount /= self.shape()[j] as usize;
+                self.ptr += curent_shape_prg[j] * self.strides()[j];
+            }
+            self.prg = curent_shape_prg;
+            let mut new_shape = self.shape().to_vec();
+            new_shape.iter_mut().for_each(|x| {
+                *x -= 1;
+            });
+            self.last_stride = self.strides()[self.strides().len() - 1];
+            self.set_shape(Shape::from(new_shape));
+        }
+
+        /// undo `init_leaf`, the pointer and the shape are moved back to the ones of the whole tensor
+        fn reset_leaf(&mut self) {
+            let index = self
+                .prg
+                .iter()
+                .zip(self.strides().iter())
+                .map(|(p, s)| p * s)
+                .sum::<i64>();
+            self.ptr -= index;
+            self.prg = vec![];
+            let mut new_shape = self.shape().to_vec();
+            new_shape.iter_mut().for_each(|x| {
+                *x += 1;
+            });
+            self.set_shape(Shape::from(new_shape));
+        }
+
         /// Map the iterator with a function.
         pub fn strided_map_simd<'a, F, F2>(
             self,
@@ -180,7 +216,7 @@ pub mod par_strided_simd {
         }
 
         fn outer_loop_size(&self) -> usize {
-            self.intervals[self.start_index].1 - self.intervals[self.start_index].0
+            self.intervals[self.end_index - 1].1 - self.intervals[self.start_index].0
         }
 
         fn inner_loop_size(&self) -> usize {
@@ -244,28 +280,17 @@ pub mod par_strided_simd {
         type Item = T;
 
         fn split(mut self) -> (Self, Option<Self>) {
+            if !self.prg.is_empty() {
+                self.reset_leaf();
+            }
             if self.end_index - self.start_index <= 1 {
-                let mut curent_shape_prg: Vec<i64> = vec![0; self.shape().len()];
-                let mut amount =
-                    self.intervals[self.start_index].0 * (*self.shape().last().unwrap() as usize);
-                for j in (0..self.shape().len()).rev() {
-                    curent_shape_prg[j] = (amount as i64) % self.shape()[j];
-                    amount /= self.shape()[j] as usize;
-                    self.ptr += curent_shape_prg[j] * self.strides()[j];
-                }
-                self.prg = curent_shape_prg;
-                let mut new_shape = self.shape().to_vec();
-                new_shape.iter_mut().for_each(|x| {
-                    *x -= 1;
-                });
-                self.last_stride = self.strides()[self.strides().len() - 1];
-                self.set_shape(Shape::from(new_shape));
+                self.init_leaf();
                 return (self, None);
             }
             let _left_interval = &self.intervals[self.start_index..self.end_index];
             let left = _left_interval.len() / 2;
             let right = _left_interval.len() / 2 + (_left_interval.len() % 2);
-            (
+            let (mut lhs, mut rhs) = (
                 ParStridedSimd {
                     ptr: self.ptr.clone(),
                     layout: self.layout.clone(),
@@ -275,7 +300,7 @@ pub mod par_strided_simd {
                     end_index: self.start_index + left,
                     last_stride: self.last_stride,
                 },
-                Some(ParStridedSimd {
+                ParStridedSimd {
                     ptr: self.ptr.clone(),
                     layout: self.layout.clone(),
                     prg: vec![],
@@ -283,8 +308,13 @@ pub mod par_strided_simd {
                     start_index: self.start_index + left,
                     end_index: self.start_index + left + right,
                     last_stride: self.last_stride,
-                }),
-            )
+                },
+            );
+            // rayon can stop splitting at any depth, for example when the iterator is consumed in a smaller pool than
+            // the one its intervals were computed for, so both halves are ready to be folded over all their intervals
+            lhs.init_leaf();
+            rhs.init_leaf();
+            (lhs, Some(rhs))
         }
 
         fn fold_with<F>(self, folder: F) -> F
@@ -411,6 +441,44 @@ impl<T: CommonBounds> ParStrided<T> {
             last_stride: tensor.strides()[tensor.strides().len() - 1],
         }
     }
+
+    /// move the pointer to the start of the intervals this producer covers and shrink the shape for the inner loop
+    fn init_leaf(&mut self) {
+        let mut curent_shape_prg: Vec<i64> = vec![0; self.shape().len()];
+        let mut amount =
+            self.intervals[self.start_index].0 * (*self.shape().last().unwrap() as usize);
+        let mut index = 0;
+        for j in (0..self.shape().len()).rev() {
+            curent_shape_prg[j] = (amount as i64) % self.shape()[j];
+            amount /= self.shape()[j] as usize;
+            index += curent_shape_prg[j] * self.strides()[j];
+        }
+        self.ptr.offset(index);
+        self.prg = curent_shape_prg;
+        let mut new_shape = self.shape().to_vec();
+        new_shape.iter_mut().for_each(|x| {
+            *x -= 1;
+        });
+        self.last_stride = self.strides()[self.strides().len() - 1];
+        self.set_shape(Shape::from(new_shape));
+    }
+
+    /// undo `init_leaf`, the pointer and the shape are moved back to the ones of the whole tensor
+    fn reset_leaf(&mut self) {
+        let index = self
+            .prg
+            .iter()
+            .zip(self.strides().iter())
+            .map(|(p, s)| p * s)
+            .sum::<i64>();
+        self.ptr.offset(-index);
+        self.prg = vec![];
+        let mut new_shape = self.shape().to_vec();
+        new_shape.iter_mut().for_each(|x| {
+            *x += 1;
+        });
+        self.set_shape(Shape::from(new_shape));
+    }
     /// Performs a parallel fold (reduce) operation over the tensor elements.
     ///
     /// This method applies a folding function `fold_op` to accumulate tensor elements into an initial
@@ -518,7 +586,7 @@ impl<T: CommonBounds> IterGetSet for ParStrided<T> {
     }
 
     fn outer_loop_size(&self) -> usize {
-        self.intervals[self.start_index].1 - self.intervals[self.start_index].0
+        self.intervals[self.end_index - 1].1 - self.intervals[self.start_index].0
     }
 
     fn inner_loop_size(&self) -> usize {
@@ -567,30 +635,17 @@ where
     type Item = T;
 
     fn split(mut self) -> (Self, Option<Self>) {
+        if !self.prg.is_empty() {
+            self.reset_leaf();
+        }
         if self.end_index - self.start_index <= 1 {
-            let mut curent_shape_prg: Vec<i64> = vec![0; self.shape().len()];
-            let mut amount =
-                self.intervals[self.start_index].0 * (*self.shape().last().unwrap() as usize);
-            let mut index = 0;
-            for j in (0..self.shape().len()).rev() {
-                curent_shape_prg[j] = (amount as i64) % self.shape()[j];
-                amount /= self.shape()[j] as usize;
-                index += curent_shape_prg[j] * self.strides()[j];
-            }
-            self.ptr.offset(index);
-            self.prg = curent_shape_prg;
-            let mut new_shape = self.shape().to_vec();
-            new_shape.iter_mut().for_each(|x| {
-                *x -= 1;
-            });
-            self.last_stride = self.strides()[self.strides().len() - 1];
-            self.set_shape(Shape::from(new_shape));
+            self.init_leaf();
             return (self, None);
         }
         let _left_interval = &self.intervals[self.start_index..self.end_index];
         let left = _left_interval.len() / 2;
         let right = _left_interval.len() / 2 + (_left_interval.len() % 2);
-        (
+        let (mut lhs, mut rhs) = (
             ParStrided {
                 ptr: self.ptr.clone(),
                 layout: self.layout.clone(),
@@ -600,7 +655,7 @@ where
                 end_index: self.start_index + left,
                 last_stride: self.last_stride,
             },
-            Some(ParStrided {
+            ParStrided {
                 ptr: self.ptr.clone(),
                 layout: self.layout.clone(),
                 prg: vec![],
@@ -608,8 +663,13 @@ where
                 start_index: self.start_index + left,
                 end_index: self.start_index + left + right,
                 last_stride: self.last_stride,
-            }),
-        )
+            },
+        );
+        // rayon can stop splitting at any depth, for example when the iterator is consumed in a smaller pool than
+        // the one its intervals were computed for, so both halves are ready to be folded over all their intervals
+        lhs.init_leaf();
+        rhs.init_leaf();
+        (lhs, Some(rhs))
     }
 
     fn fold_with<F>(self, folder: F) -> F
@@ -0,0 +1,616 @@
+use std::ops::Range;
+
+use hpt_common::{shape::shape::Shape, shape::shape_utils::mt_intervals, utils::pointer::Pointer};
+use hpt_traits::ops::advance::PadMode;
+use hpt_traits::tensor::{CommonBounds, TensorAlloc, TensorInfo};
+use hpt_types::{dtype::TypeCommon, vectors::traits::VecTrait};
+use rayon::iter::{IntoParallelRefIterator, ParallelIterator};
+
+/// The geometry of a windowed tensor, shared by all of the windows.
+///
+/// Without padding the output has `(n - dilation * (k - 1) - 1) / step + 1` windows along each
+/// dimension. The padding modes pad the tensor like the `same` padding of convolutions: the output
+/// has `ceil(n / step)` windows and the padding is split evenly, the extra element going to the end.
+struct Geometry<T> {
+    ptr: Pointer<T>,
+    shape: Vec<i64>,
+    strides: Vec<i64>,
+    kernel: Vec<i64>,
+    steps: Vec<i64>,
+    dilation: Vec<i64>,
+    pad_begin: Vec<i64>,
+    out_shape: Vec<i64>,
+    /// memory offsets of the kernel elements from the window origin, in row-major order
+    offsets: Vec<i64>,
+    /// `None` visits only the windows fully inside of the tensor
+    padding: Option<PadMode<T>>,
+}
+
+impl<T: CommonBounds> Geometry<T> {
+    #[track_caller]
+    fn new<U: TensorInfo<T>>(
+        tensor: U,
+        kernel_shape: &[i64],
+        steps: &[i64],
+        dilation: &[i64],
+        padding: Option<PadMode<T>>,
+    ) -> Self {
+        let ndim = tensor.ndim();
+        assert!(
+            ndim > 0,
+            "windows requires a tensor with at least one dimension"
+        );
+        for (name, values) in [
+            ("kernel_shape", kernel_shape),
+            ("strides", steps),
+            ("dilation", dilation),
+        ] {
+            assert!(
+                values.len() == ndim,
+                "windows: {} has {} values, the tensor has {} dimensions",
+                name,
+                values.len(),
+                ndim
+            );
+            assert!(
+                values.iter().all(|&v| v > 0),
+                "windows: {} must be positive, got {:?}",
+                name,
+                values
+            );
+        }
+        let shape = tensor.shape().to_vec();
+        let mut out_shape = Vec::with_capacity(ndim);
+        let mut pad_begin = Vec::with_capacity(ndim);
+        for i in 0..ndim {
+            let span = dilation[i] * (kernel_shape[i] - 1) + 1;
+            match padding {
+                None => {
+                    out_shape.push(if shape[i] >= span {
+                        (shape[i] - span) / steps[i] + 1
+                    } else {
+                        0
+                    });
+                    pad_begin.push(0);
+                }
+                Some(_) => {
+                    let out = (shape[i] + steps[i] - 1) / steps[i];
+                    let total = ((out - 1) * steps[i] + span - shape[i]).max(0);
+                    out_shape.push(out);
+                    pad_begin.push(total / 2);
+                }
+            }
+        }
+        let strides = tensor.strides().to_vec();
+        let mut offsets = vec![0i64];
+        for i in 0..ndim {
+            let step = dilation[i] * strides[i];
+            offsets = offsets
+                .iter()
+                .flat_map(|&o| (0..kernel_shape[i]).map(move |k| o + k * step))
+                .collect();
+        }
+        Geometry {
+            ptr: tensor.ptr(),
+            shape,
+            strides,
+            kernel: kernel_shape.to_vec(),
+            steps: steps.to_vec(),
+            dilation: dilation.to_vec(),
+            pad_begin,
+            out_shape,
+            offsets,
+            padding,
+        }
+    }
+
+    #[inline(always)]
+    fn read(&self, offset: i64) -> T {
+        unsafe { *self.ptr.ptr.offset(offset as isize) }
+    }
+
+    /// true if the window starting at `origin` along `dim` is inside of the tensor
+    #[inline(always)]
+    fn inside(&self, dim: usize, origin: i64) -> bool {
+        origin >= 0 && origin + self.dilation[dim] * (self.kernel[dim] - 1) < self.shape[dim]
+    }
+
+    /// the coordinate read for `coord` along `dim`, `None` for the constant padding
+    #[inline(always)]
+    fn resolve(&self, dim: usize, coord: i64) -> Option<i64> {
+        match &self.padding {
+            Some(mode) => mode.source(coord, self.shape[dim]),
+            // without padding the windows never leave the tensor
+            None => Some(coord),
+        }
+    }
+
+    fn pad_value(&self) -> T {
+        match self.padding {
+            Some(PadMode::Constant(v)) => v,
+            _ => T::ZERO,
+        }
+    }
+
+    fn rows(&self) -> usize {
+        self.out_shape[..self.out_shape.len() - 1]
+            .iter()
+            .product::<i64>() as usize
+    }
+
+    fn inner(&self) -> usize {
+        *self.out_shape.last().unwrap() as usize
+    }
+
+    fn intervals(&self) -> Vec<(usize, usize)> {
+        let rows = self.rows();
+        mt_intervals(rows, rows.min(rayon::current_num_threads()))
+    }
+
+    /// set the origin of the row `row` for the dimensions except the last one, returns whether they
+    /// are inside of the tensor and the memory offset of the origin
+    fn row_origin(&self, row: usize, origin: &mut [i64]) -> (bool, i64) {
+        let last = origin.len() - 1;
+        let mut rem = row as i64;
+        let (mut inside, mut base) = (true, 0);
+        for d in (0..last).rev() {
+            let pos = rem % self.out_shape[d];
+            rem /= self.out_shape[d];
+            origin[d] = pos * self.steps[d] - self.pad_begin[d];
+            inside &= self.inside(d, origin[d]);
+            base += origin[d] * self.strides[d];
+        }
+        (inside, base)
+    }
+
+    /// call `f` with the output index and the window of every window in the rows `rows`
+    fn for_rows<F>(&self, rows: Range<usize>, mut f: F)
+    where
+        F: FnMut(usize, Window<'_, T>),
+    {
+        let last = self.shape.len() - 1;
+        let inner = self.inner();
+        let mut origin = vec![0; self.shape.len()];
+        for row in rows {
+            let (row_inside, row_base) = self.row_origin(row, &mut origin);
+            for j in 0..inner {
+                origin[last] = j as i64 * self.steps[last] - self.pad_begin[last];
+                let inside = row_inside && self.inside(last, origin[last]);
+                let base = row_base + origin[last] * self.strides[last];
+                f(
+                    row * inner + j,
+                    Window {
+                        geom: self,
+                        origin: &origin,
+                        base,
+                        inside,
+                    },
+                );
+            }
+        }
+    }
+
+    /// write `f` of every window of the rows `rows` to `out`, `vec_op` computes the lanes of
+    /// `O::Vec::SIZE` consecutive windows fully inside of the tensor
+    fn map_rows_simd<O, F, F2>(&self, rows: Range<usize>, out: &Pointer<O>, f: &F, vec_op: &F2)
+    where
+        O: CommonBounds,
+        F: Fn(Window<'_, T>) -> O,
+        F2: Fn(WindowSimd<'_, T>) -> O::Vec,
+    {
+        let last = self.shape.len() - 1;
+        let inner = self.inner();
+        let lanes = T::Vec::SIZE;
+        let lane_stride = self.steps[last] * self.strides[last];
+        let mut origin = vec![0; self.shape.len()];
+        for row in rows {
+            let (row_inside, row_base) = self.row_origin(row, &mut origin);
+            let mut j = 0;
+            while j < inner {
+                let first = j as i64 * self.steps[last] - self.pad_begin[last];
+                let last_lane = first + (lanes as i64 - 1) * self.steps[last];
+                if row_inside
+                    && lanes == O::Vec::SIZE
+                    && j + lanes <= inner
+                    && self.inside(last, first)
+                    && self.inside(last, last_lane)
+                {
+                    let lanes_window = WindowSimd {
+                        geom: self,
+                        base: row_base + first * self.strides[last],
+                        lane_stride,
+                    };
+                    unsafe {
+                        (out.get_ptr().add(row * inner + j) as *mut O::Vec)
+                            .write_unaligned(vec_op(lanes_window));
+                    }
+                    j += lanes;
+                    continue;
+                }
+                origin[last] = first;
+                let window = Window {
+                    geom: self,
+                    origin: &origin,
+                    base: row_base + first * self.strides[last],
+                    inside: row_inside && self.inside(last, first),
+                };
+                unsafe { out.get_ptr().add(row * inner + j).write(f(window)) };
+                j += 1;
+            }
+        }
+    }
+}
+
+/// A neighborhood of a tensor element, visited by the windows iterators.
+///
+/// A window fully inside of the tensor reads the tensor memory directly, a window crossing the
+/// border reads the outside elements according to the `PadMode`.
+#[derive(Clone, Copy)]
+pub struct Window<'w, T> {
+    geom: &'w Geometry<T>,
+    origin: &'w [i64],
+    base: i64,
+    inside: bool,
+}
+
+impl<'w, T: CommonBounds> Window<'w, T> {
+    /// Returns the shape of the window, the kernel shape.
+    pub fn shape(&self) -> &[i64] {
+        &self.geom.kernel
+    }
+
+    /// Returns the number of elements of the window.
+    pub fn len(&self) -> usize {
+        self.geom.offsets.len()
+    }
+
+    /// Returns `true` if the window has no element.
+    pub fn is_empty(&self) -> bool {
+        self.geom.offsets.is_empty()
+    }
+
+    /// Returns the coordinates in the tensor of the first element of the window, negative in the
+    /// padding.
+    pub fn origin(&self) -> &[i64] {
+        self.origin
+    }
+
+    /// Returns `true` if the whole window is inside of the tensor.
+    pub fn is_inside(&self) -> bool {
+        self.inside
+    }
+
+    /// Returns the element at `idx`, an index in the window.
+    #[track_caller]
+    pub fn get(&self, idx: &[i64]) -> T {
+        let geom = self.geom;
+        assert_eq!(
+            idx.len(),
+            geom.kernel.len(),
+            "window index has a wrong rank"
+        );
+        if self.inside {
+            let offset = idx
+                .iter()
+                .enumerate()
+                .map(|(d, &i)| i * geom.dilation[d] * geom.strides[d])
+                .sum::<i64>();
+            return geom.read(self.base + offset);
+        }
+        let mut offset = 0;
+        for (d, &i) in idx.iter().enumerate() {
+            match geom.resolve(d, self.origin[d] + i * geom.dilation[d]) {
+                Some(c) => offset += c * geom.strides[d],
+                None => return geom.pad_value(),
+            }
+        }
+        geom.read(offset)
+    }
+
+    /// Returns the `i`-th element of the window in row-major order.
+    #[inline(always)]
+    pub fn at(&self, i: usize) -> T {
+        let geom = self.geom;
+        if self.inside {
+            return geom.read(self.base + geom.offsets[i]);
+        }
+        let mut rem = i as i64;
+        let mut offset = 0;
+        for d in (0..geom.kernel.len()).rev() {
+            let k = rem % geom.kernel[d];
+            rem /= geom.kernel[d];
+            match geom.resolve(d, self.origin[d] + k * geom.dilation[d]) {
+                Some(c) => offset += c * geom.strides[d],
+                None => return geom.pad_value(),
+            }
+        }
+        geom.read(offset)
+    }
+
+    /// Returns an iterator over the elements of the window in row-major order.
+    pub fn iter(&self) -> impl Iterator<Item = T> + '_ {
+        (0..self.len()).map(move |i| self.at(i))
+    }
+}
+
+/// `T::Vec::SIZE` consecutive windows along the last dimension, all of them inside of the tensor.
+///
+/// Each lane of the vectors returned belongs to one window.
+#[derive(Clone, Copy)]
+pub struct WindowSimd<'w, T> {
+    geom: &'w Geometry<T>,
+    base: i64,
+    lane_stride: i64,
+}
+
+impl<'w, T: CommonBounds> WindowSimd<'w, T> {
+    /// Returns the shape of the windows, the kernel shape.
+    pub fn shape(&self) -> &[i64] {
+        &self.geom.kernel
+    }
+
+    /// Returns the number of elements of each window.
+    pub fn len(&self) -> usize {
+        self.geom.offsets.len()
+    }
+
+    /// Returns `true` if the windows have no element.
+    pub fn is_empty(&self) -> bool {
+        self.geom.offsets.is_empty()
+    }
+
+    #[inline(always)]
+    fn load(&self, offset: i64) -> T::Vec {
+        let ptr = unsafe { self.geom.ptr.ptr.offset(offset as isize) };
+        if self.lane_stride == 1 {
+            unsafe { (ptr as *const T::Vec).read_unaligned() }
+        } else {
+            let mut vec = T::Vec::splat(T::ZERO);
+            let lanes = vec.as_mut_ptr();
+            for lane in 0..T::Vec::SIZE {
+                unsafe {
+                    *lanes.add(lane) = *ptr.offset(lane as isize * self.lane_stride as isize)
+                };
+            }
+            vec
+        }
+    }
+
+    /// Returns the elements at `idx`, an index in the windows, one lane per window.
+    #[track_caller]
+    pub fn get(&self, idx: &[i64]) -> T::Vec {
+        let geom = self.geom;
+        assert_eq!(
+            idx.len(),
+            geom.kernel.len(),
+            "window index has a wrong rank"
+        );
+        let offset = idx
+            .iter()
+            .enumerate()
+            .map(|(d, &i)| i * geom.dilation[d] * geom.strides[d])
+            .sum::<i64>();
+        self.load(self.base + offset)
+    }
+
+    /// Returns the `i`-th elements of the windows in row-major order.
+    #[inline(always)]
+    pub fn at(&self, i: usize) -> T::Vec {
+        self.load(self.base + self.geom.offsets[i])
+    }
+
+    /// Returns an iterator over the elements of the windows in row-major order.
+    pub fn iter(&self) -> impl Iterator<Item = T::Vec> + '_ {
+        (0..self.len()).map(move |i| self.at(i))
+    }
+}
+
+/// A single-threaded iterator over the windows of a tensor.
+///
+/// The windows are visited in row-major order of the output, see `TensorIterator::windows`.
+pub struct Windows<'a, T> {
+    geom: Geometry<T>,
+    phantom: std::marker::PhantomData<&'a ()>,
+}
+
+impl<'a, T: CommonBounds> Windows<'a, T> {
+    /// Create a new windows iterator.
+    #[track_caller]
+    pub fn new<U: TensorInfo<T>>(
+        tensor: U,
+        kernel_shape: &[i64],
+        strides: &[i64],
+        dilation: &[i64],
+        padding: Option<PadMode<T>>,
+    ) -> Self {
+        Windows {
+            geom: Geometry::new(tensor, kernel_shape, strides, dilation, padding),
+            phantom: std::marker::PhantomData,
+        }
+    }
+
+    /// Returns the shape of the output, the number of windows along each dimension.
+    pub fn shape(&self) -> Shape {
+        Shape::from(&self.geom.out_shape)
+    }
+
+    /// Call `f` for every window.
+    pub fn for_each<F>(&self, mut f: F)
+    where
+        F: FnMut(Window<'_, T>),
+    {
+        self.geom.for_rows(0..self.geom.rows(), |_, w| f(w));
+    }
+
+    /// Map every window to an element of the output.
+    pub fn strided_map<F, O>(self, f: F) -> WindowsMap<'a, T, F>
+    where
+        F: Fn(Window<'_, T>) -> O,
+    {
+        WindowsMap {
+            geom: self.geom,
+            f,
+            parallel: false,
+            phantom: std::marker::PhantomData,
+        }
+    }
+
+    /// Map every window to an element of the output, `vec_op` maps `T::Vec::SIZE` consecutive
+    /// windows inside of the tensor at once.
+    pub fn strided_map_simd<F, F2, O, OV>(self, f: F, vec_op: F2) -> WindowsMapSimd<'a, T, F, F2>
+    where
+        F: Fn(Window<'_, T>) -> O,
+        F2: Fn(WindowSimd<'_, T>) -> OV,
+    {
+        WindowsMapSimd {
+            geom: self.geom,
+            f,
+            vec_op,
+            parallel: false,
+            phantom: std::marker::PhantomData,
+        }
+    }
+}
+
+/// A multi-threaded iterator over the windows of a tensor.
+///
+/// The rows of the output are split in chunks with `mt_intervals` like `ParStrided`.
+pub struct ParWindows<'a, T> {
+    geom: Geometry<T>,
+    phantom: std::marker::PhantomData<&'a ()>,
+}
+
+impl<'a, T: CommonBounds> ParWindows<'a, T> {
+    /// Create a new parallel windows iterator.
+    #[track_caller]
+    pub fn new<U: TensorInfo<T>>(
+        tensor: U,
+        kernel_shape: &[i64],
+        strides: &[i64],
+        dilation: &[i64],
+        padding: Option<PadMode<T>>,
+    ) -> Self {
+        ParWindows {
+            geom: Geometry::new(tensor, kernel_shape, strides, dilation, padding),
+            phantom: std::marker::PhantomData,
+        }
+    }
+
+    /// Returns the shape of the output, the number of windows along each dimension.
+    pub fn shape(&self) -> Shape {
+        Shape::from(&self.geom.out_shape)
+    }
+
+    /// Call `f` for every window, in parallel.
+    pub fn for_each<F>(&self, f: F)
+    where
+        F: Fn(Window<'_, T>) + Sync + Send,
+    {
+        let geom = &self.geom;
+        geom.intervals()
+            .par_iter()
+            .for_each(|&(start, end)| geom.for_rows(start..end, |_, w| f(w)));
+    }
+
+    /// Map every window to an element of the output, in parallel.
+    pub fn strided_map<F, O>(self, f: F) -> WindowsMap<'a, T, F>
+    where
+        F: Fn(Window<'_, T>) -> O,
+    {
+        WindowsMap {
+            geom: self.geom,
+            f,
+            parallel: true,
+            phantom: std::marker::PhantomData,
+        }
+    }
+
+    /// Map every window to an element of the output in parallel, `vec_op` maps `T::Vec::SIZE`
+    /// consecutive windows inside of the tensor at once.
+    pub fn strided_map_simd<F, F2, O, OV>(self, f: F, vec_op: F2) -> WindowsMapSimd<'a, T, F, F2>
+    where
+        F: Fn(Window<'_, T>) -> O,
+        F2: Fn(WindowSimd<'_, T>) -> OV,
+    {
+        WindowsMapSimd {
+            geom: self.geom,
+            f,
+            vec_op,
+            parallel: true,
+            phantom: std::marker::PhantomData,
+        }
+    }
+}
+
+/// A map over the windows of a tensor, collected into a tensor with one element per window.
+pub struct WindowsMap<'a, T, F> {
+    geom: Geometry<T>,
+    f: F,
+    parallel: bool,
+    phantom: std::marker::PhantomData<&'a ()>,
+}
+
+impl<'a, T: CommonBounds, F> WindowsMap<'a, T, F> {
+    /// Collects the results of the map into a new tensor of the output shape.
+    pub fn collect<U>(self) -> U
+    where
+        F: Fn(Window<'_, T>) -> U::Meta + Sync + Send + 'a,
+        U: Clone + TensorInfo<U::Meta> + TensorAlloc,
+        <U as TensorAlloc>::Meta: CommonBounds,
+    {
+        let res = U::_empty(self.geom.out_shape.clone()).unwrap();
+        let out = res.ptr();
+        let (geom, f) = (&self.geom, &self.f);
+        let write = |start: usize, end: usize| {
+            geom.for_rows(start..end, |i, w| unsafe {
+                out.get_ptr().add(i).write(f(w))
+            })
+        };
+        if self.parallel {
+            geom.intervals()
+                .par_iter()
+                .for_each(|&(start, end)| write(start, end));
+        } else {
+            write(0, geom.rows());
+        }
+        res
+    }
+}
+
+/// A SIMD map over the windows of a tensor, collected into a tensor with one element per window.
+pub struct WindowsMapSimd<'a, T, F, F2> {
+    geom: Geometry<T>,
+    f: F,
+    vec_op: F2,
+    parallel: bool,
+    phantom: std::marker::PhantomData<&'a ()>,
+}
+
+impl<'a, T: CommonBounds, F, F2> WindowsMapSimd<'a, T, F, F2> {
+    /// Collects the results of the map into a new tensor of the output shape.
+    ///
+    /// `vec_op` is only used when `U::Meta::Vec` has as many lanes as `T::Vec`, `f` maps the other
+    /// windows.
+    pub fn collect<U>(self) -> U
+    where
+        F: Fn(Window<'_, T>) -> U::Meta + Sync + Send + 'a,
+        F2: Fn(WindowSimd<'_, T>) -> <<U as TensorAlloc>::Meta as TypeCommon>::Vec
+            + Sync
+            + Send
+            + 'a,
+        U: Clone + TensorInfo<U::Meta> + TensorAlloc,
+        <U as TensorAlloc>::Meta: CommonBounds,
+    {
+        let res = U::_empty(self.geom.out_shape.clone()).unwrap();
+        let out = res.ptr();
+        let (geom, f, vec_op) = (&self.geom, &self.f, &self.vec_op);
+        if self.parallel {
+            geom.intervals()
+                .par_iter()
+                .for_each(|&(start, end)| geom.map_rows_simd(start..end, &out, f, vec_op));
+        } else {
+            geom.map_rows_simd(0..geom.rows(), &out, f, vec_op);
+        }
+        res
+    }
+}
@@ -15,7 +15,7 @@ pub fn __gen_fast_reduce_simd_helper(stream: TokenStream) -> TokenStream {
         not(target_feature = "avx2")
     ))]
     let num_registers = 8;
-    #[cfg(any(target_feature = "avx512f", target_arch = "aarch64"))]
+    #[cfg(target_arch = "aarch64")]
     let num_registers = 32;
 
     let mut body = proc_macro2::TokenStream::new();
@@ -54,7 +54,7 @@ pub fn __gen_fast_layernorm_simd_helper(stream: TokenStream) -> TokenStream {
         not(target_feature = "avx2")
     ))]
     let num_registers = 8;
-    #[cfg(any(target_feature = "avx512f", target_arch = "aarch64"))]
+    #[cfg(target_arch = "aarch64")]
     let num_registers = 32;
 
     let mut body = proc_macro2::TokenStream::new();
@@ -67,7 +67,7 @@ const NUM_REG: usize = 16;
     not(target_feature = "avx2")
 ))]
 const NUM_REG: usize = 8;
-#[cfg(any(target_feature = "avx512f", target_arch = "aarch64"))]
+#[cfg(target_arch = "aarch64")]
 const NUM_REG: usize = 32;
 
 struct SelectionParser {
@@ -889,11 +889,11 @@ pub fn impl_save(input: TokenStream) -> TokenStream {
         let level = compress_levels[idx].clone().unwrap_or(quote!(level));
         if let Some(name) = name {
             quote! {
-                let #ident = <#ty as Save>::__save(&data.#name, file, len_so_far, global_cnt, #compression_algo, #endian, #level)?;
+                let #ident = <#ty as Save>::__save(&data.#name, writer, #compression_algo, #endian, #level)?;
             }
         } else {
             quote! {
-                let #ident = <#ty as Save>::__save(&data.#idx, file, len_so_far, global_cnt, #compression_algo, #endian, #level)?;
+                let #ident = <#ty as Save>::__save(&data.#idx, writer, #compression_algo, #endian, #level)?;
             }
         }
     });
@@ -914,11 +914,9 @@ pub fn impl_save(input: TokenStream) -> TokenStream {
         }
         impl #impl_generics hpt::Save for #name #ty_generics #where_clause {
             type Meta = #meta_name #ty_generics;
-            fn __save(
+            fn __save<W: std::io::Write>(
                 data: &Self,
-                file: &mut std::fs::File,
-                len_so_far: &mut usize,
-                global_cnt: &mut usize,
+                writer: &mut hpt::TensorWriter<W>,
                 compression_algo: hpt::CompressionAlgo,
                 endian: hpt::Endian,
                 level: u32,
@@ -1134,3 +1132,109 @@ pub fn impl_from_safetensors(input: TokenStream) -> TokenStream {
     // println!("{}", formatted);
     expanded.into()
 }
+
+/// generate to safetensors trait
+///
+/// the tensor names follow the ones read by the `FromSafeTensors` derive, a field is written as `{path}.{field}`,
+/// the `map` attribute is interpreted as follows:
+///
+/// - `tensor_name = "..."`: the field is written with the name
+/// - `value = ...`: the field is not a tensor stored in the file and is skipped
+/// - `path = "..."`: the rule only applies when the struct is written under the path
+/// - `vec_len = ..`: the elements are written as `{path}.{i}`
+#[proc_macro_derive(ToSafeTensors, attributes(map))]
+pub fn impl_to_safetensors(input: TokenStream) -> TokenStream {
+    let ast = syn::parse_macro_input!(input as syn::DeriveInput);
+    let struct_name = &ast.ident;
+    let fields = match &ast.data {
+        syn::Data::Struct(s) => &s.fields,
+        _ => panic!("ToSafeTensors can only be derived for structs"),
+    };
+    let (impl_generics, ty_generics, where_clause) = ast.generics.split_for_impl();
+    let mut call_to_safetensors = vec![];
+    for (idx, field) in fields.iter().enumerate() {
+        let ty = &field.ty;
+        let (access, field_name) = match &field.ident {
+            Some(name) => (quote!(#name), name.to_string()),
+            None => {
+                let idx = syn::Index::from(idx);
+                (quote!(#idx), idx.index.to_string())
+            }
+        };
+        let mut default = quote! {
+            <#ty as hpt::ToSafeTensors>::to_safe_tensors(&self.#access, &format!("{}.{}", path, #field_name), tensors)?;
+        };
+        let mut path_arms = vec![];
+        for attr in &field.attrs {
+            if !attr.path().is_ident("map") {
+                continue;
+            }
+            let mut path = None;
+            let mut has_value = false;
+            let mut tensor_name = None;
+            let mut vec_len = None;
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("path") {
+                    let value: syn::LitStr = meta.value()?.parse()?;
+                    path = Some(value.value());
+                } else if meta.path.is_ident("value") {
+                    let _: syn::Expr = meta.value()?.parse()?;
+                    has_value = true;
+                } else if meta.path.is_ident("tensor_name") {
+                    let value: syn::LitStr = meta.value()?.parse()?;
+                    tensor_name = Some(value.value());
+                } else if meta.path.is_ident("vec_len") {
+                    let value: syn::LitInt = meta.value()?.parse()?;
+                    vec_len = Some(value.base10_parse::<usize>().unwrap());
+                } else if meta.path.is_ident("inner_type") {
+                    let _: syn::Ident = meta.value()?.parse()?;
+                }
+                Ok(())
+            })
+            .unwrap_or_else(|err| println!("Failed to parse attribute: {}", err));
+            let write = if let Some(vec_len) = vec_len {
+                quote! {
+                    for (i, item) in self.#access.iter().take(#vec_len).enumerate() {
+                        hpt::ToSafeTensors::to_safe_tensors(item, &format!("{}.{}", path, i), tensors)?;
+                    }
+                }
+            } else {
+                match (has_value, tensor_name) {
+                    (false, Some(tensor_name)) => quote! {
+                        <#ty as hpt::ToSafeTensors>::to_safe_tensors(&self.#access, #tensor_name, tensors)?;
+                    },
+                    (true, None) => quote! {},
+                    (true, Some(_)) => panic!("value and tensor_name cannot be used together"),
+                    (false, None) => panic!("path and value are not present"),
+                }
+            };
+            match path {
+                Some(path) => path_arms.push(quote! { #path => { #write } }),
+                None => default = write,
+            }
+        }
+        if path_arms.is_empty() {
+            call_to_safetensors.push(default);
+        } else {
+            call_to_safetensors.push(quote! {
+                match path {
+                    #(#path_arms)*
+                    _ => { #default }
+                }
+            });
+        }
+    }
+    let expanded = quote! {
+        impl #impl_generics hpt::ToSafeTensors for #struct_name #ty_generics #where_clause {
+            fn to_safe_tensors(
+                &self,
+                path: &str,
+                tensors: &mut Vec<(String, hpt::SafeTensorData)>,
+            ) -> std::io::Result<()> {
+                #(#call_to_safetensors)*
+                Ok(())
+            }
+        }
+    };
+    expanded.into()
+}
@@ -72,39 +72,6 @@ pub fn type_simd_lanes(list: &str) -> u8 {
         "complex64" => 4,
         _ => 0,
     }
-    #[cfg(target_feature = "avx512f")]
-    match list.to_lowercase().as_str() {
-        "bool" => 64,
-        "i8" => 64,
-        "u8" => 64,
-        "i16" => 32,
-        "u16" => 32,
-        "i32" => 16,
-        "u32" => 16,
-        "i64" => 8,
-        "u64" => 8,
-        "bf16" => 32,
-        "f16" => 32,
-        "f32" => 16,
-        "f64" => 8,
-        #[cfg(target_pointer_width = "64")]
-        "isize" => 8,
-        #[cfg(target_pointer_width = "64")]
-        "usize" => 8,
-        #[cfg(target_pointer_width = "32")]
-        "isize" => 16,
-        #[cfg(target_pointer_width = "32")]
-        "usize" => 16,
-        #[cfg(target_pointer_width = "64")]
-        "complex32" => 8,
-        #[cfg(target_pointer_width = "64")]
-        "complex64" => 4,
-        #[cfg(target_pointer_width = "32")]
-        "complex32" => 16,
-        #[cfg(target_pointer_width = "32")]
-        "complex64" => 8,
-        _ => 0,
-    }
 }
 
 #[derive(Copy, Clone, PartialEq, Eq)]
@@ -313,28 +280,6 @@ impl ToTokens for SimdType {
             SimdType::Complex32 => quote!(cplx32x2::cplx32x2),
             SimdType::Complex64 => quote!(cplx64x1::cplx64x1),
         };
-        #[cfg(target_feature = "avx512f")]
-        let token = match self {
-            SimdType::Bool => quote!(boolx64::boolx64),
-            SimdType::I8 => quote!(i8x64::i8x64),
-            SimdType::U8 => quote!(u8x64::u8x64),
-            SimdType::I16 => quote!(i16x32::i16x32),
-            SimdType::U16 => quote!(u16x32::u16x32),
-            SimdType::I32 => quote!(i32x16::i32x16),
-            SimdType::U32 => quote!(u32x16::u32x16),
-            SimdType::I64 => quote!(i64x8::i64x8),
-            SimdType::U64 => quote!(u64x8::u64x8),
-            SimdType::BF16 => quote!(bf16x32::bf16x32),
-            SimdType::F16 => quote!(f16x32::f16x32),
-            SimdType::F32 => quote!(f32x16::f32x16),
-            SimdType::F64 => quote!(f64x8::f64x8),
-            SimdType::C32 => quote!(cplx32x8::cplx32x8),
-            SimdType::C64 => quote!(cplx64x4::cplx64x4),
-            SimdType::Isize => quote!(isizex8::isizex8),
-            SimdType::Usize => quote!(usizex8::usizex8),
-            SimdType::Complex32 => quote!(cplx32x8::cplx32x8),
-            SimdType::Complex64 => quote!(cplx64x4::cplx64x4),
-        };
         tokens.extend(token);
     }
 }
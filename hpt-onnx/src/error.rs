@@ -0,0 +1,101 @@
+use std::fmt::Display;
+
+use hpt::TensorError;
+use thiserror::Error;
+
+/// Errors of the ONNX importer and executor
+#[derive(Debug, Error)]
+pub enum OnnxError {
+    /// Error that occurs when the file is not a valid ONNX protobuf message
+    #[error("Invalid ONNX protobuf at byte {offset}: {message}")]
+    Decode {
+        /// Byte offset in the message being decoded
+        offset: usize,
+        /// What is wrong with the message
+        message: String,
+    },
+    /// Error that occurs when the graph contains operators the executor doesn't implement
+    #[error("{0}")]
+    Unsupported(UnsupportedReport),
+    /// Error that occurs when a node attribute or input is outside of what the executor supports
+    #[error("Invalid node {node} ({op_type}): {message}")]
+    InvalidNode {
+        /// Name of the node
+        node: String,
+        /// Operator of the node
+        op_type: String,
+        /// What is wrong with the node
+        message: String,
+    },
+    /// Error that occurs when a value is read before any node or input produces it
+    #[error("Value {name} is missing, required by {node}")]
+    MissingValue {
+        /// Name of the value
+        name: String,
+        /// Name of the node reading the value
+        node: String,
+    },
+    /// Error that occurs when the graph contains a cycle
+    #[error("The graph is not a DAG, the nodes {0:?} are never ready")]
+    Cycle(Vec<String>),
+    /// Error that occurs when a tensor has an ONNX data type hpt doesn't have
+    #[error("Unsupported ONNX data type {data_type} of tensor {name}")]
+    UnsupportedDtype {
+        /// `TensorProto.DataType` value
+        data_type: i32,
+        /// Name of the tensor
+        name: String,
+    },
+    /// Error raised by a tensor operation
+    #[error(transparent)]
+    Tensor(#[from] TensorError),
+    /// Error that occurs when the model file can't be read
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+}
+
+/// an operator the executor doesn't implement, with the nodes using it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnsupportedOp {
+    /// Domain of the operator, empty for the default `ai.onnx` domain
+    pub domain: String,
+    /// Name of the operator
+    pub op_type: String,
+    /// Names of the nodes using the operator, in graph order
+    pub nodes: Vec<String>,
+}
+
+/// the operators of a graph the executor doesn't implement, sorted by name
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct UnsupportedReport {
+    /// Unsupported operators
+    pub ops: Vec<UnsupportedOp>,
+}
+
+impl UnsupportedReport {
+    /// true if every operator of the graph is supported
+    pub fn is_empty(&self) -> bool {
+        self.ops.is_empty()
+    }
+}
+
+impl Display for UnsupportedReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} unsupported operator(s):", self.ops.len())?;
+        for op in &self.ops {
+            let name = if op.domain.is_empty() {
+                op.op_type.clone()
+            } else {
+                format!("{}::{}", op.domain, op.op_type)
+            };
+            write!(
+                f,
+                "\n  {} used by {} node(s): {}",
+                name,
+                op.nodes.len(),
+                op.nodes.join(", ")
+            )?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,13 @@
+//! This crate imports ONNX models and runs them on the CPU with hpt tensors.
+#![deny(missing_docs)]
+
+mod error;
+mod model;
+mod ops;
+/// the ONNX protobuf messages read by the importer
+pub mod proto;
+mod tensor;
+
+pub use error::{OnnxError, UnsupportedOp, UnsupportedReport};
+pub use model::OnnxModel;
+pub use tensor::onnx_dtype;
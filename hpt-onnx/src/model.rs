@@ -0,0 +1,254 @@
+use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    path::Path,
+};
+
+use hpt::DynTensor;
+
+use crate::{
+    error::{OnnxError, UnsupportedOp, UnsupportedReport},
+    ops,
+    proto::{GraphProto, ModelProto, ValueInfoProto},
+};
+
+/// the opset assumed when the model doesn't import the default domain
+const DEFAULT_OPSET: i64 = 13;
+
+/// an ONNX model ready to run on the CPU
+///
+/// The initializers are converted to tensors and the nodes are sorted topologically when the
+/// model is loaded. Loading doesn't fail on unsupported operators, use
+/// [`unsupported_ops`](OnnxModel::unsupported_ops) to list them, `run` returns the same report as
+/// an error.
+///
+/// # Example
+/// ```no_run
+/// use hpt::{DynTensor, Tensor, TensorCreator};
+/// use hpt_onnx::OnnxModel;
+///
+/// let model = OnnxModel::load("model.onnx")?;
+/// let report = model.unsupported_ops();
+/// assert!(report.is_empty(), "{}", report);
+/// let x: DynTensor = Tensor::<f32>::zeros(&[1, 3, 224, 224])?.into();
+/// let outputs = model.run([("input", x)])?;
+/// println!("{}", outputs["output"]);
+/// # Ok::<(), hpt_onnx::OnnxError>(())
+/// ```
+pub struct OnnxModel {
+    model: ModelProto,
+    initializers: HashMap<String, DynTensor>,
+    /// indices of the nodes in execution order
+    order: Vec<usize>,
+}
+
+impl OnnxModel {
+    /// load a model from an `.onnx` file
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, OnnxError> {
+        Self::from_bytes(&std::fs::read(path)?)
+    }
+
+    /// load a model from the bytes of an `.onnx` file
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, OnnxError> {
+        let model = ModelProto::decode(bytes)?;
+        let initializers = model
+            .graph
+            .initializer
+            .iter()
+            .map(|t| Ok((t.name.clone(), t.to_tensor()?)))
+            .collect::<Result<HashMap<_, _>, OnnxError>>()?;
+        let order = topological_order(&model.graph, &initializers)?;
+        Ok(OnnxModel {
+            model,
+            initializers,
+            order,
+        })
+    }
+
+    /// the decoded model
+    pub fn proto(&self) -> &ModelProto {
+        &self.model
+    }
+
+    /// the graph inputs that must be fed to `run`, the initializers are excluded
+    pub fn inputs(&self) -> Vec<&ValueInfoProto> {
+        self.model
+            .graph
+            .input
+            .iter()
+            .filter(|input| !self.initializers.contains_key(&input.name))
+            .collect()
+    }
+
+    /// the graph outputs returned by `run`
+    pub fn outputs(&self) -> &[ValueInfoProto] {
+        &self.model.graph.output
+    }
+
+    /// the initializer `name`
+    pub fn initializer(&self, name: &str) -> Option<&DynTensor> {
+        self.initializers.get(name)
+    }
+
+    /// the operators of the graph the executor doesn't implement
+    pub fn unsupported_ops(&self) -> UnsupportedReport {
+        let mut ops: Vec<UnsupportedOp> = Vec::new();
+        for node in &self.model.graph.node {
+            if ops::is_supported(&node.domain, &node.op_type) {
+                continue;
+            }
+            let name = node.display_name().to_string();
+            match ops
+                .iter_mut()
+                .find(|op| op.domain == node.domain && op.op_type == node.op_type)
+            {
+                Some(op) => op.nodes.push(name),
+                None => ops.push(UnsupportedOp {
+                    domain: node.domain.clone(),
+                    op_type: node.op_type.clone(),
+                    nodes: vec![name],
+                }),
+            }
+        }
+        ops.sort_by(|a, b| (&a.domain, &a.op_type).cmp(&(&b.domain, &b.op_type)));
+        UnsupportedReport { ops }
+    }
+
+    /// run the graph, returns the graph outputs by name
+    ///
+    /// every graph input that is not an initializer must be given, an input given for an
+    /// initializer overrides it. Intermediate values are dropped after their last use.
+    pub fn run<I, S>(&self, inputs: I) -> Result<HashMap<String, DynTensor>, OnnxError>
+    where
+        I: IntoIterator<Item = (S, DynTensor)>,
+        S: Into<String>,
+    {
+        let report = self.unsupported_ops();
+        if !report.is_empty() {
+            return Err(OnnxError::Unsupported(report));
+        }
+        let graph = &self.model.graph;
+        let opset = self.model.opset_version().unwrap_or(DEFAULT_OPSET);
+
+        let mut values: HashMap<String, DynTensor> = self.initializers.clone();
+        values.extend(inputs.into_iter().map(|(name, t)| (name.into(), t)));
+        for input in self.inputs() {
+            if !values.contains_key(&input.name) {
+                return Err(OnnxError::MissingValue {
+                    name: input.name.clone(),
+                    node: "the graph inputs".to_string(),
+                });
+            }
+        }
+
+        let outputs: HashSet<&str> = graph.output.iter().map(|o| o.name.as_str()).collect();
+        let mut last_use: HashMap<&str, usize> = HashMap::new();
+        for (step, &idx) in self.order.iter().enumerate() {
+            for input in &graph.node[idx].input {
+                last_use.insert(input.as_str(), step);
+            }
+        }
+
+        for (step, &idx) in self.order.iter().enumerate() {
+            let node = &graph.node[idx];
+            let args = node
+                .input
+                .iter()
+                .map(|name| {
+                    if name.is_empty() {
+                        return Ok(None);
+                    }
+                    values
+                        .get(name)
+                        .cloned()
+                        .map(Some)
+                        .ok_or_else(|| OnnxError::MissingValue {
+                            name: name.clone(),
+                            node: node.display_name().to_string(),
+                        })
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+            let results = ops::run(node, args, opset)?;
+            for input in &node.input {
+                if last_use.get(input.as_str()) == Some(&step) && !outputs.contains(input.as_str())
+                {
+                    values.remove(input);
+                }
+            }
+            for (name, value) in node.output.iter().zip(results) {
+                if !name.is_empty() {
+                    values.insert(name.clone(), value);
+                }
+            }
+        }
+
+        graph
+            .output
+            .iter()
+            .map(|o| {
+                values
+                    .remove(&o.name)
+                    .map(|t| (o.name.clone(), t))
+                    .ok_or_else(|| OnnxError::MissingValue {
+                        name: o.name.clone(),
+                        node: "the graph outputs".to_string(),
+                    })
+            })
+            .collect()
+    }
+}
+
+/// Kahn's algorithm over the values the nodes read and write, ties keep the graph order
+fn topological_order(
+    graph: &GraphProto,
+    initializers: &HashMap<String, DynTensor>,
+) -> Result<Vec<usize>, OnnxError> {
+    let mut producer: HashMap<&str, usize> = HashMap::new();
+    for (idx, node) in graph.node.iter().enumerate() {
+        for output in node.output.iter().filter(|o| !o.is_empty()) {
+            producer.insert(output.as_str(), idx);
+        }
+    }
+    let available = |name: &str| {
+        initializers.contains_key(name) || graph.input.iter().any(|input| input.name == name)
+    };
+
+    let mut pending = vec![0usize; graph.node.len()];
+    let mut consumers: Vec<Vec<usize>> = vec![Vec::new(); graph.node.len()];
+    for (idx, node) in graph.node.iter().enumerate() {
+        for input in node.input.iter().filter(|i| !i.is_empty()) {
+            match producer.get(input.as_str()) {
+                Some(&p) => {
+                    pending[idx] += 1;
+                    consumers[p].push(idx);
+                }
+                None if available(input) => {}
+                None => {
+                    return Err(OnnxError::MissingValue {
+                        name: input.clone(),
+                        node: node.display_name().to_string(),
+                    })
+                }
+            }
+        }
+    }
+
+    let mut ready: VecDeque<usize> = (0..graph.node.len()).filter(|&i| pending[i] == 0).collect();
+    let mut order = Vec::with_capacity(graph.node.len());
+    while let Some(idx) = ready.pop_front() {
+        order.push(idx);
+        for &consumer in &consumers[idx] {
+            pending[consumer] -= 1;
+            if pending[consumer] == 0 {
+                ready.push_back(consumer);
+            }
+        }
+    }
+    if order.len() != graph.node.len() {
+        let stuck = (0..graph.node.len())
+            .filter(|&i| pending[i] > 0)
+            .map(|i| graph.node[i].display_name().to_string())
+            .collect();
+        return Err(OnnxError::Cycle(stuck));
+    }
+    Ok(order)
+}
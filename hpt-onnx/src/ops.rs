@@ -0,0 +1,966 @@
+//! mapping of the ONNX operators to the hpt tensor operations
+//!
+//! Operators hpt implements on `DynTensor` run on the input dtype. Operators hpt only implements
+//! on typed tensors (conv, pooling, normalization, ...) run in `f32` for `f32`, `f16` and `bf16`
+//! inputs and in `f64` otherwise, the result is cast back to the dtype ONNX expects.
+
+use hpt::{
+    AdvancedOps, Conv, Dtype, DynTensor, FloatOutPooling, FloatReduce, FloatUnaryOps, HardMax,
+    Matmul, NormalBinOps, NormalPooling, NormalReduce, NormalUaryOps, PadMode, QTensor,
+    QuantParams, ShapeManipulate, Shrinkage, Tensor, TensorCreator, TensorInfo, TensorLike,
+    TensorWhere,
+};
+
+use crate::{
+    error::OnnxError,
+    proto::{AttributeProto, NodeProto},
+};
+
+/// operators of the `ai.onnx` domain the executor implements
+pub(crate) const SUPPORTED_OPS: &[&str] = &[
+    "Abs",
+    "Acos",
+    "Acosh",
+    "Add",
+    "Asin",
+    "Asinh",
+    "Atan",
+    "Atanh",
+    "AveragePool",
+    "BatchNormalization",
+    "Cast",
+    "Ceil",
+    "Celu",
+    "Clip",
+    "Concat",
+    "Constant",
+    "Conv",
+    "Cos",
+    "Cosh",
+    "DequantizeLinear",
+    "Div",
+    "Dropout",
+    "Elu",
+    "Erf",
+    "Exp",
+    "Flatten",
+    "Floor",
+    "Gelu",
+    "Gemm",
+    "GlobalAveragePool",
+    "GlobalMaxPool",
+    "Hardmax",
+    "HardSigmoid",
+    "HardSwish",
+    "Identity",
+    "LayerNormalization",
+    "LeakyRelu",
+    "Log",
+    "LogSoftmax",
+    "MatMul",
+    "MaxPool",
+    "Mish",
+    "Mul",
+    "Neg",
+    "OneHot",
+    "Pad",
+    "Pow",
+    "QuantizeLinear",
+    "Reciprocal",
+    "ReduceL1",
+    "ReduceL2",
+    "ReduceLogSumExp",
+    "ReduceMax",
+    "ReduceMean",
+    "ReduceMin",
+    "ReduceProd",
+    "ReduceSum",
+    "ReduceSumSquare",
+    "Relu",
+    "Reshape",
+    "Round",
+    "ScatterElements",
+    "Selu",
+    "Shape",
+    "Shrink",
+    "Sigmoid",
+    "Sign",
+    "Sin",
+    "Sinh",
+    "Softmax",
+    "Softplus",
+    "Softsign",
+    "Split",
+    "Sqrt",
+    "Squeeze",
+    "Sub",
+    "Tan",
+    "Tanh",
+    "TopK",
+    "Transpose",
+    "Unsqueeze",
+    "Where",
+];
+
+/// true if the executor implements `op_type` of `domain`
+pub(crate) fn is_supported(domain: &str, op_type: &str) -> bool {
+    (domain.is_empty() || domain == "ai.onnx") && SUPPORTED_OPS.contains(&op_type)
+}
+
+/// run `$body` on the typed tensor of `$x`, `$T` is `f32` for the 32 bits and smaller floats and
+/// `f64` otherwise, the result is cast back to `$dtype`
+macro_rules! typed {
+    ($x:expr, $dtype:expr, |$t:ident: $T:ident| $body:expr) => {{
+        let x: &DynTensor = $x;
+        let dtype: Dtype = $dtype;
+        let out: DynTensor = if matches!(x.dtype(), Dtype::F32 | Dtype::F16 | Dtype::BF16) {
+            #[allow(dead_code)]
+            type $T = f32;
+            let $t = x.astype(Dtype::F32)?.downcast::<f32>()?;
+            DynTensor::from($body)
+        } else {
+            #[allow(dead_code)]
+            type $T = f64;
+            let $t = x.astype(Dtype::F64)?.downcast::<f64>()?;
+            DynTensor::from($body)
+        };
+        out.astype(dtype)?
+    }};
+}
+
+/// a node being executed, with its inputs
+struct Ctx<'a> {
+    node: &'a NodeProto,
+    inputs: Vec<Option<DynTensor>>,
+    opset: i64,
+}
+
+impl Ctx<'_> {
+    fn invalid(&self, message: impl Into<String>) -> OnnxError {
+        OnnxError::InvalidNode {
+            node: self.node.display_name().to_string(),
+            op_type: self.node.op_type.clone(),
+            message: message.into(),
+        }
+    }
+
+    /// the optional input `idx`
+    fn opt(&self, idx: usize) -> Option<&DynTensor> {
+        self.inputs.get(idx).and_then(|x| x.as_ref())
+    }
+
+    /// the required input `idx`
+    fn input(&self, idx: usize) -> Result<&DynTensor, OnnxError> {
+        self.opt(idx)
+            .ok_or_else(|| self.invalid(format!("input {} is required", idx)))
+    }
+
+    fn attr(&self, name: &str) -> Option<&AttributeProto> {
+        self.node.attr(name)
+    }
+
+    fn int(&self, name: &str, default: i64) -> i64 {
+        self.attr(name).map_or(default, |a| a.i)
+    }
+
+    fn float(&self, name: &str, default: f32) -> f32 {
+        self.attr(name).map_or(default, |a| a.f)
+    }
+
+    fn ints(&self, name: &str) -> Option<Vec<i64>> {
+        self.attr(name).map(|a| a.ints.clone())
+    }
+
+    fn string(&self, name: &str, default: &str) -> String {
+        self.attr(name).map_or(default.to_string(), |a| {
+            String::from_utf8_lossy(&a.s).into_owned()
+        })
+    }
+
+    /// axes given by the input `idx` (newer opsets) or the attribute `axes`
+    fn axes(&self, idx: usize) -> Result<Option<Vec<i64>>, OnnxError> {
+        match self.opt(idx) {
+            Some(axes) => Ok(Some(to_ints(axes)?)),
+            None => Ok(self.ints("axes")),
+        }
+    }
+
+    /// normalize a possibly negative axis of a tensor of rank `ndim`
+    fn axis(&self, axis: i64, ndim: usize) -> Result<i64, OnnxError> {
+        let ndim = ndim as i64;
+        let normalized = if axis < 0 { axis + ndim } else { axis };
+        if normalized < 0 || normalized >= ndim {
+            return Err(self.invalid(format!("axis {} is out of range for rank {}", axis, ndim)));
+        }
+        Ok(normalized)
+    }
+}
+
+/// the elements of `x` in row major order, a scalar is already contiguous
+fn elements<T: hpt::CommonBounds>(x: Tensor<T>) -> Result<Vec<T>, OnnxError> {
+    if x.ndim() == 0 {
+        return Ok(x.as_raw().to_vec());
+    }
+    Ok(x.contiguous()?.as_raw().to_vec())
+}
+
+/// the elements of an integer tensor
+fn to_ints(x: &DynTensor) -> Result<Vec<i64>, OnnxError> {
+    elements(x.astype(Dtype::I64)?.downcast::<i64>()?)
+}
+
+/// the elements of a tensor as `f64`
+fn to_floats(x: &DynTensor) -> Result<Vec<f64>, OnnxError> {
+    elements(x.astype(Dtype::F64)?.downcast::<f64>()?)
+}
+
+/// a 1-D tensor of `values`
+fn from_slice<T>(values: &[T]) -> Result<DynTensor, OnnxError>
+where
+    T: hpt::CommonBounds,
+    DynTensor: From<Tensor<T>>,
+{
+    let mut tensor = Tensor::<T>::empty(&[values.len() as i64])?;
+    tensor.as_raw_mut().copy_from_slice(values);
+    Ok(tensor.into())
+}
+
+/// a scalar of `dtype`, broadcastable to any shape
+fn scalar(value: f64, dtype: Dtype) -> Result<DynTensor, OnnxError> {
+    Ok(DynTensor::from(Tensor::<f64>::new(&[value])).astype(dtype)?)
+}
+
+fn is_float(dtype: Dtype) -> bool {
+    matches!(dtype, Dtype::BF16 | Dtype::F16 | Dtype::F32 | Dtype::F64)
+}
+
+/// the output shape of `Reshape`, `0` copies the input dimension unless `allowzero` is set
+fn reshape_shape(ctx: &Ctx, input: &[i64], target: &[i64]) -> Result<Vec<i64>, OnnxError> {
+    let allow_zero = ctx.int("allowzero", 0) != 0;
+    let mut shape = target
+        .iter()
+        .enumerate()
+        .map(|(i, &dim)| {
+            if dim == 0 && !allow_zero {
+                input
+                    .get(i)
+                    .copied()
+                    .ok_or_else(|| ctx.invalid(format!("dimension {} has nothing to copy", i)))
+            } else {
+                Ok(dim)
+            }
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    let known = shape.iter().filter(|&&d| d != -1).product::<i64>();
+    if let Some(pos) = shape.iter().position(|&d| d == -1) {
+        if known == 0 {
+            return Err(ctx.invalid("can't infer the -1 dimension of an empty shape"));
+        }
+        shape[pos] = input.iter().product::<i64>() / known;
+    }
+    Ok(shape)
+}
+
+/// onnx `MatMul`, 1-D operands follow the numpy rules
+fn matmul(a: &DynTensor, b: &DynTensor) -> Result<DynTensor, OnnxError> {
+    let (a_vec, b_vec) = (a.ndim() == 1, b.ndim() == 1);
+    let a = if a_vec { a.unsqueeze(0)? } else { a.clone() };
+    let b = if b_vec { b.unsqueeze(1)? } else { b.clone() };
+    let mut out = a.matmul(&b)?;
+    if b_vec {
+        out = out.squeeze(-1)?;
+    }
+    if a_vec {
+        out = out.squeeze(if b_vec { -1 } else { -2 })?;
+    }
+    Ok(out)
+}
+
+/// the 2-D spatial attributes of `Conv` and the pooling operators
+struct Window {
+    kernel: [i64; 2],
+    steps: [i64; 2],
+    padding: [(i64, i64); 2],
+    dilation: [i64; 2],
+}
+
+impl Window {
+    fn new(ctx: &Ctx, kernel: Option<Vec<i64>>) -> Result<Window, OnnxError> {
+        let pair = |name: &str, default: i64| -> Result<[i64; 2], OnnxError> {
+            match ctx.ints(name) {
+                None => Ok([default; 2]),
+                Some(v) if v.len() == 2 => Ok([v[0], v[1]]),
+                Some(v) => Err(ctx.invalid(format!("{} must have 2 values, got {:?}", name, v))),
+            }
+        };
+        let kernel = match kernel {
+            Some(k) if k.len() == 2 => [k[0], k[1]],
+            _ => return Err(ctx.invalid("only 2-D kernels are supported")),
+        };
+        let auto_pad = ctx.string("auto_pad", "NOTSET");
+        let padding = match auto_pad.as_str() {
+            "NOTSET" => match ctx.ints("pads") {
+                None => [(0, 0); 2],
+                Some(p) if p.len() == 4 => [(p[0], p[2]), (p[1], p[3])],
+                Some(p) => return Err(ctx.invalid(format!("pads must have 4 values, got {:?}", p))),
+            },
+            "VALID" => [(0, 0); 2],
+            other => return Err(ctx.invalid(format!("auto_pad {} is not supported", other))),
+        };
+        Ok(Window {
+            kernel,
+            steps: pair("strides", 1)?,
+            padding,
+            dilation: pair("dilations", 1)?,
+        })
+    }
+}
+
+/// `NCHW` to the `NHWC` layout hpt's conv and pooling kernels use
+fn to_nhwc<T: hpt::CommonBounds>(ctx: &Ctx, x: Tensor<T>) -> Result<Tensor<T>, OnnxError> {
+    if x.ndim() != 4 {
+        return Err(ctx.invalid(format!("expected a 4-D NCHW input, got rank {}", x.ndim())));
+    }
+    Ok(x.permute(&[0, 2, 3, 1])?.contiguous()?)
+}
+
+/// `NHWC` back to `NCHW`
+fn to_nchw<T: hpt::CommonBounds>(x: Tensor<T>) -> Result<Tensor<T>, OnnxError> {
+    Ok(x.permute(&[0, 3, 1, 2])?.contiguous()?)
+}
+
+fn conv(ctx: &Ctx) -> Result<DynTensor, OnnxError> {
+    let (x, w) = (ctx.input(0)?, ctx.input(1)?);
+    let kernel = ctx
+        .ints("kernel_shape")
+        .or_else(|| Some(w.shape()[2..].to_vec()));
+    let window = Window::new(ctx, kernel)?;
+    let groups = ctx.int("group", 1);
+    let dtype = x.dtype();
+    Ok(typed!(x, dtype, |x: T| {
+        let x = to_nhwc(ctx, x)?;
+        // [M, C/group, kH, kW] -> [kH, kW, C/group, M]
+        let w = w
+            .astype(x_dtype::<T>())?
+            .downcast::<T>()?
+            .permute(&[2, 3, 1, 0])?
+            .contiguous()?;
+        let b = match ctx.opt(2) {
+            Some(b) => Some(b.astype(x_dtype::<T>())?.downcast::<T>()?),
+            None => None,
+        };
+        let out = if groups == 1 {
+            x.conv2d(
+                &w,
+                b.as_ref(),
+                window.steps,
+                window.padding,
+                window.dilation,
+                None,
+            )?
+        } else {
+            x.conv2d_group(
+                &w,
+                b.as_ref(),
+                window.steps,
+                window.padding,
+                window.dilation,
+                groups,
+                None,
+            )?
+        };
+        to_nchw(out)?
+    }))
+}
+
+/// the dtype of the typed path `T`
+fn x_dtype<T: hpt::TypeNum>() -> Dtype {
+    T::type_num()
+}
+
+fn pool(ctx: &Ctx, max: bool) -> Result<DynTensor, OnnxError> {
+    let x = ctx.input(0)?;
+    let window = Window::new(ctx, ctx.ints("kernel_shape"))?;
+    if ctx.int("ceil_mode", 0) != 0 {
+        return Err(ctx.invalid("ceil_mode is not supported"));
+    }
+    let padded = window.padding.iter().any(|&(b, e)| b != 0 || e != 0);
+    if !max && padded && ctx.int("count_include_pad", 0) == 0 {
+        return Err(ctx.invalid("padding is only supported with count_include_pad=1"));
+    }
+    let dtype = x.dtype();
+    Ok(typed!(x, dtype, |x: T| {
+        let x = to_nhwc(ctx, x)?;
+        let out = if max {
+            x.maxpool2d(
+                &window.kernel,
+                window.steps,
+                window.padding,
+                window.dilation,
+            )?
+        } else {
+            x.avgpool2d(
+                &window.kernel,
+                window.steps,
+                window.padding,
+                window.dilation,
+            )?
+        };
+        to_nchw(out)?
+    }))
+}
+
+fn global_pool(ctx: &Ctx, max: bool) -> Result<DynTensor, OnnxError> {
+    let x = ctx.input(0)?;
+    let dtype = x.dtype();
+    Ok(typed!(x, dtype, |x: T| {
+        let x = to_nhwc(ctx, x)?;
+        let out = if max {
+            x.adaptive_maxpool2d([1, 1])?
+        } else {
+            x.adaptive_avgpool2d([1, 1])?
+        };
+        to_nchw(out)?
+    }))
+}
+
+/// `Softmax`, `LogSoftmax` and `Hardmax`, before opset 13 the input is coerced to 2-D at `axis`
+fn softmax_like(ctx: &Ctx, kind: u8) -> Result<DynTensor, OnnxError> {
+    let x = ctx.input(0)?;
+    let legacy = ctx.opset < 13;
+    let axis = ctx.axis(ctx.int("axis", if legacy { 1 } else { -1 }), x.ndim())?;
+    let shape = x.shape().to_vec();
+    let (input, axis) = if legacy {
+        let outer = shape[..axis as usize].iter().product::<i64>();
+        (x.reshape(&[outer, -1])?, 1)
+    } else {
+        (x.clone(), axis)
+    };
+    let out = typed!(&input, x.dtype(), |t: T| match kind {
+        0 => t.softmax(axis)?,
+        1 => t.log_softmax(axis)?,
+        _ => t.hardmax(axis)?,
+    });
+    Ok(out.reshape(&shape)?)
+}
+
+fn reduce(ctx: &Ctx, op: &str) -> Result<DynTensor, OnnxError> {
+    let x = ctx.input(0)?;
+    let keepdims = ctx.int("keepdims", 1) != 0;
+    let axes = match ctx.axes(1)? {
+        Some(axes) if !axes.is_empty() => axes,
+        _ if ctx.int("noop_with_empty_axes", 0) != 0 => return Ok(x.clone()),
+        _ => (0..x.ndim() as i64).collect(),
+    };
+    let axes = axes
+        .iter()
+        .map(|&a| ctx.axis(a, x.ndim()))
+        .collect::<Result<Vec<_>, _>>()?;
+    let axes = axes.as_slice();
+    Ok(match op {
+        "ReduceSum" => x.sum(axes, keepdims)?,
+        "ReduceMean" => x.mean(axes, keepdims)?,
+        "ReduceMax" => x.max(axes, keepdims)?,
+        "ReduceMin" => x.min(axes, keepdims)?,
+        "ReduceProd" => x.prod(axes, keepdims)?,
+        "ReduceL1" => x.reducel1(axes, keepdims)?,
+        "ReduceL2" => x.reducel2(axes, keepdims)?,
+        "ReduceSumSquare" => x.sum_square(axes, keepdims)?,
+        _ => x.logsumexp(axes, keepdims)?,
+    })
+}
+
+/// `Gemm`: `alpha * A' * B' + beta * C`
+fn gemm(ctx: &Ctx) -> Result<DynTensor, OnnxError> {
+    let mut a = ctx.input(0)?.clone();
+    let mut b = ctx.input(1)?.clone();
+    if ctx.int("transA", 0) != 0 {
+        a = a.t()?;
+    }
+    if ctx.int("transB", 0) != 0 {
+        b = b.t()?;
+    }
+    let (alpha, beta) = (
+        ctx.float("alpha", 1.0) as f64,
+        ctx.float("beta", 1.0) as f64,
+    );
+    let mut out = a.matmul(&b)?;
+    if alpha != 1.0 {
+        out = out.mul(&scalar(alpha, out.dtype())?)?;
+    }
+    if let Some(c) = ctx.opt(2) {
+        let c = if beta != 1.0 {
+            c.mul(&scalar(beta, c.dtype())?)?
+        } else {
+            c.clone()
+        };
+        out = out.add(&c)?;
+    }
+    Ok(out)
+}
+
+/// `BatchNormalization` in inference mode
+fn batch_norm(ctx: &Ctx) -> Result<DynTensor, OnnxError> {
+    if ctx.int("training_mode", 0) != 0 {
+        return Err(ctx.invalid("training_mode is not supported"));
+    }
+    let x = ctx.input(0)?;
+    if x.ndim() < 2 {
+        return Err(ctx.invalid("the input must have a channel dimension"));
+    }
+    let mut shape = vec![1i64; x.ndim() - 1];
+    shape[0] = -1;
+    let param = |idx: usize| -> Result<DynTensor, OnnxError> {
+        Ok(ctx.input(idx)?.astype(x.dtype())?.reshape(&shape)?)
+    };
+    let (scale, bias, mean, var) = (param(1)?, param(2)?, param(3)?, param(4)?);
+    let eps = scalar(ctx.float("epsilon", 1e-5) as f64, x.dtype())?;
+    let std = var.add(&eps)?.sqrt()?;
+    Ok(x.sub(&mean)?.div(&std)?.mul(&scale)?.add(&bias)?)
+}
+
+fn layer_norm(ctx: &Ctx) -> Result<DynTensor, OnnxError> {
+    let x = ctx.input(0)?;
+    let axis = ctx.axis(ctx.int("axis", -1), x.ndim())? as usize;
+    let normalized = x.shape()[axis..].to_vec();
+    let eps = ctx.float("epsilon", 1e-5) as f64;
+    let dtype = x.dtype();
+    Ok(typed!(x, dtype, |x: T| {
+        let param = |idx: usize| -> Result<Option<Tensor<T>>, OnnxError> {
+            match ctx.opt(idx) {
+                Some(p) => Ok(Some(p.astype(x_dtype::<T>())?.downcast::<T>()?)),
+                None => Ok(None),
+            }
+        };
+        let (gamma, beta) = (param(1)?, param(2)?);
+        x.layernorm(&normalized, gamma.as_ref(), beta.as_ref(), eps as T)?
+    }))
+}
+
+fn clip(ctx: &Ctx) -> Result<DynTensor, OnnxError> {
+    let x = ctx.input(0)?;
+    let bound = |idx: usize, attr: &str, default: f64| -> Result<f64, OnnxError> {
+        match ctx.opt(idx) {
+            Some(v) => Ok(to_floats(v)?.first().copied().unwrap_or(default)),
+            None => Ok(ctx.attr(attr).map_or(default, |a| a.f as f64)),
+        }
+    };
+    let min = bound(1, "min", f64::NEG_INFINITY)?;
+    let max = bound(2, "max", f64::INFINITY)?;
+    Ok(x.clamp(min, max)?)
+}
+
+fn pad(ctx: &Ctx) -> Result<DynTensor, OnnxError> {
+    let x = ctx.input(0)?;
+    let mode = ctx.string("mode", "constant");
+    if !matches!(mode.as_str(), "constant" | "reflect" | "edge" | "wrap") {
+        return Err(ctx.invalid(format!("pad mode {} is not supported", mode)));
+    }
+    let pads = match ctx.opt(1) {
+        Some(p) => to_ints(p)?,
+        None => ctx.ints("pads").unwrap_or_default(),
+    };
+    let value = match ctx.opt(2) {
+        Some(v) => to_floats(v)?.first().copied().unwrap_or(0.0),
+        None => ctx.float("value", 0.0) as f64,
+    };
+    let ndim = x.ndim();
+    let axes = match ctx.opt(3) {
+        Some(axes) => to_ints(axes)?
+            .iter()
+            .map(|&a| ctx.axis(a, ndim))
+            .collect::<Result<Vec<_>, _>>()?,
+        None => (0..ndim as i64).collect(),
+    };
+    if pads.len() != axes.len() * 2 {
+        return Err(ctx.invalid(format!(
+            "expected {} pads, got {}",
+            axes.len() * 2,
+            pads.len()
+        )));
+    }
+    let mut pairs = vec![(0, 0); ndim];
+    for (i, &axis) in axes.iter().enumerate() {
+        pairs[axis as usize] = (pads[i], pads[i + axes.len()]);
+    }
+    let dtype = x.dtype();
+    Ok(typed!(x, dtype, |x: T| {
+        let mode = match mode.as_str() {
+            "reflect" => PadMode::Reflect,
+            "edge" => PadMode::Edge,
+            "wrap" => PadMode::Circular,
+            _ => PadMode::Constant(value as T),
+        };
+        x.pad_with_mode(&pairs, mode)?
+    }))
+}
+
+fn topk(ctx: &Ctx) -> Result<Vec<DynTensor>, OnnxError> {
+    let x = ctx.input(0)?;
+    let k = match ctx.opt(1) {
+        Some(k) => to_ints(k)?.first().copied().unwrap_or(0),
+        None => ctx.int("k", 0),
+    };
+    let axis = ctx.axis(ctx.int("axis", -1), x.ndim())?;
+    let largest = ctx.int("largest", 1) != 0;
+    let sorted = ctx.int("sorted", 1) != 0;
+    let indices;
+    let values = typed!(x, x.dtype(), |x: T| {
+        let (i, v) = x.topk(k, axis, largest, sorted)?;
+        indices = DynTensor::from(i);
+        v
+    });
+    Ok(vec![values, indices])
+}
+
+fn one_hot(ctx: &Ctx) -> Result<DynTensor, OnnxError> {
+    let indices = ctx.input(0)?;
+    let depth = to_floats(ctx.input(1)?)?.first().copied().unwrap_or(0.0) as i64;
+    let values = ctx.input(2)?;
+    let [off, on] = to_floats(values)?[..] else {
+        return Err(ctx.invalid("values must have 2 elements"));
+    };
+    let axis = ctx.int("axis", -1);
+    // negative indices count from the end of the depth
+    let depth_t = scalar(depth as f64, indices.dtype())?;
+    let zero = scalar(0.0, indices.dtype())?;
+    let indices = indices.add(&depth_t)?.rem(&depth_t)?.add(&zero)?;
+    Ok(typed!(&indices, values.dtype(), |x: T| x.onehot(
+        depth as usize,
+        axis,
+        on as T,
+        off as T
+    )?))
+}
+
+fn scatter_elements(ctx: &Ctx) -> Result<DynTensor, OnnxError> {
+    let (data, indices, updates) = (ctx.input(0)?, ctx.input(1)?, ctx.input(2)?);
+    let reduction = ctx.string("reduction", "none");
+    if reduction != "none" {
+        return Err(ctx.invalid(format!("reduction {} is not supported", reduction)));
+    }
+    let axis = ctx.axis(ctx.int("axis", 0), data.ndim())?;
+    let indices = indices.astype(Dtype::I64)?.downcast::<i64>()?;
+    let dtype = data.dtype();
+    Ok(typed!(data, dtype, |x: T| {
+        let updates = updates.astype(x_dtype::<T>())?.downcast::<T>()?;
+        x.scatter(&indices, axis, &updates)?
+    }))
+}
+
+fn where_op(ctx: &Ctx) -> Result<DynTensor, OnnxError> {
+    let cond = ctx.input(0)?.astype(Dtype::Bool)?.downcast::<bool>()?;
+    let (x, y) = (ctx.input(1)?, ctx.input(2)?);
+    let dtype = x.dtype().promote(y.dtype());
+    Ok(typed!(x, dtype, |x: T| {
+        let y = y.astype(x_dtype::<T>())?.downcast::<T>()?;
+        Tensor::<T>::tensor_where(&cond, &x, &y)?
+    }))
+}
+
+fn split(ctx: &Ctx) -> Result<Vec<DynTensor>, OnnxError> {
+    let x = ctx.input(0)?;
+    let axis = ctx.axis(ctx.int("axis", 0), x.ndim())?;
+    let dim = x.shape()[axis as usize];
+    let sizes = match ctx.opt(1) {
+        Some(s) => to_ints(s)?,
+        None => match ctx.ints("split") {
+            Some(s) => s,
+            None => {
+                let n = ctx.int("num_outputs", ctx.node.output.len() as i64);
+                let chunk = (dim + n - 1) / n;
+                (0..n).map(|i| chunk.min(dim - chunk * i)).collect()
+            }
+        },
+    };
+    if sizes.iter().sum::<i64>() != dim {
+        return Err(ctx.invalid(format!("split sizes {:?} don't add up to {}", sizes, dim)));
+    }
+    let indices = sizes
+        .iter()
+        .scan(0, |acc, &s| {
+            *acc += s;
+            Some(*acc)
+        })
+        .take(sizes.len() - 1)
+        .collect::<Vec<_>>();
+    Ok(x.split(&indices, axis)?)
+}
+
+fn constant(ctx: &Ctx) -> Result<DynTensor, OnnxError> {
+    let attr = ctx
+        .node
+        .attribute
+        .first()
+        .ok_or_else(|| ctx.invalid("Constant has no value"))?;
+    Ok(match attr.name.as_str() {
+        "value" => attr
+            .t
+            .as_ref()
+            .ok_or_else(|| ctx.invalid("value is not a tensor"))?
+            .to_tensor()?,
+        "value_float" => Tensor::<f32>::new(attr.f).into(),
+        "value_floats" => from_slice(&attr.floats)?,
+        "value_int" => Tensor::<i64>::new(attr.i).into(),
+        "value_ints" => from_slice(&attr.ints)?,
+        other => return Err(ctx.invalid(format!("constant {} is not supported", other))),
+    })
+}
+
+/// the parameters of `QuantizeLinear` and `DequantizeLinear`, a single scale quantizes the whole tensor and a 1-D
+/// scale quantizes every index of `axis`
+fn quant_params(ctx: &Ctx, ndim: usize) -> Result<QuantParams, OnnxError> {
+    let scales = to_floats(ctx.input(1)?)?
+        .iter()
+        .map(|&s| s as f32)
+        .collect::<Vec<_>>();
+    let zero_points = match ctx.opt(2) {
+        Some(zp) => to_ints(zp)?.iter().map(|&z| z as i32).collect(),
+        None => vec![0; scales.len()],
+    };
+    if scales.len() == 1 && zero_points.len() == 1 {
+        return Ok(QuantParams::per_tensor(scales[0], zero_points[0]));
+    }
+    let axis = ctx.axis(ctx.int("axis", 1), ndim)?;
+    Ok(QuantParams::per_channel(scales, zero_points, axis as usize))
+}
+
+fn quantize_linear(ctx: &Ctx) -> Result<DynTensor, OnnxError> {
+    let x = ctx.input(0)?.astype(Dtype::F32)?.downcast::<f32>()?;
+    let params = quant_params(ctx, x.ndim())?;
+    // the zero point decides the output type, uint8 without one
+    Ok(match ctx.opt(2).map_or(Dtype::U8, |zp| zp.dtype()) {
+        Dtype::U8 => x.quantize::<u8>(params)?.int_repr().clone().into(),
+        Dtype::I8 => x.quantize::<i8>(params)?.int_repr().clone().into(),
+        other => return Err(ctx.invalid(format!("can't quantize to {}", other))),
+    })
+}
+
+fn dequantize_linear(ctx: &Ctx) -> Result<DynTensor, OnnxError> {
+    let x = ctx.input(0)?;
+    let params = quant_params(ctx, x.ndim())?;
+    let out = match x.dtype() {
+        Dtype::U8 => QTensor::new(x.downcast::<u8>()?, params)?.dequantize()?,
+        Dtype::I8 => QTensor::new(x.downcast::<i8>()?, params)?.dequantize()?,
+        other => return Err(ctx.invalid(format!("can't dequantize {}", other))),
+    };
+    Ok(DynTensor::from(out).astype(ctx.input(1)?.dtype())?)
+}
+
+/// the outputs of `node`, `inputs` are in the order of `node.input` and `None` for the missing
+/// optional inputs
+pub(crate) fn run(
+    node: &NodeProto,
+    inputs: Vec<Option<DynTensor>>,
+    opset: i64,
+) -> Result<Vec<DynTensor>, OnnxError> {
+    let ctx = Ctx {
+        node,
+        inputs,
+        opset,
+    };
+    let ctx = &ctx;
+    let x = || ctx.input(0);
+    let y = || ctx.input(1);
+    let out = match node.op_type.as_str() {
+        "Add" => x()?.add(y()?)?,
+        "Sub" => x()?.sub(y()?)?,
+        "Mul" => x()?.mul(y()?)?,
+        "Div" => {
+            let (a, b) = (x()?, y()?);
+            let dtype = a.dtype().promote(b.dtype());
+            if is_float(dtype) {
+                a.div(b)?
+            } else {
+                // integer division truncates, which is what the cast back does
+                let (a, b) = (a.astype(Dtype::F64)?, b.astype(Dtype::F64)?);
+                a.div(&b)?.astype(dtype)?
+            }
+        }
+        "Pow" => {
+            let a = x()?;
+            a.pow(&y()?.astype(a.dtype())?)?.astype(a.dtype())?
+        }
+        "Neg" => NormalUaryOps::neg(x()?)?,
+        "Abs" => x()?.abs()?,
+        "Floor" => x()?.floor()?,
+        "Ceil" => x()?.ceil()?,
+        "Round" => x()?.round()?,
+        "Sign" => x()?.sign()?,
+        "Relu" => x()?.relu()?,
+        "Sqrt" => x()?.sqrt()?,
+        "Exp" => x()?.exp()?,
+        "Log" => x()?.ln()?,
+        "Sin" => x()?.sin()?,
+        "Cos" => x()?.cos()?,
+        "Tan" => x()?.tan()?,
+        "Asin" => x()?.asin()?,
+        "Acos" => x()?.acos()?,
+        "Atan" => x()?.atan()?,
+        "Sinh" => x()?.sinh()?,
+        "Cosh" => x()?.cosh()?,
+        "Asinh" => x()?.asinh()?,
+        "Acosh" => x()?.acosh()?,
+        "Atanh" => x()?.atanh()?,
+        "Tanh" => x()?.tanh()?,
+        "Sigmoid" => x()?.sigmoid()?,
+        "Erf" => x()?.erf()?,
+        "Reciprocal" => x()?.recip()?,
+        "Softplus" => x()?.softplus()?,
+        "Softsign" => x()?.softsign()?,
+        "Mish" => x()?.mish()?,
+        "HardSwish" => x()?.hard_swish()?,
+        "Gelu" => match ctx.string("approximate", "none").as_str() {
+            "none" => x()?.gelu()?,
+            other => return Err(ctx.invalid(format!("approximate {} is not supported", other))),
+        },
+        "Elu" => x()?.elu(ctx.float("alpha", 1.0) as f64)?,
+        "Celu" => x()?.celu(ctx.float("alpha", 1.0) as f64)?,
+        "Selu" => x()?.selu(
+            ctx.float("alpha", 1.673_263_2) as f64,
+            ctx.float("gamma", 1.050_701) as f64,
+        )?,
+        "LeakyRelu" => x()?.leaky_relu(ctx.float("alpha", 0.01) as f64)?,
+        "HardSigmoid" => {
+            let x = x()?;
+            let alpha = scalar(ctx.float("alpha", 0.2) as f64, x.dtype())?;
+            let beta = scalar(ctx.float("beta", 0.5) as f64, x.dtype())?;
+            x.mul(&alpha)?.add(&beta)?.clamp(0.0, 1.0)?
+        }
+        "Clip" => clip(ctx)?,
+        "Softmax" => softmax_like(ctx, 0)?,
+        "LogSoftmax" => softmax_like(ctx, 1)?,
+        "Hardmax" => softmax_like(ctx, 2)?,
+        "Shrink" => {
+            let x = x()?;
+            let bias = ctx.float("bias", 0.0) as f64;
+            let lambd = ctx.float("lambd", 0.5) as f64;
+            typed!(x, x.dtype(), |t: T| t.shrinkage(bias as T, lambd as T)?)
+        }
+        "MatMul" => matmul(x()?, y()?)?,
+        "Gemm" => gemm(ctx)?,
+        "Conv" => conv(ctx)?,
+        "MaxPool" => pool(ctx, true)?,
+        "AveragePool" => pool(ctx, false)?,
+        "GlobalMaxPool" => global_pool(ctx, true)?,
+        "GlobalAveragePool" => global_pool(ctx, false)?,
+        "BatchNormalization" => batch_norm(ctx)?,
+        "LayerNormalization" => layer_norm(ctx)?,
+        "ReduceSum" | "ReduceMean" | "ReduceMax" | "ReduceMin" | "ReduceProd" | "ReduceL1"
+        | "ReduceL2" | "ReduceSumSquare" | "ReduceLogSumExp" => reduce(ctx, &node.op_type)?,
+        "Reshape" => {
+            let x = x()?;
+            let target = match ctx.opt(1) {
+                Some(shape) => to_ints(shape)?,
+                None => ctx.ints("shape").unwrap_or_default(),
+            };
+            x.reshape(reshape_shape(ctx, x.shape(), &target)?)?
+        }
+        "Flatten" => {
+            let x = x()?;
+            // unlike the other ops, the axis of Flatten can be the rank itself
+            let (axis, ndim) = (ctx.int("axis", 1), x.ndim() as i64);
+            if axis < -ndim || axis > ndim {
+                return Err(ctx.invalid(format!(
+                    "axis {} is out of range [-{}, {}]",
+                    axis, ndim, ndim
+                )));
+            }
+            let axis = if axis < 0 { axis + ndim } else { axis };
+            let (outer, inner) = x.shape().split_at(axis as usize);
+            let shape = [outer.iter().product::<i64>(), inner.iter().product::<i64>()];
+            x.reshape(&shape)?
+        }
+        "Transpose" => {
+            let x = x()?;
+            let perm = ctx
+                .ints("perm")
+                .unwrap_or_else(|| (0..x.ndim() as i64).rev().collect());
+            x.permute(perm.as_slice())?
+        }
+        "Squeeze" => {
+            let x = x()?;
+            let shape = x.shape().to_vec();
+            let axes = match ctx.axes(1)? {
+                Some(axes) => axes
+                    .iter()
+                    .map(|&a| ctx.axis(a, x.ndim()))
+                    .collect::<Result<Vec<_>, _>>()?,
+                None => (0..x.ndim() as i64)
+                    .filter(|&i| shape[i as usize] == 1)
+                    .collect(),
+            };
+            let shape = shape
+                .iter()
+                .enumerate()
+                .filter(|(i, _)| !axes.contains(&(*i as i64)))
+                .map(|(_, &d)| d)
+                .collect::<Vec<_>>();
+            x.reshape(shape)?
+        }
+        "Unsqueeze" => {
+            let x = x()?;
+            let axes = ctx
+                .axes(1)?
+                .ok_or_else(|| ctx.invalid("axes are required"))?;
+            let rank = x.ndim() + axes.len();
+            let axes = axes
+                .iter()
+                .map(|&a| ctx.axis(a, rank))
+                .collect::<Result<Vec<_>, _>>()?;
+            let mut dims = x.shape().iter();
+            let shape = (0..rank as i64)
+                .map(|i| {
+                    if axes.contains(&i) {
+                        1
+                    } else {
+                        *dims.next().unwrap_or(&1)
+                    }
+                })
+                .collect::<Vec<_>>();
+            x.reshape(shape)?
+        }
+        "Concat" => {
+            let first = x()?;
+            let axis = ctx.axis(ctx.int("axis", 0), first.ndim())?;
+            let tensors = ctx.inputs.iter().flatten().cloned().collect::<Vec<_>>();
+            DynTensor::concat(tensors, axis as usize, false)?
+        }
+        "Split" => return split(ctx),
+        "TopK" => return topk(ctx),
+        "Identity" => x()?.clone(),
+        "Dropout" => {
+            let x = x()?.clone();
+            if node.output.len() > 1 && !node.output[1].is_empty() {
+                let mask = DynTensor::ones(x.shape(), Dtype::Bool)?;
+                return Ok(vec![x, mask]);
+            }
+            x
+        }
+        "Cast" => {
+            let to = ctx.int("to", 0) as i32;
+            let dtype = crate::onnx_dtype(to)
+                .ok_or_else(|| ctx.invalid(format!("can't cast to {}", to)))?;
+            x()?.astype(dtype)?
+        }
+        "Constant" => constant(ctx)?,
+        "Shape" => {
+            let shape = x()?.shape().to_vec();
+            let rank = shape.len() as i64;
+            let clamp = |v: i64| (if v < 0 { v + rank } else { v }).clamp(0, rank) as usize;
+            let start = clamp(ctx.int("start", 0));
+            let end = clamp(ctx.int("end", rank));
+            from_slice(&shape[start..end.max(start)])?
+        }
+        "Where" => where_op(ctx)?,
+        "Pad" => pad(ctx)?,
+        "OneHot" => one_hot(ctx)?,
+        "ScatterElements" => scatter_elements(ctx)?,
+        "QuantizeLinear" => quantize_linear(ctx)?,
+        "DequantizeLinear" => dequantize_linear(ctx)?,
+        other => {
+            return Err(ctx.invalid(format!("operator {} is not supported", other)));
+        }
+    };
+    Ok(vec![out])
+}
@@ -0,0 +1,494 @@
+//! the subset of `onnx.proto` read by the importer, decoded straight from the protobuf wire format
+
+use crate::error::OnnxError;
+
+const VARINT: u8 = 0;
+const FIXED64: u8 = 1;
+const LEN: u8 = 2;
+const FIXED32: u8 = 5;
+
+/// reads the fields of a protobuf message
+pub(crate) struct Reader<'a> {
+    buf: &'a [u8],
+    pos: usize,
+    /// offset of `buf` in the file, only used by the error messages
+    base: usize,
+}
+
+impl<'a> Reader<'a> {
+    pub(crate) fn new(buf: &'a [u8]) -> Self {
+        Reader {
+            buf,
+            pos: 0,
+            base: 0,
+        }
+    }
+
+    fn error(&self, message: impl Into<String>) -> OnnxError {
+        OnnxError::Decode {
+            offset: self.base + self.pos,
+            message: message.into(),
+        }
+    }
+
+    fn varint(&mut self) -> Result<u64, OnnxError> {
+        let mut value = 0u64;
+        for shift in (0..64).step_by(7) {
+            let byte = *self
+                .buf
+                .get(self.pos)
+                .ok_or_else(|| self.error("truncated varint"))?;
+            self.pos += 1;
+            value |= ((byte & 0x7f) as u64) << shift;
+            if byte & 0x80 == 0 {
+                return Ok(value);
+            }
+        }
+        Err(self.error("varint longer than 10 bytes"))
+    }
+
+    fn take(&mut self, len: usize) -> Result<&'a [u8], OnnxError> {
+        if self.buf.len() - self.pos < len {
+            return Err(self.error(format!("field of {} bytes is truncated", len)));
+        }
+        let bytes = &self.buf[self.pos..self.pos + len];
+        self.pos += len;
+        Ok(bytes)
+    }
+
+    /// the next field number and wire type, `None` at the end of the message
+    fn key(&mut self) -> Result<Option<(u32, u8)>, OnnxError> {
+        if self.pos == self.buf.len() {
+            return Ok(None);
+        }
+        let key = self.varint()?;
+        Ok(Some(((key >> 3) as u32, (key & 7) as u8)))
+    }
+
+    fn bytes(&mut self) -> Result<&'a [u8], OnnxError> {
+        let len = self.varint()? as usize;
+        self.take(len)
+    }
+
+    fn skip(&mut self, wire: u8) -> Result<(), OnnxError> {
+        match wire {
+            VARINT => self.varint().map(|_| ()),
+            FIXED64 => self.take(8).map(|_| ()),
+            LEN => self.bytes().map(|_| ()),
+            FIXED32 => self.take(4).map(|_| ()),
+            _ => Err(self.error(format!("unsupported wire type {}", wire))),
+        }
+    }
+
+    fn expect(&self, wire: u8, expected: u8) -> Result<(), OnnxError> {
+        if wire != expected {
+            return Err(self.error(format!("wire type {} where {} is expected", wire, expected)));
+        }
+        Ok(())
+    }
+
+    fn int(&mut self, wire: u8) -> Result<i64, OnnxError> {
+        self.expect(wire, VARINT)?;
+        Ok(self.varint()? as i64)
+    }
+
+    fn f32(&mut self, wire: u8) -> Result<f32, OnnxError> {
+        self.expect(wire, FIXED32)?;
+        Ok(f32::from_le_bytes(self.take(4)?.try_into().unwrap()))
+    }
+
+    fn string(&mut self, wire: u8) -> Result<String, OnnxError> {
+        self.expect(wire, LEN)?;
+        let bytes = self.bytes()?;
+        String::from_utf8(bytes.to_vec()).map_err(|_| self.error("string is not utf-8"))
+    }
+
+    fn message<M: Message>(&mut self, wire: u8) -> Result<M, OnnxError> {
+        self.expect(wire, LEN)?;
+        let len = self.varint()? as usize;
+        let base = self.base + self.pos;
+        let mut reader = Reader {
+            buf: self.take(len)?,
+            pos: 0,
+            base,
+        };
+        M::decode_from(&mut reader)
+    }
+
+    /// a repeated scalar field, packed or not
+    fn repeated<T>(
+        &mut self,
+        wire: u8,
+        scalar: u8,
+        out: &mut Vec<T>,
+        mut read: impl FnMut(&mut Self) -> Result<T, OnnxError>,
+    ) -> Result<(), OnnxError> {
+        if wire == LEN {
+            let len = self.varint()? as usize;
+            let end = self.pos + len;
+            if end > self.buf.len() {
+                return Err(self.error("packed field is truncated"));
+            }
+            while self.pos < end {
+                out.push(read(self)?);
+            }
+            Ok(())
+        } else {
+            self.expect(wire, scalar)?;
+            out.push(read(self)?);
+            Ok(())
+        }
+    }
+
+    fn ints(&mut self, wire: u8, out: &mut Vec<i64>) -> Result<(), OnnxError> {
+        self.repeated(wire, VARINT, out, |r| Ok(r.varint()? as i64))
+    }
+
+    fn floats(&mut self, wire: u8, out: &mut Vec<f32>) -> Result<(), OnnxError> {
+        self.repeated(wire, FIXED32, out, |r| {
+            Ok(f32::from_le_bytes(r.take(4)?.try_into().unwrap()))
+        })
+    }
+
+    fn doubles(&mut self, wire: u8, out: &mut Vec<f64>) -> Result<(), OnnxError> {
+        self.repeated(wire, FIXED64, out, |r| {
+            Ok(f64::from_le_bytes(r.take(8)?.try_into().unwrap()))
+        })
+    }
+}
+
+/// a protobuf message, unknown fields are skipped
+pub(crate) trait Message: Default {
+    /// reads the field `field` of wire type `wire`
+    fn field(&mut self, field: u32, wire: u8, r: &mut Reader) -> Result<bool, OnnxError>;
+
+    fn decode_from(r: &mut Reader) -> Result<Self, OnnxError> {
+        let mut msg = Self::default();
+        while let Some((field, wire)) = r.key()? {
+            if !msg.field(field, wire, r)? {
+                r.skip(wire)?;
+            }
+        }
+        Ok(msg)
+    }
+}
+
+/// `ModelProto`
+#[derive(Debug, Clone, Default)]
+pub struct ModelProto {
+    /// version of the IR
+    pub ir_version: i64,
+    /// operator sets the model depends on
+    pub opset_import: Vec<OperatorSetId>,
+    /// name of the tool that generated the model
+    pub producer_name: String,
+    /// the graph of the model
+    pub graph: GraphProto,
+}
+
+impl ModelProto {
+    /// decode a serialized `ModelProto`
+    pub fn decode(buf: &[u8]) -> Result<Self, OnnxError> {
+        Self::decode_from(&mut Reader::new(buf))
+    }
+
+    /// version of the default (`ai.onnx`) operator set
+    pub fn opset_version(&self) -> Option<i64> {
+        self.opset_import
+            .iter()
+            .find(|op| op.domain.is_empty() || op.domain == "ai.onnx")
+            .map(|op| op.version)
+    }
+}
+
+impl Message for ModelProto {
+    fn field(&mut self, field: u32, wire: u8, r: &mut Reader) -> Result<bool, OnnxError> {
+        match field {
+            1 => self.ir_version = r.int(wire)?,
+            2 => self.producer_name = r.string(wire)?,
+            7 => self.graph = r.message(wire)?,
+            8 => self.opset_import.push(r.message(wire)?),
+            _ => return Ok(false),
+        }
+        Ok(true)
+    }
+}
+
+/// `OperatorSetIdProto`
+#[derive(Debug, Clone, Default)]
+pub struct OperatorSetId {
+    /// domain of the operator set, empty for `ai.onnx`
+    pub domain: String,
+    /// version of the operator set
+    pub version: i64,
+}
+
+impl Message for OperatorSetId {
+    fn field(&mut self, field: u32, wire: u8, r: &mut Reader) -> Result<bool, OnnxError> {
+        match field {
+            1 => self.domain = r.string(wire)?,
+            2 => self.version = r.int(wire)?,
+            _ => return Ok(false),
+        }
+        Ok(true)
+    }
+}
+
+/// `GraphProto`
+#[derive(Debug, Clone, Default)]
+pub struct GraphProto {
+    /// name of the graph
+    pub name: String,
+    /// nodes of the graph
+    pub node: Vec<NodeProto>,
+    /// constant tensors, usually the weights
+    pub initializer: Vec<TensorProto>,
+    /// inputs of the graph, may also list the initializers
+    pub input: Vec<ValueInfoProto>,
+    /// outputs of the graph
+    pub output: Vec<ValueInfoProto>,
+}
+
+impl Message for GraphProto {
+    fn field(&mut self, field: u32, wire: u8, r: &mut Reader) -> Result<bool, OnnxError> {
+        match field {
+            1 => self.node.push(r.message(wire)?),
+            2 => self.name = r.string(wire)?,
+            5 => self.initializer.push(r.message(wire)?),
+            11 => self.input.push(r.message(wire)?),
+            12 => self.output.push(r.message(wire)?),
+            _ => return Ok(false),
+        }
+        Ok(true)
+    }
+}
+
+/// `NodeProto`
+#[derive(Debug, Clone, Default)]
+pub struct NodeProto {
+    /// names of the inputs, an empty name is a missing optional input
+    pub input: Vec<String>,
+    /// names of the outputs
+    pub output: Vec<String>,
+    /// name of the node
+    pub name: String,
+    /// name of the operator
+    pub op_type: String,
+    /// domain of the operator, empty for `ai.onnx`
+    pub domain: String,
+    /// attributes of the node
+    pub attribute: Vec<AttributeProto>,
+}
+
+impl Message for NodeProto {
+    fn field(&mut self, field: u32, wire: u8, r: &mut Reader) -> Result<bool, OnnxError> {
+        match field {
+            1 => self.input.push(r.string(wire)?),
+            2 => self.output.push(r.string(wire)?),
+            3 => self.name = r.string(wire)?,
+            4 => self.op_type = r.string(wire)?,
+            5 => self.attribute.push(r.message(wire)?),
+            7 => self.domain = r.string(wire)?,
+            _ => return Ok(false),
+        }
+        Ok(true)
+    }
+}
+
+impl NodeProto {
+    /// the attribute `name`
+    pub fn attr(&self, name: &str) -> Option<&AttributeProto> {
+        self.attribute.iter().find(|attr| attr.name == name)
+    }
+
+    /// a name for the error messages, the first output if the node has no name
+    pub(crate) fn display_name(&self) -> &str {
+        if self.name.is_empty() {
+            self.output.first().map(|s| s.as_str()).unwrap_or("")
+        } else {
+            &self.name
+        }
+    }
+}
+
+/// `AttributeProto`, graph attributes are not decoded
+#[derive(Debug, Clone, Default)]
+pub struct AttributeProto {
+    /// name of the attribute
+    pub name: String,
+    /// `AttributeType` of the attribute
+    pub r#type: i32,
+    /// float value
+    pub f: f32,
+    /// int value
+    pub i: i64,
+    /// string value
+    pub s: Vec<u8>,
+    /// tensor value
+    pub t: Option<TensorProto>,
+    /// list of floats
+    pub floats: Vec<f32>,
+    /// list of ints
+    pub ints: Vec<i64>,
+    /// list of strings
+    pub strings: Vec<Vec<u8>>,
+}
+
+impl Message for AttributeProto {
+    fn field(&mut self, field: u32, wire: u8, r: &mut Reader) -> Result<bool, OnnxError> {
+        match field {
+            1 => self.name = r.string(wire)?,
+            2 => self.f = r.f32(wire)?,
+            3 => self.i = r.int(wire)?,
+            4 => {
+                r.expect(wire, LEN)?;
+                self.s = r.bytes()?.to_vec();
+            }
+            5 => self.t = Some(r.message(wire)?),
+            7 => r.floats(wire, &mut self.floats)?,
+            8 => r.ints(wire, &mut self.ints)?,
+            9 => {
+                r.expect(wire, LEN)?;
+                self.strings.push(r.bytes()?.to_vec());
+            }
+            20 => self.r#type = r.int(wire)? as i32,
+            _ => return Ok(false),
+        }
+        Ok(true)
+    }
+}
+
+/// `TensorProto`
+#[derive(Debug, Clone, Default)]
+pub struct TensorProto {
+    /// shape of the tensor
+    pub dims: Vec<i64>,
+    /// `DataType` of the tensor
+    pub data_type: i32,
+    /// name of the tensor
+    pub name: String,
+    /// data of `FLOAT` tensors
+    pub float_data: Vec<f32>,
+    /// data of the integer types of at most 32 bits, `BOOL`, `FLOAT16` and `BFLOAT16` tensors
+    pub int32_data: Vec<i64>,
+    /// data of `INT64` tensors
+    pub int64_data: Vec<i64>,
+    /// data of `DOUBLE` tensors
+    pub double_data: Vec<f64>,
+    /// data of `UINT32` and `UINT64` tensors
+    pub uint64_data: Vec<i64>,
+    /// little endian data of any type
+    pub raw_data: Vec<u8>,
+    /// `DataLocation` of the data, `1` when it is stored in an external file
+    pub data_location: i32,
+}
+
+impl Message for TensorProto {
+    fn field(&mut self, field: u32, wire: u8, r: &mut Reader) -> Result<bool, OnnxError> {
+        match field {
+            1 => r.ints(wire, &mut self.dims)?,
+            2 => self.data_type = r.int(wire)? as i32,
+            4 => r.floats(wire, &mut self.float_data)?,
+            5 => r.ints(wire, &mut self.int32_data)?,
+            7 => r.ints(wire, &mut self.int64_data)?,
+            8 => self.name = r.string(wire)?,
+            9 => {
+                r.expect(wire, LEN)?;
+                self.raw_data = r.bytes()?.to_vec();
+            }
+            10 => r.doubles(wire, &mut self.double_data)?,
+            11 => r.ints(wire, &mut self.uint64_data)?,
+            14 => self.data_location = r.int(wire)? as i32,
+            _ => return Ok(false),
+        }
+        Ok(true)
+    }
+}
+
+/// `ValueInfoProto`, only the name and the tensor type are decoded
+#[derive(Debug, Clone, Default)]
+pub struct ValueInfoProto {
+    /// name of the value
+    pub name: String,
+    /// `DataType` of the tensor, `0` if unknown
+    pub elem_type: i32,
+    /// shape of the tensor, `None` for a symbolic dimension, empty if unknown
+    pub shape: Vec<Option<i64>>,
+}
+
+impl Message for ValueInfoProto {
+    fn field(&mut self, field: u32, wire: u8, r: &mut Reader) -> Result<bool, OnnxError> {
+        match field {
+            1 => self.name = r.string(wire)?,
+            2 => {
+                let ty: TypeProto = r.message(wire)?;
+                self.elem_type = ty.tensor.elem_type;
+                self.shape = ty.tensor.shape.dim.into_iter().map(|d| d.value).collect();
+            }
+            _ => return Ok(false),
+        }
+        Ok(true)
+    }
+}
+
+#[derive(Default)]
+struct TypeProto {
+    tensor: TensorTypeProto,
+}
+
+impl Message for TypeProto {
+    fn field(&mut self, field: u32, wire: u8, r: &mut Reader) -> Result<bool, OnnxError> {
+        match field {
+            1 => self.tensor = r.message(wire)?,
+            _ => return Ok(false),
+        }
+        Ok(true)
+    }
+}
+
+#[derive(Default)]
+struct TensorTypeProto {
+    elem_type: i32,
+    shape: TensorShapeProto,
+}
+
+impl Message for TensorTypeProto {
+    fn field(&mut self, field: u32, wire: u8, r: &mut Reader) -> Result<bool, OnnxError> {
+        match field {
+            1 => self.elem_type = r.int(wire)? as i32,
+            2 => self.shape = r.message(wire)?,
+            _ => return Ok(false),
+        }
+        Ok(true)
+    }
+}
+
+#[derive(Default)]
+struct TensorShapeProto {
+    dim: Vec<Dimension>,
+}
+
+impl Message for TensorShapeProto {
+    fn field(&mut self, field: u32, wire: u8, r: &mut Reader) -> Result<bool, OnnxError> {
+        match field {
+            1 => self.dim.push(r.message(wire)?),
+            _ => return Ok(false),
+        }
+        Ok(true)
+    }
+}
+
+#[derive(Default)]
+struct Dimension {
+    value: Option<i64>,
+}
+
+impl Message for Dimension {
+    fn field(&mut self, field: u32, wire: u8, r: &mut Reader) -> Result<bool, OnnxError> {
+        match field {
+            1 => self.value = Some(r.int(wire)?),
+            _ => return Ok(false),
+        }
+        Ok(true)
+    }
+}
@@ -0,0 +1,150 @@
+use half::{bf16, f16};
+use hpt::{Dtype, DynTensor, ShapeManipulate, Tensor, TensorCreator, TensorLike};
+
+use crate::{error::OnnxError, proto::TensorProto};
+
+/// `TensorProto.DataType` values
+pub(crate) mod data_type {
+    pub const FLOAT: i32 = 1;
+    pub const UINT8: i32 = 2;
+    pub const INT8: i32 = 3;
+    pub const UINT16: i32 = 4;
+    pub const INT16: i32 = 5;
+    pub const INT32: i32 = 6;
+    pub const INT64: i32 = 7;
+    pub const BOOL: i32 = 9;
+    pub const FLOAT16: i32 = 10;
+    pub const DOUBLE: i32 = 11;
+    pub const UINT32: i32 = 12;
+    pub const UINT64: i32 = 13;
+    pub const BFLOAT16: i32 = 16;
+}
+
+/// the hpt dtype of an ONNX data type, `None` for the types hpt doesn't have (strings, complex, fp8, ...)
+pub fn onnx_dtype(data_type: i32) -> Option<Dtype> {
+    use data_type::*;
+    Some(match data_type {
+        FLOAT => Dtype::F32,
+        UINT8 => Dtype::U8,
+        INT8 => Dtype::I8,
+        UINT16 => Dtype::U16,
+        INT16 => Dtype::I16,
+        INT32 => Dtype::I32,
+        INT64 => Dtype::I64,
+        BOOL => Dtype::Bool,
+        FLOAT16 => Dtype::F16,
+        DOUBLE => Dtype::F64,
+        UINT32 => Dtype::U32,
+        UINT64 => Dtype::U64,
+        BFLOAT16 => Dtype::BF16,
+        _ => return None,
+    })
+}
+
+/// build a tensor of `shape` from its elements
+fn typed<T>(values: Vec<T>, shape: &[i64]) -> Result<DynTensor, OnnxError>
+where
+    T: hpt::CommonBounds,
+    DynTensor: From<Tensor<T>>,
+{
+    let mut tensor = Tensor::<T>::empty(&[values.len() as i64])?;
+    tensor.as_raw_mut().copy_from_slice(&values);
+    Ok(tensor.reshape(shape)?.into())
+}
+
+/// decode little endian elements of `N` bytes
+fn le<T, const N: usize>(raw: &[u8], f: fn([u8; N]) -> T) -> Vec<T> {
+    raw.chunks_exact(N)
+        .map(|chunk| f(chunk.try_into().unwrap()))
+        .collect()
+}
+
+impl TensorProto {
+    /// convert the tensor to a `DynTensor`, the data is copied
+    pub fn to_tensor(&self) -> Result<DynTensor, OnnxError> {
+        let unsupported = || OnnxError::UnsupportedDtype {
+            data_type: self.data_type,
+            name: self.name.clone(),
+        };
+        let dtype = onnx_dtype(self.data_type).ok_or_else(unsupported)?;
+        if self.data_location == 1 {
+            return Err(OnnxError::Decode {
+                offset: 0,
+                message: format!("tensor {} stores its data in an external file", self.name),
+            });
+        }
+        let shape = &self.dims;
+        let numel = shape.iter().product::<i64>() as usize;
+        let raw = &self.raw_data;
+        let tensor = if !raw.is_empty() || numel == 0 {
+            if raw.len() != numel * dtype.size() {
+                return Err(OnnxError::Decode {
+                    offset: 0,
+                    message: format!(
+                        "tensor {} has {} bytes of raw data, {} are expected",
+                        self.name,
+                        raw.len(),
+                        numel * dtype.size()
+                    ),
+                });
+            }
+            match dtype {
+                Dtype::Bool => typed(raw.iter().map(|&b| b != 0).collect(), shape)?,
+                Dtype::I8 => typed(le(raw, i8::from_le_bytes), shape)?,
+                Dtype::U8 => typed(raw.to_vec(), shape)?,
+                Dtype::I16 => typed(le(raw, i16::from_le_bytes), shape)?,
+                Dtype::U16 => typed(le(raw, u16::from_le_bytes), shape)?,
+                Dtype::I32 => typed(le(raw, i32::from_le_bytes), shape)?,
+                Dtype::U32 => typed(le(raw, u32::from_le_bytes), shape)?,
+                Dtype::I64 => typed(le(raw, i64::from_le_bytes), shape)?,
+                Dtype::U64 => typed(le(raw, u64::from_le_bytes), shape)?,
+                Dtype::BF16 => typed(le(raw, bf16::from_le_bytes), shape)?,
+                Dtype::F16 => typed(le(raw, f16::from_le_bytes), shape)?,
+                Dtype::F32 => typed(le(raw, f32::from_le_bytes), shape)?,
+                Dtype::F64 => typed(le(raw, f64::from_le_bytes), shape)?,
+                Dtype::C32 | Dtype::C64 => return Err(unsupported()),
+            }
+        } else {
+            let int32 = &self.int32_data;
+            let len = match dtype {
+                Dtype::F32 => self.float_data.len(),
+                Dtype::F64 => self.double_data.len(),
+                Dtype::I64 => self.int64_data.len(),
+                Dtype::U32 | Dtype::U64 => self.uint64_data.len(),
+                _ => int32.len(),
+            };
+            if len != numel {
+                return Err(OnnxError::Decode {
+                    offset: 0,
+                    message: format!(
+                        "tensor {} has {} elements, {} are expected",
+                        self.name, len, numel
+                    ),
+                });
+            }
+            match dtype {
+                Dtype::Bool => typed(int32.iter().map(|&x| x != 0).collect(), shape)?,
+                Dtype::I8 => typed(int32.iter().map(|&x| x as i8).collect(), shape)?,
+                Dtype::U8 => typed(int32.iter().map(|&x| x as u8).collect(), shape)?,
+                Dtype::I16 => typed(int32.iter().map(|&x| x as i16).collect(), shape)?,
+                Dtype::U16 => typed(int32.iter().map(|&x| x as u16).collect(), shape)?,
+                Dtype::I32 => typed(int32.iter().map(|&x| x as i32).collect(), shape)?,
+                Dtype::BF16 => typed(
+                    int32.iter().map(|&x| bf16::from_bits(x as u16)).collect(),
+                    shape,
+                )?,
+                Dtype::F16 => typed(
+                    int32.iter().map(|&x| f16::from_bits(x as u16)).collect(),
+                    shape,
+                )?,
+                Dtype::U32 => typed(self.uint64_data.iter().map(|&x| x as u32).collect(), shape)?,
+                Dtype::U64 => typed(self.uint64_data.iter().map(|&x| x as u64).collect(), shape)?,
+                Dtype::I64 => typed(self.int64_data.clone(), shape)?,
+                Dtype::F32 => typed(self.float_data.clone(), shape)?,
+                Dtype::F64 => typed(self.double_data.clone(), shape)?,
+                Dtype::C32 | Dtype::C64 => return Err(unsupported()),
+            }
+        };
+        Ok(tensor)
+    }
+}
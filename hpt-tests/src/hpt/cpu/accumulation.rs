@@ -0,0 +1,163 @@
+#![allow(unused_imports)]
+use half::f16;
+use hpt::{
+    Accumulation, FloatReduce, NormalReduce, Random, ShapeManipulate, Tensor, TensorCreator,
+    TensorInfo, TensorLike,
+};
+
+#[test]
+fn test_accumulation_sum_f32() -> anyhow::Result<()> {
+    let data = (0..1_000_003)
+        .map(|i| 0.1f32 + (i % 1000) as f32 * 1e-4)
+        .collect::<Vec<_>>();
+    let expected = data.iter().map(|x| *x as f64).sum::<f64>();
+    let a = Tensor::<f32>::new(data);
+    for accumulation in [
+        Accumulation::Widened,
+        Accumulation::Kahan,
+        Accumulation::Pairwise,
+    ] {
+        let sum = a.sum_with(0, false, accumulation)?;
+        let err = (sum.as_raw()[0] as f64 - expected).abs();
+        assert!(
+            err <= expected * f32::EPSILON as f64,
+            "{:?}: {}",
+            accumulation,
+            err
+        );
+    }
+    let native = a.sum_with(0, false, Accumulation::Native)?;
+    assert_eq!(native.as_raw(), a.sum(0, false)?.as_raw());
+    Ok(())
+}
+
+#[test]
+fn test_accumulation_sum_f16() -> anyhow::Result<()> {
+    let data = vec![f16::from_f32(0.1); 100_000];
+    let expected = data.iter().map(|x| x.to_f64()).sum::<f64>();
+    let a = Tensor::<f16>::new(data).reshape(&[10, 10_000])?;
+    for accumulation in [
+        Accumulation::Widened,
+        Accumulation::Kahan,
+        Accumulation::Pairwise,
+    ] {
+        let sum = a.sum_with([0, 1], false, accumulation)?;
+        let err = (sum.as_raw()[0].to_f64() - expected).abs();
+        assert!(err <= expected * 1e-3, "{:?}: {}", accumulation, err);
+    }
+    Ok(())
+}
+
+#[test]
+fn test_accumulation_float_reduce() -> anyhow::Result<()> {
+    let a = Tensor::<f32>::randn(&[37, 1025])?;
+    let b = Tensor::<f64>::new(a.as_raw().iter().map(|x| *x as f64).collect::<Vec<_>>())
+        .reshape(&[37, 1025])?;
+    let check = |res: Tensor<f32>, expected: Tensor<f64>| {
+        res.as_raw()
+            .iter()
+            .zip(expected.as_raw())
+            .all(|(x, y)| ((*x as f64) - y).abs() <= y.abs() * 1e-5 + 1e-7)
+    };
+    let logsumexp = Tensor::<f64>::new(
+        b.as_raw()
+            .chunks(1025)
+            .map(|row| row.iter().map(|x| x.exp()).sum::<f64>().ln())
+            .collect::<Vec<_>>(),
+    );
+    for accumulation in [
+        Accumulation::Widened,
+        Accumulation::Kahan,
+        Accumulation::Pairwise,
+    ] {
+        let t = a.t()?;
+        assert!(check(
+            a.mean_with(1, false, accumulation)?,
+            b.mean(1, false)?
+        ));
+        assert!(check(
+            a.reducel2_with(1, true, accumulation)?,
+            b.reducel2(1, true)?
+        ));
+        assert!(check(
+            a.logsumexp_with(1, false, accumulation)?,
+            logsumexp.clone()
+        ));
+        assert!(check(
+            t.mean_with(0, false, accumulation)?,
+            b.mean(1, false)?
+        ));
+    }
+    Ok(())
+}
+
+#[test]
+fn test_accumulation_integer() -> anyhow::Result<()> {
+    let a = Tensor::<i32>::arange(0, 1000)?.reshape(&[10, 100])?;
+    let expected = a.sum(1, false)?;
+    for accumulation in [
+        Accumulation::Widened,
+        Accumulation::Kahan,
+        Accumulation::Pairwise,
+    ] {
+        assert_eq!(
+            a.sum_with(1, false, accumulation)?.as_raw(),
+            expected.as_raw()
+        );
+    }
+    let mean = a.mean_with(1, false, Accumulation::Widened)?;
+    assert!(mean.allclose(&a.mean(1, false)?));
+    Ok(())
+}
+
+#[test]
+fn test_accumulation_axes() -> anyhow::Result<()> {
+    let a = Tensor::<f32>::randn(&[7, 33, 19])?;
+    let b = Tensor::<f64>::new(a.as_raw().iter().map(|x| *x as f64).collect::<Vec<_>>())
+        .reshape(&[7, 33, 19])?;
+    let views = [
+        (a.clone(), b.clone()),
+        (a.permute([2, 0, 1])?, b.permute([2, 0, 1])?),
+        (a.permute([1, 2, 0])?, b.permute([1, 2, 0])?),
+    ];
+    let axes: [&[i64]; 7] = [&[0], &[1], &[2], &[0, 1], &[0, 2], &[1, 2], &[0, 1, 2]];
+    for (x, y) in views.iter() {
+        for axes in axes.iter() {
+            for keep_dims in [false, true] {
+                let expected = y.sum(*axes, false)?;
+                for accumulation in [
+                    Accumulation::Widened,
+                    Accumulation::Kahan,
+                    Accumulation::Pairwise,
+                ] {
+                    let res = x.sum_with(*axes, keep_dims, accumulation)?;
+                    let mut shape = x.shape().to_vec();
+                    for &axis in axes.iter().rev() {
+                        if keep_dims {
+                            shape[axis as usize] = 1;
+                        } else {
+                            shape.remove(axis as usize);
+                        }
+                    }
+                    if shape.is_empty() {
+                        shape.push(1);
+                    }
+                    assert_eq!(res.shape().to_vec(), shape);
+                    let res = res.contiguous()?;
+                    let expected = expected.contiguous()?;
+                    assert!(
+                        res.as_raw()
+                            .iter()
+                            .zip(expected.as_raw())
+                            .all(|(r, e)| ((*r as f64) - e).abs() <= 1e-4),
+                        "{:?} {:?} {:?}",
+                        x.shape(),
+                        axes,
+                        accumulation
+                    );
+                }
+            }
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,145 @@
+#![allow(unused_imports)]
+use hpt::{
+    ShapeManipulate, StridedIterator, Tensor, TensorCreator, TensorInfo, TensorIterator, TensorLike,
+};
+use rayon::iter::{IndexedParallelIterator, ParallelIterator};
+
+fn values(a: &Tensor<i64>) -> Vec<i64> {
+    a.contiguous().unwrap().as_raw().to_vec()
+}
+
+#[test]
+fn test_axis_iter() -> anyhow::Result<()> {
+    let a = Tensor::<i64>::arange(0, 24)?.reshape(&[2, 3, 4])?;
+    let raw = a.as_raw().to_vec();
+
+    let outer = a.outer_iter()?.collect::<Vec<_>>();
+    assert_eq!(outer.len(), 2);
+    for (i, view) in outer.iter().enumerate() {
+        assert_eq!(view.shape().to_vec(), vec![3, 4]);
+        assert_eq!(values(view), raw[i * 12..(i + 1) * 12].to_vec());
+    }
+
+    let iter = a.axis_iter(-2)?;
+    assert_eq!(iter.len(), 3);
+    let views = iter.rev().collect::<Vec<_>>();
+    for (j, view) in views.iter().rev().enumerate() {
+        assert_eq!(view.shape().to_vec(), vec![2, 4]);
+        let expected = (0..2)
+            .flat_map(|i| (0..4).map(move |k| (i * 12 + j * 4 + k) as i64))
+            .collect::<Vec<_>>();
+        assert_eq!(values(view), expected);
+    }
+
+    let par = a.par_axis_iter(2)?.map(|v| values(&v)).collect::<Vec<_>>();
+    let serial = a.axis_iter(2)?.map(|v| values(&v)).collect::<Vec<_>>();
+    assert_eq!(par, serial);
+    assert_eq!(serial[3], vec![3, 7, 11, 15, 19, 23]);
+
+    assert!(a.axis_iter(3).is_err());
+    assert!(Tensor::<i64>::new(1).outer_iter().is_err());
+    Ok(())
+}
+
+#[test]
+fn test_lanes() -> anyhow::Result<()> {
+    let a = Tensor::<i64>::arange(0, 24)?.reshape(&[2, 3, 4])?;
+    let lanes = a.lanes(1)?.collect::<Vec<_>>();
+    assert_eq!(lanes.len(), 8);
+    for (idx, lane) in lanes.iter().enumerate() {
+        let (i, k) = (idx / 4, idx % 4);
+        assert_eq!(lane.shape().to_vec(), vec![3]);
+        assert_eq!(
+            values(lane),
+            (0..3)
+                .map(|j| (i * 12 + j * 4 + k) as i64)
+                .collect::<Vec<_>>()
+        );
+    }
+
+    // lanes of a non contiguous tensor follow its logical layout
+    let t = a.permute(&[2, 0, 1])?;
+    let sums = t
+        .lanes(-1)?
+        .map(|l| values(&l).iter().sum())
+        .collect::<Vec<i64>>();
+    let par_sums = t
+        .par_lanes(-1)?
+        .map(|l| values(&l).iter().sum())
+        .collect::<Vec<i64>>();
+    assert_eq!(sums, par_sums);
+    // t[k, i, :] = a[i, :, k]
+    assert_eq!(sums[0], 0 + 4 + 8);
+    assert_eq!(sums[1], 12 + 16 + 20);
+    assert_eq!(sums[2], 1 + 5 + 9);
+
+    let v = Tensor::<i64>::arange(0, 5)?;
+    let lanes = v.lanes(0)?.collect::<Vec<_>>();
+    assert_eq!(lanes.len(), 1);
+    assert_eq!(values(&lanes[0]), vec![0, 1, 2, 3, 4]);
+    Ok(())
+}
+
+#[test]
+fn test_axis_chunks() -> anyhow::Result<()> {
+    let a = Tensor::<i64>::arange(0, 14)?.reshape(&[7, 2])?;
+    let chunks = a.axis_chunks(0, 3)?.collect::<Vec<_>>();
+    assert_eq!(chunks.len(), 3);
+    assert_eq!(chunks[0].shape().to_vec(), vec![3, 2]);
+    assert_eq!(chunks[2].shape().to_vec(), vec![1, 2]);
+    assert_eq!(values(&chunks[1]), vec![6, 7, 8, 9, 10, 11]);
+    assert_eq!(values(&chunks[2]), vec![12, 13]);
+
+    let last = a.axis_chunks(0, 3)?.next_back().unwrap();
+    assert_eq!(values(&last), vec![12, 13]);
+    let par = a
+        .par_axis_chunks(0, 3)?
+        .map(|c| c.shape()[0])
+        .collect::<Vec<_>>();
+    assert_eq!(par, vec![3, 3, 1]);
+
+    let columns = a.axis_chunks(1, 4)?.collect::<Vec<_>>();
+    assert_eq!(columns.len(), 1);
+    assert_eq!(values(&columns[0]), a.as_raw().to_vec());
+
+    assert!(a.axis_chunks(0, 0).is_err());
+    assert!(a.axis_chunks(2, 1).is_err());
+    Ok(())
+}
+
+#[test]
+fn test_axis_iter_mut() -> anyhow::Result<()> {
+    let mut a = Tensor::<i64>::zeros(&[3, 4])?;
+    for (i, mut row) in a.outer_iter_mut()?.enumerate() {
+        row.iter_mut().for_each(|x| *x = i as i64);
+    }
+    assert_eq!(values(&a), vec![0, 0, 0, 0, 1, 1, 1, 1, 2, 2, 2, 2]);
+
+    a.par_lanes_mut(0)?.enumerate().for_each(|(j, mut column)| {
+        column.iter_mut().for_each(|x| *x += 10 * j as i64);
+    });
+    assert_eq!(
+        values(&a),
+        vec![0, 10, 20, 30, 1, 11, 21, 31, 2, 12, 22, 32]
+    );
+
+    for mut chunk in a.axis_chunks_mut(1, 3)? {
+        chunk.iter_mut().for_each(|x| *x = -*x);
+    }
+    assert_eq!(
+        values(&a),
+        vec![0, -10, -20, -30, -1, -11, -21, -31, -2, -12, -22, -32]
+    );
+
+    a.par_axis_iter_mut(1)?.for_each(|mut column| {
+        column.iter_mut().for_each(|x| *x = x.abs());
+    });
+    a.par_outer_iter_mut()?.for_each(|mut row| {
+        row.iter_mut().for_each(|x| *x += 100);
+    });
+    assert_eq!(
+        values(&a),
+        vec![100, 110, 120, 130, 101, 111, 121, 131, 102, 112, 122, 132]
+    );
+    Ok(())
+}
@@ -398,3 +398,21 @@ fn test_binary_out_invalid_empty() -> anyhow::Result<()> {
         .contains("Size mismatch: expected 100, got 10"));
     Ok(())
 }
+
+#[test]
+fn test_binary_out_uncontiguous() -> anyhow::Result<()> {
+    let a = Tensor::<f64>::arange(0, 100)?.reshape([10, 10])?;
+    let b = Tensor::<f64>::arange(100, 200)?.reshape([10, 10])?;
+    let expected = a.t()?.contiguous()?.add(&b.t()?.contiguous()?);
+    let mut out = Tensor::<f64>::zeros(&[10, 10])?;
+    let res = a.t()?.add_(&b.t()?, &mut out)?;
+    assert_eq!(out.as_raw(), expected.as_raw());
+    assert_eq!(res.as_raw(), expected.as_raw());
+
+    // the output can alias the uncontiguous input
+    let mut c = a.contiguous()?;
+    let c_t = c.t()?;
+    c_t.add_(&b.t()?, &mut c)?;
+    assert_eq!(c.as_raw(), expected.as_raw());
+    Ok(())
+}
@@ -0,0 +1,167 @@
+#![allow(unused_imports)]
+use hpt::{
+    CumulativeOps, ExecutionContext, FloatUnaryOps, Matmul, NormalReduce, Random, ShapeManipulate,
+    Tensor, TensorCreator, TensorInfo, TensorLike,
+};
+
+#[test]
+fn test_context_num_threads() -> anyhow::Result<()> {
+    let ctx = ExecutionContext::builder().num_threads(3).build()?;
+    assert!(ExecutionContext::current().is_none());
+    ctx.install(|| {
+        assert_eq!(hpt::get_num_threads(), 3);
+        assert_eq!(rayon::current_num_threads(), 3);
+        assert_eq!(ExecutionContext::current().unwrap().num_threads(), 3);
+    });
+    assert!(ExecutionContext::current().is_none());
+    Ok(())
+}
+
+#[test]
+fn test_context_nested() -> anyhow::Result<()> {
+    let outer = ExecutionContext::builder().num_threads(2).build()?;
+    let inner = ExecutionContext::builder()
+        .num_threads(1)
+        .deterministic(true)
+        .build()?;
+    outer.install(|| {
+        inner.install(|| {
+            let ctx = ExecutionContext::current().unwrap();
+            assert_eq!(ctx.num_threads(), 1);
+            assert!(ctx.deterministic());
+        });
+        let ctx = ExecutionContext::current().unwrap();
+        assert_eq!(ctx.num_threads(), 2);
+        assert!(!ctx.deterministic());
+    });
+    Ok(())
+}
+
+#[test]
+fn test_context_workers() -> anyhow::Result<()> {
+    use rayon::iter::{IntoParallelIterator, ParallelIterator};
+    let ctx = ExecutionContext::builder().num_threads(4).build()?;
+    let counts = ctx.install(|| {
+        (0..64)
+            .into_par_iter()
+            .map(|_| ExecutionContext::current().map(|x| x.num_threads()))
+            .collect::<Vec<_>>()
+    });
+    assert!(counts.iter().all(|x| *x == Some(4)));
+    Ok(())
+}
+
+#[test]
+fn test_context_kernels() -> anyhow::Result<()> {
+    let a = Tensor::<f64>::randn(&[37, 129])?;
+    let b = Tensor::<f64>::randn(&[129, 23])?;
+    let expected_sum = a.sum(1, false)?;
+    let expected_mm = a.matmul(&b)?;
+    let expected_sin = a.sin()?;
+    let expected_cumsum = a.cumsum(1)?;
+    let expected_t = a.t()?.contiguous()?;
+    for num_threads in [1, 2, 5] {
+        let ctx = ExecutionContext::builder()
+            .num_threads(num_threads)
+            .pin_threads(true)
+            .build()?;
+        let (sum, mm, sin, cumsum, t) = ctx.install(|| -> anyhow::Result<_> {
+            Ok((
+                a.sum(1, false)?,
+                a.matmul(&b)?,
+                a.sin()?,
+                a.cumsum(1)?,
+                a.t()?.contiguous()?,
+            ))
+        })?;
+        assert!(sum.allclose(&expected_sum));
+        assert!(mm.allclose(&expected_mm));
+        assert_eq!(sin.as_raw(), expected_sin.as_raw());
+        assert_eq!(cumsum.as_raw(), expected_cumsum.as_raw());
+        assert_eq!(t.as_raw(), expected_t.as_raw());
+    }
+    Ok(())
+}
+
+#[test]
+fn test_context_errors() {
+    assert!(ExecutionContext::builder().num_threads(0).build().is_err());
+    assert!(ExecutionContext::builder().cores(&[]).build().is_err());
+    assert!(ExecutionContext::builder()
+        .cores(&[usize::MAX])
+        .build()
+        .is_err());
+}
+
+#[test]
+fn test_context_cores() -> anyhow::Result<()> {
+    let ctx = ExecutionContext::builder()
+        .num_threads(3)
+        .cores(&[0])
+        .build()?;
+    assert!(ctx.pin_threads());
+    assert_eq!(ctx.cores(), Some(&[0][..]));
+    let a = Tensor::<f64>::randn(&[37, 129])?;
+    let cumsum = ctx.install(|| a.cumsum(1))?;
+    assert_eq!(cumsum.as_raw(), a.cumsum(1)?.as_raw());
+    let ctx = ExecutionContext::builder().num_threads(3).build()?;
+    assert!(!ctx.pin_threads());
+    assert_eq!(ctx.cores(), None);
+    Ok(())
+}
+
+#[test]
+fn test_context_shared_by_threads() -> anyhow::Result<()> {
+    // the calls running at the same time on the thread pool of a context only wait for their own jobs
+    let ctx = ExecutionContext::builder().num_threads(2).build()?;
+    let handles = (0..4)
+        .map(|i| {
+            let ctx = ctx.clone();
+            std::thread::spawn(move || -> anyhow::Result<()> {
+                let a = Tensor::<f64>::randn(&[16 + i, 257])?;
+                let b = Tensor::<f64>::randn(&[257, 9])?;
+                let expected_cumsum = a.cumsum(1)?;
+                let expected_mm = a.matmul(&b)?;
+                for _ in 0..8 {
+                    let (cumsum, mm) =
+                        ctx.install(|| -> anyhow::Result<_> { Ok((a.cumsum(1)?, a.matmul(&b)?)) })?;
+                    assert_eq!(cumsum.as_raw(), expected_cumsum.as_raw());
+                    assert!(mm.allclose(&expected_mm));
+                }
+                Ok(())
+            })
+        })
+        .collect::<Vec<_>>();
+    for handle in handles {
+        handle.join().unwrap()?;
+    }
+    Ok(())
+}
+
+#[test]
+fn test_context_iterator_from_larger_pool() -> anyhow::Result<()> {
+    use hpt::{ParStridedIteratorZip, TensorIterator};
+    use rayon::iter::ParallelIterator;
+    // the intervals of an iterator are computed for the pool it is created in, a smaller pool stops
+    // splitting before it reaches single intervals
+    let a = Tensor::<i64>::arange(0, 37 * 29)?.reshape(&[37, 29])?;
+    let expected = a.t()?.contiguous()?;
+    let large = ExecutionContext::builder().num_threads(8).build()?;
+    let small = ExecutionContext::builder().num_threads(1).build()?;
+    for created_in in [None, Some(&large)] {
+        let t = a.t()?;
+        let mut res = Tensor::<i64>::zeros(t.shape())?;
+        let (res_mut, t_ref) = (&mut res, &t);
+        let create = move || {
+            let res_mut = res_mut;
+            res_mut.par_iter_mut().zip(t_ref.par_iter())
+        };
+        let iter = match created_in {
+            Some(ctx) => ctx.install(create),
+            None => create(),
+        };
+        small.install(|| iter.for_each(|(r, x)| *r = x));
+        assert_eq!(res.as_raw(), expected.as_raw());
+    }
+    Ok(())
+}
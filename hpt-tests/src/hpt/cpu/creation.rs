@@ -80,6 +80,159 @@ fn test_blackman_window() -> anyhow::Result<()> {
     Ok(())
 }
 
+#[test]
+fn test_kaiser_window() -> anyhow::Result<()> {
+    let tch_a =
+        tch::Tensor::kaiser_window_beta(1000, true, 12.0, (tch::Kind::Double, tch::Device::Cpu));
+    let a = Tensor::<f64>::kaiser_window(1000, 12.0, true)?;
+    assert_eq(&a, &tch_a);
+    Ok(())
+}
+
+#[test]
+fn test_bartlett_window() -> anyhow::Result<()> {
+    let tch_a =
+        tch::Tensor::bartlett_window_periodic(1000, false, (tch::Kind::Double, tch::Device::Cpu));
+    let a = Tensor::<f64>::bartlett_window(1000, false)?;
+    assert_eq(&a, &tch_a);
+    Ok(())
+}
+
+fn assert_window(a: &[f64], expected: &[f64]) {
+    assert_eq!(a.len(), expected.len());
+    for (x, y) in a.iter().zip(expected) {
+        assert!(
+            (x - y).abs() <= y.abs() * 1e-7 + 1e-12,
+            "{:?} != {:?}",
+            a,
+            expected
+        );
+    }
+}
+
+#[test]
+fn test_window_values() -> anyhow::Result<()> {
+    // np.kaiser(12, 14)
+    let half = [
+        7.72686684e-06,
+        3.46009194e-03,
+        4.65200189e-02,
+        2.29737120e-01,
+        5.99885316e-01,
+        9.45674898e-01,
+    ];
+    let expected = half
+        .iter()
+        .chain(half.iter().rev())
+        .copied()
+        .collect::<Vec<_>>();
+    assert_window(
+        Tensor::<f64>::kaiser_window(12, 14.0, false)?.as_raw(),
+        &expected,
+    );
+    assert_window(
+        Tensor::<f64>::bartlett_window(5, false)?.as_raw(),
+        &[0.0, 0.5, 1.0, 0.5, 0.0],
+    );
+    assert_window(
+        Tensor::<f64>::bartlett_window(4, true)?.as_raw(),
+        &[0.0, 0.5, 1.0, 0.5],
+    );
+    let gaussian = [(-4.5f64).exp(), (-2.0f64).exp(), (-0.5f64).exp(), 1.0];
+    assert_window(
+        Tensor::<f64>::gaussian_window(7, 1.0, false)?.as_raw(),
+        &[
+            gaussian[0],
+            gaussian[1],
+            gaussian[2],
+            1.0,
+            gaussian[2],
+            gaussian[1],
+            gaussian[0],
+        ],
+    );
+    assert_window(
+        Tensor::<f64>::gaussian_window(6, 1.0, true)?.as_raw(),
+        &[
+            gaussian[0],
+            gaussian[1],
+            gaussian[2],
+            1.0,
+            gaussian[2],
+            gaussian[1],
+        ],
+    );
+    let taper = [
+        0.0,
+        0.5 * (1.0 - (0.4 * std::f64::consts::PI).cos()),
+        0.5 * (1.0 - (0.8 * std::f64::consts::PI).cos()),
+    ];
+    assert_window(
+        Tensor::<f64>::tukey_window(11, 0.5, false)?.as_raw(),
+        &[
+            taper[0], taper[1], taper[2], 1.0, 1.0, 1.0, 1.0, 1.0, taper[2], taper[1], taper[0],
+        ],
+    );
+    Ok(())
+}
+
+#[test]
+fn test_window_identities() -> anyhow::Result<()> {
+    for periodic in [true, false] {
+        let hann = Tensor::<f64>::general_cosine_window(64, &[0.5, 0.5], periodic)?;
+        assert_window(
+            Tensor::<f64>::tukey_window(64, 1.0, periodic)?.as_raw(),
+            hann.as_raw(),
+        );
+        assert_window(
+            Tensor::<f64>::tukey_window(64, 0.0, periodic)?.as_raw(),
+            &[1.0; 64],
+        );
+        assert_window(
+            Tensor::<f64>::kaiser_window(64, 0.0, periodic)?.as_raw(),
+            &[1.0; 64],
+        );
+    }
+    assert_window(
+        Tensor::<f64>::general_cosine_window(64, &[0.54, 0.46], true)?.as_raw(),
+        Tensor::<f64>::hamming_window(64, true)?.as_raw(),
+    );
+    assert_window(
+        Tensor::<f64>::general_cosine_window(64, &[0.5, 0.5], true)?.as_raw(),
+        Tensor::<f64>::hann_window(64, true)?.as_raw(),
+    );
+    Ok(())
+}
+
+#[test]
+fn test_window_edge_cases() -> anyhow::Result<()> {
+    for periodic in [true, false] {
+        assert_eq!(
+            Tensor::<f64>::kaiser_window(1, 8.0, periodic)?.as_raw(),
+            &[1.0]
+        );
+        assert_eq!(
+            Tensor::<f64>::bartlett_window(1, periodic)?.as_raw(),
+            &[1.0]
+        );
+        assert_eq!(Tensor::<f64>::tukey_window(0, 0.5, periodic)?.size(), 0);
+    }
+    assert!(Tensor::<f64>::bartlett_window(-1, true).is_err());
+    assert!(Tensor::<f64>::gaussian_window(8, 0.0, true).is_err());
+    assert!(Tensor::<f64>::gaussian_window(8, f64::NAN, true).is_err());
+
+    let a = Tensor::<f32>::kaiser_window(33, 8.6, false)?;
+    let b = Tensor::<f64>::kaiser_window(33, 8.6, false)?;
+    assert!(a
+        .as_raw()
+        .iter()
+        .zip(b.as_raw())
+        .all(|(x, y)| (*x as f64 - y).abs() < 1e-6));
+    let a = Tensor::<half::f16>::gaussian_window(9, 2.0, false)?;
+    assert_eq!(a.as_raw()[4], half::f16::ONE);
+    Ok(())
+}
+
 #[test]
 fn test_zeros() -> anyhow::Result<()> {
     let tch_a = tch::Tensor::zeros(&[1000], (tch::Kind::Double, tch::Device::Cpu));
@@ -133,3 +286,18 @@ fn test_identity() -> anyhow::Result<()> {
     assert_eq(&a, &tch_a);
     Ok(())
 }
+
+#[test]
+fn test_from_fn() -> anyhow::Result<()> {
+    let tch_a = tch::Tensor::arange(24, (tch::Kind::Double, tch::Device::Cpu)).reshape(&[2, 3, 4]);
+    let a = Tensor::<f64>::from_fn(&[2, 3, 4], |idx| (idx[0] * 12 + idx[1] * 4 + idx[2]) as f64)?;
+    assert_eq(&a, &tch_a);
+    let b = Tensor::<f64>::from_fn(&[10, 10], |idx| (idx[0] == idx[1]) as u8 as f64)?;
+    assert_eq(
+        &b,
+        &tch::Tensor::eye(10, (tch::Kind::Double, tch::Device::Cpu)),
+    );
+    let s = Tensor::<f64>::from_fn(&[] as &[i64], |idx| idx.len() as f64 + 1.0)?;
+    assert_eq!(s.as_raw(), &[1.0]);
+    Ok(())
+}
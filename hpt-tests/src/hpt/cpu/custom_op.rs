@@ -0,0 +1,70 @@
+#![allow(unused_imports)]
+use hpt::{
+    binary_op, unary_op, NormalOut, Random, ShapeManipulate, Tensor, TensorCreator, TensorInfo,
+    TensorLike,
+};
+
+unary_op! {
+    Cube: f32 => f32,
+    scalar: |x| x * x * x,
+    simd: |v| v * v * v,
+}
+
+binary_op! {
+    SquaredDiff: f32, f32 => f32,
+    scalar: |a, b| (a - b) * (a - b),
+    simd: |a, b| (a - b) * (a - b),
+}
+
+binary_op! {
+    Clamp: i64, i64 => i64,
+    scalar: |a, b| a.min(b),
+    simd: |a, b| a._min(b),
+}
+
+fn assert_close(a: &Tensor<f32>, b: &Tensor<f32>) {
+    assert_eq!(a.shape(), b.shape());
+    for (x, y) in a.as_raw().iter().zip(b.as_raw().iter()) {
+        assert!((x - y).abs() <= 1e-5 * y.abs().max(1.0), "{} != {}", x, y);
+    }
+}
+
+#[test]
+fn test_unary_op() -> anyhow::Result<()> {
+    // the odd length goes through both the simd and the scalar path
+    let a = Tensor::<f32>::randn(&[13, 37])?;
+    let expected = a.clone() * a.clone() * a.clone();
+    assert_close(&a.unary_op::<Cube>()?, &expected);
+
+    // non contiguous input
+    let t = a.t()?;
+    assert_close(&t.unary_op::<Cube>()?, &expected.t()?.contiguous()?);
+
+    let out = Tensor::<f32>::empty(&[13, 37])?;
+    let res = a.unary_op_::<Cube, _>(out.clone())?;
+    assert_close(&out, &expected);
+    assert_eq!(res.ptr().ptr, out.ptr().ptr);
+    Ok(())
+}
+
+#[test]
+fn test_binary_op() -> anyhow::Result<()> {
+    let a = Tensor::<f32>::randn(&[13, 1, 37])?;
+    let b = Tensor::<f32>::randn(&[5, 37])?;
+    let diff = a.clone() - b.clone();
+    let expected = diff.clone() * diff;
+    let res = a.binary_op::<SquaredDiff, _>(&b)?;
+    assert_eq!(res.shape().to_vec(), vec![13, 5, 37]);
+    assert_close(&res, &expected);
+
+    let out = Tensor::<f32>::empty(&[13, 5, 37])?;
+    a.binary_op_::<SquaredDiff, _, _>(&b, out.clone())?;
+    assert_close(&out, &expected);
+
+    // scalar rhs
+    let x = Tensor::<i64>::arange(0, 10)?;
+    let y = Tensor::<i64>::new(&[4]);
+    let clamped = x.binary_op::<Clamp, _>(&y)?;
+    assert_eq!(clamped.as_raw(), &[0, 1, 2, 3, 4, 4, 4, 4, 4, 4]);
+    Ok(())
+}
@@ -0,0 +1,75 @@
+#![allow(unused_imports)]
+use hpt::{
+    IndexReduce, NormalOut, NormalReduce, Random, ShapeManipulate, Tensor, TensorCreator,
+    TensorInfo, TensorLike, TypeCommon,
+};
+
+#[test]
+fn test_reduce_axes() -> anyhow::Result<()> {
+    let a = Tensor::<i64>::arange(0, 2 * 3 * 4 * 5)?.reshape(&[2, 3, 4, 5])?;
+    for axes in [vec![0], vec![1, 3], vec![0, 2, 3], vec![0, 1, 2, 3]] {
+        for keep_dims in [false, true] {
+            let res = a.reduce_axes(
+                axes.as_slice(),
+                keep_dims,
+                0,
+                |a, b| a._add(b),
+                |a, b| a._add(b),
+            )?;
+            let sum = a.sum(axes.as_slice(), keep_dims)?;
+            assert_eq!(res.shape(), sum.shape());
+            assert_eq!(res.as_raw(), sum.as_raw());
+        }
+    }
+
+    // non contiguous input
+    let t = a.permute(&[3, 1, 0, 2])?;
+    let res = t.reduce_axes(&[1, 2], false, i64::MIN, |a, b| a._max(b), |a, b| a._max(b))?;
+    assert_eq!(res.as_raw(), t.max(&[1, 2], false)?.as_raw());
+    Ok(())
+}
+
+#[test]
+fn test_reduce_axes_multi() -> anyhow::Result<()> {
+    let a = Tensor::<f32>::randn(&[6, 7, 300])?;
+    for axis in [0i64, 1, 2] {
+        let t = a.permute(&[2, 0, 1])?;
+        for input in [&a, &t] {
+            let (min, argmin) = input.reduce_axes_multi(
+                axis,
+                false,
+                (f32::INFINITY, -1i64),
+                |(m, i), x, idx| if x < m { (x, idx) } else { (m, i) },
+                |l, r| if r.0 < l.0 { r } else { l },
+            )?;
+            assert_eq!(min.as_raw(), input.min(axis, false)?.as_raw());
+            assert_eq!(argmin.as_raw(), input.argmin(axis, false)?.as_raw());
+        }
+    }
+
+    // fewer outputs than threads, the reduced axis is split between the threads
+    let c = Tensor::<f32>::randn(&[2, 5000])?;
+    let (min, argmin) = c.reduce_axes_multi(
+        1,
+        false,
+        (f32::INFINITY, -1i64),
+        |(m, i), x, idx| if x < m { (x, idx) } else { (m, i) },
+        |l, r| if r.0 < l.0 { r } else { l },
+    )?;
+    assert_eq!(min.as_raw(), c.min(1, false)?.as_raw());
+    assert_eq!(argmin.as_raw(), c.argmin(1, false)?.as_raw());
+
+    // the positions are row major over all the reduced axes
+    let b = Tensor::<i64>::arange(0, 24)?.reshape(&[2, 3, 4])?;
+    let (max, argmax) = b.reduce_axes_multi(
+        &[0, 2],
+        true,
+        (i64::MIN, -1i64),
+        |(m, i), x, idx| if x > m { (x, idx) } else { (m, i) },
+        |l, r| if r.0 > l.0 { r } else { l },
+    )?;
+    assert_eq!(max.shape().to_vec(), vec![1, 3, 1]);
+    assert_eq!(max.as_raw(), &[15, 19, 23]);
+    assert_eq!(argmax.as_raw(), &[7, 7, 7]);
+    Ok(())
+}
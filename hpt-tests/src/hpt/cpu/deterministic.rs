@@ -0,0 +1,92 @@
+#![allow(unused_imports)]
+use hpt::{
+    ExecutionContext, FloatReduce, NormalReduce, Random, ShapeManipulate, Tensor, TensorCreator,
+    TensorInfo, TensorLike,
+};
+
+fn bits(a: &Tensor<f32>) -> Vec<u32> {
+    a.as_raw().iter().map(|x| x.to_bits()).collect()
+}
+
+fn run<R: Send>(num_threads: usize, f: impl FnOnce() -> R + Send) -> anyhow::Result<R> {
+    let ctx = ExecutionContext::builder()
+        .num_threads(num_threads)
+        .deterministic(true)
+        .build()?;
+    Ok(ctx.install(f))
+}
+
+#[test]
+fn test_deterministic_full_reduce() -> anyhow::Result<()> {
+    let a = Tensor::<f32>::randn(&[513, 1031])?;
+    let mut expected = None;
+    for num_threads in [1, 2, 3, 5] {
+        let res = run(num_threads, || -> anyhow::Result<_> {
+            let t = a.t()?;
+            Ok([
+                bits(&a.sum([0, 1], false)?),
+                bits(&a.mean([0, 1], false)?),
+                bits(&a.logsumexp([0, 1], false)?),
+                bits(&t.sum([0, 1], false)?),
+                bits(&t.mean([0, 1], false)?),
+            ])
+        })??;
+        match &expected {
+            None => expected = Some(res),
+            Some(expected) => assert_eq!(expected, &res),
+        }
+    }
+    Ok(())
+}
+
+#[test]
+fn test_deterministic_softmax() -> anyhow::Result<()> {
+    let a = Tensor::<f32>::randn(&[100_003])?;
+    let mut expected = None;
+    for num_threads in [1, 2, 3, 5] {
+        let res = run(num_threads, || -> anyhow::Result<_> {
+            Ok((bits(&a.softmax(0)?), bits(&a.log_softmax(0)?)))
+        })??;
+        match &expected {
+            None => expected = Some(res),
+            Some(expected) => assert_eq!(expected, &res),
+        }
+    }
+    Ok(())
+}
+
+#[test]
+fn test_deterministic_reduce_axes_multi() -> anyhow::Result<()> {
+    let a = Tensor::<f32>::randn(&[65_537])?;
+    let mut expected = None;
+    for num_threads in [1, 2, 3, 5] {
+        let res = run(num_threads, || -> anyhow::Result<_> {
+            let (sum, count) = a.reduce_axes_multi(
+                0,
+                false,
+                (0.0f32, 0i64),
+                |(s, c), x, _| (s + x, c + 1),
+                |l, r| (l.0 + r.0, l.1 + r.1),
+            )?;
+            assert_eq!(count.as_raw(), &[65_537]);
+            Ok(bits(&sum))
+        })??;
+        match &expected {
+            None => expected = Some(res),
+            Some(expected) => assert_eq!(expected, &res),
+        }
+    }
+    Ok(())
+}
+
+#[test]
+fn test_deterministic_random_errors() -> anyhow::Result<()> {
+    let res = run(2, || Tensor::<f32>::randn(&[16]).map(|_| ()))?;
+    assert!(res
+        .unwrap_err()
+        .to_string()
+        .contains("can't guarantee deterministic results"));
+    let ctx = ExecutionContext::builder().num_threads(2).build()?;
+    assert!(ctx.install(|| Tensor::<f32>::randn(&[16])).is_ok());
+    Ok(())
+}
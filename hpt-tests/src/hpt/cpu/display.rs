@@ -0,0 +1,129 @@
+#![allow(unused_imports)]
+use hpt::{PrintOptions, PrintSign, ShapeManipulate, Tensor, TensorCreator};
+
+#[test]
+fn test_display_alternate() -> anyhow::Result<()> {
+    let options = PrintOptions::default();
+    let a = Tensor::<i32>::arange(0, 4)?.reshape(&[2, 2])?;
+    assert_eq!(format!("{:#}", a.display_with(&options)), "[[0 1]\n [2 3]]");
+    let a = Tensor::<f32>::new(1.5);
+    assert_eq!(format!("{:#}", a.display_with(&options)), "1.5000");
+    let a = Tensor::<f32>::empty(&[0])?;
+    assert_eq!(format!("{:#}", a.display_with(&options)), "[]");
+    Ok(())
+}
+
+#[test]
+fn test_display_debug() -> anyhow::Result<()> {
+    let options = PrintOptions::default();
+    let a = Tensor::<i32>::arange(0, 6)?.reshape(&[2, 3])?;
+    assert_eq!(
+        format!("{}", a.display_with(&options)),
+        "Tensor([[0 1 2]\n        [3 4 5]], shape=(2, 3), strides=(3, 1), dtype=i32)\n"
+    );
+    assert_eq!(
+        format!("{:?}", a.display_with(&options)),
+        "Tensor([[0 1 2]\n        [3 4 5]], shape=(2, 3), strides=(3, 1), contiguous=true, dtype=i32)\n"
+    );
+    let t = a.t()?;
+    assert!(format!("{:?}", t.display_with(&options))
+        .ends_with("shape=(3, 2), strides=(1, 3), contiguous=false, dtype=i32)\n"));
+    Ok(())
+}
+
+#[test]
+fn test_display_threshold() -> anyhow::Result<()> {
+    let a = Tensor::<i64>::arange(0, 100)?.reshape(&[10, 10])?;
+    let options = PrintOptions {
+        threshold: 1000,
+        ..Default::default()
+    };
+    let full = format!("{:#}", a.display_with(&options));
+    assert!(!full.contains("..."));
+    assert_eq!(full.lines().count(), 10);
+    let options = PrintOptions {
+        threshold: 99,
+        edge_items: 1,
+        ..Default::default()
+    };
+    assert_eq!(
+        format!("{:#}", a.display_with(&options)),
+        "[[ 0 ...  9 ]\n ...\n\n [90 ... 99 ]]"
+    );
+    Ok(())
+}
+
+#[test]
+fn test_display_scientific() -> anyhow::Result<()> {
+    let a = Tensor::<f64>::new(&[1.0, 1234.5]);
+    let options = PrintOptions {
+        precision: 2,
+        scientific: Some(true),
+        ..Default::default()
+    };
+    assert_eq!(
+        format!("{:#}", a.display_with(&options)),
+        "[1.00e+00 1.23e+03]"
+    );
+    let a = Tensor::<f32>::new(&[1e-5, 1.0]);
+    let options = PrintOptions::default();
+    assert_eq!(
+        format!("{:#}", a.display_with(&options)),
+        "[1.0000e-05 1.0000e+00]"
+    );
+    let options = PrintOptions {
+        suppress_small: true,
+        ..Default::default()
+    };
+    assert_eq!(format!("{:#}", a.display_with(&options)), "[0.0000 1.]");
+    let options = PrintOptions {
+        scientific: Some(false),
+        ..Default::default()
+    };
+    assert_eq!(format!("{:#}", a.display_with(&options)), "[0.0000 1.]");
+    Ok(())
+}
+
+#[test]
+fn test_display_sign() -> anyhow::Result<()> {
+    let a = Tensor::<f32>::new(&[-1.5, 2.5]);
+    let options = PrintOptions {
+        precision: 1,
+        sign: PrintSign::Plus,
+        ..Default::default()
+    };
+    assert_eq!(format!("{:#}", a.display_with(&options)), "[-1.5 +2.5]");
+    let options = PrintOptions {
+        precision: 1,
+        sign: PrintSign::Space,
+        ..Default::default()
+    };
+    assert_eq!(format!("{:#}", a.display_with(&options)), "[-1.5  2.5]");
+    Ok(())
+}
+
+#[test]
+fn test_display_line_width() -> anyhow::Result<()> {
+    let a = Tensor::<i64>::arange(0, 30)?;
+    let options = PrintOptions {
+        threshold: 1000,
+        line_width: 20,
+        ..Default::default()
+    };
+    let res = format!("{}", a.display_with(&options));
+    let data = res.split(", shape").next().unwrap();
+    assert!(data.lines().count() > 1);
+    assert!(data.lines().all(|line| line.len() <= 20));
+    assert!(data
+        .lines()
+        .skip(1)
+        .all(|line| line.starts_with("        ")));
+    let values = data
+        .trim_start_matches("Tensor([")
+        .trim_end_matches(']')
+        .split_whitespace()
+        .map(|x| x.parse::<i64>())
+        .collect::<Result<Vec<_>, _>>()?;
+    assert_eq!(values, (0..30).collect::<Vec<_>>());
+    Ok(())
+}
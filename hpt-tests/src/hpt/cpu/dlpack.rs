@@ -0,0 +1,110 @@
+#![allow(unused_imports)]
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use hpt::dlpack::{DLDataType, DLDevice, DLDeviceType, DLManagedTensor, DLTensor};
+use hpt::{ShapeManipulate, Tensor, TensorCreator, TensorInfo, TensorLike};
+
+#[test]
+fn test_dlpack_round_trip() -> anyhow::Result<()> {
+    let a = Tensor::<f32>::arange(0, 24)?.reshape(&[2, 3, 4])?;
+    let managed = a.to_dlpack()?;
+    let b = unsafe { Tensor::<f32>::from_dlpack(managed)? };
+    assert_eq!(b.shape().inner(), a.shape().inner());
+    assert_eq!(b.as_raw().as_ptr(), a.as_raw().as_ptr());
+    drop(a);
+    assert!(b.allclose(&Tensor::<f32>::arange(0, 24)?.reshape(&[2, 3, 4])?));
+    Ok(())
+}
+
+#[test]
+fn test_dlpack_strided() -> anyhow::Result<()> {
+    let a = Tensor::<i64>::arange(0, 24)?.reshape(&[2, 3, 4])?;
+    let a = a.permute(&[2, 0, 1])?;
+    let managed = a.to_dlpack()?;
+    unsafe {
+        assert_eq!(
+            std::slice::from_raw_parts((*managed).dl_tensor.strides, 3),
+            a.strides().inner().as_slice()
+        );
+    }
+    let b = unsafe { Tensor::<i64>::from_dlpack(managed)? };
+    assert_eq!(b.strides().inner(), a.strides().inner());
+    assert!(b.allclose(&a.contiguous()?));
+    Ok(())
+}
+
+#[test]
+fn test_dlpack_dtype_mismatch() -> anyhow::Result<()> {
+    let a = Tensor::<f32>::arange(0, 4)?;
+    let managed = a.to_dlpack()?;
+    assert!(unsafe { Tensor::<i32>::from_dlpack(managed) }.is_err());
+    let b = unsafe { Tensor::<f32>::from_dlpack(managed)? };
+    assert_eq!(b.as_raw(), a.as_raw());
+    Ok(())
+}
+
+#[test]
+fn test_dlpack_dtype_codes() -> anyhow::Result<()> {
+    assert_eq!(DLDataType::of::<half::f16>()?.hpt_type(), Some("f16"));
+    assert_eq!(DLDataType::of::<half::bf16>()?.hpt_type(), Some("bf16"));
+    assert_eq!(DLDataType::of::<bool>()?.hpt_type(), Some("bool"));
+    assert_eq!(
+        DLDataType::of::<num_complex::Complex64>()?.hpt_type(),
+        Some("c64")
+    );
+    assert_eq!(DLDataType::of::<isize>()?, DLDataType::of::<i64>()?);
+    Ok(())
+}
+
+static FOREIGN_DELETED: AtomicUsize = AtomicUsize::new(0);
+
+struct ForeignCtx {
+    data: Vec<f64>,
+    shape: Vec<i64>,
+    strides: Vec<i64>,
+}
+
+unsafe extern "C" fn foreign_deleter(managed: *mut DLManagedTensor) {
+    let managed = Box::from_raw(managed);
+    drop(Box::from_raw(managed.manager_ctx as *mut ForeignCtx));
+    FOREIGN_DELETED.fetch_add(1, Ordering::SeqCst);
+}
+
+#[test]
+fn test_dlpack_foreign() -> anyhow::Result<()> {
+    // a column major [3, 2] tensor with 1 element byte offset
+    let mut ctx = Box::new(ForeignCtx {
+        data: vec![-1.0, 0.0, 1.0, 2.0, 3.0, 4.0, 5.0],
+        shape: vec![3, 2],
+        strides: vec![1, 3],
+    });
+    let managed = Box::into_raw(Box::new(DLManagedTensor {
+        dl_tensor: DLTensor {
+            data: ctx.data.as_mut_ptr() as *mut std::ffi::c_void,
+            device: DLDevice {
+                device_type: DLDeviceType::Cpu as i32,
+                device_id: 0,
+            },
+            ndim: 2,
+            dtype: DLDataType::of::<f64>()?,
+            shape: ctx.shape.as_mut_ptr(),
+            strides: ctx.strides.as_mut_ptr(),
+            byte_offset: std::mem::size_of::<f64>() as u64,
+        },
+        manager_ctx: std::ptr::null_mut(),
+        deleter: Some(foreign_deleter),
+    }));
+    unsafe {
+        (*managed).manager_ctx = Box::into_raw(ctx) as *mut std::ffi::c_void;
+    }
+    let a = unsafe { Tensor::<f64>::from_dlpack(managed)? };
+    let b = a.clone();
+    let view = a.permute(&[1, 0])?;
+    drop(a);
+    drop(b);
+    assert_eq!(FOREIGN_DELETED.load(Ordering::SeqCst), 0);
+    assert!(view.allclose(&Tensor::<f64>::arange(0, 6)?.reshape(&[2, 3])?));
+    drop(view);
+    assert_eq!(FOREIGN_DELETED.load(Ordering::SeqCst), 1);
+    Ok(())
+}
@@ -0,0 +1,84 @@
+#![allow(unused_imports)]
+use hpt::{
+    Dtype, DynTensor, FloatBinOps, FloatReduce, FloatUnaryOps, Matmul, NormalBinOps, NormalReduce,
+    NormalUaryOps, ShapeManipulate, Tensor, TensorCreator, TensorError, TensorInfo, TypeNum,
+};
+use half::f16;
+use num_complex::Complex32;
+
+#[test]
+fn test_dyn_promotion() -> anyhow::Result<()> {
+    let a = Tensor::<i32>::arange(0, 12)?.reshape(&[3, 4])?;
+    let b = Tensor::<f32>::arange(0, 4)?;
+    let c = Tensor::<i8>::arange(0, 4)?;
+    let (da, db, dc): (DynTensor, DynTensor, DynTensor) = (a.clone().into(), b.clone().into(), c.into());
+
+    let sum = &da + &db;
+    assert_eq!(sum.dtype(), Dtype::F32);
+    assert!(sum.downcast::<f32>()?.allclose(&(&a.astype::<f32>()? + &b)));
+
+    let prod = &da * &dc;
+    assert_eq!(prod.dtype(), Dtype::I32);
+    assert_eq!(Dtype::I8.promote(Dtype::I16), Dtype::I16);
+    assert_eq!(Dtype::I32.promote_float(Dtype::I32), Dtype::F32);
+    assert_eq!(Dtype::U8.float_unary(), <f16 as TypeNum>::type_num());
+
+    let div = &da / &da.add(&DynTensor::ones(&[1], Dtype::I32)?)?;
+    assert_eq!(div.dtype(), Dtype::F32);
+    let a1: Tensor<i32> = &a + 1;
+    assert!(div.allclose(&(a.astype::<f32>()? / a1.astype::<f32>()?).into()));
+    Ok(())
+}
+
+#[test]
+fn test_dyn_unary_reduce_shape() -> anyhow::Result<()> {
+    let a: Tensor<f64> = Tensor::<f64>::arange(0, 24)?.reshape(&[2, 3, 4])? / 10.0;
+    let d: DynTensor = a.clone().into();
+    assert!(d.sin()?.downcast::<f64>()?.allclose(&a.sin()?));
+    assert!(d.celu(0.5)?.downcast::<f64>()?.allclose(&a.celu(0.5)?));
+    assert!(d.clamp(0.5, 1.5)?.downcast::<f64>()?.allclose(&a.clamp(0.5, 1.5)?));
+    assert!(d.sum(1, true)?.downcast::<f64>()?.allclose(&a.sum(1, true)?));
+    assert!(d.mean(&[0, 2], false)?.downcast::<f64>()?.allclose(&a.mean(&[0, 2], false)?));
+    let p = d.permute(&[2, 0, 1])?.reshape(&[4, 6])?;
+    assert_eq!(p.shape().to_vec(), vec![4, 6]);
+    assert!(p.downcast::<f64>()?.allclose(&a.permute(&[2, 0, 1])?.reshape(&[4, 6])?));
+
+    let i: DynTensor = Tensor::<i64>::arange(0, 6)?.into();
+    assert_eq!(i.exp()?.dtype(), Dtype::F64);
+    let parts = i.split(&[2, 4], 0)?;
+    assert_eq!(parts.len(), 3);
+    let f: DynTensor = Tensor::<f32>::arange(0, 2)?.into();
+    let cat = DynTensor::concat(vec![parts[0].clone(), f], 0, false)?;
+    assert_eq!(cat.dtype(), Dtype::F64);
+    assert!(cat.downcast::<f64>()?.allclose(&Tensor::<f64>::new(&[0.0, 1.0, 0.0, 1.0])));
+    Ok(())
+}
+
+#[test]
+fn test_dyn_matmul_and_out() -> anyhow::Result<()> {
+    let a = Tensor::<f32>::arange(0, 12)?.reshape(&[3, 4])?;
+    let b = Tensor::<i16>::arange(0, 8)?.reshape(&[4, 2])?;
+    let (da, db): (DynTensor, DynTensor) = (a.clone().into(), b.clone().into());
+    let c = da.matmul(&db)?;
+    assert_eq!(c.dtype(), Dtype::F32);
+    assert!(c.downcast::<f32>()?.allclose(&a.matmul(b.astype::<f32>()?)?));
+
+    let mut out = DynTensor::zeros(&[3, 4], Dtype::F32)?;
+    da.sin_(&mut out)?;
+    assert!(out.downcast::<f32>()?.allclose(&a.sin()?));
+    let mut wrong = DynTensor::zeros(&[3, 4], Dtype::F64)?;
+    assert!(da.add_(&da, &mut wrong).is_err());
+    Ok(())
+}
+
+#[test]
+fn test_dyn_errors() -> anyhow::Result<()> {
+    let d: DynTensor = Tensor::<i32>::arange(0, 4)?.into();
+    assert!(d.downcast::<i32>().is_ok());
+    assert!(matches!(d.downcast::<f32>(), Err(TensorError::Param(_))));
+    let c: DynTensor = Tensor::<Complex32>::zeros(&[4])?.into();
+    assert!(c.sin().is_err());
+    assert_eq!(d.astype(Dtype::U8)?.dtype(), Dtype::U8);
+    assert_eq!(format!("{}", d), format!("{}", d.downcast::<i32>()?));
+    Ok(())
+}
@@ -0,0 +1,76 @@
+#![allow(unused_imports)]
+use hpt::{ShapeManipulate, Tensor, TensorCreator, TensorInfo, TensorIterator, TensorLike};
+use std::sync::Mutex;
+
+#[test]
+fn test_indexed_iter() -> anyhow::Result<()> {
+    let a = Tensor::<i64>::arange(0, 24)?.reshape(&[2, 3, 4])?;
+    let mut visited = Vec::new();
+    a.indexed_iter().for_each(|(idx, x)| {
+        assert_eq!(x, idx[0] * 12 + idx[1] * 4 + idx[2]);
+        visited.push(x);
+    });
+    assert_eq!(visited, (0..24).collect::<Vec<_>>());
+
+    // the indices follow the logical layout of a non contiguous tensor
+    let t = a.permute(&[2, 0, 1])?;
+    let mut count = 0;
+    t.indexed_iter().for_each(|(idx, x)| {
+        assert_eq!(x, idx[1] * 12 + idx[2] * 4 + idx[0]);
+        count += 1;
+    });
+    assert_eq!(count, 24);
+
+    let sum = Mutex::new(0);
+    t.par_indexed_iter().for_each(|(idx, x)| {
+        assert_eq!(x, idx[1] * 12 + idx[2] * 4 + idx[0]);
+        *sum.lock().unwrap() += x;
+    });
+    assert_eq!(sum.into_inner().unwrap(), (0..24).sum::<i64>());
+    Ok(())
+}
+
+#[test]
+fn test_indexed_map() -> anyhow::Result<()> {
+    let a = Tensor::<f32>::ones(&[5, 5])?;
+    let masked: Tensor<f32> = a
+        .indexed_iter()
+        .strided_map(|(idx, x)| if idx[1] > idx[0] { 0.0 } else { x })
+        .collect();
+    assert_eq!(masked.as_raw(), a.tril(0)?.as_raw());
+
+    let t = Tensor::<i64>::arange(0, 6)?.reshape(&[2, 3])?.t()?;
+    let res: Tensor<i64> = t
+        .par_indexed_iter()
+        .strided_map(|(idx, x)| x * 10 + idx[0])
+        .collect();
+    assert_eq!(res.shape().to_vec(), vec![3, 2]);
+    assert_eq!(res.as_raw(), &[0, 30, 11, 41, 22, 52]);
+    Ok(())
+}
+
+#[test]
+fn test_indexed_iter_mut() -> anyhow::Result<()> {
+    let mut a = Tensor::<i64>::zeros(&[3, 4])?;
+    a.indexed_iter_mut().for_each(|(idx, x)| {
+        *x = idx[0] * 4 + idx[1];
+    });
+    assert_eq!(a.as_raw(), (0..12).collect::<Vec<_>>().as_slice());
+
+    let mut t = a.t()?;
+    t.par_indexed_iter_mut().for_each(|(idx, x)| {
+        *x += 100 * idx[0];
+    });
+    assert_eq!(
+        a.as_raw(),
+        &[0, 101, 202, 303, 4, 105, 206, 307, 8, 109, 210, 311]
+    );
+
+    let mut s = Tensor::<i64>::new(7);
+    s.indexed_iter_mut().for_each(|(idx, x)| {
+        assert!(idx.is_empty());
+        *x += 1;
+    });
+    assert_eq!(s.as_raw(), &[8]);
+    Ok(())
+}
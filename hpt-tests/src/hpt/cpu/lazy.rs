@@ -0,0 +1,136 @@
+#![allow(unused_imports)]
+use hpt::{
+    lazy, lazy_with_stats, CumulativeOps, FloatUnaryOps, Matmul, NormalReduce, NormalUaryOps,
+    ShapeManipulate, Slice, Tensor, TensorCreator, TensorError, TensorInfo, TensorLike,
+};
+
+#[test]
+fn test_lazy_fuse_elementwise() -> anyhow::Result<()> {
+    let a = Tensor::<f32>::arange(0, 6000)?.reshape(&[2, 3000])?;
+    let b = Tensor::<f32>::arange(1, 6001)?.reshape(&[2, 3000])?;
+    let (res, stats) = lazy_with_stats(|| {
+        let c = &a + &b;
+        let d = c.sin()?;
+        Ok::<_, TensorError>(d * &a)
+    })?;
+    assert!(res.allclose(&((&a + &b).sin()? * &a)));
+    assert_eq!(stats.recorded, 3);
+    assert_eq!(stats.kernels, 1);
+    Ok(())
+}
+
+#[test]
+fn test_lazy_dead_nodes_and_constants() -> anyhow::Result<()> {
+    let x = Tensor::<f64>::arange(0, 100)?;
+    let s = Tensor::<f64>::full(0.5, &[1])?;
+    let (res, stats) = lazy_with_stats(|| {
+        let unused = x.exp()?;
+        let _ = &unused + 1.0;
+        let c: Tensor<f64> = &s * 3.0;
+        let c = c.exp()?;
+        Ok::<_, TensorError>(&x * &c)
+    })?;
+    assert!(res.allclose(&(&x * 1.5f64.exp())));
+    assert_eq!(stats.recorded, 5);
+    assert_eq!(stats.eliminated, 2);
+    assert_eq!(stats.folded, 2);
+    assert_eq!(stats.kernels, 1);
+    Ok(())
+}
+
+#[test]
+fn test_lazy_matmul_reduce_views() -> anyhow::Result<()> {
+    let x = Tensor::<f32>::arange(0, 24)?.reshape(&[4, 6])?;
+    let w = Tensor::<f32>::arange(0, 18)?.reshape(&[6, 3])?;
+    let bias = Tensor::<f32>::arange(-40, -37)?;
+    let f = |x: &Tensor<f32>| -> Result<_, TensorError> {
+        let h = (&x.matmul(&w)? + &bias).relu()?;
+        let total = h.sum(1, true)?;
+        let t = h.permute(&[1, 0])?;
+        let doubled = &t * 2.0f32;
+        let all = h.max(&[0, 1], false)?;
+        Ok((&h / &total, doubled, all))
+    };
+    let (n, d, m) = lazy(|| f(&x))?;
+    let (en, ed, em) = f(&x)?;
+    assert!(n.allclose(&en));
+    assert_eq!(d.shape(), ed.shape());
+    assert!(d.allclose(&ed));
+    assert_eq!(m.shape(), em.shape());
+    assert!(m.allclose(&em));
+    Ok(())
+}
+
+#[test]
+fn test_lazy_reuse_buffers() -> anyhow::Result<()> {
+    let x = Tensor::<f64>::arange(0, 40)?.reshape(&[5, 8])? / 10.0;
+    let f = |x: &Tensor<f64>| -> Result<_, TensorError> {
+        let a = x.sin()?;
+        let m = a.max(1, true)?;
+        let e = (&a - &m).exp()?;
+        let s = e.sum(1, true)?;
+        Ok(e / s)
+    };
+    let (res, stats) = lazy_with_stats(|| f(&x))?;
+    assert!(res.allclose(&f(&x)?));
+    assert!(stats.reused_buffers > 0);
+    Ok(())
+}
+
+#[test]
+fn test_lazy_unrecorded_op() -> anyhow::Result<()> {
+    let x = Tensor::<i64>::arange(0, 12)?.reshape(&[3, 4])?;
+    let (res, stats) = lazy_with_stats(|| {
+        let a: Tensor<i64> = &x * 2;
+        let b = a.cumsum(1)?;
+        Ok::<_, TensorError>(&b + &a)
+    })?;
+    let a: Tensor<i64> = &x * 2;
+    assert!(res.allclose(&(&a.cumsum(1)? + &a)));
+    assert_eq!(stats.materialized, 1);
+    Ok(())
+}
+
+#[test]
+fn test_lazy_errors_and_leaves() -> anyhow::Result<()> {
+    let x = Tensor::<f32>::arange(0, 12)?.reshape(&[3, 4])?;
+    let y = Tensor::<f32>::arange(0, 12)?.reshape(&[3, 4])?;
+    assert!(lazy(|| x.sin()?.matmul(&y)).is_err());
+    let (res, stats) = lazy_with_stats(|| Ok::<_, TensorError>(vec![x.clone()]))?;
+    assert_eq!(res[0].as_raw().as_ptr(), x.as_raw().as_ptr());
+    assert_eq!(stats.kernels, 0);
+    let nested = lazy(|| lazy(|| x.exp()))?;
+    assert!(nested.allclose(&x.exp()?));
+    Ok(())
+}
+
+#[test]
+#[should_panic(expected = "lazy mode")]
+fn test_lazy_escaped_tensor() {
+    let x = Tensor::<f32>::arange(0, 4).unwrap();
+    let mut escaped = None;
+    lazy(|| {
+        escaped = Some(x.sin()?);
+        Ok::<_, TensorError>(())
+    })
+    .unwrap();
+    let _ = escaped.unwrap().as_raw()[0];
+}
+
+#[test]
+fn test_lazy_offset_views() -> anyhow::Result<()> {
+    let x = Tensor::<f32>::arange(0, 24)?.reshape(&[4, 6])?;
+    let f = |x: &Tensor<f32>| -> Result<_, TensorError> {
+        let a = x.cos()?;
+        let s = a.slice(&[Slice::Range((1, 3)), Slice::StepByRangeFrom((2, 2))])?;
+        let row = a.outer_iter()?.nth(2).expect("a has 4 rows");
+        // the views are read by recorded operations and by an operation that isn't recorded
+        Ok((&s + 1.0f32, row.exp()?, s.cumsum(1)?))
+    };
+    let (s, row, c) = lazy(|| f(&x))?;
+    let (es, erow, ec) = f(&x)?;
+    assert!(s.allclose(&es));
+    assert!(row.allclose(&erow));
+    assert!(c.allclose(&ec));
+    Ok(())
+}
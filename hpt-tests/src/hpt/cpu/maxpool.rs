@@ -115,3 +115,27 @@ fn test_pad() -> anyhow::Result<()> {
 
     Ok(())
 }
+
+#[test]
+fn test_negative() -> anyhow::Result<()> {
+    let mut rng = rand::thread_rng();
+
+    for _ in 0..100 {
+        let batch = rng.gen_range(1..=4);
+        let channel = rng.gen_range(1..=16);
+
+        let kernel_height = rng.gen_range(4..=7);
+        let kernel_width = rng.gen_range(4..=7);
+
+        let height = rng.gen_range(8..=32);
+        let width = rng.gen_range(8..=32);
+        let (kernel, a, tch_kernel, tch_a) =
+            common_input([batch, channel, kernel_height, kernel_width, height, width])?;
+        let a: Tensor<i64> = &a * -1 - 1;
+        let tch_a = &tch_a * -1 - 1;
+        assert_eq(&a, &kernel, &tch_a, &tch_kernel)?;
+        assert_eq_pad(&a, &kernel, &tch_a, &tch_kernel)?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,89 @@
+#![allow(unused_imports)]
+use hpt::{AdvancedOps, PadMode, ShapeManipulate, Tensor, TensorCreator, TensorInfo, TensorLike};
+
+fn pad_1d(data: &[i64], pads: (i64, i64), mode: PadMode<i64>) -> anyhow::Result<Vec<i64>> {
+    let a = Tensor::<i64>::new(data.to_vec());
+    Ok(a.pad_with_mode(&[pads], mode)?.as_raw().to_vec())
+}
+
+#[test]
+fn test_pad_modes_1d() -> anyhow::Result<()> {
+    let a = [1, 2, 3];
+    assert_eq!(pad_1d(&a, (2, 2), PadMode::Reflect)?, [3, 2, 1, 2, 3, 2, 1]);
+    assert_eq!(
+        pad_1d(&a, (2, 2), PadMode::Symmetric)?,
+        [2, 1, 1, 2, 3, 3, 2]
+    );
+    assert_eq!(pad_1d(&a, (2, 2), PadMode::Edge)?, [1, 1, 1, 2, 3, 3, 3]);
+    assert_eq!(
+        pad_1d(&a, (2, 2), PadMode::Circular)?,
+        [2, 3, 1, 2, 3, 1, 2]
+    );
+    assert_eq!(pad_1d(&a, (1, 0), PadMode::Constant(9))?, [9, 1, 2, 3]);
+    Ok(())
+}
+
+#[test]
+fn test_pad_larger_than_axis() -> anyhow::Result<()> {
+    let a = [1, 2, 3];
+    assert_eq!(
+        pad_1d(&a, (5, 4), PadMode::Reflect)?,
+        [2, 1, 2, 3, 2, 1, 2, 3, 2, 1, 2, 3]
+    );
+    assert_eq!(
+        pad_1d(&a, (4, 4), PadMode::Symmetric)?,
+        [3, 3, 2, 1, 1, 2, 3, 3, 2, 1, 1]
+    );
+    assert_eq!(
+        pad_1d(&a, (4, 4), PadMode::Circular)?,
+        [3, 1, 2, 3, 1, 2, 3, 1, 2, 3, 1]
+    );
+    assert_eq!(pad_1d(&[7], (2, 1), PadMode::Reflect)?, [7, 7, 7, 7]);
+    Ok(())
+}
+
+#[test]
+fn test_pad_modes_2d() -> anyhow::Result<()> {
+    let a = Tensor::<i64>::arange(0, 6)?.reshape(&[2, 3])?;
+    let res = a.pad_with_mode(&[(1, 1), (0, 2)], PadMode::Reflect)?;
+    assert_eq!(res.shape().inner(), &[4, 5]);
+    assert_eq!(
+        res.as_raw(),
+        &[3, 4, 5, 4, 3, 0, 1, 2, 1, 0, 3, 4, 5, 4, 3, 0, 1, 2, 1, 0]
+    );
+    let res = a.pad_with_mode(&[(1, 0), (1, 1)], PadMode::Edge)?;
+    assert_eq!(res.as_raw(), &[0, 0, 1, 2, 2, 0, 0, 1, 2, 2, 3, 3, 4, 5, 5]);
+    // pads missing for the trailing axes leave them unpadded
+    let res = a.pad_with_mode(&[(1, 1)], PadMode::Circular)?;
+    assert_eq!(res.as_raw(), &[3, 4, 5, 0, 1, 2, 3, 4, 5, 0, 1, 2]);
+    Ok(())
+}
+
+#[test]
+fn test_pad_uncontiguous() -> anyhow::Result<()> {
+    let a = Tensor::<f32>::arange(0, 12)?.reshape(&[3, 4])?;
+    let t = a.t()?;
+    let expected = t.contiguous()?;
+    for mode in [
+        PadMode::Reflect,
+        PadMode::Symmetric,
+        PadMode::Edge,
+        PadMode::Circular,
+        PadMode::Constant(-1.0),
+    ] {
+        let pads = [(2, 1), (1, 2)];
+        let res = t.pad_with_mode(&pads, mode)?;
+        assert!(res.allclose(&expected.pad_with_mode(&pads, mode)?));
+    }
+    Ok(())
+}
+
+#[test]
+fn test_pad_mode_errors() -> anyhow::Result<()> {
+    let a = Tensor::<f32>::new(&[1.0, 2.0]);
+    assert!(a.pad_with_mode(&[(-1, 0)], PadMode::Reflect).is_err());
+    assert!(a.pad_with_mode(&[(1, 0), (1, 0)], PadMode::Edge).is_err());
+    let empty = Tensor::<f32>::empty(&[0])?;
+    assert!(empty.pad_with_mode(&[(1, 1)], PadMode::Circular).is_err());
+    Ok(())
+}
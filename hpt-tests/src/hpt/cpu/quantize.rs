@@ -0,0 +1,264 @@
+#![allow(unused_imports)]
+use crate::utils::{typed, values};
+use hpt::{
+    Conv, Matmul, NormalOutUnary, NormalUaryOps, QTensor, QuantParams, ShapeManipulate, Tensor,
+    TensorCreator, TensorInfo, TensorLike, TypeCommon,
+};
+
+/// quantize the reference result the way the kernels requantize their accumulators
+fn requantize(x: &Tensor<f32>, scale: f32, zp: i32, lo: i32, hi: i32) -> Vec<i32> {
+    x.contiguous()
+        .unwrap()
+        .as_raw()
+        .iter()
+        .map(|x| ((x / scale).round_ties_even() as i32 + zp).clamp(lo, hi))
+        .collect()
+}
+
+/// the kernels scale the exact i32 accumulators, the float reference may round the other way at the midpoints
+fn assert_close<T: hpt::QuantType>(got: &QTensor<T>, expected: &[i32]) {
+    let got = got.int_repr().contiguous().unwrap();
+    assert_eq!(got.size(), expected.len());
+    for (g, e) in got.as_raw().iter().zip(expected.iter()) {
+        assert!((g.to_i32() - e).abs() <= 1, "{} != {}", g.to_i32(), e);
+    }
+}
+
+#[test]
+fn test_quantize_per_tensor() -> anyhow::Result<()> {
+    let a = typed(
+        &[-1.0, -0.125, 0.0, 0.375, 0.625, 1.0, 40.0, -40.0],
+        &[2, 4],
+    )?;
+    let q = a.quantize_per_tensor::<u8>(0.25, 10)?;
+    // ties round to even, out of range values saturate
+    assert_eq!(q.int_repr().as_raw(), &[6, 10, 10, 12, 12, 14, 170, 0]);
+    assert_eq!(q.params().scales(), &[0.25]);
+    assert_eq!(q.params().zero_points(), &[10]);
+    assert_eq!(q.params().axis(), None);
+    let d = q.dequantize()?;
+    assert_eq!(d.as_raw(), &[-1.0, 0.0, 0.0, 0.5, 0.5, 1.0, 40.0, -2.5]);
+
+    // non contiguous input
+    let t = a.t()?;
+    let q = t.quantize_per_tensor::<i8>(0.5, -3)?;
+    let expected = requantize(&t, 0.5, -3, -128, 127);
+    assert_eq!(
+        q.int_repr()
+            .as_raw()
+            .iter()
+            .map(|&x| x as i32)
+            .collect::<Vec<_>>(),
+        expected
+    );
+    Ok(())
+}
+
+#[test]
+fn test_quantize_per_channel() -> anyhow::Result<()> {
+    let a = typed(&values(24, 0.3), &[2, 3, 4])?;
+    let scales = [0.1, 0.2, 0.4];
+    let zps = [0, 5, -5];
+    let q = a.quantize_per_channel::<i8>(&scales, &zps, 1)?;
+    assert_eq!(q.params().axis(), Some(1));
+    for (i, (x, q)) in a.as_raw().iter().zip(q.int_repr().as_raw()).enumerate() {
+        let c = (i / 4) % 3;
+        let expected = ((x / scales[c]).round_ties_even() as i32 + zps[c]).clamp(-128, 127);
+        assert_eq!(*q as i32, expected);
+    }
+    let d = q.dequantize()?;
+    for (x, y) in d.as_raw().iter().zip(a.as_raw().iter()) {
+        assert!((x - y).abs() <= 0.2 + 1e-6);
+    }
+    // negative axis
+    let q2 = a.quantize_per_channel::<u8>(&[0.1, 0.2, 0.3, 0.4], &[128; 4], -1)?;
+    assert_eq!(q2.params().axis(), Some(2));
+    Ok(())
+}
+
+#[test]
+fn test_quantize_errors() -> anyhow::Result<()> {
+    let a = typed(&values(6, 1.0), &[2, 3])?;
+    assert!(a.quantize_per_tensor::<i8>(0.0, 0).is_err());
+    assert!(a.quantize_per_tensor::<i8>(f32::NAN, 0).is_err());
+    assert!(a.quantize_per_tensor::<u8>(1.0, -1).is_err());
+    assert!(a.quantize_per_tensor::<i8>(1.0, 128).is_err());
+    assert!(a
+        .quantize_per_channel::<i8>(&[1.0, 1.0], &[0, 0], 1)
+        .is_err());
+    assert!(a.quantize_per_channel::<i8>(&[1.0, 1.0], &[0], 0).is_err());
+    assert!(a
+        .quantize_per_channel::<i8>(&[1.0, 1.0], &[0, 0], 2)
+        .is_err());
+    assert!(QTensor::new(
+        Tensor::<u8>::zeros(&[2, 3])?,
+        QuantParams::per_channel(vec![1.0; 3], vec![0; 3], 0)
+    )
+    .is_err());
+
+    // matmul only supports per channel weights along the output axis
+    let x = a.quantize_per_tensor::<u8>(0.1, 128)?;
+    let w = typed(&values(12, 0.1), &[3, 4])?.quantize_per_channel::<i8>(&[0.1; 3], &[0; 3], 0)?;
+    assert!(x.matmul::<i8, u8>(&w, 0.1, 0, None).is_err());
+    Ok(())
+}
+
+#[test]
+fn test_qmatmul() -> anyhow::Result<()> {
+    let (sx, zx) = (0.05, 120);
+    let sw = [0.01, 0.02, 0.015, 0.03, 0.01, 0.025];
+    let (so, zo) = (0.04, 10);
+    let x = typed(&values(2 * 5 * 8, 0.2), &[2, 5, 8])?.quantize_per_tensor::<u8>(sx, zx)?;
+    let w = typed(&values(8 * 6, 0.05), &[8, 6])?.quantize_per_channel::<i8>(&sw, &[0; 6], 1)?;
+    let reference = x.dequantize()?.matmul(&w.dequantize()?)?;
+
+    let out = x.matmul::<i8, i8>(&w, so, zo, None)?;
+    assert_eq!(out.shape().to_vec(), vec![2, 5, 6]);
+    assert_eq!(out.params().scales(), &[so]);
+    assert_close(&out, &requantize(&reference, so, zo, -128, 127));
+
+    // fused relu, the activation sees the real values
+    let out = x.matmul::<i8, u8>(&w, so, zo, Some(|x| x._relu()))?;
+    assert_close(&out, &requantize(&reference.relu()?, so, zo, 0, 255));
+    assert!(out.int_repr().as_raw().iter().all(|&q| q as i32 >= zo));
+
+    // per tensor weights, transposed lhs
+    let w = typed(&values(40, 0.05), &[5, 8])?.quantize_per_tensor::<i8>(0.02, 3)?;
+    let lhs = typed(&values(40, 0.2), &[5, 8])?;
+    let xt = lhs.t()?.quantize_per_tensor::<u8>(sx, zx)?;
+    let out = xt.matmul::<i8, u8>(&w, so, 128, None)?;
+    let reference = xt.dequantize()?.matmul(&w.dequantize()?)?;
+    assert_close(&out, &requantize(&reference, so, 128, 0, 255));
+
+    // the inner dimension isn't a multiple of the vector size
+    let x = typed(&values(3 * 37, 0.2), &[3, 37])?.quantize_per_tensor::<u8>(sx, zx)?;
+    let w = typed(&values(37 * 4, 0.05), &[37, 4])?.quantize_per_tensor::<i8>(0.02, -3)?;
+    let out = x.matmul::<i8, i8>(&w, so, zo, None)?;
+    let reference = x.dequantize()?.matmul(&w.dequantize()?)?;
+    assert_close(&out, &requantize(&reference, so, zo, -128, 127));
+    Ok(())
+}
+
+#[test]
+fn test_qconv2d() -> anyhow::Result<()> {
+    let (sx, zx) = (0.05, 5);
+    let sk = [0.01, 0.02, 0.03, 0.015, 0.025];
+    let (so, zo) = (0.05, -20);
+    let x = typed(&values(2 * 7 * 6 * 3, 0.1), &[2, 7, 6, 3])?.quantize_per_tensor::<i8>(sx, zx)?;
+    let k = typed(&values(3 * 3 * 3 * 5, 0.05), &[3, 3, 3, 5])?.quantize_per_channel::<i8>(
+        &sk,
+        &[0, 1, -1, 2, 0],
+        3,
+    )?;
+    let bias = Tensor::<i32>::new(&[-200, -50, 0, 75, 300]);
+    let float_bias =
+        Tensor::<f32>::new(&[-200.0, -50.0, 0.0, 75.0, 300.0]) * Tensor::<f32>::new(&sk) * sx;
+    for (steps, padding) in [([1, 1], [(0, 0), (0, 0)]), ([2, 2], [(1, 1), (1, 1)])] {
+        let reference = x.dequantize()?.conv2d(
+            &k.dequantize()?,
+            Some(&float_bias),
+            steps,
+            padding,
+            [1, 1],
+            None,
+        )?;
+        let out = x.conv2d::<i8, i8>(&k, Some(&bias), steps, padding, [1, 1], so, zo, None)?;
+        assert_eq!(out.shape().to_vec(), reference.shape().to_vec());
+        assert_close(&out, &requantize(&reference, so, zo, -128, 127));
+
+        let out = x.conv2d::<i8, u8>(
+            &k,
+            Some(&bias),
+            steps,
+            padding,
+            [1, 1],
+            so,
+            0,
+            Some(|x| x._relu()),
+        )?;
+        assert_close(&out, &requantize(&reference.relu()?, so, 0, 0, 255));
+    }
+
+    // dilation, no bias
+    let reference = x.dequantize()?.conv2d(
+        &k.dequantize()?,
+        None,
+        [1, 1],
+        [(2, 2), (2, 2)],
+        [2, 2],
+        None,
+    )?;
+    let out = x.conv2d::<i8, i8>(&k, None, [1, 1], [(2, 2), (2, 2)], [2, 2], so, zo, None)?;
+    assert_close(&out, &requantize(&reference, so, zo, -128, 127));
+
+    // mismatched in channels
+    let k2 = Tensor::<i8>::zeros(&[3, 3, 4, 5])?;
+    let k2 = QTensor::new(k2, QuantParams::per_tensor(0.1, 0))?;
+    assert!(x
+        .conv2d::<i8, i8>(&k2, None, [1, 1], [(0, 0), (0, 0)], [1, 1], so, zo, None)
+        .is_err());
+
+    // steps and dilation must be positive
+    for (steps, dilation) in [([0, 1], [1, 1]), ([1, -1], [1, 1]), ([1, 1], [1, 0])] {
+        assert!(x
+            .conv2d::<i8, i8>(&k, None, steps, [(0, 0), (0, 0)], dilation, so, zo, None)
+            .is_err());
+    }
+    Ok(())
+}
+
+#[test]
+fn test_quantize_int4() -> anyhow::Result<()> {
+    let w = typed(&values(8 * 3, 0.25), &[8, 3])?;
+    let q = w.quantize_int4(4)?;
+    assert_eq!(q.shape(), [8, 3]);
+    assert_eq!(q.group_size(), 4);
+    assert_eq!(q.packed().len(), 12);
+    assert_eq!(q.scales().len(), 6);
+    let d = q.dequantize()?;
+    for i in 0..8 {
+        for j in 0..3 {
+            let scale = q.scales()[j * 2 + i / 4];
+            let group = (0..4).map(|r| w.as_raw()[((i / 4) * 4 + r) * 3 + j].abs());
+            assert_eq!(scale, group.fold(0f32, f32::max) / 7.0);
+            let (x, y) = (d.as_raw()[i * 3 + j], w.as_raw()[i * 3 + j]);
+            assert!((x - y).abs() <= scale / 2.0 + 1e-6, "{} != {}", x, y);
+        }
+    }
+    // a group of zeros
+    let z = Tensor::<f32>::zeros(&[4, 2])?.quantize_int4(2)?;
+    assert!(z.dequantize()?.as_raw().iter().all(|&x| x == 0.0));
+    assert!(w.quantize_int4(3).is_err());
+    assert!(w.quantize_int4(0).is_err());
+    assert!(w.quantize_int4(16).is_err());
+    Ok(())
+}
+
+#[test]
+fn test_matmul_q4() -> anyhow::Result<()> {
+    // the group size isn't a multiple of the vector lanes, the dot products have a tail
+    for (k, group_size) in [(64, 32), (36, 6)] {
+        let w = typed(&values(k * 10, 0.05), &[k as i64, 10])?;
+        let q = w.quantize_int4(group_size)?;
+        let dw = q.dequantize()?;
+        let x = typed(&values(2 * 3 * k, 0.1), &[2, 3, k as i64])?;
+        let y = x.matmul_q4(&q)?;
+        assert_eq!(y.shape().to_vec(), vec![2, 3, 10]);
+        assert!(y.allclose(&x.matmul(&dw)?));
+
+        // non contiguous activations
+        let xt = typed(&values(k * 5, 0.1), &[k as i64, 5])?.t()?;
+        assert!(xt.matmul_q4(&q)?.allclose(&xt.matmul(&dw)?));
+
+        // f16 activations accumulate in f32
+        let xh = x.astype::<half::f16>()?;
+        let yh = xh.matmul_q4(&q)?;
+        let expected = xh.astype::<f32>()?.matmul(&dw)?;
+        for (a, b) in yh.as_raw().iter().zip(expected.as_raw().iter()) {
+            assert!((a.to_f32() - b).abs() <= 1e-2 * b.abs().max(1.0));
+        }
+    }
+    let q = Tensor::<f32>::zeros(&[8, 4])?.quantize_int4(8)?;
+    assert!(Tensor::<f32>::zeros(&[2, 6])?.matmul_q4(&q).is_err());
+    Ok(())
+}
@@ -1924,3 +1924,12 @@ fn test_uncontiguous_any2() -> anyhow::Result<()> {
     assert_eq_bool(&sum, &tch_sum);
     Ok(())
 }
+
+#[test]
+fn test_reduce_1d_contiguous() -> anyhow::Result<()> {
+    let a = hpt::tensor::Tensor::<i64>::arange(0, 100_003)?;
+    assert_eq!(a.sum(0, false)?.as_raw(), &[100_002 * 100_003 / 2]);
+    assert_eq!(a.sum(0, true)?.shape().inner(), &[1]);
+    assert_eq!(a.max(0, false)?.as_raw(), &[100_002]);
+    Ok(())
+}
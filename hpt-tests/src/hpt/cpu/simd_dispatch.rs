@@ -0,0 +1,262 @@
+#![allow(unused_imports)]
+use hpt::{
+    set_simd_level, simd_level, Conv, FloatUnaryOps, Matmul, NormalReduce, NormalUaryOps, Random,
+    ShapeManipulate, SimdLevel, Tensor, TensorCreator, TensorInfo, TensorLike,
+};
+use hpt_common::slice;
+use hpt_common::slice::Slice;
+use hpt_macros::match_selection;
+
+fn run() -> anyhow::Result<Vec<Tensor<f32>>> {
+    let a = Tensor::<f32>::arange(0, 1003)? - 500.0f32;
+    let b = Tensor::<f32>::arange(0, 1003)? * 0.5f32 + 1.0f32;
+    let m = Tensor::<f32>::arange(0, 7 * 9 * 11)?.reshape(&[7, 9, 11])?;
+    let inp = (Tensor::<f32>::arange(0, 2 * 10 * 10 * 8)? / 100.0f32).reshape(&[2, 10, 10, 8])?;
+    let kernel = (Tensor::<f32>::arange(0, 3 * 3 * 8 * 16)? / 100.0f32).reshape(&[3, 3, 8, 16])?;
+    let lhs = (Tensor::<f32>::arange(0, 37 * 53)? / 1000.0f32).reshape(&[37, 53])?;
+    let rhs = (Tensor::<f32>::arange(0, 53 * 29)? / 1000.0f32).reshape(&[53, 29])?;
+    Ok(vec![
+        a.square()?,
+        a.abs()?,
+        -&a,
+        a.relu()?,
+        b.sqrt()?,
+        b.recip()?,
+        &a + &b,
+        &a - &b,
+        &a * 2.0f32,
+        &a / &b,
+        m.sum(1, false)?,
+        m.sum(&[0, 2], false)?,
+        m.prod(2, false)? / 1e20f32,
+        m.permute(&[2, 0, 1])?.max(2, true)?,
+        m.min(0, false)?,
+        inp.conv2d(&kernel, None, [1, 1], [(1, 1), (1, 1)], [1, 1], None)?,
+        lhs.matmul(&rhs)?,
+    ])
+}
+
+fn run_f64() -> anyhow::Result<Vec<Tensor<f64>>> {
+    let a = Tensor::<f64>::arange(0, 517)? - 200.0f64;
+    let m = Tensor::<f64>::arange(0, 5 * 13 * 3)?.reshape(&[5, 13, 3])?;
+    let lhs = (Tensor::<f64>::arange(0, 19 * 67)? / 1000.0f64).reshape(&[19, 67])?;
+    let rhs = (Tensor::<f64>::arange(0, 67 * 23)? / 1000.0f64).reshape(&[67, 23])?;
+    Ok(vec![
+        a.abs()?.sqrt()?,
+        &a * &a - &a,
+        m.sum(&[0, 2], true)?,
+        m.max(1, false)?,
+        lhs.matmul(&rhs)?,
+    ])
+}
+
+fn assert_close<T>(res: &[Tensor<T>], expected: &[Tensor<T>], tol: f64)
+where
+    T: hpt::CommonBounds + Into<f64>,
+{
+    for (res, expected) in res.iter().zip(expected.iter()) {
+        assert_eq!(res.shape(), expected.shape());
+        for (&x, &y) in res.as_raw().iter().zip(expected.as_raw().iter()) {
+            let (x, y): (f64, f64) = (x.into(), y.into());
+            // the levels differ in the lane order of the reductions and in the fused multiply add
+            assert!((x - y).abs() <= y.abs() * tol + tol, "{} != {}", x, y);
+        }
+    }
+}
+
+#[test]
+fn test_simd_dispatch() -> anyhow::Result<()> {
+    let detected = SimdLevel::detected();
+    let compiled = SimdLevel::compiled();
+    assert!(detected >= compiled);
+
+    set_simd_level(compiled);
+    let expected = run()?;
+    let expected_f64 = run_f64()?;
+    for level in [SimdLevel::Sse, SimdLevel::Avx2, SimdLevel::Avx512] {
+        let applied = set_simd_level(level);
+        assert_eq!(applied, simd_level());
+        assert!(applied <= detected);
+        assert_close(&run()?, &expected, 1e-5);
+        assert_close(&run_f64()?, &expected_f64, 1e-12);
+    }
+    set_simd_level(detected);
+    Ok(())
+}
+
+#[test]
+fn test_simd_level_parse() {
+    assert_eq!(SimdLevel::parse("AVX512"), Some(SimdLevel::Avx512));
+    assert_eq!(SimdLevel::parse(" avx2 "), Some(SimdLevel::Avx2));
+    assert_eq!(SimdLevel::parse("sse"), Some(SimdLevel::Sse));
+    assert_eq!(SimdLevel::parse("avx1024"), None);
+    assert_eq!(SimdLevel::Avx512.to_string(), "avx512");
+}
+
+/// `lhs [.., m, k] x rhs [k, n]` on the raw values
+fn naive_matmul(lhs: &[f64], rhs: &[f64], m: usize, k: usize, n: usize) -> Vec<f64> {
+    let mut res = vec![0.0; lhs.len() / k * n];
+    for (r, row) in res.chunks_exact_mut(n).enumerate() {
+        for (j, res) in row.iter_mut().enumerate() {
+            *res = (0..k).map(|p| lhs[r * k + p] * rhs[p * n + j]).sum();
+        }
+    }
+    assert_eq!(res.len() % (m * n), 0);
+    res
+}
+
+/// conv2d of a NHWC input and a HWIO kernel on the raw values
+fn naive_conv2d(
+    inp: &[f64],
+    [b, h, w, ic]: [usize; 4],
+    kernel: &[f64],
+    [kh, kw, oc]: [usize; 3],
+    bias: &[f64],
+    [sh, sw, ph, pw, dh, dw]: [i64; 6],
+    [oh, ow]: [usize; 2],
+) -> Vec<f64> {
+    let mut res = vec![0.0; b * oh * ow * oc];
+    for (idx, res) in res.iter_mut().enumerate() {
+        let (n, y, x, o) = (
+            idx / (oh * ow * oc),
+            idx / (ow * oc) % oh,
+            idx / oc % ow,
+            idx % oc,
+        );
+        *res = bias[o];
+        for i in 0..kh {
+            for j in 0..kw {
+                let iy = y as i64 * sh - ph + i as i64 * dh;
+                let ix = x as i64 * sw - pw + j as i64 * dw;
+                if iy < 0 || ix < 0 || iy >= h as i64 || ix >= w as i64 {
+                    continue;
+                }
+                for c in 0..ic {
+                    *res += inp[((n * h + iy as usize) * w + ix as usize) * ic + c]
+                        * kernel[((i * kw + j) * ic + c) * oc + o];
+                }
+            }
+        }
+    }
+    res
+}
+
+fn assert_close_raw<T>(res: &Tensor<T>, expected: &[f64], tol: f64)
+where
+    T: hpt::CommonBounds + Into<f64>,
+{
+    assert_eq!(res.size(), expected.len());
+    for (&x, &y) in res.as_raw().iter().zip(expected.iter()) {
+        let x: f64 = x.into();
+        assert!((x - y).abs() <= y.abs() * tol + tol, "{} != {}", x, y);
+    }
+}
+
+fn raw<T: hpt::CommonBounds + Into<f64>>(t: &Tensor<T>) -> Vec<f64> {
+    t.contiguous()
+        .unwrap()
+        .as_raw()
+        .iter()
+        .map(|&x| x.into())
+        .collect()
+}
+
+macro_rules! check_reference {
+    ($name:ident, $t:ty) => {
+        fn $name(tol: f64) -> anyhow::Result<()> {
+            let a = Tensor::<$t>::randn(&[1031])?;
+            let b = Tensor::<$t>::randn(&[1031])?;
+            let (ra, rb) = (raw(&a), raw(&b));
+            let ew = |res: &Tensor<$t>, f: &dyn Fn(f64, f64) -> f64| {
+                let expected = ra
+                    .iter()
+                    .zip(rb.iter())
+                    .map(|(&x, &y)| f(x, y))
+                    .collect::<Vec<_>>();
+                assert_close_raw(res, &expected, tol);
+            };
+            ew(&(&a + &b), &|x, y| x + y);
+            ew(&(&a - &b), &|x, y| x - y);
+            ew(&(&a * &b), &|x, y| x * y);
+            ew(&a.square()?, &|x, _| x * x);
+            ew(&a.abs()?, &|x, _| x.abs());
+            ew(&a.relu()?, &|x, _| x.max(0.0));
+            ew(&a.abs()?.sqrt()?, &|x, _| x.abs().sqrt());
+
+            let m = Tensor::<$t>::randn(&[9, 33, 21])?;
+            let rm = raw(&m);
+            let at = |i: usize, j: usize, l: usize| rm[(i * 33 + j) * 21 + l];
+            let sum1 = (0..9)
+                .flat_map(|i| (0..21).map(move |l| (i, l)))
+                .map(|(i, l)| (0..33).map(|j| at(i, j, l)).sum::<f64>())
+                .collect::<Vec<_>>();
+            assert_close_raw(&m.sum(1, false)?, &sum1, tol * 10.0);
+            let sum2 = (0..9 * 33)
+                .map(|r| (0..21).map(|l| rm[r * 21 + l]).sum::<f64>())
+                .collect::<Vec<_>>();
+            assert_close_raw(&m.sum(2, false)?, &sum2, tol * 10.0);
+            let max02 = (0..33)
+                .map(|j| {
+                    (0..9)
+                        .flat_map(|i| (0..21).map(move |l| (i, l)))
+                        .map(|(i, l)| at(i, j, l))
+                        .fold(f64::NEG_INFINITY, f64::max)
+                })
+                .collect::<Vec<_>>();
+            assert_close_raw(&m.max(&[0, 2], false)?, &max02, 0.0);
+            let min0 = (0..33 * 21)
+                .map(|r| {
+                    (0..9)
+                        .map(|i| rm[i * 33 * 21 + r])
+                        .fold(f64::INFINITY, f64::min)
+                })
+                .collect::<Vec<_>>();
+            assert_close_raw(&m.min(0, false)?, &min0, 0.0);
+            let total = rm.iter().sum::<f64>();
+            assert_close_raw(&m.sum(&[0, 1, 2], false)?, &[total], tol * 100.0);
+
+            // larger than the blocks of the gemm in every dimension, with a transposed rhs
+            let (mm, k, n) = (131, 300, 70);
+            let lhs = Tensor::<$t>::randn(&[2, mm, k])?;
+            let rhs = Tensor::<$t>::randn(&[n, k])?.t()?;
+            let expected = naive_matmul(&raw(&lhs), &raw(&rhs), mm, k, n);
+            assert_close_raw(&lhs.matmul(&rhs)?, &expected, tol * 100.0);
+            let lhs2 = slice!(lhs[:, 1:131:2, :])?;
+            let expected = naive_matmul(&raw(&lhs2), &raw(&rhs), 65, k, n);
+            assert_close_raw(&lhs2.matmul(&rhs)?, &expected, tol * 100.0);
+
+            let (inp_shape, kernel_shape) = ([2, 11, 13, 5], [3, 2, 5, 19]);
+            let inp = Tensor::<$t>::randn(&inp_shape)?;
+            let kernel = Tensor::<$t>::randn(&kernel_shape)?;
+            let bias = Tensor::<$t>::randn(&[19])?;
+            let res = inp.conv2d(&kernel, Some(&bias), [2, 2], [(1, 1), (2, 2)], [2, 1], None)?;
+            let (oh, ow) = (res.shape()[1] as usize, res.shape()[2] as usize);
+            let expected = naive_conv2d(
+                &raw(&inp),
+                inp_shape.map(|x| x as usize),
+                &raw(&kernel),
+                [3, 2, 19],
+                &raw(&bias),
+                [2, 2, 1, 2, 2, 1],
+                [oh, ow],
+            );
+            assert_close_raw(&res, &expected, tol * 10.0);
+            Ok(())
+        }
+    };
+}
+
+check_reference!(check_reference_f32, f32);
+check_reference!(check_reference_f64, f64);
+
+#[test]
+fn test_simd_kernels_reference() -> anyhow::Result<()> {
+    let detected = SimdLevel::detected();
+    for level in [SimdLevel::Sse, SimdLevel::Avx2, SimdLevel::Avx512] {
+        set_simd_level(level);
+        check_reference_f32(1e-5)?;
+        check_reference_f64(1e-12)?;
+    }
+    set_simd_level(detected);
+    Ok(())
+}
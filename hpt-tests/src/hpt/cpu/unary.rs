@@ -196,6 +196,19 @@ fn test_sub_tensor_sin() -> anyhow::Result<()> {
     Ok(())
 }
 
+#[test]
+fn test_uncontiguous_sin() -> anyhow::Result<()> {
+    // a transposed tensor has no parent but is not contiguous
+    let a = Tensor::<f64>::arange(0, 100)?.reshape([10, 10])?;
+    let b = a.t()?.sin()?;
+    let expected = a.t()?.contiguous()?.sin()?;
+    assert_eq!(b.as_raw(), expected.as_raw());
+    let mut out = Tensor::<f64>::zeros(&[10, 10])?;
+    a.t()?.sin_(&mut out)?;
+    assert_eq!(out.as_raw(), expected.as_raw());
+    Ok(())
+}
+
 #[test]
 fn test_cast() -> anyhow::Result<()> {
     let a = Tensor::<f64>::arange(0, 100)?.reshape([10, 10])?;
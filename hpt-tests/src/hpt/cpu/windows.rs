@@ -0,0 +1,141 @@
+#![allow(unused_imports)]
+use hpt::{
+    PadMode, ShapeManipulate, Tensor, TensorCreator, TensorInfo, TensorIterator, TensorLike,
+    VecTrait,
+};
+
+/// naive 2-D box filter over the `valid` windows
+fn box_filter(a: &[f32], h: usize, w: usize, k: usize) -> Vec<f32> {
+    let (oh, ow) = (h - k + 1, w - k + 1);
+    let mut out = vec![0.0; oh * ow];
+    for i in 0..oh {
+        for j in 0..ow {
+            for ki in 0..k {
+                for kj in 0..k {
+                    out[i * ow + j] += a[(i + ki) * w + j + kj];
+                }
+            }
+        }
+    }
+    out
+}
+
+#[test]
+fn test_windows_valid() -> anyhow::Result<()> {
+    let a = Tensor::<f32>::arange(0, 7 * 9)?.reshape(&[7, 9])?;
+    let expected = box_filter(a.as_raw(), 7, 9, 3);
+    let windows = a.windows(&[3, 3], &[1, 1], &[1, 1], None);
+    assert_eq!(windows.shape().to_vec(), vec![5, 7]);
+    let res: Tensor<f32> = windows.strided_map(|w| w.iter().sum()).collect();
+    assert_eq!(res.as_raw(), expected.as_slice());
+
+    let res: Tensor<f32> = a
+        .par_windows(&[3, 3], &[1, 1], &[1, 1], None)
+        .strided_map(|w| w.iter().sum())
+        .collect();
+    assert_eq!(res.as_raw(), expected.as_slice());
+
+    let mut count = 0;
+    a.windows(&[3, 3], &[2, 3], &[2, 1], None).for_each(|w| {
+        assert!(w.is_inside());
+        assert_eq!(
+            w.get(&[2, 1]),
+            a.as_raw()[((w.origin()[0] + 4) * 9 + w.origin()[1] + 1) as usize]
+        );
+        count += 1;
+    });
+    // (7 - 5) / 2 + 1 rows, (9 - 3) / 3 + 1 columns
+    assert_eq!(count, 2 * 3);
+    Ok(())
+}
+
+#[test]
+fn test_windows_padding() -> anyhow::Result<()> {
+    let a = Tensor::<i64>::arange(1, 5)?;
+    let collect = |padding: Option<PadMode<i64>>| -> Vec<Vec<i64>> {
+        let mut windows = Vec::new();
+        a.windows(&[3], &[1], &[1], padding)
+            .for_each(|w| windows.push(w.iter().collect()));
+        windows
+    };
+    assert_eq!(
+        collect(Some(PadMode::Constant(0))),
+        vec![vec![0, 1, 2], vec![1, 2, 3], vec![2, 3, 4], vec![3, 4, 0]]
+    );
+    assert_eq!(
+        collect(Some(PadMode::Edge)),
+        vec![vec![1, 1, 2], vec![1, 2, 3], vec![2, 3, 4], vec![3, 4, 4]]
+    );
+    assert_eq!(
+        collect(Some(PadMode::Reflect)),
+        vec![vec![2, 1, 2], vec![1, 2, 3], vec![2, 3, 4], vec![3, 4, 3]]
+    );
+    assert_eq!(
+        collect(Some(PadMode::Symmetric)),
+        vec![vec![1, 1, 2], vec![1, 2, 3], vec![2, 3, 4], vec![3, 4, 4]]
+    );
+    assert_eq!(
+        collect(Some(PadMode::Circular)),
+        vec![vec![4, 1, 2], vec![1, 2, 3], vec![2, 3, 4], vec![3, 4, 1]]
+    );
+
+    // the padded modes have ceil(n / step) windows along each dimension
+    let b = Tensor::<f64>::arange(0, 5 * 6)?.reshape(&[5, 6])?;
+    let res: Tensor<f64> = b
+        .par_windows(&[3, 4], &[2, 2], &[1, 1], Some(PadMode::Edge))
+        .strided_map(|w| w.iter().fold(f64::MIN, f64::max))
+        .collect();
+    assert_eq!(res.shape().to_vec(), vec![3, 3]);
+    let max: Tensor<f64> = b
+        .windows(&[3, 4], &[2, 2], &[1, 1], Some(PadMode::Edge))
+        .strided_map(|w| {
+            let (r, c) = (w.origin()[0] + 2, w.origin()[1] + 3);
+            (r.min(4) * 6 + c.min(5)) as f64
+        })
+        .collect();
+    assert_eq!(res.as_raw(), max.as_raw());
+    Ok(())
+}
+
+#[test]
+fn test_windows_simd() -> anyhow::Result<()> {
+    let a = Tensor::<f32>::arange(0, 3 * 100)?.reshape(&[3, 100])?;
+    let weights = [0.1f32, -0.2, 0.4, -0.2, 0.1];
+    let scalar = |w: hpt::Window<'_, f32>| w.iter().zip(weights).map(|(x, k)| x * k).sum::<f32>();
+    let expected: Tensor<f32> = a
+        .windows(&[1, 5], &[1, 1], &[1, 1], Some(PadMode::Reflect))
+        .strided_map(scalar)
+        .collect();
+    let res: Tensor<f32> = a
+        .par_windows(&[1, 5], &[1, 1], &[1, 1], Some(PadMode::Reflect))
+        .strided_map_simd(scalar, |w| {
+            w.iter()
+                .zip(weights)
+                .fold(<f32 as hpt::TypeCommon>::Vec::splat(0.0), |acc, (x, k)| {
+                    x.mul_add(<f32 as hpt::TypeCommon>::Vec::splat(k), acc)
+                })
+        })
+        .collect();
+    assert_eq!(res.shape().to_vec(), vec![3, 100]);
+    assert!(res.allclose(&expected));
+
+    // non contiguous input, the lanes are gathered
+    let t = a.t()?;
+    let expected: Tensor<f32> = t
+        .windows(&[3, 1], &[2, 1], &[1, 1], None)
+        .strided_map(|w| w.iter().sum())
+        .collect();
+    let res: Tensor<f32> = t
+        .windows(&[3, 1], &[2, 1], &[1, 1], None)
+        .strided_map_simd(
+            |w| w.iter().sum(),
+            |w| {
+                w.iter()
+                    .fold(<f32 as hpt::TypeCommon>::Vec::splat(0.0), |acc, x| acc + x)
+            },
+        )
+        .collect();
+    assert_eq!(res.shape().to_vec(), vec![49, 3]);
+    assert_eq!(res.as_raw(), expected.as_raw());
+    Ok(())
+}
@@ -0,0 +1,203 @@
+#![allow(unused_imports)]
+use std::io::Cursor;
+
+use crate::utils::TempPath;
+use hpt::{
+    CompressionAlgo, Endian, Load, Save, TensorLoader, TensorReader, TensorSaver, TensorWriter,
+};
+use hpt::{ShapeManipulate, Slice, Tensor, TensorCreator, TensorInfo, TensorLike};
+
+fn write_file() -> anyhow::Result<Vec<u8>> {
+    let a = Tensor::<f32>::arange(0, 24)?.reshape(&[2, 3, 4])?;
+    let b = Tensor::<i64>::arange(0, 100)?.reshape(&[10, 10])?;
+    let mut writer = TensorWriter::new(Vec::new())?;
+    writer.write("a", a, CompressionAlgo::NoCompression, Endian::Native, 9)?;
+    writer.write("b", b, CompressionAlgo::Gzip, Endian::Big, 9)?;
+    Ok(writer.finish()?)
+}
+
+#[test]
+fn test_stream_round_trip() -> anyhow::Result<()> {
+    let bytes = write_file()?;
+    let mut reader = TensorReader::new(Cursor::new(bytes))?;
+    assert_eq!(reader.version(), hpt::FORMAT_VERSION);
+    let mut names = reader.names();
+    names.sort();
+    assert_eq!(names, vec!["a", "b"]);
+    assert_eq!(reader.info("b"), Some((&[10i64, 10][..], "i64")));
+    reader.verify()?;
+    let a = reader.read::<f32, Tensor<f32>, 4>("a", &[])?;
+    assert!(a.allclose(&Tensor::<f32>::arange(0, 24)?.reshape(&[2, 3, 4])?));
+    let b = reader.read::<i64, Tensor<i64>, 8>("b", &[Slice::Range((2, 5)), Slice::Full])?;
+    let expected = Tensor::<i64>::arange(0, 100)?.reshape(&[10, 10])?;
+    assert!(b.allclose(
+        &expected
+            .slice(&[Slice::Range((2, 5)), Slice::Full])?
+            .contiguous()?
+    ));
+    assert!(reader.read::<f32, Tensor<f32>, 4>("c", &[]).is_err());
+    assert!(reader.read::<i32, Tensor<i32>, 4>("a", &[]).is_err());
+    Ok(())
+}
+
+#[test]
+fn test_corrupted_chunk() -> anyhow::Result<()> {
+    let mut bytes = write_file()?;
+    // the first chunk of `a` is aligned to 64 bytes
+    bytes[64] ^= 0xff;
+    let path = TempPath::new("fasttensor_corrupted.ftz");
+    std::fs::write(&path, &bytes)?;
+    let err = hpt::verify(&path).unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+    assert!(err.to_string().contains("checksum mismatch"));
+    let mut reader = TensorReader::new(Cursor::new(bytes))?;
+    let err = reader.read::<f32, Tensor<f32>, 4>("a", &[]).unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+    // the other tensor is still readable
+    assert!(reader.read::<i64, Tensor<i64>, 8>("b", &[]).is_ok());
+    Ok(())
+}
+
+#[test]
+fn test_corrupted_header() -> anyhow::Result<()> {
+    let mut bytes = write_file()?;
+    let len = bytes.len();
+    // the last byte of the json header
+    bytes[len - 41] = b' ';
+    let err = TensorReader::new(Cursor::new(bytes)).err().unwrap();
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+    Ok(())
+}
+
+#[test]
+fn test_truncated() -> anyhow::Result<()> {
+    let bytes = write_file()?;
+    for len in [0, 20, 100, bytes.len() - 1] {
+        let err = TensorReader::new(Cursor::new(bytes[..len].to_vec()))
+            .err()
+            .unwrap();
+        assert_eq!(err.kind(), std::io::ErrorKind::UnexpectedEof);
+    }
+    Ok(())
+}
+
+#[test]
+fn test_version_1() -> anyhow::Result<()> {
+    let bytes = write_file()?;
+    // convert to version 1: header offset in the prefix, no trailer
+    let trailer = &bytes[bytes.len() - 40..];
+    let offset = std::str::from_utf8(&trailer[..20])?
+        .trim()
+        .parse::<usize>()?;
+    let mut v1 = format!("FASTTENSOR{:20}", offset).into_bytes();
+    v1.extend_from_slice(&bytes[30..bytes.len() - 40]);
+    let mut reader = TensorReader::new(Cursor::new(v1))?;
+    assert_eq!(reader.version(), 1);
+    let a = reader.read::<f32, Tensor<f32>, 4>("a", &[])?;
+    assert!(a.allclose(&Tensor::<f32>::arange(0, 24)?.reshape(&[2, 3, 4])?));
+
+    let mut future = bytes.clone();
+    future[10..30].copy_from_slice(format!("{:>20}", "v99").as_bytes());
+    let err = TensorReader::new(Cursor::new(future)).err().unwrap();
+    assert_eq!(err.kind(), std::io::ErrorKind::Unsupported);
+    Ok(())
+}
+
+#[test]
+fn test_duplicate_name() -> anyhow::Result<()> {
+    let a = Tensor::<f32>::arange(0, 4)?;
+    let mut writer = TensorWriter::new(Vec::new())?;
+    writer.write("a", a.clone(), CompressionAlgo::Zlib, Endian::Little, 9)?;
+    let err = writer
+        .write("a", a, CompressionAlgo::Zlib, Endian::Little, 9)
+        .unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+    Ok(())
+}
+
+#[test]
+fn test_saver_loader_with_streams() -> anyhow::Result<()> {
+    let a = Tensor::<f64>::arange(0, 3000)?.reshape(&[30, 100])?;
+    let bytes = TensorSaver::new("unused")
+        .push("a", a.clone(), CompressionAlgo::Deflate, Endian::Little, 5)
+        .save_to(Vec::new())?;
+    let loaded = TensorLoader::new("unused")
+        .push("a", &[])
+        .load_from::<f64, Tensor<f64>, _, 8>(Cursor::new(bytes.clone()))?;
+    assert!(loaded["a"].allclose(&a));
+    let loaded =
+        TensorLoader::new("unused").load_all_from::<f64, Tensor<f64>, _, 8>(Cursor::new(bytes))?;
+    assert!(loaded["a"].allclose(&a));
+
+    let path = TempPath::new("fasttensor_saver.ftz");
+    TensorSaver::new(&path)
+        .push("a", a.clone(), CompressionAlgo::Gzip, Endian::Native, 9)
+        .save()?;
+    hpt::verify(&path)?;
+    let loaded = TensorLoader::new(&path).load_all::<f64, Tensor<f64>, 8>()?;
+    assert!(loaded["a"].allclose(&a));
+    Ok(())
+}
+
+#[derive(Save, Load)]
+struct Linear {
+    #[compress(algo = "gzip", level = "9", endian = "little")]
+    weight: Tensor<f32>,
+    bias: Tensor<f32>,
+    name: String,
+}
+
+#[test]
+fn test_struct_save_load() -> anyhow::Result<()> {
+    let linear = Linear {
+        weight: Tensor::<f32>::arange(0, 12)?.reshape(&[3, 4])?,
+        bias: Tensor::<f32>::arange(0, 4)?,
+        name: "fc".to_string(),
+    };
+    let path = TempPath::new("fasttensor_struct.ftz");
+    linear.save(path.to_str().unwrap())?;
+    hpt::verify(&path)?;
+    let reader = TensorReader::new(std::fs::File::open(&path)?)?;
+    assert_eq!(reader.version(), hpt::FORMAT_VERSION);
+    assert_eq!(reader.names().len(), 2);
+    let loaded = Linear::load(path.to_str().unwrap())?;
+    assert!(loaded.weight.allclose(&linear.weight));
+    assert!(loaded.bias.allclose(&linear.bias));
+    assert_eq!(loaded.name, "fc");
+
+    // `bias` is stored without compression in native endian
+    let mut bytes = std::fs::read(&path)?;
+    let bias = [0.0f32, 1.0, 2.0, 3.0]
+        .iter()
+        .flat_map(|x| x.to_ne_bytes())
+        .collect::<Vec<u8>>();
+    let pos = bytes
+        .windows(bias.len())
+        .position(|x| x == bias)
+        .expect("bias is stored");
+    bytes[pos + 4] ^= 0xff;
+    std::fs::write(&path, &bytes)?;
+    let err = hpt::verify(&path).unwrap_err();
+    assert!(err.to_string().contains("checksum mismatch"));
+    assert!(Linear::load(path.to_str().unwrap()).is_err());
+    Ok(())
+}
+
+#[test]
+fn test_no_empty_chunk() -> anyhow::Result<()> {
+    // 1023 lines of 1024 bytes fill a chunk, 2046 lines fill exactly two chunks
+    let a = Tensor::<f32>::arange(0, 2046 * 256)?.reshape(&[2046, 256])?;
+    let mut writer = TensorWriter::new(Vec::new())?;
+    writer.write("a", a.clone(), CompressionAlgo::Gzip, Endian::Native, 1)?;
+    let bytes = writer.finish()?;
+    let trailer = &bytes[bytes.len() - 40..];
+    let offset = std::str::from_utf8(&trailer[..20])?
+        .trim()
+        .parse::<usize>()?;
+    let header: serde_json::Value = serde_json::from_slice(&bytes[offset..bytes.len() - 40])?;
+    assert_eq!(header["a"]["indices"].as_array().unwrap().len(), 2);
+    let mut reader = TensorReader::new(Cursor::new(bytes))?;
+    reader.verify()?;
+    assert!(reader.read::<f32, Tensor<f32>, 4>("a", &[])?.allclose(&a));
+    Ok(())
+}
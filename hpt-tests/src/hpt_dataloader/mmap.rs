@@ -0,0 +1,111 @@
+#![allow(unused_imports)]
+use crate::utils::TempPath;
+use hpt::{CompressionAlgo, Endian, MmapLoader, TensorSaver, ToSafeTensors};
+use hpt::{ShapeManipulate, Tensor, TensorCreator, TensorInfo, TensorLike};
+
+#[test]
+fn test_mmap_fasttensor() -> anyhow::Result<()> {
+    let a = Tensor::<f32>::arange(0, 24)?.reshape(&[2, 3, 4])?;
+    let b = Tensor::<i64>::arange(0, 7)?;
+    let c = Tensor::<f64>::arange(0, 100)?.reshape(&[10, 10])?;
+    let d = Tensor::<i32>::arange(0, 9)?.reshape(&[3, 3])?.t()?;
+    let path = TempPath::new("mmap_fasttensor.ftz");
+    TensorSaver::new(&path)
+        .push(
+            "a",
+            a.clone(),
+            CompressionAlgo::NoCompression,
+            Endian::Native,
+            9,
+        )
+        .push(
+            "b",
+            b.clone(),
+            CompressionAlgo::NoCompression,
+            Endian::Native,
+            9,
+        )
+        .push("c", c.clone(), CompressionAlgo::Gzip, Endian::Native, 9)
+        .push(
+            "d",
+            d.clone(),
+            CompressionAlgo::NoCompression,
+            Endian::Big,
+            9,
+        )
+        .save()?;
+
+    let loader = MmapLoader::open(&path)?;
+    let mut names = loader.names();
+    names.sort();
+    assert_eq!(names, vec!["a", "b", "c", "d"]);
+    assert!(loader.is_viewable::<f32>("a"));
+    assert!(loader.is_viewable::<i64>("b"));
+    assert!(!loader.is_viewable::<f64>("c"));
+    assert!(!loader.is_viewable::<i32>("d"));
+    assert!(loader.load::<i32, Tensor<i32>, 4>("a").is_err());
+    assert!(loader.load::<f32, Tensor<f32>, 4>("e").is_err());
+
+    assert!(unsafe { loader.view::<f64, Tensor<f64>>("c") }.is_err());
+    assert!(unsafe { loader.view::<i32, Tensor<i32>>("d") }.is_err());
+
+    let loaded_a = unsafe { loader.view::<f32, Tensor<f32>>("a")? };
+    let loaded_b = unsafe { loader.view::<i64, Tensor<i64>>("b")? };
+    let loaded_c = loader.load::<f64, Tensor<f64>, 8>("c")?;
+    let loaded_d = loader.load::<i32, Tensor<i32>, 4>("d")?;
+    // the views keep the mapping alive, even once the file is removed
+    drop(loader);
+    std::fs::remove_file(&path)?;
+    let view = loaded_a.permute(&[2, 0, 1])?;
+    drop(loaded_a);
+    assert!(view.allclose(&a.permute(&[2, 0, 1])?));
+    assert!(loaded_b.allclose(&b));
+    assert!(loaded_c.allclose(&c));
+    assert!(loaded_d.allclose(&d.contiguous()?));
+    Ok(())
+}
+
+#[test]
+fn test_mmap_load_copies() -> anyhow::Result<()> {
+    let a = Tensor::<f32>::arange(0, 16)?;
+    let path = TempPath::new("mmap_load.ftz");
+    TensorSaver::new(&path)
+        .push(
+            "a",
+            a.clone(),
+            CompressionAlgo::NoCompression,
+            Endian::Native,
+            9,
+        )
+        .save()?;
+    let loader = MmapLoader::open(&path)?;
+    let view = unsafe { loader.view::<f32, Tensor<f32>>("a")? };
+    let mut loaded = loader.load::<f32, Tensor<f32>, 4>("a")?;
+    assert_ne!(loaded.as_raw().as_ptr(), view.as_raw().as_ptr());
+    loaded.as_raw_mut().iter_mut().for_each(|x| *x = -1.0);
+    // the loaded tensors own their data, writing to them doesn't touch the mapping or the file
+    let reloaded = MmapLoader::open(&path)?.load::<f32, Tensor<f32>, 4>("a")?;
+    assert!(view.allclose(&a));
+    assert!(reloaded.allclose(&a));
+    assert!(loaded.allclose(&Tensor::<f32>::full(-1.0, &[16])?));
+    Ok(())
+}
+
+#[test]
+fn test_mmap_safetensors() -> anyhow::Result<()> {
+    let a = Tensor::<f32>::arange(0, 12)?.reshape(&[3, 4])?;
+    let path = TempPath::new("mmap_model.safetensors");
+    vec![a.clone(), a.t()?].save_safetensors(&path, "w", None)?;
+    let loader = MmapLoader::open(&path)?;
+    assert_eq!(
+        loader.entry("w.1").map(|x| x.shape.clone()),
+        Some(vec![4, 3])
+    );
+    assert!(loader.is_viewable::<f32>("w.0"));
+    let w0 = unsafe { loader.view::<f32, Tensor<f32>>("w.0")? };
+    let w1 = loader.load::<f32, Tensor<f32>, 4>("w.1")?;
+    drop(loader);
+    assert!(w0.allclose(&a));
+    assert!(w1.allclose(&a.t()?.contiguous()?));
+    Ok(())
+}
@@ -0,0 +1,124 @@
+#![allow(unused_imports)]
+use std::io::Write;
+
+use crate::utils::TempPath;
+use hpt::{Endian, ShapeManipulate, Tensor, TensorCreator, TensorInfo, TensorLike};
+use hpt::{NpyHeader, NpzReader, NpzWriter};
+
+fn npy_bytes(dict: &str, data: &[u8]) -> Vec<u8> {
+    let mut header = dict.to_string();
+    while (10 + header.len() + 1) % 64 != 0 {
+        header.push(' ');
+    }
+    header.push('\n');
+    let mut bytes = b"\x93NUMPY\x01\x00".to_vec();
+    bytes.extend_from_slice(&(header.len() as u16).to_le_bytes());
+    bytes.extend_from_slice(header.as_bytes());
+    bytes.extend_from_slice(data);
+    bytes
+}
+
+#[test]
+fn test_npy_header() -> anyhow::Result<()> {
+    let header =
+        NpyHeader::parse("{'descr': '>i2', 'fortran_order': True, 'shape': (2, 3), }    \n")?;
+    assert_eq!(header.descr, ">i2");
+    assert!(header.fortran_order);
+    assert_eq!(header.shape, vec![2, 3]);
+    assert!(matches!(header.endian()?, Endian::Big));
+    assert_eq!(header.kind()?, ('i', 2));
+    let scalar = NpyHeader::parse("{'descr': '|b1', 'fortran_order': False, 'shape': (), }")?;
+    assert!(scalar.shape.is_empty());
+    assert!(NpyHeader::parse("{'descr': '<f4', 'shape': (2,), }").is_err());
+    Ok(())
+}
+
+#[test]
+fn test_load_npy_c_order() -> anyhow::Result<()> {
+    let data = (0..6)
+        .flat_map(|x| (x as f32).to_le_bytes())
+        .collect::<Vec<u8>>();
+    let path = TempPath::new("npy_c_order.npy");
+    std::fs::write(
+        &path,
+        npy_bytes(
+            "{'descr': '<f4', 'fortran_order': False, 'shape': (2, 3), }",
+            &data,
+        ),
+    )?;
+    let a = Tensor::<f32>::load_npy(&path)?;
+    assert_eq!(a.shape().inner(), &vec![2, 3]);
+    assert!(a.allclose(&Tensor::<f32>::arange(0, 6)?.reshape(&[2, 3])?));
+    Ok(())
+}
+
+#[test]
+fn test_load_npy_fortran_big_endian() -> anyhow::Result<()> {
+    // column major [[0, 1, 2], [3, 4, 5]]
+    let data = [0i32, 3, 1, 4, 2, 5]
+        .iter()
+        .flat_map(|x| x.to_be_bytes())
+        .collect::<Vec<u8>>();
+    let path = TempPath::new("npy_fortran.npy");
+    std::fs::write(
+        &path,
+        npy_bytes(
+            "{'descr': '>i4', 'fortran_order': True, 'shape': (2, 3), }",
+            &data,
+        ),
+    )?;
+    let a = Tensor::<i32>::load_npy(&path)?;
+    assert!(Tensor::<f32>::load_npy(&path).is_err());
+    assert_eq!(a.shape().inner(), &vec![2, 3]);
+    assert_eq!(a.strides().inner(), &vec![1, 2]);
+    assert!(a.allclose(&Tensor::<i32>::arange(0, 6)?.reshape(&[2, 3])?));
+    Ok(())
+}
+
+#[test]
+fn test_save_npy_round_trip() -> anyhow::Result<()> {
+    let a = Tensor::<f64>::arange(0, 24)?.reshape(&[2, 3, 4])?;
+    let path = TempPath::new("npy_round_trip.npy");
+    a.save_npy(&path)?;
+    let b = Tensor::<f64>::load_npy(&path)?;
+    assert!(b.allclose(&a));
+
+    // fortran contiguous tensor is saved with fortran order
+    let t = a.permute(&[2, 1, 0])?;
+    t.save_npy(&path)?;
+    let b = Tensor::<f64>::load_npy(&path)?;
+    assert_eq!(b.strides().inner(), t.strides().inner());
+    assert!(b.allclose(&t.contiguous()?));
+
+    // other strided tensor is saved in c order
+    let s = a.permute(&[1, 0, 2])?;
+    s.save_npy(&path)?;
+    let b = Tensor::<f64>::load_npy(&path)?;
+    assert!(b.is_contiguous());
+    assert!(b.allclose(&s.contiguous()?));
+    Ok(())
+}
+
+#[test]
+fn test_npz() -> anyhow::Result<()> {
+    let a = Tensor::<f32>::arange(0, 12)?.reshape(&[3, 4])?;
+    let b = Tensor::<i64>::arange(0, 5)?;
+    for compressed in [false, true] {
+        let path = TempPath::new(&format!("npy_arrays_{}.npz", compressed));
+        let mut writer = NpzWriter::create(&path, compressed)?;
+        writer.add_array("a", a.t()?, Endian::Little)?;
+        writer.add_array("b", b.clone(), Endian::Big)?;
+        writer.finish()?;
+
+        let mut reader = NpzReader::open(&path)?;
+        let mut names = reader.names();
+        names.sort();
+        assert_eq!(names, vec!["a".to_string(), "b".to_string()]);
+        assert!(reader.header("a")?.fortran_order);
+        let loaded_a = reader.by_name::<f32, Tensor<f32>, 4>("a")?;
+        let loaded_b = reader.by_name::<i64, Tensor<i64>, 8>("b.npy")?;
+        assert!(loaded_a.allclose(&a.t()?.contiguous()?));
+        assert!(loaded_b.allclose(&b));
+    }
+    Ok(())
+}
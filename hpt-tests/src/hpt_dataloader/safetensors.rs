@@ -0,0 +1,110 @@
+#![allow(unused_imports)]
+use std::collections::HashMap;
+
+use crate::utils::TempPath;
+use half::{bf16, f16};
+use hpt::{Cast, ToSafeTensors};
+use hpt::{FromSafeTensors, ShapeManipulate, Tensor, TensorCreator, TensorInfo, TensorLike};
+use hpt_macros::FromSafeTensors;
+use safetensors::{Dtype, SafeTensors};
+
+#[derive(hpt::ToSafeTensors, FromSafeTensors)]
+struct Linear {
+    weight: Tensor<f32>,
+    bias: Option<Tensor<f32>>,
+}
+
+#[derive(hpt::ToSafeTensors, FromSafeTensors)]
+struct Model {
+    fc1: Linear,
+    fc2: Linear,
+    #[map(tensor_name = "embedding")]
+    embed: Tensor<bf16>,
+    #[map(value = 0.5)]
+    scale: f32,
+}
+
+#[test]
+fn test_save_safetensors() -> anyhow::Result<()> {
+    // non contiguous tensor is written in C order
+    let b = Tensor::<f32>::arange(0, 12)?.reshape(&[3, 4])?.t()?;
+    let path = TempPath::new("safetensors_tensors.safetensors");
+    let mut metadata = HashMap::new();
+    metadata.insert("format".to_string(), "pt".to_string());
+    b.save_safetensors(&path, "b", Some(metadata))?;
+
+    let buffer = std::fs::read(&path)?;
+    let (_, meta) = SafeTensors::read_metadata(&buffer)?;
+    assert_eq!(
+        meta.metadata().as_ref().and_then(|x| x.get("format")),
+        Some(&"pt".to_string())
+    );
+    let data = SafeTensors::deserialize(&buffer)?;
+    let view = data.tensor("b")?;
+    assert_eq!(view.dtype(), Dtype::F32);
+    assert_eq!(view.shape(), &[4, 3]);
+    let loaded = Tensor::<f32>::from_safe_tensors(&data, "b");
+    assert!(loaded.is_contiguous());
+    assert!(loaded.allclose(&b.contiguous()?));
+    Ok(())
+}
+
+#[test]
+fn test_save_safetensors_vec() -> anyhow::Result<()> {
+    let a = Tensor::<f16>::arange(0, 12)?.reshape(&[3, 4])?;
+    let path = TempPath::new("safetensors_vec.safetensors");
+    vec![a.clone(), a.clone()].save_safetensors(&path, "a", None)?;
+    let buffer = std::fs::read(&path)?;
+    let data = SafeTensors::deserialize(&buffer)?;
+    let mut names = data.names();
+    names.sort();
+    assert_eq!(names, vec!["a.0", "a.1"]);
+    assert_eq!(data.tensor("a.1")?.dtype(), Dtype::F16);
+    let loaded = Tensor::<f16>::from_safe_tensors(&data, "a.1");
+    assert_eq!(loaded.as_raw(), a.as_raw());
+    Ok(())
+}
+
+#[test]
+fn test_derive_to_safetensors() -> anyhow::Result<()> {
+    let model = Model {
+        fc1: Linear {
+            weight: Tensor::<f32>::arange(0, 6)?.reshape(&[2, 3])?.t()?,
+            bias: Some(Tensor::<f32>::arange(0, 2)?),
+        },
+        fc2: Linear {
+            weight: Tensor::<f32>::arange(0, 4)?.reshape(&[2, 2])?,
+            bias: None,
+        },
+        embed: Tensor::<f32>::arange(0, 8)?.astype::<bf16>()?,
+        scale: 0.5,
+    };
+    let path = TempPath::new("safetensors_model.safetensors");
+    model.save_safetensors(&path, "model", None)?;
+    let buffer = std::fs::read(&path)?;
+    let data = SafeTensors::deserialize(&buffer)?;
+    let mut names = data.names();
+    names.sort();
+    assert_eq!(
+        names,
+        vec![
+            "embedding",
+            "model.fc1.bias",
+            "model.fc1.weight",
+            "model.fc2.weight"
+        ]
+    );
+    assert_eq!(data.tensor("embedding")?.dtype(), Dtype::BF16);
+
+    let fc1 = Linear::from_safe_tensors(&data, "model.fc1");
+    assert!(fc1.weight.allclose(&model.fc1.weight.contiguous()?));
+    assert!(fc1
+        .bias
+        .expect("bias is saved")
+        .allclose(&Tensor::<f32>::arange(0, 2)?));
+    // `scale` is not a tensor, so it is not saved
+    assert_eq!(model.scale, 0.5);
+    let embed = Tensor::<bf16>::from_safe_tensors(&data, "embedding");
+    assert_eq!(embed.as_raw(), model.embed.as_raw());
+    Ok(())
+}
@@ -0,0 +1,444 @@
+#![allow(unused_imports)]
+use crate::utils::{typed, values};
+use hpt::{
+    Dtype, DynTensor, FloatUnaryOps, Matmul, NormalReduce, NormalUaryOps, ShapeManipulate, Tensor,
+    TensorCreator, TensorInfo, TensorLike,
+};
+use hpt_onnx::{OnnxError, OnnxModel};
+
+/// minimal protobuf writer for building the test models
+fn varint(buf: &mut Vec<u8>, mut v: u64) {
+    while v >= 0x80 {
+        buf.push((v as u8) | 0x80);
+        v >>= 7;
+    }
+    buf.push(v as u8);
+}
+
+fn int(buf: &mut Vec<u8>, field: u64, v: i64) {
+    varint(buf, field << 3);
+    varint(buf, v as u64);
+}
+
+fn bytes(buf: &mut Vec<u8>, field: u64, v: &[u8]) {
+    varint(buf, (field << 3) | 2);
+    varint(buf, v.len() as u64);
+    buf.extend_from_slice(v);
+}
+
+fn f32_tensor(name: &str, dims: &[i64], data: &[f32]) -> Vec<u8> {
+    let mut buf = Vec::new();
+    for &d in dims {
+        int(&mut buf, 1, d);
+    }
+    int(&mut buf, 2, 1);
+    bytes(&mut buf, 8, name.as_bytes());
+    let raw = data
+        .iter()
+        .flat_map(|x| x.to_le_bytes())
+        .collect::<Vec<_>>();
+    bytes(&mut buf, 9, &raw);
+    buf
+}
+
+fn i64_tensor(name: &str, dims: &[i64], data: &[i64]) -> Vec<u8> {
+    let mut buf = Vec::new();
+    for &d in dims {
+        int(&mut buf, 1, d);
+    }
+    int(&mut buf, 2, 7);
+    for &x in data {
+        int(&mut buf, 7, x);
+    }
+    bytes(&mut buf, 8, name.as_bytes());
+    buf
+}
+
+fn i8_tensor(name: &str, dims: &[i64], data: &[i8]) -> Vec<u8> {
+    let mut buf = Vec::new();
+    for &d in dims {
+        int(&mut buf, 1, d);
+    }
+    int(&mut buf, 2, 3);
+    for &x in data {
+        int(&mut buf, 5, x as i64);
+    }
+    bytes(&mut buf, 8, name.as_bytes());
+    buf
+}
+
+fn attr_int(name: &str, v: i64) -> Vec<u8> {
+    let mut buf = Vec::new();
+    bytes(&mut buf, 1, name.as_bytes());
+    int(&mut buf, 3, v);
+    int(&mut buf, 20, 2);
+    buf
+}
+
+fn attr_ints(name: &str, v: &[i64]) -> Vec<u8> {
+    let mut buf = Vec::new();
+    bytes(&mut buf, 1, name.as_bytes());
+    for &x in v {
+        int(&mut buf, 8, x);
+    }
+    int(&mut buf, 20, 7);
+    buf
+}
+
+fn attr_tensor(name: &str, t: Vec<u8>) -> Vec<u8> {
+    let mut buf = Vec::new();
+    bytes(&mut buf, 1, name.as_bytes());
+    bytes(&mut buf, 5, &t);
+    int(&mut buf, 20, 4);
+    buf
+}
+
+fn node(op: &str, domain: &str, inputs: &[&str], outputs: &[&str], attrs: &[Vec<u8>]) -> Vec<u8> {
+    let mut buf = Vec::new();
+    for i in inputs {
+        bytes(&mut buf, 1, i.as_bytes());
+    }
+    for o in outputs {
+        bytes(&mut buf, 2, o.as_bytes());
+    }
+    bytes(&mut buf, 3, format!("{}_{}", op, outputs[0]).as_bytes());
+    bytes(&mut buf, 4, op.as_bytes());
+    for a in attrs {
+        bytes(&mut buf, 5, a);
+    }
+    if !domain.is_empty() {
+        bytes(&mut buf, 7, domain.as_bytes());
+    }
+    buf
+}
+
+fn model(nodes: &[Vec<u8>], inits: &[Vec<u8>], inputs: &[&str], outputs: &[&str]) -> Vec<u8> {
+    let mut graph = Vec::new();
+    for n in nodes {
+        bytes(&mut graph, 1, n);
+    }
+    bytes(&mut graph, 2, b"test");
+    for t in inits {
+        bytes(&mut graph, 5, t);
+    }
+    for (field, names) in [(11, inputs), (12, outputs)] {
+        for name in names {
+            let mut info = Vec::new();
+            bytes(&mut info, 1, name.as_bytes());
+            bytes(&mut graph, field, &info);
+        }
+    }
+    let mut opset = Vec::new();
+    int(&mut opset, 2, 17);
+    let mut buf = Vec::new();
+    int(&mut buf, 1, 8);
+    bytes(&mut buf, 7, &graph);
+    bytes(&mut buf, 8, &opset);
+    buf
+}
+
+#[test]
+fn test_onnx_mlp() -> anyhow::Result<()> {
+    let (w, b) = (values(12, 0.1), values(4, 0.2));
+    let bytes = model(
+        &[
+            node("MatMul", "", &["x", "w"], &["h"], &[]),
+            node("Add", "", &["h", "b"], &["a"], &[]),
+            node("Relu", "", &["a"], &["r"], &[]),
+            node("Softmax", "", &["r"], &["y"], &[attr_int("axis", -1)]),
+        ],
+        &[f32_tensor("w", &[3, 4], &w), f32_tensor("b", &[4], &b)],
+        &["x", "w", "b"],
+        &["y"],
+    );
+    let model = OnnxModel::from_bytes(&bytes)?;
+    assert!(model.unsupported_ops().is_empty());
+    assert_eq!(model.inputs().len(), 1);
+
+    let x = typed(&values(6, 0.3), &[2, 3])?;
+    let out = model.run([("x", DynTensor::from(x.clone()))])?;
+    let y = out["y"].downcast::<f32>()?;
+    let expected = (x.matmul(&typed(&w, &[3, 4])?)? + typed(&b, &[4])?)
+        .relu()?
+        .softmax(-1)?;
+    assert_eq!(y.shape().to_vec(), vec![2, 4]);
+    assert!(y.allclose(&expected));
+    Ok(())
+}
+
+/// naive NCHW convolution with a stride of 1
+fn conv_ref(
+    x: &[f32],
+    w: &[f32],
+    b: &[f32],
+    c: usize,
+    hw: usize,
+    m: usize,
+    pad: usize,
+) -> Vec<f32> {
+    let k = 3;
+    let out_hw = hw + 2 * pad - k + 1;
+    let mut out = vec![0.0; m * out_hw * out_hw];
+    for o in 0..m {
+        for i in 0..out_hw {
+            for j in 0..out_hw {
+                let mut acc = b[o];
+                for ci in 0..c {
+                    for ki in 0..k {
+                        for kj in 0..k {
+                            let (h, v) =
+                                ((i + ki) as i64 - pad as i64, (j + kj) as i64 - pad as i64);
+                            if h < 0 || v < 0 || h >= hw as i64 || v >= hw as i64 {
+                                continue;
+                            }
+                            let xv = x[(ci * hw + h as usize) * hw + v as usize];
+                            acc += xv * w[((o * c + ci) * k + ki) * k + kj];
+                        }
+                    }
+                }
+                out[(o * out_hw + i) * out_hw + j] = acc;
+            }
+        }
+    }
+    out
+}
+
+#[test]
+fn test_onnx_conv_pool_nchw() -> anyhow::Result<()> {
+    let (c, hw, m) = (2, 4, 3);
+    let (w, b) = (values(m * c * 9, 0.1), values(m, 0.5));
+    let bytes = model(
+        &[
+            node(
+                "Conv",
+                "",
+                &["x", "w", "b"],
+                &["c"],
+                &[
+                    attr_ints("kernel_shape", &[3, 3]),
+                    attr_ints("pads", &[1, 1, 1, 1]),
+                ],
+            ),
+            node(
+                "MaxPool",
+                "",
+                &["c"],
+                &["p"],
+                &[
+                    attr_ints("kernel_shape", &[2, 2]),
+                    attr_ints("strides", &[2, 2]),
+                ],
+            ),
+            node("GlobalAveragePool", "", &["c"], &["g"], &[]),
+        ],
+        &[
+            f32_tensor("w", &[m as i64, c as i64, 3, 3], &w),
+            f32_tensor("b", &[m as i64], &b),
+        ],
+        &["x"],
+        &["c", "p", "g"],
+    );
+    let model = OnnxModel::from_bytes(&bytes)?;
+    let x = values(c * hw * hw, 0.25);
+    let out = model.run([("x", DynTensor::from(typed(&x, &[1, 2, 4, 4])?))])?;
+
+    let expected = conv_ref(&x, &w, &b, c, hw, m, 1);
+    let conv = out["c"].downcast::<f32>()?;
+    assert_eq!(conv.shape().to_vec(), vec![1, 3, 4, 4]);
+    assert!(conv.allclose(&typed(&expected, &[1, 3, 4, 4])?));
+
+    let pooled = out["p"].downcast::<f32>()?;
+    assert_eq!(pooled.shape().to_vec(), vec![1, 3, 2, 2]);
+    let mut max = vec![f32::MIN; 12];
+    for o in 0..3 {
+        for i in 0..4 {
+            for j in 0..4 {
+                let idx = (o * 2 + i / 2) * 2 + j / 2;
+                max[idx] = max[idx].max(expected[(o * 4 + i) * 4 + j]);
+            }
+        }
+    }
+    assert!(pooled.allclose(&typed(&max, &[1, 3, 2, 2])?));
+
+    let global = out["g"].downcast::<f32>()?;
+    assert_eq!(global.shape().to_vec(), vec![1, 3, 1, 1]);
+    let mean = (0..3)
+        .map(|o| expected[o * 16..(o + 1) * 16].iter().sum::<f32>() / 16.0)
+        .collect::<Vec<_>>();
+    assert!(global.allclose(&typed(&mean, &[1, 3, 1, 1])?));
+    Ok(())
+}
+
+#[test]
+fn test_onnx_shape_ops() -> anyhow::Result<()> {
+    let bytes = model(
+        &[
+            node(
+                "Constant",
+                "",
+                &[],
+                &["shape"],
+                &[attr_tensor("value", i64_tensor("", &[3], &[0, -1, 2]))],
+            ),
+            node("Reshape", "", &["x", "shape"], &["r"], &[]),
+            node(
+                "Transpose",
+                "",
+                &["r"],
+                &["t"],
+                &[attr_ints("perm", &[2, 0, 1])],
+            ),
+            node(
+                "ReduceSum",
+                "",
+                &["t", "axes"],
+                &["s"],
+                &[attr_int("keepdims", 0)],
+            ),
+            node("Cast", "", &["s"], &["i"], &[attr_int("to", 7)]),
+            node("Div", "", &["i", "two"], &["d"], &[]),
+            node("Shape", "", &["t"], &["shape_t"], &[]),
+        ],
+        &[i64_tensor("axes", &[1], &[1]), i64_tensor("two", &[], &[2])],
+        &["x"],
+        &["t", "d", "shape_t"],
+    );
+    let model = OnnxModel::from_bytes(&bytes)?;
+    let x = Tensor::<f32>::arange(0, 24)?.reshape(&[2, 3, 4])?;
+    let out = model.run([("x", DynTensor::from(x.clone()))])?;
+
+    let t = x.reshape(&[2, 6, 2])?.permute(&[2, 0, 1])?;
+    assert!(out["t"].downcast::<f32>()?.allclose(&t));
+    let d = out["d"].downcast::<i64>()?;
+    assert_eq!(d.shape().to_vec(), vec![2, 6]);
+    let sums = t.sum(1, false)?.contiguous()?;
+    for (got, sum) in d.contiguous()?.as_raw().iter().zip(sums.as_raw()) {
+        assert_eq!(*got, *sum as i64 / 2);
+    }
+    assert_eq!(out["shape_t"].dtype(), Dtype::I64);
+    assert_eq!(
+        out["shape_t"].downcast::<i64>()?.as_raw().to_vec(),
+        vec![2, 2, 6]
+    );
+    Ok(())
+}
+
+#[test]
+fn test_onnx_flatten_axis() -> anyhow::Result<()> {
+    let flatten = |axis: i64| -> anyhow::Result<OnnxModel> {
+        let bytes = model(
+            &[node("Flatten", "", &["x"], &["y"], &[attr_int("axis", axis)])],
+            &[],
+            &["x"],
+            &["y"],
+        );
+        Ok(OnnxModel::from_bytes(&bytes)?)
+    };
+    let x = Tensor::<f32>::arange(0, 24)?.reshape(&[2, 3, 4])?;
+    for (axis, shape) in [(0, [1, 24]), (3, [24, 1]), (-3, [1, 24]), (-1, [6, 4])] {
+        let out = flatten(axis)?.run([("x", DynTensor::from(x.clone()))])?;
+        assert_eq!(out["y"].downcast::<f32>()?.shape().to_vec(), shape.to_vec());
+    }
+    for axis in [4, -4] {
+        assert!(matches!(
+            flatten(axis)?.run([("x", DynTensor::from(x.clone()))]),
+            Err(OnnxError::InvalidNode { .. })
+        ));
+    }
+    Ok(())
+}
+
+#[test]
+fn test_onnx_quantize_linear() -> anyhow::Result<()> {
+    let bytes = model(
+        &[
+            node("QuantizeLinear", "", &["x", "scale", "zp"], &["q"], &[]),
+            node("DequantizeLinear", "", &["q", "scale", "zp"], &["y"], &[]),
+            node("QuantizeLinear", "", &["x", "s"], &["qu"], &[]),
+        ],
+        &[
+            f32_tensor("scale", &[3], &[0.1, 0.2, 0.05]),
+            i8_tensor("zp", &[3], &[0, -4, 10]),
+            f32_tensor("s", &[], &[0.1]),
+        ],
+        &["x"],
+        &["q", "y", "qu"],
+    );
+    let model = OnnxModel::from_bytes(&bytes)?;
+    assert!(model.unsupported_ops().is_empty());
+    let x = typed(&values(12, 0.4), &[2, 3, 2])?;
+    let out = model.run([("x", DynTensor::from(x.clone()))])?;
+
+    // a 1-D scale quantizes along the default axis 1
+    let q = x.quantize_per_channel::<i8>(&[0.1, 0.2, 0.05], &[0, -4, 10], 1)?;
+    assert_eq!(out["q"].dtype(), Dtype::I8);
+    assert_eq!(out["q"].downcast::<i8>()?.as_raw(), q.int_repr().as_raw());
+    assert!(out["y"].downcast::<f32>()?.allclose(&q.dequantize()?));
+    // uint8 without a zero point
+    let qu = x.quantize_per_tensor::<u8>(0.1, 0)?;
+    assert_eq!(out["qu"].dtype(), Dtype::U8);
+    assert_eq!(out["qu"].downcast::<u8>()?.as_raw(), qu.int_repr().as_raw());
+    Ok(())
+}
+
+#[test]
+fn test_onnx_unsupported_and_errors() -> anyhow::Result<()> {
+    let bytes = model(
+        &[
+            node("Gather", "", &["x", "idx"], &["g"], &[]),
+            node("Relu", "", &["g"], &["r"], &[]),
+            node("Gather", "", &["r", "idx"], &["g2"], &[]),
+            node("FusedThing", "com.vendor", &["g2"], &["y"], &[]),
+        ],
+        &[i64_tensor("idx", &[1], &[0])],
+        &["x"],
+        &["y"],
+    );
+    let unsupported = OnnxModel::from_bytes(&bytes)?;
+    let report = unsupported.unsupported_ops();
+    assert_eq!(report.ops.len(), 2);
+    assert_eq!(report.ops[0].op_type, "Gather");
+    assert_eq!(report.ops[0].nodes, vec!["Gather_g", "Gather_g2"]);
+    assert_eq!(report.ops[1].domain, "com.vendor");
+    let text = report.to_string();
+    assert!(text.starts_with("2 unsupported operator(s):"));
+    assert!(text.contains("Gather used by 2 node(s): Gather_g, Gather_g2"));
+    let x: DynTensor = Tensor::<f32>::zeros(&[2])?.into();
+    assert!(matches!(unsupported.run([("x", x)]), Err(OnnxError::Unsupported(r)) if r == report));
+
+    // a cycle between two nodes
+    let cycle = model_bytes_cycle();
+    assert!(
+        matches!(OnnxModel::from_bytes(&cycle), Err(OnnxError::Cycle(nodes)) if nodes.len() == 2)
+    );
+
+    // a missing graph input
+    let bytes = model(
+        &[node("Relu", "", &["x"], &["y"], &[])],
+        &[],
+        &["x"],
+        &["y"],
+    );
+    let relu = OnnxModel::from_bytes(&bytes)?;
+    assert!(matches!(
+        relu.run(Vec::<(&str, DynTensor)>::new()),
+        Err(OnnxError::MissingValue { .. })
+    ));
+    assert!(matches!(
+        OnnxModel::from_bytes(&[0xff]),
+        Err(OnnxError::Decode { .. })
+    ));
+    Ok(())
+}
+
+fn model_bytes_cycle() -> Vec<u8> {
+    model(
+        &[
+            node("Add", "", &["x", "b"], &["a"], &[]),
+            node("Relu", "", &["a"], &["b"], &[]),
+        ],
+        &[],
+        &["x"],
+        &["b"],
+    )
+}
@@ -0,0 +1,177 @@
+#![allow(unused_imports)]
+use std::io::Cursor;
+
+use crate::utils::TempPath;
+use half::f16;
+use hpt::{f8e4m3, f8e5m2};
+use hpt::{Cast, FloatOutBinary, FloatOutUnary, FloatUnaryOps, NormalOut, NormalOutUnary};
+use hpt::{CompressionAlgo, Endian, MmapLoader, TensorReader, TensorWriter, ToSafeTensors};
+use hpt::{ShapeManipulate, Tensor, TensorCreator, TensorInfo, TensorLike};
+use hpt::{TypeCommon, VecTrait};
+
+#[test]
+fn test_f8_round_trip() {
+    for bits in 0..=255u8 {
+        let x = f8e4m3::from_bits(bits);
+        if !x.is_nan() {
+            assert_eq!(f8e4m3::from_f32(x.to_f32()).to_bits(), bits);
+        }
+        let y = f8e5m2::from_bits(bits);
+        if !y.is_nan() {
+            assert_eq!(f8e5m2::from_f32(y.to_f32()).to_bits(), bits);
+        }
+    }
+    assert_eq!(f8e4m3::from_bits(0x7f).to_f32().is_nan(), true);
+    assert_eq!(f8e5m2::from_bits(0x7c).to_f32(), f32::INFINITY);
+    assert_eq!(f8e5m2::from_bits(0x7d).to_f32().is_nan(), true);
+}
+
+#[test]
+fn test_f8_values() {
+    assert_eq!(f8e4m3::MAX.to_f32(), 448.0);
+    assert_eq!(f8e5m2::MAX.to_f32(), 57344.0);
+    // smallest subnormals
+    assert_eq!(f8e4m3::from_bits(1).to_f32(), 2f32.powi(-9));
+    assert_eq!(f8e5m2::from_bits(1).to_f32(), 2f32.powi(-16));
+    macro_rules! check_consts {
+        ($t:ty) => {
+            assert_eq!(<$t as TypeCommon>::ZERO.to_f32(), 0.0);
+            assert_eq!(<$t as TypeCommon>::ONE.to_f32(), 1.0);
+            assert_eq!(<$t as TypeCommon>::TWO.to_f32(), 2.0);
+            assert_eq!(<$t as TypeCommon>::SIX.to_f32(), 6.0);
+            assert_eq!(<$t as TypeCommon>::TEN.to_f32(), 10.0);
+            assert_eq!(<$t as TypeCommon>::BIT_SIZE, 1);
+        };
+    }
+    check_consts!(f8e4m3);
+    check_consts!(f8e5m2);
+}
+
+#[test]
+fn test_f8_rounding() {
+    // ties to even
+    assert_eq!(f8e4m3::from_f32(1.0625).to_f32(), 1.0);
+    assert_eq!(f8e4m3::from_f32(1.1875).to_f32(), 1.25);
+    assert_eq!(f8e5m2::from_f32(1.125).to_f32(), 1.0);
+    assert_eq!(f8e5m2::from_f32(1.375).to_f32(), 1.5);
+    // subnormals and underflow
+    assert_eq!(f8e4m3::from_f32(2f32.powi(-10)).to_bits(), 0);
+    assert_eq!(f8e4m3::from_f32(3.0 * 2f32.powi(-10)).to_bits(), 2);
+    assert_eq!(f8e4m3::from_f32(-1e-9).to_bits(), 0x80);
+    // e4m3 saturates, e5m2 overflows to infinity
+    assert_eq!(f8e4m3::from_f32(1000.0), f8e4m3::MAX);
+    assert_eq!(f8e4m3::from_f32(f32::NEG_INFINITY), f8e4m3::MIN);
+    assert_eq!(f8e5m2::from_f32(1e6).to_f32(), f32::INFINITY);
+    assert_eq!(f8e5m2::from_f32(-1e6).to_f32(), f32::NEG_INFINITY);
+    assert!(f8e4m3::from_f32(f32::NAN).is_nan());
+    assert!(f8e5m2::from_f32(f32::NAN).is_nan());
+}
+
+#[test]
+fn test_f8_promote() {
+    fn add<A: NormalOut<B>, B>(a: A, b: B) -> A::Output {
+        a._add(b)
+    }
+    let x = f8e4m3::from_f32(1.5);
+    let y = f8e5m2::from_f32(3.0);
+    let _: f8e4m3 = add(x, x);
+    let _: f8e5m2 = add(y, true);
+    let z: f16 = add(x, y);
+    assert_eq!(z, f16::from_f32(4.5));
+    let z: f32 = add(2.25f32, x);
+    assert_eq!(z, 3.75);
+    let z: f64 = add(y, 1i64);
+    assert_eq!(z, 4.0);
+    let z: f16 = add(x, 1u8);
+    assert_eq!(z, f16::from_f32(2.5));
+    assert_eq!(x._exp(), f8e4m3::from_f32(1.5f32.exp()));
+    let c: f8e5m2 = 48.0f64.cast();
+    assert_eq!(c.to_f32(), 48.0);
+    let c: i32 = f8e4m3::from_f32(-3.0).cast();
+    assert_eq!(c, -3);
+}
+
+#[test]
+fn test_f8_vec() {
+    let vals: Vec<f8e4m3> = (0..16).map(|x| f8e4m3::from_f32(x as f32 / 4.0)).collect();
+    let a = unsafe { <f8e4m3 as TypeCommon>::Vec::from_ptr(vals.as_ptr()) };
+    let b = <f8e4m3 as TypeCommon>::Vec::splat(f8e4m3::TWO);
+    let c = a._mul(b)._add(a);
+    for i in 0..16 {
+        assert_eq!(c[i], vals[i]._mul(f8e4m3::TWO)._add(vals[i]));
+    }
+    assert_eq!(a.sum().to_f32(), 30.0);
+    // the arithmetic widens to the f32 vectors of the simd level, the lanes match the scalar ops
+    let vals: Vec<f8e5m2> = (0..16).map(|x| f8e5m2::from_f32(x as f32 - 5.5)).collect();
+    let a = unsafe { <f8e5m2 as TypeCommon>::Vec::from_ptr(vals.as_ptr()) };
+    let b = <f8e5m2 as TypeCommon>::Vec::splat(f8e5m2::from_f32(3.0));
+    let (div, fma, sub) = (a._div(b), a._mul_add(b, a), a._sub(b));
+    let (abs, neg, sqrt) = (a._abs(), a._neg(), a._abs()._sqrt());
+    for i in 0..16 {
+        let (x, y) = (vals[i], f8e5m2::from_f32(3.0));
+        assert_eq!(div[i], x._div(y));
+        assert_eq!(fma[i], x._mul_add(y, x));
+        assert_eq!(sub[i], x._sub(y));
+        assert_eq!(abs[i], x._abs());
+        assert_eq!(neg[i], x._neg());
+        assert_eq!(sqrt[i], x._abs()._sqrt());
+    }
+}
+
+#[test]
+fn test_f8_tensor() -> anyhow::Result<()> {
+    let a = Tensor::<f32>::arange(0, 48)?.reshape(&[6, 8])? / 8.0f32;
+    let b = a.astype::<f8e4m3>()?;
+    let c = b.exp()?;
+    let d = &b + &b.t()?.contiguous()?.reshape(&[6, 8])?;
+    let expected = a.astype::<f8e4m3>()?.astype::<f32>()?;
+    assert_eq!(b.astype::<f32>()?.as_raw(), expected.as_raw());
+    for (i, x) in c.as_raw().iter().enumerate() {
+        assert_eq!(*x, f8e4m3::from_f32(b.as_raw()[i].to_f32().exp()));
+    }
+    let bt = b.t()?.contiguous()?.reshape(&[6, 8])?;
+    for i in 0..48 {
+        assert_eq!(
+            d.as_raw()[i],
+            f8e4m3::from_f32(b.as_raw()[i].to_f32() + bt.as_raw()[i].to_f32())
+        );
+    }
+    // mixed types compute in the promoted type
+    let e: Tensor<f32> = &b * &a;
+    for i in 0..48 {
+        assert_eq!(e.as_raw()[i], b.as_raw()[i].to_f32() * a.as_raw()[i]);
+    }
+    Ok(())
+}
+
+#[test]
+fn test_f8_save_load() -> anyhow::Result<()> {
+    let a = (Tensor::<f32>::arange(0, 24)? - 12.0f32)
+        .reshape(&[4, 6])?
+        .astype::<f8e4m3>()?;
+    let b = Tensor::<f32>::arange(0, 10)?.astype::<f8e5m2>()?;
+    let mut writer = TensorWriter::new(Vec::new())?;
+    writer.write("a", a.clone(), CompressionAlgo::Gzip, Endian::Native, 9)?;
+    writer.write(
+        "b",
+        b.clone(),
+        CompressionAlgo::NoCompression,
+        Endian::Big,
+        9,
+    )?;
+    let mut reader = TensorReader::new(Cursor::new(writer.finish()?))?;
+    assert_eq!(reader.info("a"), Some((&[4i64, 6][..], "f8e4m3")));
+    let a2 = reader.read::<f8e4m3, Tensor<f8e4m3>, 1>("a", &[])?;
+    let b2 = reader.read::<f8e5m2, Tensor<f8e5m2>, 1>("b", &[])?;
+    assert_eq!(a2.as_raw(), a.as_raw());
+    assert_eq!(b2.as_raw(), b.as_raw());
+
+    let path = TempPath::new("f8_f8.safetensors");
+    a.save_safetensors(&path, "a", None)?;
+    let loader = MmapLoader::open(&path)?;
+    assert_eq!(loader.entry("a").map(|x| x.dtype.as_str()), Some("f8e4m3"));
+    let a3 = loader.load::<f8e4m3, Tensor<f8e4m3>, 1>("a")?;
+    drop(loader);
+    assert_eq!(a3.as_raw(), a.as_raw());
+    Ok(())
+}
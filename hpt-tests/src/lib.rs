@@ -1,30 +1,45 @@
 pub mod hpt {
     pub mod cpu {
+        pub mod accumulation;
         pub mod adaptive_avg_pool;
         pub mod adaptive_max_pool;
         pub mod assert_utils;
         pub mod avg_pool;
+        pub mod axis_iter;
         pub mod binary;
         pub mod binary_out;
         pub mod bn_conv2d;
+        pub mod context;
         pub mod conv2d;
         pub mod conv2d_group;
         pub mod conv2d_transpose;
         pub mod creation;
         pub mod cumulate;
+        pub mod custom_op;
+        pub mod custom_reduce;
+        pub mod deterministic;
+        pub mod display;
+        pub mod dlpack;
         pub mod dwconv2d;
+        pub mod dyn_tensor;
         pub mod gather;
+        pub mod indexed;
+        pub mod lazy;
         pub mod maxpool;
         pub mod onehot;
+        pub mod pad;
         pub mod pwconv2d;
+        pub mod quantize;
         pub mod reduce;
         pub mod scatter;
         pub mod shape_manipulate;
+        pub mod simd_dispatch;
         pub mod slice;
         pub mod softmax;
         pub mod test_lib;
         pub mod topk;
         pub mod unary;
+        pub mod windows;
     }
     #[cfg(feature = "cuda")]
     pub mod cuda {
@@ -46,12 +61,27 @@ pub mod hpt_common {
     pub mod strides;
 }
 
+pub mod hpt_dataloader {
+    pub mod fasttensor;
+    pub mod mmap;
+    pub mod npy;
+    pub mod safetensors;
+}
+
+pub mod hpt_onnx {
+    pub mod onnx;
+}
+
 pub mod hpt_types {
     pub mod test_display;
+    pub mod test_f8;
     pub mod test_vector_index;
     pub mod tests;
 }
 
-// pub mod macro_tests {
-//     pub mod tests;
-// }
+pub mod macro_tests {
+    pub mod fuse;
+    pub mod tests;
+}
+
+mod utils;
@@ -0,0 +1,214 @@
+#![allow(unused_imports)]
+use hpt::{
+    FloatReduce, FloatUnaryOps, Matmul, NormalReduce, NormalUaryOps, ShapeManipulate, Tensor,
+    TensorCreator, TensorError, TensorInfo,
+};
+
+#[hpt::compile]
+fn binary_unary(a: Tensor<f32>, b: Tensor<f32>) -> anyhow::Result<Tensor<f32>> {
+    let c = &a + &b;
+    let d = c.sin()?;
+    let e = d * &a;
+    Ok(e)
+}
+
+#[test]
+fn test_fuse_binary_unary() -> anyhow::Result<()> {
+    let a = Tensor::<f32>::arange(0, 24)?.reshape(&[2, 3, 4])?;
+    let b = Tensor::<f32>::arange(1, 25)?.reshape(&[2, 3, 4])?;
+    let res = binary_unary(a.clone(), b.clone())?;
+    assert!(res.allclose(&((&a + &b).sin()? * &a)));
+    Ok(())
+}
+
+#[test]
+fn test_fuse_broadcast_uncontiguous() -> anyhow::Result<()> {
+    let a = Tensor::<f32>::arange(0, 24)?
+        .reshape(&[2, 3, 4])?
+        .permute(&[1, 0, 2])?;
+    let b = Tensor::<f32>::arange(1, 5)?;
+    let res = binary_unary(a.clone(), b.clone())?;
+    assert_eq!(res.shape().inner(), &[3, 2, 4]);
+    assert!(res.allclose(&((&a + &b).sin()? * &a)));
+    assert!(binary_unary(a, Tensor::<f32>::arange(0, 3)?).is_err());
+    Ok(())
+}
+
+#[hpt::compile]
+fn with_literals(a: Tensor<f32>, b: Tensor<f32>) -> Result<Tensor<f64>, TensorError> {
+    let c = &a - 1.5f32;
+    let d = c.relu()?;
+    let e = d / &b;
+    let f = 2.0 * e;
+    Ok(f)
+}
+
+#[test]
+fn test_fuse_literals() -> anyhow::Result<()> {
+    let a = Tensor::<f32>::arange(0, 100)?.reshape(&[10, 10])?;
+    let b = Tensor::<f32>::arange(1, 11)?;
+    let res = with_literals(a.clone(), b.clone())?;
+    assert!(res.allclose(&(2.0 * ((&a - 1.5f32).relu()? / &b))));
+    Ok(())
+}
+
+#[allow(deprecated)]
+#[hpt::compile]
+fn split_by_matmul(x: Tensor<f32>, w: Tensor<f32>, b: Tensor<f32>) -> anyhow::Result<Tensor<f32>> {
+    let y = x.matmul(&w)?;
+    let z = &y + &b;
+    let z = z.tanh()?;
+    let z = z * 0.5f32;
+    let r = z.reshape(&[6])?;
+    Ok(r)
+}
+
+#[test]
+fn test_fuse_split_by_opaque() -> anyhow::Result<()> {
+    let x = Tensor::<f32>::arange(0, 12)?.reshape(&[3, 4])?;
+    let w = Tensor::<f32>::arange(0, 8)?.reshape(&[4, 2])?;
+    let b = Tensor::<f32>::arange(0, 2)?;
+    let res = split_by_matmul(x.clone(), w.clone(), b.clone())?;
+    let expected = ((&x.matmul(&w)? + &b).tanh()? * 0.5f32).reshape(&[6])?;
+    assert!(res.allclose(&expected));
+    Ok(())
+}
+
+#[hpt::compile]
+fn live_intermediate(a: Tensor<f64>) -> anyhow::Result<(Tensor<f64>, Tensor<f64>)> {
+    let b = a.exp()?;
+    let c = &b + &a;
+    let d = c.sqrt()?;
+    Ok((b, d))
+}
+
+#[test]
+fn test_fuse_live_intermediate() -> anyhow::Result<()> {
+    let a = Tensor::<f64>::arange(0, 50)?;
+    let (b, d) = live_intermediate(a.clone())?;
+    assert!(b.allclose(&a.exp()?));
+    assert!(d.allclose(&(&a.exp()? + &a).sqrt()?));
+    Ok(())
+}
+
+#[allow(deprecated)]
+#[hpt::compile]
+fn squared_deviation(x: Tensor<f32>) -> anyhow::Result<Tensor<f32>> {
+    let m = x.mean(1, true)?;
+    let d = &x - &m;
+    let s = d.square()?;
+    let r = s.sum(1, false)?;
+    Ok(r)
+}
+
+#[test]
+fn test_fuse_reduce() -> anyhow::Result<()> {
+    let x = Tensor::<f32>::arange(0, 24)?.reshape(&[4, 6])?;
+    let expected = (&x - &x.mean(1, true)?).square()?.sum(1, false)?;
+    assert!(squared_deviation(x.clone())?.allclose(&expected));
+    let x = x.permute(&[1, 0])?;
+    let expected = (&x - &x.mean(1, true)?).square()?.sum(1, false)?;
+    assert!(squared_deviation(x)?.allclose(&expected));
+    // spans several tiles, with vector runs and scalar tails
+    let x: Tensor<f32> = Tensor::<f32>::arange(0, 300 * 257)?.reshape(&[300, 257])? / 1000.0f32;
+    let expected = (&x - &x.mean(1, true)?).square()?.sum(1, false)?;
+    assert!(squared_deviation(x.clone())?.allclose(&expected));
+    let x = x.permute(&[1, 0])?;
+    let expected = (&x - &x.mean(1, true)?).square()?.sum(1, false)?;
+    assert!(squared_deviation(x)?.allclose(&expected));
+    Ok(())
+}
+
+#[hpt::compile]
+fn reduce_kinds(
+    x: Tensor<f64>,
+    axis: i64,
+) -> anyhow::Result<(Tensor<f64>, Tensor<f64>, Tensor<f64>)> {
+    let a = x.abs()?;
+    let b = a * 0.5;
+    let p = b.prod(axis, true)?;
+    let c = x.sin()?;
+    let mx = c.max(axis, false)?;
+    let d = x.cos()?;
+    let mn = d.min(axis, false)?;
+    Ok((p, mx, mn))
+}
+
+#[test]
+fn test_fuse_reduce_kinds() -> anyhow::Result<()> {
+    let x = Tensor::<f64>::arange(-12, 12)?.reshape(&[2, 3, 4])?;
+    for axis in [0, 1, 2, -1] {
+        let (p, mx, mn) = reduce_kinds(x.clone(), axis)?;
+        let b: Tensor<f64> = x.abs()? * 0.5;
+        assert!(p.allclose(&b.prod(axis, true)?));
+        assert!(mx.allclose(&x.sin()?.max(axis, false)?));
+        assert!(mn.allclose(&x.cos()?.min(axis, false)?));
+    }
+    Ok(())
+}
+
+#[hpt::compile]
+fn shared_producer(x: Tensor<f32>) -> anyhow::Result<(Tensor<f32>, Tensor<f32>)> {
+    let a = x.exp()?;
+    let b = a.sum(0, false)?;
+    let c = a.sin()?;
+    Ok((b, c))
+}
+
+#[test]
+fn test_fuse_reduce_shared_producer() -> anyhow::Result<()> {
+    let x = Tensor::<f32>::arange(0, 12)?.reshape(&[3, 4])?;
+    let (b, c) = shared_producer(x.clone())?;
+    assert!(b.allclose(&x.exp()?.sum(0, false)?));
+    assert!(c.allclose(&x.exp()?.sin()?));
+    Ok(())
+}
+
+#[hpt::compile]
+fn dense(x: Tensor<f32>, w: Tensor<f32>, bias: Tensor<f32>) -> anyhow::Result<Tensor<f32>> {
+    let y = x.matmul(&w)?;
+    let z = &y + &bias;
+    let r = z.relu()?;
+    Ok(r)
+}
+
+#[test]
+fn test_fuse_matmul_epilogue() -> anyhow::Result<()> {
+    let x = Tensor::<f32>::arange(0, 24)?.reshape(&[4, 6])?;
+    let w = Tensor::<f32>::arange(0, 18)?.reshape(&[6, 3])?;
+    let bias = Tensor::<f32>::arange(-40, -37)?;
+    let res = dense(x.clone(), w.clone(), bias.clone())?;
+    assert!(res.allclose(&(&x.matmul(&w)? + &bias).relu()?));
+
+    let x = Tensor::<f32>::arange(0, 48)?.reshape(&[2, 4, 6])?;
+    let res = dense(x.clone(), w.clone(), bias.clone())?;
+    assert!(res.allclose(&(&x.matmul(&w)? + &bias).relu()?));
+
+    // the bias broadcasts the output beyond the matmul shape, the matmul isn't fused
+    let bias = Tensor::<f32>::arange(-300, -291)?.reshape(&[3, 1, 1, 3])?;
+    let res = dense(x.clone(), w.clone(), bias.clone())?;
+    assert_eq!(res.shape().inner(), &vec![3, 2, 4, 3]);
+    assert!(res.allclose(&(&x.matmul(&w)? + &bias).relu()?));
+    Ok(())
+}
+
+#[hpt::compile]
+fn residual(x: Tensor<f32>, w: Tensor<f32>) -> anyhow::Result<Tensor<f64>> {
+    let a = x.sin()?;
+    let y = a.matmul(&w)?;
+    let z = &y + &x;
+    let r = z * 2.0;
+    Ok(r)
+}
+
+#[test]
+fn test_fuse_matmul_epilogue_operand() -> anyhow::Result<()> {
+    let x = Tensor::<f32>::arange(0, 16)?.reshape(&[4, 4])?;
+    let w = Tensor::<f32>::arange(0, 16)?
+        .reshape(&[4, 4])?
+        .permute(&[1, 0])?;
+    let res = residual(x.clone(), w.clone())?;
+    let expected: Tensor<f64> = (&x.sin()?.matmul(&w)? + &x) * 2.0;
+    assert!(res.allclose(&expected));
+    Ok(())
+}
@@ -0,0 +1,51 @@
+use std::path::{Path, PathBuf};
+
+use hpt::{ShapeManipulate, Tensor, TensorCreator, TensorLike};
+
+/// a file path in the temp directory, unique to the test process, the file is removed when the path is dropped
+pub(crate) struct TempPath(PathBuf);
+
+impl TempPath {
+    pub(crate) fn new(name: &str) -> Self {
+        Self(std::env::temp_dir().join(format!("hpt_{}_{}", std::process::id(), name)))
+    }
+}
+
+impl Drop for TempPath {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.0);
+    }
+}
+
+impl std::ops::Deref for TempPath {
+    type Target = Path;
+    fn deref(&self) -> &Path {
+        &self.0
+    }
+}
+
+impl AsRef<Path> for TempPath {
+    fn as_ref(&self) -> &Path {
+        &self.0
+    }
+}
+
+impl From<&TempPath> for PathBuf {
+    fn from(path: &TempPath) -> Self {
+        path.0.clone()
+    }
+}
+
+/// `n` deterministic values in `[-6 * scale, 6 * scale]`
+pub(crate) fn values(n: usize, scale: f32) -> Vec<f32> {
+    (0..n)
+        .map(|i| ((i * 7 % 13) as f32 - 6.0) * scale)
+        .collect()
+}
+
+/// a tensor of `shape` holding `data`
+pub(crate) fn typed(data: &[f32], shape: &[i64]) -> anyhow::Result<Tensor<f32>> {
+    let mut t = Tensor::<f32>::empty(&[data.len() as i64])?;
+    t.as_raw_mut().copy_from_slice(data);
+    Ok(t.reshape(shape)?)
+}
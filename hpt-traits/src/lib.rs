@@ -16,6 +16,8 @@ pub mod ops {
     pub mod conv;
     /// A module contains cumulative operations
     pub mod cumulative;
+    /// A module contains the user defined operators
+    pub mod custom;
     /// A module contains fft operations
     pub mod fft;
     /// A module contains pooling operations
@@ -35,6 +37,7 @@ pub use ops::binary::*;
 pub use ops::cmp::*;
 pub use ops::conv::*;
 pub use ops::cumulative::*;
+pub use ops::custom::*;
 pub use ops::fft::*;
 pub use ops::pooling::*;
 pub use ops::uary::*;
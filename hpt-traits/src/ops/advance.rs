@@ -1,5 +1,48 @@
 use hpt_common::error::base::TensorError;
 
+/// How `pad_with_mode` fills the padded elements, the modes of the ONNX `Pad` operator.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum PadMode<T> {
+    /// the padded elements are a constant, `00|abc|00`
+    Constant(T),
+    /// the tensor is mirrored without repeating the edge element, `cb|abc|ba`
+    Reflect,
+    /// the tensor is mirrored repeating the edge element, `ba|abc|cb`
+    Symmetric,
+    /// the padded elements repeat the closest edge element, `aa|abc|cc`
+    Edge,
+    /// the tensor wraps around, `bc|abc|ab`
+    Circular,
+}
+
+impl<T> PadMode<T> {
+    /// The index read for the position `i` of an axis of size `n`, `None` for the constant padding.
+    ///
+    /// The positions inside of the axis read themselves, `n` must be positive.
+    #[inline(always)]
+    pub fn source(&self, i: i64, n: i64) -> Option<i64> {
+        if (0..n).contains(&i) {
+            return Some(i);
+        }
+        match self {
+            PadMode::Constant(_) => None,
+            PadMode::Edge => Some(i.clamp(0, n - 1)),
+            PadMode::Reflect if n == 1 => Some(0),
+            PadMode::Reflect => {
+                let period = 2 * (n - 1);
+                let i = i.rem_euclid(period);
+                Some(if i < n { i } else { period - i })
+            }
+            PadMode::Symmetric => {
+                let period = 2 * n;
+                let i = i.rem_euclid(period);
+                Some(if i < n { i } else { period - 1 - i })
+            }
+            PadMode::Circular => Some(i.rem_euclid(n)),
+        }
+    }
+}
+
 /// A trait contains advance operations
 pub trait AdvancedOps {
     /// The type of the meta data
@@ -10,6 +53,14 @@ pub trait AdvancedOps {
     type IndexOutput;
     /// Pad the tensor
     fn pad(&self, pads: &[(i64, i64)], val: Self::Meta) -> Result<Self::Output, TensorError>;
+    /// Pad the tensor, `pads[i]` is the number of elements added before and after the axis `i`.
+    ///
+    /// The `Reflect`, `Symmetric` and `Circular` modes repeat periodically when the padding is larger than the axis.
+    fn pad_with_mode(
+        &self,
+        pads: &[(i64, i64)],
+        mode: PadMode<Self::Meta>,
+    ) -> Result<Self::Output, TensorError>;
     /// Topk the tensor
     fn topk(
         &self,
@@ -0,0 +1,120 @@
+use hpt_types::dtype::TypeCommon;
+
+#[doc(hidden)]
+pub use hpt_types::dtype::TypeCommon as __TypeCommon;
+
+/// A user defined element-wise unary operator.
+///
+/// The operator runs through the same kernels as the builtin unary operators, `simd` is used on the
+/// contiguous part of the input and `scalar` on the remaining elements, both must compute the same thing.
+pub trait CustomUnary<T: TypeCommon> {
+    /// The type of the output elements
+    type Output: TypeCommon;
+    /// Apply the operator to one element.
+    fn scalar(x: T) -> Self::Output;
+    /// Apply the operator to a simd vector of elements.
+    fn simd(x: T::Vec) -> <Self::Output as TypeCommon>::Vec;
+}
+
+/// A user defined element-wise binary operator.
+///
+/// The operator runs through the same kernels as the builtin binary operators, the inputs are broadcasted,
+/// `simd` is used when the vectors of `A`, `B` and the output have the same length and `scalar` otherwise.
+pub trait CustomBinary<A: TypeCommon, B: TypeCommon> {
+    /// The type of the output elements
+    type Output: TypeCommon;
+    /// Apply the operator to one pair of elements.
+    fn scalar(lhs: A, rhs: B) -> Self::Output;
+    /// Apply the operator to a pair of simd vectors.
+    fn simd(lhs: A::Vec, rhs: B::Vec) -> <Self::Output as TypeCommon>::Vec;
+}
+
+/// Define a unit struct implementing [`CustomUnary`] from a scalar and a simd closure.
+///
+/// # Example
+/// ```rust
+/// use hpt_traits::unary_op;
+/// unary_op! {
+///     /// `x^3`
+///     pub Cube: f32 => f32,
+///     scalar: |x| x * x * x,
+///     simd: |v| v * v * v,
+/// }
+/// ```
+#[macro_export]
+macro_rules! unary_op {
+    (
+        $(#[$meta:meta])*
+        $vis:vis $name:ident: $inp:ty => $out:ty,
+        scalar: $scalar:expr,
+        simd: $simd:expr $(,)?
+    ) => {
+        $(#[$meta])*
+        #[derive(Debug, Clone, Copy)]
+        $vis struct $name;
+
+        impl $crate::CustomUnary<$inp> for $name {
+            type Output = $out;
+            #[inline(always)]
+            fn scalar(x: $inp) -> $out {
+                let f: fn($inp) -> $out = $scalar;
+                f(x)
+            }
+            #[inline(always)]
+            fn simd(
+                x: <$inp as $crate::__TypeCommon>::Vec,
+            ) -> <$out as $crate::__TypeCommon>::Vec {
+                let f: fn(
+                    <$inp as $crate::__TypeCommon>::Vec,
+                ) -> <$out as $crate::__TypeCommon>::Vec = $simd;
+                f(x)
+            }
+        }
+    };
+}
+
+/// Define a unit struct implementing [`CustomBinary`] from a scalar and a simd closure.
+///
+/// # Example
+/// ```rust
+/// use hpt_traits::binary_op;
+/// binary_op! {
+///     /// `(a - b)^2`
+///     pub SquaredDiff: f32, f32 => f32,
+///     scalar: |a, b| (a - b) * (a - b),
+///     simd: |a, b| (a - b) * (a - b),
+/// }
+/// ```
+#[macro_export]
+macro_rules! binary_op {
+    (
+        $(#[$meta:meta])*
+        $vis:vis $name:ident: $lhs:ty, $rhs:ty => $out:ty,
+        scalar: $scalar:expr,
+        simd: $simd:expr $(,)?
+    ) => {
+        $(#[$meta])*
+        #[derive(Debug, Clone, Copy)]
+        $vis struct $name;
+
+        impl $crate::CustomBinary<$lhs, $rhs> for $name {
+            type Output = $out;
+            #[inline(always)]
+            fn scalar(lhs: $lhs, rhs: $rhs) -> $out {
+                let f: fn($lhs, $rhs) -> $out = $scalar;
+                f(lhs, rhs)
+            }
+            #[inline(always)]
+            fn simd(
+                lhs: <$lhs as $crate::__TypeCommon>::Vec,
+                rhs: <$rhs as $crate::__TypeCommon>::Vec,
+            ) -> <$out as $crate::__TypeCommon>::Vec {
+                let f: fn(
+                    <$lhs as $crate::__TypeCommon>::Vec,
+                    <$rhs as $crate::__TypeCommon>::Vec,
+                ) -> <$out as $crate::__TypeCommon>::Vec = $simd;
+                f(lhs, rhs)
+            }
+        }
+    };
+}
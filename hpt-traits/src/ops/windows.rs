@@ -71,4 +71,109 @@ pub trait WindowOps {
     where
         Self::Meta: FloatConst,
         i64: Cast<<Self::Meta as FloatOutBinary>::Output>;
+    /// Generates a Kaiser window of a specified length.
+    ///
+    /// The Kaiser window trades the width of the main lobe against the level of the side lobes with `beta`, it is commonly used in FIR filter design.
+    ///
+    /// ```text
+    /// w(n) = I0(beta * sqrt(1 - (2n / M - 1)^2)) / I0(beta)
+    /// ```
+    ///
+    /// where `I0` is the zeroth order modified Bessel function of the first kind, `M` is `window_length` for periodic windows and `window_length - 1` otherwise.
+    ///
+    /// # Parameters
+    ///
+    /// - `window_length`: The length of the window.
+    /// - `beta`: The shape parameter, `0` gives a rectangular window and larger values give narrower windows.
+    /// - `periodic`: If `true`, creates a periodic window, suitable for use in spectral analysis.
+    ///
+    /// # Returns
+    ///
+    /// - A tensor containing the Kaiser window.
+    fn kaiser_window(
+        window_length: i64,
+        beta: f64,
+        periodic: bool,
+    ) -> Result<Self::Output, TensorError>;
+    /// Generates a Bartlett (triangular) window of a specified length.
+    ///
+    /// ```text
+    /// w(n) = 1 - |2n / M - 1|
+    /// ```
+    ///
+    /// where `M` is `window_length` for periodic windows and `window_length - 1` otherwise.
+    ///
+    /// # Parameters
+    ///
+    /// - `window_length`: The length of the window.
+    /// - `periodic`: If `true`, creates a periodic window, suitable for use in spectral analysis.
+    ///
+    /// # Returns
+    ///
+    /// - A tensor containing the Bartlett window.
+    fn bartlett_window(window_length: i64, periodic: bool) -> Result<Self::Output, TensorError>;
+    /// Generates a Gaussian window of a specified length.
+    ///
+    /// ```text
+    /// w(n) = exp(-0.5 * ((n - M / 2) / std)^2)
+    /// ```
+    ///
+    /// where `M` is `window_length` for periodic windows and `window_length - 1` otherwise.
+    ///
+    /// # Parameters
+    ///
+    /// - `window_length`: The length of the window.
+    /// - `std`: The standard deviation of the gaussian, must be positive.
+    /// - `periodic`: If `true`, creates a periodic window, suitable for use in spectral analysis.
+    ///
+    /// # Returns
+    ///
+    /// - A tensor containing the Gaussian window.
+    fn gaussian_window(
+        window_length: i64,
+        std: f64,
+        periodic: bool,
+    ) -> Result<Self::Output, TensorError>;
+    /// Generates a Tukey (tapered cosine) window of a specified length.
+    ///
+    /// The window is flat in the middle and tapers to zero with cosine lobes covering a fraction `alpha` of the window.
+    /// `alpha = 0` gives a rectangular window and `alpha = 1` gives a Hann window, values outside of `[0, 1]` are clamped.
+    ///
+    /// # Parameters
+    ///
+    /// - `window_length`: The length of the window.
+    /// - `alpha`: The fraction of the window inside of the cosine tapers.
+    /// - `periodic`: If `true`, creates a periodic window, suitable for use in spectral analysis.
+    ///
+    /// # Returns
+    ///
+    /// - A tensor containing the Tukey window.
+    fn tukey_window(
+        window_length: i64,
+        alpha: f64,
+        periodic: bool,
+    ) -> Result<Self::Output, TensorError>;
+    /// Generates a generalized cosine window of a specified length.
+    ///
+    /// ```text
+    /// w(n) = sum((-1)^k * coeffs[k] * cos(2πkn / M))
+    /// ```
+    ///
+    /// where `M` is `window_length` for periodic windows and `window_length - 1` otherwise.
+    /// The Hann window is `[0.5, 0.5]`, the Hamming window is `[0.54, 0.46]` and the Blackman window is `[0.42, 0.5, 0.08]`.
+    ///
+    /// # Parameters
+    ///
+    /// - `window_length`: The length of the window.
+    /// - `coeffs`: The weights of the cosine terms.
+    /// - `periodic`: If `true`, creates a periodic window, suitable for use in spectral analysis.
+    ///
+    /// # Returns
+    ///
+    /// - A tensor containing the window.
+    fn general_cosine_window(
+        window_length: i64,
+        coeffs: &[f64],
+        periodic: bool,
+    ) -> Result<Self::Output, TensorError>;
 }
@@ -14,8 +14,6 @@ use std::{borrow::BorrowMut, fmt::Display};
 
 #[cfg(target_feature = "avx2")]
 type BoolVector = simd::_256bit::boolx32::boolx32;
-#[cfg(target_feature = "avx512f")]
-type BoolVector = simd::_512bit::boolx64::boolx64;
 #[cfg(any(
     all(not(target_feature = "avx2"), target_feature = "sse"),
     target_arch = "arm",
@@ -258,6 +256,28 @@ where
     #[track_caller]
     fn full_like(&self, val: T) -> Result<Self::Output, TensorError>;
 
+    /// Creates a tensor of the specified shape, computing every element from its index.
+    ///
+    /// This function calls `f` with the multi-dimensional index of every element, the elements can be computed in parallel.
+    ///
+    /// # Arguments
+    ///
+    /// * `shape` - The desired shape of the tensor. The type `S` must implement `Into<Shape>`.
+    /// * `f` - A function returning the element at the given index.
+    ///
+    /// # Returns
+    ///
+    /// * A tensor with the specified shape, the element at `idx` being `f(idx)`.
+    ///
+    /// # Panics
+    ///
+    /// * This function may panic if the requested shape is invalid or too large for available memory.
+    #[track_caller]
+    fn from_fn<S, F>(shape: S, f: F) -> Result<Self::Output, TensorError>
+    where
+        S: Into<Shape>,
+        F: Fn(&[i64]) -> T + Sync + Send;
+
     /// Creates a tensor with values within a specified range.
     ///
     /// This function generates a 1D tensor with values ranging from `start` (inclusive) to `end` (exclusive).
@@ -567,6 +587,22 @@ where
     fn argmin<S: Into<Axis>>(&self, axis: S, keep_dims: bool) -> Result<Self::Output, TensorError>;
 }
 
+/// How the floating point sums of the reductions are accumulated.
+///
+/// Only the floating point types are affected, the other types always use [`Accumulation::Native`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Accumulation {
+    /// accumulate in the element type with the simd kernels
+    #[default]
+    Native,
+    /// accumulate `f16`, `bf16` and `f8` in `f32`, and `f32` in `f64`
+    Widened,
+    /// Kahan-compensated summation, `f16`, `bf16` and `f8` are compensated in `f32`
+    Kahan,
+    /// pairwise summation, `f16`, `bf16` and `f8` accumulate in `f32`
+    Pairwise,
+}
+
 /// A trait for normal tensor reduction operations.
 pub trait NormalReduce<T>
 where
@@ -594,6 +630,29 @@ where
     #[track_caller]
     fn sum<S: Into<Axis>>(&self, axis: S, keep_dims: bool) -> Result<Self::Output, TensorError>;
 
+    /// Computes the sum of the elements along the specified axis with the given accumulation policy.
+    ///
+    /// # Parameters
+    ///
+    /// - `axis`: The axis along which to sum the elements.
+    /// - `keep_dims`: Whether to retain the reduced dimensions in the result.
+    /// - `accumulation`: How the partial sums are accumulated, see [`Accumulation`].
+    ///
+    /// # Returns
+    ///
+    /// - `anyhow::Result<Self::Output>`: A tensor containing the sum of elements along the specified axis.
+    ///
+    /// # Note
+    ///
+    /// Backends which can't accumulate the element type with `accumulation` return an error.
+    #[track_caller]
+    fn sum_with<S: Into<Axis>>(
+        &self,
+        axis: S,
+        keep_dims: bool,
+        accumulation: Accumulation,
+    ) -> Result<Self::Output, TensorError>;
+
     /// Computes the sum of the elements along the specified axis, storing the result in a pre-allocated tensor.
     ///
     /// The `sum_` function computes the sum of elements along the specified axis, and optionally initializes an output tensor to store the result.
@@ -968,6 +1027,21 @@ where
     #[track_caller]
     fn mean<S: Into<Axis>>(&self, axis: S, keep_dims: bool) -> Result<Self::Output, TensorError>;
 
+    /// Computes the mean of the elements along the specified axis with the given accumulation policy.
+    ///
+    /// # Parameters
+    ///
+    /// - `axis`: The axis along which to reduce the tensor.
+    /// - `keep_dims`: Whether to retain the reduced dimensions in the result.
+    /// - `accumulation`: How the partial sums are accumulated, see [`Accumulation`].
+    #[track_caller]
+    fn mean_with<S: Into<Axis>>(
+        &self,
+        axis: S,
+        keep_dims: bool,
+        accumulation: Accumulation,
+    ) -> Result<Self::Output, TensorError>;
+
     /// Reduces the tensor along the specified axis using the L2 norm (Euclidean norm).
     ///
     /// The `reducel2` function computes the L2 norm (Euclidean norm) along the specified axis of the tensor.
@@ -987,6 +1061,21 @@ where
         keep_dims: bool,
     ) -> Result<Self::Output, TensorError>;
 
+    /// Reduces the tensor along the specified axis using the L2 norm with the given accumulation policy.
+    ///
+    /// # Parameters
+    ///
+    /// - `axis`: The axis along which to reduce the tensor.
+    /// - `keep_dims`: Whether to retain the reduced dimensions in the result.
+    /// - `accumulation`: How the partial sums are accumulated, see [`Accumulation`].
+    #[track_caller]
+    fn reducel2_with<S: Into<Axis>>(
+        &self,
+        axis: S,
+        keep_dims: bool,
+        accumulation: Accumulation,
+    ) -> Result<Self::Output, TensorError>;
+
     /// Reduces the tensor along the specified axis using the L3 norm.
     ///
     /// The `reducel3` function computes the L3 norm along the specified axis of the tensor.
@@ -1025,6 +1114,21 @@ where
         axis: S,
         keep_dims: bool,
     ) -> Result<Self::Output, TensorError>;
+
+    /// Computes the logarithm of the sum of exponentials along the specified axis with the given accumulation policy.
+    ///
+    /// # Parameters
+    ///
+    /// - `axis`: The axis along which to reduce the tensor.
+    /// - `keep_dims`: Whether to retain the reduced dimensions in the result.
+    /// - `accumulation`: How the partial sums are accumulated, see [`Accumulation`].
+    #[track_caller]
+    fn logsumexp_with<S: Into<Axis>>(
+        &self,
+        axis: S,
+        keep_dims: bool,
+        accumulation: Accumulation,
+    ) -> Result<Self::Output, TensorError>;
 }
 
 /// Common bounds for primitive types
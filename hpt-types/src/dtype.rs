@@ -1,5 +1,3 @@
-#[cfg(target_feature = "avx512f")]
-use crate::vectors::_512bit::*;
 use crate::{
     into_vec::IntoVec,
     type_promote::{FloatOutBinary, FloatOutUnary, NormalOut, NormalOutUnary},
@@ -118,6 +116,14 @@ impl CudaType for bf16 {
     const CUDA_TYPE: &'static str = "__nv_bfloat16";
 }
 
+impl CudaType for crate::f8::f8e4m3 {
+    const CUDA_TYPE: &'static str = "__nv_fp8_e4m3";
+}
+
+impl CudaType for crate::f8::f8e5m2 {
+    const CUDA_TYPE: &'static str = "__nv_fp8_e5m2";
+}
+
 /// common trait for all data types
 ///
 /// This trait is used to define the common properties of all data types
@@ -825,6 +831,43 @@ mod type_impl {
     );
 }
 
+mod f8_impl {
+    use super::TypeCommon;
+    use crate::f8::{f8e4m3, f8e5m2};
+    use crate::vectors::f8x16::{f8e4m3x16, f8e5m2x16};
+    use crate::vectors::traits::VecTrait;
+    impl_type_common!(
+        f8e4m3,
+        f8e4m3::MAX,
+        f8e4m3::MIN,
+        f8e4m3::ZERO,
+        f8e4m3::ONE,
+        f8e4m3::MAX,
+        f8e4m3::MIN,
+        f8e4m3::from_bits(0x40),
+        f8e4m3::from_bits(0x4c),
+        f8e4m3::from_bits(0x52),
+        "f8e4m3",
+        f8e4m3x16,
+        u8
+    );
+    impl_type_common!(
+        f8e5m2,
+        f8e5m2::MAX,
+        f8e5m2::MIN,
+        f8e5m2::ZERO,
+        f8e5m2::ONE,
+        f8e5m2::INFINITY,
+        f8e5m2::NEG_INFINITY,
+        f8e5m2::from_bits(0x40),
+        f8e5m2::from_bits(0x46),
+        f8e5m2::from_bits(0x49),
+        "f8e5m2",
+        f8e5m2x16,
+        u8
+    );
+}
+
 /// constant values for floating point data types
 pub trait FloatConst {
     /// 0.5
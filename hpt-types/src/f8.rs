@@ -0,0 +1,396 @@
+use crate::convertion::Convertor;
+use crate::into_scalar::Cast;
+use half::{bf16, f16};
+use num_complex::{Complex32, Complex64};
+
+/// decode the bits of an 8-bit float with `man_bits` mantissa bits to the bits of a f32
+///
+/// `ieee` formats (e5m2) reserve the largest exponent for infinity and nan, the other formats (e4m3)
+/// only use the all-ones pattern for nan and have no infinity
+const fn decode(bits: u8, man_bits: u32, bias: i32, ieee: bool) -> u32 {
+    let sign = ((bits as u32) & 0x80) << 24;
+    let man_mask = (1u32 << man_bits) - 1;
+    let exp_max = (1i32 << (7 - man_bits)) - 1;
+    let exp = ((bits & 0x7f) >> man_bits) as i32;
+    let man = (bits as u32) & man_mask;
+    if exp == exp_max && (ieee || man == man_mask) {
+        if ieee && man == 0 {
+            return sign | 0x7f80_0000;
+        }
+        return sign | 0x7fc0_0000;
+    }
+    if exp == 0 {
+        if man == 0 {
+            return sign;
+        }
+        let mut e = 1 - bias;
+        let mut m = man;
+        while m & (1 << man_bits) == 0 {
+            m <<= 1;
+            e -= 1;
+        }
+        return sign | (((e + 127) as u32) << 23) | ((m & man_mask) << (23 - man_bits));
+    }
+    sign | (((exp - bias + 127) as u32) << 23) | (man << (23 - man_bits))
+}
+
+const fn decode_table(man_bits: u32, bias: i32, ieee: bool) -> [f32; 256] {
+    let mut table = [0.0f32; 256];
+    let mut i = 0;
+    while i < 256 {
+        table[i] = f32::from_bits(decode(i as u8, man_bits, bias, ieee));
+        i += 1;
+    }
+    table
+}
+
+/// round a f32 to the nearest 8-bit float with `man_bits` mantissa bits, ties to even
+///
+/// values larger than the largest finite value (`max_code`) are encoded as `overflow_code`
+#[inline(always)]
+fn encode(x: f32, man_bits: u32, bias: i32, max_code: u32, overflow_code: u8, nan_code: u8) -> u8 {
+    let bits = x.to_bits();
+    let sign = ((bits >> 24) & 0x80) as u8;
+    if x.is_nan() {
+        return sign | nan_code;
+    }
+    let exp = ((bits >> 23) & 0xff) as i32 - 127;
+    if exp < 1 - bias {
+        // the subnormals of the 8-bit float are multiples of 2^(1 - bias - man_bits), rounding up to the
+        // smallest normal gives the right bits as well
+        let scale = f32::from_bits(((bias + man_bits as i32 - 1 + 127) as u32) << 23);
+        return sign | (x.abs() * scale).round_ties_even() as u8;
+    }
+    let shift = 23 - man_bits;
+    let man = bits & 0x7f_ffff;
+    let mut code = (((exp + bias) as u32) << man_bits) | (man >> shift);
+    let rem = man & ((1 << shift) - 1);
+    let half = 1 << (shift - 1);
+    if rem > half || (rem == half && code & 1 == 1) {
+        code += 1;
+    }
+    if code > max_code {
+        return sign | overflow_code;
+    }
+    sign | code as u8
+}
+
+static E4M3_TO_F32: [f32; 256] = decode_table(3, 7, false);
+static E5M2_TO_F32: [f32; 256] = decode_table(2, 15, true);
+
+/// 8-bit floating point type with 1 sign bit, 4 exponent bits and 3 mantissa bits (OCP `E4M3FN`)
+///
+/// the exponent bias is 7, the largest finite value is 448, there is no infinity and only the all-ones
+/// pattern is nan. Conversions round to nearest even and saturate to ±448, so [`TypeCommon::INF`] is the
+/// largest finite value like the integer types.
+///
+/// [`TypeCommon::INF`]: crate::dtype::TypeCommon::INF
+#[allow(non_camel_case_types)]
+#[derive(Clone, Copy, Default)]
+#[repr(transparent)]
+pub struct f8e4m3(u8);
+
+/// 8-bit floating point type with 1 sign bit, 5 exponent bits and 2 mantissa bits (OCP `E5M2`)
+///
+/// the exponent bias is 15, the largest finite value is 57344, it follows the ieee 754 rules for
+/// infinity and nan. Conversions round to nearest even and overflow to infinity.
+#[allow(non_camel_case_types)]
+#[derive(Clone, Copy, Default)]
+#[repr(transparent)]
+pub struct f8e5m2(u8);
+
+impl f8e4m3 {
+    /// the largest finite value, 448
+    pub const MAX: Self = Self(0x7e);
+    /// the smallest finite value, -448
+    pub const MIN: Self = Self(0xfe);
+    /// 0
+    pub const ZERO: Self = Self(0x00);
+    /// 1
+    pub const ONE: Self = Self(0x38);
+    /// nan
+    pub const NAN: Self = Self(0x7f);
+
+    /// create the value from its raw bits
+    #[inline(always)]
+    pub const fn from_bits(bits: u8) -> Self {
+        Self(bits)
+    }
+    /// the raw bits of the value
+    #[inline(always)]
+    pub const fn to_bits(self) -> u8 {
+        self.0
+    }
+    /// convert a f32 to the nearest e4m3 value, out of range values saturate to ±448
+    #[inline(always)]
+    pub fn from_f32(x: f32) -> Self {
+        Self(encode(x, 3, 7, 0x7e, 0x7e, 0x7f))
+    }
+    /// convert a f64 to the nearest e4m3 value
+    #[inline(always)]
+    pub fn from_f64(x: f64) -> Self {
+        Self::from_f32(x as f32)
+    }
+    /// convert the value to f32, the conversion is exact
+    #[inline(always)]
+    pub fn to_f32(self) -> f32 {
+        E4M3_TO_F32[self.0 as usize]
+    }
+    /// convert the value to f64, the conversion is exact
+    #[inline(always)]
+    pub fn to_f64(self) -> f64 {
+        self.to_f32() as f64
+    }
+    /// check if the value is nan
+    #[inline(always)]
+    pub const fn is_nan(self) -> bool {
+        self.0 & 0x7f == 0x7f
+    }
+}
+
+impl f8e5m2 {
+    /// the largest finite value, 57344
+    pub const MAX: Self = Self(0x7b);
+    /// the smallest finite value, -57344
+    pub const MIN: Self = Self(0xfb);
+    /// 0
+    pub const ZERO: Self = Self(0x00);
+    /// 1
+    pub const ONE: Self = Self(0x3c);
+    /// positive infinity
+    pub const INFINITY: Self = Self(0x7c);
+    /// negative infinity
+    pub const NEG_INFINITY: Self = Self(0xfc);
+    /// nan
+    pub const NAN: Self = Self(0x7f);
+
+    /// create the value from its raw bits
+    #[inline(always)]
+    pub const fn from_bits(bits: u8) -> Self {
+        Self(bits)
+    }
+    /// the raw bits of the value
+    #[inline(always)]
+    pub const fn to_bits(self) -> u8 {
+        self.0
+    }
+    /// convert a f32 to the nearest e5m2 value, out of range values become ±infinity
+    #[inline(always)]
+    pub fn from_f32(x: f32) -> Self {
+        Self(encode(x, 2, 15, 0x7b, 0x7c, 0x7f))
+    }
+    /// convert a f64 to the nearest e5m2 value
+    #[inline(always)]
+    pub fn from_f64(x: f64) -> Self {
+        Self::from_f32(x as f32)
+    }
+    /// convert the value to f32, the conversion is exact
+    #[inline(always)]
+    pub fn to_f32(self) -> f32 {
+        E5M2_TO_F32[self.0 as usize]
+    }
+    /// convert the value to f64, the conversion is exact
+    #[inline(always)]
+    pub fn to_f64(self) -> f64 {
+        self.to_f32() as f64
+    }
+    /// check if the value is nan
+    #[inline(always)]
+    pub const fn is_nan(self) -> bool {
+        self.0 & 0x7f > 0x7c
+    }
+}
+
+macro_rules! impl_f8_common {
+    ($f8:ident) => {
+        impl std::fmt::Display for $f8 {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                std::fmt::Display::fmt(&self.to_f32(), f)
+            }
+        }
+
+        impl std::fmt::Debug for $f8 {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                std::fmt::Debug::fmt(&self.to_f32(), f)
+            }
+        }
+
+        impl PartialEq for $f8 {
+            #[inline(always)]
+            fn eq(&self, other: &Self) -> bool {
+                self.to_f32() == other.to_f32()
+            }
+        }
+
+        impl PartialOrd for $f8 {
+            #[inline(always)]
+            fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+                self.to_f32().partial_cmp(&other.to_f32())
+            }
+        }
+
+        impl From<$f8> for f32 {
+            #[inline(always)]
+            fn from(x: $f8) -> Self {
+                x.to_f32()
+            }
+        }
+
+        impl From<$f8> for f64 {
+            #[inline(always)]
+            fn from(x: $f8) -> Self {
+                x.to_f64()
+            }
+        }
+
+        impl num_traits::ToBytes for $f8 {
+            type Bytes = [u8; 1];
+            #[inline(always)]
+            fn to_be_bytes(&self) -> Self::Bytes {
+                [self.0]
+            }
+            #[inline(always)]
+            fn to_le_bytes(&self) -> Self::Bytes {
+                [self.0]
+            }
+        }
+
+        impl num_traits::FromBytes for $f8 {
+            type Bytes = [u8; 1];
+            #[inline(always)]
+            fn from_be_bytes(bytes: &Self::Bytes) -> Self {
+                Self(bytes[0])
+            }
+            #[inline(always)]
+            fn from_le_bytes(bytes: &Self::Bytes) -> Self {
+                Self(bytes[0])
+            }
+        }
+
+        impl Convertor for $f8 {
+            #[inline(always)]
+            fn to_bool(self) -> bool {
+                self.to_f32() != 0.0
+            }
+            #[inline(always)]
+            fn to_u8(self) -> u8 {
+                self.to_f32().to_u8()
+            }
+            #[inline(always)]
+            fn to_u16(self) -> u16 {
+                self.to_f32().to_u16()
+            }
+            #[inline(always)]
+            fn to_u32(self) -> u32 {
+                self.to_f32().to_u32()
+            }
+            #[inline(always)]
+            fn to_u64(self) -> u64 {
+                self.to_f32().to_u64()
+            }
+            #[inline(always)]
+            fn to_usize(self) -> usize {
+                self.to_f32().to_usize()
+            }
+            #[inline(always)]
+            fn to_i8(self) -> i8 {
+                self.to_f32().to_i8()
+            }
+            #[inline(always)]
+            fn to_i16(self) -> i16 {
+                self.to_f32().to_i16()
+            }
+            #[inline(always)]
+            fn to_i32(self) -> i32 {
+                self.to_f32().to_i32()
+            }
+            #[inline(always)]
+            fn to_i64(self) -> i64 {
+                self.to_f32().to_i64()
+            }
+            #[inline(always)]
+            fn to_isize(self) -> isize {
+                self.to_f32().to_isize()
+            }
+            #[inline(always)]
+            fn to_f32(self) -> f32 {
+                $f8::to_f32(self)
+            }
+            #[inline(always)]
+            fn to_f64(self) -> f64 {
+                $f8::to_f64(self)
+            }
+            #[inline(always)]
+            fn to_f16(self) -> f16 {
+                f16::from_f32(self.to_f32())
+            }
+            #[inline(always)]
+            fn to_bf16(self) -> bf16 {
+                bf16::from_f32(self.to_f32())
+            }
+            #[inline(always)]
+            fn to_complex32(self) -> Complex32 {
+                Complex32::new(self.to_f32(), 0.0)
+            }
+            #[inline(always)]
+            fn to_complex64(self) -> Complex64 {
+                Complex64::new(self.to_f64(), 0.0)
+            }
+        }
+
+        impl_f8_cast!(
+            $f8, bool, u8, u16, u32, u64, usize, i8, i16, i32, i64, isize, f32, f64, f16, bf16,
+            Complex32, Complex64
+        );
+    };
+}
+
+macro_rules! impl_f8_cast {
+    ($f8:ident, $($t:ty),*) => {
+        $(
+            impl Cast<$t> for $f8 {
+                #[inline(always)]
+                fn cast(self) -> $t {
+                    self.to_f32().cast()
+                }
+            }
+            impl Cast<$f8> for $t {
+                #[inline(always)]
+                fn cast(self) -> $f8 {
+                    $f8::from_f32(self.to_f32())
+                }
+            }
+        )*
+    };
+}
+
+impl_f8_common!(f8e4m3);
+impl_f8_common!(f8e5m2);
+
+impl Cast<f8e4m3> for f8e4m3 {
+    #[inline(always)]
+    fn cast(self) -> f8e4m3 {
+        self
+    }
+}
+
+impl Cast<f8e5m2> for f8e5m2 {
+    #[inline(always)]
+    fn cast(self) -> f8e5m2 {
+        self
+    }
+}
+
+impl Cast<f8e5m2> for f8e4m3 {
+    #[inline(always)]
+    fn cast(self) -> f8e5m2 {
+        f8e5m2::from_f32(self.to_f32())
+    }
+}
+
+impl Cast<f8e4m3> for f8e5m2 {
+    #[inline(always)]
+    fn cast(self) -> f8e4m3 {
+        f8e4m3::from_f32(self.to_f32())
+    }
+}
@@ -5,6 +5,8 @@
 pub mod convertion;
 /// A module defines a set of data types and utilities
 pub mod dtype;
+/// A module defines the 8-bit floating point types
+pub mod f8;
 /// A module implement type conversion
 pub mod into_scalar;
 /// A module implement simd vector conversion
@@ -18,6 +20,7 @@ pub(crate) mod scalars {
     pub(crate) mod _f16;
     pub(crate) mod _f32;
     pub(crate) mod _f64;
+    pub(crate) mod _f8;
     pub(crate) mod impls;
 }
 
@@ -32,6 +35,7 @@ pub mod promotion {
         pub(crate) mod _f16;
         pub(crate) mod _f32;
         pub(crate) mod _f64;
+        pub(crate) mod _f8;
         pub(crate) mod _i16;
         pub(crate) mod _i32;
         pub(crate) mod _i64;
@@ -132,7 +136,10 @@ pub mod vectors {
             pub mod usizex4;
         }
         /// A module defines a set of 512-bit vector types
-        #[cfg(target_feature = "avx512f")]
+        ///
+        /// they predate the current vector traits and are not built, `T::Vec` of the avx512f builds
+        /// stays on the 256-bit vectors and the kernels of the avx512 level use `levels::avx512`
+        #[cfg(any())]
         pub mod _512bit {
             /// A module defines a set of 512-bit vector types for bf16
             pub mod bf16x32;
@@ -243,6 +250,28 @@ pub mod vectors {
             }
         }
     }
+    /// A module detects the simd features of the cpu at runtime and dispatches the kernels to them
+    pub mod dispatch;
+    /// A module defines the vector types of the 8-bit floats, they widen to f32 vectors for arithmetic
+    pub mod f8x16;
+    /// A module defines the vector types of every simd level the kernels are dispatched to
+    pub mod levels {
+        /// A module defines the 256-bit avx2 vectors
+        #[cfg(target_arch = "x86_64")]
+        pub mod avx2;
+        /// A module defines the 512-bit avx512f vectors
+        #[cfg(target_arch = "x86_64")]
+        pub mod avx512;
+        /// A module defines the 128-bit neon vectors
+        #[cfg(target_arch = "aarch64")]
+        pub mod neon;
+        /// A module defines the single lane vectors of the targets without simd levels
+        #[cfg(not(any(target_arch = "x86_64", target_arch = "aarch64")))]
+        pub mod scalar;
+        /// A module defines the 128-bit sse2 vectors
+        #[cfg(target_arch = "x86_64")]
+        pub mod sse;
+    }
     /// A module defines a set of traits for vector
     pub mod traits;
     /// A module defines a set of utils for vector
@@ -289,24 +318,6 @@ pub mod vectors {
             u8x16::u8_promote,
         };
     }
-    #[cfg(target_feature = "avx512f")]
-    pub(crate) mod vector_promote {
-        #[cfg(target_pointer_width = "32")]
-        pub(crate) use crate::vectors::arch_simd::_512bit::isizex16::isize_promote;
-        #[cfg(target_pointer_width = "64")]
-        pub(crate) use crate::vectors::arch_simd::_512bit::isizex8::isize_promote;
-        #[cfg(target_pointer_width = "32")]
-        pub(crate) use crate::vectors::arch_simd::_512bit::usizex16::usize_promote;
-        #[cfg(target_pointer_width = "64")]
-        pub(crate) use crate::vectors::arch_simd::_512bit::usizex8::usize_promote;
-        pub(crate) use crate::vectors::arch_simd::_512bit::{
-            bf16x32::bf16_promote, boolx64::bool_promote, cplx32x8::Complex32_promote,
-            cplx64x4::Complex64_promote, f16x32::f16_promote, f32x16::f32_promote,
-            f64x8::f64_promote, i16x32::i16_promote, i32x16::i32_promote, i64x8::i64_promote,
-            i8x64::i8_promote, u16x32::u16_promote, u32x16::u32_promote, u64x8::u64_promote,
-            u8x64::u8_promote,
-        };
-    }
 }
 
 #[cfg(feature = "cuda")]
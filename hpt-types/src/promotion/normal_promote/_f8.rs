@@ -0,0 +1,255 @@
+#[cfg(feature = "cuda")]
+use crate::cuda_types::scalar::Scalar;
+use crate::dtype::TypeCommon;
+use crate::f8::{f8e4m3, f8e5m2};
+use crate::into_scalar::Cast;
+use crate::traits::VecTrait;
+use crate::type_promote::{
+    FloatOutBinary, FloatOutBinaryPromote, FloatOutUnaryPromote, NormalOut, NormalOutPromote,
+};
+use crate::vectors::f8x16::{f8e4m3_promote, f8e5m2_promote};
+use crate::vectors::vector_promote::*;
+use crate::{impl_float_out_binary_promote, impl_float_out_unary_promote, impl_normal_out_promote};
+use half::{bf16, f16};
+use num_complex::{Complex32, Complex64};
+
+/// apply `f` lane by lane on the promoted lanes of the vectors, the kernels only use the simd path of
+/// mixed types when the vectors have the same number of lanes
+#[inline(always)]
+fn zip_lanes<L, R, O, LV, RV, OV>(lhs: LV, rhs: RV, f: impl Fn(O, O) -> O) -> OV
+where
+    L: Copy + Cast<O>,
+    R: Copy + Cast<O>,
+    O: TypeCommon,
+    LV: VecTrait<L>,
+    RV: VecTrait<R>,
+    OV: VecTrait<O>,
+{
+    if LV::SIZE != OV::SIZE || RV::SIZE != OV::SIZE {
+        unreachable!()
+    }
+    let mut ret = OV::splat(O::ZERO);
+    for i in 0..OV::SIZE {
+        unsafe { *ret.as_mut_ptr().add(i) = f(lhs.extract(i).cast(), rhs.extract(i).cast()) };
+    }
+    ret
+}
+
+/// the three operands version of [`zip_lanes`]
+#[inline(always)]
+fn zip3_lanes<L, R, O, LV, RV, OV>(lhs: LV, a: RV, b: RV, f: impl Fn(O, O, O) -> O) -> OV
+where
+    L: Copy + Cast<O>,
+    R: Copy + Cast<O>,
+    O: TypeCommon,
+    LV: VecTrait<L>,
+    RV: VecTrait<R>,
+    OV: VecTrait<O>,
+{
+    if LV::SIZE != OV::SIZE || RV::SIZE != OV::SIZE {
+        unreachable!()
+    }
+    let mut ret = OV::splat(O::ZERO);
+    for i in 0..OV::SIZE {
+        let (x, a, b) = (
+            lhs.extract(i).cast(),
+            a.extract(i).cast(),
+            b.extract(i).cast(),
+        );
+        unsafe { *ret.as_mut_ptr().add(i) = f(x, a, b) };
+    }
+    ret
+}
+
+/// the 8-bit floats are only used for storage, the mixed type operations promote both operands to
+/// `$output` and compute there, the rules apply to both operand orders
+macro_rules! impl_f8_promote {
+    ($f8:ty, $rhs:ty, $output:ty) => {
+        impl_float_out_binary_promote!($f8, $rhs, $output);
+        impl_float_out_binary_promote!($rhs, $f8, $output);
+        impl_normal_out_promote!($f8, $rhs, $output);
+        impl_normal_out_promote!($rhs, $f8, $output);
+        impl_f8_promote!(@impl $f8, $rhs, $output);
+        impl_f8_promote!(@impl $rhs, $f8, $output);
+    };
+    (@impl $lhs:ty, $rhs:ty, $output:ty) => {
+        impl NormalOut<$rhs> for $lhs {
+            type Output = $output;
+            #[inline(always)]
+            fn _add(self, rhs: $rhs) -> $output {
+                Cast::<$output>::cast(self)._add(Cast::<$output>::cast(rhs))
+            }
+            #[inline(always)]
+            fn _sub(self, rhs: $rhs) -> $output {
+                Cast::<$output>::cast(self)._sub(Cast::<$output>::cast(rhs))
+            }
+            #[inline(always)]
+            fn _mul_add(self, a: $rhs, b: $rhs) -> $output {
+                Cast::<$output>::cast(self)
+                    ._mul_add(Cast::<$output>::cast(a), Cast::<$output>::cast(b))
+            }
+            #[inline(always)]
+            fn _mul(self, rhs: $rhs) -> $output {
+                Cast::<$output>::cast(self)._mul(Cast::<$output>::cast(rhs))
+            }
+            #[inline(always)]
+            fn _pow(self, rhs: $rhs) -> $output {
+                Cast::<$output>::cast(self)._pow(Cast::<$output>::cast(rhs))
+            }
+            #[inline(always)]
+            fn _rem(self, rhs: $rhs) -> $output {
+                Cast::<$output>::cast(self)._rem(Cast::<$output>::cast(rhs))
+            }
+            #[inline(always)]
+            fn _max(self, rhs: $rhs) -> $output {
+                Cast::<$output>::cast(self)._max(Cast::<$output>::cast(rhs))
+            }
+            #[inline(always)]
+            fn _min(self, rhs: $rhs) -> $output {
+                Cast::<$output>::cast(self)._min(Cast::<$output>::cast(rhs))
+            }
+            #[inline(always)]
+            fn _clamp(self, min: $rhs, max: $rhs) -> $output {
+                Cast::<$output>::cast(self)
+                    ._clamp(Cast::<$output>::cast(min), Cast::<$output>::cast(max))
+            }
+        }
+
+        impl FloatOutBinary<$rhs> for $lhs {
+            type Output = $output;
+            #[inline(always)]
+            fn _div(self, rhs: $rhs) -> $output {
+                Cast::<$output>::cast(self)._div(Cast::<$output>::cast(rhs))
+            }
+            #[inline(always)]
+            fn _log(self, base: $rhs) -> $output {
+                Cast::<$output>::cast(self)._log(Cast::<$output>::cast(base))
+            }
+            #[inline(always)]
+            fn _hypot(self, rhs: $rhs) -> $output {
+                Cast::<$output>::cast(self)._hypot(Cast::<$output>::cast(rhs))
+            }
+        }
+
+        paste::paste! {
+            impl NormalOut<[<$rhs _promote>]> for [<$lhs _promote>] {
+                type Output = [<$output _promote>];
+                #[inline(always)]
+                fn _add(self, rhs: [<$rhs _promote>]) -> Self::Output {
+                    zip_lanes::<$lhs, $rhs, $output, _, _, _>(self, rhs, |a, b| a._add(b))
+                }
+                #[inline(always)]
+                fn _sub(self, rhs: [<$rhs _promote>]) -> Self::Output {
+                    zip_lanes::<$lhs, $rhs, $output, _, _, _>(self, rhs, |a, b| a._sub(b))
+                }
+                #[inline(always)]
+                fn _mul_add(self, a: [<$rhs _promote>], b: [<$rhs _promote>]) -> Self::Output {
+                    zip3_lanes::<$lhs, $rhs, $output, _, _, _>(self, a, b, |x, a, b| {
+                        x._mul_add(a, b)
+                    })
+                }
+                #[inline(always)]
+                fn _mul(self, rhs: [<$rhs _promote>]) -> Self::Output {
+                    zip_lanes::<$lhs, $rhs, $output, _, _, _>(self, rhs, |a, b| a._mul(b))
+                }
+                #[inline(always)]
+                fn _pow(self, rhs: [<$rhs _promote>]) -> Self::Output {
+                    zip_lanes::<$lhs, $rhs, $output, _, _, _>(self, rhs, |a, b| a._pow(b))
+                }
+                #[inline(always)]
+                fn _rem(self, rhs: [<$rhs _promote>]) -> Self::Output {
+                    zip_lanes::<$lhs, $rhs, $output, _, _, _>(self, rhs, |a, b| a._rem(b))
+                }
+                #[inline(always)]
+                fn _max(self, rhs: [<$rhs _promote>]) -> Self::Output {
+                    zip_lanes::<$lhs, $rhs, $output, _, _, _>(self, rhs, |a, b| a._max(b))
+                }
+                #[inline(always)]
+                fn _min(self, rhs: [<$rhs _promote>]) -> Self::Output {
+                    zip_lanes::<$lhs, $rhs, $output, _, _, _>(self, rhs, |a, b| a._min(b))
+                }
+                #[inline(always)]
+                fn _clamp(self, min: [<$rhs _promote>], max: [<$rhs _promote>]) -> Self::Output {
+                    zip3_lanes::<$lhs, $rhs, $output, _, _, _>(self, min, max, |x, min, max| {
+                        x._clamp(min, max)
+                    })
+                }
+            }
+
+            impl FloatOutBinary<[<$rhs _promote>]> for [<$lhs _promote>] {
+                type Output = [<$output _promote>];
+                #[inline(always)]
+                fn _div(self, rhs: [<$rhs _promote>]) -> Self::Output {
+                    zip_lanes::<$lhs, $rhs, $output, _, _, _>(self, rhs, |a, b| a._div(b))
+                }
+                #[inline(always)]
+                fn _log(self, base: [<$rhs _promote>]) -> Self::Output {
+                    zip_lanes::<$lhs, $rhs, $output, _, _, _>(self, base, |a, b| a._log(b))
+                }
+                #[inline(always)]
+                fn _hypot(self, rhs: [<$rhs _promote>]) -> Self::Output {
+                    zip_lanes::<$lhs, $rhs, $output, _, _, _>(self, rhs, |a, b| a._hypot(b))
+                }
+            }
+        }
+    };
+}
+
+impl_float_out_binary_promote!(f8e4m3, f8e4m3, f8e4m3);
+impl_normal_out_promote!(f8e4m3, f8e4m3, f8e4m3);
+impl_float_out_unary_promote!(f8e4m3, f8e4m3);
+
+impl_float_out_binary_promote!(f8e5m2, f8e5m2, f8e5m2);
+impl_normal_out_promote!(f8e5m2, f8e5m2, f8e5m2);
+impl_float_out_unary_promote!(f8e5m2, f8e5m2);
+
+// f16 holds every e4m3 and e5m2 value exactly
+impl_f8_promote!(f8e4m3, f8e5m2, f16);
+
+impl_f8_promote!(f8e4m3, bool, f8e4m3);
+impl_f8_promote!(f8e4m3, i8, f16);
+impl_f8_promote!(f8e4m3, i16, f16);
+impl_f8_promote!(f8e4m3, i32, f32);
+impl_f8_promote!(f8e4m3, i64, f64);
+#[cfg(target_pointer_width = "64")]
+impl_f8_promote!(f8e4m3, isize, f64);
+#[cfg(target_pointer_width = "32")]
+impl_f8_promote!(f8e4m3, isize, f32);
+impl_f8_promote!(f8e4m3, u8, f16);
+impl_f8_promote!(f8e4m3, u16, f16);
+impl_f8_promote!(f8e4m3, u32, f32);
+impl_f8_promote!(f8e4m3, u64, f64);
+#[cfg(target_pointer_width = "64")]
+impl_f8_promote!(f8e4m3, usize, f64);
+#[cfg(target_pointer_width = "32")]
+impl_f8_promote!(f8e4m3, usize, f32);
+impl_f8_promote!(f8e4m3, f16, f16);
+impl_f8_promote!(f8e4m3, bf16, bf16);
+impl_f8_promote!(f8e4m3, f32, f32);
+impl_f8_promote!(f8e4m3, f64, f64);
+impl_f8_promote!(f8e4m3, Complex32, Complex32);
+impl_f8_promote!(f8e4m3, Complex64, Complex64);
+
+impl_f8_promote!(f8e5m2, bool, f8e5m2);
+impl_f8_promote!(f8e5m2, i8, f16);
+impl_f8_promote!(f8e5m2, i16, f16);
+impl_f8_promote!(f8e5m2, i32, f32);
+impl_f8_promote!(f8e5m2, i64, f64);
+#[cfg(target_pointer_width = "64")]
+impl_f8_promote!(f8e5m2, isize, f64);
+#[cfg(target_pointer_width = "32")]
+impl_f8_promote!(f8e5m2, isize, f32);
+impl_f8_promote!(f8e5m2, u8, f16);
+impl_f8_promote!(f8e5m2, u16, f16);
+impl_f8_promote!(f8e5m2, u32, f32);
+impl_f8_promote!(f8e5m2, u64, f64);
+#[cfg(target_pointer_width = "64")]
+impl_f8_promote!(f8e5m2, usize, f64);
+#[cfg(target_pointer_width = "32")]
+impl_f8_promote!(f8e5m2, usize, f32);
+impl_f8_promote!(f8e5m2, f16, f16);
+impl_f8_promote!(f8e5m2, bf16, bf16);
+impl_f8_promote!(f8e5m2, f32, f32);
+impl_f8_promote!(f8e5m2, f64, f64);
+impl_f8_promote!(f8e5m2, Complex32, Complex32);
+impl_f8_promote!(f8e5m2, Complex64, Complex64);
@@ -0,0 +1,199 @@
+use crate::f8::{f8e4m3, f8e5m2};
+use crate::type_promote::{Cmp, Eval, FloatOutBinary, FloatOutUnary, NormalOut, NormalOutUnary};
+
+/// the 8-bit floats compute in f32 and round the result back
+macro_rules! impl_f8_scalar {
+    ($f8:ident) => {
+        impl NormalOut for $f8 {
+            type Output = $f8;
+            #[inline(always)]
+            fn _add(self, rhs: Self) -> Self {
+                $f8::from_f32(self.to_f32() + rhs.to_f32())
+            }
+            #[inline(always)]
+            fn _sub(self, rhs: Self) -> Self {
+                $f8::from_f32(self.to_f32() - rhs.to_f32())
+            }
+            #[inline(always)]
+            fn _mul_add(self, a: Self, b: Self) -> Self {
+                $f8::from_f32(self.to_f32().mul_add(a.to_f32(), b.to_f32()))
+            }
+            #[inline(always)]
+            fn _mul(self, rhs: Self) -> Self {
+                $f8::from_f32(self.to_f32() * rhs.to_f32())
+            }
+            #[inline(always)]
+            fn _pow(self, rhs: Self) -> Self {
+                $f8::from_f32(self.to_f32().powf(rhs.to_f32()))
+            }
+            #[inline(always)]
+            fn _rem(self, rhs: Self) -> Self {
+                $f8::from_f32(self.to_f32() % rhs.to_f32())
+            }
+            #[inline(always)]
+            fn _max(self, rhs: Self) -> Self {
+                $f8::from_f32(self.to_f32().max(rhs.to_f32()))
+            }
+            #[inline(always)]
+            fn _min(self, rhs: Self) -> Self {
+                $f8::from_f32(self.to_f32().min(rhs.to_f32()))
+            }
+            #[inline(always)]
+            fn _clamp(self, min: Self, max: Self) -> Self {
+                $f8::from_f32(self.to_f32().max(min.to_f32()).min(max.to_f32()))
+            }
+        }
+
+        impl NormalOutUnary for $f8 {
+            #[inline(always)]
+            fn _square(self) -> Self {
+                $f8::from_f32(self.to_f32()._square())
+            }
+            #[inline(always)]
+            fn _abs(self) -> Self {
+                $f8::from_bits(self.to_bits() & 0x7f)
+            }
+            #[inline(always)]
+            fn _ceil(self) -> Self {
+                $f8::from_f32(self.to_f32()._ceil())
+            }
+            #[inline(always)]
+            fn _floor(self) -> Self {
+                $f8::from_f32(self.to_f32()._floor())
+            }
+            #[inline(always)]
+            fn _neg(self) -> Self {
+                $f8::from_bits(self.to_bits() ^ 0x80)
+            }
+            #[inline(always)]
+            fn _round(self) -> Self {
+                $f8::from_f32(self.to_f32()._round())
+            }
+            #[inline(always)]
+            fn _signum(self) -> Self {
+                $f8::from_f32(self.to_f32()._signum())
+            }
+            #[inline(always)]
+            fn _trunc(self) -> Self {
+                $f8::from_f32(self.to_f32()._trunc())
+            }
+            #[inline(always)]
+            fn _leaky_relu(self, alpha: Self) -> Self {
+                $f8::from_f32(self.to_f32()._leaky_relu(alpha.to_f32()))
+            }
+            #[inline(always)]
+            fn _relu(self) -> Self {
+                $f8::from_f32(self.to_f32()._relu())
+            }
+            #[inline(always)]
+            fn _relu6(self) -> Self {
+                $f8::from_f32(self.to_f32()._relu6())
+            }
+            #[inline(always)]
+            fn _copysign(self, rhs: Self) -> Self {
+                $f8::from_bits((self.to_bits() & 0x7f) | (rhs.to_bits() & 0x80))
+            }
+        }
+
+        impl FloatOutBinary for $f8 {
+            type Output = $f8;
+            #[inline(always)]
+            fn _div(self, rhs: Self) -> Self {
+                $f8::from_f32(self.to_f32() / rhs.to_f32())
+            }
+            #[inline(always)]
+            fn _log(self, base: Self) -> Self {
+                $f8::from_f32(self.to_f32().log(base.to_f32()))
+            }
+            #[inline(always)]
+            fn _hypot(self, rhs: Self) -> Self {
+                $f8::from_f32(self.to_f32().hypot(rhs.to_f32()))
+            }
+        }
+
+        impl FloatOutUnary for $f8 {
+            type Output = $f8;
+            impl_f8_scalar!(@unary $f8,
+                _exp, _expm1, _exp2, _exp10, _ln, _log1p, _log2, _log10, _sqrt, _sin, _cos, _tan,
+                _asin, _acos, _atan, _sinh, _cosh, _tanh, _asinh, _acosh, _atanh, _recip, _erf,
+                _sigmoid, _gelu, _hard_sigmoid, _hard_swish, _softplus, _softsign, _mish, _cbrt
+            );
+            #[inline(always)]
+            fn _celu(self, alpha: Self) -> Self {
+                $f8::from_f32(self.to_f32()._celu(alpha.to_f32()))
+            }
+            #[inline(always)]
+            fn _sincos(self) -> (Self, Self) {
+                let (sin, cos) = self.to_f32()._sincos();
+                ($f8::from_f32(sin), $f8::from_f32(cos))
+            }
+            #[inline(always)]
+            fn _atan2(self, rhs: Self) -> Self {
+                $f8::from_f32(self.to_f32()._atan2(rhs.to_f32()))
+            }
+            #[inline(always)]
+            fn _elu(self, alpha: Self) -> Self {
+                $f8::from_f32(self.to_f32()._elu(alpha.to_f32()))
+            }
+            #[inline(always)]
+            fn _selu(self, alpha: Self, scale: Self) -> Self {
+                $f8::from_f32(self.to_f32()._selu(alpha.to_f32(), scale.to_f32()))
+            }
+        }
+
+        impl Cmp for $f8 {
+            type Output = bool;
+            #[inline(always)]
+            fn _eq(self, rhs: Self) -> bool {
+                self.to_f32() == rhs.to_f32()
+            }
+            #[inline(always)]
+            fn _ne(self, rhs: Self) -> bool {
+                self.to_f32() != rhs.to_f32()
+            }
+            #[inline(always)]
+            fn _lt(self, rhs: Self) -> bool {
+                self.to_f32() < rhs.to_f32()
+            }
+            #[inline(always)]
+            fn _le(self, rhs: Self) -> bool {
+                self.to_f32() <= rhs.to_f32()
+            }
+            #[inline(always)]
+            fn _gt(self, rhs: Self) -> bool {
+                self.to_f32() > rhs.to_f32()
+            }
+            #[inline(always)]
+            fn _ge(self, rhs: Self) -> bool {
+                self.to_f32() >= rhs.to_f32()
+            }
+        }
+
+        impl Eval for $f8 {
+            type Output = bool;
+            #[inline(always)]
+            fn _is_nan(&self) -> bool {
+                self.is_nan()
+            }
+            #[inline(always)]
+            fn _is_true(&self) -> bool {
+                self.to_bits() & 0x7f != 0
+            }
+            #[inline(always)]
+            fn _is_inf(&self) -> bool {
+                self.to_f32().is_infinite()
+            }
+        }
+    };
+    (@unary $f8:ident, $($method:ident),*) => {
+        $(
+            #[inline(always)]
+            fn $method(self) -> Self {
+                $f8::from_f32(self.to_f32().$method())
+            }
+        )*
+    };
+}
+
+impl_f8_scalar!(f8e4m3);
+impl_f8_scalar!(f8e5m2);
@@ -9,8 +9,6 @@ use crate::into_vec::IntoVec;
 use crate::simd::_128bit::*;
 #[cfg(target_feature = "avx2")]
 use crate::simd::_256bit::*;
-#[cfg(target_feature = "avx512f")]
-use crate::simd::_512bit::*;
 use crate::traits::SimdMath;
 use crate::vectors::traits::SimdCompare;
 use crate::vectors::traits::VecTrait;
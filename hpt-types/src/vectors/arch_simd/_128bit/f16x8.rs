@@ -262,10 +262,7 @@ pub(crate) fn from_2_f32vec(val: [f32x4; 2]) -> f16x8 {
         let result = _mm_unpacklo_epi64(f16_high, f16_low);
         f16x8(std::mem::transmute(result))
     }
-    #[cfg(not(all(
-        target_feature = "f16c",
-        all(target_feature = "neon", target_arch = "aarch64")
-    )))]
+    #[cfg(not(all(target_feature = "f16c", target_arch = "x86_64")))]
     {
         let mut result = [half::f16::ZERO; 8];
         for i in 0..4 {
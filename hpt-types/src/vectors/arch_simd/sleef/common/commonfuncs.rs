@@ -198,11 +198,9 @@ pub(crate) unsafe fn visodd_vo_vd(d: VDouble) -> Vopmask {
     vneq_vo_vd_vd(vrint2_vd_vd(x), x)
 }
 
-#[cfg(not(target_feature = "avx512f"))]
 use crate::sleef_types::VInt;
 
 use super::dd::VDouble2;
-#[cfg(not(target_feature = "avx512f"))]
 #[inline(always)]
 pub(crate) unsafe fn vilogbk_vi_vd(d: VDouble) -> VInt {
     use helper::{
@@ -225,7 +223,6 @@ pub(crate) unsafe fn vilogbk_vi_vd(d: VDouble) -> VInt {
     q
 }
 
-#[cfg(not(target_feature = "avx512f"))]
 #[inline(always)]
 pub(crate) unsafe fn vilogb2k_vi_vd(d: VDouble) -> VInt {
     use helper::{vand_vi_vi_vi, vcast_vi_i, vcastu_vi_vm, vsrl_vi_vi_i, vsub_vi_vi_vi};
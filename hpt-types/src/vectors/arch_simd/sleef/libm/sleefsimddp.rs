@@ -106,12 +106,6 @@ unsafe fn rempi(a: VDouble) -> DDI {
 
     let mut ex = vilogb2k_vi_vd(a);
 
-    #[cfg(target_feature = "avx512f")]
-    {
-        ex = vandnot_vi_vi_vi(vsra_vi_vi_i(ex, 31), ex);
-        ex = vand_vi_vi_vi(ex, vcast_vi_i(1023));
-    }
-
     ex = vsub_vi_vi_vi(ex, vcast_vi_i(55));
 
     let q = vand_vi_vo_vi(vgt_vo_vi_vi(ex, vcast_vi_i(700 - 55)), vcast_vi_i(-64));
@@ -1016,7 +1010,6 @@ pub(crate) unsafe fn xlog_u1(d: VDouble) -> VDouble {
     let t: VDouble;
     let x2: VDouble;
 
-    #[cfg(not(target_feature = "avx512f"))]
     let (m, e) = {
         let o = vlt_vo_vd_vd(d, vcast_vd_d(SLEEF_DBL_MIN));
         let d = vsel_vd_vo_vd_vd(
@@ -1030,14 +1023,6 @@ pub(crate) unsafe fn xlog_u1(d: VDouble) -> VDouble {
         (m, e)
     };
 
-    #[cfg(target_feature = "avx512f")]
-    let (m, e) = {
-        let mut e = vgetexp_vd_vd(vmul_vd_vd_vd(d, vcast_vd_d(1.0 / 0.75)));
-        e = vsel_vd_vo_vd_vd(vispinf_vo_vd(e), vcast_vd_d(1024.0), e);
-        let m = vgetmant_vd_vd(d);
-        (m, e)
-    };
-
     x = dddiv_vd2_vd2_vd2(
         ddadd2_vd2_vd_vd(vcast_vd_d(-1.0), m),
         ddadd2_vd2_vd_vd(vcast_vd_d(1.0), m),
@@ -1059,24 +1044,16 @@ pub(crate) unsafe fn xlog_u1(d: VDouble) -> VDouble {
         0.6666666666667333541e+0,
     );
 
-    #[cfg(not(target_feature = "avx512f"))]
     let mut s = ddmul_vd2_vd2_vd(
         vcast_vd2_d_d(0.693147180559945286226764, 2.319046813846299558417771e-17),
         vcast_vd_vi(e),
     );
 
-    #[cfg(target_feature = "avx512f")]
-    let mut s = ddmul_vd2_vd2_vd(
-        vcast_vd2_d_d(0.693147180559945286226764, 2.319046813846299558417771e-17),
-        e,
-    );
-
     s = ddadd_vd2_vd2_vd2(s, ddscale_vd2_vd2_vd(x, vcast_vd_d(2.0)));
     s = ddadd_vd2_vd2_vd(s, vmul_vd_vd_vd(vmul_vd_vd_vd(x2, vd2getx_vd_vd2(x)), t));
 
     let mut r = vadd_vd_vd_vd(vd2getx_vd_vd2(s), vd2gety_vd_vd2(s));
 
-    #[cfg(not(target_feature = "avx512f"))]
     {
         r = vsel_vd_vo_vd_vd(vispinf_vo_vd(d), vcast_vd_d(f64::INFINITY), r);
         r = vsel_vd_vo_vd_vd(
@@ -1091,16 +1068,6 @@ pub(crate) unsafe fn xlog_u1(d: VDouble) -> VDouble {
         );
     }
 
-    #[cfg(target_feature = "avx512f")]
-    {
-        r = vfixup_vd_vd_vd_vi2_i(
-            r,
-            d,
-            vcast_vi2_i((4 << (2 * 4)) | (3 << (4 * 4)) | (5 << (5 * 4)) | (2 << (6 * 4))),
-            0,
-        );
-    }
-
     r
 }
 
@@ -1117,9 +1084,6 @@ pub(crate) unsafe fn xcbrt_u1(d: VDouble) -> VDouble {
     let qu: VInt;
     let re: VInt;
 
-    #[cfg(target_feature = "avx512f")]
-    let s = d;
-
     e = vadd_vi_vi_vi(vilogbk_vi_vd(vabs_vd_vd(d)), vcast_vi_i(1));
     let mut d = vldexp2_vd_vd_vi(d, vneg_vi_vi(e));
 
@@ -1178,7 +1142,6 @@ pub(crate) unsafe fn xcbrt_u1(d: VDouble) -> VDouble {
         vsub_vi_vi_vi(qu, vcast_vi_i(2048)),
     );
 
-    #[cfg(not(target_feature = "avx512f"))]
     {
         z = vsel_vd_vo_vd_vd(
             visinf_vo_vd(d),
@@ -1192,20 +1155,6 @@ pub(crate) unsafe fn xcbrt_u1(d: VDouble) -> VDouble {
         );
     }
 
-    #[cfg(target_feature = "avx512f")]
-    {
-        z = vsel_vd_vo_vd_vd(
-            visinf_vo_vd(s),
-            vmulsign_vd_vd_vd(vcast_vd_d(SLEEF_INFINITY), s),
-            z,
-        );
-        z = vsel_vd_vo_vd_vd(
-            veq_vo_vd_vd(s, vcast_vd_d(0.0)),
-            vmulsign_vd_vd_vd(vcast_vd_d(0.0), s),
-            z,
-        );
-    }
-
     z
 }
 
@@ -1294,7 +1243,6 @@ unsafe fn logk(d: VDouble) -> VDouble2 {
     let mut s: VDouble2;
     let t: VDouble;
 
-    #[cfg(not(target_feature = "avx512f"))]
     let (m, e) = {
         let o = vlt_vo_vd_vd(d, vcast_vd_d(SLEEF_DBL_MIN));
         let d = vsel_vd_vo_vd_vd(
@@ -1308,14 +1256,6 @@ unsafe fn logk(d: VDouble) -> VDouble2 {
         (m, e)
     };
 
-    #[cfg(target_feature = "avx512f")]
-    let (m, e) = {
-        let mut e = vgetexp_vd_vd(vmul_vd_vd_vd(d, vcast_vd_d(1.0 / 0.75)));
-        e = vsel_vd_vo_vd_vd(vispinf_vo_vd(e), vcast_vd_d(1024.0), e);
-        let m = vgetmant_vd_vd(d);
-        (m, e)
-    };
-
     x = dddiv_vd2_vd2_vd2(
         ddadd2_vd2_vd_vd(vcast_vd_d(-1.0), m),
         ddadd2_vd2_vd_vd(vcast_vd_d(1.0), m),
@@ -1343,7 +1283,6 @@ unsafe fn logk(d: VDouble) -> VDouble2 {
 
     let c = vcast_vd2_d_d(0.666666666666666629659233, 3.80554962542412056336616e-17);
 
-    #[cfg(not(target_feature = "avx512f"))]
     {
         s = ddmul_vd2_vd2_vd(
             vcast_vd2_d_d(0.693147180559945286226764, 2.319046813846299558417771e-17),
@@ -1351,14 +1290,6 @@ unsafe fn logk(d: VDouble) -> VDouble2 {
         );
     }
 
-    #[cfg(target_feature = "avx512f")]
-    {
-        s = ddmul_vd2_vd2_vd(
-            vcast_vd2_d_d(0.693147180559945286226764, 2.319046813846299558417771e-17),
-            e,
-        );
-    }
-
     s = ddadd_vd2_vd2_vd2(s, ddscale_vd2_vd2_vd(x, vcast_vd_d(2.0)));
     x = ddmul_vd2_vd2_vd2(x2, x);
     s = ddadd_vd2_vd2_vd2(s, ddmul_vd2_vd2_vd2(x, c));
@@ -1921,7 +1852,6 @@ pub(crate) unsafe fn xlog10(d: VDouble) -> VDouble {
     let t: VDouble;
     let x2: VDouble;
 
-    #[cfg(not(target_feature = "avx512f"))]
     let (m, e) = {
         let o = vlt_vo_vd_vd(d, vcast_vd_d(SLEEF_DBL_MIN));
         let d = vsel_vd_vo_vd_vd(o, vmul_vd_vd_vd(d, vcast_vd_d(2_f64.powi(64))), d);
@@ -1931,14 +1861,6 @@ pub(crate) unsafe fn xlog10(d: VDouble) -> VDouble {
         (m, e)
     };
 
-    #[cfg(target_feature = "avx512f")]
-    let (m, e) = {
-        let mut e = vgetexp_vd_vd(vmul_vd_vd_vd(d, vcast_vd_d(1.0 / 0.75)));
-        e = vsel_vd_vo_vd_vd(vispinf_vo_vd(e), vcast_vd_d(1024.0), e);
-        let m = vgetmant_vd_vd(d);
-        (m, e)
-    };
-
     x = dddiv_vd2_vd2_vd2(
         ddadd2_vd2_vd_vd(vcast_vd_d(-1.0), m),
         ddadd2_vd2_vd_vd(vcast_vd_d(1.0), m),
@@ -1960,18 +1882,11 @@ pub(crate) unsafe fn xlog10(d: VDouble) -> VDouble {
         0.2895296546021972617e+0,
     );
 
-    #[cfg(not(target_feature = "avx512f"))]
     let mut s = ddmul_vd2_vd2_vd(
         vcast_vd2_d_d(0.30102999566398119802, -2.803728127785170339e-18),
         vcast_vd_vi(e),
     );
 
-    #[cfg(target_feature = "avx512f")]
-    let mut s = ddmul_vd2_vd2_vd(
-        vcast_vd2_d_d(0.30102999566398119802, -2.803728127785170339e-18),
-        e,
-    );
-
     s = ddadd_vd2_vd2_vd2(
         s,
         ddmul_vd2_vd2_vd2(
@@ -1983,7 +1898,6 @@ pub(crate) unsafe fn xlog10(d: VDouble) -> VDouble {
 
     let mut r = vadd_vd_vd_vd(vd2getx_vd_vd2(s), vd2gety_vd_vd2(s));
 
-    #[cfg(not(target_feature = "avx512f"))]
     {
         r = vsel_vd_vo_vd_vd(vispinf_vo_vd(d), vcast_vd_d(f64::INFINITY), r);
         r = vsel_vd_vo_vd_vd(
@@ -1998,16 +1912,6 @@ pub(crate) unsafe fn xlog10(d: VDouble) -> VDouble {
         );
     }
 
-    #[cfg(target_feature = "avx512f")]
-    {
-        r = vfixup_vd_vd_vd_vi2_i(
-            r,
-            d,
-            vcast_vi2_i((4 << (2 * 4)) | (3 << (4 * 4)) | (5 << (5 * 4)) | (2 << (6 * 4))),
-            0,
-        );
-    }
-
     r
 }
 
@@ -2017,7 +1921,6 @@ pub(crate) unsafe fn xlog2(d: VDouble) -> VDouble {
     let t: VDouble;
     let x2: VDouble;
 
-    #[cfg(not(target_feature = "avx512f"))]
     let (m, e) = {
         let o = vlt_vo_vd_vd(d, vcast_vd_d(SLEEF_DBL_MIN));
         let d = vsel_vd_vo_vd_vd(o, vmul_vd_vd_vd(d, vcast_vd_d(2_f64.powi(64))), d);
@@ -2027,14 +1930,6 @@ pub(crate) unsafe fn xlog2(d: VDouble) -> VDouble {
         (m, e)
     };
 
-    #[cfg(target_feature = "avx512f")]
-    let (m, e) = {
-        let mut e = vgetexp_vd_vd(vmul_vd_vd_vd(d, vcast_vd_d(1.0 / 0.75)));
-        e = vsel_vd_vo_vd_vd(vispinf_vo_vd(e), vcast_vd_d(1024.0), e);
-        let m = vgetmant_vd_vd(d);
-        (m, e)
-    };
-
     x = dddiv_vd2_vd2_vd2(
         ddadd2_vd2_vd_vd(vcast_vd_d(-1.0), m),
         ddadd2_vd2_vd_vd(vcast_vd_d(1.0), m),
@@ -2056,7 +1951,6 @@ pub(crate) unsafe fn xlog2(d: VDouble) -> VDouble {
         0.96179669392608091449,
     );
 
-    #[cfg(not(target_feature = "avx512f"))]
     let mut s = ddadd2_vd2_vd_vd2(
         vcast_vd_vi(e),
         ddmul_vd2_vd2_vd2(
@@ -2065,20 +1959,10 @@ pub(crate) unsafe fn xlog2(d: VDouble) -> VDouble {
         ),
     );
 
-    #[cfg(target_feature = "avx512f")]
-    let mut s = ddadd2_vd2_vd_vd2(
-        e,
-        ddmul_vd2_vd2_vd2(
-            x,
-            vcast_vd2_d_d(2.885390081777926774, 6.0561604995516736434e-18),
-        ),
-    );
-
     s = ddadd2_vd2_vd2_vd(s, vmul_vd_vd_vd(vmul_vd_vd_vd(x2, vd2getx_vd_vd2(x)), t));
 
     let mut r = vadd_vd_vd_vd(vd2getx_vd_vd2(s), vd2gety_vd_vd2(s));
 
-    #[cfg(not(target_feature = "avx512f"))]
     {
         r = vsel_vd_vo_vd_vd(vispinf_vo_vd(d), vcast_vd_d(f64::INFINITY), r);
         r = vsel_vd_vo_vd_vd(
@@ -2093,16 +1977,6 @@ pub(crate) unsafe fn xlog2(d: VDouble) -> VDouble {
         );
     }
 
-    #[cfg(target_feature = "avx512f")]
-    {
-        r = vfixup_vd_vd_vd_vi2_i(
-            r,
-            d,
-            vcast_vi2_i((4 << (2 * 4)) | (3 << (4 * 4)) | (5 << (5 * 4)) | (2 << (6 * 4))),
-            0,
-        );
-    }
-
     r
 }
 
@@ -2114,7 +1988,6 @@ pub(crate) unsafe fn xlog1p(d: VDouble) -> VDouble {
 
     let dp1 = vadd_vd_vd_vd(d, vcast_vd_d(1.0));
 
-    #[cfg(not(target_feature = "avx512f"))]
     let (m, s) = {
         let o = vlt_vo_vd_vd(dp1, vcast_vd_d(SLEEF_DBL_MIN));
         let dp1 = vsel_vd_vo_vd_vd(o, vmul_vd_vd_vd(dp1, vcast_vd_d(2_f64.powi(64))), dp1);
@@ -2129,19 +2002,6 @@ pub(crate) unsafe fn xlog1p(d: VDouble) -> VDouble {
         (m, s)
     };
 
-    #[cfg(target_feature = "avx512f")]
-    let (m, s) = {
-        let mut e = vgetexp_vd_vd(vmul_vd_vd_vd(dp1, vcast_vd_d(1.0 / 0.75)));
-        e = vsel_vd_vo_vd_vd(vispinf_vo_vd(e), vcast_vd_d(1024.0), e);
-        let t = vldexp3_vd_vd_vi(vcast_vd_d(1.0), vneg_vi_vi(vrint_vi_vd(e)));
-        let m = vmla_vd_vd_vd_vd(d, t, vsub_vd_vd_vd(t, vcast_vd_d(1.0)));
-        let s = ddmul_vd2_vd2_vd(
-            vcast_vd2_d_d(0.693147180559945286226764, 2.319046813846299558417771e-17),
-            e,
-        );
-        (m, s)
-    };
-
     x = dddiv_vd2_vd2_vd2(
         vcast_vd2_vd_vd(m, vcast_vd_d(0.0)),
         ddadd_vd2_vd_vd(vcast_vd_d(2.0), m),
@@ -241,12 +241,6 @@ pub(crate) unsafe fn rempif(a: VFloat) -> Dfit {
     let mut y: VFloat2;
     let mut ex = vilogb2k_vi2_vf(a);
 
-    #[cfg(target_feature = "avx512f")]
-    {
-        ex = vandnot_vi2_vi2_vi2(vsra_vi2_vi2_i::<31>(ex), ex);
-        ex = vand_vi2_vi2_vi2(ex, vcast_vi2_i(127));
-    }
-
     ex = vsub_vi2_vi2_vi2(ex, vcast_vi2_i(25));
     let mut q = vand_vi2_vo_vi2(vgt_vo_vi2_vi2(ex, vcast_vi2_i(90 - 25)), vcast_vi2_i(-64));
     let a = vldexp3_vf_vf_vi2(a, q);
@@ -983,7 +977,6 @@ pub(crate) unsafe fn xlogf_u1(d: VFloat) -> VFloat {
     let mut t: VFloat;
     let x2: VFloat;
 
-    #[cfg(not(target_feature = "avx512f"))]
     let (m, s) = {
         let o = vlt_vo_vf_vf(d, vcast_vf_f(SLEEF_FLT_MIN));
         let d = vsel_vf_vo_vf_vf(
@@ -1001,18 +994,6 @@ pub(crate) unsafe fn xlogf_u1(d: VFloat) -> VFloat {
         (m, s)
     };
 
-    #[cfg(target_feature = "avx512f")]
-    let (m, s) = {
-        let mut e = vgetexp_vf_vf(vmul_vf_vf_vf(d, vcast_vf_f(1.0f32 / 0.75f32)));
-        e = vsel_vf_vo_vf_vf(vispinf_vo_vf(e), vcast_vf_f(128.0f32), e);
-        let m = vgetmant_vf_vf(d);
-        let s = dfmul_vf2_vf2_vf(
-            vcast_vf2_f_f(0.69314718246459960938f32, -1.904654323148236017e-9f32),
-            e,
-        );
-        (m, s)
-    };
-
     x = dfdiv_vf2_vf2_vf2(
         dfadd2_vf2_vf_vf(vcast_vf_f(-1.0), m),
         dfadd2_vf2_vf_vf(vcast_vf_f(1.0), m),
@@ -1028,7 +1009,6 @@ pub(crate) unsafe fn xlogf_u1(d: VFloat) -> VFloat {
 
     let mut r = vadd_vf_vf_vf(vf2getx_vf_vf2(s), vf2gety_vf_vf2(s));
 
-    #[cfg(not(target_feature = "avx512f"))]
     {
         r = vsel_vf_vo_vf_vf(vispinf_vo_vf(d), vcast_vf_f(f32::INFINITY), r);
         r = vsel_vf_vo_vf_vf(
@@ -1043,16 +1023,6 @@ pub(crate) unsafe fn xlogf_u1(d: VFloat) -> VFloat {
         );
     }
 
-    #[cfg(target_feature = "avx512f")]
-    {
-        r = vfixup_vf_vf_vf_vi2_i(
-            r,
-            d,
-            vcast_vi2_i((4 << (2 * 4)) | (3 << (4 * 4)) | (5 << (5 * 4)) | (2 << (6 * 4))),
-            0,
-        );
-    }
-
     r
 }
 
@@ -1069,9 +1039,6 @@ pub(crate) unsafe fn xcbrtf_u1(d: VFloat) -> VFloat {
     let qu: VInt2;
     let re: VInt2;
 
-    #[cfg(target_feature = "avx512f")]
-    let s = d;
-
     e = vadd_vi2_vi2_vi2(vilogbk_vi2_vf(vabs_vf_vf(d)), vcast_vi2_i(1));
     let d = vldexp2_vf_vf_vi2(d, vneg_vi2_vi2(e));
 
@@ -1139,20 +1106,6 @@ pub(crate) unsafe fn xcbrtf_u1(d: VFloat) -> VFloat {
         z,
     );
 
-    #[cfg(target_feature = "avx512f")]
-    {
-        z = vsel_vf_vo_vf_vf(
-            visinf_vo_vf(s),
-            vmulsign_vf_vf_vf(vcast_vf_f(SLEEF_INFINITYf), s),
-            z,
-        );
-        z = vsel_vf_vo_vf_vf(
-            veq_vo_vf_vf(s, vcast_vf_f(0.0)),
-            vmulsign_vf_vf_vf(vcast_vf_f(0.0), s),
-            z,
-        );
-    }
-
     z
 }
 
@@ -1247,7 +1200,6 @@ unsafe fn logkf(d: VFloat) -> VFloat2 {
     let x2: VFloat2;
     let mut t: VFloat;
 
-    #[cfg(not(target_feature = "avx512f"))]
     let (m, e) = {
         let o = vlt_vo_vf_vf(d, vcast_vf_f(SLEEF_FLT_MIN));
         let d = vsel_vf_vo_vf_vf(
@@ -1261,14 +1213,6 @@ unsafe fn logkf(d: VFloat) -> VFloat2 {
         (m, e)
     };
 
-    #[cfg(target_feature = "avx512f")]
-    let (m, e) = {
-        let mut e = vgetexp_vf_vf(vmul_vf_vf_vf(d, vcast_vf_f(1.0f32 / 0.75f32)));
-        e = vsel_vf_vo_vf_vf(vispinf_vo_vf(e), vcast_vf_f(128.0f32), e);
-        let m = vgetmant_vf_vf(d);
-        (m, e)
-    };
-
     x = dfdiv_vf2_vf2_vf2(
         dfadd2_vf2_vf_vf(vcast_vf_f(-1.0), m),
         dfadd2_vf2_vf_vf(vcast_vf_f(1.0), m),
@@ -1291,18 +1235,11 @@ unsafe fn logkf(d: VFloat) -> VFloat2 {
         3.69183861259614332084311e-9f32,
     );
 
-    #[cfg(not(target_feature = "avx512f"))]
     let mut s = dfmul_vf2_vf2_vf(
         vcast_vf2_f_f(0.69314718246459960938f32, -1.904654323148236017e-9f32),
         vcast_vf_vi2(e),
     );
 
-    #[cfg(target_feature = "avx512f")]
-    let mut s = dfmul_vf2_vf2_vf(
-        vcast_vf2_f_f(0.69314718246459960938f32, -1.904654323148236017e-9f32),
-        e,
-    );
-
     s = dfadd_vf2_vf2_vf2(s, dfscale_vf2_vf2_vf(x, vcast_vf_f(2.0)));
     s = dfadd_vf2_vf2_vf2(
         s,
@@ -1473,18 +1410,11 @@ unsafe fn logk2f(d: VFloat2) -> VFloat2 {
     let mut s: VFloat2;
     let mut t: VFloat;
 
-    #[cfg(not(target_feature = "avx512f"))]
     let e = vilogbk_vi2_vf(vmul_vf_vf_vf(
         vf2getx_vf_vf2(d),
         vcast_vf_f(1.0f32 / 0.75f32),
     ));
 
-    #[cfg(target_feature = "avx512f")]
-    let e = vrint_vi2_vf(vgetexp_vf_vf(vmul_vf_vf_vf(
-        vf2getx_vf_vf2(d),
-        vcast_vf_f(1.0f32 / 0.75f32),
-    )));
-
     let m = dfscale_vf2_vf2_vf(d, vpow2i_vf_vi2(vneg_vi2_vi2(e)));
 
     x = dfdiv_vf2_vf2_vf2(
@@ -1771,7 +1701,6 @@ pub(crate) unsafe fn xlog10f(d: VFloat) -> VFloat {
     let mut t: VFloat;
     let x2: VFloat;
 
-    #[cfg(not(target_feature = "avx512f"))]
     let (m, e) = {
         let o = vlt_vo_vf_vf(d, vcast_vf_f(SLEEF_FLT_MIN));
         let d = vsel_vf_vo_vf_vf(
@@ -1788,14 +1717,6 @@ pub(crate) unsafe fn xlog10f(d: VFloat) -> VFloat {
         (m, e)
     };
 
-    #[cfg(target_feature = "avx512f")]
-    let (m, e) = {
-        let mut e = vgetexp_vf_vf(vmul_vf_vf_vf(d, vcast_vf_f(1.0 / 0.75)));
-        e = vsel_vf_vo_vf_vf(vispinf_vo_vf(e), vcast_vf_f(128.0), e);
-        let m = vgetmant_vf_vf(d);
-        (m, e)
-    };
-
     x = dfdiv_vf2_vf2_vf2(
         dfadd2_vf2_vf_vf(vcast_vf_f(-1.0), m),
         dfadd2_vf2_vf_vf(vcast_vf_f(1.0), m),
@@ -1806,15 +1727,11 @@ pub(crate) unsafe fn xlog10f(d: VFloat) -> VFloat {
     t = vmla_vf_vf_vf_vf(t, x2, vcast_vf_f(0.1735493541f32));
     t = vmla_vf_vf_vf_vf(t, x2, vcast_vf_f(0.2895309627f32));
 
-    #[cfg(not(target_feature = "avx512f"))]
     let mut s = dfmul_vf2_vf2_vf(
         vcast_vf2_f_f(0.30103001f32, -1.432098889e-8f32),
         vcast_vf_vi2(e),
     );
 
-    #[cfg(target_feature = "avx512f")]
-    let mut s = dfmul_vf2_vf2_vf(vcast_vf2_f_f(0.30103001f32, -1.432098889e-8f32), e);
-
     s = dfadd_vf2_vf2_vf2(
         s,
         dfmul_vf2_vf2_vf2(x, vcast_vf2_f_f(0.868588984f32, -2.170757285e-8f32)),
@@ -1823,7 +1740,6 @@ pub(crate) unsafe fn xlog10f(d: VFloat) -> VFloat {
 
     let mut r = vadd_vf_vf_vf(vf2getx_vf_vf2(s), vf2gety_vf_vf2(s));
 
-    #[cfg(not(target_feature = "avx512f"))]
     {
         r = vsel_vf_vo_vf_vf(vispinf_vo_vf(d), vcast_vf_f(f32::INFINITY), r);
         r = vsel_vf_vo_vf_vf(
@@ -1838,16 +1754,6 @@ pub(crate) unsafe fn xlog10f(d: VFloat) -> VFloat {
         );
     }
 
-    #[cfg(target_feature = "avx512f")]
-    {
-        r = vfixup_vf_vf_vf_vi2_i(
-            r,
-            d,
-            vcast_vi2_i((4 << (2 * 4)) | (3 << (4 * 4)) | (5 << (5 * 4)) | (2 << (6 * 4))),
-            0,
-        );
-    }
-
     r
 }
 
@@ -1857,7 +1763,6 @@ pub(crate) unsafe fn xlog2f(d: VFloat) -> VFloat {
     let mut t: VFloat;
     let x2: VFloat;
 
-    #[cfg(not(target_feature = "avx512f"))]
     let (m, e) = {
         let o = vlt_vo_vf_vf(d, vcast_vf_f(SLEEF_FLT_MIN));
         let d = vsel_vf_vo_vf_vf(
@@ -1874,14 +1779,6 @@ pub(crate) unsafe fn xlog2f(d: VFloat) -> VFloat {
         (m, e)
     };
 
-    #[cfg(target_feature = "avx512f")]
-    let (m, e) = {
-        let mut e = vgetexp_vf_vf(vmul_vf_vf_vf(d, vcast_vf_f(1.0 / 0.75)));
-        e = vsel_vf_vo_vf_vf(vispinf_vo_vf(e), vcast_vf_f(128.0), e);
-        let m = vgetmant_vf_vf(d);
-        (m, e)
-    };
-
     x = dfdiv_vf2_vf2_vf2(
         dfadd2_vf2_vf_vf(vcast_vf_f(-1.0), m),
         dfadd2_vf2_vf_vf(vcast_vf_f(1.0), m),
@@ -1892,7 +1789,6 @@ pub(crate) unsafe fn xlog2f(d: VFloat) -> VFloat {
     t = vmla_vf_vf_vf_vf(t, x2, vcast_vf_f(0.5764790177f32));
     t = vmla_vf_vf_vf_vf(t, x2, vcast_vf_f(0.961801290512f32));
 
-    #[cfg(not(target_feature = "avx512f"))]
     let mut s = dfadd2_vf2_vf_vf2(
         vcast_vf_vi2(e),
         dfmul_vf2_vf2_vf2(
@@ -1901,20 +1797,10 @@ pub(crate) unsafe fn xlog2f(d: VFloat) -> VFloat {
         ),
     );
 
-    #[cfg(target_feature = "avx512f")]
-    let mut s = dfadd2_vf2_vf_vf2(
-        e,
-        dfmul_vf2_vf2_vf2(
-            x,
-            vcast_vf2_f_f(2.8853900432586669922f32, 3.2734474483568488616e-8f32),
-        ),
-    );
-
     s = dfadd2_vf2_vf2_vf(s, vmul_vf_vf_vf(vmul_vf_vf_vf(x2, vf2getx_vf_vf2(x)), t));
 
     let mut r = vadd_vf_vf_vf(vf2getx_vf_vf2(s), vf2gety_vf_vf2(s));
 
-    #[cfg(not(target_feature = "avx512f"))]
     {
         r = vsel_vf_vo_vf_vf(vispinf_vo_vf(d), vcast_vf_f(f32::INFINITY), r);
         r = vsel_vf_vo_vf_vf(
@@ -1929,16 +1815,6 @@ pub(crate) unsafe fn xlog2f(d: VFloat) -> VFloat {
         );
     }
 
-    #[cfg(target_feature = "avx512f")]
-    {
-        r = vfixup_vf_vf_vf_vi2_i(
-            r,
-            d,
-            vcast_vi2_i((4 << (2 * 4)) | (3 << (4 * 4)) | (5 << (5 * 4)) | (2 << (6 * 4))),
-            0,
-        );
-    }
-
     r
 }
 
@@ -1948,7 +1824,6 @@ pub(crate) unsafe fn xlog1pf(d: VFloat) -> VFloat {
 
     let dp1 = vadd_vf_vf_vf(d, vcast_vf_f(1.0));
 
-    #[cfg(not(target_feature = "avx512f"))]
     let (m, mut s) = {
         let o = vlt_vo_vf_vf(dp1, vcast_vf_f(SLEEF_FLT_MIN));
         let dp1 = vsel_vf_vo_vf_vf(
@@ -1970,19 +1845,6 @@ pub(crate) unsafe fn xlog1pf(d: VFloat) -> VFloat {
         (m, s)
     };
 
-    #[cfg(target_feature = "avx512f")]
-    let (m, mut s) = {
-        let mut e = vgetexp_vf_vf(vmul_vf_vf_vf(dp1, vcast_vf_f(1.0 / 0.75)));
-        e = vsel_vf_vo_vf_vf(vispinf_vo_vf(e), vcast_vf_f(128.0), e);
-        t = vldexp3_vf_vf_vi2(vcast_vf_f(1.0), vneg_vi2_vi2(vrint_vi2_vf(e)));
-        let m = vmla_vf_vf_vf_vf(d, t, vsub_vf_vf_vf(t, vcast_vf_f(1.0)));
-        let s = dfmul_vf2_vf2_vf(
-            vcast_vf2_f_f(0.69314718246459960938f32, -1.904654323148236017e-9f32),
-            e,
-        );
-        (m, s)
-    };
-
     let x = dfdiv_vf2_vf2_vf2(
         vcast_vf2_vf_vf(m, vcast_vf_f(0.0)),
         dfadd_vf2_vf_vf(vcast_vf_f(2.0), m),
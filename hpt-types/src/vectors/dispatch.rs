@@ -0,0 +1,278 @@
+use std::sync::atomic::{AtomicU8, Ordering};
+use std::sync::OnceLock;
+
+/// The environment variable overriding the detected simd level, one of `sse`, `avx2`, `avx512`, `neon` or
+/// `scalar`
+pub const SIMD_LEVEL_ENV: &str = "HPT_SIMD";
+
+/// The instruction sets the hot kernels can be dispatched to at runtime.
+///
+/// Every level has its own vector types (see [`Simd`]), the kernels written on them are instantiated
+/// once per level and [`dispatch_kernel`] picks the instantiation of the running cpu. The kernels on
+/// `T::Vec`, whose width is fixed by the target features the crate is compiled with, are wrapped by
+/// [`dispatch`] instead, which only recompiles them with the target features of the level.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum SimdLevel {
+    /// 128-bit sse on x86
+    Sse = 1,
+    /// 256-bit avx2 with fma on x86
+    Avx2 = 2,
+    /// 512-bit avx512f on x86
+    Avx512 = 3,
+    /// 128-bit neon on arm
+    Neon = 4,
+    /// single lane vectors on the targets without simd levels
+    Scalar = 5,
+}
+
+impl SimdLevel {
+    fn from_u8(v: u8) -> Option<Self> {
+        match v {
+            1 => Some(SimdLevel::Sse),
+            2 => Some(SimdLevel::Avx2),
+            3 => Some(SimdLevel::Avx512),
+            4 => Some(SimdLevel::Neon),
+            5 => Some(SimdLevel::Scalar),
+            _ => None,
+        }
+    }
+
+    /// parse a simd level name, case insensitive
+    pub fn parse(name: &str) -> Option<Self> {
+        match name.trim().to_ascii_lowercase().as_str() {
+            "sse" | "sse2" | "sse4.1" => Some(SimdLevel::Sse),
+            "avx2" | "avx" => Some(SimdLevel::Avx2),
+            "avx512" | "avx512f" => Some(SimdLevel::Avx512),
+            "neon" => Some(SimdLevel::Neon),
+            "scalar" => Some(SimdLevel::Scalar),
+            _ => None,
+        }
+    }
+
+    /// the lowest simd level the binary is compiled for, the kernels never run below this level
+    pub const fn compiled() -> Self {
+        if cfg!(target_arch = "aarch64") {
+            SimdLevel::Neon
+        } else if !cfg!(target_arch = "x86_64") {
+            SimdLevel::Scalar
+        } else if cfg!(target_feature = "avx512f") {
+            SimdLevel::Avx512
+        } else if cfg!(target_feature = "avx2") {
+            SimdLevel::Avx2
+        } else {
+            SimdLevel::Sse
+        }
+    }
+
+    /// the best simd level the running cpu supports, detected once
+    pub fn detected() -> Self {
+        static DETECTED: OnceLock<SimdLevel> = OnceLock::new();
+        *DETECTED.get_or_init(|| {
+            #[cfg(target_arch = "x86_64")]
+            {
+                if std::is_x86_feature_detected!("avx512f") {
+                    return SimdLevel::Avx512;
+                }
+                if std::is_x86_feature_detected!("avx2") && std::is_x86_feature_detected!("fma") {
+                    return SimdLevel::Avx2;
+                }
+            }
+            SimdLevel::compiled()
+        })
+    }
+
+    /// clamp the level between the compiled and the detected level, a level of another architecture
+    /// falls back to the compiled level
+    fn clamp_supported(self) -> Self {
+        let (compiled, detected) = (SimdLevel::compiled(), SimdLevel::detected());
+        let x86 = |level| matches!(level, SimdLevel::Sse | SimdLevel::Avx2 | SimdLevel::Avx512);
+        if x86(self) && x86(compiled) {
+            self.max(compiled).min(detected)
+        } else {
+            compiled
+        }
+    }
+}
+
+impl std::fmt::Display for SimdLevel {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            SimdLevel::Sse => "sse",
+            SimdLevel::Avx2 => "avx2",
+            SimdLevel::Avx512 => "avx512",
+            SimdLevel::Neon => "neon",
+            SimdLevel::Scalar => "scalar",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// 0 means not initialized yet
+static LEVEL: AtomicU8 = AtomicU8::new(0);
+
+/// the simd level the kernels are dispatched to
+///
+/// defaults to [`SimdLevel::detected`], `HPT_SIMD` can lower it for testing, a level the cpu doesn't
+/// support is clamped to the detected one.
+pub fn simd_level() -> SimdLevel {
+    if let Some(level) = SimdLevel::from_u8(LEVEL.load(Ordering::Relaxed)) {
+        return level;
+    }
+    let level = std::env::var(SIMD_LEVEL_ENV)
+        .ok()
+        .and_then(|name| SimdLevel::parse(&name))
+        .unwrap_or_else(SimdLevel::detected)
+        .clamp_supported();
+    LEVEL.store(level as u8, Ordering::Relaxed);
+    level
+}
+
+/// override the simd level the kernels are dispatched to, returns the level actually applied
+pub fn set_simd_level(level: SimdLevel) -> SimdLevel {
+    let level = level.clamp_supported();
+    LEVEL.store(level as u8, Ordering::Relaxed);
+    level
+}
+
+/// run `f` with the code generated for the current [`simd_level`]
+///
+/// `f` should be the body of a kernel processing a chunk of data on the vectors of `T::Vec`, it is
+/// called from a function compiled with the target features of the simd level. The kernels on the
+/// vectors of the levels have to implement [`SimdKernel`] instead, a closure isn't reliably inlined.
+#[inline(always)]
+pub fn dispatch<R>(f: impl FnOnce() -> R) -> R {
+    struct Vectorize<F>(F);
+    impl<R, F: FnOnce() -> R> SimdKernel for Vectorize<F> {
+        type Output = R;
+        #[inline(always)]
+        fn run<S: Simd>(self, _: S) -> R {
+            (self.0)()
+        }
+    }
+    dispatch_kernel(Vectorize(f))
+}
+
+/// An instruction set the kernels are instantiated for.
+///
+/// A value of a level can only be created by [`dispatch_kernel`] once the running cpu is known to
+/// support it, the vectors of the level are safe to compute with as long as one exists.
+pub trait Simd: Copy + Send + Sync + 'static {
+    /// the runtime level of the instruction set
+    const LEVEL: SimdLevel;
+    /// the width of the vectors in bits
+    const WIDTH: usize;
+    /// the number of vector registers, the kernels block their accumulators by it
+    const REGNUM: usize;
+    /// the vector of f32
+    type F32: SimdVec<f32, Level = Self>;
+    /// the vector of f64
+    type F64: SimdVec<f64, Level = Self>;
+
+    /// run `kernel` with the vectors of the level in a function compiled with its target features
+    ///
+    /// [`dispatch_kernel`] already runs the kernels this way, the work a kernel hands to other threads
+    /// doesn't inherit the target features and has to be wrapped in a kernel and vectorized again.
+    fn vectorize<K: SimdKernel>(self, kernel: K) -> K::Output;
+}
+
+/// The scalar types that have a vector in every simd level
+pub trait SimdElement: Copy + Send + Sync + 'static {
+    /// the vector of the element in the level `S`
+    type Vector<S: Simd>: SimdVec<Self, Level = S>;
+}
+
+impl SimdElement for f32 {
+    type Vector<S: Simd> = S::F32;
+}
+
+impl SimdElement for f64 {
+    type Vector<S: Simd> = S::F64;
+}
+
+/// A vector of a simd level
+pub trait SimdVec<T>: Copy + Send + Sync + 'static {
+    /// the number of elements in the vector
+    const LANES: usize;
+    /// the simd level of the vector
+    type Level: Simd;
+
+    /// create a vector with all lanes set to `v`
+    fn splat(simd: Self::Level, v: T) -> Self;
+    /// load a vector from `ptr`
+    ///
+    /// # Safety
+    ///
+    /// `ptr` must be valid for `LANES` reads, it doesn't need to be aligned, and the cpu must support
+    /// the level of the vector.
+    unsafe fn load(ptr: *const T) -> Self;
+    /// store the vector to `ptr`
+    ///
+    /// # Safety
+    ///
+    /// `ptr` must be valid for `LANES` writes, it doesn't need to be aligned.
+    unsafe fn store(self, ptr: *mut T);
+    /// self + rhs
+    fn add(self, rhs: Self) -> Self;
+    /// self - rhs
+    fn sub(self, rhs: Self) -> Self;
+    /// self * rhs
+    fn mul(self, rhs: Self) -> Self;
+    /// self / rhs
+    fn div(self, rhs: Self) -> Self;
+    /// self * a + b, fused when the level has fma
+    fn mul_add(self, a: Self, b: Self) -> Self;
+    /// the maximum of the lanes, a nan lane is ignored like [`f32::max`]
+    fn max(self, rhs: Self) -> Self;
+    /// the minimum of the lanes, a nan lane is ignored like [`f32::min`]
+    fn min(self, rhs: Self) -> Self;
+    /// square root
+    fn sqrt(self) -> Self;
+    /// absolute value
+    fn abs(self) -> Self;
+    /// negation
+    fn neg(self) -> Self;
+    /// sum of the lanes
+    fn reduce_add(self) -> T;
+    /// product of the lanes
+    fn reduce_mul(self) -> T;
+    /// maximum of the lanes
+    fn reduce_max(self) -> T;
+    /// minimum of the lanes
+    fn reduce_min(self) -> T;
+}
+
+/// A kernel written on the vectors of [`Simd`], instantiated for every level
+///
+/// The target features of the level only reach the code inlined into [`Simd::vectorize`], `run` and
+/// the functions it calls on the vectors have to be `#[inline(always)]`, otherwise every vector op
+/// becomes a call.
+pub trait SimdKernel {
+    /// the output of the kernel
+    type Output;
+    /// run the kernel with the vectors of the level `S`
+    fn run<S: Simd>(self, simd: S) -> Self::Output;
+}
+
+/// run `kernel` with the level of [`simd_level`], the level is read once and the whole kernel is
+/// compiled with its target features
+#[inline(always)]
+pub fn dispatch_kernel<K: SimdKernel>(kernel: K) -> K::Output {
+    #[cfg(target_arch = "x86_64")]
+    {
+        use crate::vectors::levels::{avx2::Avx2, avx512::Avx512, sse::Sse};
+        // `simd_level` never returns a level the cpu doesn't support
+        match simd_level() {
+            SimdLevel::Avx512 => Avx512(()).vectorize(kernel),
+            SimdLevel::Avx2 => Avx2(()).vectorize(kernel),
+            _ => Sse(()).vectorize(kernel),
+        }
+    }
+    #[cfg(target_arch = "aarch64")]
+    {
+        crate::vectors::levels::neon::Neon(()).vectorize(kernel)
+    }
+    #[cfg(not(any(target_arch = "x86_64", target_arch = "aarch64")))]
+    {
+        crate::vectors::levels::scalar::Scalar(()).vectorize(kernel)
+    }
+}
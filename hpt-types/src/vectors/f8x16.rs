@@ -0,0 +1,294 @@
+use crate::dispatch::{dispatch_kernel, Simd, SimdKernel, SimdVec};
+use crate::dtype::TypeCommon;
+use crate::f8::{f8e4m3, f8e5m2};
+use crate::into_vec::IntoVec;
+use crate::traits::VecTrait;
+use crate::type_promote::{FloatOutBinary, FloatOutUnary, NormalOut, NormalOutUnary};
+
+/// the f32 vector the ops the simd levels don't have widen to, `f32x4`, `f32x8` or `f32x16` depends on
+/// the target features the crate is compiled with
+type F32Vec = <f32 as TypeCommon>::Vec;
+const F32_LANES: usize = <F32Vec as VecTrait<f32>>::SIZE;
+/// the number of f32 vectors a vector of 16 8-bit floats widens to
+const CHUNKS: usize = 16 / F32_LANES;
+
+/// the arithmetic of the 8-bit float vectors done on the f32 vectors of the runtime simd level
+#[derive(Clone, Copy)]
+enum LevelOp {
+    Add,
+    Sub,
+    Mul,
+    Div,
+    MulAdd,
+    Sqrt,
+    Abs,
+    Neg,
+}
+
+/// `op` on the lanes widened to f32, the operands are `lanes[0]`, `lanes[1]` and `lanes[2]` and the
+/// result is written to `lanes[0]`
+struct Widened<'a> {
+    op: LevelOp,
+    lanes: &'a mut [[f32; 16]; 3],
+}
+
+impl SimdKernel for Widened<'_> {
+    type Output = ();
+
+    #[inline(always)]
+    fn run<S: Simd>(self, _: S) {
+        let [a, b, c] = self.lanes;
+        for i in (0..16).step_by(S::F32::LANES) {
+            unsafe {
+                let [x, y, z] = [&*a, b, c].map(|lanes| S::F32::load(lanes.as_ptr().add(i)));
+                let res = match self.op {
+                    LevelOp::Add => x.add(y),
+                    LevelOp::Sub => x.sub(y),
+                    LevelOp::Mul => x.mul(y),
+                    LevelOp::Div => x.div(y),
+                    LevelOp::MulAdd => x.mul_add(y, z),
+                    LevelOp::Sqrt => x.sqrt(),
+                    LevelOp::Abs => x.abs(),
+                    LevelOp::Neg => x.neg(),
+                };
+                res.store(a.as_mut_ptr().add(i));
+            }
+        }
+    }
+}
+
+/// a vector of 16 f8e4m3 values
+#[allow(non_camel_case_types)]
+#[derive(Default, Clone, Copy, PartialEq, Debug)]
+#[repr(C, align(16))]
+pub struct f8e4m3x16(pub(crate) [f8e4m3; 16]);
+
+/// a vector of 16 f8e5m2 values
+#[allow(non_camel_case_types)]
+#[derive(Default, Clone, Copy, PartialEq, Debug)]
+#[repr(C, align(16))]
+pub struct f8e5m2x16(pub(crate) [f8e5m2; 16]);
+
+/// helper to impl the promote trait
+#[allow(non_camel_case_types)]
+pub(crate) type f8e4m3_promote = f8e4m3x16;
+/// helper to impl the promote trait
+#[allow(non_camel_case_types)]
+pub(crate) type f8e5m2_promote = f8e5m2x16;
+
+macro_rules! impl_f8_vec {
+    ($vec:ident, $f8:ident) => {
+        impl $vec {
+            /// widen the vector to f32 vectors, the arithmetic of the vector is done on them
+            #[inline(always)]
+            pub fn to_f32_vecs(self) -> [F32Vec; CHUNKS] {
+                let mut ret = [F32Vec::splat(0.0); CHUNKS];
+                for (i, vec) in ret.iter_mut().enumerate() {
+                    let mut lanes = [0.0f32; F32_LANES];
+                    for (j, lane) in lanes.iter_mut().enumerate() {
+                        *lane = self.0[i * F32_LANES + j].to_f32();
+                    }
+                    vec.copy_from_slice(&lanes);
+                }
+                ret
+            }
+            /// narrow f32 vectors to a vector of 8-bit floats, rounding to nearest even
+            #[inline(always)]
+            pub fn from_f32_vecs(vecs: [F32Vec; CHUNKS]) -> Self {
+                let mut ret = [$f8::ZERO; 16];
+                for (i, vec) in vecs.iter().enumerate() {
+                    for j in 0..F32_LANES {
+                        ret[i * F32_LANES + j] = $f8::from_f32(vec[j]);
+                    }
+                }
+                $vec(ret)
+            }
+            /// widen the lanes of `self`, `b` and `c` to f32 and compute `op` on the f32 vectors of the
+            /// runtime simd level, `f32x4`, `f32x8` or `f32x16`
+            #[inline(always)]
+            fn level_op(self, op: LevelOp, b: Option<Self>, c: Option<Self>) -> Self {
+                let widen = |v: Option<Self>| v.map_or([0.0; 16], |v| v.0.map(|x| x.to_f32()));
+                let mut lanes = [widen(Some(self)), widen(b), widen(c)];
+                dispatch_kernel(Widened { op, lanes: &mut lanes });
+                $vec(lanes[0].map($f8::from_f32))
+            }
+            #[inline(always)]
+            fn map(self, f: impl Fn(F32Vec) -> F32Vec) -> Self {
+                let mut vecs = self.to_f32_vecs();
+                for vec in vecs.iter_mut() {
+                    *vec = f(*vec);
+                }
+                Self::from_f32_vecs(vecs)
+            }
+            #[inline(always)]
+            fn zip(self, rhs: Self, f: impl Fn(F32Vec, F32Vec) -> F32Vec) -> Self {
+                let mut vecs = self.to_f32_vecs();
+                for (vec, rhs) in vecs.iter_mut().zip(rhs.to_f32_vecs()) {
+                    *vec = f(*vec, rhs);
+                }
+                Self::from_f32_vecs(vecs)
+            }
+            #[inline(always)]
+            fn zip3(self, a: Self, b: Self, f: impl Fn(F32Vec, F32Vec, F32Vec) -> F32Vec) -> Self {
+                let mut vecs = self.to_f32_vecs();
+                let (a, b) = (a.to_f32_vecs(), b.to_f32_vecs());
+                for (i, vec) in vecs.iter_mut().enumerate() {
+                    *vec = f(*vec, a[i], b[i]);
+                }
+                Self::from_f32_vecs(vecs)
+            }
+        }
+
+        impl VecTrait<$f8> for $vec {
+            const SIZE: usize = 16;
+            type Base = $f8;
+            #[inline(always)]
+            fn mul_add(self, a: Self, b: Self) -> Self {
+                self.level_op(LevelOp::MulAdd, Some(a), Some(b))
+            }
+            #[inline(always)]
+            fn copy_from_slice(&mut self, slice: &[$f8]) {
+                self.0.copy_from_slice(slice);
+            }
+            #[inline(always)]
+            fn sum(&self) -> $f8 {
+                $f8::from_f32(self.0.iter().map(|x| x.to_f32()).sum())
+            }
+            #[inline(always)]
+            fn splat(val: $f8) -> Self {
+                $vec([val; 16])
+            }
+            #[inline(always)]
+            unsafe fn from_ptr(ptr: *const $f8) -> Self {
+                $vec((ptr as *const [$f8; 16]).read_unaligned())
+            }
+        }
+
+        impl IntoVec<$vec> for $vec {
+            #[inline(always)]
+            fn into_vec(self) -> $vec {
+                self
+            }
+        }
+
+        impl NormalOut for $vec {
+            type Output = Self;
+            #[inline(always)]
+            fn _add(self, rhs: Self) -> Self {
+                self.level_op(LevelOp::Add, Some(rhs), None)
+            }
+            #[inline(always)]
+            fn _sub(self, rhs: Self) -> Self {
+                self.level_op(LevelOp::Sub, Some(rhs), None)
+            }
+            #[inline(always)]
+            fn _mul_add(self, a: Self, b: Self) -> Self {
+                self.level_op(LevelOp::MulAdd, Some(a), Some(b))
+            }
+            #[inline(always)]
+            fn _mul(self, rhs: Self) -> Self {
+                self.level_op(LevelOp::Mul, Some(rhs), None)
+            }
+            #[inline(always)]
+            fn _pow(self, rhs: Self) -> Self {
+                self.zip(rhs, |a, b| a._pow(b))
+            }
+            #[inline(always)]
+            fn _rem(self, rhs: Self) -> Self {
+                self.zip(rhs, |a, b| a._rem(b))
+            }
+            #[inline(always)]
+            fn _max(self, rhs: Self) -> Self {
+                self.zip(rhs, |a, b| a._max(b))
+            }
+            #[inline(always)]
+            fn _min(self, rhs: Self) -> Self {
+                self.zip(rhs, |a, b| a._min(b))
+            }
+            #[inline(always)]
+            fn _clamp(self, min: Self, max: Self) -> Self {
+                self.zip3(min, max, |x, min, max| x._clamp(min, max))
+            }
+        }
+
+        impl NormalOutUnary for $vec {
+            impl_f8_vec!(@unary _square, _ceil, _floor, _round, _signum, _trunc, _relu, _relu6);
+            #[inline(always)]
+            fn _abs(self) -> Self {
+                self.level_op(LevelOp::Abs, None, None)
+            }
+            #[inline(always)]
+            fn _neg(self) -> Self {
+                self.level_op(LevelOp::Neg, None, None)
+            }
+            #[inline(always)]
+            fn _leaky_relu(self, alpha: Self) -> Self {
+                self.zip(alpha, |x, alpha| x._leaky_relu(alpha))
+            }
+            #[inline(always)]
+            fn _copysign(self, rhs: Self) -> Self {
+                self.zip(rhs, |a, b| a._copysign(b))
+            }
+        }
+
+        impl FloatOutBinary for $vec {
+            type Output = Self;
+            #[inline(always)]
+            fn _div(self, rhs: Self) -> Self {
+                self.level_op(LevelOp::Div, Some(rhs), None)
+            }
+            #[inline(always)]
+            fn _log(self, base: Self) -> Self {
+                self.zip(base, |a, b| a._log(b))
+            }
+            #[inline(always)]
+            fn _hypot(self, rhs: Self) -> Self {
+                self.zip(rhs, |a, b| a._hypot(b))
+            }
+        }
+
+        impl FloatOutUnary for $vec {
+            type Output = Self;
+            impl_f8_vec!(@unary
+                _exp, _expm1, _exp2, _exp10, _ln, _log1p, _log2, _log10, _sin, _cos, _tan, _asin,
+                _acos, _atan, _sinh, _cosh, _tanh, _asinh, _acosh, _atanh, _recip, _erf, _sigmoid,
+                _gelu, _hard_sigmoid, _hard_swish, _softplus, _softsign, _mish, _cbrt
+            );
+            #[inline(always)]
+            fn _sqrt(self) -> Self {
+                self.level_op(LevelOp::Sqrt, None, None)
+            }
+            #[inline(always)]
+            fn _celu(self, alpha: Self) -> Self {
+                self.zip(alpha, |x, alpha| x._celu(alpha))
+            }
+            #[inline(always)]
+            fn _sincos(self) -> (Self, Self) {
+                (self._sin(), self._cos())
+            }
+            #[inline(always)]
+            fn _atan2(self, rhs: Self) -> Self {
+                self.zip(rhs, |a, b| a._atan2(b))
+            }
+            #[inline(always)]
+            fn _elu(self, alpha: Self) -> Self {
+                self.zip(alpha, |x, alpha| x._elu(alpha))
+            }
+            #[inline(always)]
+            fn _selu(self, alpha: Self, scale: Self) -> Self {
+                self.zip3(alpha, scale, |x, alpha, scale| x._selu(alpha, scale))
+            }
+        }
+    };
+    (@unary $($method:ident),*) => {
+        $(
+            #[inline(always)]
+            fn $method(self) -> Self {
+                self.map(|x| x.$method())
+            }
+        )*
+    };
+}
+
+impl_f8_vec!(f8e4m3x16, f8e4m3);
+impl_f8_vec!(f8e5m2x16, f8e5m2);
@@ -0,0 +1,136 @@
+use std::arch::x86_64::*;
+
+use crate::dispatch::{Simd, SimdKernel, SimdLevel, SimdVec};
+
+/// 256-bit avx2 with fma
+#[derive(Debug, Clone, Copy)]
+pub struct Avx2(pub(crate) ());
+
+impl Simd for Avx2 {
+    const LEVEL: SimdLevel = SimdLevel::Avx2;
+    const WIDTH: usize = 256;
+    const REGNUM: usize = 16;
+    type F32 = f32x8;
+    type F64 = f64x4;
+
+    #[inline(always)]
+    fn vectorize<K: SimdKernel>(self, kernel: K) -> K::Output {
+        #[target_feature(enable = "avx2,fma")]
+        unsafe fn vectorize<K: SimdKernel>(simd: Avx2, kernel: K) -> K::Output {
+            kernel.run(simd)
+        }
+        // safety: an `Avx2` only exists when the cpu supports avx2 and fma
+        unsafe { vectorize(self, kernel) }
+    }
+}
+
+/// a vector of 8 f32 in an avx2 register
+#[allow(non_camel_case_types)]
+#[derive(Debug, Clone, Copy)]
+pub struct f32x8(__m256);
+
+/// a vector of 4 f64 in an avx2 register
+#[allow(non_camel_case_types)]
+#[derive(Debug, Clone, Copy)]
+pub struct f64x4(__m256d);
+
+macro_rules! impl_avx2_vec {
+    ($vec:ident, $elem:ty, $lanes:expr, $suffix:ident) => {
+        paste::paste! {
+            impl SimdVec<$elem> for $vec {
+                const LANES: usize = $lanes;
+                type Level = Avx2;
+
+                #[inline(always)]
+                fn splat(_: Avx2, v: $elem) -> Self {
+                    $vec(unsafe { [<_mm256_set1_ $suffix>](v) })
+                }
+                #[inline(always)]
+                unsafe fn load(ptr: *const $elem) -> Self {
+                    $vec([<_mm256_loadu_ $suffix>](ptr))
+                }
+                #[inline(always)]
+                unsafe fn store(self, ptr: *mut $elem) {
+                    [<_mm256_storeu_ $suffix>](ptr, self.0)
+                }
+                #[inline(always)]
+                fn add(self, rhs: Self) -> Self {
+                    $vec(unsafe { [<_mm256_add_ $suffix>](self.0, rhs.0) })
+                }
+                #[inline(always)]
+                fn sub(self, rhs: Self) -> Self {
+                    $vec(unsafe { [<_mm256_sub_ $suffix>](self.0, rhs.0) })
+                }
+                #[inline(always)]
+                fn mul(self, rhs: Self) -> Self {
+                    $vec(unsafe { [<_mm256_mul_ $suffix>](self.0, rhs.0) })
+                }
+                #[inline(always)]
+                fn div(self, rhs: Self) -> Self {
+                    $vec(unsafe { [<_mm256_div_ $suffix>](self.0, rhs.0) })
+                }
+                #[inline(always)]
+                fn mul_add(self, a: Self, b: Self) -> Self {
+                    $vec(unsafe { [<_mm256_fmadd_ $suffix>](self.0, a.0, b.0) })
+                }
+                #[inline(always)]
+                fn max(self, rhs: Self) -> Self {
+                    // `max` returns `rhs` when any operand is nan, keep `self` when `rhs` is nan
+                    unsafe {
+                        let max = [<_mm256_max_ $suffix>](self.0, rhs.0);
+                        let nan = [<_mm256_cmp_ $suffix>]::<_CMP_UNORD_Q>(rhs.0, rhs.0);
+                        $vec([<_mm256_blendv_ $suffix>](max, self.0, nan))
+                    }
+                }
+                #[inline(always)]
+                fn min(self, rhs: Self) -> Self {
+                    unsafe {
+                        let min = [<_mm256_min_ $suffix>](self.0, rhs.0);
+                        let nan = [<_mm256_cmp_ $suffix>]::<_CMP_UNORD_Q>(rhs.0, rhs.0);
+                        $vec([<_mm256_blendv_ $suffix>](min, self.0, nan))
+                    }
+                }
+                #[inline(always)]
+                fn sqrt(self) -> Self {
+                    $vec(unsafe { [<_mm256_sqrt_ $suffix>](self.0) })
+                }
+                #[inline(always)]
+                fn abs(self) -> Self {
+                    $vec(unsafe { [<_mm256_andnot_ $suffix>]([<_mm256_set1_ $suffix>](-0.0), self.0) })
+                }
+                #[inline(always)]
+                fn neg(self) -> Self {
+                    $vec(unsafe { [<_mm256_xor_ $suffix>]([<_mm256_set1_ $suffix>](-0.0), self.0) })
+                }
+                #[inline(always)]
+                fn reduce_add(self) -> $elem {
+                    self.lanes().into_iter().fold(0.0, |acc, x| acc + x)
+                }
+                #[inline(always)]
+                fn reduce_mul(self) -> $elem {
+                    self.lanes().into_iter().fold(1.0, |acc, x| acc * x)
+                }
+                #[inline(always)]
+                fn reduce_max(self) -> $elem {
+                    self.lanes().into_iter().fold(<$elem>::NAN, <$elem>::max)
+                }
+                #[inline(always)]
+                fn reduce_min(self) -> $elem {
+                    self.lanes().into_iter().fold(<$elem>::NAN, <$elem>::min)
+                }
+            }
+
+            impl $vec {
+                #[inline(always)]
+                fn lanes(self) -> [$elem; $lanes] {
+                    let mut lanes = [0.0; $lanes];
+                    unsafe { [<_mm256_storeu_ $suffix>](lanes.as_mut_ptr(), self.0) };
+                    lanes
+                }
+            }
+        }
+    };
+}
+
+impl_avx2_vec!(f32x8, f32, 8, ps);
+impl_avx2_vec!(f64x4, f64, 4, pd);
@@ -0,0 +1,141 @@
+use std::arch::x86_64::*;
+
+use crate::dispatch::{Simd, SimdKernel, SimdLevel, SimdVec};
+
+/// 512-bit avx512f
+#[derive(Debug, Clone, Copy)]
+pub struct Avx512(pub(crate) ());
+
+impl Simd for Avx512 {
+    const LEVEL: SimdLevel = SimdLevel::Avx512;
+    const WIDTH: usize = 512;
+    const REGNUM: usize = 32;
+    type F32 = f32x16;
+    type F64 = f64x8;
+
+    #[inline(always)]
+    fn vectorize<K: SimdKernel>(self, kernel: K) -> K::Output {
+        #[target_feature(enable = "avx512f,avx2,fma")]
+        unsafe fn vectorize<K: SimdKernel>(simd: Avx512, kernel: K) -> K::Output {
+            kernel.run(simd)
+        }
+        // safety: an `Avx512` only exists when the cpu supports avx512f
+        unsafe { vectorize(self, kernel) }
+    }
+}
+
+/// a vector of 16 f32 in an avx512 register
+#[allow(non_camel_case_types)]
+#[derive(Debug, Clone, Copy)]
+pub struct f32x16(__m512);
+
+/// a vector of 8 f64 in an avx512 register
+#[allow(non_camel_case_types)]
+#[derive(Debug, Clone, Copy)]
+pub struct f64x8(__m512d);
+
+macro_rules! impl_avx512_vec {
+    ($vec:ident, $elem:ty, $lanes:expr, $suffix:ident) => {
+        paste::paste! {
+            impl SimdVec<$elem> for $vec {
+                const LANES: usize = $lanes;
+                type Level = Avx512;
+
+                #[inline(always)]
+                fn splat(_: Avx512, v: $elem) -> Self {
+                    $vec(unsafe { [<_mm512_set1_ $suffix>](v) })
+                }
+                #[inline(always)]
+                unsafe fn load(ptr: *const $elem) -> Self {
+                    $vec([<_mm512_loadu_ $suffix>](ptr))
+                }
+                #[inline(always)]
+                unsafe fn store(self, ptr: *mut $elem) {
+                    [<_mm512_storeu_ $suffix>](ptr, self.0)
+                }
+                #[inline(always)]
+                fn add(self, rhs: Self) -> Self {
+                    $vec(unsafe { [<_mm512_add_ $suffix>](self.0, rhs.0) })
+                }
+                #[inline(always)]
+                fn sub(self, rhs: Self) -> Self {
+                    $vec(unsafe { [<_mm512_sub_ $suffix>](self.0, rhs.0) })
+                }
+                #[inline(always)]
+                fn mul(self, rhs: Self) -> Self {
+                    $vec(unsafe { [<_mm512_mul_ $suffix>](self.0, rhs.0) })
+                }
+                #[inline(always)]
+                fn div(self, rhs: Self) -> Self {
+                    $vec(unsafe { [<_mm512_div_ $suffix>](self.0, rhs.0) })
+                }
+                #[inline(always)]
+                fn mul_add(self, a: Self, b: Self) -> Self {
+                    $vec(unsafe { [<_mm512_fmadd_ $suffix>](self.0, a.0, b.0) })
+                }
+                #[inline(always)]
+                fn max(self, rhs: Self) -> Self {
+                    // `max` returns `rhs` when any operand is nan, keep `self` when `rhs` is nan
+                    unsafe {
+                        let max = [<_mm512_max_ $suffix>](self.0, rhs.0);
+                        let nan = [<_mm512_cmp_ $suffix _mask>]::<_CMP_UNORD_Q>(rhs.0, rhs.0);
+                        $vec([<_mm512_mask_blend_ $suffix>](nan, max, self.0))
+                    }
+                }
+                #[inline(always)]
+                fn min(self, rhs: Self) -> Self {
+                    unsafe {
+                        let min = [<_mm512_min_ $suffix>](self.0, rhs.0);
+                        let nan = [<_mm512_cmp_ $suffix _mask>]::<_CMP_UNORD_Q>(rhs.0, rhs.0);
+                        $vec([<_mm512_mask_blend_ $suffix>](nan, min, self.0))
+                    }
+                }
+                #[inline(always)]
+                fn sqrt(self) -> Self {
+                    $vec(unsafe { [<_mm512_sqrt_ $suffix>](self.0) })
+                }
+                #[inline(always)]
+                fn abs(self) -> Self {
+                    $vec(unsafe { [<_mm512_abs_ $suffix>](self.0) })
+                }
+                #[inline(always)]
+                fn neg(self) -> Self {
+                    // the float xor needs avx512dq, flip the sign bit on the integer lanes
+                    unsafe {
+                        let bits = [<_mm512_cast $suffix _si512>](self.0);
+                        let sign = [<_mm512_cast $suffix _si512>]([<_mm512_set1_ $suffix>](-0.0));
+                        $vec([<_mm512_castsi512_ $suffix>](_mm512_xor_si512(bits, sign)))
+                    }
+                }
+                #[inline(always)]
+                fn reduce_add(self) -> $elem {
+                    self.lanes().into_iter().fold(0.0, |acc, x| acc + x)
+                }
+                #[inline(always)]
+                fn reduce_mul(self) -> $elem {
+                    self.lanes().into_iter().fold(1.0, |acc, x| acc * x)
+                }
+                #[inline(always)]
+                fn reduce_max(self) -> $elem {
+                    self.lanes().into_iter().fold(<$elem>::NAN, <$elem>::max)
+                }
+                #[inline(always)]
+                fn reduce_min(self) -> $elem {
+                    self.lanes().into_iter().fold(<$elem>::NAN, <$elem>::min)
+                }
+            }
+
+            impl $vec {
+                #[inline(always)]
+                fn lanes(self) -> [$elem; $lanes] {
+                    let mut lanes = [0.0; $lanes];
+                    unsafe { [<_mm512_storeu_ $suffix>](lanes.as_mut_ptr(), self.0) };
+                    lanes
+                }
+            }
+        }
+    };
+}
+
+impl_avx512_vec!(f32x16, f32, 16, ps);
+impl_avx512_vec!(f64x8, f64, 8, pd);
@@ -0,0 +1,123 @@
+use std::arch::aarch64::*;
+
+use crate::dispatch::{Simd, SimdKernel, SimdLevel, SimdVec};
+
+/// 128-bit neon, the baseline of aarch64
+#[derive(Debug, Clone, Copy)]
+pub struct Neon(pub(crate) ());
+
+impl Simd for Neon {
+    const LEVEL: SimdLevel = SimdLevel::Neon;
+    const WIDTH: usize = 128;
+    const REGNUM: usize = 32;
+    type F32 = f32x4;
+    type F64 = f64x2;
+
+    #[inline(always)]
+    fn vectorize<K: SimdKernel>(self, kernel: K) -> K::Output {
+        kernel.run(self)
+    }
+}
+
+/// a vector of 4 f32 in a neon register
+#[allow(non_camel_case_types)]
+#[derive(Debug, Clone, Copy)]
+pub struct f32x4(float32x4_t);
+
+/// a vector of 2 f64 in a neon register
+#[allow(non_camel_case_types)]
+#[derive(Debug, Clone, Copy)]
+pub struct f64x2(float64x2_t);
+
+macro_rules! impl_neon_vec {
+    ($vec:ident, $elem:ty, $lanes:expr, $suffix:ident) => {
+        paste::paste! {
+            #[allow(unused_unsafe)]
+            impl SimdVec<$elem> for $vec {
+                const LANES: usize = $lanes;
+                type Level = Neon;
+
+                #[inline(always)]
+                fn splat(_: Neon, v: $elem) -> Self {
+                    $vec(unsafe { [<vdupq_n_ $suffix>](v) })
+                }
+                #[inline(always)]
+                unsafe fn load(ptr: *const $elem) -> Self {
+                    $vec([<vld1q_ $suffix>](ptr))
+                }
+                #[inline(always)]
+                unsafe fn store(self, ptr: *mut $elem) {
+                    [<vst1q_ $suffix>](ptr, self.0)
+                }
+                #[inline(always)]
+                fn add(self, rhs: Self) -> Self {
+                    $vec(unsafe { [<vaddq_ $suffix>](self.0, rhs.0) })
+                }
+                #[inline(always)]
+                fn sub(self, rhs: Self) -> Self {
+                    $vec(unsafe { [<vsubq_ $suffix>](self.0, rhs.0) })
+                }
+                #[inline(always)]
+                fn mul(self, rhs: Self) -> Self {
+                    $vec(unsafe { [<vmulq_ $suffix>](self.0, rhs.0) })
+                }
+                #[inline(always)]
+                fn div(self, rhs: Self) -> Self {
+                    $vec(unsafe { [<vdivq_ $suffix>](self.0, rhs.0) })
+                }
+                #[inline(always)]
+                fn mul_add(self, a: Self, b: Self) -> Self {
+                    $vec(unsafe { [<vfmaq_ $suffix>](b.0, self.0, a.0) })
+                }
+                #[inline(always)]
+                fn max(self, rhs: Self) -> Self {
+                    $vec(unsafe { [<vmaxnmq_ $suffix>](self.0, rhs.0) })
+                }
+                #[inline(always)]
+                fn min(self, rhs: Self) -> Self {
+                    $vec(unsafe { [<vminnmq_ $suffix>](self.0, rhs.0) })
+                }
+                #[inline(always)]
+                fn sqrt(self) -> Self {
+                    $vec(unsafe { [<vsqrtq_ $suffix>](self.0) })
+                }
+                #[inline(always)]
+                fn abs(self) -> Self {
+                    $vec(unsafe { [<vabsq_ $suffix>](self.0) })
+                }
+                #[inline(always)]
+                fn neg(self) -> Self {
+                    $vec(unsafe { [<vnegq_ $suffix>](self.0) })
+                }
+                #[inline(always)]
+                fn reduce_add(self) -> $elem {
+                    self.lanes().into_iter().fold(0.0, |acc, x| acc + x)
+                }
+                #[inline(always)]
+                fn reduce_mul(self) -> $elem {
+                    self.lanes().into_iter().fold(1.0, |acc, x| acc * x)
+                }
+                #[inline(always)]
+                fn reduce_max(self) -> $elem {
+                    self.lanes().into_iter().fold(<$elem>::NAN, <$elem>::max)
+                }
+                #[inline(always)]
+                fn reduce_min(self) -> $elem {
+                    self.lanes().into_iter().fold(<$elem>::NAN, <$elem>::min)
+                }
+            }
+
+            impl $vec {
+                #[inline(always)]
+                fn lanes(self) -> [$elem; $lanes] {
+                    let mut lanes = [0.0; $lanes];
+                    unsafe { [<vst1q_ $suffix>](lanes.as_mut_ptr(), self.0) };
+                    lanes
+                }
+            }
+        }
+    };
+}
+
+impl_neon_vec!(f32x4, f32, 4, f32);
+impl_neon_vec!(f64x2, f64, 2, f64);
@@ -0,0 +1,109 @@
+use crate::dispatch::{Simd, SimdKernel, SimdLevel, SimdVec};
+
+/// single lane vectors, the kernels of the levels still run on the targets without one
+#[derive(Debug, Clone, Copy)]
+pub struct Scalar(pub(crate) ());
+
+impl Simd for Scalar {
+    const LEVEL: SimdLevel = SimdLevel::Scalar;
+    const WIDTH: usize = 64;
+    const REGNUM: usize = 16;
+    type F32 = f32x1;
+    type F64 = f64x1;
+
+    #[inline(always)]
+    fn vectorize<K: SimdKernel>(self, kernel: K) -> K::Output {
+        kernel.run(self)
+    }
+}
+
+/// a vector of 1 f32
+#[allow(non_camel_case_types)]
+#[derive(Debug, Clone, Copy)]
+pub struct f32x1(f32);
+
+/// a vector of 1 f64
+#[allow(non_camel_case_types)]
+#[derive(Debug, Clone, Copy)]
+pub struct f64x1(f64);
+
+macro_rules! impl_scalar_vec {
+    ($vec:ident, $elem:ty) => {
+        impl SimdVec<$elem> for $vec {
+            const LANES: usize = 1;
+            type Level = Scalar;
+
+            #[inline(always)]
+            fn splat(_: Scalar, v: $elem) -> Self {
+                $vec(v)
+            }
+            #[inline(always)]
+            unsafe fn load(ptr: *const $elem) -> Self {
+                $vec(ptr.read_unaligned())
+            }
+            #[inline(always)]
+            unsafe fn store(self, ptr: *mut $elem) {
+                ptr.write_unaligned(self.0)
+            }
+            #[inline(always)]
+            fn add(self, rhs: Self) -> Self {
+                $vec(self.0 + rhs.0)
+            }
+            #[inline(always)]
+            fn sub(self, rhs: Self) -> Self {
+                $vec(self.0 - rhs.0)
+            }
+            #[inline(always)]
+            fn mul(self, rhs: Self) -> Self {
+                $vec(self.0 * rhs.0)
+            }
+            #[inline(always)]
+            fn div(self, rhs: Self) -> Self {
+                $vec(self.0 / rhs.0)
+            }
+            #[inline(always)]
+            fn mul_add(self, a: Self, b: Self) -> Self {
+                $vec(self.0 * a.0 + b.0)
+            }
+            #[inline(always)]
+            fn max(self, rhs: Self) -> Self {
+                $vec(self.0.max(rhs.0))
+            }
+            #[inline(always)]
+            fn min(self, rhs: Self) -> Self {
+                $vec(self.0.min(rhs.0))
+            }
+            #[inline(always)]
+            fn sqrt(self) -> Self {
+                $vec(self.0.sqrt())
+            }
+            #[inline(always)]
+            fn abs(self) -> Self {
+                $vec(self.0.abs())
+            }
+            #[inline(always)]
+            fn neg(self) -> Self {
+                $vec(-self.0)
+            }
+            #[inline(always)]
+            fn reduce_add(self) -> $elem {
+                self.0
+            }
+            #[inline(always)]
+            fn reduce_mul(self) -> $elem {
+                self.0
+            }
+            #[inline(always)]
+            fn reduce_max(self) -> $elem {
+                self.0
+            }
+            #[inline(always)]
+            fn reduce_min(self) -> $elem {
+                self.0
+            }
+        }
+    };
+}
+
+impl_scalar_vec!(f32x1, f32);
+impl_scalar_vec!(f64x1, f64);
@@ -0,0 +1,137 @@
+use std::arch::x86_64::*;
+
+use crate::dispatch::{Simd, SimdKernel, SimdLevel, SimdVec};
+
+/// 128-bit sse2, the baseline of x86_64
+#[derive(Debug, Clone, Copy)]
+pub struct Sse(pub(crate) ());
+
+impl Simd for Sse {
+    const LEVEL: SimdLevel = SimdLevel::Sse;
+    const WIDTH: usize = 128;
+    const REGNUM: usize = 16;
+    type F32 = f32x4;
+    type F64 = f64x2;
+
+    #[inline(always)]
+    fn vectorize<K: SimdKernel>(self, kernel: K) -> K::Output {
+        kernel.run(self)
+    }
+}
+
+/// a vector of 4 f32 in an sse register
+#[allow(non_camel_case_types)]
+#[derive(Debug, Clone, Copy)]
+pub struct f32x4(__m128);
+
+/// a vector of 2 f64 in an sse register
+#[allow(non_camel_case_types)]
+#[derive(Debug, Clone, Copy)]
+pub struct f64x2(__m128d);
+
+macro_rules! impl_sse_vec {
+    ($vec:ident, $elem:ty, $lanes:expr, $suffix:ident) => {
+        paste::paste! {
+            impl SimdVec<$elem> for $vec {
+                const LANES: usize = $lanes;
+                type Level = Sse;
+
+                #[inline(always)]
+                fn splat(_: Sse, v: $elem) -> Self {
+                    $vec(unsafe { [<_mm_set1_ $suffix>](v) })
+                }
+                #[inline(always)]
+                unsafe fn load(ptr: *const $elem) -> Self {
+                    $vec([<_mm_loadu_ $suffix>](ptr))
+                }
+                #[inline(always)]
+                unsafe fn store(self, ptr: *mut $elem) {
+                    [<_mm_storeu_ $suffix>](ptr, self.0)
+                }
+                #[inline(always)]
+                fn add(self, rhs: Self) -> Self {
+                    $vec(unsafe { [<_mm_add_ $suffix>](self.0, rhs.0) })
+                }
+                #[inline(always)]
+                fn sub(self, rhs: Self) -> Self {
+                    $vec(unsafe { [<_mm_sub_ $suffix>](self.0, rhs.0) })
+                }
+                #[inline(always)]
+                fn mul(self, rhs: Self) -> Self {
+                    $vec(unsafe { [<_mm_mul_ $suffix>](self.0, rhs.0) })
+                }
+                #[inline(always)]
+                fn div(self, rhs: Self) -> Self {
+                    $vec(unsafe { [<_mm_div_ $suffix>](self.0, rhs.0) })
+                }
+                #[inline(always)]
+                fn mul_add(self, a: Self, b: Self) -> Self {
+                    $vec(unsafe { [<_mm_add_ $suffix>]([<_mm_mul_ $suffix>](self.0, a.0), b.0) })
+                }
+                #[inline(always)]
+                fn max(self, rhs: Self) -> Self {
+                    // `max` returns `rhs` when any operand is nan, keep `self` when `rhs` is nan
+                    unsafe {
+                        let max = [<_mm_max_ $suffix>](self.0, rhs.0);
+                        let nan = [<_mm_cmpunord_ $suffix>](rhs.0, rhs.0);
+                        $vec([<_mm_or_ $suffix>](
+                            [<_mm_and_ $suffix>](nan, self.0),
+                            [<_mm_andnot_ $suffix>](nan, max),
+                        ))
+                    }
+                }
+                #[inline(always)]
+                fn min(self, rhs: Self) -> Self {
+                    unsafe {
+                        let min = [<_mm_min_ $suffix>](self.0, rhs.0);
+                        let nan = [<_mm_cmpunord_ $suffix>](rhs.0, rhs.0);
+                        $vec([<_mm_or_ $suffix>](
+                            [<_mm_and_ $suffix>](nan, self.0),
+                            [<_mm_andnot_ $suffix>](nan, min),
+                        ))
+                    }
+                }
+                #[inline(always)]
+                fn sqrt(self) -> Self {
+                    $vec(unsafe { [<_mm_sqrt_ $suffix>](self.0) })
+                }
+                #[inline(always)]
+                fn abs(self) -> Self {
+                    $vec(unsafe { [<_mm_andnot_ $suffix>]([<_mm_set1_ $suffix>](-0.0), self.0) })
+                }
+                #[inline(always)]
+                fn neg(self) -> Self {
+                    $vec(unsafe { [<_mm_xor_ $suffix>]([<_mm_set1_ $suffix>](-0.0), self.0) })
+                }
+                #[inline(always)]
+                fn reduce_add(self) -> $elem {
+                    self.lanes().into_iter().fold(0.0, |acc, x| acc + x)
+                }
+                #[inline(always)]
+                fn reduce_mul(self) -> $elem {
+                    self.lanes().into_iter().fold(1.0, |acc, x| acc * x)
+                }
+                #[inline(always)]
+                fn reduce_max(self) -> $elem {
+                    self.lanes().into_iter().fold(<$elem>::NAN, <$elem>::max)
+                }
+                #[inline(always)]
+                fn reduce_min(self) -> $elem {
+                    self.lanes().into_iter().fold(<$elem>::NAN, <$elem>::min)
+                }
+            }
+
+            impl $vec {
+                #[inline(always)]
+                fn lanes(self) -> [$elem; $lanes] {
+                    let mut lanes = [0.0; $lanes];
+                    unsafe { [<_mm_storeu_ $suffix>](lanes.as_mut_ptr(), self.0) };
+                    lanes
+                }
+            }
+        }
+    };
+}
+
+impl_sse_vec!(f32x4, f32, 4, ps);
+impl_sse_vec!(f64x2, f64, 2, pd);
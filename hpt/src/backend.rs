@@ -33,6 +33,8 @@ pub struct Wgpu;
 #[derive(Clone)]
 pub struct Backend<B> {
     pub(crate) _backend: B,
+    /// whether the memory stands for an operation recorded in lazy mode, its data isn't computed yet
+    pub(crate) lazy: bool,
 }
 
 impl Clone for Cpu {
@@ -57,6 +59,7 @@ impl Backend<Cpu> {
                 ptr: address,
                 device_id,
             },
+            lazy: false,
         }
     }
 }
@@ -93,6 +96,7 @@ impl Backend<Cuda> {
                 device,
                 cap: (cap_major * 10 + cap_minor) as usize,
             },
+            lazy: false,
         }
     }
 }
@@ -0,0 +1,401 @@
+use std::{
+    cell::RefCell,
+    panic::Location,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, Condvar, Mutex, OnceLock, Weak,
+    },
+};
+
+use hpt_common::error::{base::TensorError, kernel::KernelError, param::ParamError};
+
+use crate::{get_deterministic, THREAD_POOL};
+
+thread_local! {
+    /// the context installed on this thread by `ExecutionContext::install`
+    static CURRENT: RefCell<Option<ExecutionContext>> = const { RefCell::new(None) };
+    /// the context owning this thread when it is one of the context's rayon workers
+    static WORKER_OF: RefCell<Option<Arc<OnceLock<Weak<Inner>>>>> = const { RefCell::new(None) };
+}
+
+struct Inner {
+    num_threads: usize,
+    cores: Option<Arc<[usize]>>,
+    deterministic: bool,
+    rayon: rayon::ThreadPool,
+    pool: threadpool::ThreadPool,
+    pool_workers: Arc<PoolWorkers>,
+}
+
+/// registers the threads of the context's `threadpool` as its workers, the thread pool has no start handler
+/// so the threads register when they run their first job
+struct PoolWorkers {
+    owner: Arc<OnceLock<Weak<Inner>>>,
+    cores: Option<Arc<[usize]>>,
+    next: AtomicUsize,
+}
+
+impl PoolWorkers {
+    fn register(&self) {
+        if WORKER_OF.with_borrow(|x| x.is_some()) {
+            return;
+        }
+        WORKER_OF.with_borrow_mut(|x| *x = Some(self.owner.clone()));
+        if let Some(cores) = &self.cores {
+            let idx = self.next.fetch_add(1, Ordering::Relaxed);
+            pin_current_thread(cores[idx % cores.len()]);
+        }
+    }
+}
+
+/// The resources the cpu kernels run with: the number of threads, the thread pools, whether the worker
+/// threads are pinned to cores and whether the kernels must produce run-to-run identical results.
+///
+/// A context does nothing until it is installed with [`ExecutionContext::install`]. Outside of any installed
+/// context, kernels use the global pools configured by [`set_num_threads`](crate::set_num_threads).
+///
+/// Cloning a context is cheap, the clones share the same thread pools.
+///
+/// # Example
+/// ```rust
+/// use hpt::{ExecutionContext, NormalReduce, Tensor, TensorCreator, TensorInfo};
+/// let ctx = ExecutionContext::builder().num_threads(2).build()?;
+/// let sum = ctx.install(|| {
+///     let a = Tensor::<f32>::ones(&[128, 128])?;
+///     a.sum(1, false)
+/// })?;
+/// assert_eq!(sum.shape().as_slice(), &[128]);
+/// # Ok::<(), hpt::TensorError>(())
+/// ```
+#[derive(Clone)]
+pub struct ExecutionContext {
+    inner: Arc<Inner>,
+}
+
+/// Builder for [`ExecutionContext`]
+#[derive(Debug, Clone)]
+pub struct ExecutionContextBuilder {
+    num_threads: usize,
+    pin_threads: bool,
+    cores: Option<Vec<usize>>,
+    deterministic: bool,
+    stack_size: usize,
+}
+
+impl Default for ExecutionContextBuilder {
+    fn default() -> Self {
+        Self {
+            num_threads: num_cpus::get_physical(),
+            pin_threads: false,
+            cores: None,
+            deterministic: false,
+            stack_size: 4 * 1024 * 1024,
+        }
+    }
+}
+
+impl ExecutionContextBuilder {
+    /// Set the number of worker threads, defaults to the number of physical cores
+    pub fn num_threads(mut self, num_threads: usize) -> Self {
+        self.num_threads = num_threads;
+        self
+    }
+
+    /// Pin the worker threads of both thread pools to cores, defaults to `false`.
+    ///
+    /// Worker `i` runs on `cores[i % cores.len()]`, the cores default to every logical core of the machine,
+    /// so contexts running at the same time should be given disjoint cores with [`cores`](Self::cores).
+    ///
+    /// # Note
+    /// Pinning is only supported on Linux, on other platforms this option is ignored.
+    pub fn pin_threads(mut self, pin_threads: bool) -> Self {
+        self.pin_threads = pin_threads;
+        self
+    }
+
+    /// Set the logical cores the worker threads are pinned to and enable [`pin_threads`](Self::pin_threads)
+    pub fn cores(mut self, cores: &[usize]) -> Self {
+        self.cores = Some(cores.to_vec());
+        self.pin_threads = true;
+        self
+    }
+
+    /// Enable the deterministic mode inside the context, see [`set_deterministic`](crate::set_deterministic), defaults to `false`
+    pub fn deterministic(mut self, deterministic: bool) -> Self {
+        self.deterministic = deterministic;
+        self
+    }
+
+    /// Set the stack size of the worker threads in bytes, defaults to 4MB
+    pub fn stack_size(mut self, stack_size: usize) -> Self {
+        self.stack_size = stack_size;
+        self
+    }
+
+    /// Spawn the thread pools and build the context
+    #[track_caller]
+    pub fn build(self) -> Result<ExecutionContext, TensorError> {
+        if self.num_threads == 0 {
+            return Err(ParamError::InvalidNumThreads {
+                num_threads: self.num_threads,
+                location: Location::caller(),
+            }
+            .into());
+        }
+        let cores: Option<Arc<[usize]>> = match (self.pin_threads, self.cores) {
+            (false, _) => None,
+            (true, None) => Some((0..num_cpus::get()).collect()),
+            (true, Some(cores)) => {
+                if cores.is_empty() {
+                    return Err(ParamError::InvalidCores {
+                        message: "expected at least 1 core".to_string(),
+                        location: Location::caller(),
+                    }
+                    .into());
+                }
+                if let Some(core) = cores.iter().find(|core| **core >= MAX_CORES) {
+                    return Err(ParamError::InvalidCores {
+                        message: format!(
+                            "core {} is out of range, expected less than {}",
+                            core, MAX_CORES
+                        ),
+                        location: Location::caller(),
+                    }
+                    .into());
+                }
+                Some(cores.into())
+            }
+        };
+        let owner: Arc<OnceLock<Weak<Inner>>> = Arc::new(OnceLock::new());
+        let worker_owner = owner.clone();
+        let worker_cores = cores.clone();
+        let rayon = rayon::ThreadPoolBuilder::new()
+            .num_threads(self.num_threads)
+            .stack_size(self.stack_size)
+            .thread_name(|idx| format!("hpt-worker-{}", idx))
+            .start_handler(move |idx| {
+                WORKER_OF.with_borrow_mut(|x| *x = Some(worker_owner.clone()));
+                if let Some(cores) = &worker_cores {
+                    pin_current_thread(cores[idx % cores.len()]);
+                }
+            })
+            .build()
+            .map_err(|e| KernelError::ExecutionFailed {
+                message: format!("failed to build the rayon thread pool: {}", e),
+                location: Location::caller(),
+            })?;
+        let pool = threadpool::Builder::new()
+            .num_threads(self.num_threads)
+            .thread_name("hpt-pool".to_string())
+            .thread_stack_size(self.stack_size)
+            .build();
+        let pool_workers = Arc::new(PoolWorkers {
+            owner: owner.clone(),
+            cores: cores.clone(),
+            next: AtomicUsize::new(0),
+        });
+        let inner = Arc::new(Inner {
+            num_threads: self.num_threads,
+            cores,
+            deterministic: self.deterministic,
+            rayon,
+            pool,
+            pool_workers,
+        });
+        let _ = owner.set(Arc::downgrade(&inner));
+        Ok(ExecutionContext { inner })
+    }
+}
+
+impl ExecutionContext {
+    /// Create a builder with the default settings
+    pub fn builder() -> ExecutionContextBuilder {
+        ExecutionContextBuilder::default()
+    }
+
+    /// Run `f` with this context installed.
+    ///
+    /// `f` runs on one of the context's worker threads, every kernel called inside `f`, including the
+    /// ones running on the rayon workers spawned by `f`, uses this context's thread pools. Contexts can be
+    /// nested, the innermost one wins.
+    pub fn install<F, R>(&self, f: F) -> R
+    where
+        F: FnOnce() -> R + Send,
+        R: Send,
+    {
+        self.inner.rayon.install(|| {
+            let prev = CURRENT.with_borrow_mut(|x| x.replace(self.clone()));
+            let _guard = Restore(prev);
+            f()
+        })
+    }
+
+    /// Get the context installed for the calling thread, if any
+    pub fn current() -> Option<ExecutionContext> {
+        if let Some(ctx) = CURRENT.with_borrow(|x| x.clone()) {
+            return Some(ctx);
+        }
+        WORKER_OF.with_borrow(|x| {
+            x.as_ref()
+                .and_then(|owner| owner.get())
+                .and_then(|weak| weak.upgrade())
+                .map(|inner| ExecutionContext { inner })
+        })
+    }
+
+    /// Number of worker threads of this context
+    pub fn num_threads(&self) -> usize {
+        self.inner.num_threads
+    }
+
+    /// Whether the worker threads are pinned to cores
+    pub fn pin_threads(&self) -> bool {
+        self.inner.cores.is_some()
+    }
+
+    /// The logical cores the worker threads are pinned to, `None` if they are not pinned
+    pub fn cores(&self) -> Option<&[usize]> {
+        self.inner.cores.as_deref()
+    }
+
+    /// Whether the kernels must produce results that don't depend on the number of threads
+    pub fn deterministic(&self) -> bool {
+        self.inner.deterministic
+    }
+}
+
+impl std::fmt::Debug for ExecutionContext {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ExecutionContext")
+            .field("num_threads", &self.inner.num_threads)
+            .field("cores", &self.inner.cores)
+            .field("deterministic", &self.inner.deterministic)
+            .finish()
+    }
+}
+
+struct Restore(Option<ExecutionContext>);
+
+impl Drop for Restore {
+    fn drop(&mut self) {
+        let prev = self.0.take();
+        CURRENT.with_borrow_mut(|x| *x = prev);
+    }
+}
+
+/// the jobs one kernel call runs on a thread pool, [`PoolScope::join`] and dropping the scope only wait for these
+/// jobs, the calls sharing the thread pool of a context don't wait for each other
+pub(crate) struct PoolScope<'a> {
+    pool: &'a threadpool::ThreadPool,
+    workers: Option<Arc<PoolWorkers>>,
+    pending: Arc<(Mutex<usize>, Condvar)>,
+}
+
+impl PoolScope<'_> {
+    /// number of threads of the thread pool
+    pub(crate) fn max_count(&self) -> usize {
+        self.pool.max_count()
+    }
+
+    /// run `job` on the thread pool
+    pub(crate) fn execute<F>(&self, job: F)
+    where
+        F: FnOnce() + Send + 'static,
+    {
+        *self.pending.0.lock().unwrap() += 1;
+        let done = Done(self.pending.clone());
+        let workers = self.workers.clone();
+        self.pool.execute(move || {
+            let _done = done;
+            if let Some(workers) = workers {
+                workers.register();
+            }
+            job();
+        });
+    }
+
+    /// wait for the jobs of this scope
+    pub(crate) fn join(&self) {
+        let (pending, cvar) = &*self.pending;
+        let mut pending = pending.lock().unwrap();
+        while *pending > 0 {
+            pending = cvar.wait(pending).unwrap();
+        }
+    }
+}
+
+impl Drop for PoolScope<'_> {
+    fn drop(&mut self) {
+        self.join();
+    }
+}
+
+/// marks a job of a [`PoolScope`] as finished, also when the job panics
+struct Done(Arc<(Mutex<usize>, Condvar)>);
+
+impl Drop for Done {
+    fn drop(&mut self) {
+        let (pending, cvar) = &*self.0;
+        *pending.lock().unwrap() -= 1;
+        cvar.notify_all();
+    }
+}
+
+/// run `f` with the thread pool of the installed context, or with the global thread pool if no context is installed
+pub(crate) fn with_thread_pool<R>(f: impl FnOnce(&PoolScope<'_>) -> R) -> R {
+    match ExecutionContext::current() {
+        Some(ctx) => {
+            let scope = PoolScope {
+                pool: &ctx.inner.pool,
+                workers: Some(ctx.inner.pool_workers.clone()),
+                pending: Arc::new((Mutex::new(0), Condvar::new())),
+            };
+            f(&scope)
+        }
+        None => THREAD_POOL.with_borrow(|pool| {
+            let scope = PoolScope {
+                pool,
+                workers: None,
+                pending: Arc::new((Mutex::new(0), Condvar::new())),
+            };
+            f(&scope)
+        }),
+    }
+}
+
+/// whether the kernels must produce results that don't depend on the number of threads, either because the global
+/// deterministic mode is enabled or because the installed context requests it
+pub(crate) fn is_deterministic() -> bool {
+    get_deterministic() || ExecutionContext::current().is_some_and(|ctx| ctx.deterministic())
+}
+
+/// fail with `KernelError::NotDeterministic` when `op` is called in deterministic mode
+#[track_caller]
+pub(crate) fn ensure_deterministic(op: &'static str) -> Result<(), TensorError> {
+    if is_deterministic() {
+        return Err(KernelError::NotDeterministic {
+            op,
+            location: Location::caller(),
+        }
+        .into());
+    }
+    Ok(())
+}
+
+#[cfg(target_os = "linux")]
+const MAX_CORES: usize = libc::CPU_SETSIZE as usize;
+#[cfg(not(target_os = "linux"))]
+const MAX_CORES: usize = usize::MAX;
+
+#[cfg(target_os = "linux")]
+fn pin_current_thread(core: usize) {
+    unsafe {
+        let mut set: libc::cpu_set_t = std::mem::zeroed();
+        libc::CPU_ZERO(&mut set);
+        libc::CPU_SET(core, &mut set);
+        libc::sched_setaffinity(0, std::mem::size_of::<libc::cpu_set_t>(), &set);
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+fn pin_current_thread(_: usize) {}
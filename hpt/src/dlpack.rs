@@ -0,0 +1,201 @@
+//! DLPack data structures, see <https://github.com/dmlc/dlpack>
+//!
+//! the layout follows `dlpack.h` v0.8 (`DLManagedTensor`, unversioned), which is the one accepted by most runtimes' `from_dlpack`
+
+use std::{ffi::c_void, panic::Location};
+
+use half::{bf16, f16};
+use hpt_common::error::{base::TensorError, param::ParamError};
+use hpt_types::dtype::TypeCommon;
+
+/// The device type in DLDevice.
+#[repr(i32)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DLDeviceType {
+    /// CPU device
+    Cpu = 1,
+    /// CUDA GPU device
+    Cuda = 2,
+    /// Pinned CUDA CPU memory by cudaMallocHost
+    CudaHost = 3,
+    /// OpenCL devices
+    OpenCL = 4,
+    /// Vulkan buffer for next generation graphics
+    Vulkan = 7,
+    /// Metal for Apple GPU
+    Metal = 8,
+    /// Verilog simulator buffer
+    Vpi = 9,
+    /// ROCm GPUs for AMD GPUs
+    Rocm = 10,
+    /// Pinned ROCm CPU memory allocated by hipMallocHost
+    RocmHost = 11,
+    /// Reserved extension device type
+    ExtDev = 12,
+    /// CUDA managed/unified memory allocated by cudaMallocManaged
+    CudaManaged = 13,
+    /// Unified shared memory allocated on a oneAPI non-partititioned device
+    OneApi = 14,
+    /// GPU support for next generation WebGPU standard
+    WebGpu = 15,
+    /// Qualcomm Hexagon DSP
+    Hexagon = 16,
+}
+
+/// The type code options of DLDataType.
+#[repr(u8)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DLDataTypeCode {
+    /// signed integer
+    Int = 0,
+    /// unsigned integer
+    UInt = 1,
+    /// IEEE floating point
+    Float = 2,
+    /// Opaque handle type, reserved for testing purposes
+    OpaqueHandle = 3,
+    /// bfloat16
+    Bfloat = 4,
+    /// complex number (C/C++/Python layout: compact struct per complex number)
+    Complex = 5,
+    /// boolean
+    Bool = 6,
+}
+
+/// A Device for Tensor and operator.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DLDevice {
+    /// The device type used in the device, value of [`DLDeviceType`]
+    pub device_type: i32,
+    /// The device index. For vanilla CPU memory, pinned memory, or managed memory, this is set to 0.
+    pub device_id: i32,
+}
+
+/// The data type the tensor can hold. The data type is assumed to follow the native endian-ness.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DLDataType {
+    /// Type code of base types, value of [`DLDataTypeCode`]
+    pub code: u8,
+    /// Number of bits, common choices are 8, 16, 32.
+    pub bits: u8,
+    /// Number of lanes in the type, used for vector types.
+    pub lanes: u16,
+}
+
+/// Plain C Tensor object, does not manage memory.
+#[repr(C)]
+#[derive(Debug)]
+pub struct DLTensor {
+    /// The data pointer points to the allocated data.
+    pub data: *mut c_void,
+    /// The device of the tensor
+    pub device: DLDevice,
+    /// Number of dimensions
+    pub ndim: i32,
+    /// The data type of the pointer
+    pub dtype: DLDataType,
+    /// The shape of the tensor
+    pub shape: *mut i64,
+    /// strides of the tensor (in number of elements, not bytes), can be NULL, indicating tensor is compact and row-majored.
+    pub strides: *mut i64,
+    /// The offset in bytes to the beginning pointer to data
+    pub byte_offset: u64,
+}
+
+/// C Tensor object, manage memory of DLTensor.
+///
+/// This data structure is intended to facilitate the borrowing of DLTensor by another framework.
+#[repr(C)]
+#[derive(Debug)]
+pub struct DLManagedTensor {
+    /// DLTensor which is being memory managed
+    pub dl_tensor: DLTensor,
+    /// the context of the original host framework of DLManagedTensor in which DLManagedTensor is used in the framework.
+    pub manager_ctx: *mut c_void,
+    /// Destructor - this should be called to destruct the manager_ctx which backs the DLManagedTensor.
+    pub deleter: Option<unsafe extern "C" fn(*mut DLManagedTensor)>,
+}
+
+impl DLDataType {
+    /// get the DLPack data type of the hpt type `T`
+    #[track_caller]
+    pub fn of<T: TypeCommon>() -> Result<Self, TensorError> {
+        let code = match T::STR {
+            "bool" => DLDataTypeCode::Bool,
+            "i8" | "i16" | "i32" | "i64" | "isize" => DLDataTypeCode::Int,
+            "u8" | "u16" | "u32" | "u64" | "usize" => DLDataTypeCode::UInt,
+            "f16" | "f32" | "f64" => DLDataTypeCode::Float,
+            "bf16" => DLDataTypeCode::Bfloat,
+            "c32" | "c64" => DLDataTypeCode::Complex,
+            _ => {
+                return Err(ParamError::DtypeMismatch {
+                    expected: "a dtype supported by DLPack".to_string(),
+                    actual: T::STR.to_string(),
+                    location: Location::caller(),
+                }
+                .into())
+            }
+        };
+        Ok(DLDataType {
+            code: code as u8,
+            bits: (std::mem::size_of::<T>() * 8) as u8,
+            lanes: 1,
+        })
+    }
+
+    /// get the hpt type name (`TypeCommon::STR`) of the DLPack data type, return `None` if hpt has no such type
+    pub fn hpt_type(&self) -> Option<&'static str> {
+        if self.lanes != 1 {
+            return None;
+        }
+        const INT: u8 = DLDataTypeCode::Int as u8;
+        const UINT: u8 = DLDataTypeCode::UInt as u8;
+        const FLOAT: u8 = DLDataTypeCode::Float as u8;
+        const BFLOAT: u8 = DLDataTypeCode::Bfloat as u8;
+        const COMPLEX: u8 = DLDataTypeCode::Complex as u8;
+        const BOOL: u8 = DLDataTypeCode::Bool as u8;
+        match (self.code, self.bits) {
+            (BOOL, 8) => Some(bool::STR),
+            (INT, 8) => Some(i8::STR),
+            (INT, 16) => Some(i16::STR),
+            (INT, 32) => Some(i32::STR),
+            (INT, 64) => Some(i64::STR),
+            (UINT, 8) => Some(u8::STR),
+            (UINT, 16) => Some(u16::STR),
+            (UINT, 32) => Some(u32::STR),
+            (UINT, 64) => Some(u64::STR),
+            (FLOAT, 16) => Some(f16::STR),
+            (FLOAT, 32) => Some(f32::STR),
+            (FLOAT, 64) => Some(f64::STR),
+            (BFLOAT, 16) => Some(bf16::STR),
+            (COMPLEX, 64) => Some(num::complex::Complex32::STR),
+            (COMPLEX, 128) => Some(num::complex::Complex64::STR),
+            _ => None,
+        }
+    }
+
+    /// check if the DLPack data type can be viewed as the hpt type `T`
+    ///
+    /// `isize`/`usize` are accepted for the integer type with the same bit width
+    pub(crate) fn matches<T: TypeCommon>(&self) -> bool {
+        match DLDataType::of::<T>() {
+            Ok(dtype) => dtype == *self,
+            Err(_) => false,
+        }
+    }
+}
+
+impl std::fmt::Display for DLDataType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.hpt_type() {
+            Some(ty) => write!(f, "{}", ty),
+            None => write!(
+                f,
+                "DLDataType(code: {}, bits: {}, lanes: {})",
+                self.code, self.bits, self.lanes
+            ),
+        }
+    }
+}
@@ -0,0 +1,1063 @@
+use std::{
+    any::Any,
+    borrow::BorrowMut,
+    fmt::{Debug, Display},
+    ops::{Add, Div, Mul, Neg, Rem, Sub},
+    panic::Location,
+};
+
+use half::{bf16, f16};
+use hpt_common::{
+    axis::axis::Axis,
+    error::{base::TensorError, param::ParamError},
+    layout::layout::Layout,
+    shape::shape::Shape,
+    strides::strides::Strides,
+};
+use hpt_traits::{
+    ops::{
+        binary::{FloatBinOps, Matmul, NormalBinOps},
+        uary::{FloatUnaryOps, NormalUaryOps},
+    },
+    shape_manipulate::ShapeManipulate,
+    tensor::{Accumulation, CommonBounds, FloatReduce, NormalReduce, TensorCreator},
+};
+use hpt_types::{
+    dtype::TypeCommon,
+    into_scalar::Cast,
+    type_promote::{
+        FloatOutBinary, FloatOutBinaryPromote, FloatOutUnaryPromote, NormalOut, NormalOutPromote,
+    },
+};
+use num::complex::{Complex32, Complex64};
+
+use crate::{
+    ops::cpu::utils::binary::binary_normal::binary_fn_with_out_simd, tensor::Tensor,
+    tensor_base::_Tensor,
+};
+
+/// the data type of a [`DynTensor`], only known at runtime
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Dtype {
+    /// `bool`
+    Bool,
+    /// `i8`
+    I8,
+    /// `u8`
+    U8,
+    /// `i16`
+    I16,
+    /// `u16`
+    U16,
+    /// `i32`
+    I32,
+    /// `u32`
+    U32,
+    /// `i64`
+    I64,
+    /// `u64`
+    U64,
+    /// `bf16`
+    BF16,
+    /// `f16`
+    F16,
+    /// `f32`
+    F32,
+    /// `f64`
+    F64,
+    /// `Complex32`
+    C32,
+    /// `Complex64`
+    C64,
+}
+
+/// map a rust type to its runtime [`Dtype`]
+pub trait TypeNum {
+    /// the runtime data type of `Self`
+    fn type_num() -> Dtype;
+}
+
+/// generates a method mapping two dtypes to the `Output` of a promotion trait of `hpt_types`
+macro_rules! impl_promote {
+    ($name:ident, $trait:ident, $doc:literal; $([$lv:ident, $lt:ty]),*; $rhs:tt) => {
+        #[doc = $doc]
+        pub fn $name(self, rhs: Dtype) -> Dtype {
+            match self {
+                $(Dtype::$lv => impl_promote!(@row $trait, $lt, rhs, $rhs),)*
+            }
+        }
+    };
+    (@row $trait:ident, $lt:ty, $rhs:ident, [$([$rv:ident, $rt:ty]),*]) => {
+        match $rhs {
+            $(Dtype::$rv => <<$lt as $trait<$rt>>::Output as TypeNum>::type_num(),)*
+        }
+    };
+}
+
+macro_rules! impl_dtype {
+    ($all:tt) => {
+        impl_dtype!(@impl $all; $all);
+    };
+    (@impl [$([$v:ident, $t:ty]),*]; $all:tt) => {
+        impl Dtype {
+            /// size in bytes of an element
+            pub fn size(&self) -> usize {
+                match self {
+                    $(Dtype::$v => std::mem::size_of::<$t>(),)*
+                }
+            }
+
+            /// the name of the type, same as `TypeCommon::STR`
+            pub fn as_str(&self) -> &'static str {
+                match self {
+                    $(Dtype::$v => <$t as TypeCommon>::STR,)*
+                }
+            }
+
+            /// the output dtype of the float unary operations (`sin`, `exp`, ...) of this dtype
+            pub fn float_unary(self) -> Dtype {
+                match self {
+                    $(Dtype::$v => <<$t as FloatOutUnaryPromote>::Output as TypeNum>::type_num(),)*
+                }
+            }
+
+            impl_promote!(
+                promote,
+                NormalOutPromote,
+                "the dtype both operands are cast to by `add`, `sub`, `mul`, `rem`, `pow` and `matmul`";
+                $([$v, $t]),*;
+                $all
+            );
+
+            impl_promote!(
+                promote_float,
+                FloatOutBinaryPromote,
+                "the dtype both operands are cast to by `div` and `hypot`";
+                $([$v, $t]),*;
+                $all
+            );
+        }
+
+        /// the typed tensor of a `DynTensor`
+        #[derive(Clone)]
+        pub(crate) enum DynInner {
+            $($v(Tensor<$t>),)*
+        }
+
+        impl DynInner {
+            fn dtype(&self) -> Dtype {
+                match self {
+                    $(DynInner::$v(_) => Dtype::$v,)*
+                }
+            }
+
+            fn layout(&self) -> &Layout {
+                match self {
+                    $(DynInner::$v(t) => &t.inner.layout,)*
+                }
+            }
+
+            fn as_any(&self) -> &dyn Any {
+                match self {
+                    $(DynInner::$v(t) => t,)*
+                }
+            }
+
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                match self {
+                    $(DynInner::$v(t) => Display::fmt(t, f),)*
+                }
+            }
+
+            fn fmt_debug(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                match self {
+                    $(DynInner::$v(t) => Debug::fmt(t, f),)*
+                }
+            }
+
+            #[track_caller]
+            fn astype(&self, dtype: Dtype) -> Result<DynInner, TensorError> {
+                match self {
+                    $(DynInner::$v(t) => impl_dtype!(@astype t, dtype, $all),)*
+                }
+            }
+
+            #[track_caller]
+            fn zeros(shape: Shape, dtype: Dtype) -> Result<DynInner, TensorError> {
+                match dtype {
+                    $(Dtype::$v => Ok(DynInner::$v(Tensor::<$t>::zeros(shape)?)),)*
+                }
+            }
+
+            #[track_caller]
+            fn ones(shape: Shape, dtype: Dtype) -> Result<DynInner, TensorError> {
+                match dtype {
+                    $(Dtype::$v => Ok(DynInner::$v(Tensor::<$t>::ones(shape)?)),)*
+                }
+            }
+        }
+
+        $(
+            impl From<Tensor<$t>> for DynTensor {
+                fn from(tensor: Tensor<$t>) -> Self {
+                    DynTensor {
+                        inner: DynInner::$v(tensor),
+                    }
+                }
+            }
+        )*
+    };
+    (@astype $x:ident, $dtype:ident, [$([$v:ident, $t:ty]),*]) => {
+        match $dtype {
+            $(Dtype::$v => Ok(DynInner::$v($x.astype::<$t>()?)),)*
+        }
+    };
+}
+
+impl_dtype!([
+    [Bool, bool],
+    [I8, i8],
+    [U8, u8],
+    [I16, i16],
+    [U16, u16],
+    [I32, i32],
+    [U32, u32],
+    [I64, i64],
+    [U64, u64],
+    [BF16, bf16],
+    [F16, f16],
+    [F32, f32],
+    [F64, f64],
+    [C32, Complex32],
+    [C64, Complex64]
+]);
+
+impl Display for Dtype {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+/// dispatches `$body` to the typed tensors of one, two or three `DynInner` of the same dtype.
+///
+/// the first argument selects the dtypes supported by the operation, the other dtypes return `UnsupportedDtype`.
+macro_rules! dispatch {
+    (all, $($rest:tt)*) => {
+        dispatch!(@list [Bool, I8, U8, I16, U16, I32, U32, I64, U64, BF16, F16, F32, F64, C32, C64], $($rest)*)
+    };
+    (real, $($rest:tt)*) => {
+        dispatch!(@list [I8, U8, I16, U16, I32, U32, I64, U64, BF16, F16, F32, F64], $($rest)*)
+    };
+    (float, $($rest:tt)*) => {
+        dispatch!(@list [BF16, F16, F32, F64], $($rest)*)
+    };
+    (@list [$($v:ident),*], $op:expr, ($x:expr, $y:expr, $z:expr), |$a:ident, $b:ident, $c:ident| $body:expr) => {
+        match ($x, $y, $z) {
+            $((DynInner::$v($a), DynInner::$v($b), DynInner::$v($c)) => $body,)*
+            #[allow(unreachable_patterns)]
+            (other, _, _) => Err(unsupported($op, other.dtype())),
+        }
+    };
+    (@list [$($v:ident),*], $op:expr, ($x:expr, $y:expr), |$a:ident, $b:ident| $body:expr) => {
+        match ($x, $y) {
+            $((DynInner::$v($a), DynInner::$v($b)) => $body,)*
+            #[allow(unreachable_patterns)]
+            (other, _) => Err(unsupported($op, other.dtype())),
+        }
+    };
+    (@list [$($v:ident),*], $op:expr, $x:expr, |$a:ident| $body:expr) => {
+        match $x {
+            $(DynInner::$v($a) => $body,)*
+            #[allow(unreachable_patterns)]
+            other => Err(unsupported($op, other.dtype())),
+        }
+    };
+}
+
+#[track_caller]
+fn unsupported(op: &'static str, dtype: Dtype) -> TensorError {
+    ParamError::UnsupportedDtype {
+        op,
+        dtype: dtype.to_string(),
+        location: Location::caller(),
+    }
+    .into()
+}
+
+#[track_caller]
+fn check_dtype(expected: Dtype, actual: Dtype) -> Result<(), TensorError> {
+    if expected != actual {
+        return Err(ParamError::DtypeMismatch {
+            expected: expected.to_string(),
+            actual: actual.to_string(),
+            location: Location::caller(),
+        }
+        .into());
+    }
+    Ok(())
+}
+
+/// `DynTensor` is a tensor whose data type is only known at runtime.
+///
+/// every operation matches the [`Dtype`] of the tensor and calls the typed `Tensor` implementation. Operands of
+/// different dtypes are cast to a common dtype first, following the promotion rules of `hpt_types`. Scalar parameters
+/// (`alpha` of `celu`, `min` of `clamp`, ...) are passed as `f64` and cast to the dtype of the tensor.
+///
+/// # Example
+/// ```
+/// use hpt::{DynTensor, Dtype, Tensor, TensorCreator};
+/// let a: DynTensor = Tensor::<i32>::arange(0, 4).unwrap().into();
+/// let b: DynTensor = Tensor::<f32>::arange(0, 4).unwrap().into();
+/// let c = &a + &b;
+/// assert_eq!(c.dtype(), Dtype::F32);
+/// let c: Tensor<f32> = c.downcast().unwrap();
+/// ```
+#[derive(Clone)]
+pub struct DynTensor {
+    pub(crate) inner: DynInner,
+}
+
+impl DynTensor {
+    /// the data type of the tensor
+    pub fn dtype(&self) -> Dtype {
+        self.inner.dtype()
+    }
+
+    /// the shape of the tensor
+    pub fn shape(&self) -> &Shape {
+        self.inner.layout().shape()
+    }
+
+    /// the strides of the tensor
+    pub fn strides(&self) -> &Strides {
+        self.inner.layout().strides()
+    }
+
+    /// the number of dimensions of the tensor
+    pub fn ndim(&self) -> usize {
+        self.inner.layout().ndim()
+    }
+
+    /// the number of elements of the tensor
+    pub fn size(&self) -> usize {
+        self.inner.layout().size() as usize
+    }
+
+    /// create a tensor of `dtype` filled with zeros
+    #[track_caller]
+    pub fn zeros<S: Into<Shape>>(shape: S, dtype: Dtype) -> Result<DynTensor, TensorError> {
+        Ok(DynTensor {
+            inner: DynInner::zeros(shape.into(), dtype)?,
+        })
+    }
+
+    /// create a tensor of `dtype` filled with ones
+    #[track_caller]
+    pub fn ones<S: Into<Shape>>(shape: S, dtype: Dtype) -> Result<DynTensor, TensorError> {
+        Ok(DynTensor {
+            inner: DynInner::ones(shape.into(), dtype)?,
+        })
+    }
+
+    /// get the typed tensor, returns an error if `T` is not the dtype of the tensor
+    #[track_caller]
+    pub fn downcast<T: TypeNum + 'static>(&self) -> Result<Tensor<T>, TensorError> {
+        match self.inner.as_any().downcast_ref::<Tensor<T>>() {
+            Some(tensor) => Ok(Tensor {
+                inner: tensor.inner.clone(),
+            }),
+            None => Err(ParamError::DtypeMismatch {
+                expected: T::type_num().to_string(),
+                actual: self.dtype().to_string(),
+                location: Location::caller(),
+            }
+            .into()),
+        }
+    }
+
+    /// cast the tensor to `dtype`, the tensor itself is returned if it already has this dtype
+    #[track_caller]
+    pub fn astype(&self, dtype: Dtype) -> Result<DynTensor, TensorError> {
+        if dtype == self.dtype() {
+            return Ok(self.clone());
+        }
+        Ok(DynTensor {
+            inner: self.inner.astype(dtype)?,
+        })
+    }
+
+    /// check if two tensors are close to each other, the tensors are compared in their promoted dtype
+    pub fn allclose(&self, other: &DynTensor) -> bool {
+        let dtype = self.dtype().promote(other.dtype());
+        match (self.astype(dtype), other.astype(dtype)) {
+            (Ok(lhs), Ok(rhs)) if lhs.shape() == rhs.shape() => {
+                let res: Result<bool, TensorError> =
+                    dispatch!(all, "allclose", (&lhs.inner, &rhs.inner), |a, b| Ok(
+                        a.allclose(b)
+                    ));
+                res.unwrap_or(false)
+            }
+            _ => false,
+        }
+    }
+
+    /// computes `self / rhs` elementwise, the operands are cast to their float promoted dtype
+    #[track_caller]
+    pub fn div(&self, rhs: &DynTensor) -> Result<DynTensor, TensorError> {
+        self.binary_float(rhs, |a, b| {
+            dispatch!(float, "div", (a, b), |a, b| Ok(elementwise(
+                a,
+                b,
+                |x, y| x._div(y),
+                |x, y| x._div(y)
+            )?
+            .into()))
+        })
+    }
+
+    /// cast both operands to `dtype` and call `f` with the typed tensors
+    #[track_caller]
+    fn binary_as<R>(
+        &self,
+        rhs: &DynTensor,
+        dtype: Dtype,
+        f: impl FnOnce(&DynInner, &DynInner) -> Result<R, TensorError>,
+    ) -> Result<R, TensorError> {
+        f(&self.astype(dtype)?.inner, &rhs.astype(dtype)?.inner)
+    }
+
+    #[track_caller]
+    fn binary<R>(
+        &self,
+        rhs: &DynTensor,
+        f: impl FnOnce(&DynInner, &DynInner) -> Result<R, TensorError>,
+    ) -> Result<R, TensorError> {
+        self.binary_as(rhs, self.dtype().promote(rhs.dtype()), f)
+    }
+
+    #[track_caller]
+    fn binary_float<R>(
+        &self,
+        rhs: &DynTensor,
+        f: impl FnOnce(&DynInner, &DynInner) -> Result<R, TensorError>,
+    ) -> Result<R, TensorError> {
+        self.binary_as(rhs, self.dtype().promote_float(rhs.dtype()), f)
+    }
+
+    /// cast `self` to the output dtype of the float unary operations
+    #[track_caller]
+    fn to_float(&self) -> Result<DynTensor, TensorError> {
+        self.astype(self.dtype().float_unary())
+    }
+}
+
+#[track_caller]
+fn elementwise<T, F, F2>(
+    a: &Tensor<T>,
+    b: &Tensor<T>,
+    f: F,
+    f2: F2,
+) -> Result<Tensor<T>, TensorError>
+where
+    T: CommonBounds,
+    F: Fn(T, T) -> T + Sync + Send + Copy + 'static,
+    F2: Fn(T::Vec, T::Vec) -> T::Vec + Sync + Send + Copy + 'static,
+{
+    Ok(binary_fn_with_out_simd(
+        a.inner.as_ref(),
+        b.inner.as_ref(),
+        f,
+        f2,
+        None::<_Tensor<T>>,
+    )?
+    .into())
+}
+
+macro_rules! impl_normal_binary {
+    ($($name:ident, $method:ident);*) => {
+        impl DynTensor {
+            $(
+                #[doc = concat!("computes `", stringify!($name), "` elementwise, the operands are cast to their promoted dtype")]
+                #[track_caller]
+                pub fn $name(&self, rhs: &DynTensor) -> Result<DynTensor, TensorError> {
+                    self.binary(rhs, |a, b| {
+                        dispatch!(all, stringify!($name), (a, b), |a, b| Ok(
+                            elementwise(a, b, |x, y| x.$method(y), |x, y| x.$method(y))?.into()
+                        ))
+                    })
+                }
+            )*
+        }
+    };
+}
+
+impl_normal_binary!(add, _add; sub, _sub; mul, _mul; rem, _rem);
+
+/// calls the typed `$method` of `$a` with `$b` and the typed tensor of `$out`, `$out` must have the dtype of `$a`
+macro_rules! binary_out {
+    ($list:ident, $a:ident, $b:ident, $out:ident, $method:ident) => {{
+        check_dtype($a.dtype(), $out.dtype())?;
+        dispatch!(
+            $list,
+            stringify!($method),
+            ($a, $b, &mut $out.inner),
+            |a, b, o| Ok(a.$method(b, o)?.into())
+        )
+    }};
+}
+
+macro_rules! impl_bin_ops {
+    ($rhs:ty) => {
+        impl NormalBinOps<$rhs> for DynTensor {
+            type Output = DynTensor;
+            type OutputMeta = f64;
+            type InplaceOutput = DynTensor;
+
+            fn add_<U>(&self, rhs: $rhs, mut out: U) -> Result<Self::Output, TensorError>
+            where
+                U: BorrowMut<Self::InplaceOutput>,
+            {
+                let out = out.borrow_mut();
+                self.binary(&rhs, |a, b| binary_out!(all, a, b, out, add_))
+            }
+
+            fn sub_<U>(&self, rhs: $rhs, mut out: U) -> Result<Self::Output, TensorError>
+            where
+                U: BorrowMut<Self::InplaceOutput>,
+            {
+                let out = out.borrow_mut();
+                self.binary(&rhs, |a, b| binary_out!(all, a, b, out, sub_))
+            }
+
+            fn mul_<U>(&self, rhs: $rhs, mut out: U) -> Result<Self::Output, TensorError>
+            where
+                U: BorrowMut<Self::InplaceOutput>,
+            {
+                let out = out.borrow_mut();
+                self.binary(&rhs, |a, b| binary_out!(all, a, b, out, mul_))
+            }
+
+            fn rem_<U>(&self, rhs: $rhs, mut out: U) -> Result<Self::Output, TensorError>
+            where
+                U: BorrowMut<Self::InplaceOutput>,
+            {
+                let out = out.borrow_mut();
+                self.binary(&rhs, |a, b| binary_out!(all, a, b, out, rem_))
+            }
+
+            fn pow(&self, rhs: $rhs) -> Result<Self::Output, TensorError> {
+                self.binary(&rhs, |a, b| {
+                    dispatch!(all, "pow", (a, b), |a, b| Ok(a.pow(b)?.into()))
+                })
+            }
+
+            fn pow_<U>(&self, rhs: $rhs, mut out: U) -> Result<Self::Output, TensorError>
+            where
+                U: BorrowMut<Self::InplaceOutput>,
+            {
+                let out = out.borrow_mut();
+                self.binary(&rhs, |a, b| binary_out!(all, a, b, out, pow_))
+            }
+        }
+
+        impl Matmul<$rhs> for DynTensor {
+            type Output = DynTensor;
+            type OutputMeta = f64;
+            type InplaceOutput = DynTensor;
+
+            fn matmul(&self, rhs: $rhs) -> Result<Self::Output, TensorError> {
+                self.binary(&rhs, |a, b| {
+                    dispatch!(real, "matmul", (a, b), |a, b| Ok(a.matmul(b)?.into()))
+                })
+            }
+
+            fn matmul_<U>(&self, rhs: $rhs, mut out: U) -> Result<Self::InplaceOutput, TensorError>
+            where
+                U: BorrowMut<Self::InplaceOutput> + BorrowMut<Self::InplaceOutput>,
+            {
+                let out: &mut DynTensor = out.borrow_mut();
+                self.binary(&rhs, |a, b| binary_out!(real, a, b, out, matmul_))
+            }
+        }
+    };
+}
+
+impl_bin_ops!(DynTensor);
+impl_bin_ops!(&DynTensor);
+
+impl FloatBinOps for DynTensor {
+    type Output = DynTensor;
+    type OutputMeta = f64;
+    type InplaceOutput = DynTensor;
+
+    fn hypot(&self, rhs: &DynTensor) -> Result<Self::Output, TensorError> {
+        self.binary_float(rhs, |a, b| {
+            dispatch!(float, "hypot", (a, b), |a, b| Ok(a.hypot(b)?.into()))
+        })
+    }
+
+    fn hypot_<U>(&self, rhs: &DynTensor, mut out: U) -> Result<Self::Output, TensorError>
+    where
+        U: BorrowMut<Self::InplaceOutput>,
+    {
+        let out = out.borrow_mut();
+        self.binary_float(rhs, |a, b| binary_out!(float, a, b, out, hypot_))
+    }
+
+    fn div_<U>(&self, rhs: &DynTensor, mut out: U) -> Result<Self::Output, TensorError>
+    where
+        U: BorrowMut<Self::InplaceOutput>,
+    {
+        let out = out.borrow_mut();
+        self.binary_float(rhs, |a, b| binary_out!(float, a, b, out, div_))
+    }
+}
+
+macro_rules! impl_std_ops {
+    ($($trait:ident, $method:ident);*) => {
+        $(
+            impl $trait<&DynTensor> for &DynTensor {
+                type Output = DynTensor;
+                #[track_caller]
+                fn $method(self, rhs: &DynTensor) -> Self::Output {
+                    DynTensor::$method(self, rhs).unwrap()
+                }
+            }
+
+            impl $trait<DynTensor> for &DynTensor {
+                type Output = DynTensor;
+                #[track_caller]
+                fn $method(self, rhs: DynTensor) -> Self::Output {
+                    DynTensor::$method(self, &rhs).unwrap()
+                }
+            }
+
+            impl $trait<&DynTensor> for DynTensor {
+                type Output = DynTensor;
+                #[track_caller]
+                fn $method(self, rhs: &DynTensor) -> Self::Output {
+                    DynTensor::$method(&self, rhs).unwrap()
+                }
+            }
+
+            impl $trait<DynTensor> for DynTensor {
+                type Output = DynTensor;
+                #[track_caller]
+                fn $method(self, rhs: DynTensor) -> Self::Output {
+                    DynTensor::$method(&self, &rhs).unwrap()
+                }
+            }
+        )*
+    };
+}
+
+impl_std_ops!(Add, add; Sub, sub; Mul, mul; Div, div; Rem, rem);
+
+impl Neg for &DynTensor {
+    type Output = DynTensor;
+    #[track_caller]
+    fn neg(self) -> Self::Output {
+        NormalUaryOps::neg(self).unwrap()
+    }
+}
+
+impl Neg for DynTensor {
+    type Output = DynTensor;
+    #[track_caller]
+    fn neg(self) -> Self::Output {
+        NormalUaryOps::neg(&self).unwrap()
+    }
+}
+
+/// float unary operations, `self` is cast to its float dtype before the typed operation
+macro_rules! impl_float_unary {
+    ($($name:ident, $inplace:ident);*) => {
+        $(
+            fn $name(&self) -> Result<Self::Output, TensorError> {
+                let x = self.to_float()?;
+                dispatch!(float, stringify!($name), &x.inner, |t| Ok(t.$name()?.into()))
+            }
+
+            fn $inplace<U>(&self, mut out: U) -> Result<Self::InplaceOutput, TensorError>
+            where
+                U: BorrowMut<Self::InplaceOutput>,
+            {
+                let out = out.borrow_mut();
+                let x = self.to_float()?;
+                check_dtype(x.dtype(), out.dtype())?;
+                dispatch!(float, stringify!($name), (&x.inner, &mut out.inner), |t, o| Ok(
+                    t.$inplace(o)?.into()
+                ))
+            }
+        )*
+    };
+}
+
+impl FloatUnaryOps for DynTensor {
+    type Output = DynTensor;
+    type InplaceOutput = DynTensor;
+    type OutputMeta = f64;
+
+    impl_float_unary!(
+        sin, sin_; cos, cos_; tan, tan_; asin, asin_; acos, acos_; atan, atan_; sinh, sinh_; cosh, cosh_;
+        tanh, tanh_; asinh, asinh_; acosh, acosh_; atanh, atanh_; exp, exp_; exp2, exp2_; exp10, exp10_;
+        sqrt, sqrt_; recip, recip_; ln, ln_; log2, log2_; log10, log10_; sigmoid, sigmoid_; erf, erf_;
+        gelu, gelu_; hard_sigmoid, hard_sigmoid_; hard_swish, hard_swish_; softplus, softplus_;
+        softsign, softsign_; mish, mish_; cbrt, cbrt_
+    );
+
+    fn sincos(&self) -> Result<(Self::Output, Self::Output), TensorError> {
+        let x = self.to_float()?;
+        dispatch!(float, "sincos", &x.inner, |t| {
+            let (sin, cos) = t.sincos()?;
+            Ok((sin.into(), cos.into()))
+        })
+    }
+
+    fn sincos_<U, O>(&self, mut outs: (U, O)) -> Result<(Self::Output, Self::Output), TensorError>
+    where
+        U: BorrowMut<Self::InplaceOutput>,
+        O: BorrowMut<Self::InplaceOutput>,
+    {
+        let (sin, cos) = (outs.0.borrow_mut(), outs.1.borrow_mut());
+        let x = self.to_float()?;
+        check_dtype(x.dtype(), sin.dtype())?;
+        check_dtype(x.dtype(), cos.dtype())?;
+        dispatch!(
+            float,
+            "sincos",
+            (&x.inner, &mut sin.inner, &mut cos.inner),
+            |t, sin, cos| {
+                let (sin, cos) = t.sincos_((sin, cos))?;
+                Ok((sin.into(), cos.into()))
+            }
+        )
+    }
+
+    fn celu(&self, alpha: Self::OutputMeta) -> Result<Self::Output, TensorError> {
+        let x = self.to_float()?;
+        dispatch!(float, "celu", &x.inner, |t| Ok(t
+            .celu(alpha.cast())?
+            .into()))
+    }
+
+    fn celu_<U>(
+        &self,
+        alpha: Self::OutputMeta,
+        mut out: U,
+    ) -> Result<Self::InplaceOutput, TensorError>
+    where
+        U: BorrowMut<Self::InplaceOutput>,
+    {
+        let out = out.borrow_mut();
+        let x = self.to_float()?;
+        check_dtype(x.dtype(), out.dtype())?;
+        dispatch!(float, "celu", (&x.inner, &mut out.inner), |t, o| Ok(t
+            .celu_(alpha.cast(), o)?
+            .into()))
+    }
+
+    fn elu(&self, alpha: Self::OutputMeta) -> Result<Self::Output, TensorError> {
+        let x = self.to_float()?;
+        dispatch!(float, "elu", &x.inner, |t| Ok(t.elu(alpha.cast())?.into()))
+    }
+
+    fn elu_<U>(
+        &self,
+        alpha: Self::OutputMeta,
+        mut out: U,
+    ) -> Result<Self::InplaceOutput, TensorError>
+    where
+        U: BorrowMut<Self::InplaceOutput>,
+    {
+        let out = out.borrow_mut();
+        let x = self.to_float()?;
+        check_dtype(x.dtype(), out.dtype())?;
+        dispatch!(float, "elu", (&x.inner, &mut out.inner), |t, o| Ok(t
+            .elu_(alpha.cast(), o)?
+            .into()))
+    }
+
+    fn selu<U>(&self, alpha: U, gamma: U) -> Result<Self::Output, TensorError>
+    where
+        U: Into<Option<Self::OutputMeta>>,
+    {
+        let (alpha, gamma) = (alpha.into(), gamma.into());
+        let x = self.to_float()?;
+        dispatch!(float, "selu", &x.inner, |t| Ok(t
+            .selu(alpha.map(|a| a.cast()), gamma.map(|g| g.cast()))?
+            .into()))
+    }
+
+    fn selu_<U>(
+        &self,
+        alpha: Option<Self::OutputMeta>,
+        gamma: Option<Self::OutputMeta>,
+        mut out: U,
+    ) -> Result<Self::InplaceOutput, TensorError>
+    where
+        U: BorrowMut<Self::InplaceOutput>,
+    {
+        let out = out.borrow_mut();
+        let x = self.to_float()?;
+        check_dtype(x.dtype(), out.dtype())?;
+        dispatch!(float, "selu", (&x.inner, &mut out.inner), |t, o| Ok(t
+            .selu_(alpha.map(|a| a.cast()), gamma.map(|g| g.cast()), o)?
+            .into()))
+    }
+}
+
+/// normal unary operations, the output has the dtype of `self`
+macro_rules! impl_normal_unary {
+    ($($name:ident, $inplace:ident);*) => {
+        $(
+            fn $name(&self) -> Result<Self::Output, TensorError> {
+                dispatch!(all, stringify!($name), &self.inner, |t| Ok(
+                    NormalUaryOps::$name(t)?.into()
+                ))
+            }
+
+            fn $inplace<U>(&self, mut out: U) -> Result<Self::Output, TensorError>
+            where
+                U: BorrowMut<Self::InplaceOutput>,
+            {
+                let out = out.borrow_mut();
+                check_dtype(self.dtype(), out.dtype())?;
+                dispatch!(all, stringify!($name), (&self.inner, &mut out.inner), |t, o| Ok(
+                    t.$inplace(o)?.into()
+                ))
+            }
+        )*
+    };
+}
+
+impl NormalUaryOps for DynTensor {
+    type Output = DynTensor;
+    type InplaceOutput = DynTensor;
+    type OutputMeta = f64;
+
+    impl_normal_unary!(
+        floor, floor_; square, square_; abs, abs_; ceil, ceil_; sign, sign_; round, round_; neg, neg_;
+        relu, relu_; relu6, relu6_
+    );
+
+    fn clamp(
+        &self,
+        min: Self::OutputMeta,
+        max: Self::OutputMeta,
+    ) -> Result<Self::Output, TensorError> {
+        dispatch!(all, "clamp", &self.inner, |t| Ok(t
+            .clamp(min.cast(), max.cast())?
+            .into()))
+    }
+
+    fn clamp_<U>(
+        &self,
+        min: Self::OutputMeta,
+        max: Self::OutputMeta,
+        mut out: U,
+    ) -> Result<Self::Output, TensorError>
+    where
+        U: BorrowMut<Self::InplaceOutput>,
+    {
+        let out = out.borrow_mut();
+        check_dtype(self.dtype(), out.dtype())?;
+        dispatch!(all, "clamp", (&self.inner, &mut out.inner), |t, o| Ok(t
+            .clamp_(min.cast(), max.cast(), o)?
+            .into()))
+    }
+
+    fn leaky_relu(&self, alpha: Self::OutputMeta) -> Result<Self::Output, TensorError> {
+        dispatch!(all, "leaky_relu", &self.inner, |t| Ok(t
+            .leaky_relu(alpha.cast())?
+            .into()))
+    }
+
+    fn leaky_relu_<U>(
+        &self,
+        alpha: Self::OutputMeta,
+        mut out: U,
+    ) -> Result<Self::Output, TensorError>
+    where
+        U: BorrowMut<Self::InplaceOutput>,
+    {
+        let out = out.borrow_mut();
+        check_dtype(self.dtype(), out.dtype())?;
+        dispatch!(all, "leaky_relu", (&self.inner, &mut out.inner), |t, o| Ok(
+            t.leaky_relu_(alpha.cast(), o)?.into()
+        ))
+    }
+}
+
+macro_rules! impl_reduce {
+    ($list:ident; $($name:ident),*) => {
+        $(
+            fn $name<S: Into<Axis>>(&self, axis: S, keep_dims: bool) -> Result<Self::Output, TensorError> {
+                dispatch!($list, stringify!($name), &self.inner, |t| Ok(t.$name(axis, keep_dims)?.into()))
+            }
+        )*
+    };
+}
+
+/// the reductions taking an accumulation policy
+macro_rules! impl_reduce_with {
+    ($list:ident; $($name:ident),*) => {
+        $(
+            fn $name<S: Into<Axis>>(
+                &self,
+                axis: S,
+                keep_dims: bool,
+                accumulation: Accumulation,
+            ) -> Result<Self::Output, TensorError> {
+                dispatch!($list, stringify!($name), &self.inner, |t| Ok(t
+                    .$name(axis, keep_dims, accumulation)?
+                    .into()))
+            }
+        )*
+    };
+}
+
+impl NormalReduce<f64> for DynTensor {
+    type Output = DynTensor;
+
+    impl_reduce!(all; sum, prod, min, max, reducel1, sum_square);
+    impl_reduce_with!(all; sum_with);
+
+    fn sum_<S: Into<Axis>, O>(
+        &self,
+        axis: S,
+        keep_dims: bool,
+        init_out: bool,
+        mut out: O,
+    ) -> Result<Self::Output, TensorError>
+    where
+        O: BorrowMut<Self::Output>,
+    {
+        let out = out.borrow_mut();
+        check_dtype(self.dtype(), out.dtype())?;
+        dispatch!(all, "sum", (&self.inner, &mut out.inner), |t, o| Ok(t
+            .sum_(axis, keep_dims, init_out, o)?
+            .into()))
+    }
+}
+
+impl FloatReduce<f64> for DynTensor {
+    type Output = DynTensor;
+
+    impl_reduce!(real; mean, reducel2, reducel3, logsumexp);
+    impl_reduce_with!(real; mean_with, reducel2_with, logsumexp_with);
+}
+
+/// collects the typed tensors of `tensors`, `_dtype` only selects `T`
+fn typed<T: TypeNum + 'static>(
+    _dtype: &Tensor<T>,
+    tensors: &[DynTensor],
+) -> Result<Vec<Tensor<T>>, TensorError> {
+    tensors.iter().map(|t| t.downcast::<T>()).collect()
+}
+
+/// stacking operations, the tensors are cast to the dtype promoting all of them
+macro_rules! impl_stack {
+    ($($name:ident($($arg:ident: $ty:ty),*)),*) => {
+        $(
+            fn $name(tensors: Vec<Self>, $($arg: $ty),*) -> Result<Self::Output, TensorError> {
+                let Some(first) = tensors.first() else {
+                    return Ok(Tensor::<f32>::$name(Vec::new(), $($arg),*)?.into());
+                };
+                let dtype = tensors
+                    .iter()
+                    .fold(first.dtype(), |dtype, t| dtype.promote(t.dtype()));
+                let tensors = tensors
+                    .iter()
+                    .map(|t| t.astype(dtype))
+                    .collect::<Result<Vec<_>, _>>()?;
+                dispatch!(all, stringify!($name), &tensors[0].inner, |t| Ok(
+                    Tensor::$name(typed(t, &tensors)?, $($arg),*)?.into()
+                ))
+            }
+        )*
+    };
+}
+
+macro_rules! impl_shape {
+    ($($name:ident$(<$g:ident: $bound:path>)?($($arg:ident: $ty:ty),*)),*) => {
+        $(
+            fn $name$(<$g: $bound>)?(&self, $($arg: $ty),*) -> Result<Self::Output, TensorError> {
+                dispatch!(all, stringify!($name), &self.inner, |t| Ok(t.$name($($arg),*)?.into()))
+            }
+        )*
+    };
+}
+
+macro_rules! impl_split {
+    ($($name:ident($($arg:ident: $ty:ty),*)),*) => {
+        $(
+            fn $name(&self, $($arg: $ty),*) -> Result<Vec<Self::Output>, TensorError> {
+                dispatch!(all, stringify!($name), &self.inner, |t| Ok(t
+                    .$name($($arg),*)?
+                    .into_iter()
+                    .map(DynTensor::from)
+                    .collect()))
+            }
+        )*
+    };
+}
+
+impl ShapeManipulate for DynTensor {
+    type Meta = f64;
+    type Output = DynTensor;
+
+    impl_shape!(
+        squeeze<A: Into<Axis>>(axes: A),
+        unsqueeze<A: Into<Axis>>(axes: A),
+        reshape<S: Into<Shape>>(shape: S),
+        transpose(axis1: i64, axis2: i64),
+        permute<A: Into<Axis>>(axes: A),
+        permute_inv<A: Into<Axis>>(axes: A),
+        expand<S: Into<Shape>>(shape: S),
+        t(),
+        mt(),
+        flip<A: Into<Axis>>(axes: A),
+        fliplr(),
+        flipud(),
+        tile<S: Into<Axis>>(reps: S),
+        trim_zeros(trim: &str),
+        repeat(repeats: usize, axis: i16),
+        swap_axes(axis1: i64, axis2: i64)
+    );
+
+    impl_split!(
+        split(indices_or_sections: &[i64], axis: i64),
+        dsplit(indices: &[i64]),
+        hsplit(indices: &[i64]),
+        vsplit(indices: &[i64])
+    );
+
+    fn flatten<A>(&self, start: A, end: A) -> Result<Self::Output, TensorError>
+    where
+        A: Into<Option<usize>>,
+    {
+        dispatch!(all, "flatten", &self.inner, |t| Ok(t
+            .flatten(start, end)?
+            .into()))
+    }
+
+    impl_stack!(
+        concat(axis: usize, keepdims: bool),
+        vstack(),
+        hstack(),
+        dstack()
+    );
+}
+
+impl Display for DynTensor {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.inner.fmt(f)
+    }
+}
+
+impl Debug for DynTensor {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.inner.fmt_debug(f)
+    }
+}
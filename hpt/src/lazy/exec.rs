@@ -0,0 +1,295 @@
+use std::collections::HashMap;
+
+use hpt_common::error::base::TensorError;
+use rayon::iter::{IntoParallelIterator, ParallelIterator};
+
+use super::graph::{Access, ChunkKernel, Graph, Kind, Value};
+
+/// number of elements a fused kernel computes at once, the intermediates of a chunk stay in cache
+const CHUNK: usize = 2048;
+
+/// what the graph passes and the memory plan did with a lazy graph
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct LazyStats {
+    /// number of recorded operations
+    pub recorded: usize,
+    /// number of operations removed because their results are never used
+    pub eliminated: usize,
+    /// number of operations computed ahead of the execution because they only depend on scalars
+    pub folded: usize,
+    /// number of kernels executed, a chain of fused elementwise operations is a single kernel
+    pub kernels: usize,
+    /// number of results written into the buffer of an input that was no longer needed
+    pub reused_buffers: usize,
+    /// number of operations computed during the recording because their data was read
+    pub materialized: usize,
+}
+
+/// a kernel of the plan
+struct Step {
+    /// the nodes computed by the step, in topological order
+    nodes: Vec<usize>,
+    /// the nodes of the step whose values are needed after the step
+    outputs: Vec<usize>,
+    /// an input of the step whose buffer receives the single output of the step
+    reuse: Option<usize>,
+}
+
+/// the execution order of a graph, with the lifetime of every value
+pub(crate) struct Plan {
+    steps: Vec<Step>,
+    /// the values dropped after each step
+    release: Vec<Vec<usize>>,
+    pub(crate) stats: LazyStats,
+}
+
+impl Plan {
+    /// runs the graph passes and plans the execution of the nodes `outputs` depend on
+    pub(crate) fn new(graph: &mut Graph, outputs: &[usize]) -> Result<Plan, TensorError> {
+        let len = graph.nodes.len();
+        let alive = graph.eliminate_dead_nodes(outputs);
+        let mut stats = LazyStats {
+            recorded: graph.recorded,
+            eliminated: (0..len)
+                .filter(|&idx| !alive[idx] && !graph.nodes[idx].is_leaf())
+                .count(),
+            folded: graph.fold_constants(&alive)?,
+            materialized: graph.materialized,
+            ..Default::default()
+        };
+        let groups = graph.fuse_elementwise(&alive);
+
+        let mut step_of = vec![usize::MAX; len];
+        for (step, group) in groups.iter().enumerate() {
+            for &idx in group {
+                step_of[idx] = step;
+            }
+        }
+        // the last step reading each value, steps are visited in order so the last write wins
+        let mut last_use = vec![None; len];
+        for (step, group) in groups.iter().enumerate() {
+            for &idx in group {
+                for &inp in &graph.nodes[idx].inputs {
+                    if step_of[inp] != step {
+                        last_use[inp] = Some(step);
+                    }
+                }
+            }
+        }
+        let mut is_output = vec![false; len];
+        outputs.iter().for_each(|&idx| is_output[idx] = true);
+
+        let mut steps = Vec::with_capacity(groups.len());
+        let mut release = vec![Vec::new(); groups.len()];
+        for (idx, last) in last_use.iter().enumerate() {
+            if let (Some(step), false) = (last, is_output[idx]) {
+                release[*step].push(idx);
+            }
+        }
+        for (step, group) in groups.into_iter().enumerate() {
+            let step_outputs = group
+                .iter()
+                .copied()
+                .filter(|&idx| is_output[idx] || last_use[idx].is_some())
+                .collect::<Vec<_>>();
+            let last = *group.last().expect("empty group");
+            let node = &graph.nodes[last];
+            let reuse = if step_outputs == [last] && node.is_elementwise() {
+                // an input dying in this step, read element by element, can hold the result. only the results of
+                // elementwise nodes are candidates, the other operations may return a view of their input
+                group
+                    .iter()
+                    .flat_map(|&idx| graph.nodes[idx].inputs.iter().copied())
+                    .find(|&inp| {
+                        let input = &graph.nodes[inp];
+                        step_of[inp] != step
+                            && input.is_elementwise()
+                            && !is_output[inp]
+                            && last_use[inp] == Some(step)
+                            && input.dtype.id == node.dtype.id
+                            && input.shape == node.shape
+                            && group.iter().all(|&idx| {
+                                let reader = &graph.nodes[idx];
+                                reader
+                                    .inputs
+                                    .iter()
+                                    .zip(reader.access.iter())
+                                    .all(|(&i, access)| i != inp || *access == Access::Same)
+                            })
+                    })
+            } else {
+                None
+            };
+            stats.reused_buffers += reuse.is_some() as usize;
+            steps.push(Step {
+                nodes: group,
+                outputs: step_outputs,
+                reuse,
+            });
+        }
+        stats.kernels = steps.len();
+        Ok(Plan {
+            steps,
+            release,
+            stats,
+        })
+    }
+
+    /// executes the plan, returns the value of every node still alive at the end, the outputs among them
+    pub(crate) fn execute(&self, graph: &Graph) -> Result<Vec<Option<Value>>, TensorError> {
+        let mut values = graph
+            .nodes
+            .iter()
+            .map(|node| match &node.kind {
+                Kind::Leaf(value) => Some(value.clone()),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        for (step, release) in self.steps.iter().zip(self.release.iter()) {
+            if step.nodes.len() == 1 {
+                let idx = step.nodes[0];
+                let node = &graph.nodes[idx];
+                let inputs = node
+                    .inputs
+                    .iter()
+                    .map(|&inp| input(&values, inp))
+                    .collect::<Vec<_>>();
+                let out = step.reuse.map(|inp| input(&values, inp));
+                values[idx] = Some(match &node.kind {
+                    Kind::Elementwise { eager, .. } | Kind::Opaque(eager) => eager(&inputs, out)?,
+                    Kind::Leaf(_) => unreachable!("leaves are never scheduled"),
+                });
+            } else {
+                run_fused(graph, step, &mut values)?;
+            }
+            for &idx in release {
+                values[idx] = None;
+            }
+        }
+        Ok(values)
+    }
+}
+
+/// computes `node` and turns it into a leaf, the other nodes are left as they are
+pub(crate) fn force(graph: &mut Graph, node: usize) -> Result<Value, TensorError> {
+    if let Kind::Leaf(value) = &graph.nodes[node].kind {
+        return Ok(value.clone());
+    }
+    let plan = Plan::new(graph, &[node])?;
+    let value = plan.execute(graph)?[node]
+        .take()
+        .expect("output released before the end of the plan");
+    let forced = &mut graph.nodes[node];
+    forced.kind = Kind::Leaf(value.clone());
+    forced.inputs.clear();
+    forced.access.clear();
+    graph.materialized += 1;
+    Ok(value)
+}
+
+fn input(values: &[Option<Value>], idx: usize) -> Value {
+    values[idx]
+        .clone()
+        .expect("lazy value released before its last use")
+}
+
+/// where a fused kernel reads one of its inputs, addresses are kept as `usize` so the closure is `Send`
+#[derive(Clone, Copy)]
+enum Src {
+    /// the chunk starting at element `start` of a contiguous buffer of elements of `size` bytes
+    Buffer(usize, usize),
+    /// a single element broadcasted to the whole chunk
+    Scalar(usize),
+    /// the scratch buffer of an intermediate of the step
+    Scratch(usize),
+}
+
+/// where a fused kernel writes its result
+#[derive(Clone, Copy)]
+enum Dst {
+    Buffer(usize, usize),
+    Scratch(usize),
+}
+
+/// executes a group of elementwise nodes chunk by chunk, only the outputs of the step are allocated
+fn run_fused(graph: &Graph, step: &Step, values: &mut [Option<Value>]) -> Result<(), TensorError> {
+    let last = *step.nodes.last().expect("empty step");
+    let numel = graph.nodes[last].shape.size() as usize;
+    let mut dst = HashMap::with_capacity(step.nodes.len());
+    let mut scratch_bytes = Vec::new();
+    for &idx in &step.nodes {
+        let node = &graph.nodes[idx];
+        let slot = if step.outputs.contains(&idx) {
+            let value = match step.reuse {
+                Some(inp) if idx == last => input(values, inp),
+                _ => (node.dtype.empty)(&node.shape)?,
+            };
+            let addr = (node.dtype.ptr)(&value) as usize;
+            values[idx] = Some(value);
+            Dst::Buffer(addr, node.dtype.size)
+        } else {
+            scratch_bytes.push(CHUNK * node.dtype.size);
+            Dst::Scratch(scratch_bytes.len() - 1)
+        };
+        dst.insert(idx, slot);
+    }
+    let mut kernels: Vec<(ChunkKernel, Vec<Src>, Dst)> = Vec::with_capacity(step.nodes.len());
+    for &idx in &step.nodes {
+        let node = &graph.nodes[idx];
+        let srcs = node
+            .inputs
+            .iter()
+            .zip(node.access.iter())
+            .map(|(&inp, access)| match dst.get(&inp) {
+                Some(Dst::Buffer(addr, size)) => Src::Buffer(*addr, *size),
+                Some(Dst::Scratch(slot)) => Src::Scratch(*slot),
+                None => {
+                    let dtype = graph.nodes[inp].dtype;
+                    let addr = (dtype.ptr)(&input(values, inp)) as usize;
+                    match access {
+                        Access::Scalar(offset) => Src::Scalar(addr + offset),
+                        _ => Src::Buffer(addr, dtype.size),
+                    }
+                }
+            })
+            .collect::<Vec<_>>();
+        let chunk = match &node.kind {
+            Kind::Elementwise { chunk, .. } => chunk.clone(),
+            _ => unreachable!("only elementwise nodes are fused"),
+        };
+        kernels.push((chunk, srcs, dst[&idx]));
+    }
+
+    (0..numel.div_ceil(CHUNK)).into_par_iter().for_each_init(
+        || {
+            let scratch = scratch_bytes
+                .iter()
+                .map(|bytes| vec![0u64; bytes.div_ceil(8)])
+                .collect::<Vec<_>>();
+            (scratch, Vec::new(), Vec::new())
+        },
+        |(scratch, ptrs, scalars), chunk| {
+            let start = chunk * CHUNK;
+            let len = CHUNK.min(numel - start);
+            for (kernel, srcs, dst) in &kernels {
+                ptrs.clear();
+                scalars.clear();
+                for src in srcs {
+                    let (ptr, scalar) = match *src {
+                        Src::Buffer(addr, size) => (addr + start * size, false),
+                        Src::Scalar(addr) => (addr, true),
+                        Src::Scratch(slot) => (scratch[slot].as_ptr() as usize, false),
+                    };
+                    ptrs.push(ptr as *const u8);
+                    scalars.push(scalar);
+                }
+                let out = match *dst {
+                    Dst::Buffer(addr, size) => (addr + start * size) as *mut u8,
+                    Dst::Scratch(slot) => scratch[slot].as_mut_ptr() as *mut u8,
+                };
+                kernel(ptrs, scalars, out, len);
+            }
+        },
+    );
+    Ok(())
+}
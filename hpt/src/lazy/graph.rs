@@ -0,0 +1,212 @@
+use std::{any::TypeId, collections::HashMap, sync::Arc};
+
+use hpt_common::{error::base::TensorError, shape::shape::Shape};
+use hpt_traits::tensor::{CommonBounds, TensorCreator};
+
+use crate::{backend::Cpu, tensor_base::_Tensor};
+
+/// a tensor materialized while a lazy graph is executed, it is always a `_Tensor<T, Cpu, DEVICE>`
+pub(crate) type Value = Arc<dyn std::any::Any + Send + Sync>;
+
+/// computes a node from the values of its inputs, the second argument is a buffer the result can be written into
+pub(crate) type Eager =
+    Arc<dyn Fn(&[Value], Option<Value>) -> Result<Value, TensorError> + Send + Sync>;
+
+/// computes `len` contiguous elements of an elementwise node
+///
+/// the arguments are the pointers of the inputs, whether each input is a broadcasted scalar, and the output pointer
+pub(crate) type ChunkKernel = Arc<dyn Fn(&[*const u8], &[bool], *mut u8, usize) + Send + Sync>;
+
+/// the element type of a node, with the functions the executor needs to handle it without knowing the type
+#[derive(Clone, Copy)]
+pub(crate) struct DType {
+    pub(crate) id: TypeId,
+    pub(crate) size: usize,
+    /// allocates a contiguous tensor of this type
+    pub(crate) empty: fn(&Shape) -> Result<Value, TensorError>,
+    /// the data pointer of a value of this type
+    pub(crate) ptr: fn(&Value) -> *mut u8,
+}
+
+impl DType {
+    pub(crate) fn of<T: CommonBounds, const DEVICE: usize>() -> Self {
+        DType {
+            id: TypeId::of::<T>(),
+            size: std::mem::size_of::<T>(),
+            empty: |shape| Ok(Arc::new(_Tensor::<T, Cpu, DEVICE>::empty(shape.clone())?)),
+            ptr: |value| downcast::<T, DEVICE>(value).data.ptr as *mut u8,
+        }
+    }
+}
+
+/// the tensor held by `value`
+pub(crate) fn downcast<T: CommonBounds, const DEVICE: usize>(
+    value: &Value,
+) -> &_Tensor<T, Cpu, DEVICE> {
+    value
+        .downcast_ref::<_Tensor<T, Cpu, DEVICE>>()
+        .expect("lazy value has an unexpected type")
+}
+
+/// how an elementwise node reads one of its inputs
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub(crate) enum Access {
+    /// the input is contiguous and has the shape of the output, element `i` of the output reads element `i`
+    Same,
+    /// the input has a single element, the element is `offset` bytes after the data pointer of the input
+    Scalar(usize),
+    /// anything else, the node can only be executed on its own
+    Strided,
+}
+
+/// what a node does
+pub(crate) enum Kind {
+    /// a tensor that already has its data
+    Leaf(Value),
+    /// an elementwise operation, it can be fused with the elementwise nodes around it
+    Elementwise { chunk: ChunkKernel, eager: Eager },
+    /// any other operation, it is executed as it is
+    Opaque(Eager),
+}
+
+/// an operation recorded in lazy mode
+pub(crate) struct Node {
+    pub(crate) inputs: Vec<usize>,
+    /// how each input is read, only meaningful for elementwise nodes
+    pub(crate) access: Vec<Access>,
+    pub(crate) shape: Shape,
+    pub(crate) dtype: DType,
+    pub(crate) kind: Kind,
+}
+
+impl Node {
+    pub(crate) fn is_leaf(&self) -> bool {
+        matches!(self.kind, Kind::Leaf(_))
+    }
+    pub(crate) fn is_elementwise(&self) -> bool {
+        matches!(self.kind, Kind::Elementwise { .. })
+    }
+}
+
+/// the operations recorded in lazy mode, in the order they were called
+///
+/// nodes only read nodes recorded before them, so the order of `nodes` is a topological order
+#[derive(Default)]
+pub(crate) struct Graph {
+    pub(crate) nodes: Vec<Node>,
+    /// the node producing each tensor created in lazy mode, keyed by the storage of the tensor
+    pub(crate) placeholders: HashMap<u64, usize>,
+    /// number of recorded operations, the leaves excluded
+    pub(crate) recorded: usize,
+    /// number of nodes computed during the recording because their data was read
+    pub(crate) materialized: usize,
+}
+
+impl Graph {
+    pub(crate) fn push(&mut self, node: Node) -> usize {
+        self.nodes.push(node);
+        self.nodes.len() - 1
+    }
+
+    /// marks the nodes `outputs` depend on, the other nodes are never executed
+    pub(crate) fn eliminate_dead_nodes(&self, outputs: &[usize]) -> Vec<bool> {
+        let mut alive = vec![false; self.nodes.len()];
+        let mut stack = outputs.to_vec();
+        while let Some(idx) = stack.pop() {
+            if !alive[idx] {
+                alive[idx] = true;
+                stack.extend(self.nodes[idx].inputs.iter().copied());
+            }
+        }
+        alive
+    }
+
+    /// computes the nodes that only depend on single element leaves, they become leaves
+    ///
+    /// returns the number of folded nodes
+    pub(crate) fn fold_constants(&mut self, alive: &[bool]) -> Result<usize, TensorError> {
+        let mut folded = 0;
+        for idx in 0..self.nodes.len() {
+            let node = &self.nodes[idx];
+            if !alive[idx] || node.is_leaf() || node.shape.size() != 1 {
+                continue;
+            }
+            let constant = node
+                .inputs
+                .iter()
+                .all(|&inp| self.nodes[inp].is_leaf() && self.nodes[inp].shape.size() == 1);
+            if !constant {
+                continue;
+            }
+            let values = node
+                .inputs
+                .iter()
+                .map(|&inp| match &self.nodes[inp].kind {
+                    Kind::Leaf(value) => value.clone(),
+                    _ => unreachable!(),
+                })
+                .collect::<Vec<_>>();
+            let value = match &node.kind {
+                Kind::Elementwise { eager, .. } | Kind::Opaque(eager) => eager(&values, None)?,
+                Kind::Leaf(_) => unreachable!(),
+            };
+            let node = &mut self.nodes[idx];
+            node.kind = Kind::Leaf(value);
+            node.inputs.clear();
+            node.access.clear();
+            folded += 1;
+        }
+        Ok(folded)
+    }
+
+    /// groups the chains of elementwise nodes, each group is executed as a single loop
+    ///
+    /// a node joins the group of one of its inputs when it reads every input in the group element by element and its other
+    /// inputs are leaves or are computed before the group starts, so a group never has to wait for a node that depends on it.
+    /// returns the groups ordered by their first node, which is an order respecting the dependencies between groups
+    pub(crate) fn fuse_elementwise(&self, alive: &[bool]) -> Vec<Vec<usize>> {
+        let mut groups: Vec<Vec<usize>> = Vec::new();
+        // whether more nodes can join the group
+        let mut open: Vec<bool> = Vec::new();
+        let mut group_of: Vec<Option<usize>> = vec![None; self.nodes.len()];
+        for (idx, node) in self.nodes.iter().enumerate() {
+            if !alive[idx] || node.is_leaf() {
+                continue;
+            }
+            let fusable = node.is_elementwise()
+                && node.access.iter().all(|access| *access != Access::Strided);
+            let can_join = |group: usize| {
+                let first = groups[group][0];
+                open[group]
+                    && node
+                        .inputs
+                        .iter()
+                        .zip(node.access.iter())
+                        .all(|(&inp, access)| match group_of[inp] {
+                            Some(g) if g == group => *access == Access::Same,
+                            // leaves don't depend on anything, they are often recorded in the middle of a chain
+                            _ => inp < first || self.nodes[inp].is_leaf(),
+                        })
+            };
+            let target = node
+                .inputs
+                .iter()
+                .zip(node.access.iter())
+                .filter(|(_, access)| fusable && **access == Access::Same)
+                .filter_map(|(inp, _)| group_of[*inp])
+                .find(|&group| can_join(group));
+            match target {
+                Some(group) => {
+                    groups[group].push(idx);
+                    group_of[idx] = Some(group);
+                }
+                None => {
+                    groups.push(vec![idx]);
+                    open.push(fusable);
+                    group_of[idx] = Some(groups.len() - 1);
+                }
+            }
+        }
+        groups
+    }
+}
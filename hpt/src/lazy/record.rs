@@ -0,0 +1,511 @@
+use std::{
+    cell::RefCell,
+    collections::VecDeque,
+    ptr::NonNull,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+};
+
+use hpt_common::{
+    error::base::TensorError,
+    shape::{shape::Shape, shape_utils::predict_broadcast_shape},
+    utils::pointer::Pointer,
+};
+use hpt_traits::tensor::{CommonBounds, TensorInfo};
+use hpt_types::{dtype::TypeCommon, traits::VecTrait};
+
+use super::{
+    exec::{force, LazyStats, Plan},
+    graph::{downcast, Access, ChunkKernel, DType, Eager, Graph, Kind, Node, Value},
+};
+use crate::{
+    backend::{BackendTy, Buffer, Cpu},
+    ops::cpu::utils::{
+        binary::binary_normal::binary_kernel_with_out, unary::unary::unary_kernel_with_out,
+    },
+    tensor::Tensor,
+    tensor_base::_Tensor,
+};
+
+thread_local! {
+    /// the graph recorded by the current thread, `None` outside of [`lazy`]
+    static RECORDER: RefCell<Option<Graph>> = const { RefCell::new(None) };
+}
+
+/// number of threads recording, the operations only look at [`RECORDER`] when one of them is
+static RECORDINGS: AtomicUsize = AtomicUsize::new(0);
+
+/// number of tensors created in lazy mode that are still alive, the data pointers are only checked when there is one
+static PLACEHOLDERS: AtomicUsize = AtomicUsize::new(0);
+
+/// whether the operations called by the current thread are recorded instead of executed
+#[inline]
+pub(crate) fn is_recording() -> bool {
+    RECORDINGS.load(Ordering::Relaxed) != 0 && RECORDER.with(|recorder| recorder.borrow().is_some())
+}
+
+fn with_graph<R>(f: impl FnOnce(&mut Graph) -> R) -> R {
+    RECORDER.with(|recorder| {
+        f(recorder
+            .borrow_mut()
+            .as_mut()
+            .expect("operation recorded outside of lazy mode"))
+    })
+}
+
+fn storage_key<T, B: BackendTy + Buffer, const DEVICE: usize>(
+    tensor: &_Tensor<T, B, DEVICE>,
+) -> u64 {
+    tensor._backend._backend.get_ptr()
+}
+
+/// the data pointer of a tensor created in lazy mode, nothing is stored behind it
+fn placeholder_base<T>() -> *mut T {
+    NonNull::dangling().as_ptr()
+}
+
+/// the offset in bytes of a view of a tensor created in lazy mode from the start of that tensor
+fn view_offset<T, const DEVICE: usize>(view: &_Tensor<T, Cpu, DEVICE>) -> usize {
+    view.data.ptr as usize - placeholder_base::<T>() as usize
+}
+
+/// a tensor without data standing for the result of a recorded operation
+///
+/// its backend is marked as lazy, the views of it keep the marker and their data pointers are offsets from
+/// [`placeholder_base`]
+fn placeholder<T: CommonBounds, const DEVICE: usize>(
+    shape: &Shape,
+) -> Result<_Tensor<T, Cpu, DEVICE>, TensorError> {
+    let mut tensor = unsafe {
+        _Tensor::from_foreign(
+            placeholder_base(),
+            shape.clone(),
+            shape.to_strides(),
+            Box::new(|| {
+                PLACEHOLDERS.fetch_sub(1, Ordering::Relaxed);
+            }),
+        )?
+    };
+    PLACEHOLDERS.fetch_add(1, Ordering::Relaxed);
+    tensor._backend.lazy = true;
+    Ok(tensor)
+}
+
+/// the data pointer of `tensor`
+///
+/// a tensor created in lazy mode is computed the first time its data is read during the recording, so operations
+/// that aren't recorded still work on it
+#[inline]
+pub(crate) fn data_ptr<T, B: BackendTy + Buffer, const DEVICE: usize>(
+    tensor: &_Tensor<T, B, DEVICE>,
+) -> Pointer<T> {
+    let mut data = tensor.data.clone();
+    if PLACEHOLDERS.load(Ordering::Relaxed) != 0 && tensor._backend.lazy {
+        let offset = data.ptr as usize - placeholder_base::<T>() as usize;
+        data.ptr = materialize(storage_key(tensor), offset) as *mut T;
+    }
+    data
+}
+
+#[cold]
+fn materialize(key: u64, offset: usize) -> usize {
+    let graph = RECORDER.with(|recorder| recorder.borrow_mut().take());
+    let mut graph = match graph {
+        Some(graph) if graph.placeholders.contains_key(&key) => graph,
+        _ => panic!(
+            "the data of a tensor created in lazy mode is read outside of its recording, \
+            only the tensors returned by the closure passed to `lazy` are computed"
+        ),
+    };
+    // the operations run by `force` must not be recorded
+    let node = graph.placeholders[&key];
+    let value = force(&mut graph, node);
+    let base = value
+        .as_ref()
+        .map(|value| (graph.nodes[node].dtype.ptr)(value) as usize);
+    RECORDER.with(|recorder| *recorder.borrow_mut() = Some(graph));
+    match base {
+        Ok(base) => base + offset,
+        Err(err) => panic!("failed to compute a lazy tensor: {}", err),
+    }
+}
+
+/// the tensor `view` refers to once the node it was read from is computed as `value`
+pub(crate) fn resolve<T: CommonBounds, const DEVICE: usize>(
+    view: &_Tensor<T, Cpu, DEVICE>,
+    value: &Value,
+) -> _Tensor<T, Cpu, DEVICE> {
+    let value = downcast::<T, DEVICE>(value);
+    if storage_key(view) == storage_key(value) {
+        // a leaf, its value is the tensor itself
+        return view.clone();
+    }
+    let offset = view_offset(view) / std::mem::size_of::<T>();
+    if offset == 0 && view.layout == value.layout {
+        return value.clone();
+    }
+    let mut data = value.data.clone();
+    data.add(offset);
+    _Tensor {
+        data,
+        parent: Some(value.data.clone()),
+        layout: view.layout.clone(),
+        mem_layout: value.mem_layout.clone(),
+        _backend: value._backend.clone(),
+    }
+}
+
+/// a tensor read by a recorded operation
+pub(crate) trait Bind {
+    /// the node of the tensor and how an operation producing `out_shape` reads it, a tensor not created in lazy mode
+    /// becomes a leaf
+    fn bind(&self, graph: &mut Graph, out_shape: &Shape) -> (usize, Access);
+}
+
+impl<T: CommonBounds, const DEVICE: usize> Bind for _Tensor<T, Cpu, DEVICE> {
+    fn bind(&self, graph: &mut Graph, out_shape: &Shape) -> (usize, Access) {
+        let node = graph.placeholders.get(&storage_key(self)).copied();
+        let access = if self.size() == 1 {
+            Access::Scalar(node.map_or(0, |_| view_offset(self)))
+        } else if self.shape() == out_shape
+            && self.is_contiguous()
+            && (node.is_none() || view_offset(self) == 0)
+        {
+            Access::Same
+        } else {
+            Access::Strided
+        };
+        let node = node.unwrap_or_else(|| {
+            graph.push(Node {
+                inputs: vec![],
+                access: vec![],
+                shape: self.shape().clone(),
+                dtype: DType::of::<T, DEVICE>(),
+                kind: Kind::Leaf(Arc::new(self.clone())),
+            })
+        });
+        (node, access)
+    }
+}
+
+fn push<K: CommonBounds, const DEVICE: usize>(
+    inputs: &[&dyn Bind],
+    shape: Shape,
+    kind: Kind,
+) -> Result<_Tensor<K, Cpu, DEVICE>, TensorError> {
+    let tensor = placeholder::<K, DEVICE>(&shape)?;
+    with_graph(|graph| {
+        let (inputs, access) = inputs.iter().map(|input| input.bind(graph, &shape)).unzip();
+        let node = graph.push(Node {
+            inputs,
+            access,
+            shape,
+            dtype: DType::of::<K, DEVICE>(),
+            kind,
+        });
+        graph.placeholders.insert(storage_key(&tensor), node);
+        graph.recorded += 1;
+    });
+    Ok(tensor)
+}
+
+/// records an operation executed as it is, `eager` computes the result from the values of `inputs`
+pub(crate) fn record_op<K, F, const DEVICE: usize>(
+    inputs: &[&dyn Bind],
+    shape: Shape,
+    eager: F,
+) -> Result<_Tensor<K, Cpu, DEVICE>, TensorError>
+where
+    K: CommonBounds,
+    F: Fn(&[Value]) -> Result<_Tensor<K, Cpu, DEVICE>, TensorError> + Send + Sync + 'static,
+{
+    let eager: Eager = Arc::new(move |values, _| Ok(Arc::new(eager(values)?)));
+    push(inputs, shape, Kind::Opaque(eager))
+}
+
+/// records a unary elementwise operation, see `unary_fn_with_out`
+pub(crate) fn record_unary<A, K, F, F2, const DEVICE: usize>(
+    inp: &_Tensor<A, Cpu, DEVICE>,
+    f: F,
+    f2: F2,
+) -> Result<_Tensor<K, Cpu, DEVICE>, TensorError>
+where
+    A: CommonBounds,
+    K: CommonBounds,
+    F: Fn(A::Vec) -> K::Vec + Sync + Send + 'static,
+    F2: Fn(A) -> K + Sync + Send + 'static,
+{
+    let (f, f2) = (Arc::new(f), Arc::new(f2));
+    let chunk: ChunkKernel = {
+        let (f, f2) = (f.clone(), f2.clone());
+        Arc::new(move |inputs, scalars, out, len| unsafe {
+            let (inp, out) = (inputs[0] as *const A, out as *mut K);
+            let mut i = 0;
+            if A::Vec::SIZE == K::Vec::SIZE {
+                while i + K::Vec::SIZE <= len {
+                    let a = if scalars[0] {
+                        A::Vec::splat(*inp)
+                    } else {
+                        A::Vec::from_ptr(inp.add(i))
+                    };
+                    let res = f(a);
+                    std::ptr::copy_nonoverlapping(res.as_ptr(), out.add(i), K::Vec::SIZE);
+                    i += K::Vec::SIZE;
+                }
+            }
+            for j in i..len {
+                *out.add(j) = f2(if scalars[0] { *inp } else { *inp.add(j) });
+            }
+        })
+    };
+    let view = inp.clone();
+    let eager: Eager = Arc::new(move |values, out| {
+        let out = out.map(|out| downcast::<K, DEVICE>(&out).clone());
+        let res = unary_kernel_with_out(&resolve(&view, &values[0]), &*f, &*f2, out)?;
+        Ok(Arc::new(res))
+    });
+    push(
+        &[inp],
+        inp.shape().clone(),
+        Kind::Elementwise { chunk, eager },
+    )
+}
+
+/// records a binary elementwise operation, see `binary_fn_with_out_simd`
+pub(crate) fn record_binary<A, B, K, F, F2, const DEVICE: usize>(
+    lhs: &_Tensor<A, Cpu, DEVICE>,
+    rhs: &_Tensor<B, Cpu, DEVICE>,
+    f: F,
+    f2: F2,
+) -> Result<_Tensor<K, Cpu, DEVICE>, TensorError>
+where
+    A: CommonBounds,
+    B: CommonBounds,
+    K: CommonBounds,
+    F: Fn(A, B) -> K + Sync + Send + Copy + 'static,
+    F2: Fn(<A as TypeCommon>::Vec, <B as TypeCommon>::Vec) -> <K as TypeCommon>::Vec
+        + Sync
+        + Send
+        + Copy
+        + 'static,
+{
+    // the shape rules of the eager implementation
+    let shape = if lhs.size() == 1 {
+        rhs.shape().clone()
+    } else if rhs.size() == 1 {
+        lhs.shape().clone()
+    } else {
+        predict_broadcast_shape(lhs.shape(), rhs.shape())?
+    };
+    let chunk: ChunkKernel = Arc::new(move |inputs, scalars, out, len| unsafe {
+        let (lhs, rhs, out) = (inputs[0] as *const A, inputs[1] as *const B, out as *mut K);
+        let mut i = 0;
+        if A::Vec::SIZE == B::Vec::SIZE && B::Vec::SIZE == K::Vec::SIZE {
+            while i + K::Vec::SIZE <= len {
+                let a = if scalars[0] {
+                    A::Vec::splat(*lhs)
+                } else {
+                    A::Vec::from_ptr(lhs.add(i))
+                };
+                let b = if scalars[1] {
+                    B::Vec::splat(*rhs)
+                } else {
+                    B::Vec::from_ptr(rhs.add(i))
+                };
+                let res = f2(a, b);
+                std::ptr::copy_nonoverlapping(res.as_ptr(), out.add(i), K::Vec::SIZE);
+                i += K::Vec::SIZE;
+            }
+        }
+        for j in i..len {
+            let a = if scalars[0] { *lhs } else { *lhs.add(j) };
+            let b = if scalars[1] { *rhs } else { *rhs.add(j) };
+            *out.add(j) = f(a, b);
+        }
+    });
+    let (lhs_view, rhs_view) = (lhs.clone(), rhs.clone());
+    let eager: Eager = Arc::new(move |values, out| {
+        let out = out.map(|out| downcast::<K, DEVICE>(&out).clone());
+        let res = binary_kernel_with_out(
+            &resolve(&lhs_view, &values[0]),
+            &resolve(&rhs_view, &values[1]),
+            f,
+            f2,
+            out,
+        )?;
+        Ok(Arc::new(res))
+    });
+    push(&[lhs, rhs], shape, Kind::Elementwise { chunk, eager })
+}
+
+/// the values a closure passed to [`lazy`] can return, the tensors they hold are computed when the closure returns
+pub trait LazyOutput: Sized {
+    #[doc(hidden)]
+    fn collect(&self, outputs: &mut Outputs);
+    #[doc(hidden)]
+    fn materialize(self, outputs: &mut Outputs) -> Result<Self, TensorError>;
+}
+
+/// the tensors returned by a lazy closure, in the order [`LazyOutput::collect`] visits them
+#[doc(hidden)]
+#[derive(Default)]
+pub struct Outputs {
+    keys: Vec<u64>,
+    values: VecDeque<Option<Value>>,
+}
+
+impl<T: CommonBounds, const DEVICE: usize> LazyOutput for Tensor<T, Cpu, DEVICE> {
+    fn collect(&self, outputs: &mut Outputs) {
+        outputs.keys.push(storage_key(self.inner.as_ref()));
+    }
+    fn materialize(self, outputs: &mut Outputs) -> Result<Self, TensorError> {
+        match outputs.values.pop_front().flatten() {
+            Some(value) => Ok(resolve(self.inner.as_ref(), &value).into()),
+            None => Ok(self),
+        }
+    }
+}
+
+impl LazyOutput for () {
+    fn collect(&self, _: &mut Outputs) {}
+    fn materialize(self, _: &mut Outputs) -> Result<Self, TensorError> {
+        Ok(())
+    }
+}
+
+impl<O: LazyOutput> LazyOutput for Option<O> {
+    fn collect(&self, outputs: &mut Outputs) {
+        if let Some(inner) = self {
+            inner.collect(outputs);
+        }
+    }
+    fn materialize(self, outputs: &mut Outputs) -> Result<Self, TensorError> {
+        self.map(|inner| inner.materialize(outputs)).transpose()
+    }
+}
+
+impl<O: LazyOutput> LazyOutput for Vec<O> {
+    fn collect(&self, outputs: &mut Outputs) {
+        self.iter().for_each(|inner| inner.collect(outputs));
+    }
+    fn materialize(self, outputs: &mut Outputs) -> Result<Self, TensorError> {
+        self.into_iter()
+            .map(|inner| inner.materialize(outputs))
+            .collect()
+    }
+}
+
+macro_rules! impl_lazy_output_tuple {
+    ($($name:ident),+) => {
+        impl<$($name: LazyOutput),+> LazyOutput for ($($name,)+) {
+            #[allow(non_snake_case)]
+            fn collect(&self, outputs: &mut Outputs) {
+                let ($($name,)+) = self;
+                $($name.collect(outputs);)+
+            }
+            #[allow(non_snake_case)]
+            fn materialize(self, outputs: &mut Outputs) -> Result<Self, TensorError> {
+                let ($($name,)+) = self;
+                Ok(($($name.materialize(outputs)?,)+))
+            }
+        }
+    };
+}
+
+impl_lazy_output_tuple!(A);
+impl_lazy_output_tuple!(A, B);
+impl_lazy_output_tuple!(A, B, C);
+impl_lazy_output_tuple!(A, B, C, D);
+
+/// records the operations of the current thread until it's dropped, even if the closure panics
+struct Recording;
+
+impl Recording {
+    fn start() -> Self {
+        RECORDINGS.fetch_add(1, Ordering::Relaxed);
+        RECORDER.with(|recorder| *recorder.borrow_mut() = Some(Graph::default()));
+        Recording
+    }
+    fn finish(self) -> Graph {
+        RECORDER
+            .with(|recorder| recorder.borrow_mut().take())
+            .expect("the recording was stopped by the closure")
+    }
+}
+
+impl Drop for Recording {
+    fn drop(&mut self) {
+        RECORDER.with(|recorder| recorder.borrow_mut().take());
+        RECORDINGS.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+/// Run a closure in lazy mode
+///
+/// The tensor operations called by `f` are recorded as a graph instead of being executed. When `f` returns, the unused
+/// operations are removed, the operations on scalars are folded, the chains of elementwise operations are fused into
+/// single kernels and the buffers of the intermediates are reused, then the graph is executed and the tensors returned
+/// by `f` are computed.
+///
+/// # Arguments
+/// - `f`: the closure to record
+///
+/// # Returns
+/// The result of `f`, with its tensors computed
+///
+/// # Note
+/// An operation that reads the data of a recorded tensor directly computes the tensor right away. Calling `lazy` inside
+/// `f` just runs the inner closure.
+pub fn lazy<R, E, F>(f: F) -> Result<R, E>
+where
+    F: FnOnce() -> Result<R, E>,
+    R: LazyOutput,
+    E: From<TensorError>,
+{
+    lazy_with_stats(f).map(|(res, _)| res)
+}
+
+/// Run a closure in lazy mode and report what the graph passes did, see [`lazy`]
+///
+/// # Arguments
+/// - `f`: the closure to record
+///
+/// # Returns
+/// The result of `f`, with its tensors computed, and the statistics of the execution
+pub fn lazy_with_stats<R, E, F>(f: F) -> Result<(R, LazyStats), E>
+where
+    F: FnOnce() -> Result<R, E>,
+    R: LazyOutput,
+    E: From<TensorError>,
+{
+    if is_recording() {
+        return f().map(|res| (res, LazyStats::default()));
+    }
+    let recording = Recording::start();
+    let res = f();
+    let mut graph = recording.finish();
+    let res = res?;
+
+    let mut outputs = Outputs::default();
+    res.collect(&mut outputs);
+    let nodes = outputs
+        .keys
+        .iter()
+        .filter_map(|key| graph.placeholders.get(key).copied())
+        .collect::<Vec<_>>();
+    let plan = Plan::new(&mut graph, &nodes)?;
+    let values = plan.execute(&graph)?;
+    outputs.values = outputs
+        .keys
+        .iter()
+        .map(|key| {
+            graph
+                .placeholders
+                .get(key)
+                .and_then(|&node| values[node].clone())
+        })
+        .collect();
+    Ok((res.materialize(&mut outputs)?, plan.stats))
+}
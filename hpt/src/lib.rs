@@ -7,8 +7,13 @@ pub mod ops {
     pub mod cpu {
         /// a module defines affine_grid operation
         pub mod affine_grid;
+        /// a module defines DLPack import and export for cpu tensors
+        pub mod dlpack;
+        /// a module defines the affine quantized tensor and the quantized kernels
+        pub mod quantize;
         pub(crate) mod utils {
             pub(crate) mod reduce {
+                pub(crate) mod accumulate;
                 pub(crate) mod reduce;
                 pub(crate) mod reduce_template;
                 pub(crate) mod reduce_utils;
@@ -22,6 +27,8 @@ pub mod ops {
             pub(crate) mod unary {
                 pub(crate) mod unary;
             }
+            pub(crate) mod dispatch;
+            pub(crate) mod fused;
         }
         /// a module defines all the std::ops operations
         pub mod std_ops;
@@ -83,6 +90,8 @@ pub mod ops {
             pub mod advance;
             /// a module that contains all the arg reduce functions
             pub mod arg_reduce;
+            /// a module that contains the iterators over sub-tensors along an axis
+            pub mod axis_iter;
             /// a module defines all normal binary operation
             pub mod binary;
             /// a module that contains all the tensor compare functions
@@ -93,6 +102,10 @@ pub mod ops {
             pub mod conv;
             /// a module that contains all the cumulative operations
             pub mod cumulative;
+            /// a module that contains the user defined operators
+            pub mod custom_op;
+            /// a module that contains the reductions with user provided closures
+            pub mod custom_reduce;
             /// a module that contains all fft operations
             pub mod fft;
             /// a module that contains all the float out binary operations
@@ -124,6 +137,8 @@ pub mod ops {
             pub mod advance;
             /// a module that contains all the arg reduce functions
             pub mod arg_reduce;
+            /// a module that contains the geometry of the axis iterators
+            pub mod axis_iter;
             /// a module that contains all the tensor compare functions
             pub mod cmp;
             /// a module that contains all the common reduce functions
@@ -132,6 +147,8 @@ pub mod ops {
             pub mod conv;
             /// a module that contains all the cumulative operations
             pub mod cumulative;
+            /// a module that contains the reductions with user provided closures
+            pub mod custom_reduce;
             /// a module that contains all fft operations
             pub mod fft;
             /// a module that contains all the float out binary operations
@@ -255,6 +272,19 @@ pub mod ops {
 }
 
 pub mod backend;
+pub mod dlpack;
+/// a module that defines the runtime typed `DynTensor`
+pub mod dyn_tensor;
+/// a module contains the lazy execution mode
+pub mod lazy {
+    pub(crate) mod exec;
+    pub(crate) mod graph;
+    pub(crate) mod record;
+    pub use exec::LazyStats;
+    pub use record::{lazy, lazy_with_stats, LazyOutput, Outputs};
+}
+/// a module defines the execution context the cpu kernels run with
+pub mod context;
 /// a module that wrap the _Tensor struct
 pub mod tensor;
 /// a module that defines the _Tensor struct
@@ -264,30 +294,42 @@ pub mod tensor_base;
 /// # Note
 /// for this library's developer, not necessary need to know how they works
 pub mod to_tensor;
+pub use crate::context::{ExecutionContext, ExecutionContextBuilder};
+pub use crate::ops::cpu::quantize::{PackedI4, QTensor, QuantParams, QuantType};
+pub use crate::ops::cpu::tensor_external::axis_iter::{AxisIter, AxisIterMut};
 pub use crate::ops::cpu::utils::binary::binary_normal::binary_with_out;
+pub use crate::ops::cpu::utils::fused::{fused_load_vec, fused_reduce, matmul_post};
 use ctor::ctor;
+pub use dyn_tensor::{Dtype, DynTensor, TypeNum};
 pub use hpt_iterator::iterator_traits::*;
+pub use hpt_iterator::windows::{Window, WindowSimd};
 pub use hpt_iterator::TensorIterator;
+pub use lazy::{lazy, lazy_with_stats, LazyOutput, LazyStats};
 
 pub use crate::backend::*;
 pub use flate2;
-// #[cfg(feature = "codegen")]
-// pub use hpt_codegen::compile;
-// #[cfg(feature = "codegen")]
-// pub use hpt_codegen::fuse_proc_macro;
+#[cfg(feature = "codegen")]
+pub use hpt_codegen::compile;
+#[cfg(feature = "codegen")]
+pub use hpt_codegen::fuse_proc_macro;
 pub use hpt_common::slice;
 pub use hpt_common::{
-    error::base::TensorError, shape::shape::Shape, slice::Slice, strides::strides::Strides,
+    axis::axis::Axis, error::base::TensorError, shape::shape::Shape, slice::Slice,
+    strides::strides::Strides,
 };
 pub use hpt_dataloader::data_loader::parse_header_compressed;
 pub(crate) use hpt_dataloader::save;
 pub use hpt_dataloader::{
-    CompressionAlgo, DataLoader, Endian, FromSafeTensors, Load, MetaLoad, Save, TensorLoader,
-    TensorSaver,
+    verify, CompressionAlgo, DataLoader, Endian, FromMapped, FromSafeTensors, Load, MappedEntry,
+    MetaLoad, MmapLoader, NpyHeader, NpzReader, NpzWriter, SafeTensorData, Save, TensorLoader,
+    TensorReader, TensorSaver, TensorWriter, ToSafeTensors, FORMAT_VERSION,
 };
-pub use hpt_macros::{match_selection, Load, Save};
+pub use hpt_display::{DisplayWith, PrintOptions, PrintSign};
+pub use hpt_macros::{match_selection, Load, Save, ToSafeTensors};
 pub use hpt_traits::*;
+pub use hpt_types::dispatch::{set_simd_level, simd_level, SimdLevel};
 pub use hpt_types::dtype::TypeCommon;
+pub use hpt_types::f8::{f8e4m3, f8e5m2};
 pub use hpt_types::into_scalar::Cast;
 pub use hpt_types::into_vec::IntoVec;
 pub use hpt_types::traits::VecTrait;
@@ -299,7 +341,13 @@ pub use hpt_types::vectors::*;
 pub use serde;
 pub use tensor::Tensor;
 
-use std::{cell::RefCell, sync::atomic::AtomicUsize};
+use std::{
+    cell::RefCell,
+    sync::{
+        atomic::{AtomicBool, AtomicU8},
+        RwLock,
+    },
+};
 thread_local! {
     static THREAD_POOL: RefCell<threadpool::ThreadPool> = RefCell::new(
         threadpool::ThreadPool::new(num_cpus::get_physical())
@@ -308,18 +356,29 @@ thread_local! {
 
 /// Set the Tensor display precision
 pub fn set_global_display_precision(precision: usize) {
-    DISPLAY_PRECISION.store(precision, std::sync::atomic::Ordering::Relaxed);
+    PRINT_OPTIONS.write().unwrap().precision = precision;
 }
 
 /// Set the left and right elements to display for each dimension
 pub fn set_global_display_lr_elements(lr_elements: usize) {
-    DISPLAY_LR_ELEMENTS.store(lr_elements, std::sync::atomic::Ordering::Relaxed);
+    PRINT_OPTIONS.write().unwrap().edge_items = lr_elements;
+}
+
+/// Set the options used to print tensors with `{}`, `{:?}` and `{:#}`
+pub fn set_print_options(options: PrintOptions) {
+    *PRINT_OPTIONS.write().unwrap() = options;
+}
+
+/// Get the options used to print tensors
+pub fn get_print_options() -> PrintOptions {
+    PRINT_OPTIONS.read().unwrap().clone()
 }
 
 /// Set the global number of threads
 ///
 /// # Note
 /// Rayon only allows the number of threads to be set once, so the rayon thread pool won't have any effect if it's called more than once.
+/// Use [`ExecutionContext`] to run kernels with a different number of threads for a scope.
 pub fn set_num_threads(num_threads: usize) {
     THREAD_POOL.with(|x| {
         x.borrow_mut().set_num_threads(num_threads);
@@ -334,13 +393,54 @@ pub fn set_num_threads(num_threads: usize) {
     }
 }
 
-/// Get the global number of threads
+/// Enable or disable the global deterministic mode.
+///
+/// In deterministic mode the floating point reductions, `cumsum`, matmul and conv produce bit-identical results
+/// regardless of the number of threads, and the operations that can't guarantee it, like the random number
+/// generators, return an error. The mode can also be enabled for a scope with [`ExecutionContextBuilder::deterministic`].
+pub fn set_deterministic(deterministic: bool) {
+    DETERMINISTIC.store(deterministic, std::sync::atomic::Ordering::Relaxed);
+}
+
+/// Get the global deterministic mode
+pub fn get_deterministic() -> bool {
+    DETERMINISTIC.load(std::sync::atomic::Ordering::Relaxed)
+}
+
+/// Set the global accumulation policy of `sum`, `mean`, `reducel2` and `logsumexp`.
+///
+/// The policy can also be selected per call with [`NormalReduce::sum_with`] and the `_with` methods of [`FloatReduce`].
+pub fn set_accumulation(accumulation: Accumulation) {
+    let val = match accumulation {
+        Accumulation::Native => 0,
+        Accumulation::Widened => 1,
+        Accumulation::Kahan => 2,
+        Accumulation::Pairwise => 3,
+    };
+    ACCUMULATION.store(val, std::sync::atomic::Ordering::Relaxed);
+}
+
+/// Get the global accumulation policy
+pub fn get_accumulation() -> Accumulation {
+    match ACCUMULATION.load(std::sync::atomic::Ordering::Relaxed) {
+        1 => Accumulation::Widened,
+        2 => Accumulation::Kahan,
+        3 => Accumulation::Pairwise,
+        _ => Accumulation::Native,
+    }
+}
+
+/// Get the number of threads of the installed [`ExecutionContext`], or the global number of threads if no context is installed
 pub fn get_num_threads() -> usize {
+    if let Some(ctx) = ExecutionContext::current() {
+        return ctx.num_threads();
+    }
     THREAD_POOL.with(|x| x.borrow().max_count())
 }
 
-static DISPLAY_PRECISION: AtomicUsize = AtomicUsize::new(4);
-static DISPLAY_LR_ELEMENTS: AtomicUsize = AtomicUsize::new(3);
+static DETERMINISTIC: AtomicBool = AtomicBool::new(false);
+static ACCUMULATION: AtomicU8 = AtomicU8::new(0);
+static PRINT_OPTIONS: RwLock<PrintOptions> = RwLock::new(PrintOptions::new());
 
 #[cfg(feature = "cuda")]
 pub(crate) mod cuda_compiled {
@@ -370,15 +470,13 @@ static ALIGN: usize = 64;
 pub(crate) const REGNUM: usize = 16;
 #[cfg(all(not(target_feature = "avx2"), target_feature = "sse"))]
 pub(crate) const REGNUM: usize = 8;
-#[cfg(any(target_feature = "avx512f", target_arch = "aarch64"))]
+#[cfg(target_arch = "aarch64")]
 pub(crate) const REGNUM: usize = 32;
 
 use hpt_types::arch_simd as simd;
 
 #[cfg(target_feature = "avx2")]
 type BoolVector = simd::_256bit::boolx32::boolx32;
-#[cfg(any(target_feature = "avx512f"))]
-type BoolVector = simd::_512bit::boolx64::boolx64;
 #[cfg(any(
     all(not(target_feature = "avx2"), target_feature = "sse"),
     target_arch = "arm",
@@ -389,8 +487,6 @@ type BoolVector = simd::_128bit::boolx16::boolx16;
 
 #[cfg(target_feature = "avx2")]
 const SIMD_WIDTH: usize = 256;
-#[cfg(any(target_feature = "avx512f"))]
-const SIMD_WIDTH: usize = 512;
 #[cfg(any(
     all(not(target_feature = "avx2"), target_feature = "sse"),
     target_arch = "arm",
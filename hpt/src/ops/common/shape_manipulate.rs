@@ -209,7 +209,9 @@ pub(crate) fn flip<
     let mut new_strides = a.layout.strides().to_vec();
     let mut ptr = a.data.clone();
     for &i in axes.iter() {
-        ptr.offset(new_strides[i] * (a.layout.shape()[i] - 1));
+        ptr.ptr = ptr
+            .ptr
+            .wrapping_offset((new_strides[i] * (a.layout.shape()[i] - 1)) as isize);
         new_strides[i] = -new_strides[i];
     }
     if a.parent.is_none() {
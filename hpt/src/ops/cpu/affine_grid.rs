@@ -11,7 +11,7 @@ use hpt_common::{
 use hpt_traits::{CommonBounds, TensorCreator, TensorInfo};
 use hpt_types::{dtype::FloatConst, into_scalar::Cast};
 
-use crate::{tensor_base::_Tensor, Tensor, THREAD_POOL};
+use crate::{context::with_thread_pool, tensor_base::_Tensor, Tensor};
 
 impl<T> _Tensor<T>
 where
@@ -63,7 +63,7 @@ where
             let h = shape[2];
             let w = shape[3];
             let grid = _Tensor::<T>::zeros(vec![n, h, w, 2])?;
-            THREAD_POOL.with_borrow_mut(|x| {
+            with_thread_pool(|x| {
                 let outer_loop_size = grid.size() / 2;
                 let num_threads;
                 if outer_loop_size < x.max_count() {
@@ -0,0 +1,163 @@
+use std::{ffi::c_void, panic::Location};
+
+use hpt_common::{
+    error::{base::TensorError, device::DeviceError, memory::MemoryError, param::ParamError},
+    shape::shape::Shape,
+    strides::strides_utils::shape_to_strides,
+};
+use hpt_traits::{CommonBounds, TensorInfo};
+
+use crate::{
+    backend::Cpu,
+    dlpack::{DLDataType, DLDevice, DLDeviceType, DLManagedTensor, DLTensor},
+    tensor_base::_Tensor,
+    Tensor,
+};
+
+/// the `manager_ctx` of the exported `DLManagedTensor`
+///
+/// `_tensor` holds one reference of the storage, it is released when the consumer calls the deleter
+struct DLPackContext<T, const DEVICE: usize> {
+    _tensor: _Tensor<T, Cpu, DEVICE>,
+    shape: Vec<i64>,
+    strides: Vec<i64>,
+}
+
+unsafe extern "C" fn dlpack_deleter<T, const DEVICE: usize>(managed: *mut DLManagedTensor) {
+    if managed.is_null() {
+        return;
+    }
+    let managed = Box::from_raw(managed);
+    let ctx = Box::from_raw(managed.manager_ctx as *mut DLPackContext<T, DEVICE>);
+    drop(ctx);
+}
+
+/// wrapper to move the foreign `DLManagedTensor` into the deleter closure
+struct ManagedPtr(*mut DLManagedTensor);
+unsafe impl Send for ManagedPtr {}
+
+impl<T: CommonBounds, const DEVICE: usize> _Tensor<T, Cpu, DEVICE> {
+    pub(crate) fn to_dlpack(&self) -> Result<*mut DLManagedTensor, TensorError> {
+        let dtype = DLDataType::of::<T>()?;
+        let mut ctx = Box::new(DLPackContext {
+            _tensor: self.clone(),
+            shape: self.shape().inner().clone(),
+            strides: self.strides().inner().clone(),
+        });
+        let managed = Box::new(DLManagedTensor {
+            dl_tensor: DLTensor {
+                data: self.ptr().ptr as *mut c_void,
+                device: DLDevice {
+                    device_type: DLDeviceType::Cpu as i32,
+                    device_id: 0,
+                },
+                ndim: ctx.shape.len() as i32,
+                dtype,
+                shape: ctx.shape.as_mut_ptr(),
+                strides: ctx.strides.as_mut_ptr(),
+                byte_offset: 0,
+            },
+            manager_ctx: std::ptr::null_mut(),
+            deleter: Some(dlpack_deleter::<T, DEVICE>),
+        });
+        let managed = Box::into_raw(managed);
+        unsafe {
+            (*managed).manager_ctx = Box::into_raw(ctx) as *mut c_void;
+        }
+        Ok(managed)
+    }
+
+    #[track_caller]
+    pub(crate) unsafe fn from_dlpack(managed: *mut DLManagedTensor) -> Result<Self, TensorError> {
+        if managed.is_null() {
+            return Err(MemoryError::InvalidLayout {
+                message: "DLManagedTensor is null".to_string(),
+                location: Location::caller(),
+            }
+            .into());
+        }
+        let dl_tensor = &(*managed).dl_tensor;
+        if dl_tensor.device.device_type != DLDeviceType::Cpu as i32 {
+            return Err(DeviceError::DeviceMismatch {
+                expected: "kDLCPU".to_string(),
+                actual: format!("DLDeviceType({})", dl_tensor.device.device_type),
+                location: Location::caller(),
+            }
+            .into());
+        }
+        if !dl_tensor.dtype.matches::<T>() {
+            return Err(ParamError::DtypeMismatch {
+                expected: T::STR.to_string(),
+                actual: dl_tensor.dtype.to_string(),
+                location: Location::caller(),
+            }
+            .into());
+        }
+        let ndim = dl_tensor.ndim as usize;
+        let shape = if ndim == 0 {
+            vec![]
+        } else {
+            std::slice::from_raw_parts(dl_tensor.shape, ndim).to_vec()
+        };
+        let shape = Shape::from(shape);
+        let strides = if dl_tensor.strides.is_null() || ndim == 0 {
+            shape_to_strides(&shape)
+        } else {
+            std::slice::from_raw_parts(dl_tensor.strides, ndim)
+                .to_vec()
+                .into()
+        };
+        let data = (dl_tensor.data as *mut u8).add(dl_tensor.byte_offset as usize) as *mut T;
+
+        // the data may also be owned by hpt if it is exported by `to_dlpack`, it is kept alive by `managed` in that case
+        let foreign = ManagedPtr(managed);
+        _Tensor::from_foreign(
+            data,
+            shape,
+            strides,
+            Box::new(move || {
+                let managed = foreign;
+                if let Some(deleter) = (*managed.0).deleter {
+                    deleter(managed.0);
+                }
+            }),
+        )
+    }
+}
+
+impl<T: CommonBounds, const DEVICE: usize> Tensor<T, Cpu, DEVICE> {
+    /// Export the tensor as a DLPack `DLManagedTensor` without copying the data.
+    ///
+    /// The returned tensor holds a reference of the tensor's memory, the memory is released when the consumer calls the `deleter`.
+    ///
+    /// # Returns
+    ///
+    /// A pointer to the `DLManagedTensor`, the consumer takes the ownership of it.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `T` has no DLPack data type.
+    pub fn to_dlpack(&self) -> Result<*mut DLManagedTensor, TensorError> {
+        self.inner.to_dlpack()
+    }
+
+    /// Create a tensor viewing the memory of a DLPack `DLManagedTensor` without copying the data.
+    ///
+    /// Any strides are supported, the `deleter` of `managed` is called once the last tensor referencing the memory is dropped.
+    ///
+    /// # Arguments
+    ///
+    /// * `managed` - The DLPack tensor, must be located on `kDLCPU` and have the same data type as `T`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the device or the data type doesn't match, in that case the ownership of `managed` is not taken.
+    ///
+    /// # Safety
+    ///
+    /// `managed` must point to a valid `DLManagedTensor`, and the ownership is transferred to hpt on success.
+    #[track_caller]
+    pub unsafe fn from_dlpack(managed: *mut DLManagedTensor) -> Result<Self, TensorError> {
+        Ok(_Tensor::from_dlpack(managed)?.into())
+    }
+}
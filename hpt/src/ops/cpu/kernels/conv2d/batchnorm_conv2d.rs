@@ -5,6 +5,7 @@ use crate::ops::cpu::kernels::batch_norm_conv::conv2d_full_oc_kernel_dispatch;
 use crate::ops::cpu::kernels::batch_norm_conv::remain_oc_kernel_dispatch;
 use crate::ops::cpu::kernels::batch_norm_conv::Params;
 use crate::ops::cpu::kernels::batch_norm_conv::PartialParams;
+use crate::ops::cpu::utils::dispatch::par_for_each_dispatch;
 use crate::tensor_base::_Tensor;
 use crate::Cpu;
 use crate::Tensor;
@@ -227,7 +228,7 @@ where
 
         let ic_block_size = ic_nvec * T::Vec::SIZE; // in channel block size
         let oc_block_size = oc_nvec * T::Vec::SIZE; // out channel block size, but this is only caculated based on cache line size, we have another block size `jb`
-        (0..outer).into_par_iter().for_each(|idx| {
+        par_for_each_dispatch(0..outer, |idx| {
             let mut out = out.clone();
             let mut kernel = ro_ptr.clone();
             let b = idx / num_oh;
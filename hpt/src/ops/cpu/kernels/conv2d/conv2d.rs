@@ -5,6 +5,7 @@ use crate::ops::cpu::kernels::conv::conv2d_full_oc_kernel_dispatch;
 use crate::ops::cpu::kernels::conv::remain_oc_kernel_dispatch;
 use crate::ops::cpu::kernels::conv::Params;
 use crate::ops::cpu::kernels::conv::PartialParams;
+use crate::ops::cpu::utils::dispatch::{load_partial, par_for_each_dispatch, store_partial};
 use crate::tensor_base::_Tensor;
 use crate::Cpu;
 use crate::REGNUM;
@@ -14,6 +15,8 @@ use hpt_common::utils::pointer::Pointer;
 use hpt_traits::CommonBounds;
 use hpt_traits::TensorCreator;
 use hpt_traits::TensorInfo;
+use hpt_traits::TensorLike;
+use hpt_types::dispatch::{dispatch_kernel, Simd, SimdElement, SimdKernel, SimdVec};
 use hpt_types::into_scalar::Cast;
 use hpt_types::vectors::traits::*;
 use rayon::prelude::*;
@@ -84,6 +87,39 @@ where
         }
         .into());
     }
+    if matches!(T::STR, "f32" | "f64") {
+        let mut output = conv2d_level(
+            input,
+            kernels,
+            bias,
+            [step_height, step_width],
+            [ph_start, pw_start],
+            [dh, dw],
+            [out_height, out_width],
+        )?;
+        if let Some(activation) = activation {
+            output
+                .as_raw_mut()
+                .par_chunks_mut(T::Vec::SIZE)
+                .for_each(|chunk| {
+                    let mut vec = T::Vec::splat(T::ZERO);
+                    unsafe {
+                        std::ptr::copy_nonoverlapping(
+                            chunk.as_ptr(),
+                            vec.as_mut_ptr(),
+                            chunk.len(),
+                        );
+                        let vec = activation(vec);
+                        std::ptr::copy_nonoverlapping(
+                            vec.as_ptr(),
+                            chunk.as_mut_ptr(),
+                            chunk.len(),
+                        );
+                    }
+                });
+        }
+        return Ok(output);
+    }
     let activation = activation.unwrap_or(|x| x);
     let output = _Tensor::<T, Cpu, DEVICE>::empty([batch, out_height, out_width, out_channels])?;
     let out = output.ptr();
@@ -213,7 +249,7 @@ where
 
     let ic_block_size = ic_nvec * T::Vec::SIZE; // in channel block size
     let oc_block_size = oc_nvec * T::Vec::SIZE; // out channel block size, but this is only caculated based on cache line size, we have another block size `jb`
-    (0..outer).into_par_iter().for_each(|idx| {
+    par_for_each_dispatch(0..outer, |idx| {
         let mut out = out.clone();
         let mut kernel = ro_ptr.clone();
         let b = idx / num_oh;
@@ -353,6 +389,176 @@ where
     Ok(output)
 }
 
+/// `conv2d` of a f32 or f64 tensor with the kernels of the simd level, without the activation
+#[track_caller]
+fn conv2d_level<T: CommonBounds, const DEVICE: usize>(
+    input: &_Tensor<T, Cpu, DEVICE>,
+    kernels: &_Tensor<T, Cpu, DEVICE>,
+    bias: Option<&_Tensor<T, Cpu, DEVICE>>,
+    steps: [i64; 2],
+    pads: [i64; 2],
+    dilation: [i64; 2],
+    out_shape: [i64; 2],
+) -> Result<_Tensor<T, Cpu, DEVICE>, TensorError> {
+    macro_rules! level_kernel {
+        ($t:ty) => {{
+            let output = _Tensor::<$t, Cpu, DEVICE>::empty([
+                input.shape()[0],
+                out_shape[0],
+                out_shape[1],
+                kernels.shape()[3],
+            ])?;
+            let bias = bias.map(|bias| bias.static_cast::<$t>()).transpose()?;
+            dispatch_kernel(Conv2dLevel::<$t> {
+                inp: input.static_cast::<$t>()?.ptr(),
+                kernel: kernels.static_cast::<$t>()?.ptr(),
+                bias: bias.as_ref().map(|bias| bias.ptr()),
+                out: output.ptr(),
+                inp_shape: [
+                    input.shape()[0],
+                    input.shape()[1],
+                    input.shape()[2],
+                    input.shape()[3],
+                ],
+                out_shape: [out_shape[0], out_shape[1], kernels.shape()[3]],
+                kernel_shape: [kernels.shape()[0], kernels.shape()[1]],
+                steps,
+                pads,
+                dilation,
+            });
+            output.static_cast::<T>()
+        }};
+    }
+    match T::STR {
+        "f32" => level_kernel!(f32),
+        "f64" => level_kernel!(f64),
+        _ => unreachable!("no simd level kernels for {}", T::STR),
+    }
+}
+
+/// a direct conv2d of a contiguous NHWC input and a contiguous `[kh, kw, in_channels, out_channels]` kernel
+struct Conv2dLevel<E> {
+    inp: Pointer<E>,
+    kernel: Pointer<E>,
+    bias: Option<Pointer<E>>,
+    out: Pointer<E>,
+    /// batch, height, width, in channels
+    inp_shape: [i64; 4],
+    /// height, width, out channels
+    out_shape: [i64; 3],
+    kernel_shape: [i64; 2],
+    steps: [i64; 2],
+    pads: [i64; 2],
+    dilation: [i64; 2],
+}
+
+impl<E: SimdElement + CommonBounds> SimdKernel for Conv2dLevel<E> {
+    type Output = ();
+
+    #[inline(always)]
+    fn run<S: Simd>(self, simd: S) {
+        let [batch, _, _, _] = self.inp_shape;
+        let [out_height, _, _] = self.out_shape;
+        (0..batch * out_height)
+            .into_par_iter()
+            .for_each(|idx| simd.vectorize(Conv2dRow { conv: &self, idx }));
+    }
+}
+
+/// the output row `idx % out_height` of the image `idx / out_height`, one task of [`Conv2dLevel`]
+struct Conv2dRow<'a, E> {
+    conv: &'a Conv2dLevel<E>,
+    idx: i64,
+}
+
+impl<E: SimdElement + CommonBounds> SimdKernel for Conv2dRow<'_, E> {
+    type Output = ();
+
+    #[inline(always)]
+    fn run<S: Simd>(self, simd: S) {
+        let [out_height, _, _] = self.conv.out_shape;
+        let (b, oh) = (self.idx / out_height, self.idx % out_height);
+        // the micro kernel keeps `OW x 2` accumulators, two vectors of the kernel and a broadcast of the input in registers
+        unsafe {
+            if S::REGNUM >= 32 {
+                self.conv.row::<S, 12>(simd, b, oh)
+            } else {
+                self.conv.row::<S, 6>(simd, b, oh)
+            }
+        }
+    }
+}
+
+impl<E: SimdElement + CommonBounds> Conv2dLevel<E> {
+    /// compute the output row `oh` of the image `b`
+    #[inline(always)]
+    unsafe fn row<S: Simd, const OW: usize>(&self, simd: S, b: i64, oh: i64) {
+        let lanes = E::Vector::<S>::LANES;
+        let [_, img_height, img_width, in_channels] = self.inp_shape;
+        let [out_height, out_width, out_channels] = self.out_shape;
+        let [kh, kw] = self.kernel_shape;
+        let [step_height, step_width] = self.steps;
+        let [ph, pw] = self.pads;
+        let [dh, dw] = self.dilation;
+        let (isw, ish) = (in_channels, img_width * in_channels);
+        let (ksw, ksh) = (in_channels * out_channels, kw * in_channels * out_channels);
+        let inp = self.inp.ptr.offset((b * img_height * ish) as isize);
+        let out = self
+            .out
+            .ptr
+            .offset(((b * out_height + oh) * out_width * out_channels) as isize);
+        for oc in (0..out_channels).step_by(2 * lanes) {
+            let oc_len = (out_channels - oc).min(2 * lanes as i64) as usize;
+            let (len0, len1) = (oc_len.min(lanes), oc_len.saturating_sub(lanes));
+            let init = match &self.bias {
+                Some(bias) => {
+                    let bias = bias.ptr.offset(oc as isize);
+                    [
+                        load_partial::<E, S>(bias, len0, E::ZERO),
+                        load_partial::<E, S>(bias.add(len0), len1, E::ZERO),
+                    ]
+                }
+                None => [E::Vector::<S>::splat(simd, E::ZERO); 2],
+            };
+            for ow0 in (0..out_width).step_by(OW) {
+                let ows = ((out_width - ow0) as usize).min(OW);
+                let mut acc = [init; OW];
+                for i in 0..kh {
+                    let ih = oh * step_height - ph + i * dh;
+                    if ih < 0 || ih >= img_height {
+                        continue;
+                    }
+                    for j in 0..kw {
+                        let kernel = self.kernel.ptr.offset((i * ksh + j * ksw + oc) as isize);
+                        for c in 0..in_channels {
+                            let kernel = kernel.offset((c * out_channels) as isize);
+                            let k0 = load_partial::<E, S>(kernel, len0, E::ZERO);
+                            let k1 = load_partial::<E, S>(kernel.add(len0), len1, E::ZERO);
+                            for (w, acc) in acc.iter_mut().enumerate().take(ows) {
+                                let iw = (ow0 + w as i64) * step_width - pw + j * dw;
+                                if iw < 0 || iw >= img_width {
+                                    continue;
+                                }
+                                let x = E::Vector::<S>::splat(
+                                    simd,
+                                    *inp.offset((ih * ish + iw * isw + c) as isize),
+                                );
+                                acc[0] = x.mul_add(k0, acc[0]);
+                                acc[1] = x.mul_add(k1, acc[1]);
+                            }
+                        }
+                    }
+                }
+                for (w, acc) in acc.iter().enumerate().take(ows) {
+                    let out = out.offset(((ow0 + w as i64) * out_channels + oc) as isize);
+                    store_partial::<E, S>(acc[0], out, len0);
+                    store_partial::<E, S>(acc[1], out.add(len0), len1);
+                }
+            }
+        }
+    }
+}
+
 fn reorder_kernel<T: CommonBounds>(
     kernel: &Pointer<T>,
     reordered: Pointer<T>,
@@ -5,6 +5,7 @@ use crate::ops::cpu::kernels::conv_group::conv2d_full_oc_kernel_dispatch;
 use crate::ops::cpu::kernels::conv_group::remain_oc_kernel_dispatch;
 use crate::ops::cpu::kernels::conv_group::Params;
 use crate::ops::cpu::kernels::conv_group::PartialParams;
+use crate::ops::cpu::utils::dispatch::par_for_each_dispatch;
 use crate::tensor_base::_Tensor;
 use crate::Cpu;
 use crate::REGNUM;
@@ -205,7 +206,7 @@ where
 
     let ic_block_size = ic_nvec * T::Vec::SIZE; // in channel block size
     let oc_block_size = oc_nvec * T::Vec::SIZE; // out channel block size, but this is only caculated based on cache line size, we have another block size `jb`
-    (0..outer).into_par_iter().for_each(|idx| {
+    par_for_each_dispatch(0..outer, |idx| {
         let mut out = out.clone();
         let mut kernel = ro_ptr.clone();
         let b = idx / num_oh;
@@ -1,6 +1,7 @@
 use crate::ops::cpu::kernels::conv_transpose::{
     full_oc_kernel_dispatch, remain_ic_kernel_dispatch, Params, PartialParams,
 };
+use crate::ops::cpu::utils::dispatch::par_for_each_dispatch;
 use crate::tensor_base::_Tensor;
 use crate::Cpu;
 use hpt_common::error::base::TensorError;
@@ -10,7 +11,6 @@ use hpt_traits::TensorCreator;
 use hpt_traits::TensorInfo;
 use hpt_types::into_scalar::Cast;
 use hpt_types::vectors::traits::*;
-use rayon::iter::{IntoParallelIterator, ParallelIterator};
 
 #[track_caller]
 pub(crate) fn conv2d_transpose<T: CommonBounds, const DEVICE: usize>(
@@ -284,7 +284,7 @@ pub(crate) fn conv2d_backward_kernel<T: CommonBounds, const DEVICE: usize>(
     let outer = batch * grad_shape[1] * grad_shape[2];
 
     let grad_kernel_ptr = grad_kernel.ptr();
-    (0..outer).into_par_iter().for_each(|idx| {
+    par_for_each_dispatch(0..outer, |idx| {
         let mut grad_kernel_ptr = grad_kernel_ptr.clone();
         let b = idx / (grad_shape[1] * grad_shape[2]);
         let h_out = (idx % (grad_shape[1] * grad_shape[2])) / grad_shape[2];
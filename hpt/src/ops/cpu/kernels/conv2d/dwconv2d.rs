@@ -5,6 +5,7 @@ use crate::ops::cpu::kernels::dwconv::conv2d_full_oc_kernel_dispatch;
 use crate::ops::cpu::kernels::dwconv::remain_oc_kernel_dispatch;
 use crate::ops::cpu::kernels::dwconv::Params;
 use crate::ops::cpu::kernels::dwconv::PartialParams;
+use crate::ops::cpu::utils::dispatch::par_for_each_dispatch;
 use crate::tensor_base::_Tensor;
 use crate::Cpu;
 use crate::REGNUM;
@@ -193,7 +194,7 @@ where
     );
 
     let ic_block_size = ic_nvec * T::Vec::SIZE; // in channel block size
-    (0..outer).into_par_iter().for_each(|idx| {
+    par_for_each_dispatch(0..outer, |idx| {
         let mut out = out.clone();
         let mut kernel;
         let origin = ro_ptr.clone();
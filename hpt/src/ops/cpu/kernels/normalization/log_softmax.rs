@@ -1,12 +1,13 @@
 use std::{cell::RefCell, rc::Rc};
 
 use crate::{
+    context::is_deterministic,
     ops::cpu::{
         kernels::logsoftmax::{
             contiguous_dim_include, logsoftmax_dim_not_include,
             uncontiguous_logsoftmax_dim_include, uncontiguous_logsoftmax_dim_not_include,
         },
-        utils::diff::diff_utils::handle_grad,
+        utils::{diff::diff_utils::handle_grad, reduce::reduce_utils::ordered_fold},
     },
     tensor::{DiffTensor, Tensor},
     tensor_base::_Tensor,
@@ -211,10 +212,18 @@ where
 
             let res_raw = unsafe { std::slice::from_raw_parts_mut(res, a.size() as usize) };
 
-            let sum = raw
-                .par_iter()
-                .fold(|| O::ZERO, |acc, &x| acc._add(x._sub(max)._exp()))
-                .reduce(|| O::ZERO, |a, b| a._add(b));
+            let sum = if is_deterministic() {
+                ordered_fold(
+                    raw,
+                    O::ZERO,
+                    |acc, x| acc._add(x._sub(max)._exp()),
+                    |a, b| a._add(b),
+                )
+            } else {
+                raw.par_iter()
+                    .fold(|| O::ZERO, |acc, &x| acc._add(x._sub(max)._exp()))
+                    .reduce(|| O::ZERO, |a, b| a._add(b))
+            };
             let log_sum = sum._ln();
             res_raw
                 .par_iter_mut()
@@ -342,10 +351,18 @@ where
 
             let res_raw = unsafe { std::slice::from_raw_parts_mut(res, a.size() as usize) };
 
-            let sum = raw
-                .par_iter()
-                .fold(|| O::ZERO, |acc, &x| acc._add(x._sub(max)._exp()))
-                .reduce(|| O::ZERO, |a, b| a._add(b));
+            let sum = if is_deterministic() {
+                ordered_fold(
+                    raw,
+                    O::ZERO,
+                    |acc, x| acc._add(x._sub(max)._exp()),
+                    |a, b| a._add(b),
+                )
+            } else {
+                raw.par_iter()
+                    .fold(|| O::ZERO, |acc, &x| acc._add(x._sub(max)._exp()))
+                    .reduce(|| O::ZERO, |a, b| a._add(b))
+            };
 
             // 3. 计算 log_softmax = (x - max) - log(sum)
             let log_sum = sum._ln();
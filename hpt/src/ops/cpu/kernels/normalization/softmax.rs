@@ -1,12 +1,13 @@
 use std::{cell::RefCell, rc::Rc};
 
 use crate::{
+    context::is_deterministic,
     ops::cpu::{
         kernels::softmax::{
             contiguous_dim_include, softmax_dim_not_include, uncontiguous_softmax_dim_include,
             uncontiguous_softmax_dim_not_include,
         },
-        utils::diff::diff_utils::handle_grad,
+        utils::{diff::diff_utils::handle_grad, reduce::reduce_utils::ordered_fold},
     },
     tensor::{DiffTensor, Tensor},
     tensor_base::_Tensor,
@@ -205,10 +206,14 @@ where
                 .for_each(|(res, &x)| {
                     *res = x._sub(max)._exp();
                 });
-            let sum = res_raw
-                .par_iter()
-                .fold(|| O::ZERO, |acc, &x| acc._add(x))
-                .reduce(|| O::ZERO, |a, b| a._add(b));
+            let sum = if is_deterministic() {
+                ordered_fold(res_raw, O::ZERO, |acc, x| acc._add(x), |a, b| a._add(b))
+            } else {
+                res_raw
+                    .par_iter()
+                    .fold(|| O::ZERO, |acc, &x| acc._add(x))
+                    .reduce(|| O::ZERO, |a, b| a._add(b))
+            };
             res_raw.par_iter_mut().for_each(|x| {
                 *x = x._div(sum);
             });
@@ -336,10 +341,14 @@ where
                 .for_each(|(res, &x)| {
                     *res = x._sub(max)._exp();
                 });
-            let sum = res_raw
-                .par_iter()
-                .fold(|| O::ZERO, |acc, &x| acc._add(x))
-                .reduce(|| O::ZERO, |a, b| a._add(b));
+            let sum = if is_deterministic() {
+                ordered_fold(res_raw, O::ZERO, |acc, x| acc._add(x), |a, b| a._add(b))
+            } else {
+                res_raw
+                    .par_iter()
+                    .fold(|| O::ZERO, |acc, &x| acc._add(x))
+                    .reduce(|| O::ZERO, |a, b| a._add(b))
+            };
             res_raw.par_iter_mut().for_each(|x| {
                 *x = x._div(sum);
             });
@@ -15,6 +15,7 @@ pub(crate) fn pooling_template<T: CommonBounds, O: CommonBounds, const DEVICE: u
     steps: [i64; 2],
     padding: [(i64, i64); 2],
     dilation: [i64; 2],
+    init: T,
     scalar_op: impl Fn(T, T) -> T + Send + Sync,
     vec_op: impl Fn(T::Vec, T::Vec) -> T::Vec + Send + Sync,
     post_scalar_op: impl Fn(T) -> O + Send + Sync,
@@ -75,7 +76,7 @@ pub(crate) fn pooling_template<T: CommonBounds, O: CommonBounds, const DEVICE: u
         let w = idx % out_width;
 
         for ii in (0..in_channels - in_channel_remain).step_by(IC_BLOCK_SIZE * T::Vec::SIZE) {
-            let mut res_vecs = [T::Vec::splat(T::ZERO); IC_BLOCK_SIZE];
+            let mut res_vecs = [T::Vec::splat(init); IC_BLOCK_SIZE];
             for kh in 0..kernel_height {
                 if h * step_height + kh * dh < ph_start
                     || h * step_height + kh * dh - ph_start >= img_height
@@ -126,7 +127,7 @@ pub(crate) fn pooling_template<T: CommonBounds, O: CommonBounds, const DEVICE: u
 
         let remain = in_channel_remain % (T::Vec::SIZE as i64);
         for ii in (in_channels - in_channel_remain..in_channels - remain).step_by(T::Vec::SIZE) {
-            let mut res_vecs = T::Vec::splat(T::ZERO);
+            let mut res_vecs = T::Vec::splat(init);
             for kh in 0..kernel_height {
                 if h * step_height + kh * dh < ph_start
                     || h * step_height + kh * dh - ph_start >= img_height
@@ -168,7 +169,7 @@ pub(crate) fn pooling_template<T: CommonBounds, O: CommonBounds, const DEVICE: u
         }
 
         for ii in in_channels - remain..in_channels {
-            let mut res = T::ZERO;
+            let mut res = init;
             for kh in 0..kernel_height {
                 if h * step_height + kh * dh < ph_start
                     || h * step_height + kh * dh - ph_start >= img_height
@@ -206,6 +207,7 @@ pub(crate) fn pooling_template<T: CommonBounds, O: CommonBounds, const DEVICE: u
 pub(crate) fn adaptive_pooling_template<T: CommonBounds, O: CommonBounds, const DEVICE: usize>(
     img: &_Tensor<T, Cpu, DEVICE>,
     output_size: [i64; 2],
+    init: T,
     scalar_op: impl Fn(T, T) -> T + Send + Sync,
     vec_op: impl Fn(T::Vec, T::Vec) -> T::Vec + Send + Sync,
     post_scalar_op: impl Fn(T, O) -> O + Send + Sync,
@@ -268,7 +270,7 @@ where
         let kernel_size_vec = O::Vec::splat(kernel_size);
         let same_vec_size = T::Vec::SIZE == O::Vec::SIZE;
         for ii in (0..in_channels - in_channel_remain).step_by(IC_BLOCK_SIZE * T::Vec::SIZE) {
-            let mut res_vecs = [T::Vec::splat(T::ZERO); IC_BLOCK_SIZE];
+            let mut res_vecs = [T::Vec::splat(init); IC_BLOCK_SIZE];
             for kh in start_h..end_h {
                 for kw in start_w..end_w {
                     let mut inp_vecs = [T::Vec::splat(T::ZERO); IC_BLOCK_SIZE];
@@ -306,7 +308,7 @@ where
 
         let remain = in_channel_remain % (T::Vec::SIZE as i64);
         for ii in (in_channels - in_channel_remain..in_channels - remain).step_by(T::Vec::SIZE) {
-            let mut res_vecs = T::Vec::splat(T::ZERO);
+            let mut res_vecs = T::Vec::splat(init);
             for kh in start_h..end_h {
                 for kw in start_w..end_w {
                     let i = ii;
@@ -336,7 +338,7 @@ where
         }
 
         for ii in in_channels - remain..in_channels {
-            let mut res = T::ZERO;
+            let mut res = init;
             for kh in start_h..end_h {
                 for kw in start_w..end_w {
                     let i = ii;
@@ -1,6 +1,9 @@
 use hpt_common::utils::pointer::Pointer;
 use hpt_traits::CommonBounds;
 
+use crate::ops::cpu::utils::dispatch::{load_partial, store_partial};
+use hpt_types::dispatch::{Simd, SimdElement, SimdVec};
+
 use hpt_macros::{gen_fast_reduce_simd_helper, gen_reduce_dim_not_include_simd_helper};
 use hpt_types::dtype::TypeCommon;
 use hpt_types::utils::array_vec_reduce;
@@ -195,7 +198,7 @@ pub(crate) fn fast_reduce_simd<T, O, F, F2, F3, F4>(
         vec_op_post,
         [1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16]
     );
-    #[cfg(any(target_feature = "avx512f", target_arch = "aarch64"))]
+    #[cfg(target_arch = "aarch64")]
     gen_kernel!(
         num_largest_vecs,
         REGNUM as isize,
@@ -442,7 +445,7 @@ pub(crate) fn reduce_dim_not_include_simd<T, O, F, F2, F3, F4>(
                 vec_post,
                 [1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16]
             );
-            #[cfg(any(target_feature = "avx512f", target_arch = "aarch64"))]
+            #[cfg(target_arch = "aarch64")]
             gen_kernel2!(
                 num_largest_vecs,
                 32,
@@ -749,3 +752,294 @@ pub(crate) fn uncontiguous_reduce_dim_not_include<T, O, F, F2>(
         });
     }
 }
+
+/// The reductions with kernels on the vectors of every simd level
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum ReduceOp {
+    Sum,
+    Prod,
+    Max,
+    Min,
+}
+
+impl ReduceOp {
+    /// combine two partial results
+    #[inline(always)]
+    pub(crate) fn combine<E: CommonBounds>(self, a: E, b: E) -> E {
+        match self {
+            ReduceOp::Sum => a._add(b),
+            ReduceOp::Prod => a._mul(b),
+            ReduceOp::Max => a._max(b),
+            ReduceOp::Min => a._min(b),
+        }
+    }
+}
+
+/// run `$body` with `$vec` the vector op and `$lanes` the reduction of the lanes of a [`ReduceOp`] for
+/// the vectors of the level `$s`, every op gets its own instantiation of `$body`
+macro_rules! with_reduce_op {
+    ($op:expr, $e:ty, $s:ty, |$vec:ident, $lanes:ident| $body:expr) => {
+        match $op {
+            ReduceOp::Sum => {
+                let $vec = <<$e as SimdElement>::Vector<$s> as SimdVec<$e>>::add;
+                let $lanes = <<$e as SimdElement>::Vector<$s> as SimdVec<$e>>::reduce_add;
+                $body
+            }
+            ReduceOp::Prod => {
+                let $vec = <<$e as SimdElement>::Vector<$s> as SimdVec<$e>>::mul;
+                let $lanes = <<$e as SimdElement>::Vector<$s> as SimdVec<$e>>::reduce_mul;
+                $body
+            }
+            ReduceOp::Max => {
+                let $vec = <<$e as SimdElement>::Vector<$s> as SimdVec<$e>>::max;
+                let $lanes = <<$e as SimdElement>::Vector<$s> as SimdVec<$e>>::reduce_max;
+                $body
+            }
+            ReduceOp::Min => {
+                let $vec = <<$e as SimdElement>::Vector<$s> as SimdVec<$e>>::min;
+                let $lanes = <<$e as SimdElement>::Vector<$s> as SimdVec<$e>>::reduce_min;
+                $body
+            }
+        }
+    };
+}
+
+/// the number of accumulators of the kernels reducing a row into one value
+const ROW_ACCS: usize = 4;
+
+/// fold the `len` contiguous elements at `ptr` into `accs`
+#[inline(always)]
+unsafe fn fold_row<E, S, F>(
+    accs: &mut [E::Vector<S>; ROW_ACCS],
+    ptr: *const E,
+    len: usize,
+    fill: E,
+    f: &F,
+) where
+    E: SimdElement,
+    S: Simd,
+    F: Fn(E::Vector<S>, E::Vector<S>) -> E::Vector<S>,
+{
+    let lanes = E::Vector::<S>::LANES;
+    let mut i = 0;
+    while i + ROW_ACCS * lanes <= len {
+        for (j, acc) in accs.iter_mut().enumerate() {
+            *acc = f(*acc, E::Vector::<S>::load(ptr.add(i + j * lanes)));
+        }
+        i += ROW_ACCS * lanes;
+    }
+    while i + lanes <= len {
+        accs[0] = f(accs[0], E::Vector::<S>::load(ptr.add(i)));
+        i += lanes;
+    }
+    if i < len {
+        accs[0] = f(accs[0], load_partial::<E, S>(ptr.add(i), len - i, fill));
+    }
+}
+
+/// reduce the `len` contiguous elements at `ptr` to one value, `init` must be the identity of `op`
+#[inline(always)]
+pub(crate) unsafe fn reduce_row_level<E, S>(
+    simd: S,
+    op: ReduceOp,
+    ptr: *const E,
+    len: usize,
+    init: E,
+) -> E
+where
+    E: SimdElement,
+    S: Simd,
+{
+    with_reduce_op!(op, E, S, |f, lanes| {
+        let mut accs = [E::Vector::<S>::splat(simd, init); ROW_ACCS];
+        fold_row::<E, S, _>(&mut accs, ptr, len, init, &f);
+        lanes(accs.into_iter().reduce(f).unwrap())
+    })
+}
+
+/// `contiguous_reduce_dim_include_simd` on the vectors of the simd level
+#[inline(always)]
+pub(crate) fn contiguous_reduce_dim_include_level<E, S>(
+    simd: S,
+    op: ReduceOp,
+    init: E,
+    inner_loop_size: isize,
+    outer_loop_size: isize,
+    intermediate_size: isize,
+    mut inp_ptr: Pointer<E>,
+    mut res_ptr: Pointer<E>,
+    inp_strides: &[i64],
+    inp_shape: &[i64],
+    prg1: &mut [i64],
+    shape_len: i64,
+) where
+    E: SimdElement + CommonBounds,
+    S: Simd,
+{
+    with_reduce_op!(op, E, S, |f, lanes| {
+        for _ in 0..outer_loop_size {
+            let mut accs = [E::Vector::<S>::splat(simd, init); ROW_ACCS];
+            for _ in 0..intermediate_size {
+                unsafe {
+                    fold_row::<E, S, _>(&mut accs, inp_ptr.ptr, inner_loop_size as usize, init, &f)
+                };
+                update_prg3(prg1, shape_len, &mut inp_ptr, inp_strides, inp_shape);
+            }
+            let val = lanes(accs.into_iter().reduce(f).unwrap());
+            res_ptr[0isize] = op.combine(res_ptr[0isize], val);
+            res_ptr += 1usize;
+        }
+    })
+}
+
+/// accumulate the `N` vectors from column `col` of the `outer_loop_size` rows the strides step
+/// through into the result, `partial` is the length of a last vector that isn't full
+#[inline(always)]
+unsafe fn reduce_columns<const N: usize, E, S, F>(
+    f: &F,
+    col: isize,
+    partial: Option<usize>,
+    outer_loop_size: isize,
+    mut inp_ptr: Pointer<E>,
+    res_ptr: *mut E,
+    mut update: impl FnMut(&mut Pointer<E>),
+) where
+    E: SimdElement,
+    S: Simd,
+    F: Fn(E::Vector<S>, E::Vector<S>) -> E::Vector<S>,
+{
+    let lanes = E::Vector::<S>::LANES as isize;
+    let res = res_ptr.offset(col);
+    let load = |ptr: *const E, j: usize| match partial {
+        Some(len) => load_partial::<E, S>(ptr, len, *ptr),
+        None => E::Vector::<S>::load(ptr.offset(j as isize * lanes)),
+    };
+    let mut accs: [E::Vector<S>; N] = std::array::from_fn(|j| load(res, j));
+    for _ in 0..outer_loop_size {
+        let inp = inp_ptr.ptr.offset(col);
+        for (j, acc) in accs.iter_mut().enumerate() {
+            *acc = f(*acc, load(inp, j));
+        }
+        update(&mut inp_ptr);
+    }
+    for (j, acc) in accs.into_iter().enumerate() {
+        match partial {
+            Some(len) => store_partial::<E, S>(acc, res, len),
+            None => acc.store(res.offset(j as isize * lanes)),
+        }
+    }
+}
+
+/// accumulate the `inner_loop_size` contiguous columns of the rows the strides step through into the
+/// result, the columns are blocked by the registers of the level
+#[inline(always)]
+unsafe fn reduce_all_columns<E, S, F>(
+    f: &F,
+    inner_loop_size: isize,
+    outer_loop_size: isize,
+    inp_ptr: &Pointer<E>,
+    res_ptr: *mut E,
+    update: impl Fn(&mut Pointer<E>, &mut [i64]),
+    ndim: usize,
+) where
+    E: SimdElement,
+    S: Simd,
+    F: Fn(E::Vector<S>, E::Vector<S>) -> E::Vector<S>,
+{
+    let lanes = E::Vector::<S>::LANES as isize;
+    let block = (S::REGNUM / 2) as isize * lanes;
+    let mut prg = vec![0; ndim];
+    let mut col = 0;
+    macro_rules! columns {
+        ($n:expr, $partial:expr) => {{
+            prg.iter_mut().for_each(|x| *x = 0);
+            reduce_columns::<$n, E, S, F>(
+                f,
+                col,
+                $partial,
+                outer_loop_size,
+                inp_ptr.clone(),
+                res_ptr,
+                |ptr| update(ptr, &mut prg),
+            );
+        }};
+    }
+    while col + block <= inner_loop_size {
+        if S::REGNUM >= 32 {
+            columns!(16, None);
+        } else {
+            columns!(8, None);
+        }
+        col += block;
+    }
+    while col + lanes <= inner_loop_size {
+        columns!(1, None);
+        col += lanes;
+    }
+    if col < inner_loop_size {
+        columns!(1, Some((inner_loop_size - col) as usize));
+    }
+}
+
+/// `fast_reduce_simd` on the vectors of the simd level
+#[inline(always)]
+pub(crate) fn fast_reduce_level<E, S>(
+    op: ReduceOp,
+    inner_loop_size: isize,
+    outer_loop_size: isize,
+    inp_ptr: Pointer<E>,
+    res_ptr: Pointer<E>,
+    inp_strides: &[i64],
+    inp_shape: &[i64],
+) where
+    E: SimdElement,
+    S: Simd,
+{
+    with_reduce_op!(op, E, S, |f, _lanes| unsafe {
+        reduce_all_columns::<E, S, _>(
+            &f,
+            inner_loop_size,
+            outer_loop_size,
+            &inp_ptr,
+            res_ptr.ptr,
+            |ptr, prg| update_prg(prg, ptr, inp_strides, inp_shape),
+            inp_strides.len(),
+        )
+    })
+}
+
+/// `reduce_dim_not_include_simd` on the vectors of the simd level
+#[inline(always)]
+pub(crate) fn reduce_dim_not_include_level<E, S>(
+    op: ReduceOp,
+    inner_loop_size: isize,
+    outer_loop_size: isize,
+    intermediate_size: isize,
+    mut inp_ptr: Pointer<E>,
+    mut res_ptr: Pointer<E>,
+    inp_strides: &[i64],
+    inp_shape: &[i64],
+    prg2: &mut [i64],
+    shape_len: i64,
+) where
+    E: SimdElement,
+    S: Simd,
+{
+    with_reduce_op!(op, E, S, |f, _lanes| {
+        for _ in 0..outer_loop_size {
+            unsafe {
+                reduce_all_columns::<E, S, _>(
+                    &f,
+                    inner_loop_size,
+                    intermediate_size,
+                    &inp_ptr,
+                    res_ptr.ptr,
+                    |ptr, prg| update_prg2(prg, shape_len, ptr, inp_strides, inp_shape),
+                    inp_strides.len(),
+                )
+            };
+            update_prg3(prg2, shape_len, &mut inp_ptr, inp_strides, inp_shape);
+            res_ptr += inner_loop_size as usize;
+        }
+    })
+}
@@ -0,0 +1,684 @@
+use std::panic::Location;
+
+use hpt_common::{
+    error::{base::TensorError, param::ParamError, shape::ShapeError},
+    shape::shape::Shape,
+};
+use hpt_traits::{CommonBounds, ShapeManipulate, TensorCreator, TensorInfo, TensorLike};
+use hpt_types::{dtype::TypeCommon, into_scalar::Cast, traits::VecTrait, type_promote::NormalOut};
+use rayon::prelude::*;
+
+use crate::Tensor;
+
+/// the f32 vector the activation of the quantized kernels is applied on
+type F32Vec = <f32 as TypeCommon>::Vec;
+const F32_LANES: usize = <F32Vec as VecTrait<f32>>::SIZE;
+/// the i32 vector the products of the quantized kernels are accumulated in
+type I32Vec = <i32 as TypeCommon>::Vec;
+const I32_LANES: usize = <I32Vec as VecTrait<i32>>::SIZE;
+
+/// the integer types a [`QTensor`] can store
+pub trait QuantType: CommonBounds {
+    /// the smallest value of the type
+    const QMIN: i32;
+    /// the largest value of the type
+    const QMAX: i32;
+    /// widen the value to i32
+    fn to_i32(self) -> i32;
+    /// narrow `x` to the type, values out of range are clamped to `[QMIN, QMAX]`
+    fn saturate(x: i32) -> Self;
+}
+
+macro_rules! impl_quant_type {
+    ($($t:ty),*) => {
+        $(
+            impl QuantType for $t {
+                const QMIN: i32 = <$t>::MIN as i32;
+                const QMAX: i32 = <$t>::MAX as i32;
+                #[inline(always)]
+                fn to_i32(self) -> i32 {
+                    self as i32
+                }
+                #[inline(always)]
+                fn saturate(x: i32) -> Self {
+                    x.clamp(Self::QMIN, Self::QMAX) as $t
+                }
+            }
+        )*
+    };
+}
+
+impl_quant_type!(i8, u8);
+
+#[track_caller]
+fn invalid(message: String) -> TensorError {
+    ParamError::InvalidQuantParams {
+        message,
+        location: Location::caller(),
+    }
+    .into()
+}
+
+/// The scales and zero points of an affine quantization, a quantized value `q` represents `(q - zero_point) * scale`.
+///
+/// A per tensor quantization has a single scale and zero point, a per channel quantization has one for every index
+/// of `axis`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct QuantParams {
+    scales: Vec<f32>,
+    zero_points: Vec<i32>,
+    axis: Option<usize>,
+}
+
+impl QuantParams {
+    /// parameters shared by every element of the tensor
+    pub fn per_tensor(scale: f32, zero_point: i32) -> Self {
+        QuantParams {
+            scales: vec![scale],
+            zero_points: vec![zero_point],
+            axis: None,
+        }
+    }
+
+    /// parameters of every index of `axis`
+    pub fn per_channel(scales: Vec<f32>, zero_points: Vec<i32>, axis: usize) -> Self {
+        QuantParams {
+            scales,
+            zero_points,
+            axis: Some(axis),
+        }
+    }
+
+    /// the scales, a single one for per tensor parameters
+    pub fn scales(&self) -> &[f32] {
+        &self.scales
+    }
+
+    /// the zero points, a single one for per tensor parameters
+    pub fn zero_points(&self) -> &[i32] {
+        &self.zero_points
+    }
+
+    /// the channel axis, `None` for per tensor parameters
+    pub fn axis(&self) -> Option<usize> {
+        self.axis
+    }
+
+    /// the scale of channel `idx`
+    #[inline(always)]
+    fn scale(&self, idx: usize) -> f32 {
+        self.scales[if self.axis.is_some() { idx } else { 0 }]
+    }
+
+    /// the zero point of channel `idx`
+    #[inline(always)]
+    fn zero_point(&self, idx: usize) -> i32 {
+        self.zero_points[if self.axis.is_some() { idx } else { 0 }]
+    }
+
+    /// check the parameters can quantize a tensor of `shape` to `T`
+    #[track_caller]
+    fn check<T: QuantType>(&self, shape: &[i64]) -> Result<(), TensorError> {
+        if self.scales.len() != self.zero_points.len() {
+            return Err(invalid(format!(
+                "{} scales but {} zero points",
+                self.scales.len(),
+                self.zero_points.len()
+            )));
+        }
+        let channels = match self.axis {
+            None => 1,
+            Some(axis) if axis < shape.len() => shape[axis] as usize,
+            Some(axis) => {
+                return Err(invalid(format!(
+                    "axis {} is out of range for a tensor of rank {}",
+                    axis,
+                    shape.len()
+                )))
+            }
+        };
+        if self.scales.len() != channels {
+            return Err(invalid(format!(
+                "{} channels but {} scales",
+                channels,
+                self.scales.len()
+            )));
+        }
+        if let Some(scale) = self.scales.iter().find(|s| !(s.is_finite() && **s > 0.0)) {
+            return Err(invalid(format!("scale {} is not positive", scale)));
+        }
+        if let Some(zp) = self
+            .zero_points
+            .iter()
+            .find(|zp| !(T::QMIN..=T::QMAX).contains(*zp))
+        {
+            return Err(invalid(format!(
+                "zero point {} is out of the range of {}",
+                zp,
+                T::STR
+            )));
+        }
+        Ok(())
+    }
+
+    /// check the parameters are per tensor or per channel along `axis`
+    #[track_caller]
+    fn check_axis(&self, op: &str, axis: Option<usize>) -> Result<(), TensorError> {
+        match self.axis {
+            None => Ok(()),
+            Some(a) if Some(a) == axis => Ok(()),
+            Some(a) => Err(invalid(format!(
+                "{} doesn't support per channel parameters along axis {}",
+                op, a
+            ))),
+        }
+    }
+
+    /// the number of contiguous elements sharing the same parameters in a tensor of `shape`
+    fn inner(&self, shape: &[i64]) -> usize {
+        match self.axis {
+            None => shape.iter().product::<i64>() as usize,
+            Some(axis) => shape[axis + 1..].iter().product::<i64>() as usize,
+        }
+    }
+}
+
+/// An affine quantized tensor, the integers of type `T` with the [`QuantParams`] mapping them to real values.
+///
+/// The quantized kernels accumulate in i32 and requantize the accumulators to the output parameters.
+#[derive(Clone)]
+pub struct QTensor<T: QuantType> {
+    data: Tensor<T>,
+    params: QuantParams,
+}
+
+impl<T: QuantType> QTensor<T> {
+    /// attach `params` to already quantized `data`
+    #[track_caller]
+    pub fn new(data: Tensor<T>, params: QuantParams) -> Result<Self, TensorError> {
+        params.check::<T>(data.shape())?;
+        Ok(QTensor { data, params })
+    }
+
+    /// the quantized integers
+    pub fn int_repr(&self) -> &Tensor<T> {
+        &self.data
+    }
+
+    /// the quantization parameters
+    pub fn params(&self) -> &QuantParams {
+        &self.params
+    }
+
+    /// the shape of the tensor
+    pub fn shape(&self) -> &Shape {
+        self.data.shape()
+    }
+
+    /// the real values, `(q - zero_point) * scale`
+    pub fn dequantize(&self) -> Result<Tensor<f32>, TensorError> {
+        let data = self.data.contiguous()?;
+        let mut out = Tensor::<f32>::empty(data.shape())?;
+        let inner = self.params.inner(data.shape());
+        if out.size() == 0 || inner == 0 {
+            return Ok(out);
+        }
+        let channels = self.params.scales.len();
+        out.as_raw_mut()
+            .par_chunks_mut(inner)
+            .zip(data.as_raw().par_chunks(inner))
+            .enumerate()
+            .for_each(|(i, (out, data))| {
+                let (scale, zp) = (
+                    self.params.scale(i % channels),
+                    self.params.zero_point(i % channels),
+                );
+                for (o, &q) in out.iter_mut().zip(data.iter()) {
+                    *o = (q.to_i32() - zp) as f32 * scale;
+                }
+            });
+        Ok(out)
+    }
+
+    /// Quantized matrix multiplication of `self` with shape `[..., m, k]` and `rhs` with shape `[k, n]`.
+    ///
+    /// `self` must be quantized per tensor, `rhs` per tensor or per channel along axis 1. The products are accumulated
+    /// in i32, scaled to real values, passed through `activation` (e.g. a relu) and requantized with `out_scale` and
+    /// `out_zero_point`.
+    #[track_caller]
+    pub fn matmul<W: QuantType, O: QuantType>(
+        &self,
+        rhs: &QTensor<W>,
+        out_scale: f32,
+        out_zero_point: i32,
+        activation: Option<fn(F32Vec) -> F32Vec>,
+    ) -> Result<QTensor<O>, TensorError> {
+        let out_params = QuantParams::per_tensor(out_scale, out_zero_point);
+        out_params.check::<O>(&[])?;
+        self.params.check_axis("matmul", None)?;
+        rhs.params.check_axis("matmul", Some(1))?;
+        ShapeError::check_dim(2, rhs.shape().len())?;
+        ShapeError::check_matmul(self.shape(), rhs.shape())?;
+        let (k, n) = (rhs.shape()[0] as usize, rhs.shape()[1] as usize);
+
+        let lhs = self.data.contiguous()?;
+        // [k, n] -> [n, k], every output is the dot product of two contiguous rows
+        let rhs_t = rhs.data.t()?.contiguous()?;
+        let mut weights = vec![0i32; n * k];
+        if k > 0 {
+            weights
+                .par_chunks_mut(k)
+                .zip(rhs_t.as_raw().par_chunks(k))
+                .enumerate()
+                .for_each(|(j, (w, q))| {
+                    let zp = rhs.params.zero_point(j);
+                    for (w, &q) in w.iter_mut().zip(q.iter()) {
+                        *w = q.to_i32() - zp;
+                    }
+                });
+        }
+        let (lhs_scale, lhs_zp) = (self.params.scale(0), self.params.zero_point(0));
+        let scales = (0..n)
+            .map(|j| lhs_scale * rhs.params.scale(j))
+            .collect::<Vec<_>>();
+
+        let mut out_shape = self.shape().to_vec();
+        *out_shape.last_mut().unwrap() = n as i64;
+        let mut out = Tensor::<O>::empty(out_shape)?;
+        if out.size() > 0 {
+            // the zero point shifted row of `self` and the accumulators are reused by all of the rows of a thread
+            out.as_raw_mut()
+                .par_chunks_mut(n)
+                .enumerate()
+                .for_each_init(
+                    || (vec![0i32; k], vec![0i32; n]),
+                    |(a, acc), (row, out)| {
+                        let q = &lhs.as_raw()[row * k..(row + 1) * k];
+                        for (a, q) in a.iter_mut().zip(q.iter()) {
+                            *a = q.to_i32() - lhs_zp;
+                        }
+                        for (j, acc) in acc.iter_mut().enumerate() {
+                            *acc = dot(a, &weights[j * k..(j + 1) * k]);
+                        }
+                        requantize(acc, &scales, out_scale, out_zero_point, activation, out);
+                    },
+                );
+        }
+        Ok(QTensor {
+            data: out,
+            params: out_params,
+        })
+    }
+
+    /// Quantized 2D convolution of a `[batch, height, width, in_channels]` input with
+    /// `[kernel_height, kernel_width, in_channels, out_channels]` kernels.
+    ///
+    /// `self` must be quantized per tensor, the kernels per tensor or per output channel (axis 3). `bias` holds one i32
+    /// per output channel with the scale `input_scale * kernel_scale` and a zero point of 0. `steps`, `padding` and
+    /// `dilation` are in `[height, width]` order. The accumulators are requantized like [`QTensor::matmul`].
+    #[track_caller]
+    pub fn conv2d<W: QuantType, O: QuantType>(
+        &self,
+        kernels: &QTensor<W>,
+        bias: Option<&Tensor<i32>>,
+        steps: [i64; 2],
+        padding: [(i64, i64); 2],
+        dilation: [i64; 2],
+        out_scale: f32,
+        out_zero_point: i32,
+        activation: Option<fn(F32Vec) -> F32Vec>,
+    ) -> Result<QTensor<O>, TensorError> {
+        let out_params = QuantParams::per_tensor(out_scale, out_zero_point);
+        out_params.check::<O>(&[])?;
+        self.params.check_axis("conv2d", None)?;
+        kernels.params.check_axis("conv2d", Some(3))?;
+        ShapeError::check_dim(4, self.shape().len())?;
+        ShapeError::check_dim(4, kernels.shape().len())?;
+        let [batch, img_height, img_width, img_channels] = [0, 1, 2, 3].map(|i| self.shape()[i]);
+        let [kh, kw, in_channels, out_channels] = [0, 1, 2, 3].map(|i| kernels.shape()[i]);
+        if in_channels != img_channels {
+            return Err(ShapeError::ConvError {
+                message: format!(
+                    "kernel in_channel {} not match input in_channel {}",
+                    in_channels, img_channels
+                ),
+                location: Location::caller(),
+            }
+            .into());
+        }
+        if let Some(bias) = bias {
+            ShapeError::check_dim(1, bias.ndim())?;
+            ShapeError::check_size_match(out_channels, bias.size() as i64)?;
+        }
+        for (name, values) in [("steps", steps), ("dilation", dilation)] {
+            if values.iter().any(|&v| v <= 0) {
+                return Err(ShapeError::ConvError {
+                    message: format!("{} must be positive, got {:?}", name, values),
+                    location: Location::caller(),
+                }
+                .into());
+            }
+        }
+        let (step_height, step_width) = (steps[0], steps[1]);
+        let ((ph_start, ph_end), (pw_start, pw_end)) = (padding[0], padding[1]);
+        let (dh, dw) = (dilation[0], dilation[1]);
+        let out_height = (img_height + ph_start + ph_end - dh * (kh - 1) - 1) / step_height + 1;
+        let out_width = (img_width + pw_start + pw_end - dw * (kw - 1) - 1) / step_width + 1;
+        if out_height <= 0 || out_width <= 0 {
+            return Err(ShapeError::ConvError {
+                message: if out_height <= 0 {
+                    "output height <= 0".to_string()
+                } else {
+                    "output width <= 0".to_string()
+                },
+                location: Location::caller(),
+            }
+            .into());
+        }
+
+        let inp = self.data.contiguous()?;
+        let oc = out_channels as usize;
+        let weights = kernels
+            .data
+            .contiguous()?
+            .as_raw()
+            .iter()
+            .enumerate()
+            .map(|(i, q)| q.to_i32() - kernels.params.zero_point(i % oc))
+            .collect::<Vec<_>>();
+        let bias = match bias {
+            Some(bias) => bias.contiguous()?.as_raw().to_vec(),
+            None => vec![0; oc],
+        };
+        let (in_scale, in_zp) = (self.params.scale(0), self.params.zero_point(0));
+        let scales = (0..oc)
+            .map(|o| in_scale * kernels.params.scale(o))
+            .collect::<Vec<_>>();
+
+        let mut out = Tensor::<O>::empty([batch, out_height, out_width, out_channels])?;
+        if out.size() == 0 {
+            return Ok(QTensor {
+                data: out,
+                params: out_params,
+            });
+        }
+        let inp = inp.as_raw();
+        let ic = in_channels as usize;
+        out.as_raw_mut()
+            .par_chunks_mut(out_width as usize * oc)
+            .enumerate()
+            .for_each(|(idx, row)| {
+                let (b, y) = (idx as i64 / out_height, idx as i64 % out_height);
+                let mut acc = vec![0i32; oc];
+                for (x, out) in row.chunks_exact_mut(oc).enumerate() {
+                    acc.copy_from_slice(&bias);
+                    for i in 0..kh {
+                        let iy = y * step_height - ph_start + i * dh;
+                        if iy < 0 || iy >= img_height {
+                            continue;
+                        }
+                        for j in 0..kw {
+                            let ix = x as i64 * step_width - pw_start + j * dw;
+                            if ix < 0 || ix >= img_width {
+                                continue;
+                            }
+                            let pixel = (((b * img_height + iy) * img_width + ix) as usize) * ic;
+                            let kernel = ((i * kw + j) as usize) * ic * oc;
+                            for c in 0..ic {
+                                let v = inp[pixel + c].to_i32() - in_zp;
+                                if v == 0 {
+                                    continue;
+                                }
+                                let w = &weights[kernel + c * oc..kernel + (c + 1) * oc];
+                                for (acc, &w) in acc.iter_mut().zip(w.iter()) {
+                                    *acc += v * w;
+                                }
+                            }
+                        }
+                    }
+                    requantize(&acc, &scales, out_scale, out_zero_point, activation, out);
+                }
+            });
+        Ok(QTensor {
+            data: out,
+            params: out_params,
+        })
+    }
+}
+
+/// the dot product of two rows of the same length, accumulated in i32 vectors
+#[inline(always)]
+fn dot(a: &[i32], b: &[i32]) -> i32 {
+    let (a, b) = (a.chunks_exact(I32_LANES), b.chunks_exact(I32_LANES));
+    let tail = a
+        .remainder()
+        .iter()
+        .zip(b.remainder().iter())
+        .map(|(&a, &b)| a * b)
+        .sum::<i32>();
+    let mut acc = I32Vec::splat(0);
+    for (a, b) in a.zip(b) {
+        // safety: the chunks have `I32_LANES` elements
+        let (a, b) = unsafe { (I32Vec::from_ptr(a.as_ptr()), I32Vec::from_ptr(b.as_ptr())) };
+        acc = acc._add(a._mul(b));
+    }
+    acc.sum() + tail
+}
+
+/// turn the i32 accumulators of a row into the quantized outputs, `scales[j]` is the real value of one unit of
+/// `acc[j]`, the activation is applied on the real values
+#[inline(always)]
+fn requantize<O: QuantType>(
+    acc: &[i32],
+    scales: &[f32],
+    out_scale: f32,
+    out_zero_point: i32,
+    activation: Option<fn(F32Vec) -> F32Vec>,
+    out: &mut [O],
+) {
+    for ((acc, scales), out) in acc
+        .chunks(F32_LANES)
+        .zip(scales.chunks(F32_LANES))
+        .zip(out.chunks_mut(F32_LANES))
+    {
+        let mut lanes = [0.0f32; F32_LANES];
+        for (lane, (&acc, &scale)) in lanes.iter_mut().zip(acc.iter().zip(scales.iter())) {
+            *lane = acc as f32 * scale;
+        }
+        if let Some(activation) = activation {
+            let mut vec = F32Vec::splat(0.0);
+            vec.copy_from_slice(&lanes);
+            let vec = activation(vec);
+            for (i, lane) in lanes.iter_mut().enumerate() {
+                *lane = vec.extract(i);
+            }
+        }
+        for (o, &x) in out.iter_mut().zip(lanes.iter()) {
+            *o = O::saturate(
+                ((x / out_scale).round_ties_even() as i32).saturating_add(out_zero_point),
+            );
+        }
+    }
+}
+
+impl Tensor<f32> {
+    /// Quantize the tensor with `params`, `q = saturate(round(x / scale) + zero_point)`, ties round to even.
+    #[track_caller]
+    pub fn quantize<T: QuantType>(&self, params: QuantParams) -> Result<QTensor<T>, TensorError> {
+        params.check::<T>(self.shape())?;
+        let data = self.contiguous()?;
+        let mut out = Tensor::<T>::empty(data.shape())?;
+        let inner = params.inner(data.shape());
+        if out.size() > 0 && inner > 0 {
+            let channels = params.scales.len();
+            out.as_raw_mut()
+                .par_chunks_mut(inner)
+                .zip(data.as_raw().par_chunks(inner))
+                .enumerate()
+                .for_each(|(i, (out, data))| {
+                    let (scale, zp) = (params.scale(i % channels), params.zero_point(i % channels));
+                    for (o, &x) in out.iter_mut().zip(data.iter()) {
+                        *o = T::saturate(((x / scale).round_ties_even() as i32).saturating_add(zp));
+                    }
+                });
+        }
+        Ok(QTensor { data: out, params })
+    }
+
+    /// Quantize the tensor with a single `scale` and `zero_point`.
+    ///
+    /// # Example
+    /// ```rust
+    /// use hpt::{Tensor, TensorLike};
+    /// let a = Tensor::<f32>::new(&[-1.0, 0.0, 0.375, 1.0]);
+    /// let q = a.quantize_per_tensor::<i8>(0.25, 0).unwrap();
+    /// assert_eq!(q.int_repr().as_raw(), &[-4, 0, 2, 4]);
+    /// ```
+    #[track_caller]
+    pub fn quantize_per_tensor<T: QuantType>(
+        &self,
+        scale: f32,
+        zero_point: i32,
+    ) -> Result<QTensor<T>, TensorError> {
+        self.quantize(QuantParams::per_tensor(scale, zero_point))
+    }
+
+    /// Quantize the tensor with one scale and zero point for every index of `axis`, a negative axis counts from the
+    /// last dimension.
+    #[track_caller]
+    pub fn quantize_per_channel<T: QuantType>(
+        &self,
+        scales: &[f32],
+        zero_points: &[i32],
+        axis: i64,
+    ) -> Result<QTensor<T>, TensorError> {
+        let ndim = self.ndim() as i64;
+        let axis = if axis < 0 { axis + ndim } else { axis };
+        if axis < 0 || axis >= ndim {
+            return Err(invalid(format!(
+                "axis {} is out of range for a tensor of rank {}",
+                axis, ndim
+            )));
+        }
+        self.quantize(QuantParams::per_channel(
+            scales.to_vec(),
+            zero_points.to_vec(),
+            axis as usize,
+        ))
+    }
+}
+
+/// Weights of shape `[k, n]` stored as packed signed 4-bit integers, two per byte.
+///
+/// Every column is split into groups of `group_size` consecutive rows sharing one f32 scale, a stored value `q` in
+/// `[-8, 7]` represents `q * scale`. The columns are stored one after another so a group is `group_size / 2`
+/// contiguous bytes, the low nibble holds the even row.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PackedI4 {
+    pub(crate) packed: Vec<u8>,
+    pub(crate) scales: Vec<f32>,
+    pub(crate) k: usize,
+    pub(crate) n: usize,
+    pub(crate) group_size: usize,
+}
+
+impl PackedI4 {
+    /// the shape `[k, n]` of the weights
+    pub fn shape(&self) -> [i64; 2] {
+        [self.k as i64, self.n as i64]
+    }
+
+    /// the number of rows sharing a scale
+    pub fn group_size(&self) -> usize {
+        self.group_size
+    }
+
+    /// the scales, `k / group_size` for every column
+    pub fn scales(&self) -> &[f32] {
+        &self.scales
+    }
+
+    /// the packed bytes, `k / 2` for every column
+    pub fn packed(&self) -> &[u8] {
+        &self.packed
+    }
+
+    /// the signed value of the low or the high nibble of `byte`, the nibbles are stored with an offset of 8
+    #[inline(always)]
+    pub(crate) fn nibble(byte: u8, high: bool) -> i32 {
+        (if high { byte >> 4 } else { byte & 0xf }) as i32 - 8
+    }
+
+    /// the real values of the weights
+    pub fn dequantize(&self) -> Result<Tensor<f32>, TensorError> {
+        let (k, n, groups) = (self.k, self.n, self.k / self.group_size);
+        let mut out = Tensor::<f32>::empty([k as i64, n as i64])?;
+        if out.size() == 0 {
+            return Ok(out);
+        }
+        out.as_raw_mut()
+            .par_chunks_mut(n)
+            .enumerate()
+            .for_each(|(row, out)| {
+                for (j, o) in out.iter_mut().enumerate() {
+                    let byte = self.packed[(j * k + row) / 2];
+                    let scale = self.scales[j * groups + row / self.group_size];
+                    *o = PackedI4::nibble(byte, row % 2 == 1) as f32 * scale;
+                }
+            });
+        Ok(out)
+    }
+}
+
+impl<T> Tensor<T>
+where
+    T: CommonBounds + Cast<f32>,
+{
+    /// Quantize `[k, n]` weights to packed signed 4-bit integers for [`Tensor::matmul_q4`].
+    ///
+    /// The rows of every column are split into groups of `group_size`, a group is quantized symmetrically with the
+    /// scale `max(|w|) / 7`. `group_size` must be even and divide `k`.
+    #[track_caller]
+    pub fn quantize_int4(&self, group_size: usize) -> Result<PackedI4, TensorError> {
+        ShapeError::check_dim(2, self.ndim())?;
+        let (k, n) = (self.shape()[0] as usize, self.shape()[1] as usize);
+        if group_size == 0 || group_size % 2 != 0 || k % group_size != 0 {
+            return Err(invalid(format!(
+                "group size {} must be even and divide k = {}",
+                group_size, k
+            )));
+        }
+        let groups = k / group_size;
+        // [k, n] -> [n, k], the groups of a column are contiguous
+        let cols = self.t()?.contiguous()?;
+        let mut packed = vec![0u8; n * k / 2];
+        let mut scales = vec![0f32; n * groups];
+        if k > 0 {
+            packed
+                .par_chunks_mut(group_size / 2)
+                .zip(scales.par_iter_mut())
+                .zip(cols.as_raw().par_chunks(group_size))
+                .for_each(|((packed, scale), w)| {
+                    let max = w
+                        .iter()
+                        .fold(0f32, |acc, x| acc.max(Cast::<f32>::cast(*x).abs()));
+                    *scale = max / 7.0;
+                    let inv = if max > 0.0 { 7.0 / max } else { 0.0 };
+                    let q = |x: T| {
+                        (((Cast::<f32>::cast(x) * inv).round_ties_even() as i32).clamp(-8, 7) + 8)
+                            as u8
+                    };
+                    for (byte, pair) in packed.iter_mut().zip(w.chunks_exact(2)) {
+                        *byte = q(pair[0]) | (q(pair[1]) << 4);
+                    }
+                });
+        }
+        Ok(PackedI4 {
+            packed,
+            scales,
+            k,
+            n,
+            group_size,
+        })
+    }
+}
@@ -45,9 +45,10 @@ where
     type Output = out_type<<T as NormalOut<U>>::Output, Cpu, DEVICE>;
     #[track_caller]
     fn add(self, rhs: rhs_type<U, Cpu, DEVICE>) -> Self::Output {
-        return binary_fn_with_out_simd(
+        return binary_op_with_out_simd(
             &self,
             &rhs,
+            BinaryOp::Add,
             |x, y| x._add(y),
             |x, y| x._add(y),
             None::<Self::Output>,
@@ -253,9 +254,10 @@ where
     type Output = out_type<<T as NormalOut<U>>::Output, Cpu, DEVICE>;
     #[track_caller]
     fn sub(self, rhs: rhs_type<U, Cpu, DEVICE>) -> Self::Output {
-        return binary_fn_with_out_simd(
+        return binary_op_with_out_simd(
             &self,
             &rhs,
+            BinaryOp::Sub,
             |x, y| x._sub(y),
             |x, y| x._sub(y),
             None::<Self::Output>,
@@ -462,9 +464,10 @@ where
     type Output = out_type<<T as FloatOutBinary<U>>::Output, Cpu, DEVICE>;
     #[track_caller]
     fn div(self, rhs: rhs_type<U, Cpu, DEVICE>) -> Self::Output {
-        return binary_fn_with_out_simd(
+        return binary_op_with_out_simd(
             &self,
             &rhs,
+            BinaryOp::Div,
             |x, y| x._div(y),
             |x, y| x._div(y),
             None::<Self::Output>,
@@ -699,9 +702,10 @@ where
     type Output = out_type<<T as NormalOut<U>>::Output, Cpu, DEVICE>;
     #[track_caller]
     fn mul(self, rhs: rhs_type<U, Cpu, DEVICE>) -> Self::Output {
-        return binary_fn_with_out_simd(
+        return binary_op_with_out_simd(
             &self,
             &rhs,
+            BinaryOp::Mul,
             |x, y| x._mul(y),
             |x, y| x._mul(y),
             None::<Self::Output>,
@@ -7,7 +7,7 @@ use crate::tensor_base::_Tensor;
 use crate::{Cpu, Tensor};
 use hpt_common::error::base::TensorError;
 use hpt_common::slice::Slice;
-use hpt_traits::ops::advance::{AdvancedOps, HardMax, Shrinkage};
+use hpt_traits::ops::advance::{AdvancedOps, HardMax, PadMode, Shrinkage};
 use hpt_traits::{CommonBounds, TensorCreator, TensorInfo, TensorWhere};
 use hpt_types::dtype::TypeCommon;
 use hpt_types::into_scalar::Cast;
@@ -29,6 +29,14 @@ where
         Ok(self.inner.pad(pads, val)?.into())
     }
 
+    fn pad_with_mode(
+        &self,
+        pads: &[(i64, i64)],
+        mode: PadMode<Self::Meta>,
+    ) -> Result<Self::Output, TensorError> {
+        Ok(self.inner.pad_with_mode(pads, mode)?.into())
+    }
+
     fn topk(
         &self,
         k: i64,
@@ -113,6 +121,30 @@ where
         })
     }
 
+    fn pad_with_mode(
+        &self,
+        pads: &[(i64, i64)],
+        mode: PadMode<Self::Meta>,
+    ) -> Result<Self::Output, TensorError> {
+        if let PadMode::Constant(val) = mode {
+            return self.pad(pads, val);
+        }
+        let padded = self.inner.pad_with_mode(pads, mode)?;
+        let pads = pads.to_vec();
+        let mut lhs = self.clone();
+        Ok(DiffTensor {
+            inner: padded,
+            grad: Rc::new(RefCell::new(None)),
+            out_degree: Rc::new(RefCell::new(0)),
+            backward: Rc::new(RefCell::new(move |grad: Tensor<T, Cpu, DEVICE>| {
+                let shape = lhs.inner.shape().clone();
+                let grad = grad.inner.pad_grad(&shape, &pads, mode)?;
+                handle_grad(&mut lhs, grad.into(), &[])?;
+                Ok(false)
+            })),
+        })
+    }
+
     fn topk(
         &self,
         k: i64,
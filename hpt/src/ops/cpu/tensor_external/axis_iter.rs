@@ -0,0 +1,318 @@
+use std::{marker::PhantomData, sync::Arc};
+
+use crate::{
+    ops::cpu::tensor_internal::axis_iter::AxisGeometry, tensor::Tensor, tensor_base::_Tensor, Cpu,
+};
+use hpt_common::error::base::TensorError;
+use hpt_traits::CommonBounds;
+use rayon::iter::{IndexedParallelIterator, IntoParallelIterator, ParallelIterator};
+
+/// the views left to yield, shared by [`AxisIter`] and [`AxisIterMut`]
+struct Views<T, const DEVICE: usize> {
+    base: Arc<_Tensor<T, Cpu, DEVICE>>,
+    geometry: AxisGeometry,
+    front: usize,
+    back: usize,
+}
+
+impl<T: CommonBounds, const DEVICE: usize> Views<T, DEVICE> {
+    fn new(base: &Tensor<T, Cpu, DEVICE>, geometry: AxisGeometry) -> Self {
+        let back = geometry.len();
+        Self {
+            base: base.inner.clone(),
+            geometry,
+            front: 0,
+            back,
+        }
+    }
+
+    fn next(&mut self) -> Option<Tensor<T, Cpu, DEVICE>> {
+        if self.front == self.back {
+            return None;
+        }
+        self.front += 1;
+        Some(self.base.axis_view(&self.geometry, self.front - 1).into())
+    }
+
+    fn next_back(&mut self) -> Option<Tensor<T, Cpu, DEVICE>> {
+        if self.front == self.back {
+            return None;
+        }
+        self.back -= 1;
+        Some(self.base.axis_view(&self.geometry, self.back).into())
+    }
+
+    fn par(self) -> impl IndexedParallelIterator<Item = Tensor<T, Cpu, DEVICE>> {
+        let Views {
+            base,
+            geometry,
+            front,
+            back,
+        } = self;
+        (front..back)
+            .into_par_iter()
+            .map(move |idx| base.axis_view(&geometry, idx).into())
+    }
+}
+
+macro_rules! impl_views_iterator {
+    ($name:ident) => {
+        impl<'a, T: CommonBounds, const DEVICE: usize> Iterator for $name<'a, T, DEVICE> {
+            type Item = Tensor<T, Cpu, DEVICE>;
+
+            fn next(&mut self) -> Option<Self::Item> {
+                self.views.next()
+            }
+
+            fn size_hint(&self) -> (usize, Option<usize>) {
+                let len = self.views.back - self.views.front;
+                (len, Some(len))
+            }
+        }
+
+        impl<'a, T: CommonBounds, const DEVICE: usize> DoubleEndedIterator
+            for $name<'a, T, DEVICE>
+        {
+            fn next_back(&mut self) -> Option<Self::Item> {
+                self.views.next_back()
+            }
+        }
+
+        impl<'a, T: CommonBounds, const DEVICE: usize> ExactSizeIterator for $name<'a, T, DEVICE> {}
+    };
+}
+
+/// iterator over sub-tensor views of a tensor, created by [`Tensor::outer_iter`],
+/// [`Tensor::axis_iter`], [`Tensor::lanes`] and [`Tensor::axis_chunks`]
+///
+/// every view shares the buffer of the iterated tensor, no data is copied.
+pub struct AxisIter<'a, T, const DEVICE: usize = 0> {
+    views: Views<T, DEVICE>,
+    _marker: PhantomData<&'a Tensor<T, Cpu, DEVICE>>,
+}
+
+/// iterator over mutable sub-tensor views of a tensor, created by [`Tensor::outer_iter_mut`],
+/// [`Tensor::axis_iter_mut`], [`Tensor::lanes_mut`] and [`Tensor::axis_chunks_mut`]
+///
+/// the views don't overlap, writing to one of them writes to the iterated tensor.
+pub struct AxisIterMut<'a, T, const DEVICE: usize = 0> {
+    views: Views<T, DEVICE>,
+    _marker: PhantomData<&'a mut Tensor<T, Cpu, DEVICE>>,
+}
+
+impl_views_iterator!(AxisIter);
+impl_views_iterator!(AxisIterMut);
+
+impl<T, const DEVICE: usize> Tensor<T, Cpu, DEVICE>
+where
+    T: CommonBounds,
+{
+    fn axis_views<'a>(&'a self, geometry: AxisGeometry) -> AxisIter<'a, T, DEVICE> {
+        AxisIter {
+            views: Views::new(self, geometry),
+            _marker: PhantomData,
+        }
+    }
+
+    fn axis_views_mut<'a>(&'a mut self, geometry: AxisGeometry) -> AxisIterMut<'a, T, DEVICE> {
+        AxisIterMut {
+            views: Views::new(self, geometry),
+            _marker: PhantomData,
+        }
+    }
+
+    /// iterate over the sub-tensors along the first axis, same as `axis_iter(0)`
+    ///
+    /// # Example
+    /// ```rust
+    /// use hpt::{Tensor, TensorCreator, TensorInfo, ShapeManipulate};
+    /// let a = Tensor::<f32>::arange(0, 6)?.reshape(&[3, 2])?;
+    /// for row in a.outer_iter()? {
+    ///     assert_eq!(row.shape().as_slice(), &[2]);
+    /// }
+    /// # Ok::<(), hpt::TensorError>(())
+    /// ```
+    #[track_caller]
+    pub fn outer_iter(&self) -> Result<AxisIter<'_, T, DEVICE>, TensorError> {
+        self.axis_iter(0)
+    }
+
+    /// iterate over the sub-tensors at each index of `axis`, the views have `axis` removed
+    #[track_caller]
+    pub fn axis_iter(&self, axis: i64) -> Result<AxisIter<'_, T, DEVICE>, TensorError> {
+        let geometry =
+            AxisGeometry::axis_iter(self.inner.layout.shape(), self.inner.layout.strides(), axis)?;
+        Ok(self.axis_views(geometry))
+    }
+
+    /// iterate over the 1-D views along `axis`, one for every index of the other axes
+    ///
+    /// # Example
+    /// ```rust
+    /// use hpt::{Tensor, TensorCreator, TensorInfo, ShapeManipulate};
+    /// let a = Tensor::<f32>::arange(0, 6)?.reshape(&[2, 3])?;
+    /// // the columns of `a`
+    /// let columns = a.lanes(0)?.collect::<Vec<_>>();
+    /// assert_eq!(columns.len(), 3);
+    /// assert_eq!(columns[1].shape().as_slice(), &[2]);
+    /// # Ok::<(), hpt::TensorError>(())
+    /// ```
+    #[track_caller]
+    pub fn lanes(&self, axis: i64) -> Result<AxisIter<'_, T, DEVICE>, TensorError> {
+        let geometry =
+            AxisGeometry::lanes(self.inner.layout.shape(), self.inner.layout.strides(), axis)?;
+        Ok(self.axis_views(geometry))
+    }
+
+    /// iterate over chunks of `size` consecutive indices of `axis`, the last chunk is shorter
+    /// when `size` doesn't divide the axis
+    #[track_caller]
+    pub fn axis_chunks(
+        &self,
+        axis: i64,
+        size: i64,
+    ) -> Result<AxisIter<'_, T, DEVICE>, TensorError> {
+        let geometry = AxisGeometry::axis_chunks(
+            self.inner.layout.shape(),
+            self.inner.layout.strides(),
+            axis,
+            size,
+        )?;
+        Ok(self.axis_views(geometry))
+    }
+
+    /// mutable version of [`outer_iter`](Tensor::outer_iter)
+    #[track_caller]
+    pub fn outer_iter_mut(&mut self) -> Result<AxisIterMut<'_, T, DEVICE>, TensorError> {
+        self.axis_iter_mut(0)
+    }
+
+    /// mutable version of [`axis_iter`](Tensor::axis_iter)
+    #[track_caller]
+    pub fn axis_iter_mut(&mut self, axis: i64) -> Result<AxisIterMut<'_, T, DEVICE>, TensorError> {
+        let geometry =
+            AxisGeometry::axis_iter(self.inner.layout.shape(), self.inner.layout.strides(), axis)?;
+        Ok(self.axis_views_mut(geometry))
+    }
+
+    /// mutable version of [`lanes`](Tensor::lanes)
+    ///
+    /// # Example
+    /// ```rust
+    /// use hpt::{ShapeManipulate, StridedIterator, Tensor, TensorCreator, TensorIterator};
+    /// let mut a = Tensor::<f32>::ones(&[2, 3])?;
+    /// // scale the column `j` by `j`
+    /// for (j, mut column) in a.lanes_mut(0)?.enumerate() {
+    ///     column.iter_mut().for_each(|x| *x *= j as f32);
+    /// }
+    /// # Ok::<(), hpt::TensorError>(())
+    /// ```
+    #[track_caller]
+    pub fn lanes_mut(&mut self, axis: i64) -> Result<AxisIterMut<'_, T, DEVICE>, TensorError> {
+        let geometry =
+            AxisGeometry::lanes(self.inner.layout.shape(), self.inner.layout.strides(), axis)?;
+        Ok(self.axis_views_mut(geometry))
+    }
+
+    /// mutable version of [`axis_chunks`](Tensor::axis_chunks)
+    #[track_caller]
+    pub fn axis_chunks_mut(
+        &mut self,
+        axis: i64,
+        size: i64,
+    ) -> Result<AxisIterMut<'_, T, DEVICE>, TensorError> {
+        let geometry = AxisGeometry::axis_chunks(
+            self.inner.layout.shape(),
+            self.inner.layout.strides(),
+            axis,
+            size,
+        )?;
+        Ok(self.axis_views_mut(geometry))
+    }
+
+    /// parallel version of [`outer_iter`](Tensor::outer_iter)
+    #[track_caller]
+    pub fn par_outer_iter(
+        &self,
+    ) -> Result<impl IndexedParallelIterator<Item = Tensor<T, Cpu, DEVICE>> + '_, TensorError> {
+        Ok(self.outer_iter()?.views.par())
+    }
+
+    /// parallel version of [`axis_iter`](Tensor::axis_iter)
+    #[track_caller]
+    pub fn par_axis_iter(
+        &self,
+        axis: i64,
+    ) -> Result<impl IndexedParallelIterator<Item = Tensor<T, Cpu, DEVICE>> + '_, TensorError> {
+        Ok(self.axis_iter(axis)?.views.par())
+    }
+
+    /// parallel version of [`lanes`](Tensor::lanes)
+    #[track_caller]
+    pub fn par_lanes(
+        &self,
+        axis: i64,
+    ) -> Result<impl IndexedParallelIterator<Item = Tensor<T, Cpu, DEVICE>> + '_, TensorError> {
+        Ok(self.lanes(axis)?.views.par())
+    }
+
+    /// parallel version of [`axis_chunks`](Tensor::axis_chunks)
+    #[track_caller]
+    pub fn par_axis_chunks(
+        &self,
+        axis: i64,
+        size: i64,
+    ) -> Result<impl IndexedParallelIterator<Item = Tensor<T, Cpu, DEVICE>> + '_, TensorError> {
+        Ok(self.axis_chunks(axis, size)?.views.par())
+    }
+
+    /// parallel version of [`outer_iter_mut`](Tensor::outer_iter_mut)
+    #[track_caller]
+    pub fn par_outer_iter_mut(
+        &mut self,
+    ) -> Result<impl IndexedParallelIterator<Item = Tensor<T, Cpu, DEVICE>> + '_, TensorError> {
+        Ok(self.outer_iter_mut()?.views.par())
+    }
+
+    /// parallel version of [`axis_iter_mut`](Tensor::axis_iter_mut)
+    #[track_caller]
+    pub fn par_axis_iter_mut(
+        &mut self,
+        axis: i64,
+    ) -> Result<impl IndexedParallelIterator<Item = Tensor<T, Cpu, DEVICE>> + '_, TensorError> {
+        Ok(self.axis_iter_mut(axis)?.views.par())
+    }
+
+    /// parallel version of [`lanes_mut`](Tensor::lanes_mut)
+    ///
+    /// # Example
+    /// ```rust
+    /// use hpt::{ShapeManipulate, Tensor, TensorCreator, TensorIterator};
+    /// use hpt::{StridedIterator, TensorLike};
+    /// use rayon::iter::ParallelIterator;
+    /// let mut a = Tensor::<f32>::arange(1, 7)?.reshape(&[2, 3])?;
+    /// // divide every row by its first element, the rows are processed in parallel
+    /// a.par_lanes_mut(1)?.for_each(|mut row| {
+    ///     let first = row.as_raw()[0];
+    ///     row.iter_mut().for_each(|x| *x /= first);
+    /// });
+    /// # Ok::<(), hpt::TensorError>(())
+    /// ```
+    #[track_caller]
+    pub fn par_lanes_mut(
+        &mut self,
+        axis: i64,
+    ) -> Result<impl IndexedParallelIterator<Item = Tensor<T, Cpu, DEVICE>> + '_, TensorError> {
+        Ok(self.lanes_mut(axis)?.views.par())
+    }
+
+    /// parallel version of [`axis_chunks_mut`](Tensor::axis_chunks_mut)
+    #[track_caller]
+    pub fn par_axis_chunks_mut(
+        &mut self,
+        axis: i64,
+        size: i64,
+    ) -> Result<impl IndexedParallelIterator<Item = Tensor<T, Cpu, DEVICE>> + '_, TensorError> {
+        Ok(self.axis_chunks_mut(axis, size)?.views.par())
+    }
+}
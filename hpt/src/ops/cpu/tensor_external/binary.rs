@@ -1,4 +1,6 @@
-use crate::ops::cpu::utils::binary::binary_normal::binary_fn_with_out_simd;
+use crate::ops::cpu::utils::binary::binary_normal::{
+    binary_fn_with_out_simd, binary_op_with_out_simd, BinaryOp,
+};
 use crate::Cpu;
 use crate::{tensor::Tensor, tensor_base::_Tensor};
 use hpt_common::error::base::TensorError;
@@ -34,21 +36,42 @@ macro_rules! impl_bin_ops {
             where
                 U: BorrowMut<Self::InplaceOutput>
         {
-            binary_fn_with_out_simd(self, &rhs, |a, b| a._add(b), |a, b| a._add(b), Some(out))
+            binary_op_with_out_simd(
+                self,
+                &rhs,
+                BinaryOp::Add,
+                |a, b| a._add(b),
+                |a, b| a._add(b),
+                Some(out),
+            )
         }
         #[track_caller]
         fn sub_<U>(&self, rhs: $($rhs)*, out: U) -> std::result::Result<Self::Output, TensorError>
             where
                 U: BorrowMut<Self::InplaceOutput>
         {
-            binary_fn_with_out_simd(self, &rhs, |a, b| a._sub(b), |a, b| a._sub(b), Some(out))
+            binary_op_with_out_simd(
+                self,
+                &rhs,
+                BinaryOp::Sub,
+                |a, b| a._sub(b),
+                |a, b| a._sub(b),
+                Some(out),
+            )
         }
         #[track_caller]
         fn mul_<U>(&self, rhs: $($rhs)*, out: U) -> std::result::Result<Self::Output, TensorError>
             where
                 U: BorrowMut<Self::InplaceOutput>
         {
-            binary_fn_with_out_simd(self, &rhs, |a, b| a._mul(b), |a, b| a._mul(b), Some(out))
+            binary_op_with_out_simd(
+                self,
+                &rhs,
+                BinaryOp::Mul,
+                |a, b| a._mul(b),
+                |a, b| a._mul(b),
+                Some(out),
+            )
         }
         #[track_caller]
         fn rem_<U>(&self, rhs: $($rhs)*, out: U) -> std::result::Result<Self::Output, TensorError>
@@ -5,7 +5,7 @@ use std::rc::Rc;
 use crate::ops::cpu::utils::diff::diff_utils::handle_grad;
 use crate::tensor::{DiffTensor, Tensor};
 use crate::tensor_base::_Tensor;
-use crate::BoolVector;
+use crate::{get_accumulation, BoolVector};
 use crate::{ops::cpu::tensor_internal::float_out_unary::FloatBinaryType, Cpu};
 use hpt_common::axis::axis::Axis;
 use hpt_common::error::autograd::AutogradError;
@@ -13,8 +13,8 @@ use hpt_common::error::base::TensorError;
 use hpt_iterator::iterator_traits::ParStridedIteratorZip;
 use hpt_iterator::TensorIterator;
 use hpt_traits::{
-    CommonBounds, EvalReduce, FloatReduce, NormalEvalReduce, NormalReduce, ShapeManipulate,
-    TensorCmp, TensorInfo,
+    Accumulation, CommonBounds, EvalReduce, FloatReduce, NormalEvalReduce, NormalReduce,
+    ShapeManipulate, TensorCmp, TensorInfo,
 };
 use hpt_types::into_vec::IntoVec;
 use hpt_types::type_promote::{Cmp, NormalOutUnary, SimdCmp};
@@ -37,6 +37,15 @@ impl<T: CommonBounds, const DEVICE: usize> NormalReduce<T> for Tensor<T, Cpu, DE
         Ok(self.inner.sum(axes, keep_dims)?.into())
     }
 
+    fn sum_with<S: Into<Axis>>(
+        &self,
+        axes: S,
+        keep_dims: bool,
+        accumulation: Accumulation,
+    ) -> std::result::Result<Self::Output, TensorError> {
+        Ok(self.inner.sum_with(axes, keep_dims, accumulation)?.into())
+    }
+
     fn sum_<S: Into<Axis>, O>(
         &self,
         axes: S,
@@ -232,6 +241,16 @@ where
         Ok(self.inner.mean(axis, keep_dims)?.into())
     }
 
+    #[track_caller]
+    fn mean_with<S: Into<Axis>>(
+        &self,
+        axis: S,
+        keep_dims: bool,
+        accumulation: Accumulation,
+    ) -> Result<Tensor<FloatBinaryType<T>, Cpu, DEVICE>, TensorError> {
+        Ok(self.inner.mean_with(axis, keep_dims, accumulation)?.into())
+    }
+
     #[allow(unused)]
     #[track_caller]
     fn reducel2<S: Into<Axis>>(
@@ -246,6 +265,19 @@ where
         Ok(self.inner.reducel2(axis, keep_dims)?.into())
     }
 
+    #[track_caller]
+    fn reducel2_with<S: Into<Axis>>(
+        &self,
+        axis: S,
+        keep_dims: bool,
+        accumulation: Accumulation,
+    ) -> Result<Tensor<FloatBinaryType<T>, Cpu, DEVICE>, TensorError> {
+        Ok(self
+            .inner
+            .reducel2_with(axis, keep_dims, accumulation)?
+            .into())
+    }
+
     #[allow(unused)]
     #[track_caller]
     fn reducel3<S: Into<Axis>>(
@@ -276,6 +308,19 @@ where
     {
         Ok(self.inner.logsumexp(axis, keep_dims)?.into())
     }
+
+    #[track_caller]
+    fn logsumexp_with<S: Into<Axis>>(
+        &self,
+        axis: S,
+        keep_dims: bool,
+        accumulation: Accumulation,
+    ) -> Result<Tensor<FloatBinaryType<T>, Cpu, DEVICE>, TensorError> {
+        Ok(self
+            .inner
+            .logsumexp_with(axis, keep_dims, accumulation)?
+            .into())
+    }
 }
 
 impl<T, const DEVICE: usize> NormalReduce<T> for DiffTensor<T, Cpu, DEVICE>
@@ -294,9 +339,18 @@ where
         &self,
         axes: S,
         keep_dims: bool,
+    ) -> std::result::Result<Self::Output, TensorError> {
+        self.sum_with(axes, keep_dims, get_accumulation())
+    }
+
+    fn sum_with<S: Into<Axis>>(
+        &self,
+        axes: S,
+        keep_dims: bool,
+        accumulation: Accumulation,
     ) -> std::result::Result<Self::Output, TensorError> {
         let axes: Axis = axes.into();
-        let ret = self.inner.sum(axes.clone(), keep_dims)?;
+        let ret = self.inner.sum_with(axes.clone(), keep_dims, accumulation)?;
         let original_shape = self.inner.shape().clone();
         let mut lhs = self.clone();
         Ok(DiffTensor {
@@ -732,9 +786,21 @@ where
         &self,
         axes: S,
         keep_dims: bool,
+    ) -> Result<DiffTensor<FloatBinaryType<T>, Cpu, DEVICE>, TensorError> {
+        self.mean_with(axes, keep_dims, get_accumulation())
+    }
+
+    #[track_caller]
+    fn mean_with<S: Into<Axis>>(
+        &self,
+        axes: S,
+        keep_dims: bool,
+        accumulation: Accumulation,
     ) -> Result<DiffTensor<FloatBinaryType<T>, Cpu, DEVICE>, TensorError> {
         let axes: Axis = axes.into();
-        let ret = self.inner.mean(axes.clone(), keep_dims)?;
+        let ret = self
+            .inner
+            .mean_with(axes.clone(), keep_dims, accumulation)?;
         let original_shape = self.inner.shape().clone();
         let mut lhs = self.clone();
         let numel: <T as FloatOutBinary>::Output = axes
@@ -773,9 +839,21 @@ where
         &self,
         axes: S,
         keep_dims: bool,
+    ) -> Result<DiffTensor<FloatBinaryType<T>, Cpu, DEVICE>, TensorError> {
+        self.reducel2_with(axes, keep_dims, get_accumulation())
+    }
+
+    #[track_caller]
+    fn reducel2_with<S: Into<Axis>>(
+        &self,
+        axes: S,
+        keep_dims: bool,
+        accumulation: Accumulation,
     ) -> Result<DiffTensor<FloatBinaryType<T>, Cpu, DEVICE>, TensorError> {
         let axes: Axis = axes.into();
-        let ret = self.inner.reducel2(axes.clone(), keep_dims)?;
+        let ret = self
+            .inner
+            .reducel2_with(axes.clone(), keep_dims, accumulation)?;
         let original_shape = self.inner.shape().clone();
         let mut lhs = self.clone();
 
@@ -852,9 +930,20 @@ where
     #[allow(unused)]
     #[track_caller]
     fn logsumexp<S: Into<Axis>>(
+        &self,
+        axes: S,
+        keep_dims: bool,
+    ) -> Result<DiffTensor<FloatBinaryType<T>, Cpu, DEVICE>, TensorError> {
+        self.logsumexp_with(axes, keep_dims, get_accumulation())
+    }
+
+    #[allow(unused)]
+    #[track_caller]
+    fn logsumexp_with<S: Into<Axis>>(
         &self,
         _: S,
         _: bool,
+        _: Accumulation,
     ) -> Result<DiffTensor<FloatBinaryType<T>, Cpu, DEVICE>, TensorError> {
         todo!()
     }
@@ -0,0 +1,124 @@
+use std::borrow::BorrowMut;
+
+use crate::ops::cpu::utils::binary::binary_normal::binary_fn_with_out_simd;
+use crate::ops::cpu::utils::unary::unary::unary_fn_with_out;
+use crate::tensor_base::_Tensor;
+use crate::{tensor::Tensor, Cpu};
+use hpt_common::error::base::TensorError;
+use hpt_traits::{CommonBounds, CustomBinary, CustomUnary};
+
+impl<T, const DEVICE: usize> Tensor<T, Cpu, DEVICE>
+where
+    T: CommonBounds,
+{
+    /// apply the user defined unary operator `Op` to every element
+    ///
+    /// # Example
+    /// ```rust
+    /// use hpt::{unary_op, Tensor, TensorLike};
+    /// unary_op! {
+    ///     Cube: f32 => f32,
+    ///     scalar: |x| x * x * x,
+    ///     simd: |v| v * v * v,
+    /// }
+    /// let a = Tensor::<f32>::new(&[1.0, 2.0, 3.0]);
+    /// let b = a.unary_op::<Cube>()?;
+    /// assert_eq!(b.as_raw(), &[1.0, 8.0, 27.0]);
+    /// # Ok::<(), hpt::TensorError>(())
+    /// ```
+    #[track_caller]
+    pub fn unary_op<Op>(&self) -> Result<Tensor<Op::Output, Cpu, DEVICE>, TensorError>
+    where
+        Op: CustomUnary<T> + 'static,
+        Op::Output: CommonBounds,
+    {
+        Ok(unary_fn_with_out(
+            self.inner.as_ref(),
+            Op::simd,
+            Op::scalar,
+            None::<_Tensor<Op::Output, Cpu, DEVICE>>,
+        )?
+        .into())
+    }
+
+    /// apply the user defined unary operator `Op` to every element and write the result to `out`
+    #[track_caller]
+    pub fn unary_op_<Op, U>(
+        &self,
+        mut out: U,
+    ) -> Result<Tensor<Op::Output, Cpu, DEVICE>, TensorError>
+    where
+        Op: CustomUnary<T> + 'static,
+        Op::Output: CommonBounds,
+        U: BorrowMut<Tensor<Op::Output, Cpu, DEVICE>>,
+    {
+        Ok(unary_fn_with_out(
+            self.inner.as_ref(),
+            Op::simd,
+            Op::scalar,
+            Some(out.borrow_mut().inner.as_ref().clone()),
+        )?
+        .into())
+    }
+
+    /// apply the user defined binary operator `Op` to the broadcasted elements of `self` and `rhs`
+    ///
+    /// # Example
+    /// ```rust
+    /// use hpt::{binary_op, Tensor, TensorInfo, TensorLike};
+    /// binary_op! {
+    ///     SquaredDiff: f32, f32 => f32,
+    ///     scalar: |a, b| (a - b) * (a - b),
+    ///     simd: |a, b| (a - b) * (a - b),
+    /// }
+    /// let a = Tensor::<f32>::new(&[[1.0], [2.0]]);
+    /// let b = Tensor::<f32>::new(&[0.0, 1.0, 3.0]);
+    /// let c = a.binary_op::<SquaredDiff, _>(&b)?;
+    /// assert_eq!(c.shape().as_slice(), &[2, 3]);
+    /// assert_eq!(c.as_raw(), &[1.0, 0.0, 4.0, 4.0, 1.0, 1.0]);
+    /// # Ok::<(), hpt::TensorError>(())
+    /// ```
+    #[track_caller]
+    pub fn binary_op<Op, B>(
+        &self,
+        rhs: &Tensor<B, Cpu, DEVICE>,
+    ) -> Result<Tensor<Op::Output, Cpu, DEVICE>, TensorError>
+    where
+        B: CommonBounds,
+        Op: CustomBinary<T, B> + 'static,
+        Op::Output: CommonBounds,
+    {
+        Ok(binary_fn_with_out_simd(
+            self.inner.as_ref(),
+            rhs.inner.as_ref(),
+            Op::scalar,
+            Op::simd,
+            None::<_Tensor<Op::Output, Cpu, DEVICE>>,
+        )?
+        .into())
+    }
+
+    /// apply the user defined binary operator `Op` to the broadcasted elements of `self` and `rhs`
+    /// and write the result to `out`
+    #[track_caller]
+    pub fn binary_op_<Op, B, U>(
+        &self,
+        rhs: &Tensor<B, Cpu, DEVICE>,
+        mut out: U,
+    ) -> Result<Tensor<Op::Output, Cpu, DEVICE>, TensorError>
+    where
+        B: CommonBounds,
+        Op: CustomBinary<T, B> + 'static,
+        Op::Output: CommonBounds,
+        U: BorrowMut<Tensor<Op::Output, Cpu, DEVICE>>,
+    {
+        Ok(binary_fn_with_out_simd(
+            self.inner.as_ref(),
+            rhs.inner.as_ref(),
+            Op::scalar,
+            Op::simd,
+            Some(out.borrow_mut().inner.as_ref().clone()),
+        )?
+        .into())
+    }
+}
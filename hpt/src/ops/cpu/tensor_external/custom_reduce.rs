@@ -0,0 +1,82 @@
+use crate::{tensor::Tensor, Cpu};
+use hpt_common::axis::axis::Axis;
+use hpt_common::error::base::TensorError;
+use hpt_traits::CommonBounds;
+
+impl<T, const DEVICE: usize> Tensor<T, Cpu, DEVICE>
+where
+    T: CommonBounds,
+{
+    /// reduce `axes` with a custom operation, using the same parallel and SIMD engine as `sum`
+    ///
+    /// `combine` folds two scalars, `vec_combine` does the same for two simd vectors, both must
+    /// agree and `identity` must be neutral for them since it is used to start every partial result.
+    ///
+    /// # Example
+    /// ```rust
+    /// use hpt::{NormalOut, ShapeManipulate, Tensor, TensorCreator, TensorLike, TypeCommon};
+    /// let a = Tensor::<f32>::arange(0, 6)?.reshape(&[2, 3])?;
+    /// let max = a.reduce_axes(1, false, f32::NEG_INF, |a, b| a._max(b), |a, b| a._max(b))?;
+    /// assert_eq!(max.as_raw(), &[2.0, 5.0]);
+    /// # Ok::<(), hpt::TensorError>(())
+    /// ```
+    #[track_caller]
+    pub fn reduce_axes<S: Into<Axis>, F, F2>(
+        &self,
+        axes: S,
+        keep_dims: bool,
+        identity: T,
+        combine: F,
+        vec_combine: F2,
+    ) -> Result<Tensor<T, Cpu, DEVICE>, TensorError>
+    where
+        F: Fn(T, T) -> T + Sync + Send + 'static + Copy,
+        F2: Fn(T::Vec, T::Vec) -> T::Vec + Sync + Send + 'static + Copy,
+    {
+        Ok(self
+            .inner
+            .reduce_axes(axes, keep_dims, identity, combine, vec_combine)?
+            .into())
+    }
+
+    /// reduce `axes` into two outputs at once, for example the min and the argmin
+    ///
+    /// `combine` folds an element and its row major position inside the reduced axes into the
+    /// accumulator, `merge` joins two accumulators, the left one covering the lower positions.
+    ///
+    /// # Example
+    /// ```rust
+    /// use hpt::{ShapeManipulate, Tensor, TensorCreator, TensorLike};
+    /// let a = Tensor::<f32>::new(&[3.0, 1.0, 2.0, 0.0, 5.0, 0.0]).reshape(&[2, 3])?;
+    /// let (min, argmin) = a.reduce_axes_multi(
+    ///     1,
+    ///     false,
+    ///     (f32::INFINITY, -1i64),
+    ///     |(m, i), x, idx| if x < m { (x, idx) } else { (m, i) },
+    ///     |l, r| if r.0 < l.0 { r } else { l },
+    /// )?;
+    /// assert_eq!(min.as_raw(), &[1.0, 0.0]);
+    /// assert_eq!(argmin.as_raw(), &[1, 0]);
+    /// # Ok::<(), hpt::TensorError>(())
+    /// ```
+    #[track_caller]
+    pub fn reduce_axes_multi<S: Into<Axis>, A, B, F, F2>(
+        &self,
+        axes: S,
+        keep_dims: bool,
+        identity: (A, B),
+        combine: F,
+        merge: F2,
+    ) -> Result<(Tensor<A, Cpu, DEVICE>, Tensor<B, Cpu, DEVICE>), TensorError>
+    where
+        A: CommonBounds,
+        B: CommonBounds,
+        F: Fn((A, B), T, i64) -> (A, B) + Sync + Send,
+        F2: Fn((A, B), (A, B)) -> (A, B) + Sync + Send,
+    {
+        let (a, b) = self
+            .inner
+            .reduce_axes_multi(axes, keep_dims, identity, combine, merge)?;
+        Ok((a.into(), b.into()))
+    }
+}
@@ -9,10 +9,39 @@ use hpt_traits::{CommonBounds, Matmul, ShapeManipulate};
 use hpt_types::{into_scalar::Cast, type_promote::NormalOut};
 
 use crate::{
-    ops::cpu::{tensor_internal::matmul::matmul_with_out, utils::diff::diff_utils::handle_grad},
+    ops::cpu::{
+        quantize::PackedI4,
+        tensor_internal::matmul::{matmul_q4, matmul_with_out},
+        utils::diff::diff_utils::handle_grad,
+    },
     tensor::{DiffTensor, Tensor},
 };
 
+impl<T> Tensor<T>
+where
+    T: CommonBounds + Cast<f32>,
+    f32: Cast<T>,
+{
+    /// Matrix multiplication with packed 4-bit weights from [`Tensor::quantize_int4`].
+    ///
+    /// `self` has shape `[..., m, k]` and the weights `[k, n]`, the weights are dequantized group by group while
+    /// multiplying and the products are accumulated in f32.
+    ///
+    /// # Example
+    /// ```rust
+    /// use hpt::{Matmul, Random, Tensor, TensorCreator};
+    /// let w = Tensor::<f32>::randn(&[64, 16]).unwrap();
+    /// let q = w.quantize_int4(32).unwrap();
+    /// let x = Tensor::<f32>::randn(&[4, 64]).unwrap();
+    /// let y = x.matmul_q4(&q).unwrap();
+    /// assert!(y.allclose(&x.matmul(&q.dequantize().unwrap()).unwrap()));
+    /// ```
+    #[track_caller]
+    pub fn matmul_q4(&self, rhs: &PackedI4) -> std::result::Result<Tensor<T>, TensorError> {
+        Ok(matmul_q4(self.inner.as_ref(), rhs)?.into())
+    }
+}
+
 impl<A, B> Matmul<Tensor<B>> for Tensor<A>
 where
     A: CommonBounds + NormalOut<B> + Cast<<A as NormalOut<B>>::Output>,
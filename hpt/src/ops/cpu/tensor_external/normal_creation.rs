@@ -51,6 +51,14 @@ impl<T: CommonBounds, const DEVICE: usize> TensorCreator<T> for Tensor<T, Cpu, D
         Ok(_Tensor::full_like(self.inner.as_ref(), val)?.into())
     }
 
+    fn from_fn<S, F>(shape: S, f: F) -> Result<Self::Output, TensorError>
+    where
+        S: Into<Shape>,
+        F: Fn(&[i64]) -> T + Sync + Send,
+    {
+        Ok(_Tensor::<T, Cpu, DEVICE>::from_fn(shape, f)?.into())
+    }
+
     fn arange<U>(start: U, end: U) -> Result<Self::Output, TensorError>
     where
         usize: Cast<T>,
@@ -229,6 +237,20 @@ impl<T: CommonBounds, const DEVICE: usize> TensorCreator<T> for DiffTensor<T, Cp
         })
     }
 
+    fn from_fn<S, F>(shape: S, f: F) -> Result<Self::Output, TensorError>
+    where
+        S: Into<Shape>,
+        F: Fn(&[i64]) -> T + Sync + Send,
+    {
+        let ret = Tensor::from_fn(shape, f)?;
+        Ok(DiffTensor {
+            inner: ret,
+            grad: Rc::new(RefCell::new(None)),
+            out_degree: Rc::new(RefCell::new(0)),
+            backward: Rc::new(RefCell::new(move |_| Ok(true))),
+        })
+    }
+
     fn arange<U>(start: U, end: U) -> Result<Self::Output, TensorError>
     where
         usize: Cast<T>,
@@ -49,4 +49,43 @@ where
     {
         Ok(_Tensor::<T, Cpu, DEVICE>::blackman_window(window_length, periodic)?.into())
     }
+    #[track_caller]
+    fn kaiser_window(
+        window_length: i64,
+        beta: f64,
+        periodic: bool,
+    ) -> Result<Self::Output, TensorError> {
+        Ok(_Tensor::<T, Cpu, DEVICE>::kaiser_window(window_length, beta, periodic)?.into())
+    }
+    #[track_caller]
+    fn bartlett_window(window_length: i64, periodic: bool) -> Result<Self::Output, TensorError> {
+        Ok(_Tensor::<T, Cpu, DEVICE>::bartlett_window(window_length, periodic)?.into())
+    }
+    #[track_caller]
+    fn gaussian_window(
+        window_length: i64,
+        std: f64,
+        periodic: bool,
+    ) -> Result<Self::Output, TensorError> {
+        Ok(_Tensor::<T, Cpu, DEVICE>::gaussian_window(window_length, std, periodic)?.into())
+    }
+    #[track_caller]
+    fn tukey_window(
+        window_length: i64,
+        alpha: f64,
+        periodic: bool,
+    ) -> Result<Self::Output, TensorError> {
+        Ok(_Tensor::<T, Cpu, DEVICE>::tukey_window(window_length, alpha, periodic)?.into())
+    }
+    #[track_caller]
+    fn general_cosine_window(
+        window_length: i64,
+        coeffs: &[f64],
+        periodic: bool,
+    ) -> Result<Self::Output, TensorError> {
+        Ok(
+            _Tensor::<T, Cpu, DEVICE>::general_cosine_window(window_length, coeffs, periodic)?
+                .into(),
+        )
+    }
 }
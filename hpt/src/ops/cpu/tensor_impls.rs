@@ -1,19 +1,22 @@
+use std::fmt::Display;
 use std::marker::PhantomData;
-use std::{fmt::Display, sync::atomic::Ordering};
 
+use crate::lazy::record::data_ptr;
 use crate::tensor::DiffTensor;
 use crate::CompressionAlgo;
 use crate::Cpu;
 #[cfg(feature = "cuda")]
 use crate::Cuda;
-use crate::{save, Save};
-use crate::{tensor_base::_Tensor, Tensor, DISPLAY_LR_ELEMENTS, DISPLAY_PRECISION};
+use crate::{save, Save, TensorWriter};
+use crate::{tensor_base::_Tensor, Tensor, PRINT_OPTIONS};
 #[cfg(feature = "cuda")]
 use cudarc::driver::DeviceRepr;
 use hpt_common::error::base::TensorError;
 use hpt_common::{layout::layout::Layout, shape::shape::Shape, utils::pointer::Pointer};
 use hpt_dataloader::data_loader::TensorMeta;
-use hpt_dataloader::{DataLoader, Endian, FromSafeTensors, Meta};
+use hpt_dataloader::{
+    DataLoader, Endian, FromMapped, FromSafeTensors, Meta, SafeTensorData, ToSafeTensors,
+};
 use hpt_display::display;
 use hpt_iterator::iterator_traits::ParStridedIteratorZip;
 use hpt_iterator::TensorIterator;
@@ -22,7 +25,7 @@ use hpt_traits::{CommonBounds, TensorAlloc, TensorInfo, TensorLike};
 #[cfg(feature = "cuda")]
 use hpt_types::dtype::CudaType;
 use hpt_types::into_scalar::Cast;
-use num::traits::ToBytes;
+use num::traits::{FromBytes, ToBytes};
 use rayon::iter::{
     IndexedParallelIterator, IntoParallelRefIterator, IntoParallelRefMutIterator, ParallelIterator,
 };
@@ -32,7 +35,7 @@ where
     T: CommonBounds,
 {
     fn as_raw(&self) -> &[T] {
-        let ptr = self.data.ptr;
+        let ptr = data_ptr(self).ptr;
         let size;
         if !self.is_contiguous() {
             size = self.layout.real_size();
@@ -44,7 +47,7 @@ where
     }
 
     fn as_raw_mut(&mut self) -> &mut [T] {
-        let ptr = self.data.ptr;
+        let ptr = data_ptr(self).ptr;
         let size;
         if !self.is_contiguous() {
             size = self.layout.real_size();
@@ -64,7 +67,7 @@ macro_rules! impl_tensor_info {
     ($tensor:ty) => {
         impl<T, const DEVICE: usize> TensorInfo<T> for $tensor {
             fn ptr(&self) -> Pointer<T> {
-                self.data.clone()
+                data_ptr::<T, Cpu, DEVICE>(self)
             }
             fn size(&self) -> usize {
                 self.layout.size() as usize
@@ -261,11 +264,9 @@ impl<const N: usize, T: CommonBounds + ToBytes<Bytes = [u8; N]>, const DEVICE: u
     for Tensor<T, Cpu, DEVICE>
 {
     type Meta = TensorMeta<T, Self>;
-    fn __save(
+    fn __save<W: std::io::Write>(
         data: &Self,
-        file: &mut std::fs::File,
-        len_so_far: &mut usize,
-        global_cnt: &mut usize,
+        writer: &mut TensorWriter<W>,
         compression_algo: CompressionAlgo,
         endian: Endian,
         level: u32,
@@ -278,8 +279,7 @@ impl<const N: usize, T: CommonBounds + ToBytes<Bytes = [u8; N]>, const DEVICE: u
             data_saver: Box::new(data_loader),
             compression_level: level,
         };
-        let info = save(file, meta, len_so_far, *global_cnt)?;
-        *global_cnt += 1;
+        let info = save(writer, meta)?;
         Ok(TensorMeta {
             begin: info.0,
             shape: info.2,
@@ -289,19 +289,55 @@ impl<const N: usize, T: CommonBounds + ToBytes<Bytes = [u8; N]>, const DEVICE: u
             compression_algo: info.6,
             endian: info.7,
             indices: info.8,
+            checksums: info.9,
             phantom: PhantomData,
         })
     }
 }
 
+impl<T: CommonBounds, const DEVICE: usize> Tensor<T, Cpu, DEVICE> {
+    /// Load a tensor from a NumPy `.npy` file.
+    ///
+    /// Both C order and Fortran order files are supported, a Fortran order file is loaded as a strided tensor without copy.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The path of the `.npy` file.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file can't be read, or the dtype stored doesn't match `T`.
+    pub fn load_npy<P: AsRef<std::path::Path>, const N: usize>(path: P) -> std::io::Result<Self>
+    where
+        T: FromBytes<Bytes = [u8; N]>,
+    {
+        hpt_dataloader::npy::load_npy::<T, Self, P, N>(path)
+    }
+
+    /// Save the tensor to a NumPy `.npy` file in little endian.
+    ///
+    /// A tensor which is contiguous in Fortran order is saved with `fortran_order: True`, other tensors are saved in C order.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The path of the `.npy` file.
+    pub fn save_npy<P: AsRef<std::path::Path>, const N: usize>(
+        &self,
+        path: P,
+    ) -> std::io::Result<()>
+    where
+        T: ToBytes<Bytes = [u8; N]>,
+    {
+        hpt_dataloader::npy::save_npy::<T, _, P, N>(path, self.clone(), Endian::Little)
+    }
+}
+
 impl<T, const DEVICE: usize> Display for _Tensor<T, Cpu, DEVICE>
 where
     T: CommonBounds + Cast<f64>,
 {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let precision = DISPLAY_PRECISION.load(Ordering::Relaxed);
-        let lr_element_size = DISPLAY_LR_ELEMENTS.load(Ordering::Relaxed);
-        display(self, f, lr_element_size, precision, false)
+        display(self, f, &PRINT_OPTIONS.read().unwrap(), false, false)
     }
 }
 
@@ -310,9 +346,7 @@ where
     T: CommonBounds + Cast<f64>,
 {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let precision = DISPLAY_PRECISION.load(Ordering::Relaxed);
-        let lr_element_size = DISPLAY_LR_ELEMENTS.load(Ordering::Relaxed);
-        display(self, f, lr_element_size, precision, false)
+        display(self, f, &PRINT_OPTIONS.read().unwrap(), true, false)
     }
 }
 
@@ -376,6 +410,35 @@ impl<T: CommonBounds, const DEVICE: usize> FromSafeTensors for Tensor<T, Cpu, DE
     }
 }
 
+impl<T: CommonBounds, const DEVICE: usize> FromMapped<T> for Tensor<T, Cpu, DEVICE> {
+    unsafe fn from_mapped(
+        ptr: *mut T,
+        shape: &[i64],
+        release: hpt_dataloader::mmap::MappingRelease,
+    ) -> std::io::Result<Self> {
+        let shape = Shape::from(shape);
+        let strides = shape.to_strides();
+        // each view gets its own storage, the mapping itself is refcounted by `release`
+        let tensor = _Tensor::<T, Cpu, DEVICE>::from_foreign(ptr, shape, strides, release)
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidInput, e.to_string()))?;
+        Ok(tensor.into())
+    }
+}
+
+impl<T, const N: usize, const DEVICE: usize> ToSafeTensors for Tensor<T, Cpu, DEVICE>
+where
+    T: CommonBounds + ToBytes<Bytes = [u8; N]>,
+{
+    fn to_safe_tensors(
+        &self,
+        tensor_name: &str,
+        tensors: &mut Vec<(String, SafeTensorData)>,
+    ) -> std::io::Result<()> {
+        tensors.push((tensor_name.to_string(), SafeTensorData::new(self.clone())?));
+        Ok(())
+    }
+}
+
 impl<T: Clone, const DEVICE: usize> DiffTensor<T, Cpu, DEVICE> {
     /// Backward the gradient of the tensor
     pub fn backward(&mut self, grad: Tensor<T, Cpu, DEVICE>) -> Result<(), TensorError> {
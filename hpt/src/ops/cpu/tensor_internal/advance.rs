@@ -1,17 +1,19 @@
+use std::panic::Location;
 use std::sync::Arc;
 
+use crate::context::{ensure_deterministic, with_thread_pool};
 use crate::ops::cpu::utils::binary::binary_normal::binary_fn_with_out_simd;
 use crate::tensor_base::_Tensor;
-use crate::{Cpu, THREAD_POOL};
+use crate::Cpu;
 use hpt_common::error::base::TensorError;
 use hpt_common::error::shape::ShapeError;
 use hpt_common::shape::shape_utils::mt_intervals;
 use hpt_common::Pointer;
 use hpt_iterator::iterator_traits::{ParStridedIteratorSimdZip, ParStridedIteratorZip};
 use hpt_iterator::TensorIterator;
-use hpt_traits::ops::advance::{AdvancedOps, HardMax, Shrinkage};
+use hpt_traits::ops::advance::{AdvancedOps, HardMax, PadMode, Shrinkage};
 use hpt_traits::{
-    CommonBounds, NormalReduce, ShapeManipulate, TensorCreator, TensorInfo, TensorWhere,
+    CommonBounds, NormalReduce, ShapeManipulate, TensorCreator, TensorInfo, TensorLike, TensorWhere,
 };
 use hpt_types::dtype::TypeCommon;
 use hpt_types::into_scalar::Cast;
@@ -47,7 +49,7 @@ where
             pads.resize(self.ndim(), (0, 0));
         }
         let pads = Arc::new(pads);
-        THREAD_POOL.with_borrow_mut(|pool| {
+        with_thread_pool(|pool| {
             let num_threads = if outer_loop < (pool.max_count() as i64) {
                 outer_loop as usize
             } else {
@@ -118,6 +120,86 @@ where
         Ok(res)
     }
 
+    fn pad_with_mode(
+        &self,
+        pads: &[(i64, i64)],
+        mode: PadMode<Self::Meta>,
+    ) -> Result<Self::Output, TensorError> {
+        if let PadMode::Constant(val) = mode {
+            return self.pad(pads, val);
+        }
+        let pads = check_pads(self.shape(), pads)?;
+        let ndim = self.ndim();
+        if ndim == 0 {
+            let res = _Tensor::<T, Cpu, DEVICE>::empty(Vec::<i64>::new())?;
+            let mut res_ptr = res.ptr();
+            res_ptr[0i64] = self.ptr()[0i64];
+            return Ok(res);
+        }
+        let res_shape = self
+            .shape()
+            .iter()
+            .zip(pads.iter())
+            .map(|(x, (a, b))| x + a + b)
+            .collect::<Vec<_>>();
+        let res = _Tensor::<T, Cpu, DEVICE>::empty(&res_shape)?;
+        if res.size() == 0 {
+            return Ok(res);
+        }
+        // the memory offset of the input element read by every position of every padded axis
+        let offsets = (0..ndim)
+            .map(|d| {
+                (0..res_shape[d])
+                    .map(|i| {
+                        let source = mode.source(i - pads[d].0, self.shape()[d]);
+                        source.expect("constant padding doesn't read the tensor")
+                            * self.strides()[d]
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+        let offsets = Arc::new(offsets);
+        let res_shape = Arc::new(res_shape);
+        let inner_loop = res_shape[ndim - 1];
+        let outer_loop = res.size() / (inner_loop as usize);
+        with_thread_pool(|pool| {
+            let num_threads = outer_loop.min(pool.max_count());
+            for (start, end) in mt_intervals(outer_loop, num_threads) {
+                let ptr = self.ptr();
+                let mut res_ptr = res.ptr();
+                let offsets = offsets.clone();
+                let res_shape = res_shape.clone();
+                pool.execute(move || {
+                    let mut prg = vec![0; ndim - 1];
+                    let mut amount = start as i64;
+                    for d in (0..ndim - 1).rev() {
+                        prg[d] = amount % res_shape[d];
+                        amount /= res_shape[d];
+                    }
+                    let last = &offsets[ndim - 1];
+                    for row in start..end {
+                        let base = (0..ndim - 1)
+                            .map(|d| offsets[d][prg[d] as usize])
+                            .sum::<i64>();
+                        let out = (row as i64) * inner_loop;
+                        for (i, &offset) in last.iter().enumerate() {
+                            res_ptr[out + (i as i64)] = ptr[base + offset];
+                        }
+                        for d in (0..ndim - 1).rev() {
+                            if prg[d] < res_shape[d] - 1 {
+                                prg[d] += 1;
+                                break;
+                            }
+                            prg[d] = 0;
+                        }
+                    }
+                });
+            }
+            pool.join();
+        });
+        Ok(res)
+    }
+
     fn topk(
         &self,
         k: i64,
@@ -169,7 +251,7 @@ where
         let res_indices_ptr = transposed_res_indices.ptr();
         let transposed_res_shape = transposed_res.shape();
         let transposed_res_strides = transposed_res.strides();
-        THREAD_POOL.with_borrow_mut(move |x| {
+        with_thread_pool(move |x| {
             let num_threads = if outer_loop_size < x.max_count() {
                 outer_loop_size
             } else {
@@ -353,7 +435,7 @@ where
 
         let last_strides = *permuted_res.strides().last().unwrap();
 
-        THREAD_POOL.with_borrow_mut(|pool| {
+        with_thread_pool(|pool| {
             let num_threads = if outer_loop_size < (pool.max_count() as i64) {
                 outer_loop_size as usize
             } else {
@@ -501,6 +583,7 @@ where
     // }
 
     fn dropout(&self, rate: f64) -> Result<Self::Output, TensorError> {
+        ensure_deterministic("dropout")?;
         let mut ret = _Tensor::<T, Cpu, DEVICE>::empty(self.shape())?;
         let bernoli = rand_distr::Bernoulli::new(rate)
             .expect("Failed to create Bernoulli distribution for dropout");
@@ -788,3 +871,78 @@ where
             .collect())
     }
 }
+
+/// validate `pads` for a non constant padding of a tensor with `shape` and extend it to every axis
+#[track_caller]
+fn check_pads(shape: &[i64], pads: &[(i64, i64)]) -> Result<Vec<(i64, i64)>, TensorError> {
+    if pads.len() > shape.len() {
+        return Err(ShapeError::InvalidDimension {
+            message: format!(
+                "got {} pads for a tensor with {} dimensions",
+                pads.len(),
+                shape.len()
+            ),
+            location: Location::caller(),
+        }
+        .into());
+    }
+    for (axis, &(before, after)) in pads.iter().enumerate() {
+        if before < 0 || after < 0 {
+            return Err(ShapeError::InvalidDimension {
+                message: format!(
+                    "pads must be non negative, got ({}, {}) for axis {}",
+                    before, after, axis
+                ),
+                location: Location::caller(),
+            }
+            .into());
+        }
+        if shape[axis] == 0 && before + after > 0 {
+            return Err(ShapeError::InvalidDimension {
+                message: format!("can't pad the empty axis {} without a constant", axis),
+                location: Location::caller(),
+            }
+            .into());
+        }
+    }
+    let mut pads = pads.to_vec();
+    pads.resize(shape.len(), (0, 0));
+    Ok(pads)
+}
+
+impl<T: CommonBounds, const DEVICE: usize> _Tensor<T, Cpu, DEVICE> {
+    /// the gradient of `pad_with_mode` for a non constant mode, every padded element adds its gradient
+    /// to the element it was read from
+    pub(crate) fn pad_grad(
+        &self,
+        shape: &[i64],
+        pads: &[(i64, i64)],
+        mode: PadMode<T>,
+    ) -> Result<Self, TensorError> {
+        let grad = self.contiguous()?;
+        let mut res = _Tensor::<T, Cpu, DEVICE>::zeros(shape)?;
+        let strides = res.strides().clone();
+        let res_raw = res.as_raw_mut();
+        let mut prg = vec![0i64; shape.len()];
+        for &g in grad.as_raw() {
+            let offset = prg
+                .iter()
+                .enumerate()
+                .map(|(d, &i)| {
+                    let before = pads.get(d).map_or(0, |x| x.0);
+                    let source = mode.source(i - before, shape[d]);
+                    source.expect("constant padding doesn't read the tensor") * strides[d]
+                })
+                .sum::<i64>();
+            res_raw[offset as usize] = res_raw[offset as usize]._add(g);
+            for d in (0..prg.len()).rev() {
+                if prg[d] < grad.shape()[d] - 1 {
+                    prg[d] += 1;
+                    break;
+                }
+                prg[d] = 0;
+            }
+        }
+        Ok(res)
+    }
+}
@@ -0,0 +1,146 @@
+//! Geometry of the sub-tensor views produced by the axis iterators.
+
+use crate::BackendTy;
+use crate::{tensor_base::_Tensor, Buffer};
+use hpt_common::axis::axis::process_axes;
+use hpt_common::error::{base::TensorError, param::ParamError};
+use hpt_common::utils::pointer::Pointer;
+use hpt_traits::tensor::CommonBounds;
+
+/// describes the views an axis iterator yields
+///
+/// the views are enumerated in row major order over `outer_shape`, view `i` starts at the
+/// offset of its outer index and shares `view_strides` with all the other views.
+#[derive(Clone, Debug)]
+pub(crate) struct AxisGeometry {
+    outer_shape: Vec<i64>,
+    outer_strides: Vec<i64>,
+    view_shape: Vec<i64>,
+    view_strides: Vec<i64>,
+    /// `(axis in the view, chunk size, axis length)`, the last chunk can be shorter
+    chunk: Option<(usize, i64, i64)>,
+    len: usize,
+}
+
+impl AxisGeometry {
+    /// one view per index of `axis`, the axis is removed from the views
+    #[track_caller]
+    pub(crate) fn axis_iter(
+        shape: &[i64],
+        strides: &[i64],
+        axis: i64,
+    ) -> Result<Self, TensorError> {
+        let axis = process_axes([axis], shape.len())?[0];
+        let mut view_shape = shape.to_vec();
+        let mut view_strides = strides.to_vec();
+        view_shape.remove(axis);
+        view_strides.remove(axis);
+        Ok(Self::new(
+            vec![shape[axis]],
+            vec![strides[axis]],
+            view_shape,
+            view_strides,
+            None,
+        ))
+    }
+
+    /// one 1-D view along `axis` for every index of the other axes
+    #[track_caller]
+    pub(crate) fn lanes(shape: &[i64], strides: &[i64], axis: i64) -> Result<Self, TensorError> {
+        let axis = process_axes([axis], shape.len())?[0];
+        let mut outer_shape = shape.to_vec();
+        let mut outer_strides = strides.to_vec();
+        outer_shape.remove(axis);
+        outer_strides.remove(axis);
+        Ok(Self::new(
+            outer_shape,
+            outer_strides,
+            vec![shape[axis]],
+            vec![strides[axis]],
+            None,
+        ))
+    }
+
+    /// views of `size` consecutive indices of `axis`, the axis is kept
+    #[track_caller]
+    pub(crate) fn axis_chunks(
+        shape: &[i64],
+        strides: &[i64],
+        axis: i64,
+        size: i64,
+    ) -> Result<Self, TensorError> {
+        let axis = process_axes([axis], shape.len())?[0];
+        if size <= 0 {
+            return Err(ParamError::InvalidChunkSize {
+                size,
+                location: std::panic::Location::caller(),
+            }
+            .into());
+        }
+        let n = shape[axis];
+        let mut view_shape = shape.to_vec();
+        view_shape[axis] = size.min(n);
+        Ok(Self::new(
+            vec![(n + size - 1) / size],
+            vec![strides[axis] * size],
+            view_shape,
+            strides.to_vec(),
+            Some((axis, size, n)),
+        ))
+    }
+
+    fn new(
+        outer_shape: Vec<i64>,
+        outer_strides: Vec<i64>,
+        view_shape: Vec<i64>,
+        view_strides: Vec<i64>,
+        chunk: Option<(usize, i64, i64)>,
+    ) -> Self {
+        let len = outer_shape.iter().product::<i64>() as usize;
+        Self {
+            outer_shape,
+            outer_strides,
+            view_shape,
+            view_strides,
+            chunk,
+            len,
+        }
+    }
+
+    /// number of views
+    pub(crate) fn len(&self) -> usize {
+        self.len
+    }
+
+    /// offset and shape of the view `idx`
+    fn view(&self, idx: usize) -> (i64, Vec<i64>) {
+        let mut rem = idx as i64;
+        let mut offset = 0;
+        for (&dim, &stride) in self.outer_shape.iter().zip(&self.outer_strides).rev() {
+            offset += (rem % dim) * stride;
+            rem /= dim;
+        }
+        let mut shape = self.view_shape.clone();
+        if let Some((axis, size, n)) = self.chunk {
+            shape[axis] = size.min(n - (idx as i64) * size);
+        }
+        (offset, shape)
+    }
+}
+
+impl<T, B: BackendTy + Buffer + Clone, const DEVICE: usize> _Tensor<T, B, DEVICE>
+where
+    T: CommonBounds,
+{
+    /// the view `idx` of `geometry`, it shares the buffer of `self`
+    pub(crate) fn axis_view(&self, geometry: &AxisGeometry, idx: usize) -> _Tensor<T, B, DEVICE> {
+        let (offset, shape) = geometry.view(idx);
+        // wrapping for the same reason as `slice`
+        let ptr: *mut T = self.data.ptr.wrapping_offset(offset as isize);
+        #[cfg(feature = "bound_check")]
+        let ptr = Pointer::new(ptr, self.data.len - offset);
+        #[cfg(not(feature = "bound_check"))]
+        let ptr = Pointer::new(ptr);
+        self.from_slice(ptr, shape, geometry.view_strides.clone())
+    }
+}
@@ -1,15 +1,19 @@
 use std::borrow::Borrow;
 
+use crate::ops::cpu::kernels::reduce::ReduceOp;
 use crate::ops::cpu::tensor_internal::float_out_unary::FloatBinaryType;
-use crate::ops::cpu::utils::reduce::reduce::{reduce, reduce2, reduce3};
+use crate::ops::cpu::utils::reduce::accumulate::{
+    accumulate_reduce, resolve_accumulation, LogSumExp, Mean, Sum, L2,
+};
+use crate::ops::cpu::utils::reduce::reduce::{reduce, reduce2, reduce3, reduce_op};
 use crate::tensor_base::_Tensor;
-use crate::{BoolVector, Cpu};
+use crate::{get_accumulation, BoolVector, Cpu};
 use hpt_common::axis::axis::{process_axes, Axis};
 use hpt_common::error::base::TensorError;
 use hpt_iterator::iterator_traits::ParStridedIteratorSimd;
 use hpt_iterator::TensorIterator;
 use hpt_traits::{
-    CommonBounds, EvalReduce, FloatReduce, NormalEvalReduce, NormalReduce, TensorInfo,
+    Accumulation, CommonBounds, EvalReduce, FloatReduce, NormalEvalReduce, NormalReduce, TensorInfo,
 };
 use hpt_types::into_vec::IntoVec;
 use hpt_types::type_promote::NormalOutUnary;
@@ -28,19 +32,32 @@ impl<T: CommonBounds, const DEVICE: usize> NormalReduce<T> for _Tensor<T, Cpu, D
         &self,
         axes: S,
         keep_dims: bool,
+    ) -> std::result::Result<Self::Output, TensorError> {
+        self.sum_with(axes, keep_dims, get_accumulation())
+    }
+
+    fn sum_with<S: Into<Axis>>(
+        &self,
+        axes: S,
+        keep_dims: bool,
+        accumulation: Accumulation,
     ) -> std::result::Result<Self::Output, TensorError> {
         let axes = process_axes(axes, self.ndim())?;
-        reduce(
-            self,
-            |a, b| a._add(b),
-            |a, b| a._add(b),
-            |a, b| a._add(b),
-            &axes,
-            T::ZERO,
-            keep_dims,
-            false,
-            None,
-        )
+        match resolve_accumulation::<T>(accumulation) {
+            Accumulation::Native => reduce_op(
+                self,
+                ReduceOp::Sum,
+                |a, b| a._add(b),
+                |a, b| a._add(b),
+                |a, b| a._add(b),
+                &axes,
+                T::ZERO,
+                keep_dims,
+                false,
+                None,
+            ),
+            accumulation => accumulate_reduce(self, &axes, keep_dims, accumulation, Sum),
+        }
     }
 
     fn sum_<S: Into<Axis>, O>(
@@ -54,8 +71,9 @@ impl<T: CommonBounds, const DEVICE: usize> NormalReduce<T> for _Tensor<T, Cpu, D
         O: Borrow<Self::Output>,
     {
         let axes = process_axes(axes, self.ndim())?;
-        reduce(
+        reduce_op(
             self,
+            ReduceOp::Sum,
             |a, b| a._add(b),
             |a, b| a._add(b),
             |a, b| a._add(b),
@@ -93,8 +111,9 @@ impl<T: CommonBounds, const DEVICE: usize> NormalReduce<T> for _Tensor<T, Cpu, D
         keep_dims: bool,
     ) -> std::result::Result<Self::Output, TensorError> {
         let axes = process_axes(axis, self.ndim())?;
-        reduce(
+        reduce_op(
             self,
+            ReduceOp::Prod,
             |a, b| a._mul(b),
             |a, b| a._mul(b),
             |a, b| a._mul(b),
@@ -132,8 +151,9 @@ impl<T: CommonBounds, const DEVICE: usize> NormalReduce<T> for _Tensor<T, Cpu, D
         keep_dims: bool,
     ) -> std::result::Result<Self, TensorError> {
         let axes: Vec<usize> = process_axes(axis, self.ndim())?;
-        reduce(
+        reduce_op(
             self,
+            ReduceOp::Min,
             |a, b| a._min(b),
             |a, b| a._min(b),
             |a, b| a._min(b),
@@ -171,8 +191,9 @@ impl<T: CommonBounds, const DEVICE: usize> NormalReduce<T> for _Tensor<T, Cpu, D
         keep_dims: bool,
     ) -> std::result::Result<Self, TensorError> {
         let axes: Vec<usize> = process_axes(axis, self.ndim())?;
-        reduce(
+        reduce_op(
             self,
+            ReduceOp::Max,
             |a, b| a._max(b),
             |a, b| a._max(b),
             |a, b| a._max(b),
@@ -505,29 +526,43 @@ where
         &self,
         axis: S,
         keep_dims: bool,
+    ) -> std::result::Result<_Tensor<FloatBinaryType<T>, Cpu, DEVICE>, TensorError> {
+        self.mean_with(axis, keep_dims, get_accumulation())
+    }
+
+    #[track_caller]
+    fn mean_with<S: Into<Axis>>(
+        &self,
+        axis: S,
+        keep_dims: bool,
+        accumulation: Accumulation,
     ) -> std::result::Result<_Tensor<FloatBinaryType<T>, Cpu, DEVICE>, TensorError> {
         let axes: Vec<usize> = process_axes(axis, self.ndim())?;
-        let reduce_size: FloatBinaryType<T> = (axes
+        let size = axes
             .iter()
-            .fold(1, |acc, &x| acc * (self.shape()[x] as usize))
-            as f64)
-            .cast();
-        let reduce_vec = <FloatBinaryType<T> as TypeCommon>::Vec::splat(reduce_size);
-        reduce3(
-            self,
-            |a, b| a._add(b),
-            |a, b| a._add(b),
-            |a, b| a._add(b),
-            move |a| a._div(reduce_size),
-            |a, b| a._add(b),
-            |a, b| a._add(b),
-            move |a| a._div(reduce_vec),
-            &axes,
-            FloatBinaryType::<T>::ZERO,
-            keep_dims,
-            false,
-            None,
-        )
+            .fold(1, |acc, &x| acc * (self.shape()[x] as usize)) as f64;
+        let reduce_size: FloatBinaryType<T> = size.cast();
+        match resolve_accumulation::<FloatBinaryType<T>>(accumulation) {
+            Accumulation::Native => {
+                let reduce_vec = <FloatBinaryType<T> as TypeCommon>::Vec::splat(reduce_size);
+                reduce3(
+                    self,
+                    |a, b| a._add(b),
+                    |a, b| a._add(b),
+                    |a, b| a._add(b),
+                    move |a| a._div(reduce_size),
+                    |a, b| a._add(b),
+                    |a, b| a._add(b),
+                    move |a| a._div(reduce_vec),
+                    &axes,
+                    FloatBinaryType::<T>::ZERO,
+                    keep_dims,
+                    false,
+                    None,
+                )
+            }
+            accumulation => accumulate_reduce(self, &axes, keep_dims, accumulation, Mean(size)),
+        }
     }
 
     #[allow(unused)]
@@ -536,23 +571,36 @@ where
         &self,
         axis: S,
         keep_dims: bool,
+    ) -> std::result::Result<_Tensor<FloatBinaryType<T>, Cpu, DEVICE>, TensorError> {
+        self.reducel2_with(axis, keep_dims, get_accumulation())
+    }
+
+    #[track_caller]
+    fn reducel2_with<S: Into<Axis>>(
+        &self,
+        axis: S,
+        keep_dims: bool,
+        accumulation: Accumulation,
     ) -> std::result::Result<_Tensor<FloatBinaryType<T>, Cpu, DEVICE>, TensorError> {
         let axes: Vec<usize> = process_axes(axis, self.ndim())?;
-        reduce3(
-            self,
-            |a, b| a._add(b._square()),
-            |a, b| a._add(b._square()),
-            |a, b| a._add(b),
-            move |a| a._sqrt(),
-            |a, b| a._add(b._square()),
-            |a, b| a._add(b._square()),
-            |a| a._sqrt(),
-            &axes,
-            FloatBinaryType::<T>::ZERO,
-            keep_dims,
-            false,
-            None,
-        )
+        match resolve_accumulation::<FloatBinaryType<T>>(accumulation) {
+            Accumulation::Native => reduce3(
+                self,
+                |a, b| a._add(b._square()),
+                |a, b| a._add(b._square()),
+                |a, b| a._add(b),
+                move |a| a._sqrt(),
+                |a, b| a._add(b._square()),
+                |a, b| a._add(b._square()),
+                |a| a._sqrt(),
+                &axes,
+                FloatBinaryType::<T>::ZERO,
+                keep_dims,
+                false,
+                None,
+            ),
+            accumulation => accumulate_reduce(self, &axes, keep_dims, accumulation, L2),
+        }
     }
     #[allow(unused)]
     #[track_caller]
@@ -615,30 +663,43 @@ where
     where
         T: CommonBounds,
     {
+        self.logsumexp_with(axis, keep_dims, get_accumulation())
+    }
+
+    #[track_caller]
+    fn logsumexp_with<S: Into<Axis>>(
+        &self,
+        axis: S,
+        keep_dims: bool,
+        accumulation: Accumulation,
+    ) -> std::result::Result<_Tensor<FloatBinaryType<T>, Cpu, DEVICE>, TensorError> {
         let axes: Vec<usize> = process_axes(axis, self.ndim())?;
-        reduce3(
-            self,
-            |acc, b| {
-                let exp = b._exp();
-                acc._add(exp)
-            },
-            |acc, b| {
-                let exp = b._exp();
-                acc._add(exp)
-            },
-            |acc, b| {
-                let exp = b._exp();
-                acc._add(exp)
-            },
-            move |a| a._ln(),
-            |a, b| a._add(b._exp()),
-            |a, b| a._add(b._exp()),
-            move |a| a._ln(),
-            &axes,
-            FloatBinaryType::<T>::ZERO,
-            keep_dims,
-            false,
-            None,
-        )
+        match resolve_accumulation::<FloatBinaryType<T>>(accumulation) {
+            Accumulation::Native => reduce3(
+                self,
+                |acc, b| {
+                    let exp = b._exp();
+                    acc._add(exp)
+                },
+                |acc, b| {
+                    let exp = b._exp();
+                    acc._add(exp)
+                },
+                |acc, b| {
+                    let exp = b._exp();
+                    acc._add(exp)
+                },
+                move |a| a._ln(),
+                |a, b| a._add(b._exp()),
+                |a, b| a._add(b._exp()),
+                move |a| a._ln(),
+                &axes,
+                FloatBinaryType::<T>::ZERO,
+                keep_dims,
+                false,
+                None,
+            ),
+            accumulation => accumulate_reduce(self, &axes, keep_dims, accumulation, LogSumExp),
+        }
     }
 }
@@ -0,0 +1,193 @@
+//! Reductions along axes with user provided closures.
+
+use crate::context::is_deterministic;
+use crate::ops::cpu::utils::reduce::reduce::reduce;
+use crate::ops::cpu::utils::reduce::reduce_utils::{tree_combine, DETERMINISTIC_CHUNK};
+use crate::tensor_base::_Tensor;
+use crate::Cpu;
+use hpt_common::axis::axis::{process_axes, Axis};
+use hpt_common::error::base::TensorError;
+use hpt_common::shape::shape_utils::mt_intervals;
+use hpt_traits::{CommonBounds, ShapeManipulate, TensorCreator, TensorInfo};
+use rayon::iter::{IntoParallelIterator, ParallelIterator};
+
+/// walks the memory offsets of the elements of a sub-space of a tensor in row major order
+struct Cursor<'a> {
+    shape: &'a [i64],
+    strides: &'a [i64],
+    index: Vec<i64>,
+    offset: i64,
+}
+
+impl<'a> Cursor<'a> {
+    /// a cursor positioned at the row major position `pos`
+    fn new(shape: &'a [i64], strides: &'a [i64], pos: usize) -> Self {
+        let mut index = vec![0; shape.len()];
+        let mut offset = 0;
+        let mut rem = pos as i64;
+        for d in (0..shape.len()).rev() {
+            index[d] = rem % shape[d];
+            rem /= shape[d];
+            offset += index[d] * strides[d];
+        }
+        Cursor {
+            shape,
+            strides,
+            index,
+            offset,
+        }
+    }
+
+    #[inline(always)]
+    fn advance(&mut self) {
+        for d in (0..self.shape.len()).rev() {
+            self.index[d] += 1;
+            self.offset += self.strides[d];
+            if self.index[d] < self.shape[d] {
+                return;
+            }
+            self.offset -= self.strides[d] * self.shape[d];
+            self.index[d] = 0;
+        }
+    }
+}
+
+impl<T: CommonBounds, const DEVICE: usize> _Tensor<T, Cpu, DEVICE> {
+    /// reduce along `axes` with `combine` using the same engine as the builtin reductions
+    pub fn reduce_axes<S: Into<Axis>, F, F2>(
+        &self,
+        axes: S,
+        keep_dims: bool,
+        identity: T,
+        combine: F,
+        vec_combine: F2,
+    ) -> std::result::Result<Self, TensorError>
+    where
+        F: Fn(T, T) -> T + Sync + Send + 'static + Copy,
+        F2: Fn(T::Vec, T::Vec) -> T::Vec + Sync + Send + 'static + Copy,
+    {
+        let axes = process_axes(axes, self.ndim())?;
+        reduce(
+            self,
+            combine,
+            combine,
+            vec_combine,
+            &axes,
+            identity,
+            keep_dims,
+            false,
+            None,
+        )
+    }
+
+    /// reduce along `axes` into two outputs, `combine` receives the row major position of the
+    /// element inside the reduced axes
+    ///
+    /// the outputs are split between the threads, when there are fewer outputs than threads the
+    /// reduced elements are split instead and the partial results are merged in order with `merge`.
+    /// In deterministic mode a single output is split in chunks of fixed size merged pairwise
+    pub fn reduce_axes_multi<S: Into<Axis>, A, B, F, F2>(
+        &self,
+        axes: S,
+        keep_dims: bool,
+        identity: (A, B),
+        combine: F,
+        merge: F2,
+    ) -> std::result::Result<(_Tensor<A, Cpu, DEVICE>, _Tensor<B, Cpu, DEVICE>), TensorError>
+    where
+        A: CommonBounds,
+        B: CommonBounds,
+        F: Fn((A, B), T, i64) -> (A, B) + Sync + Send,
+        F2: Fn((A, B), (A, B)) -> (A, B) + Sync + Send,
+    {
+        let axes = process_axes(axes, self.ndim())?;
+        let (shape, strides) = (self.shape(), self.strides());
+        let (mut out_shape, mut out_strides) = (vec![], vec![]);
+        let (mut red_shape, mut red_strides) = (vec![], vec![]);
+        for d in 0..self.ndim() {
+            if axes.contains(&d) {
+                red_shape.push(shape[d]);
+                red_strides.push(strides[d]);
+            } else {
+                out_shape.push(shape[d]);
+                out_strides.push(strides[d]);
+            }
+        }
+        let outputs = out_shape.iter().product::<i64>() as usize;
+        let reduced = red_shape.iter().product::<i64>() as usize;
+
+        let a = _Tensor::<A, Cpu, DEVICE>::empty(out_shape.clone())?;
+        let b = _Tensor::<B, Cpu, DEVICE>::empty(out_shape.clone())?;
+        let (a_ptr, b_ptr, ptr) = (a.ptr(), b.ptr(), self.ptr());
+
+        let fold = |base: i64, start: usize, end: usize| {
+            let mut acc = identity;
+            let mut cursor = Cursor::new(&red_shape, &red_strides, start);
+            for pos in start..end {
+                let val = unsafe { *ptr.get_ptr().offset((base + cursor.offset) as isize) };
+                acc = combine(acc, val, pos as i64);
+                cursor.advance();
+            }
+            acc
+        };
+        let threads = rayon::current_num_threads();
+        let deterministic = is_deterministic();
+        let split_outputs = if deterministic {
+            outputs > 1 || reduced <= DETERMINISTIC_CHUNK
+        } else {
+            outputs >= threads || reduced <= 1
+        };
+        if split_outputs {
+            mt_intervals(outputs, outputs.min(threads))
+                .into_par_iter()
+                .for_each(|(start, end)| {
+                    let mut cursor = Cursor::new(&out_shape, &out_strides, start);
+                    for i in start..end {
+                        let (x, y) = fold(cursor.offset, 0, reduced);
+                        unsafe {
+                            a_ptr.get_ptr().add(i).write(x);
+                            b_ptr.get_ptr().add(i).write(y);
+                        }
+                        cursor.advance();
+                    }
+                });
+        } else {
+            let chunks = if deterministic {
+                (0..reduced.div_ceil(DETERMINISTIC_CHUNK))
+                    .map(|i| {
+                        (
+                            i * DETERMINISTIC_CHUNK,
+                            ((i + 1) * DETERMINISTIC_CHUNK).min(reduced),
+                        )
+                    })
+                    .collect::<Vec<_>>()
+            } else {
+                mt_intervals(reduced, reduced.min(threads))
+            };
+            let mut cursor = Cursor::new(&out_shape, &out_strides, 0);
+            for i in 0..outputs {
+                let base = cursor.offset;
+                let partials = chunks
+                    .clone()
+                    .into_par_iter()
+                    .map(|(start, end)| fold(base, start, end));
+                let (x, y) = if deterministic {
+                    tree_combine(partials.collect::<Vec<_>>(), identity, &merge)
+                } else {
+                    partials.reduce(|| identity, &merge)
+                };
+                unsafe {
+                    a_ptr.get_ptr().add(i).write(x);
+                    b_ptr.get_ptr().add(i).write(y);
+                }
+                cursor.advance();
+            }
+        }
+        if keep_dims {
+            let res_shape = self.layout.reduce(axes.as_slice(), true)?.shape().clone();
+            Ok((a.reshape(&res_shape)?, b.reshape(&res_shape)?))
+        } else {
+            Ok((a, b))
+        }
+    }
+}
@@ -2,7 +2,11 @@ use hpt_traits::{CommonBounds, FloatBinOps};
 use hpt_types::{dtype::TypeCommon, type_promote::FloatOutBinary};
 
 use crate::{
-    ops::cpu::utils::binary::binary_normal::binary_fn_with_out_simd, tensor_base::_Tensor, Cpu,
+    ops::cpu::utils::binary::binary_normal::{
+        binary_fn_with_out_simd, binary_op_with_out_simd, BinaryOp,
+    },
+    tensor_base::_Tensor,
+    Cpu,
 };
 
 use super::float_out_unary::FloatBinaryType;
@@ -51,6 +55,13 @@ where
     where
         U: std::borrow::BorrowMut<Self::InplaceOutput>,
     {
-        binary_fn_with_out_simd(self, rhs, |a, b| a._div(b), |a, b| a._div(b), Some(out))
+        binary_op_with_out_simd(
+            self,
+            rhs,
+            BinaryOp::Div,
+            |a, b| a._div(b),
+            |a, b| a._div(b),
+            Some(out),
+        )
     }
 }
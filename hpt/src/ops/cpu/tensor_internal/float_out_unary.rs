@@ -12,7 +12,10 @@ use hpt_types::{
 };
 
 use crate::Cpu;
-use crate::{ops::cpu::utils::unary::unary::unary_fn_with_out, tensor_base::_Tensor};
+use crate::{
+    ops::cpu::utils::unary::unary::{unary_fn_with_out, unary_op_with_out, UnaryOp},
+    tensor_base::_Tensor,
+};
 
 pub(crate) type FloatUnaryType<T> = <T as FloatOutUnary>::Output;
 pub(crate) type FloatBinaryType<T> = <T as FloatOutBinary>::Output;
@@ -277,8 +280,9 @@ where
     }
 
     fn sqrt(&self) -> std::result::Result<Self::Output, TensorError> {
-        unary_fn_with_out(
+        unary_op_with_out(
             self,
+            UnaryOp::Sqrt,
             |x| x._sqrt(),
             |x| x._sqrt(),
             None::<Self::InplaceOutput>,
@@ -289,12 +293,13 @@ where
     where
         U: BorrowMut<Self::InplaceOutput>,
     {
-        unary_fn_with_out(self, |x| x._sqrt(), |x| x._sqrt(), Some(out))
+        unary_op_with_out(self, UnaryOp::Sqrt, |x| x._sqrt(), |x| x._sqrt(), Some(out))
     }
 
     fn recip(&self) -> std::result::Result<Self::Output, TensorError> {
-        unary_fn_with_out(
+        unary_op_with_out(
             self,
+            UnaryOp::Recip,
             |x| x._recip(),
             |x| x._recip(),
             None::<Self::InplaceOutput>,
@@ -305,7 +310,13 @@ where
     where
         U: BorrowMut<Self::InplaceOutput>,
     {
-        unary_fn_with_out(self, |x| x._recip(), |x| x._recip(), Some(out))
+        unary_op_with_out(
+            self,
+            UnaryOp::Recip,
+            |x| x._recip(),
+            |x| x._recip(),
+            Some(out),
+        )
     }
 
     fn ln(&self) -> std::result::Result<Self::Output, TensorError> {
@@ -370,7 +381,12 @@ where
         U: BorrowMut<Self::InplaceOutput>,
     {
         let alpha_vec = <FloatUnaryType<T> as TypeCommon>::Vec::splat(alpha);
-        unary_fn_with_out(self, |x| x._celu(alpha_vec), |x| x._celu(alpha), Some(out))
+        unary_fn_with_out(
+            self,
+            move |x| x._celu(alpha_vec),
+            move |x| x._celu(alpha),
+            Some(out),
+        )
     }
 
     fn sigmoid(&self) -> std::result::Result<Self::Output, TensorError> {
@@ -393,8 +409,8 @@ where
         let alpha_vec = <FloatUnaryType<T> as TypeCommon>::Vec::splat(alpha);
         unary_fn_with_out(
             self,
-            |x| x._elu(alpha_vec),
-            |x| x._elu(alpha),
+            move |x| x._elu(alpha_vec),
+            move |x| x._elu(alpha),
             None::<Self::InplaceOutput>,
         )
     }
@@ -408,7 +424,12 @@ where
         U: BorrowMut<Self::InplaceOutput>,
     {
         let alpha_vec = <FloatUnaryType<T> as TypeCommon>::Vec::splat(alpha);
-        unary_fn_with_out(self, |x| x._elu(alpha_vec), |x| x._elu(alpha), Some(out))
+        unary_fn_with_out(
+            self,
+            move |x| x._elu(alpha_vec),
+            move |x| x._elu(alpha),
+            Some(out),
+        )
     }
 
     fn erf(&self) -> std::result::Result<Self::Output, TensorError> {
@@ -448,8 +469,8 @@ where
         let gamma_vec = <FloatUnaryType<T> as TypeCommon>::Vec::splat(gamma);
         unary_fn_with_out(
             self,
-            |x| x._selu(alpha_vec, gamma_vec),
-            |x| x._selu(alpha, gamma),
+            move |x| x._selu(alpha_vec, gamma_vec),
+            move |x| x._selu(alpha, gamma),
             None::<Self::InplaceOutput>,
         )
     }
@@ -469,8 +490,8 @@ where
         let gamma_vec = <FloatUnaryType<T> as TypeCommon>::Vec::splat(gamma);
         unary_fn_with_out(
             self,
-            |x| x._selu(alpha_vec, gamma_vec),
-            |x| x._selu(alpha, gamma),
+            move |x| x._selu(alpha_vec, gamma_vec),
+            move |x| x._selu(alpha, gamma),
             Some(out),
         )
     }
@@ -1,16 +1,39 @@
 use std::borrow::{Borrow, BorrowMut};
 
+use crate::context::{with_thread_pool, PoolScope};
+use crate::lazy::record::{is_recording, record_op, resolve};
+use crate::ops::cpu::quantize::PackedI4;
 use crate::tensor_base::_Tensor;
-use crate::THREAD_POOL;
 use hpt_common::error::{base::TensorError, shape::ShapeError};
+use hpt_common::shape::shape::Shape;
 use hpt_common::shape::shape_utils::predict_broadcast_shape;
 use hpt_common::shape::shape_utils::{compare_and_pad_shapes, mt_intervals};
 use hpt_common::strides::strides_utils::preprocess_strides;
 use hpt_traits::TensorLike;
 use hpt_traits::{CommonBounds, Matmul, TensorCreator, TensorInfo};
+use hpt_types::dispatch::{dispatch_kernel, Simd, SimdKernel, SimdVec};
 use hpt_types::dtype::TypeCommon;
 use hpt_types::{into_scalar::Cast, type_promote::NormalOut};
-use rayon::iter::{IntoParallelRefMutIterator, ParallelIterator};
+use rayon::iter::{IndexedParallelIterator, IntoParallelRefMutIterator, ParallelIterator};
+use rayon::slice::ParallelSliceMut;
+
+/// the shape of the result of `matmul_with_out`, the batch dimensions are broadcasted
+#[track_caller]
+fn matmul_shape(lhs: &Shape, rhs: &Shape) -> std::result::Result<Shape, TensorError> {
+    ShapeError::check_matmul(lhs, rhs)?;
+    let (longer_shape, padded_short_shape) = compare_and_pad_shapes(lhs, rhs);
+    let (a_shape, b_shape) = if lhs.len() > rhs.len() {
+        (longer_shape, padded_short_shape)
+    } else {
+        (padded_short_shape, longer_shape)
+    };
+    let mut res_shape =
+        predict_broadcast_shape(&a_shape[..a_shape.len() - 2], &b_shape[..b_shape.len() - 2])?
+            .to_vec();
+    res_shape.push(a_shape[a_shape.len() - 2]);
+    res_shape.push(b_shape[b_shape.len() - 1]);
+    Ok(res_shape.into())
+}
 
 #[track_caller]
 pub(crate) fn matmul_with_out<A, B, O, Q>(
@@ -25,6 +48,17 @@ where
     <A as NormalOut<B>>::Output: CommonBounds,
     Q: CommonBounds,
 {
+    if out.is_none() && is_recording() {
+        let shape = matmul_shape(lhs.shape(), rhs.shape())?;
+        let (lhs_view, rhs_view) = (lhs.clone(), rhs.clone());
+        return record_op(&[lhs, rhs], shape, move |values| {
+            matmul_with_out(
+                &resolve(&lhs_view, &values[0]),
+                &resolve(&rhs_view, &values[1]),
+                None::<_Tensor<Q>>,
+            )
+        });
+    }
     if lhs.shape().len() == 2 && rhs.shape().len() == 2 {
         ShapeError::check_matmul(lhs.shape(), rhs.shape())?;
         let res = if let Some(mut out) = out {
@@ -159,7 +193,7 @@ where
         let m = a_shape[a_shape.len() - 2] as usize;
         let n = b_shape[b_shape.len() - 1] as usize;
         let k = b_shape[b_shape.len() - 2] as usize;
-        THREAD_POOL.with_borrow_mut(|pool: &mut threadpool::ThreadPool| {
+        with_thread_pool(|pool: &PoolScope| {
             for i in (0..num_threads).rev() {
                 let threads: usize = num_threads_each.pop().unwrap();
                 let current_size: usize = intervals[i].1 - intervals[i].0;
@@ -217,6 +251,121 @@ where
     }
 }
 
+/// `lhs` of shape `[..., m, k]` times packed 4-bit weights of shape `[k, n]`, the activations are computed in f32.
+///
+/// Every thread owns a block of output columns. A group of a column is dequantized once into an L1 resident buffer and
+/// multiplied with the same group of every row of `lhs` in f32 vectors, so the weights are only read once.
+#[track_caller]
+pub(crate) fn matmul_q4<T>(
+    lhs: &_Tensor<T>,
+    rhs: &PackedI4,
+) -> std::result::Result<_Tensor<T>, TensorError>
+where
+    T: CommonBounds + Cast<f32>,
+    f32: Cast<T>,
+{
+    let n = rhs.n;
+    ShapeError::check_matmul(lhs.shape(), &Shape::from(rhs.shape()))?;
+    let mut res_shape = lhs.shape().to_vec();
+    *res_shape.last_mut().unwrap() = n as i64;
+    let mut res = _Tensor::<T>::empty(res_shape)?;
+    if res.size() == 0 {
+        return Ok(res);
+    }
+    let x = lhs.try_astype::<f32>()?.contiguous()?;
+    let x = x.as_raw();
+    let rows = res.size() / n;
+
+    // column major result, every column is computed by one task
+    let mut cols = vec![0f32; n * rows];
+    dispatch_kernel(Q4Columns {
+        cols: &mut cols,
+        rows,
+        rhs,
+        x,
+    });
+    res.as_raw_mut()
+        .par_chunks_mut(n)
+        .enumerate()
+        .for_each(|(r, out)| {
+            for (j, o) in out.iter_mut().enumerate() {
+                *o = cols[j * rows + r].cast();
+            }
+        });
+    Ok(res)
+}
+
+/// the columns of `matmul_q4`, every column is computed by one task
+struct Q4Columns<'a> {
+    cols: &'a mut [f32],
+    rows: usize,
+    rhs: &'a PackedI4,
+    x: &'a [f32],
+}
+
+impl SimdKernel for Q4Columns<'_> {
+    type Output = ();
+
+    #[inline(always)]
+    fn run<S: Simd>(self, simd: S) {
+        let Q4Columns { cols, rows, rhs, x } = self;
+        cols.par_chunks_mut(rows)
+            .enumerate()
+            .for_each(|(j, acc)| simd.vectorize(Q4Column { acc, j, rhs, x }));
+    }
+}
+
+/// the column `j` of `matmul_q4`, one task of [`Q4Columns`]
+struct Q4Column<'a> {
+    acc: &'a mut [f32],
+    j: usize,
+    rhs: &'a PackedI4,
+    x: &'a [f32],
+}
+
+impl SimdKernel for Q4Column<'_> {
+    type Output = ();
+
+    #[inline(always)]
+    fn run<S: Simd>(self, simd: S) {
+        let Q4Column { acc, j, rhs, x } = self;
+        let (k, group_size) = (rhs.k, rhs.group_size);
+        let groups = k / group_size;
+        let mut w = vec![0f32; group_size];
+        let column = &rhs.packed[j * k / 2..(j + 1) * k / 2];
+        for (g, bytes) in column.chunks_exact(group_size / 2).enumerate() {
+            let scale = rhs.scales[j * groups + g];
+            for (pair, &byte) in w.chunks_exact_mut(2).zip(bytes.iter()) {
+                pair[0] = PackedI4::nibble(byte, false) as f32 * scale;
+                pair[1] = PackedI4::nibble(byte, true) as f32 * scale;
+            }
+            let start = g * group_size;
+            for (r, acc) in acc.iter_mut().enumerate() {
+                *acc += dot_f32(simd, &w, &x[r * k + start..r * k + start + group_size]);
+            }
+        }
+    }
+}
+
+/// dot product of two f32 slices of the same length
+#[inline(always)]
+fn dot_f32<S: Simd>(simd: S, a: &[f32], b: &[f32]) -> f32 {
+    let lanes = S::F32::LANES;
+    let mut acc = S::F32::splat(simd, 0.0);
+    let (a_chunks, b_chunks) = (a.chunks_exact(lanes), b.chunks_exact(lanes));
+    let tail = a_chunks
+        .remainder()
+        .iter()
+        .zip(b_chunks.remainder().iter())
+        .map(|(a, b)| a * b)
+        .sum::<f32>();
+    for (a, b) in a_chunks.zip(b_chunks) {
+        let (a, b) = unsafe { (S::F32::load(a.as_ptr()), S::F32::load(b.as_ptr())) };
+        acc = a.mul_add(b, acc);
+    }
+    acc.reduce_add() + tail
+}
+
 impl<A, B> Matmul<_Tensor<B>> for _Tensor<A>
 where
     A: CommonBounds + NormalOut<B> + Cast<<A as NormalOut<B>>::Output>,
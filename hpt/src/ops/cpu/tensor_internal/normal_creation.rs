@@ -14,6 +14,7 @@ use hpt_common::{
     shape::shape::Shape,
     utils::pointer::Pointer,
 };
+use hpt_iterator::TensorIterator;
 use hpt_traits::{CommonBounds, TensorCreator, TensorInfo, TensorLike};
 use hpt_types::{into_scalar::Cast, type_promote::NormalOut};
 use rayon::iter::{IndexedParallelIterator, IntoParallelIterator, ParallelIterator};
@@ -100,6 +101,18 @@ impl<T: CommonBounds, const DEVICE: usize> TensorCreator<T> for _Tensor<T, Cpu,
         _Tensor::full(val, self.shape())
     }
 
+    fn from_fn<S, F>(shape: S, f: F) -> Result<Self, TensorError>
+    where
+        S: Into<Shape>,
+        F: Fn(&[i64]) -> T + Sync + Send,
+    {
+        let mut res = Self::empty(shape)?;
+        res.par_indexed_iter_mut().for_each(|(idx, x)| {
+            *x = f(idx);
+        });
+        Ok(res)
+    }
+
     fn arange<U>(start: U, end: U) -> Result<Self, TensorError>
     where
         usize: Cast<T>,
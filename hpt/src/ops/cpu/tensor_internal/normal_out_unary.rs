@@ -6,7 +6,11 @@ use hpt_types::{
 };
 use std::borrow::Borrow;
 
-use crate::{ops::cpu::utils::unary::unary::unary_fn_with_out, tensor_base::_Tensor, Cpu};
+use crate::{
+    ops::cpu::utils::unary::unary::{unary_fn_with_out, unary_op_with_out, UnaryOp},
+    tensor_base::_Tensor,
+    Cpu,
+};
 
 pub(crate) type NormalType<T> = <T as NormalOut>::Output;
 
@@ -35,25 +39,43 @@ where
     }
 
     fn square(&self) -> std::result::Result<Self::Output, TensorError> {
-        unary_fn_with_out(self, |x| x._square(), |x| x._square(), None::<Self::Output>)
+        unary_op_with_out(
+            self,
+            UnaryOp::Square,
+            |x| x._square(),
+            |x| x._square(),
+            None::<Self::Output>,
+        )
     }
 
     fn square_<U>(&self, out: U) -> std::result::Result<Self::Output, TensorError>
     where
         U: Borrow<Self::InplaceOutput>,
     {
-        unary_fn_with_out(self, |x| x._square(), |x| x._square(), Some(out))
+        unary_op_with_out(
+            self,
+            UnaryOp::Square,
+            |x| x._square(),
+            |x| x._square(),
+            Some(out),
+        )
     }
 
     fn abs(&self) -> std::result::Result<Self::Output, TensorError> {
-        unary_fn_with_out(self, |x| x._abs(), |x| x._abs(), None::<Self::Output>)
+        unary_op_with_out(
+            self,
+            UnaryOp::Abs,
+            |x| x._abs(),
+            |x| x._abs(),
+            None::<Self::Output>,
+        )
     }
 
     fn abs_<U>(&self, out: U) -> std::result::Result<Self::Output, TensorError>
     where
         U: Borrow<Self::InplaceOutput>,
     {
-        unary_fn_with_out(self, |x| x._abs(), |x| x._abs(), Some(out))
+        unary_op_with_out(self, UnaryOp::Abs, |x| x._abs(), |x| x._abs(), Some(out))
     }
 
     fn ceil(&self) -> std::result::Result<Self::Output, TensorError> {
@@ -84,8 +106,8 @@ where
         let max_vec = T::Vec::splat(max);
         unary_fn_with_out(
             self,
-            |x| x._clamp(min_vec, max_vec),
-            |x| <T as NormalOut<T>>::_clamp(x, min, max),
+            move |x| x._clamp(min_vec, max_vec),
+            move |x| <T as NormalOut<T>>::_clamp(x, min, max),
             None::<Self::Output>,
         )
     }
@@ -102,8 +124,8 @@ where
         let max_vec = T::Vec::splat(max);
         unary_fn_with_out(
             self,
-            |x| x._clamp(min_vec, max_vec),
-            |x| <T as NormalOut<T>>::_clamp(x, min, max),
+            move |x| x._clamp(min_vec, max_vec),
+            move |x| <T as NormalOut<T>>::_clamp(x, min, max),
             Some(out),
         )
     }
@@ -118,25 +140,37 @@ where
     }
 
     fn neg(&self) -> std::result::Result<Self::Output, TensorError> {
-        unary_fn_with_out(self, |x| x._neg(), |x| x._neg(), None::<Self::Output>)
+        unary_op_with_out(
+            self,
+            UnaryOp::Neg,
+            |x| x._neg(),
+            |x| x._neg(),
+            None::<Self::Output>,
+        )
     }
 
     fn neg_<U>(&self, out: U) -> std::result::Result<Self::Output, TensorError>
     where
         U: Borrow<Self::InplaceOutput>,
     {
-        unary_fn_with_out(self, |x| x._neg(), |x| x._neg(), Some(out))
+        unary_op_with_out(self, UnaryOp::Neg, |x| x._neg(), |x| x._neg(), Some(out))
     }
 
     fn relu(&self) -> std::result::Result<Self::Output, TensorError> {
-        unary_fn_with_out(self, |x| x._relu(), |x| x._relu(), None::<Self::Output>)
+        unary_op_with_out(
+            self,
+            UnaryOp::Relu,
+            |x| x._relu(),
+            |x| x._relu(),
+            None::<Self::Output>,
+        )
     }
 
     fn relu_<U>(&self, out: U) -> std::result::Result<Self::Output, TensorError>
     where
         U: Borrow<Self::InplaceOutput>,
     {
-        unary_fn_with_out(self, |x| x._relu(), |x| x._relu(), Some(out))
+        unary_op_with_out(self, UnaryOp::Relu, |x| x._relu(), |x| x._relu(), Some(out))
     }
 
     fn leaky_relu(
@@ -146,8 +180,8 @@ where
         let alpha_vec = T::Vec::splat(alpha);
         unary_fn_with_out(
             self,
-            |x| x._leaky_relu(alpha_vec),
-            |x| x._leaky_relu(alpha),
+            move |x| x._leaky_relu(alpha_vec),
+            move |x| x._leaky_relu(alpha),
             None::<Self::Output>,
         )
     }
@@ -163,8 +197,8 @@ where
         let alpha_vec = T::Vec::splat(alpha);
         unary_fn_with_out(
             self,
-            |x| x._leaky_relu(alpha_vec),
-            |x| x._leaky_relu(alpha),
+            move |x| x._leaky_relu(alpha_vec),
+            move |x| x._leaky_relu(alpha),
             Some(out),
         )
     }
@@ -52,6 +52,7 @@ where
             steps,
             padding,
             dilation,
+            T::ZERO,
             |a: T, b: T| a._add(b),
             |a: T::Vec, b: T::Vec| a._add(b),
             |a: T| a._div(kernel_size),
@@ -64,6 +65,7 @@ where
         adaptive_pooling_template(
             self,
             output_size,
+            T::ZERO,
             |a: T, b: T| a._add(b),
             |a: T::Vec, b: T::Vec| a._add(b),
             |a: T, kernel_size: <T as FloatOutBinary>::Output| a._div(kernel_size),
@@ -96,6 +98,7 @@ where
             steps,
             padding,
             dilation,
+            T::NEG_INF,
             |a, b| a._max(b),
             |a, b| a._max(b),
             |a| a,
@@ -111,6 +114,7 @@ where
         adaptive_pooling_template(
             self,
             output_size,
+            T::NEG_INF,
             |a, b| a._max(b),
             |a, b| a._max(b),
             |a, _| a,
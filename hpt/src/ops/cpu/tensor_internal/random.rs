@@ -1,4 +1,4 @@
-use crate::{backend::Cpu, tensor_base::_Tensor};
+use crate::{backend::Cpu, context::ensure_deterministic, tensor_base::_Tensor};
 use hpt_common::{error::base::TensorError, shape::shape::Shape};
 use hpt_traits::{
     random::Random,
@@ -24,6 +24,7 @@ where
 {
     type Meta = T;
     fn randn<S: Into<Shape>>(shape: S) -> Result<Self, TensorError> {
+        ensure_deterministic("randn")?;
         let res_shape = Shape::from(shape.into());
         let mut ret = _Tensor::<T, Cpu, DEVICE>::empty(res_shape)?;
         let normal = Normal::new(T::from(0.0).unwrap(), T::from(1.0).unwrap())?;
@@ -46,6 +47,7 @@ where
         low: Self::Meta,
         high: Self::Meta,
     ) -> Result<Self, TensorError> {
+        ensure_deterministic("rand")?;
         let res_shape = Shape::from(shape.into());
         let mut ret = _Tensor::<T, Cpu, DEVICE>::empty(res_shape)?;
         let normal = Uniform::new(low, high);
@@ -64,6 +66,7 @@ where
     }
 
     fn beta<S: Into<Shape>>(a: Self::Meta, b: Self::Meta, shape: S) -> Result<Self, TensorError> {
+        ensure_deterministic("beta")?;
         let res_shape = Shape::from(shape.into());
         let mut ret = _Tensor::<T, Cpu, DEVICE>::empty(res_shape)?;
         let normal = rand_distr::Beta::new(a, b)?;
@@ -82,6 +85,7 @@ where
     }
 
     fn chisquare<S: Into<Shape>>(df: Self::Meta, shape: S) -> Result<Self, TensorError> {
+        ensure_deterministic("chisquare")?;
         let res_shape = Shape::from(shape.into());
         let mut ret = _Tensor::<T, Cpu, DEVICE>::empty(res_shape)?;
         let normal = rand_distr::ChiSquared::new(df)?;
@@ -100,6 +104,7 @@ where
     }
 
     fn exponential<S: Into<Shape>>(lambda: Self::Meta, shape: S) -> Result<Self, TensorError> {
+        ensure_deterministic("exponential")?;
         let res_shape = Shape::from(shape.into());
         let mut ret = _Tensor::<T, Cpu, DEVICE>::empty(res_shape)?;
         let normal = rand_distr::Exp::new(lambda)?;
@@ -122,6 +127,7 @@ where
         scale: Self::Meta,
         shape: S,
     ) -> Result<Self, TensorError> {
+        ensure_deterministic("gamma")?;
         let res_shape = Shape::from(shape.into());
         let mut ret = _Tensor::<T, Cpu, DEVICE>::empty(res_shape)?;
         let normal = rand_distr::Gamma::new(gamma_shape, scale)?;
@@ -144,6 +150,7 @@ where
         beta: Self::Meta,
         shape: S,
     ) -> Result<Self, TensorError> {
+        ensure_deterministic("gumbel")?;
         let res_shape = Shape::from(shape.into());
         let mut ret = _Tensor::<T, Cpu, DEVICE>::empty(res_shape)?;
         let normal = rand_distr::Gumbel::new(mu, beta)?;
@@ -166,6 +173,7 @@ where
         std: Self::Meta,
         shape: S,
     ) -> Result<Self, TensorError> {
+        ensure_deterministic("lognormal")?;
         let res_shape = Shape::from(shape.into());
         let mut ret = _Tensor::<T, Cpu, DEVICE>::empty(res_shape)?;
         let normal = rand_distr::LogNormal::new(mean, std)?;
@@ -188,6 +196,7 @@ where
         std: Self::Meta,
         shape: S,
     ) -> Result<Self, TensorError> {
+        ensure_deterministic("normal_gaussian")?;
         let res_shape = Shape::from(shape.into());
         let mut ret = _Tensor::<T, Cpu, DEVICE>::empty(res_shape)?;
         let normal = NormalInverseGaussian::new(mean, std)?;
@@ -209,6 +218,7 @@ where
         a: Self::Meta,
         shape: S,
     ) -> Result<Self, TensorError> {
+        ensure_deterministic("pareto")?;
         let res_shape = Shape::from(shape.into());
         let mut ret = _Tensor::<T, Cpu, DEVICE>::empty(res_shape)?;
         let pareto = rand_distr::Pareto::new(a, pareto_shape)?;
@@ -226,6 +236,7 @@ where
     }
 
     fn poisson<S: Into<Shape>>(lambda: Self::Meta, shape: S) -> Result<Self, TensorError> {
+        ensure_deterministic("poisson")?;
         let res_shape = Shape::from(shape.into());
         let mut ret = _Tensor::<T, Cpu, DEVICE>::empty(res_shape)?;
         let poisson = rand_distr::Poisson::new(lambda)?;
@@ -247,6 +258,7 @@ where
         b: Self::Meta,
         shape: S,
     ) -> Result<Self, TensorError> {
+        ensure_deterministic("weibull")?;
         let res_shape = Shape::from(shape.into());
         let mut ret = _Tensor::<T, Cpu, DEVICE>::empty(res_shape)?;
         let weibull = rand_distr::Weibull::new(a, b)?;
@@ -264,6 +276,7 @@ where
     }
 
     fn zipf<S: Into<Shape>>(n: u64, a: Self::Meta, shape: S) -> Result<Self, TensorError> {
+        ensure_deterministic("zipf")?;
         let res_shape = Shape::from(shape.into());
         let mut ret = _Tensor::<T, Cpu, DEVICE>::empty(res_shape)?;
         let zipf = rand_distr::Zipf::new(n, a)?;
@@ -286,6 +299,7 @@ where
         mode: Self::Meta,
         shape: S,
     ) -> Result<Self, TensorError> {
+        ensure_deterministic("triangular")?;
         let res_shape = Shape::from(shape.into());
         let mut ret = _Tensor::<T, Cpu, DEVICE>::empty(res_shape)?;
         let triangular = rand_distr::Triangular::new(low, high, mode)?;
@@ -312,6 +326,7 @@ where
         T: Cast<f64>,
         bool: Cast<T>,
     {
+        ensure_deterministic("bernoulli")?;
         let res_shape = Shape::from(shape.into());
         let mut ret = _Tensor::<T, Cpu, DEVICE>::empty(res_shape)?;
         let bernoulli = rand_distr::Bernoulli::new(p.cast())?;
@@ -338,6 +353,7 @@ where
     where
         <T as SampleUniform>::Sampler: Sync,
     {
+        ensure_deterministic("randint")?;
         let res_shape = Shape::from(shape.into());
         let mut ret = _Tensor::<T, Cpu, DEVICE>::empty(res_shape)?;
         let normal = Uniform::new(low, high);
@@ -1,5 +1,6 @@
+use crate::context::with_thread_pool;
 use crate::tensor_base::_Tensor;
-use crate::{Cpu, THREAD_POOL};
+use crate::Cpu;
 use hpt_common::error::param::ParamError;
 use hpt_common::error::shape::ShapeError;
 use hpt_common::prg_update::next_sub1;
@@ -221,7 +222,7 @@ impl<T: CommonBounds, const DEVICE: usize> ShapeManipulate for _Tensor<T, Cpu, D
             .iter()
             .map(|x| (*x).clone())
             .collect::<Vec<_Tensor<T, Cpu, DEVICE>>>();
-        THREAD_POOL.with_borrow_mut(|pool| {
+        with_thread_pool(|pool| {
             let num_threads: usize;
             if length < pool.max_count() {
                 num_threads = length;
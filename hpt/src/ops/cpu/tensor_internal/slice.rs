@@ -39,7 +39,8 @@ where
             index,
             1,
         )?;
-        let res_ptr: *mut T = unsafe { self.data.ptr.offset(offset as isize) };
+        // a tensor created in lazy mode has no memory behind its data pointer, so the offset must not assume one
+        let res_ptr: *mut T = self.data.ptr.wrapping_offset(offset as isize);
         #[cfg(feature = "bound_check")]
         {
             if offset < 0 || offset >= (self.data.len as i64) {
@@ -75,7 +76,7 @@ where
     /// # Returns
     ///
     /// Returns a new `_Tensor` referencing the specified slice of memory.
-    fn from_slice(
+    pub(crate) fn from_slice(
         &self,
         ptr: Pointer<T>,
         shape: Vec<i64>,
@@ -1,4 +1,4 @@
-use hpt_common::error::base::TensorError;
+use hpt_common::error::{base::TensorError, param::ParamError};
 use hpt_traits::{CommonBounds, TensorCreator, TensorLike, WindowOps};
 use hpt_types::{
     dtype::{FloatConst, TypeCommon},
@@ -11,6 +11,7 @@ use rayon::{
     slice::ParallelSliceMut,
 };
 use std::ops::{Mul, Sub};
+use std::panic::Location;
 
 use crate::{tensor_base::_Tensor, Cpu};
 
@@ -75,6 +76,134 @@ where
             });
         Ok(ret)
     }
+
+    #[track_caller]
+    fn kaiser_window(
+        window_length: i64,
+        beta: f64,
+        periodic: bool,
+    ) -> Result<Self::Output, TensorError> {
+        let i0_beta = bessel_i0(beta);
+        __window(window_length, periodic, |n, m| {
+            let r = 2.0 * n / m - 1.0;
+            bessel_i0(beta * (1.0 - r * r).max(0.0).sqrt()) / i0_beta
+        })
+    }
+
+    #[track_caller]
+    fn bartlett_window(window_length: i64, periodic: bool) -> Result<Self::Output, TensorError> {
+        __window(window_length, periodic, |n, m| {
+            1.0 - (2.0 * n / m - 1.0).abs()
+        })
+    }
+
+    #[track_caller]
+    fn gaussian_window(
+        window_length: i64,
+        std: f64,
+        periodic: bool,
+    ) -> Result<Self::Output, TensorError> {
+        if std.is_nan() || std <= 0.0 {
+            return Err(ParamError::InvalidWindowParam {
+                message: format!("std of the gaussian window must be positive, got {}", std),
+                location: Location::caller(),
+            }
+            .into());
+        }
+        __window(window_length, periodic, |n, m| {
+            let x = (n - m / 2.0) / std;
+            (-0.5 * x * x).exp()
+        })
+    }
+
+    #[track_caller]
+    fn tukey_window(
+        window_length: i64,
+        alpha: f64,
+        periodic: bool,
+    ) -> Result<Self::Output, TensorError> {
+        let alpha = alpha.clamp(0.0, 1.0);
+        __window(window_length, periodic, |n, m| {
+            let x = n / m;
+            if x < alpha / 2.0 {
+                0.5 * (1.0 - (std::f64::consts::TAU * x / alpha).cos())
+            } else if x > 1.0 - alpha / 2.0 {
+                0.5 * (1.0 - (std::f64::consts::TAU * (1.0 - x) / alpha).cos())
+            } else {
+                1.0
+            }
+        })
+    }
+
+    #[track_caller]
+    fn general_cosine_window(
+        window_length: i64,
+        coeffs: &[f64],
+        periodic: bool,
+    ) -> Result<Self::Output, TensorError> {
+        __window(window_length, periodic, |n, m| {
+            coeffs
+                .iter()
+                .enumerate()
+                .map(|(k, a)| {
+                    let a = if k % 2 == 0 { *a } else { -a };
+                    a * (std::f64::consts::TAU * k as f64 * n / m).cos()
+                })
+                .sum()
+        })
+    }
+}
+
+/// the modified Bessel function of the first kind of order 0, `sum((x / 2)^(2k) / (k!)^2)`
+fn bessel_i0(x: f64) -> f64 {
+    let q = x * x / 4.0;
+    let (mut sum, mut term, mut k) = (1.0, 1.0, 0.0);
+    while term > sum * f64::EPSILON {
+        k += 1.0;
+        term *= q / (k * k);
+        sum += term;
+    }
+    sum
+}
+
+/// a window of `window_length` elements computed in `f64`, `f(n, m)` is the element `n` where `m` is `window_length` for
+/// periodic windows and `window_length - 1` for symmetric windows, a window of length 1 is `[1]`
+#[track_caller]
+fn __window<O, F, const DEVICE: usize>(
+    window_length: i64,
+    periodic: bool,
+    f: F,
+) -> Result<_Tensor<O, Cpu, DEVICE>, TensorError>
+where
+    O: CommonBounds,
+    f64: Cast<O>,
+    F: Fn(f64, f64) -> f64 + Sync + Send,
+{
+    if window_length < 0 {
+        return Err(ParamError::InvalidWindowParam {
+            message: format!("window length must be non negative, got {}", window_length),
+            location: Location::caller(),
+        }
+        .into());
+    }
+    let m = (if periodic {
+        window_length
+    } else {
+        window_length - 1
+    }) as f64;
+    let mut ret = _Tensor::<O, Cpu, DEVICE>::empty(&[window_length])?;
+    ret.as_raw_mut()
+        .par_iter_mut()
+        .enumerate()
+        .for_each(|(n, x)| {
+            let val = if window_length == 1 {
+                1.0
+            } else {
+                f(n as f64, m)
+            };
+            *x = val.cast();
+        });
+    Ok(ret)
 }
 
 #[track_caller]
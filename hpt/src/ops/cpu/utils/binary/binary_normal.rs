@@ -1,14 +1,20 @@
 use crate::backend::Cpu;
+use crate::lazy::record::{is_recording, record_binary};
+use crate::ops::cpu::utils::dispatch::{
+    load_partial, par_ranges, par_ranges_dispatch, store_partial, RangeKernel,
+};
 use crate::tensor_base::_Tensor;
 use crate::Tensor;
 use hpt_common::error::base::TensorError;
 use hpt_common::error::shape::ShapeError;
+use hpt_common::utils::pointer::Pointer;
 use hpt_iterator::iterator_traits::ParStridedIteratorZip;
 use hpt_iterator::TensorIterator;
 use hpt_traits::tensor::CommonBounds;
 use hpt_traits::tensor::TensorCreator;
 use hpt_traits::tensor::TensorInfo;
 use hpt_traits::TensorLike;
+use hpt_types::dispatch::{dispatch_kernel, Simd, SimdElement, SimdKernel, SimdVec};
 use hpt_types::dtype::TypeCommon;
 use rayon::iter::{
     IndexedParallelIterator, IntoParallelRefIterator, IntoParallelRefMutIterator, ParallelIterator,
@@ -43,6 +49,10 @@ use std::borrow::Borrow;
 ///
 /// If the vector sizes of the input tensors match and SIMD is enabled, the `f2` function is applied to
 /// perform vectorized operations for faster computation. If not, the scalar function `f` is applied to each element.
+///
+/// # Lazy Mode
+///
+/// In lazy mode, the operation is recorded unless `out` is provided.
 #[track_caller]
 pub(crate) fn binary_fn_with_out_simd<A, B, O, K, F, F2, const DEVICE: usize>(
     lhs: &_Tensor<A, Cpu, DEVICE>,
@@ -51,6 +61,96 @@ pub(crate) fn binary_fn_with_out_simd<A, B, O, K, F, F2, const DEVICE: usize>(
     f2: F2,
     out: Option<O>,
 ) -> std::result::Result<_Tensor<K, Cpu, DEVICE>, TensorError>
+where
+    A: CommonBounds,
+    B: CommonBounds,
+    O: Borrow<_Tensor<K, Cpu, DEVICE>>,
+    K: CommonBounds,
+    F: Fn(A, B) -> K + Sync + Send + Copy + 'static,
+    F2: Fn(<A as TypeCommon>::Vec, <B as TypeCommon>::Vec) -> <K as TypeCommon>::Vec
+        + Sync
+        + Send
+        + Copy
+        + 'static,
+{
+    if out.is_none() && is_recording() {
+        return record_binary(lhs, rhs, f, f2);
+    }
+    binary_kernel(lhs, rhs, None, f, f2, out)
+}
+
+/// The binary operations with a kernel on the vectors of every simd level, the f32 and f64 tensors
+/// run them instead of `f2` when the inputs are contiguous
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum BinaryOp {
+    Add,
+    Sub,
+    Mul,
+    Div,
+}
+
+/// `binary_fn_with_out_simd` for an operation of [`BinaryOp`], `f` and `f2` must compute the same
+/// operation
+#[track_caller]
+pub(crate) fn binary_op_with_out_simd<A, B, O, K, F, F2, const DEVICE: usize>(
+    lhs: &_Tensor<A, Cpu, DEVICE>,
+    rhs: &_Tensor<B, Cpu, DEVICE>,
+    op: BinaryOp,
+    f: F,
+    f2: F2,
+    out: Option<O>,
+) -> std::result::Result<_Tensor<K, Cpu, DEVICE>, TensorError>
+where
+    A: CommonBounds,
+    B: CommonBounds,
+    O: Borrow<_Tensor<K, Cpu, DEVICE>>,
+    K: CommonBounds,
+    F: Fn(A, B) -> K + Sync + Send + Copy + 'static,
+    F2: Fn(<A as TypeCommon>::Vec, <B as TypeCommon>::Vec) -> <K as TypeCommon>::Vec
+        + Sync
+        + Send
+        + Copy
+        + 'static,
+{
+    if out.is_none() && is_recording() {
+        return record_binary(lhs, rhs, f, f2);
+    }
+    binary_kernel(lhs, rhs, Some(op), f, f2, out)
+}
+
+/// Performs a binary operation on two tensors with an optional output tensor, the operation is always executed even in
+/// lazy mode. See `binary_fn_with_out_simd`
+#[track_caller]
+pub(crate) fn binary_kernel_with_out<A, B, O, K, F, F2, const DEVICE: usize>(
+    lhs: &_Tensor<A, Cpu, DEVICE>,
+    rhs: &_Tensor<B, Cpu, DEVICE>,
+    f: F,
+    f2: F2,
+    out: Option<O>,
+) -> std::result::Result<_Tensor<K, Cpu, DEVICE>, TensorError>
+where
+    A: CommonBounds,
+    B: CommonBounds,
+    O: Borrow<_Tensor<K, Cpu, DEVICE>>,
+    K: CommonBounds,
+    F: Fn(A, B) -> K + Sync + Send + Copy,
+    F2: Fn(<A as TypeCommon>::Vec, <B as TypeCommon>::Vec) -> <K as TypeCommon>::Vec
+        + Sync
+        + Send
+        + Copy,
+{
+    binary_kernel(lhs, rhs, None, f, f2, out)
+}
+
+#[track_caller]
+fn binary_kernel<A, B, O, K, F, F2, const DEVICE: usize>(
+    lhs: &_Tensor<A, Cpu, DEVICE>,
+    rhs: &_Tensor<B, Cpu, DEVICE>,
+    op: Option<BinaryOp>,
+    f: F,
+    f2: F2,
+    out: Option<O>,
+) -> std::result::Result<_Tensor<K, Cpu, DEVICE>, TensorError>
 where
     A: CommonBounds,
     B: CommonBounds,
@@ -62,11 +162,8 @@ where
         + Send
         + Copy,
 {
-    use hpt_types::traits::*;
-    use rayon::slice::{ParallelSlice, ParallelSliceMut};
     if lhs.size() == 1 {
         let val = lhs.as_raw()[0];
-        let val_vec = <A as TypeCommon>::Vec::splat(val);
         let mut res = if let Some(out) = out {
             ShapeError::check_inplace_out_layout_valid(rhs.shape(), &out.borrow().layout())?;
             let out: &_Tensor<K, Cpu, DEVICE> = out.borrow();
@@ -75,53 +172,7 @@ where
             _Tensor::<K, Cpu, DEVICE>::empty(rhs.shape())?
         };
         if rhs.is_contiguous() {
-            if <A as TypeCommon>::Vec::SIZE == <B as TypeCommon>::Vec::SIZE
-                && <B as TypeCommon>::Vec::SIZE == <K as TypeCommon>::Vec::SIZE
-            {
-                let remain = res.size() % <A as TypeCommon>::Vec::SIZE;
-                res.as_raw_mut()
-                    .par_chunks_exact_mut(<A as TypeCommon>::Vec::SIZE)
-                    .zip(rhs.as_raw().par_chunks_exact(<A as TypeCommon>::Vec::SIZE))
-                    .for_each(|(a, b)| {
-                        let inp = unsafe { <B as TypeCommon>::Vec::from_ptr(b.as_ptr()) };
-                        let res: *const K = f2(val_vec, inp).as_ptr();
-                        unsafe {
-                            std::ptr::copy_nonoverlapping(
-                                res,
-                                a.as_mut_ptr(),
-                                <A as TypeCommon>::Vec::SIZE,
-                            );
-                        }
-                    });
-                if remain > 0 {
-                    let ret_size = res.size();
-                    res.as_raw_mut()[ret_size - remain..]
-                        .iter_mut()
-                        .zip(rhs.as_raw()[ret_size - remain..].iter())
-                        .for_each(|(a, b)| {
-                            *a = f(val, *b);
-                        });
-                }
-            } else {
-                res.as_raw_mut()
-                    .par_chunks_exact_mut(<K as TypeCommon>::Vec::SIZE)
-                    .zip(rhs.as_raw().par_chunks_exact(<K as TypeCommon>::Vec::SIZE))
-                    .for_each(|(a, b)| {
-                        a.iter_mut().zip(b.iter()).for_each(|(a, b)| {
-                            *a = f(val, *b);
-                        });
-                    });
-                let remain = res.size() % <K as TypeCommon>::Vec::SIZE;
-                if remain > 0 {
-                    let ret_size = res.size();
-                    res.as_raw_mut()[ret_size - remain..]
-                        .iter_mut()
-                        .zip(rhs.as_raw()[ret_size - remain..].iter())
-                        .for_each(|(a, b)| {
-                            *a = f(val, *b);
-                        });
-                }
-            }
+            contiguous_binary(&res, lhs, true, rhs, false, op, f, f2);
         } else {
             res.par_iter_mut().zip(rhs.par_iter()).for_each(|(a, b)| {
                 *a = f(val, b);
@@ -130,7 +181,6 @@ where
         Ok(res)
     } else if rhs.size() == 1 {
         let val = rhs.as_raw()[0];
-        let val_vec = <B as TypeCommon>::Vec::splat(val);
         let mut res = if let Some(out) = out {
             ShapeError::check_inplace_out_layout_valid(lhs.shape(), &out.borrow().layout())?;
             let out: &_Tensor<K, Cpu, DEVICE> = out.borrow();
@@ -139,53 +189,7 @@ where
             _Tensor::<K, Cpu, DEVICE>::empty(lhs.shape())?
         };
         if lhs.is_contiguous() {
-            if <A as TypeCommon>::Vec::SIZE == <B as TypeCommon>::Vec::SIZE
-                && <B as TypeCommon>::Vec::SIZE == <K as TypeCommon>::Vec::SIZE
-            {
-                let remain = res.size() % <A as TypeCommon>::Vec::SIZE;
-                res.as_raw_mut()
-                    .par_chunks_exact_mut(<A as TypeCommon>::Vec::SIZE)
-                    .zip(lhs.as_raw().par_chunks_exact(<A as TypeCommon>::Vec::SIZE))
-                    .for_each(|(a, lhs)| {
-                        let inp = unsafe { <A as TypeCommon>::Vec::from_ptr(lhs.as_ptr()) };
-                        let res: *const K = f2(inp, val_vec).as_ptr();
-                        unsafe {
-                            std::ptr::copy_nonoverlapping(
-                                res,
-                                a.as_mut_ptr(),
-                                <A as TypeCommon>::Vec::SIZE,
-                            );
-                        }
-                    });
-                if remain > 0 {
-                    let ret_size = res.size();
-                    res.as_raw_mut()[ret_size - remain..]
-                        .iter_mut()
-                        .zip(lhs.as_raw()[ret_size - remain..].iter())
-                        .for_each(|(a, lhs)| {
-                            *a = f(*lhs, val);
-                        });
-                }
-            } else {
-                res.as_raw_mut()
-                    .par_chunks_exact_mut(<K as TypeCommon>::Vec::SIZE)
-                    .zip(lhs.as_raw().par_chunks_exact(<K as TypeCommon>::Vec::SIZE))
-                    .for_each(|(a, lhs)| {
-                        a.iter_mut().zip(lhs.iter()).for_each(|(a, lhs)| {
-                            *a = f(*lhs, val);
-                        });
-                    });
-                let remain = res.size() % <K as TypeCommon>::Vec::SIZE;
-                if remain > 0 {
-                    let ret_size = res.size();
-                    res.as_raw_mut()[ret_size - remain..]
-                        .iter_mut()
-                        .zip(lhs.as_raw()[ret_size - remain..].iter())
-                        .for_each(|(a, lhs)| {
-                            *a = f(*lhs, val);
-                        });
-                }
-            }
+            contiguous_binary(&res, lhs, false, rhs, true, op, f, f2);
         } else {
             res.par_iter_mut().zip(lhs.par_iter()).for_each(|(a, lhs)| {
                 *a = f(lhs, val);
@@ -194,55 +198,14 @@ where
         Ok(res)
     } else {
         if rhs.is_contiguous() && lhs.is_contiguous() && rhs.shape() == lhs.shape() {
-            let mut ret = if let Some(out) = out {
+            let ret = if let Some(out) = out {
                 ShapeError::check_inplace_out_layout_valid(rhs.shape(), &out.borrow().layout())?;
                 let out: &_Tensor<K, Cpu, DEVICE> = out.borrow();
                 out.clone()
             } else {
                 _Tensor::<K, Cpu, DEVICE>::empty(rhs.shape())?
             };
-            if <A as TypeCommon>::Vec::SIZE == <B as TypeCommon>::Vec::SIZE
-                && <B as TypeCommon>::Vec::SIZE == <K as TypeCommon>::Vec::SIZE
-            {
-                let remain = ret.size() % <K as TypeCommon>::Vec::SIZE;
-                ret.as_raw_mut()
-                    .par_chunks_exact_mut(<K as TypeCommon>::Vec::SIZE)
-                    .zip(lhs.as_raw().par_chunks_exact(<K as TypeCommon>::Vec::SIZE))
-                    .zip(rhs.as_raw().par_chunks_exact(<K as TypeCommon>::Vec::SIZE))
-                    .for_each(|((ret, lhs), rhs)| {
-                        let a = unsafe { <A as TypeCommon>::Vec::from_ptr(lhs.as_ptr()) };
-                        let b = unsafe { <B as TypeCommon>::Vec::from_ptr(rhs.as_ptr()) };
-                        let res = f2(a, b);
-                        unsafe {
-                            std::ptr::copy_nonoverlapping(
-                                res.as_ptr(),
-                                ret.as_mut_ptr(),
-                                <K as TypeCommon>::Vec::SIZE,
-                            );
-                        }
-                    });
-                if remain > 0 {
-                    let ret_size = ret.size();
-                    ret.as_raw_mut()[ret_size - remain..]
-                        .iter_mut()
-                        .zip(lhs.as_raw()[ret_size - remain..].iter())
-                        .zip(rhs.as_raw()[ret_size - remain..].iter())
-                        .for_each(|((a, &lhs), &rhs)| {
-                            *a = f(lhs, rhs);
-                        });
-                }
-            } else {
-                let min_len: usize =
-                    ret.size() / (((rayon::current_num_threads() as f64) * 1.3) as usize);
-                ret.as_raw_mut()
-                    .par_iter_mut()
-                    .with_min_len(min_len)
-                    .zip(lhs.as_raw().par_iter().with_min_len(min_len))
-                    .zip(rhs.as_raw().par_iter().with_min_len(min_len))
-                    .for_each(|((ret, &lhs), &rhs)| {
-                        *ret = f(lhs, rhs);
-                    });
-            }
+            contiguous_binary(&ret, lhs, false, rhs, false, op, f, f2);
             Ok(ret)
         } else {
             let ret = lhs
@@ -250,11 +213,175 @@ where
                 .zip(rhs.par_iter())
                 .strided_map(|(res, (x, y))| *res = f(x, y))
                 .collect::<_Tensor<K, Cpu, DEVICE>>();
+            if let Some(out) = out {
+                // the inputs can alias `out`, the result is only copied once fully computed
+                ShapeError::check_inplace_out_layout_valid(ret.shape(), &out.borrow().layout())?;
+                let mut out: _Tensor<K, Cpu, DEVICE> = out.borrow().clone();
+                out.as_raw_mut().copy_from_slice(ret.as_raw());
+                return Ok(out);
+            }
             Ok(ret)
         }
     }
 }
 
+/// computes `out[i] = f(lhs[i], rhs[i])` over contiguous tensors, a `scalar` side is broadcasted from its
+/// first element. Every thread runs its range with the code generated for the current simd level, `f2`
+/// is used when the three vector types have the same length. An `op` on f32 or f64 runs on the vectors
+/// of the simd level instead.
+#[allow(clippy::too_many_arguments)]
+fn contiguous_binary<A, B, K, F, F2, const DEVICE: usize>(
+    out: &_Tensor<K, Cpu, DEVICE>,
+    lhs: &_Tensor<A, Cpu, DEVICE>,
+    lhs_scalar: bool,
+    rhs: &_Tensor<B, Cpu, DEVICE>,
+    rhs_scalar: bool,
+    op: Option<BinaryOp>,
+    f: F,
+    f2: F2,
+) where
+    A: CommonBounds,
+    B: CommonBounds,
+    K: CommonBounds,
+    F: Fn(A, B) -> K + Sync + Send + Copy,
+    F2: Fn(<A as TypeCommon>::Vec, <B as TypeCommon>::Vec) -> <K as TypeCommon>::Vec
+        + Sync
+        + Send
+        + Copy,
+{
+    use hpt_types::traits::*;
+    let (out_ptr, lhs_ptr, rhs_ptr) = (out.ptr(), lhs.ptr(), rhs.ptr());
+    if let Some(op) = op.filter(|_| A::STR == K::STR && B::STR == K::STR) {
+        macro_rules! level_kernel {
+            ($t:ty) => {
+                return dispatch_kernel(ContiguousBinary::<$t> {
+                    op,
+                    out: out_ptr.cast(),
+                    lhs: lhs_ptr.cast(),
+                    rhs: rhs_ptr.cast(),
+                    lhs_scalar,
+                    rhs_scalar,
+                    len: out.size(),
+                })
+            };
+        }
+        match K::STR {
+            "f32" => level_kernel!(f32),
+            "f64" => level_kernel!(f64),
+            _ => {}
+        }
+    }
+    let simd = <A as TypeCommon>::Vec::SIZE == <B as TypeCommon>::Vec::SIZE
+        && <B as TypeCommon>::Vec::SIZE == <K as TypeCommon>::Vec::SIZE;
+    let vec_size = <K as TypeCommon>::Vec::SIZE;
+    let (lhs_step, rhs_step) = (!lhs_scalar as usize, !rhs_scalar as usize);
+    let lhs_vec = <A as TypeCommon>::Vec::splat(if lhs_scalar { lhs.as_raw()[0] } else { A::ZERO });
+    let rhs_vec = <B as TypeCommon>::Vec::splat(if rhs_scalar { rhs.as_raw()[0] } else { B::ZERO });
+    par_ranges_dispatch(out.size(), vec_size, |start, end| unsafe {
+        let (out, lhs, rhs) = (out_ptr.get_ptr(), lhs_ptr.get_ptr(), rhs_ptr.get_ptr());
+        let mut i = start;
+        if simd {
+            while i + vec_size <= end {
+                let a = if lhs_scalar {
+                    lhs_vec
+                } else {
+                    <A as TypeCommon>::Vec::from_ptr(lhs.add(i))
+                };
+                let b = if rhs_scalar {
+                    rhs_vec
+                } else {
+                    <B as TypeCommon>::Vec::from_ptr(rhs.add(i))
+                };
+                let res = f2(a, b);
+                std::ptr::copy_nonoverlapping(res.as_ptr(), out.add(i), vec_size);
+                i += vec_size;
+            }
+        }
+        for i in i..end {
+            out.add(i)
+                .write(f(*lhs.add(i * lhs_step), *rhs.add(i * rhs_step)));
+        }
+    });
+}
+
+/// `contiguous_binary` of a [`BinaryOp`] on the vectors of the simd level
+struct ContiguousBinary<T> {
+    op: BinaryOp,
+    out: Pointer<T>,
+    lhs: Pointer<T>,
+    rhs: Pointer<T>,
+    lhs_scalar: bool,
+    rhs_scalar: bool,
+    len: usize,
+}
+
+impl<T: SimdElement> SimdKernel for ContiguousBinary<T> {
+    type Output = ();
+    #[inline(always)]
+    fn run<S: Simd>(self, simd: S) {
+        par_ranges(simd, self.len, T::Vector::<S>::LANES, &self)
+    }
+}
+
+impl<T: SimdElement> RangeKernel for ContiguousBinary<T> {
+    #[inline(always)]
+    fn run<S: Simd>(&self, simd: S, start: usize, end: usize) {
+        match self.op {
+            BinaryOp::Add => self.run_op(simd, start, end, <T::Vector<S> as SimdVec<T>>::add),
+            BinaryOp::Sub => self.run_op(simd, start, end, <T::Vector<S> as SimdVec<T>>::sub),
+            BinaryOp::Mul => self.run_op(simd, start, end, <T::Vector<S> as SimdVec<T>>::mul),
+            BinaryOp::Div => self.run_op(simd, start, end, <T::Vector<S> as SimdVec<T>>::div),
+        }
+    }
+}
+
+impl<T: SimdElement> ContiguousBinary<T> {
+    #[inline(always)]
+    fn run_op<S, F>(&self, simd: S, start: usize, end: usize, op: F)
+    where
+        S: Simd,
+        F: Fn(T::Vector<S>, T::Vector<S>) -> T::Vector<S>,
+    {
+        let lanes = T::Vector::<S>::LANES;
+        unsafe {
+            let (out, lhs, rhs) = (self.out.get_ptr(), self.lhs.get_ptr(), self.rhs.get_ptr());
+            let (lhs_vec, rhs_vec) = (
+                T::Vector::<S>::splat(simd, *lhs),
+                T::Vector::<S>::splat(simd, *rhs),
+            );
+            let mut i = start;
+            while i + lanes <= end {
+                let a = if self.lhs_scalar {
+                    lhs_vec
+                } else {
+                    T::Vector::<S>::load(lhs.add(i))
+                };
+                let b = if self.rhs_scalar {
+                    rhs_vec
+                } else {
+                    T::Vector::<S>::load(rhs.add(i))
+                };
+                op(a, b).store(out.add(i));
+                i += lanes;
+            }
+            if i < end {
+                let remain = end - i;
+                let a = if self.lhs_scalar {
+                    lhs_vec
+                } else {
+                    load_partial::<T, S>(lhs.add(i), remain, *lhs.add(i))
+                };
+                let b = if self.rhs_scalar {
+                    rhs_vec
+                } else {
+                    load_partial::<T, S>(rhs.add(i), remain, *rhs.add(i))
+                };
+                store_partial::<T, S>(op(a, b), out.add(i), remain);
+            }
+        }
+    }
+}
+
 /// Perform binary operation with output tensor
 #[track_caller]
 pub fn binary_with_out<A, B, O, K, F, F2, const DEVICE: usize>(
@@ -276,7 +403,7 @@ where
         + Copy,
 {
     let out: Option<_Tensor<K, Cpu, DEVICE>> = out.map(|x| x.borrow().inner.as_ref().clone());
-    Ok(binary_fn_with_out_simd(lhs.inner.as_ref(), rhs.inner.as_ref(), f, f2, out)?.into())
+    Ok(binary_kernel_with_out(lhs.inner.as_ref(), rhs.inner.as_ref(), f, f2, out)?.into())
 }
 
 #[track_caller]
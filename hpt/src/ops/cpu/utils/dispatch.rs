@@ -0,0 +1,129 @@
+use hpt_types::dispatch::{dispatch_kernel, Simd, SimdElement, SimdKernel, SimdVec};
+use rayon::iter::{IntoParallelIterator, ParallelIterator};
+use std::mem::MaybeUninit;
+
+/// the most lanes a vector of any simd level has
+const MAX_LANES: usize = 16;
+
+/// A kernel run on a range of the elements by [`par_ranges`]
+///
+/// `run` is inlined into a function compiled with the target features of `S`, it has to be
+/// `#[inline(always)]` like the functions it calls on the vectors.
+pub(crate) trait RangeKernel: Sync {
+    /// process the elements `start..end`
+    fn run<S: Simd>(&self, simd: S, start: usize, end: usize);
+}
+
+/// split `0..len` into one range per thread, the ranges start at a multiple of `vec_size`, and run
+/// `kernel` on every range with the target features of `simd`
+#[inline(always)]
+pub(crate) fn par_ranges<S, K>(simd: S, len: usize, vec_size: usize, kernel: &K)
+where
+    S: Simd,
+    K: RangeKernel,
+{
+    struct Range<'a, K>(&'a K, usize, usize);
+    impl<K: RangeKernel> SimdKernel for Range<'_, K> {
+        type Output = ();
+        #[inline(always)]
+        fn run<S: Simd>(self, simd: S) {
+            self.0.run(simd, self.1, self.2)
+        }
+    }
+    if len == 0 {
+        return;
+    }
+    let per_thread = len
+        .div_ceil(rayon::current_num_threads())
+        .next_multiple_of(vec_size);
+    (0..len.div_ceil(per_thread)).into_par_iter().for_each(|i| {
+        let start = i * per_thread;
+        simd.vectorize(Range(kernel, start, (start + per_thread).min(len)))
+    });
+}
+
+/// [`par_ranges`] of a kernel on `T::Vec` with the code generated for the current simd level
+pub(crate) fn par_ranges_dispatch<F>(len: usize, vec_size: usize, f: F)
+where
+    F: Fn(usize, usize) + Sync + Send,
+{
+    struct FnRanges<F>(F);
+    impl<F: Fn(usize, usize) + Sync> RangeKernel for FnRanges<F> {
+        #[inline(always)]
+        fn run<S: Simd>(&self, _: S, start: usize, end: usize) {
+            (self.0)(start, end)
+        }
+    }
+    struct ParRanges<F>(usize, usize, F);
+    impl<F: Fn(usize, usize) + Sync + Send> SimdKernel for ParRanges<F> {
+        type Output = ();
+        #[inline(always)]
+        fn run<S: Simd>(self, simd: S) {
+            par_ranges(simd, self.0, self.1, &FnRanges(self.2))
+        }
+    }
+    dispatch_kernel(ParRanges(len, vec_size, f))
+}
+
+/// run `f` on every item of `iter` in parallel, `f` is a kernel on `T::Vec` run with the code
+/// generated for the current simd level
+pub(crate) fn par_for_each_dispatch<I, F>(iter: I, f: F)
+where
+    I: IntoParallelIterator,
+    F: Fn(I::Item) + Sync + Send,
+{
+    struct Item<'a, F, T>(&'a F, T);
+    impl<F: Fn(T), T> SimdKernel for Item<'_, F, T> {
+        type Output = ();
+        #[inline(always)]
+        fn run<S: Simd>(self, _: S) {
+            (self.0)(self.1)
+        }
+    }
+    struct ParForEach<I, F>(I, F);
+    impl<I: IntoParallelIterator, F: Fn(I::Item) + Sync + Send> SimdKernel for ParForEach<I, F> {
+        type Output = ();
+        #[inline(always)]
+        fn run<S: Simd>(self, simd: S) {
+            let f = &self.1;
+            self.0
+                .into_par_iter()
+                .for_each(|item| simd.vectorize(Item(f, item)));
+        }
+    }
+    dispatch_kernel(ParForEach(iter, f))
+}
+
+/// load the `len` elements at `ptr` into a vector, the lanes past `len` are set to `fill`
+///
+/// # Safety
+///
+/// `ptr` must be valid for `len` reads and `len` must not exceed the lanes of the vector
+#[inline(always)]
+pub(crate) unsafe fn load_partial<T: SimdElement, S: Simd>(
+    ptr: *const T,
+    len: usize,
+    fill: T,
+) -> T::Vector<S> {
+    debug_assert!(len <= T::Vector::<S>::LANES && T::Vector::<S>::LANES <= MAX_LANES);
+    let mut buf = [fill; MAX_LANES];
+    std::ptr::copy_nonoverlapping(ptr, buf.as_mut_ptr(), len);
+    T::Vector::<S>::load(buf.as_ptr())
+}
+
+/// store the first `len` lanes of `vec` to `ptr`
+///
+/// # Safety
+///
+/// `ptr` must be valid for `len` writes and `len` must not exceed the lanes of the vector
+#[inline(always)]
+pub(crate) unsafe fn store_partial<T: SimdElement, S: Simd>(
+    vec: T::Vector<S>,
+    ptr: *mut T,
+    len: usize,
+) {
+    debug_assert!(len <= T::Vector::<S>::LANES && T::Vector::<S>::LANES <= MAX_LANES);
+    let mut buf = MaybeUninit::<[T; MAX_LANES]>::uninit();
+    vec.store(buf.as_mut_ptr() as *mut T);
+    std::ptr::copy_nonoverlapping(buf.as_ptr() as *const T, ptr, len);
+}
@@ -0,0 +1,409 @@
+use crate::backend::Cpu;
+use crate::ops::cpu::utils::reduce::reduce::reduce;
+use crate::ops::cpu::utils::reduce::reduce_utils::rearrange_array;
+use crate::tensor_base::_Tensor;
+use crate::Tensor;
+use hpt_common::axis::axis::{process_axes, Axis};
+use hpt_common::error::base::TensorError;
+use hpt_common::error::shape::ShapeError;
+use hpt_common::layout::layout::Layout;
+use hpt_common::shape::shape_utils::{mt_intervals, predict_broadcast_shape, try_pad_shape};
+use hpt_common::slice::Slice;
+use hpt_common::strides::strides_utils::preprocess_strides;
+use hpt_common::utils::pointer::Pointer;
+use hpt_traits::tensor::{CommonBounds, TensorCreator, TensorInfo};
+use hpt_traits::{Matmul, ShapeManipulate, TensorLike};
+use hpt_types::dtype::TypeCommon;
+use hpt_types::traits::VecTrait;
+use hpt_types::{into_scalar::Cast, type_promote::NormalOut};
+use rayon::iter::{IndexedParallelIterator, IntoParallelIterator, ParallelIterator};
+use rayon::slice::ParallelSliceMut;
+
+/// strides of `layout` when it is broadcasted to a shape with `ndim` dimensions, broadcasted dims get a stride of 0
+fn broadcast_strides(layout: &Layout, ndim: usize) -> Vec<i64> {
+    preprocess_strides(&try_pad_shape(layout.shape(), ndim), layout.strides())
+}
+
+/// add `idx * strides[dim]` to the offset of every input
+#[inline(always)]
+fn advance(offsets: &mut [i64], strides: &[Vec<i64>], dim: usize, idx: i64) {
+    for (offset, strides) in offsets.iter_mut().zip(strides.iter()) {
+        *offset += idx * strides[dim];
+    }
+}
+
+/// number of elements of the tiles of the elementwise kernel [`fused_reduce`] computes before reducing them
+const FUSED_TILE: usize = 1 << 15;
+
+/// the vector at `offset` of `ptr`, its lanes are `stride` elements apart
+#[inline(always)]
+pub fn fused_load_vec<T: CommonBounds>(ptr: &Pointer<T>, offset: i64, stride: i64) -> T::Vec {
+    unsafe {
+        match stride {
+            1 => T::Vec::from_ptr(ptr.ptr.offset(offset as isize)),
+            0 => T::Vec::splat(*ptr.ptr.offset(offset as isize)),
+            _ => {
+                let mut vec = T::Vec::splat(T::ZERO);
+                let lanes = vec.as_mut_ptr();
+                for i in 0..T::Vec::SIZE {
+                    *lanes.add(i) = *ptr.ptr.offset((offset + i as i64 * stride) as isize);
+                }
+                vec
+            }
+        }
+    }
+}
+
+/// Reduce the output of an elementwise kernel without materializing it.
+///
+/// `layouts` are the layouts of the kernel inputs, they are broadcasted together. `load` computes one element
+/// of the kernel from the offsets of the inputs (in the same order as `layouts`), `load_vec` computes a vector of
+/// elements from the offsets of the first lanes and the distance between the lanes of every input. The kernel is
+/// computed in tiles of at most [`FUSED_TILE`] elements, each tile is folded along `axes` into the output by the
+/// SIMD reduce kernels with `combine` and `vec_combine`, starting from `init_val`.
+#[track_caller]
+pub fn fused_reduce<O, S, F, F2, F3, F4, const DEVICE: usize>(
+    layouts: &[&Layout],
+    axes: S,
+    keep_dims: bool,
+    init_val: O,
+    load: F,
+    load_vec: F2,
+    combine: F3,
+    vec_combine: F4,
+) -> std::result::Result<Tensor<O, Cpu, DEVICE>, TensorError>
+where
+    O: CommonBounds,
+    S: Into<Axis>,
+    F: Fn(&[i64]) -> O + Sync + Send,
+    F2: Fn(&[i64], &[i64]) -> O::Vec + Sync + Send,
+    F3: Fn(O, O) -> O + Sync + Send + 'static + Copy,
+    F4: Fn(O::Vec, O::Vec) -> O::Vec + Sync + Send + 'static + Copy,
+{
+    let mut layout = layouts[0].clone();
+    for other in layouts[1..].iter() {
+        layout = layout.broadcast(other)?;
+    }
+    let shape = layout.shape().inner().clone();
+    let ndim = shape.len();
+    let axes = process_axes(axes, ndim)?;
+    let res_layout = layout.reduce(axes.as_slice(), keep_dims)?;
+    let strides = layouts
+        .iter()
+        .map(|layout| broadcast_strides(layout, ndim))
+        .collect::<Vec<_>>();
+
+    // a tile is a matrix whose columns walk the dims holding the last dim, so the rows of a tile are computed
+    // along the memory order of the inputs. When the last dim is kept, the tile is reduced along its rows
+    let mut order = rearrange_array(ndim, &axes);
+    let (kept, reduced) = order.split_at_mut(ndim - axes.len());
+    reduced.sort_unstable();
+    let (kept, reduced) = (&*kept, &*reduced);
+    let reduce_rows = ndim > 0 && !axes.contains(&(ndim - 1));
+    let (row_dims, col_dims) = if reduce_rows {
+        (reduced, kept)
+    } else {
+        (kept, reduced)
+    };
+    let size = |dims: &[usize]| dims.iter().map(|&d| shape[d] as usize).product::<usize>();
+    let (rows, cols) = (size(row_dims), size(col_dims));
+
+    let res =
+        _Tensor::<O, Cpu, DEVICE>::full(init_val, [if reduce_rows { cols } else { rows } as i64])?;
+    if rows > 0 && cols > 0 {
+        // the tiles only depend on the shapes, so the results don't depend on the number of threads
+        let tile_cols = cols.min(FUSED_TILE);
+        let tile_rows = (FUSED_TILE / tile_cols).clamp(1, rows);
+        for r0 in (0..rows).step_by(tile_rows) {
+            let r1 = (r0 + tile_rows).min(rows);
+            for c0 in (0..cols).step_by(tile_cols) {
+                let c1 = (c0 + tile_cols).min(cols);
+                let mut tile =
+                    _Tensor::<O, Cpu, DEVICE>::empty([(r1 - r0) as i64, (c1 - c0) as i64])?;
+                tile.as_raw_mut()
+                    .par_chunks_mut(c1 - c0)
+                    .enumerate()
+                    .for_each(|(r, row)| {
+                        fill_tile_row(
+                            row,
+                            (r0 + r) as i64,
+                            c0 as i64,
+                            &shape,
+                            &strides,
+                            row_dims,
+                            col_dims,
+                            &load,
+                            &load_vec,
+                        )
+                    });
+                let (axis, range) = if reduce_rows {
+                    (0, (c0 as i64, c1 as i64))
+                } else {
+                    (1, (r0 as i64, r1 as i64))
+                };
+                reduce(
+                    &tile,
+                    combine,
+                    combine,
+                    vec_combine,
+                    &[axis],
+                    init_val,
+                    false,
+                    false,
+                    Some(res.slice(&[Slice::Range(range)])?),
+                )?;
+            }
+        }
+    }
+    Ok(res.reshape(res_layout.shape())?.into())
+}
+
+/// compute the columns of a tile row starting at column `col`, a run along the last column dim is computed by
+/// `load_vec` as long as it has enough elements
+#[inline]
+fn fill_tile_row<O, F, F2>(
+    row: &mut [O],
+    row_idx: i64,
+    col: i64,
+    shape: &[i64],
+    strides: &[Vec<i64>],
+    row_dims: &[usize],
+    col_dims: &[usize],
+    load: &F,
+    load_vec: &F2,
+) where
+    O: CommonBounds,
+    F: Fn(&[i64]) -> O,
+    F2: Fn(&[i64], &[i64]) -> O::Vec,
+{
+    let mut offsets = vec![0i64; strides.len()];
+    let mut rem = row_idx;
+    for &d in row_dims.iter().rev() {
+        advance(&mut offsets, strides, d, rem % shape[d]);
+        rem /= shape[d];
+    }
+    let mut prg = vec![0i64; col_dims.len()];
+    let mut rem = col;
+    for (p, &d) in prg.iter_mut().zip(col_dims.iter()).rev() {
+        *p = rem % shape[d];
+        advance(&mut offsets, strides, d, *p);
+        rem /= shape[d];
+    }
+    let Some((&last, outer_dims)) = col_dims.split_last() else {
+        row[0] = load(&offsets);
+        return;
+    };
+    let run_strides = strides.iter().map(|s| s[last]).collect::<Vec<_>>();
+    let mut i = 0;
+    while i < row.len() {
+        let start = prg[outer_dims.len()];
+        let len = ((shape[last] - start) as usize).min(row.len() - i);
+        let mut j = 0;
+        while j + O::Vec::SIZE <= len {
+            let vec = load_vec(&offsets, &run_strides);
+            unsafe {
+                std::ptr::copy_nonoverlapping(
+                    vec.as_ptr(),
+                    row.as_mut_ptr().add(i + j),
+                    O::Vec::SIZE,
+                );
+            }
+            advance(&mut offsets, strides, last, O::Vec::SIZE as i64);
+            j += O::Vec::SIZE;
+        }
+        for x in row[i + j..i + len].iter_mut() {
+            *x = load(&offsets);
+            advance(&mut offsets, strides, last, 1);
+        }
+        i += len;
+        if i < row.len() {
+            // the run reached the end of the last dim
+            advance(&mut offsets, strides, last, -shape[last]);
+            prg[outer_dims.len()] = 0;
+            for (p, &d) in prg.iter_mut().zip(outer_dims.iter()).rev() {
+                if *p < shape[d] - 1 {
+                    *p += 1;
+                    advance(&mut offsets, strides, d, 1);
+                    break;
+                } else {
+                    advance(&mut offsets, strides, d, -*p);
+                    *p = 0;
+                }
+            }
+        }
+    }
+}
+
+/// Matmul with an elementwise epilogue fused into the output write.
+///
+/// Each thread computes a block of rows of the matmul into a scratch tile, `post` then turns every element of the
+/// tile into the final output, so the matmul result is never materialized. `post` receives the matmul result and
+/// the offsets of `layouts` (the other inputs of the epilogue, in the same order) at that element.
+///
+/// When the epilogue inputs broadcast the output beyond the matmul shape, the matmul is computed unfused and `post`
+/// is applied to every element of the broadcasted output.
+#[track_caller]
+pub fn matmul_post<A, B, O, F>(
+    lhs: &Tensor<A>,
+    rhs: &Tensor<B>,
+    layouts: &[&Layout],
+    post: F,
+) -> std::result::Result<Tensor<O>, TensorError>
+where
+    A: CommonBounds + NormalOut<B> + Cast<<A as NormalOut<B>>::Output>,
+    B: CommonBounds + Cast<<A as NormalOut<B>>::Output>,
+    <A as NormalOut<B>>::Output: CommonBounds,
+    O: CommonBounds,
+    F: Fn(<A as NormalOut<B>>::Output, &[i64]) -> O + Sync + Send,
+{
+    ShapeError::check_matmul(lhs.shape(), rhs.shape())?;
+    let ndim = lhs.ndim().max(rhs.ndim());
+    let a_shape = try_pad_shape(lhs.shape(), ndim);
+    let b_shape = try_pad_shape(rhs.shape(), ndim);
+    let mut res_shape =
+        predict_broadcast_shape(&a_shape[..ndim - 2], &b_shape[..ndim - 2])?.to_vec();
+    let batch_shape = res_shape.clone();
+    let (m, k, n) = (a_shape[ndim - 2], a_shape[ndim - 1], b_shape[ndim - 1]);
+    res_shape.push(m);
+    res_shape.push(n);
+
+    let mut out_shape = res_shape.clone();
+    for layout in layouts.iter() {
+        out_shape = predict_broadcast_shape(&out_shape, layout.shape())?.to_vec();
+    }
+    if out_shape != res_shape {
+        let mm = lhs.matmul(rhs)?;
+        return broadcast_post(&mm, layouts, out_shape, post);
+    }
+
+    let lhs = lhs.inner.try_astype::<<A as NormalOut<B>>::Output>()?;
+    let rhs = rhs.inner.try_astype::<<A as NormalOut<B>>::Output>()?;
+    let a_strides = broadcast_strides(lhs.layout(), ndim);
+    let b_strides = broadcast_strides(rhs.layout(), ndim);
+    let strides = layouts
+        .iter()
+        .map(|layout| broadcast_strides(layout, ndim))
+        .collect::<Vec<_>>();
+    let (lhs_rs, lhs_cs) = (a_strides[ndim - 2], a_strides[ndim - 1]);
+    let (rhs_rs, rhs_cs) = (b_strides[ndim - 2], b_strides[ndim - 1]);
+
+    // split the rows of every matrix so there are roughly as many blocks as threads
+    let batch = batch_shape.iter().product::<i64>() as usize;
+    let num_threads = rayon::current_num_threads();
+    let blocks_per_matrix = num_threads
+        .div_ceil(batch.max(1))
+        .clamp(1, (m as usize).max(1));
+    let intervals = mt_intervals(m as usize, blocks_per_matrix);
+
+    let ret = Tensor::<O>::empty(res_shape.clone())?;
+    let ret_ptr = ret.ptr();
+    let (lhs_ptr, rhs_ptr) = (lhs.ptr(), rhs.ptr());
+    (0..batch * blocks_per_matrix)
+        .into_par_iter()
+        .for_each(|task| {
+            let (row_start, row_end) = intervals[task % blocks_per_matrix];
+            let rows = row_end - row_start;
+            if rows == 0 {
+                return;
+            }
+            let batch_idx = task / blocks_per_matrix;
+            let (mut a_offset, mut b_offset) = (0i64, 0i64);
+            let mut offsets = vec![0i64; strides.len()];
+            let mut rem = batch_idx as i64;
+            for d in (0..ndim - 2).rev() {
+                let idx = rem % batch_shape[d];
+                a_offset += idx * a_strides[d];
+                b_offset += idx * b_strides[d];
+                advance(&mut offsets, &strides, d, idx);
+                rem /= batch_shape[d];
+            }
+            a_offset += row_start as i64 * lhs_rs;
+            advance(&mut offsets, &strides, ndim - 2, row_start as i64);
+
+            let (mut a_ptr, mut b_ptr) = (lhs_ptr.clone(), rhs_ptr.clone());
+            a_ptr.offset(a_offset);
+            b_ptr.offset(b_offset);
+            let mut tile =
+                vec![<<A as NormalOut<B>>::Output as TypeCommon>::ZERO; rows * n as usize];
+            unsafe {
+                gemm::gemm(
+                    rows,
+                    n as usize,
+                    k as usize,
+                    tile.as_mut_ptr(),
+                    1,
+                    n as isize,
+                    false,
+                    a_ptr.ptr,
+                    lhs_cs as isize,
+                    lhs_rs as isize,
+                    b_ptr.ptr,
+                    rhs_cs as isize,
+                    rhs_rs as isize,
+                    <A as NormalOut<B>>::Output::ZERO,
+                    <A as NormalOut<B>>::Output::ONE,
+                    false,
+                    false,
+                    false,
+                    gemm::Parallelism::None,
+                );
+            }
+            let mut out = ret_ptr.clone();
+            out.offset((batch_idx * m as usize + row_start) as i64 * n);
+            for (r, row) in tile.chunks_exact(n as usize).enumerate() {
+                for (c, &val) in row.iter().enumerate() {
+                    out.modify((r as i64) * n + c as i64, post(val, &offsets));
+                    advance(&mut offsets, &strides, ndim - 1, 1);
+                }
+                advance(&mut offsets, &strides, ndim - 1, -n);
+                advance(&mut offsets, &strides, ndim - 2, 1);
+            }
+        });
+    Ok(ret)
+}
+
+/// apply the epilogue of [`matmul_post`] to every element of `out_shape`, `mm` and `layouts` are broadcasted to it
+fn broadcast_post<M, O, F>(
+    mm: &Tensor<M>,
+    layouts: &[&Layout],
+    out_shape: Vec<i64>,
+    post: F,
+) -> std::result::Result<Tensor<O>, TensorError>
+where
+    M: CommonBounds,
+    O: CommonBounds,
+    F: Fn(M, &[i64]) -> O + Sync + Send,
+{
+    let ndim = out_shape.len();
+    let mm_strides = vec![broadcast_strides(mm.layout(), ndim)];
+    let strides = layouts
+        .iter()
+        .map(|layout| broadcast_strides(layout, ndim))
+        .collect::<Vec<_>>();
+    let mut ret = Tensor::<O>::empty(out_shape.clone())?;
+    if ret.size() == 0 {
+        return Ok(ret);
+    }
+    let mm_ptr = mm.ptr();
+    let inner = out_shape[ndim - 1] as usize;
+    ret.as_raw_mut()
+        .par_chunks_mut(inner)
+        .enumerate()
+        .for_each(|(row, out)| {
+            let mut mm_offset = [0i64];
+            let mut offsets = vec![0i64; strides.len()];
+            let mut rem = row as i64;
+            for d in (0..ndim - 1).rev() {
+                let idx = rem % out_shape[d];
+                advance(&mut mm_offset, &mm_strides, d, idx);
+                advance(&mut offsets, &strides, d, idx);
+                rem /= out_shape[d];
+            }
+            for x in out.iter_mut() {
+                *x = post(mm_ptr[mm_offset[0]], &offsets);
+                advance(&mut mm_offset, &mm_strides, ndim - 1, 1);
+                advance(&mut offsets, &strides, ndim - 1, 1);
+            }
+        });
+    Ok(ret)
+}
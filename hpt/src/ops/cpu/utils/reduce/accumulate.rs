@@ -0,0 +1,602 @@
+//! Reductions accumulating in a wider type, with Kahan compensation or pairwise, see [`Accumulation`].
+//!
+//! The sums run in the reduce templates, the lanes are widened to `f32` or `f64` vectors when they are loaded
+//! and every policy accumulates whole vectors.
+
+use crate::lazy::record::{is_recording, record_op, resolve};
+use crate::ops::cpu::utils::reduce::reduce_template::{
+    contiguous_reduce_template, uncontiguos_reduce_template,
+};
+use crate::ops::cpu::utils::reduce::reduce_utils::{rearrange_array, tree_combine};
+use crate::tensor_base::_Tensor;
+use crate::Cpu;
+use half::slice::HalfFloatSliceExt;
+use half::{bf16, f16};
+use hpt_common::error::base::TensorError;
+use hpt_common::utils::pointer::Pointer;
+use hpt_traits::{
+    Accumulation, CommonBounds, ShapeManipulate, TensorCreator, TensorInfo, TensorLike,
+};
+use hpt_types::dtype::TypeCommon;
+use hpt_types::f8::{f8e4m3, f8e5m2};
+use hpt_types::into_scalar::Cast;
+use hpt_types::traits::VecTrait;
+use hpt_types::type_promote::{FloatOutBinary, FloatOutUnary, NormalOut};
+use rayon::iter::{IndexedParallelIterator, IntoParallelIterator, ParallelIterator};
+use rayon::iter::{IntoParallelRefIterator, IntoParallelRefMutIterator};
+
+/// number of vectors summed sequentially by one task, the tasks only depend on the shapes
+const TASK_VECS: usize = 1024;
+/// number of vectors summed sequentially by the leaves of the pairwise summation
+const PAIRWISE_BLOCK: usize = 32;
+
+/// the policy used by a reduction accumulating in `O`, the types without a wider or compensated accumulation
+/// always accumulate natively
+pub(crate) fn resolve_accumulation<O: TypeCommon>(accumulation: Accumulation) -> Accumulation {
+    match (accumulation, O::STR) {
+        (Accumulation::Widened, "f32" | "f16" | "bf16" | "f8e4m3" | "f8e5m2") => {
+            Accumulation::Widened
+        }
+        (
+            Accumulation::Kahan | Accumulation::Pairwise,
+            "f64" | "f32" | "f16" | "bf16" | "f8e4m3" | "f8e5m2",
+        ) => accumulation,
+        _ => Accumulation::Native,
+    }
+}
+
+/// the types the sums are accumulated in
+pub(crate) trait Accumulator:
+    CommonBounds + NormalOut<Self, Output = Self> + FloatOutBinary<Self, Output = Self>
+{
+    /// the vector the lanes are accumulated in
+    type V: VecTrait<Self>
+        + NormalOut<Self::V, Output = Self::V>
+        + FloatOutUnary<Output = Self::V>
+        + Copy
+        + Send
+        + Sync;
+    /// convert a lane loaded as `f64`
+    fn from_f64(x: f64) -> Self;
+    fn sqrt(self) -> Self;
+    fn ln(self) -> Self;
+}
+
+impl Accumulator for f32 {
+    type V = <f32 as TypeCommon>::Vec;
+    fn from_f64(x: f64) -> Self {
+        x as f32
+    }
+    fn sqrt(self) -> Self {
+        f32::sqrt(self)
+    }
+    fn ln(self) -> Self {
+        f32::ln(self)
+    }
+}
+
+impl Accumulator for f64 {
+    type V = <f64 as TypeCommon>::Vec;
+    fn from_f64(x: f64) -> Self {
+        x
+    }
+    fn sqrt(self) -> Self {
+        f64::sqrt(self)
+    }
+    fn ln(self) -> Self {
+        f64::ln(self)
+    }
+}
+
+/// the function applied to the elements before they are summed, and the function applied to the sums
+pub(crate) trait SumMap: Copy + Send + Sync + 'static {
+    fn map<W: Accumulator>(self, x: W::V) -> W::V;
+    fn post<W: Accumulator>(self, s: W) -> W;
+}
+
+/// `sum`
+#[derive(Clone, Copy)]
+pub(crate) struct Sum;
+
+impl SumMap for Sum {
+    fn map<W: Accumulator>(self, x: W::V) -> W::V {
+        x
+    }
+    fn post<W: Accumulator>(self, s: W) -> W {
+        s
+    }
+}
+
+/// `mean`, the sums are divided by the number of reduced elements
+#[derive(Clone, Copy)]
+pub(crate) struct Mean(pub(crate) f64);
+
+impl SumMap for Mean {
+    fn map<W: Accumulator>(self, x: W::V) -> W::V {
+        x
+    }
+    fn post<W: Accumulator>(self, s: W) -> W {
+        s._div(W::from_f64(self.0))
+    }
+}
+
+/// `reducel2`
+#[derive(Clone, Copy)]
+pub(crate) struct L2;
+
+impl SumMap for L2 {
+    fn map<W: Accumulator>(self, x: W::V) -> W::V {
+        x._mul(x)
+    }
+    fn post<W: Accumulator>(self, s: W) -> W {
+        s.sqrt()
+    }
+}
+
+/// `logsumexp`
+#[derive(Clone, Copy)]
+pub(crate) struct LogSumExp;
+
+impl SumMap for LogSumExp {
+    fn map<W: Accumulator>(self, x: W::V) -> W::V {
+        x._exp()
+    }
+    fn post<W: Accumulator>(self, s: W) -> W {
+        s.ln()
+    }
+}
+
+/// the partial sums of the lanes, `c` holds the Kahan compensations and stays zero for the other policies
+#[derive(Clone, Copy)]
+struct Partial<V> {
+    s: V,
+    c: V,
+}
+
+/// add `x` to the compensated sum `(s, c)`, the exact sum is approximately `s - c`
+#[inline(always)]
+fn kahan_add<V: NormalOut<V, Output = V> + Copy>(s: V, c: V, x: V) -> (V, V) {
+    let y = x._sub(c);
+    let t = s._add(y);
+    (t, t._sub(s)._sub(y))
+}
+
+impl<V: NormalOut<V, Output = V> + Copy> Partial<V> {
+    #[inline(always)]
+    fn add(self, x: V, compensated: bool) -> Self {
+        if compensated {
+            let (s, c) = kahan_add(self.s, self.c, x);
+            Partial { s, c }
+        } else {
+            Partial {
+                s: self.s._add(x),
+                c: self.c,
+            }
+        }
+    }
+
+    #[inline(always)]
+    fn combine(self, other: Self, compensated: bool) -> Self {
+        if compensated {
+            let (s, c) = kahan_add(self.s, self.c._add(other.c), other.s);
+            Partial { s, c }
+        } else {
+            Partial {
+                s: self.s._add(other.s),
+                c: self.c,
+            }
+        }
+    }
+}
+
+/// load `count` lanes of `T`, `stride` elements apart, into a vector of `W`, the other lanes are zero
+///
+/// `f16` and `bf16` are converted to `f32` with simd, the other types are converted lane by lane.
+#[inline(always)]
+unsafe fn load<T: CommonBounds, W: Accumulator>(ptr: *const T, stride: i64, count: usize) -> W::V {
+    if count == W::V::SIZE && stride == 1 && T::STR == W::STR {
+        return W::V::from_ptr(ptr as *const W);
+    }
+    let mut vec = W::V::splat(W::ZERO);
+    let lanes = std::slice::from_raw_parts_mut(vec.as_mut_ptr(), count);
+    match (T::STR, W::STR, stride) {
+        ("f16", "f32", 1) => std::slice::from_raw_parts(ptr as *const f16, count)
+            .convert_to_f32_slice(&mut *(lanes as *mut [W] as *mut [f32])),
+        ("bf16", "f32", 1) => std::slice::from_raw_parts(ptr as *const bf16, count)
+            .convert_to_f32_slice(&mut *(lanes as *mut [W] as *mut [f32])),
+        _ => {
+            for (i, lane) in lanes.iter_mut().enumerate() {
+                *lane = W::from_f64((*ptr.offset(i as isize * stride as isize)).cast());
+            }
+        }
+    }
+    vec
+}
+
+/// the vectors summed for one output, they walk `shape` in row major order. When `shape` ends with the lanes of
+/// a row, the vectors of its last index only hold `tail` lanes
+struct Vectors {
+    shape: Vec<i64>,
+    strides: Vec<i64>,
+    lanes: usize,
+    tail: usize,
+    lane_stride: i64,
+}
+
+impl Vectors {
+    fn len(&self) -> usize {
+        self.shape.iter().product::<i64>() as usize
+    }
+
+    /// sum `map` of the vectors `start..end` starting at `ptr`
+    fn sum<T, W, M>(
+        &self,
+        ptr: *const T,
+        start: usize,
+        end: usize,
+        policy: Accumulation,
+        map: M,
+    ) -> Partial<W::V>
+    where
+        T: CommonBounds,
+        W: Accumulator,
+        M: SumMap,
+    {
+        let zero = W::V::splat(W::ZERO);
+        if policy == Accumulation::Pairwise && end - start > PAIRWISE_BLOCK {
+            let mid = start + (end - start) / 2;
+            let lhs = self.sum::<T, W, M>(ptr, start, mid, policy, map);
+            let rhs = self.sum::<T, W, M>(ptr, mid, end, policy, map);
+            return lhs.combine(rhs, false);
+        }
+        let compensated = policy == Accumulation::Kahan;
+        let ndim = self.shape.len();
+        let mut prg = vec![0i64; ndim];
+        let mut offset = 0i64;
+        let mut rem = start as i64;
+        for d in (0..ndim).rev() {
+            prg[d] = rem % self.shape[d];
+            offset += prg[d] * self.strides[d];
+            rem /= self.shape[d];
+        }
+        let mut acc = Partial { s: zero, c: zero };
+        for _ in start..end {
+            let count = if ndim > 0 && prg[ndim - 1] == self.shape[ndim - 1] - 1 {
+                self.tail
+            } else {
+                self.lanes
+            };
+            let mut vec = map.map::<W>(unsafe {
+                load::<T, W>(ptr.offset(offset as isize), self.lane_stride, count)
+            });
+            if count < W::V::SIZE {
+                // the padding lanes are zero after the map too
+                let lanes = vec.as_mut_ptr();
+                for i in count..W::V::SIZE {
+                    unsafe { *lanes.add(i) = W::ZERO };
+                }
+            }
+            acc = acc.add(vec, compensated);
+            for d in (0..ndim).rev() {
+                if prg[d] < self.shape[d] - 1 {
+                    prg[d] += 1;
+                    offset += self.strides[d];
+                    break;
+                }
+                offset -= prg[d] * self.strides[d];
+                prg[d] = 0;
+            }
+        }
+        acc
+    }
+
+    /// sum all the vectors in tasks of fixed size
+    fn sum_all<T, W, M>(&self, ptr: Pointer<T>, policy: Accumulation, map: M) -> Partial<W::V>
+    where
+        T: CommonBounds,
+        W: Accumulator,
+        M: SumMap,
+    {
+        let zero = W::V::splat(W::ZERO);
+        let len = self.len();
+        let partials = (0..len.div_ceil(TASK_VECS))
+            .into_par_iter()
+            .map(|task| {
+                let end = ((task + 1) * TASK_VECS).min(len);
+                self.sum::<T, W, M>(ptr.get_ptr(), task * TASK_VECS, end, policy, map)
+            })
+            .collect::<Vec<_>>();
+        let compensated = policy == Accumulation::Kahan;
+        tree_combine(partials, Partial { s: zero, c: zero }, |a, b| {
+            a.combine(b, compensated)
+        })
+    }
+}
+
+/// fold the lanes of `partial` into one sum
+fn lanes_sum<W: Accumulator>(partial: Partial<W::V>, policy: Accumulation) -> W {
+    let lanes = (0..W::V::SIZE)
+        .map(|i| Partial {
+            s: partial.s.extract(i),
+            c: partial.c.extract(i),
+        })
+        .collect::<Vec<_>>();
+    let compensated = policy == Accumulation::Kahan;
+    let sum = tree_combine(
+        lanes,
+        Partial {
+            s: W::ZERO,
+            c: W::ZERO,
+        },
+        |a, b| a.combine(b, compensated),
+    );
+    sum.s._sub(sum.c)
+}
+
+/// the offset of the `idx`th element of `shape` in row major order
+#[inline]
+fn offset_of(mut idx: i64, shape: &[i64], strides: &[i64]) -> i64 {
+    let mut offset = 0;
+    for d in (0..shape.len()).rev() {
+        offset += (idx % shape[d]) * strides[d];
+        idx /= shape[d];
+    }
+    offset
+}
+
+/// drop the dims of size 1 and merge the consecutive dims which are contiguous with each other, the row major
+/// order of the elements doesn't change
+fn merge_dims(shape: &[i64], strides: &[i64]) -> (Vec<i64>, Vec<i64>) {
+    let mut merged_shape: Vec<i64> = vec![];
+    let mut merged_strides: Vec<i64> = vec![];
+    for (&dim, &stride) in shape.iter().zip(strides.iter()) {
+        if dim == 1 {
+            continue;
+        }
+        match (merged_shape.last_mut(), merged_strides.last_mut()) {
+            (Some(last), Some(last_stride)) if *last_stride == stride * dim => {
+                *last *= dim;
+                *last_stride = stride;
+            }
+            _ => {
+                merged_shape.push(dim);
+                merged_strides.push(stride);
+            }
+        }
+    }
+    (merged_shape, merged_strides)
+}
+
+/// the vectors of one output when its lanes are folded at the end, the last dim is split in vectors
+fn row_vectors<W: Accumulator>(shape: &[i64], strides: &[i64]) -> Vectors {
+    let Some((&row, outer)) = shape.split_last() else {
+        // a single element
+        return Vectors {
+            shape: vec![],
+            strides: vec![],
+            lanes: 1,
+            tail: 1,
+            lane_stride: 1,
+        };
+    };
+    let row = row as usize;
+    let lane_stride = strides[strides.len() - 1];
+    let mut vec_shape = outer.to_vec();
+    vec_shape.push(row.div_ceil(W::V::SIZE) as i64);
+    let mut vec_strides = strides[..strides.len() - 1].to_vec();
+    vec_strides.push(lane_stride * W::V::SIZE as i64);
+    Vectors {
+        shape: vec_shape,
+        strides: vec_strides,
+        lanes: W::V::SIZE.min(row),
+        tail: row + W::V::SIZE - row.div_ceil(W::V::SIZE).max(1) * W::V::SIZE,
+        lane_stride,
+    }
+}
+
+/// the sum of all the elements of `x`
+fn full_sum<T, W, M, const DEVICE: usize>(
+    x: &_Tensor<T, Cpu, DEVICE>,
+    policy: Accumulation,
+    map: M,
+) -> W
+where
+    T: CommonBounds,
+    W: Accumulator,
+    M: SumMap,
+{
+    let (shape, strides) = merge_dims(x.shape().inner(), x.strides().inner());
+    let vectors = row_vectors::<W>(&shape, &strides);
+    lanes_sum::<W>(vectors.sum_all::<T, W, M>(x.ptr(), policy, map), policy)
+}
+
+/// sum `x` over its dims after the first `nkept` ones into `res`, whose elements are the kept positions of `x`
+/// in row major order.
+///
+/// When the fastest kept dim is faster than the reduced dims, a vector holds the sums of consecutive outputs,
+/// otherwise it holds lanes of one output and they are folded at the end.
+fn sum_into<T, W, M, const DEVICE: usize>(
+    x: &_Tensor<T, Cpu, DEVICE>,
+    nkept: usize,
+    res: &_Tensor<W, Cpu, DEVICE>,
+    policy: Accumulation,
+    map: M,
+) where
+    T: CommonBounds,
+    W: Accumulator,
+    M: SumMap,
+{
+    let (kept_shape, kept_strides) =
+        merge_dims(&x.shape().inner()[..nkept], &x.strides().inner()[..nkept]);
+    let (reduced_shape, reduced_strides) =
+        merge_dims(&x.shape().inner()[nkept..], &x.strides().inner()[nkept..]);
+    let res_shape = res.shape().inner().clone();
+    let res_strides = res.strides().inner().clone();
+    let (x_ptr, res_ptr) = (x.ptr(), res.ptr());
+    let lanes_along_kept = match (kept_strides.last(), reduced_strides.last()) {
+        (Some(kept), Some(reduced)) => kept.abs() < reduced.abs(),
+        _ => false,
+    };
+    if lanes_along_kept {
+        let nkept = kept_shape.len();
+        let (row, row_stride) = (kept_shape[nkept - 1], kept_strides[nkept - 1]);
+        let chunks = (row as usize).div_ceil(W::V::SIZE) as i64;
+        let outer = kept_shape[..nkept - 1].iter().product::<i64>();
+        (0..outer * chunks).into_par_iter().for_each(|group| {
+            let (o, chunk) = (group / chunks, group % chunks);
+            let first = chunk * W::V::SIZE as i64;
+            let lanes = (row - first).min(W::V::SIZE as i64) as usize;
+            let vectors = Vectors {
+                shape: reduced_shape.clone(),
+                strides: reduced_strides.clone(),
+                lanes,
+                tail: lanes,
+                lane_stride: row_stride,
+            };
+            let mut base = x_ptr.clone();
+            base.offset(
+                offset_of(o, &kept_shape[..nkept - 1], &kept_strides[..nkept - 1])
+                    + first * row_stride,
+            );
+            let sum = vectors.sum_all::<T, W, M>(base, policy, map);
+            for i in 0..lanes {
+                let dst = offset_of(o * row + first + i as i64, &res_shape, &res_strides);
+                unsafe {
+                    *res_ptr.get_ptr().offset(dst as isize) =
+                        sum.s.extract(i)._sub(sum.c.extract(i));
+                }
+            }
+        });
+    } else {
+        let vectors = row_vectors::<W>(&reduced_shape, &reduced_strides);
+        let outputs = kept_shape.iter().product::<i64>();
+        (0..outputs).into_par_iter().for_each(|idx| {
+            let mut base = x_ptr.clone();
+            base.offset(offset_of(idx, &kept_shape, &kept_strides));
+            let sum = vectors.sum_all::<T, W, M>(base, policy, map);
+            let dst = offset_of(idx, &res_shape, &res_strides);
+            unsafe {
+                *res_ptr.get_ptr().offset(dst as isize) = lanes_sum::<W>(sum, policy);
+            }
+        });
+    }
+}
+
+/// the sums of `map(x)` over `axes` in `W`, computed by the reduce templates
+fn accumulate<T, W, M, const DEVICE: usize>(
+    a: &_Tensor<T, Cpu, DEVICE>,
+    axes: &[usize],
+    policy: Accumulation,
+    map: M,
+) -> std::result::Result<_Tensor<W, Cpu, DEVICE>, TensorError>
+where
+    T: CommonBounds,
+    W: Accumulator,
+    M: SumMap,
+{
+    let ndim = a.ndim();
+    let nkept = ndim - axes.len();
+    if a.is_contiguous() && a.parent().is_none() {
+        contiguous_reduce_template(
+            a,
+            axes,
+            W::ZERO,
+            false,
+            false,
+            None,
+            |res| *res = full_sum(a, policy, map),
+            |_, _, _, res, transposed| sum_into(transposed, nkept, res, policy, map),
+            |_, _, res| match a.permute(rearrange_array(ndim, axes)) {
+                Ok(kept_first) => sum_into(&kept_first, nkept, res, policy, map),
+                Err(err) => unreachable!("accumulate: {}", err),
+            },
+            |_, _, _, _, res, transposed| sum_into(transposed, nkept, res, policy, map),
+        )
+    } else {
+        uncontiguos_reduce_template(
+            a,
+            axes,
+            W::ZERO,
+            false,
+            false,
+            None,
+            |res| *res = full_sum(a, policy, map),
+            |_, _, _, res, transposed| sum_into(transposed, nkept, res, policy, map),
+            // the kept dim is moved last, the other kept dims have a size of 1
+            |_, _, kept_last, res| {
+                let perm = std::iter::once(ndim - 1)
+                    .chain(0..ndim - 1)
+                    .collect::<Vec<_>>();
+                match kept_last.permute(perm) {
+                    Ok(kept_first) => sum_into(&kept_first, nkept, res, policy, map),
+                    Err(err) => unreachable!("accumulate: {}", err),
+                }
+            },
+            |_, _, _, res, transposed| sum_into(transposed, nkept, res, policy, map),
+        )
+    }
+}
+
+/// apply `post` to the sums and store them as `D`, `D` and `O` are the same type
+fn narrow<W, D, O, M, const DEVICE: usize>(
+    sum: &_Tensor<W, Cpu, DEVICE>,
+    map: M,
+) -> std::result::Result<_Tensor<O, Cpu, DEVICE>, TensorError>
+where
+    W: Accumulator + Cast<D>,
+    D: CommonBounds,
+    O: CommonBounds,
+    M: SumMap,
+{
+    let mut res = _Tensor::<D, Cpu, DEVICE>::empty(sum.shape().clone())?;
+    res.as_raw_mut()
+        .par_iter_mut()
+        .zip(sum.as_raw().par_iter())
+        .for_each(|(r, &s)| {
+            *r = map.post(s).cast();
+        });
+    res.static_cast::<O>()
+}
+
+/// the sum of `map(x)` over `axes` followed by the post function of `map`, with the accumulation `policy`
+///
+/// `policy` is resolved for `O` by [`resolve_accumulation`]. `Widened` accumulates `f16`, `bf16` and `f8` in
+/// `f32` and `f32` in `f64`, `Kahan` and `Pairwise` accumulate `f64` in `f64` and the other types in `f32`.
+#[track_caller]
+pub(crate) fn accumulate_reduce<T, O, M, const DEVICE: usize>(
+    a: &_Tensor<T, Cpu, DEVICE>,
+    axes: &[usize],
+    keep_dims: bool,
+    policy: Accumulation,
+    map: M,
+) -> std::result::Result<_Tensor<O, Cpu, DEVICE>, TensorError>
+where
+    T: CommonBounds,
+    O: CommonBounds,
+    M: SumMap,
+{
+    if is_recording() {
+        let shape = a.layout.reduce(axes, keep_dims)?.shape().clone();
+        let (view, axes) = (a.clone(), axes.to_vec());
+        return record_op(&[a], shape, move |values| {
+            accumulate_reduce(&resolve(&view, &values[0]), &axes, keep_dims, policy, map)
+        });
+    }
+    macro_rules! sum_in {
+        ($acc:ty, $dst:ty) => {{
+            let sum = accumulate::<T, $acc, M, DEVICE>(a, axes, policy, map)?;
+            narrow::<$acc, $dst, O, M, DEVICE>(&sum, map)
+        }};
+    }
+    let res = match (policy, O::STR) {
+        (Accumulation::Widened, "f32") => sum_in!(f64, f32),
+        (_, "f64") => sum_in!(f64, f64),
+        (_, "f32") => sum_in!(f32, f32),
+        (_, "f16") => sum_in!(f32, f16),
+        (_, "bf16") => sum_in!(f32, bf16),
+        (_, "f8e4m3") => sum_in!(f32, f8e4m3),
+        (_, "f8e5m2") => sum_in!(f32, f8e5m2),
+        _ => unreachable!("{:?} accumulation is not supported for {}", policy, O::STR),
+    }?;
+    res.reshape(a.layout.reduce(axes, keep_dims)?.shape())
+}
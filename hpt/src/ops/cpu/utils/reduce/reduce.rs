@@ -1,12 +1,14 @@
 use crate::backend::Cpu;
+use crate::lazy::record::{is_recording, record_op, resolve};
 use crate::ops::cpu::kernels::argreduce_kernels::{argmax_kernel, argmin_kernel};
+use crate::ops::cpu::utils::dispatch::par_for_each_dispatch;
 use crate::ops::cpu::utils::reduce::reduce_template::contiguous_reduce_template;
 use crate::tensor_base::_Tensor;
 
+use crate::context::{is_deterministic, with_thread_pool};
 use crate::ops::cpu::utils::reduce::reduce_utils::{
-    ReductionPreprocessor, UCReductionPreprocessor,
+    ordered_fold, tree_combine, ReductionPreprocessor, UCReductionPreprocessor, DETERMINISTIC_CHUNK,
 };
-use crate::THREAD_POOL;
 use hpt_common::error::base::TensorError;
 use hpt_common::error::shape::ShapeError;
 use hpt_common::shape::shape::Shape;
@@ -19,11 +21,13 @@ use hpt_traits::tensor::CommonBounds;
 use hpt_traits::tensor::TensorCreator;
 use hpt_traits::tensor::TensorInfo;
 use hpt_traits::TensorLike;
+use hpt_types::dispatch::{dispatch, dispatch_kernel, Simd, SimdElement, SimdKernel, SimdVec};
 use hpt_types::into_scalar::Cast;
 use hpt_types::type_promote::{Cmp, NormalOut};
 use rayon::iter::ParallelIterator;
 use rayon::iter::{IndexedParallelIterator, IntoParallelRefMutIterator};
 use rayon::iter::{IntoParallelIterator, IntoParallelRefIterator};
+use rayon::slice::ParallelSlice;
 use std::sync::Arc;
 use std::sync::Barrier;
 
@@ -115,7 +119,7 @@ macro_rules! body_one_axis {
         let inner_loop_size = transposed_shape[a_last_index];
         let a_data_ptr = a_data.clone();
         let last_stride = transposed_strides[a_last_index];
-        THREAD_POOL.with_borrow_mut(|pool| {
+        with_thread_pool(|pool| {
             let num_threads;
             if result_size < pool.max_count() {
                 num_threads = result_size;
@@ -215,10 +219,25 @@ use hpt_types::vectors::traits::*;
 
 use super::reduce_template::uncontiguos_reduce_template;
 use crate::ops::cpu::kernels::reduce::{
-    contiguous_reduce_dim_include, contiguous_reduce_dim_include_simd,
-    uncontiguous_reduce_dim_include,
+    contiguous_reduce_dim_include, contiguous_reduce_dim_include_level,
+    contiguous_reduce_dim_include_simd, fast_reduce_level, reduce_dim_not_include_level,
+    reduce_row_level, uncontiguous_reduce_dim_include, ReduceOp,
 };
 
+/// in deterministic mode a full reduction of a non contiguous tensor runs on a contiguous copy, the contiguous kernel folds fixed size chunks
+/// while the strided one splits the elements by the number of threads
+fn deterministic_full_reduce_input<T: CommonBounds, const DEVICE: usize>(
+    a: &_Tensor<T, Cpu, DEVICE>,
+    axes: &[usize],
+) -> std::result::Result<Option<_Tensor<T, Cpu, DEVICE>>, TensorError> {
+    if axes.len() == a.ndim() && !(a.is_contiguous() && a.parent().is_none()) && is_deterministic()
+    {
+        Ok(Some(a.contiguous()?))
+    } else {
+        Ok(None)
+    }
+}
+
 #[track_caller]
 pub(crate) fn reduce<T, F, F2, F3, const DEVICE: usize>(
     a: &_Tensor<T, Cpu, DEVICE>,
@@ -237,7 +256,96 @@ where
     F2: Fn(T, T) -> T + Sync + Send + 'static + Copy,
     F3: Fn(T::Vec, T::Vec) -> T::Vec + Sync + Send + 'static + Copy,
 {
-    if a.is_contiguous() && a.parent().is_none() {
+    reduce_impl(
+        a, None, op, op_no_cast, vec_op, axes, init_val, keepdims, init_out, c,
+    )
+}
+
+/// `reduce` for a reduction of [`ReduceOp`], the contiguous f32 and f64 tensors run it on the vectors
+/// of the simd level. `init_val` must be the identity of the reduction.
+#[track_caller]
+pub(crate) fn reduce_op<T, F, F2, F3, const DEVICE: usize>(
+    a: &_Tensor<T, Cpu, DEVICE>,
+    reduce_op: ReduceOp,
+    op: F,
+    op_no_cast: F2,
+    vec_op: F3,
+    axes: &[usize],
+    init_val: T,
+    keepdims: bool,
+    init_out: bool,
+    c: Option<_Tensor<T, Cpu, DEVICE>>,
+) -> std::result::Result<_Tensor<T, Cpu, DEVICE>, TensorError>
+where
+    T: CommonBounds + Cast<T>,
+    F: Fn(T, T) -> T + Sync + Send + 'static + Copy,
+    F2: Fn(T, T) -> T + Sync + Send + 'static + Copy,
+    F3: Fn(T::Vec, T::Vec) -> T::Vec + Sync + Send + 'static + Copy,
+{
+    reduce_impl(
+        a,
+        Some(reduce_op),
+        op,
+        op_no_cast,
+        vec_op,
+        axes,
+        init_val,
+        keepdims,
+        init_out,
+        c,
+    )
+}
+
+#[track_caller]
+fn reduce_impl<T, F, F2, F3, const DEVICE: usize>(
+    a: &_Tensor<T, Cpu, DEVICE>,
+    reduce_op: Option<ReduceOp>,
+    op: F,
+    op_no_cast: F2,
+    vec_op: F3,
+    axes: &[usize],
+    init_val: T,
+    keepdims: bool,
+    init_out: bool,
+    c: Option<_Tensor<T, Cpu, DEVICE>>,
+) -> std::result::Result<_Tensor<T, Cpu, DEVICE>, TensorError>
+where
+    T: CommonBounds + Cast<T>,
+    F: Fn(T, T) -> T + Sync + Send + 'static + Copy,
+    F2: Fn(T, T) -> T + Sync + Send + 'static + Copy,
+    F3: Fn(T::Vec, T::Vec) -> T::Vec + Sync + Send + 'static + Copy,
+{
+    if c.is_none() && is_recording() {
+        let shape = a.layout.reduce(axes, keepdims)?.shape().clone();
+        let (view, axes) = (a.clone(), axes.to_vec());
+        return record_op(&[a], shape, move |values| {
+            reduce_impl(
+                &resolve(&view, &values[0]),
+                reduce_op,
+                op,
+                op_no_cast,
+                vec_op,
+                &axes,
+                init_val,
+                keepdims,
+                init_out,
+                None,
+            )
+        });
+    }
+    let level_op = reduce_op.filter(|_| matches!(T::STR, "f32" | "f64"));
+    if let Some(a) = deterministic_full_reduce_input(a, axes)? {
+        if let Some(reduce_op) = level_op {
+            return contiguous_reduce_level(&a, reduce_op, axes, init_val, keepdims, init_out, c);
+        }
+        contiguous_reduce::<_, _, _, _, fn(T) -> T, _, _, fn(T::Vec) -> T::Vec, T, DEVICE>(
+            &a, op, op_no_cast, op, None, vec_op, vec_op, None, &axes, init_val, keepdims,
+            init_out, c,
+        )
+    } else if a.is_contiguous() && a.parent().is_none() {
+        if let Some(reduce_op) = level_op {
+            return contiguous_reduce_level(a, reduce_op, axes, init_val, keepdims, init_out, c);
+        }
         contiguous_reduce::<_, _, _, _, fn(T) -> T, _, _, fn(T::Vec) -> T::Vec, T, DEVICE>(
             a, op, op_no_cast, op, None, vec_op, vec_op, None, &axes, init_val, keepdims, init_out,
             c,
@@ -274,7 +382,31 @@ where
     T::Vec: Copy,
     O::Vec: Copy,
 {
-    if a.is_contiguous() && a.parent().is_none() {
+    if c.is_none() && is_recording() {
+        let shape = a.layout.reduce(axes, keepdims)?.shape().clone();
+        let (view, axes) = (a.clone(), axes.to_vec());
+        return record_op(&[a], shape, move |values| {
+            reduce2(
+                &resolve(&view, &values[0]),
+                op,
+                op_no_cast,
+                op2,
+                vec_op,
+                vec_op2,
+                &axes,
+                init_val,
+                keepdims,
+                init_out,
+                None,
+            )
+        });
+    }
+    if let Some(a) = deterministic_full_reduce_input(a, axes)? {
+        contiguous_reduce::<T, F, F2, F3, fn(O) -> O, _, _, fn(O::Vec) -> O::Vec, O, DEVICE>(
+            &a, op, op_no_cast, op2, None, vec_op, vec_op2, None, &axes, init_val, keepdims,
+            init_out, c,
+        )
+    } else if a.is_contiguous() && a.parent().is_none() {
         contiguous_reduce::<T, F, F2, F3, fn(O) -> O, _, _, fn(O::Vec) -> O::Vec, O, DEVICE>(
             a, op, op_no_cast, op2, None, vec_op, vec_op2, None, &axes, init_val, keepdims,
             init_out, c,
@@ -314,7 +446,44 @@ where
     O: CommonBounds,
     O::Vec: Copy,
 {
-    if a.is_contiguous() && a.parent().is_none() {
+    if c.is_none() && is_recording() {
+        let shape = a.layout.reduce(axes, keepdims)?.shape().clone();
+        let (view, axes) = (a.clone(), axes.to_vec());
+        return record_op(&[a], shape, move |values| {
+            reduce3(
+                &resolve(&view, &values[0]),
+                op,
+                op_no_cast,
+                op2,
+                op3,
+                vec_op,
+                vec_op2,
+                op5,
+                &axes,
+                init_val,
+                keepdims,
+                init_out,
+                None,
+            )
+        });
+    }
+    if let Some(a) = deterministic_full_reduce_input(a, axes)? {
+        contiguous_reduce::<T, F, F2, F3, F4, F5, F6, F7, O, DEVICE>(
+            &a,
+            op,
+            op_no_cast,
+            op2,
+            Some(op3),
+            vec_op,
+            vec_op2,
+            Some(op5),
+            &axes,
+            init_val,
+            keepdims,
+            init_out,
+            c,
+        )
+    } else if a.is_contiguous() && a.parent().is_none() {
         contiguous_reduce::<T, F, F2, F3, F4, F5, F6, F7, O, DEVICE>(
             a,
             op,
@@ -410,10 +579,13 @@ where
         |res| {
             let ptr = a.ptr();
             let raw = unsafe { std::slice::from_raw_parts_mut(ptr.ptr, a.size() as usize) };
-            let val = raw
-                .par_iter()
-                .fold(|| init_val, |acc, &x| op(acc, x))
-                .reduce(|| init_val, |a, b| op2(a, b));
+            let val = if is_deterministic() {
+                ordered_fold(raw, init_val, op, op2)
+            } else {
+                raw.par_iter()
+                    .fold(|| init_val, |acc, &x| op(acc, x))
+                    .reduce(|| init_val, |a, b| op2(a, b))
+            };
             if let Some(op3) = op3 {
                 *res = op3(op2(val, *res));
             } else {
@@ -432,7 +604,7 @@ where
                 transposed_tensor.shape().clone(),
                 result.shape().clone(),
             );
-            iterators.into_par_iter().for_each(|mut iterator| {
+            par_for_each_dispatch(iterators, |mut iterator| {
                 let result_ptr_c = iterator.res_ptrs.clone();
                 let a_data_ptr = iterator.ptrs.clone();
                 let current_size = iterator.end - iterator.start;
@@ -499,30 +671,34 @@ where
                     use crate::ops::cpu::kernels::reduce::fast_reduce_no_simd;
                     use crate::ops::cpu::kernels::reduce::fast_reduce_simd;
                     if O::Vec::SIZE == T::Vec::SIZE {
-                        fast_reduce_simd(
-                            inner_loop_size,
-                            outer_loop_size,
-                            inp_ptr,
-                            res_ptr,
-                            inp.strides().inner(),
-                            inp.shape().inner(),
-                            O::Vec::SIZE as isize,
-                            op,
-                            vec_op,
-                            op3,
-                            vec_post,
-                        );
+                        dispatch(|| {
+                            fast_reduce_simd(
+                                inner_loop_size,
+                                outer_loop_size,
+                                inp_ptr,
+                                res_ptr,
+                                inp.strides().inner(),
+                                inp.shape().inner(),
+                                O::Vec::SIZE as isize,
+                                op,
+                                vec_op,
+                                op3,
+                                vec_post,
+                            );
+                        });
                     } else {
-                        fast_reduce_no_simd(
-                            inner_loop_size,
-                            outer_loop_size,
-                            inp_ptr,
-                            res_ptr,
-                            inp.strides().inner(),
-                            inp.shape().inner(),
-                            op,
-                            op3,
-                        );
+                        dispatch(|| {
+                            fast_reduce_no_simd(
+                                inner_loop_size,
+                                outer_loop_size,
+                                inp_ptr,
+                                res_ptr,
+                                inp.strides().inner(),
+                                inp.shape().inner(),
+                                op,
+                                op3,
+                            );
+                        });
                     }
                 });
         },
@@ -542,7 +718,7 @@ where
                 transposed_tensor.shape().sub_one(),
                 result.shape().clone(),
             );
-            iterators.into_par_iter().for_each(|iterator| {
+            par_for_each_dispatch(iterators, |iterator| {
                 let result_ptr_c = iterator.res_ptrs.clone();
                 let a_data_ptr = iterator.ptrs.clone();
                 let current_size = iterator.end - iterator.start;
@@ -600,6 +776,291 @@ where
     }
 }
 
+/// `contiguous_reduce` of a [`ReduceOp`] on a f32 or f64 tensor with the kernels of the simd level
+#[track_caller]
+fn contiguous_reduce_level<T: CommonBounds, const DEVICE: usize>(
+    a: &_Tensor<T, Cpu, DEVICE>,
+    op: ReduceOp,
+    axes: &[usize],
+    init_val: T,
+    keepdims: bool,
+    init_out: bool,
+    c: Option<_Tensor<T, Cpu, DEVICE>>,
+) -> std::result::Result<_Tensor<T, Cpu, DEVICE>, TensorError> {
+    macro_rules! level_kernel {
+        ($t:ty) => {
+            dispatch_kernel(ContiguousReduce::<$t, DEVICE> {
+                a: &a.static_cast()?,
+                op,
+                axes,
+                // safety: `T` is `$t`, checked by the caller through `T::STR`
+                init_val: unsafe { std::mem::transmute_copy::<T, $t>(&init_val) },
+                keepdims,
+                init_out,
+                c: c.map(|c| c.static_cast()).transpose()?,
+            })?
+            .static_cast()
+        };
+    }
+    match T::STR {
+        "f32" => level_kernel!(f32),
+        "f64" => level_kernel!(f64),
+        _ => unreachable!("no simd level kernels for {}", T::STR),
+    }
+}
+
+/// the body of `contiguous_reduce` with the kernels of the simd level
+struct ContiguousReduce<'a, E: CommonBounds, const DEVICE: usize> {
+    a: &'a _Tensor<E, Cpu, DEVICE>,
+    op: ReduceOp,
+    axes: &'a [usize],
+    init_val: E,
+    keepdims: bool,
+    init_out: bool,
+    c: Option<_Tensor<E, Cpu, DEVICE>>,
+}
+
+impl<E, const DEVICE: usize> SimdKernel for ContiguousReduce<'_, E, DEVICE>
+where
+    E: CommonBounds + SimdElement,
+{
+    type Output = std::result::Result<_Tensor<E, Cpu, DEVICE>, TensorError>;
+
+    #[inline(always)]
+    fn run<S: Simd>(self, simd: S) -> Self::Output {
+        let ContiguousReduce {
+            a,
+            op,
+            axes,
+            init_val,
+            keepdims,
+            init_out,
+            c,
+        } = self;
+        let max_axis = *axes.iter().max().unwrap();
+        let (a, fused_dims) = if max_axis == a.ndim() - 1 {
+            (a.clone(), vec![])
+        } else {
+            let prod = a.shape()[max_axis + 1..].iter().product::<i64>();
+            let mut new_shape: Vec<i64> = a.shape()[..=max_axis].to_vec();
+            new_shape.push(prod);
+            (a.reshape(&new_shape)?, a.shape()[max_axis + 1..].to_vec())
+        };
+        let res = contiguous_reduce_template(
+            &a,
+            axes,
+            init_val,
+            keepdims,
+            init_out,
+            c,
+            |res| {
+                // fixed size chunks, the result doesn't depend on the number of threads
+                let ptr = a.ptr();
+                let raw = unsafe { std::slice::from_raw_parts(ptr.ptr, a.size()) };
+                let partials = raw
+                    .par_chunks(DETERMINISTIC_CHUNK)
+                    .map(|chunk| {
+                        simd.vectorize(ReduceChunk {
+                            op,
+                            chunk,
+                            init: init_val,
+                        })
+                    })
+                    .collect::<Vec<E>>();
+                let val = tree_combine(partials, init_val, |a, b| op.combine(a, b));
+                *res = op.combine(val, *res);
+            },
+            |num_threads, inner_loop_size, inner_loop_size_2, result, transposed_tensor| {
+                let iterators = ReductionPreprocessor::new(
+                    num_threads,
+                    result.size(),
+                    inner_loop_size_2,
+                    a.ptr(),
+                    result.ptr(),
+                    transposed_tensor.strides().clone(),
+                    transposed_tensor.shape().sub_one(),
+                    transposed_tensor.shape().clone(),
+                    result.shape().clone(),
+                );
+                iterators.into_par_iter().for_each(|mut iterator| {
+                    simd.vectorize(ReduceDimInclude {
+                        op,
+                        init: init_val,
+                        inner_loop_size: inner_loop_size as isize,
+                        intermediate_size: inner_loop_size_2 as isize,
+                        iterator: &mut iterator,
+                    })
+                });
+            },
+            |num_threads, inner_loop_size, result| {
+                let intervals =
+                    mt_intervals_simd(inner_loop_size, num_threads, E::Vector::<S>::LANES);
+                let mut slices = vec![Slice::Full; a.ndim()];
+                let mut slices_res = vec![Slice::Full; result.ndim()];
+                let mut sliced_tensors = Vec::with_capacity(num_threads);
+                let mut sliced_res = Vec::with_capacity(num_threads);
+                assert_eq!(inner_loop_size, result.size());
+                for (start, end) in intervals.into_iter() {
+                    if end - start == 0 {
+                        continue;
+                    }
+                    slices[a.ndim() - 1] = Slice::Range((start as i64, end as i64));
+                    slices_res[result.ndim() - 1] = Slice::Range((start as i64, end as i64));
+                    sliced_tensors.push(a.slice(&slices).expect("Slice failed"));
+                    sliced_res.push(result.slice(&slices_res).expect("Slice failed"));
+                }
+                sliced_tensors
+                    .into_par_iter()
+                    .zip(sliced_res.into_par_iter())
+                    .for_each(move |(inp, res)| {
+                        simd.vectorize(FastReduce {
+                            op,
+                            inp: &inp,
+                            res: &res,
+                        });
+                    });
+            },
+            |num_threads,
+             outer_loop_size,
+             inner_loop_size,
+             inner_loop_size_2,
+             result,
+             transposed_tensor| {
+                let iterators = ReductionPreprocessor::new2(
+                    num_threads,
+                    outer_loop_size,
+                    inner_loop_size,
+                    a.ptr(),
+                    result.ptr(),
+                    transposed_tensor.strides().clone(),
+                    transposed_tensor.shape().sub_one(),
+                    result.shape().clone(),
+                );
+                iterators.into_par_iter().for_each(|mut iterator| {
+                    simd.vectorize(ReduceDimNotInclude {
+                        op,
+                        inner_loop_size: inner_loop_size as isize,
+                        intermediate_size: inner_loop_size_2 as isize,
+                        iterator: &mut iterator,
+                    })
+                });
+            },
+        )?;
+        if !fused_dims.is_empty() {
+            let mut new_shape = res.shape().to_vec();
+            new_shape.pop();
+            new_shape.extend(fused_dims.iter());
+            res.reshape(&new_shape)
+        } else {
+            Ok(res)
+        }
+    }
+}
+
+/// reduce a chunk of the input to one value, a task of [`ContiguousReduce`]
+struct ReduceChunk<'a, E> {
+    op: ReduceOp,
+    chunk: &'a [E],
+    init: E,
+}
+
+impl<E: SimdElement> SimdKernel for ReduceChunk<'_, E> {
+    type Output = E;
+
+    #[inline(always)]
+    fn run<S: Simd>(self, simd: S) -> E {
+        let ReduceChunk { op, chunk, init } = self;
+        unsafe { reduce_row_level(simd, op, chunk.as_ptr(), chunk.len(), init) }
+    }
+}
+
+/// the rows of a thread when the last dim is reduced, a task of [`ContiguousReduce`]
+struct ReduceDimInclude<'a, E> {
+    op: ReduceOp,
+    init: E,
+    inner_loop_size: isize,
+    intermediate_size: isize,
+    iterator: &'a mut ReductionPreprocessor<E, E>,
+}
+
+impl<E: SimdElement + CommonBounds> SimdKernel for ReduceDimInclude<'_, E> {
+    type Output = ();
+
+    #[inline(always)]
+    fn run<S: Simd>(self, simd: S) {
+        let iterator = self.iterator;
+        contiguous_reduce_dim_include_level(
+            simd,
+            self.op,
+            self.init,
+            self.inner_loop_size,
+            (iterator.end - iterator.start) as isize,
+            self.intermediate_size,
+            iterator.ptrs.clone(),
+            iterator.res_ptrs.clone(),
+            &iterator.strides,
+            &iterator.a_shape,
+            &mut iterator.prg,
+            iterator.a_shape.len() as i64,
+        )
+    }
+}
+
+/// the columns of a thread when all the leading dims are reduced, a task of [`ContiguousReduce`]
+struct FastReduce<'a, E: CommonBounds, const DEVICE: usize> {
+    op: ReduceOp,
+    inp: &'a _Tensor<E, Cpu, DEVICE>,
+    res: &'a _Tensor<E, Cpu, DEVICE>,
+}
+
+impl<E: SimdElement + CommonBounds, const DEVICE: usize> SimdKernel for FastReduce<'_, E, DEVICE> {
+    type Output = ();
+
+    #[inline(always)]
+    fn run<S: Simd>(self, _: S) {
+        let FastReduce { op, inp, res } = self;
+        let inner_loop_size = *res.shape().last().unwrap() as isize;
+        fast_reduce_level::<E, S>(
+            op,
+            inner_loop_size,
+            (inp.size() as isize) / inner_loop_size,
+            inp.ptr(),
+            res.ptr(),
+            inp.strides().inner(),
+            inp.shape().inner(),
+        )
+    }
+}
+
+/// the rows of a thread when the last dim is kept, a task of [`ContiguousReduce`]
+struct ReduceDimNotInclude<'a, E> {
+    op: ReduceOp,
+    inner_loop_size: isize,
+    intermediate_size: isize,
+    iterator: &'a mut ReductionPreprocessor<E, E>,
+}
+
+impl<E: SimdElement> SimdKernel for ReduceDimNotInclude<'_, E> {
+    type Output = ();
+
+    #[inline(always)]
+    fn run<S: Simd>(self, _: S) {
+        let iterator = self.iterator;
+        reduce_dim_not_include_level::<E, S>(
+            self.op,
+            self.inner_loop_size,
+            (iterator.end - iterator.start) as isize,
+            self.intermediate_size,
+            iterator.ptrs.clone(),
+            iterator.res_ptrs.clone(),
+            &iterator.strides,
+            &iterator.a_shape,
+            &mut iterator.a_prg,
+            iterator.shape.len() as i64,
+        )
+    }
+}
+
 #[track_caller]
 pub(crate) fn uncontiguous_reduce<T, F, F2, F3, F4, F5, O, const DEVICE: usize>(
     a: &_Tensor<T, Cpu, DEVICE>,
@@ -658,7 +1119,7 @@ where
                 result.strides().inner(),
             );
             let res_shape = result.shape().clone();
-            iterators.into_par_iter().for_each(|mut iterator| {
+            par_for_each_dispatch(iterators, |mut iterator| {
                 let result_ptr_c = iterator.res_ptrs;
                 let a_data_ptr = iterator.ptrs;
                 let current_size = iterator.end - iterator.start;
@@ -729,7 +1190,7 @@ where
                 result.strides().inner(),
             );
             let res_shape = result.shape().clone();
-            iterators.into_par_iter().for_each(|mut iterator| {
+            par_for_each_dispatch(iterators, |mut iterator| {
                 let a_last_stride = transposed_tensor.strides()[a.ndim() - axes.len() - 1];
                 let result_ptr_c = iterator.res_ptrs.clone();
                 let a_data_ptr = iterator.ptrs.clone();
@@ -5,7 +5,6 @@ use crate::{
 };
 use hpt_common::error::base::TensorError;
 use hpt_traits::{CommonBounds, ShapeManipulate, TensorInfo};
-use hpt_types::into_scalar::Cast;
 
 /// Performs a reduction operation on a tensor using customizable functions.
 ///
@@ -39,7 +38,7 @@ use hpt_types::into_scalar::Cast;
 ///
 /// # Constraints
 ///
-/// - `T`: Must implement `CommonBounds` and `Cast<O>`.
+/// - `T`: Must implement `CommonBounds`.
 /// - `O`: Must implement `CommonBounds`.
 /// - `F1`: Must be a function or closure that takes a mutable reference to `O` and performs the full reduction.
 /// - `F2`: Must be a function or closure with the signature `Fn(usize, usize, usize, &_Tensor<O>, &_Tensor<T>)`.
@@ -138,7 +137,6 @@ use hpt_types::into_scalar::Cast;
 /// - The function depends on several traits and types:
 ///     - `_Tensor<T>`: A tensor type parameterized by the data type `T`.
 ///     - `CommonBounds`: A trait that must be implemented by `T` and `O`.
-///     - `Cast<O>`: A trait implemented by `T` to allow conversion into the output scalar type `O`.
 ///     - `rayon`: Used for multithreading support.
 ///
 /// # See Also
@@ -163,7 +161,7 @@ pub(crate) fn contiguous_reduce_template<T, F1, F2, F3, F4, O, const DEVICE: usi
     kd: F4,
 ) -> std::result::Result<_Tensor<O, Cpu, DEVICE>, TensorError>
 where
-    T: CommonBounds + Cast<O>,
+    T: CommonBounds,
     O: CommonBounds,
     F1: Fn(&mut O),
     F2: Fn(usize, usize, usize, &_Tensor<O, Cpu, DEVICE>, &_Tensor<T, Cpu, DEVICE>),
@@ -178,7 +176,8 @@ where
         }
     }
     let mut fused_dims: Vec<usize> = vec![];
-    let (a, axes) = if !keep_fast_dim {
+    // a 1-D tensor has no trailing axes to fuse
+    let (a, axes) = if !keep_fast_dim && a.ndim() > 1 {
         let mut consec_axes = vec![];
         let mut new_axes = axes.to_vec();
         let mut max = a.ndim() - 1;
@@ -300,7 +299,7 @@ where
 ///
 /// # Constraints
 ///
-/// - `T`: Must implement `CommonBounds` and `Cast<O>`.
+/// - `T`: Must implement `CommonBounds`.
 /// - `O`: Must implement `CommonBounds`.
 /// - `F1`: Must be a function or closure that takes a mutable reference to `O` and performs the full reduction.
 /// - `F2`: Must be a function or closure with the signature `Fn(usize, usize, usize, &_Tensor<O>, &_Tensor<T>)`.
@@ -401,7 +400,6 @@ where
 /// - Requires the following traits and types:
 ///     - `_Tensor<T>`: A tensor type parameterized by the data type `T`.
 ///     - `CommonBounds`: A trait that must be implemented by `T` and `O`.
-///     - `Cast<O>`: A trait implemented by `T` to allow conversion into the output scalar type `O`.
 ///     - `rayon`: Used for multithreading support.
 ///
 /// # See Also
@@ -427,7 +425,7 @@ pub(crate) fn uncontiguos_reduce_template<T, F1, F2, F3, F4, O, const DEVICE: us
     kd: F4,
 ) -> std::result::Result<_Tensor<O, Cpu, DEVICE>, TensorError>
 where
-    T: CommonBounds + Cast<O>,
+    T: CommonBounds,
     O: CommonBounds,
     F1: Fn(&mut O),
     F2: Fn(usize, usize, usize, &_Tensor<O, Cpu, DEVICE>, &_Tensor<T, Cpu, DEVICE>),
@@ -9,9 +9,52 @@ use hpt_common::{
 };
 use hpt_traits::{CommonBounds, ShapeManipulate, TensorCreator, TensorInfo, TensorLike};
 use rayon::iter::{IntoParallelRefMutIterator, ParallelIterator};
+use rayon::slice::ParallelSlice;
 
 use crate::{backend::Cpu, tensor_base::_Tensor};
 
+/// number of elements folded sequentially by one task in deterministic mode, the chunks don't depend on the number of threads
+pub(crate) const DETERMINISTIC_CHUNK: usize = 4096;
+
+/// combine `partials` pairwise, level by level, the order of the combines only depends on the number of partials
+pub(crate) fn tree_combine<O: Copy>(
+    mut partials: Vec<O>,
+    init: O,
+    combine: impl Fn(O, O) -> O,
+) -> O {
+    while partials.len() > 1 {
+        partials = partials
+            .chunks(2)
+            .map(|pair| {
+                if pair.len() == 2 {
+                    combine(pair[0], pair[1])
+                } else {
+                    pair[0]
+                }
+            })
+            .collect();
+    }
+    partials.pop().unwrap_or(init)
+}
+
+/// fold `data` in chunks of `DETERMINISTIC_CHUNK` elements in parallel and combine the partial results with `tree_combine`
+pub(crate) fn ordered_fold<T, O>(
+    data: &[T],
+    init: O,
+    fold: impl Fn(O, T) -> O + Sync + Send,
+    combine: impl Fn(O, O) -> O,
+) -> O
+where
+    T: Copy + Sync,
+    O: Copy + Send + Sync,
+{
+    let partials = data
+        .par_chunks(DETERMINISTIC_CHUNK)
+        .map(|chunk| chunk.iter().fold(init, |acc, &x| fold(acc, x)))
+        .collect::<Vec<O>>();
+    tree_combine(partials, init, combine)
+}
+
 pub(crate) fn rearrange_array(ndim: usize, to_reduce: &[usize]) -> Vec<usize> {
     let mut origin_order = (0..ndim).collect::<Vec<usize>>();
     let mut to_reduce = to_reduce.to_vec();
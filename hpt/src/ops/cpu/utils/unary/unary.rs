@@ -1,28 +1,104 @@
 use crate::backend::Cpu;
+use crate::context::{with_thread_pool, PoolScope};
+use crate::lazy::record::{is_recording, record_unary};
+use crate::ops::cpu::utils::dispatch::{load_partial, par_ranges, store_partial, RangeKernel};
 use crate::tensor_base::_Tensor;
-use crate::{Tensor, THREAD_POOL};
+use crate::Tensor;
 use hpt_common::error::base::TensorError;
 use hpt_common::error::shape::ShapeError;
 use hpt_common::shape::shape_utils::mt_intervals;
+use hpt_common::utils::pointer::Pointer;
 use hpt_iterator::iterator_traits::ParStridedIteratorSimdZip;
 use hpt_iterator::TensorIterator;
 use hpt_traits::tensor::TensorCreator;
 use hpt_traits::tensor::{CommonBounds, TensorInfo, TensorLike};
+use hpt_types::dispatch::{dispatch, dispatch_kernel, Simd, SimdElement, SimdKernel, SimdVec};
 use hpt_types::dtype::TypeCommon;
 use hpt_types::type_promote::{Eval, NormalOut};
 use hpt_types::vectors::traits::*;
 use rayon::iter::{IndexedParallelIterator, ParallelIterator};
 use rayon::slice::{ParallelSlice, ParallelSliceMut};
 use std::borrow::Borrow;
-use threadpool::ThreadPool;
 
 /// Perform unary operation with output tensor
+///
+/// in lazy mode, the operation is recorded unless an output tensor is provided
 pub fn unary_fn_with_out<A, O, K, F, F2, const DEVICE: usize>(
     inp: &_Tensor<A, Cpu, DEVICE>,
     f: F,
     f2: F2,
     out: Option<O>,
 ) -> std::result::Result<_Tensor<K, Cpu, DEVICE>, TensorError>
+where
+    A: CommonBounds,
+    K: CommonBounds,
+    O: Borrow<_Tensor<K, Cpu, DEVICE>>,
+    F: Fn(A::Vec) -> K::Vec + Sync + Send + 'static,
+    F2: Fn(A) -> K + Sync + Send + 'static,
+{
+    if out.is_none() && is_recording() {
+        return record_unary(inp, f, f2);
+    }
+    unary_kernel(inp, None, f, f2, out)
+}
+
+/// The unary operations with a kernel on the vectors of every simd level, the contiguous f32 and f64
+/// tensors run them instead of `f`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum UnaryOp {
+    Abs,
+    Neg,
+    Square,
+    Sqrt,
+    Recip,
+    Relu,
+}
+
+/// `unary_fn_with_out` for an operation of [`UnaryOp`], `f` and `f2` must compute the same operation
+pub(crate) fn unary_op_with_out<A, O, K, F, F2, const DEVICE: usize>(
+    inp: &_Tensor<A, Cpu, DEVICE>,
+    op: UnaryOp,
+    f: F,
+    f2: F2,
+    out: Option<O>,
+) -> std::result::Result<_Tensor<K, Cpu, DEVICE>, TensorError>
+where
+    A: CommonBounds,
+    K: CommonBounds,
+    O: Borrow<_Tensor<K, Cpu, DEVICE>>,
+    F: Fn(A::Vec) -> K::Vec + Sync + Send + 'static,
+    F2: Fn(A) -> K + Sync + Send + 'static,
+{
+    if out.is_none() && is_recording() {
+        return record_unary(inp, f, f2);
+    }
+    unary_kernel(inp, Some(op), f, f2, out)
+}
+
+/// Perform unary operation with output tensor, the operation is always executed
+pub(crate) fn unary_kernel_with_out<A, O, K, F, F2, const DEVICE: usize>(
+    inp: &_Tensor<A, Cpu, DEVICE>,
+    f: F,
+    f2: F2,
+    out: Option<O>,
+) -> std::result::Result<_Tensor<K, Cpu, DEVICE>, TensorError>
+where
+    A: CommonBounds,
+    K: CommonBounds,
+    O: Borrow<_Tensor<K, Cpu, DEVICE>>,
+    F: Fn(A::Vec) -> K::Vec + Sync + Send,
+    F2: Fn(A) -> K + Sync + Send,
+{
+    unary_kernel(inp, None, f, f2, out)
+}
+
+fn unary_kernel<A, O, K, F, F2, const DEVICE: usize>(
+    inp: &_Tensor<A, Cpu, DEVICE>,
+    op: Option<UnaryOp>,
+    f: F,
+    f2: F2,
+    out: Option<O>,
+) -> std::result::Result<_Tensor<K, Cpu, DEVICE>, TensorError>
 where
     A: CommonBounds,
     K: CommonBounds,
@@ -37,7 +113,7 @@ where
         _Tensor::<K, Cpu, DEVICE>::empty(inp.shape())?
     };
     let ret_size = ret.size();
-    if inp.parent().is_some() {
+    if inp.parent().is_some() || !inp.is_contiguous() {
         ret.par_iter_mut_simd()
             .zip(inp.par_iter_simd())
             .for_each(|(a, b)| {
@@ -45,6 +121,24 @@ where
             });
         return Ok(ret);
     }
+    if let Some(op) = op.filter(|_| A::STR == K::STR) {
+        macro_rules! level_kernel {
+            ($t:ty) => {{
+                dispatch_kernel(ContiguousUnary::<$t> {
+                    op,
+                    out: ret.ptr().cast(),
+                    inp: inp.ptr().cast(),
+                    len: ret_size,
+                });
+                return Ok(ret);
+            }};
+        }
+        match K::STR {
+            "f32" => level_kernel!(f32),
+            "f64" => level_kernel!(f64),
+            _ => {}
+        }
+    }
     let per_thread_len = ret.size() / rayon::current_num_threads();
     let per_thread_remain = per_thread_len % K::Vec::SIZE;
     let total_remain = rayon::current_num_threads() * per_thread_remain
@@ -57,32 +151,93 @@ where
             .for_each(|(ret, lhs)| {
                 assert_eq!(lhs.len() % A::Vec::SIZE, 0);
                 assert_eq!(ret.len() % K::Vec::SIZE, 0);
-                ret.chunks_exact_mut(A::Vec::SIZE)
-                    .zip(lhs.chunks_exact(A::Vec::SIZE))
-                    .for_each(|(ret, lhs)| {
-                        let a = unsafe { A::Vec::from_ptr(lhs.as_ptr()) };
-                        let res = f(a);
-                        unsafe {
-                            std::ptr::copy_nonoverlapping(
-                                res.as_ptr(),
-                                ret.as_mut_ptr(),
-                                K::Vec::SIZE,
-                            );
-                        }
-                    });
+                dispatch(|| {
+                    ret.chunks_exact_mut(A::Vec::SIZE)
+                        .zip(lhs.chunks_exact(A::Vec::SIZE))
+                        .for_each(|(ret, lhs)| {
+                            let a = unsafe { A::Vec::from_ptr(lhs.as_ptr()) };
+                            let res = f(a);
+                            unsafe {
+                                std::ptr::copy_nonoverlapping(
+                                    res.as_ptr(),
+                                    ret.as_mut_ptr(),
+                                    K::Vec::SIZE,
+                                );
+                            }
+                        })
+                });
             });
     }
     if total_remain > 0 {
-        ret.as_raw_mut()[ret_size - total_remain..]
-            .iter_mut()
-            .zip(inp.as_raw()[ret_size - total_remain..].iter())
-            .for_each(|(a, &lhs)| {
-                *a = f2(lhs);
-            });
+        dispatch(|| {
+            ret.as_raw_mut()[ret_size - total_remain..]
+                .iter_mut()
+                .zip(inp.as_raw()[ret_size - total_remain..].iter())
+                .for_each(|(a, &lhs)| {
+                    *a = f2(lhs);
+                })
+        });
     }
     Ok(ret)
 }
 
+/// the contiguous path of `unary_kernel` for a [`UnaryOp`] on the vectors of the simd level
+struct ContiguousUnary<T> {
+    op: UnaryOp,
+    out: Pointer<T>,
+    inp: Pointer<T>,
+    len: usize,
+}
+
+impl<T: SimdElement + TypeCommon> SimdKernel for ContiguousUnary<T> {
+    type Output = ();
+    #[inline(always)]
+    fn run<S: Simd>(self, simd: S) {
+        par_ranges(simd, self.len, T::Vector::<S>::LANES, &self)
+    }
+}
+
+impl<T: SimdElement + TypeCommon> RangeKernel for ContiguousUnary<T> {
+    #[inline(always)]
+    fn run<S: Simd>(&self, simd: S, start: usize, end: usize) {
+        let (zero, one) = (
+            T::Vector::<S>::splat(simd, T::ZERO),
+            T::Vector::<S>::splat(simd, T::ONE),
+        );
+        match self.op {
+            UnaryOp::Abs => self.run_op::<S, _>(start, end, |x| x.abs()),
+            UnaryOp::Neg => self.run_op::<S, _>(start, end, |x| x.neg()),
+            UnaryOp::Square => self.run_op::<S, _>(start, end, |x| x.mul(x)),
+            UnaryOp::Sqrt => self.run_op::<S, _>(start, end, |x| x.sqrt()),
+            UnaryOp::Recip => self.run_op::<S, _>(start, end, |x| one.div(x)),
+            UnaryOp::Relu => self.run_op::<S, _>(start, end, |x| x.max(zero)),
+        }
+    }
+}
+
+impl<T: SimdElement> ContiguousUnary<T> {
+    #[inline(always)]
+    fn run_op<S, F>(&self, start: usize, end: usize, op: F)
+    where
+        S: Simd,
+        F: Fn(T::Vector<S>) -> T::Vector<S>,
+    {
+        let lanes = T::Vector::<S>::LANES;
+        unsafe {
+            let (out, inp) = (self.out.get_ptr(), self.inp.get_ptr());
+            let mut i = start;
+            while i + lanes <= end {
+                op(T::Vector::<S>::load(inp.add(i))).store(out.add(i));
+                i += lanes;
+            }
+            if i < end {
+                let x = load_partial::<T, S>(inp.add(i), end - i, *inp.add(i));
+                store_partial::<T, S>(op(x), out.add(i), end - i);
+            }
+        }
+    }
+}
+
 impl<T> _Tensor<T>
 where
     T: CommonBounds + Eval,
@@ -143,7 +298,7 @@ where
             let res_stride = res.strides()[_axis as usize];
             let mut res_strides = res.strides().to_vec();
             res_strides.swap(_axis as usize, res.strides().len() - 1);
-            THREAD_POOL.with_borrow_mut(|pool: &mut ThreadPool| {
+            with_thread_pool(|pool: &PoolScope| {
                 let num_threads;
                 if outer_loop < pool.max_count() {
                     num_threads = outer_loop;
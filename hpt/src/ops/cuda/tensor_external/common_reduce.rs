@@ -6,7 +6,9 @@ use crate::Cuda;
 use cudarc::driver::DeviceRepr;
 use hpt_common::axis::axis::Axis;
 use hpt_common::error::base::TensorError;
-use hpt_traits::{CommonBounds, EvalReduce, FloatReduce, NormalEvalReduce, NormalReduce};
+use hpt_traits::{
+    Accumulation, CommonBounds, EvalReduce, FloatReduce, NormalEvalReduce, NormalReduce,
+};
 use hpt_types::cuda_types::scalar::Scalar;
 use hpt_types::dtype::CudaType;
 use hpt_types::type_promote::{FloatOutBinary, FloatOutUnary, NormalOut};
@@ -21,6 +23,16 @@ impl<T: CommonBounds + DeviceRepr + CudaType + Cast<f64>, const DEVICE_ID: usize
         Ok(self.inner.sum(axes, keep_dims)?.into())
     }
 
+    #[track_caller]
+    fn sum_with<S: Into<Axis>>(
+        &self,
+        axes: S,
+        keep_dims: bool,
+        accumulation: Accumulation,
+    ) -> Result<Self::Output, TensorError> {
+        Ok(self.inner.sum_with(axes, keep_dims, accumulation)?.into())
+    }
+
     fn sum_<S: Into<Axis>, O>(
         &self,
         axes: S,
@@ -212,4 +224,37 @@ where
     {
         Ok(self.inner.logsumexp(axes, keep_dims)?.into())
     }
+    #[track_caller]
+    fn mean_with<S: Into<Axis>>(
+        &self,
+        axes: S,
+        keep_dims: bool,
+        accumulation: Accumulation,
+    ) -> std::result::Result<Self::Output, TensorError> {
+        Ok(self.inner.mean_with(axes, keep_dims, accumulation)?.into())
+    }
+    #[track_caller]
+    fn reducel2_with<S: Into<Axis>>(
+        &self,
+        axes: S,
+        keep_dims: bool,
+        accumulation: Accumulation,
+    ) -> std::result::Result<Self::Output, TensorError> {
+        Ok(self
+            .inner
+            .reducel2_with(axes, keep_dims, accumulation)?
+            .into())
+    }
+    #[track_caller]
+    fn logsumexp_with<S: Into<Axis>>(
+        &self,
+        axes: S,
+        keep_dims: bool,
+        accumulation: Accumulation,
+    ) -> std::result::Result<Self::Output, TensorError> {
+        Ok(self
+            .inner
+            .logsumexp_with(axes, keep_dims, accumulation)?
+            .into())
+    }
 }
@@ -47,6 +47,14 @@ impl<T: CommonBounds + DeviceRepr + CudaType, const DEVICE_ID: usize> TensorCrea
         Ok(self.inner.as_ref().full_like(val)?.into())
     }
 
+    fn from_fn<S, F>(shape: S, f: F) -> std::result::Result<Self::Output, TensorError>
+    where
+        S: Into<Shape>,
+        F: Fn(&[i64]) -> T + Sync + Send,
+    {
+        Ok(_Tensor::<T, Cuda, DEVICE_ID>::from_fn(shape, f)?.into())
+    }
+
     fn arange<U>(start: U, end: U) -> std::result::Result<Self::Output, TensorError>
     where
         usize: Cast<T>,
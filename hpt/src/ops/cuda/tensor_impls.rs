@@ -9,7 +9,7 @@ use cudarc::driver::{CudaDevice, DeviceRepr, LaunchAsync};
 use hpt_common::error::base::TensorError;
 use hpt_common::{layout::layout::Layout, shape::shape::Shape, utils::pointer::Pointer};
 use hpt_dataloader::data_loader::TensorMeta;
-use hpt_dataloader::{CompressionAlgo, Endian, Save};
+use hpt_dataloader::{CompressionAlgo, Endian, Save, TensorWriter};
 use hpt_traits::TensorCreator;
 use hpt_traits::{CommonBounds, TensorAlloc, TensorInfo, TensorLike};
 use hpt_types::cuda_types::scalar::Scalar;
@@ -324,7 +324,7 @@ where
         ptr.leak();
         data.layout.set_strides(self.strides().clone());
         data.layout.set_shape(self.shape().clone());
-        write!(f, "{}", data)
+        std::fmt::Display::fmt(&data, f)
     }
 }
 
@@ -333,7 +333,7 @@ where
     T: CommonBounds + DeviceRepr + Cast<f64> + CudaType,
 {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{}", self.inner.as_ref())
+        std::fmt::Display::fmt(self.inner.as_ref(), f)
     }
 }
 
@@ -357,11 +357,9 @@ impl<
     > Save for Tensor<T, Cuda, DEVICE>
 {
     type Meta = TensorMeta<T, Self>;
-    fn __save(
+    fn __save<W: std::io::Write>(
         data: &Self,
-        file: &mut std::fs::File,
-        len_so_far: &mut usize,
-        global_cnt: &mut usize,
+        writer: &mut TensorWriter<W>,
         compression_algo: CompressionAlgo,
         endian: Endian,
         level: u32,
@@ -369,15 +367,7 @@ impl<
         let cpu_data: Tensor<T, Cpu> = data
             .to_cpu::<0>()
             .expect("failed to convert cuda tensor to cpu tensor");
-        let meta = Tensor::<T, Cpu>::__save(
-            &cpu_data,
-            file,
-            len_so_far,
-            global_cnt,
-            compression_algo,
-            endian,
-            level,
-        )?;
+        let meta = Tensor::<T, Cpu>::__save(&cpu_data, writer, compression_algo, endian, level)?;
         Ok(TensorMeta {
             begin: meta.begin,
             shape: meta.shape,
@@ -387,6 +377,7 @@ impl<
             compression_algo,
             endian,
             indices: meta.indices,
+            checksums: meta.checksums,
             phantom: std::marker::PhantomData,
         })
     }
@@ -2,15 +2,17 @@ use std::borrow::BorrowMut;
 use std::ops::BitAnd;
 
 use crate::ops::cpu::tensor_internal::float_out_unary::FloatBinaryType;
+use crate::ops::cpu::utils::reduce::accumulate::resolve_accumulation;
 use crate::ops::cuda::utils::reduce::reduce::{reduce, reduce2, reduce3};
 use crate::tensor_base::_Tensor;
 use crate::Cuda;
 use cudarc::driver::DeviceRepr;
 use hpt_common::axis::axis::{process_axes, Axis};
 use hpt_common::error::base::TensorError;
+use hpt_common::error::kernel::KernelError;
 use hpt_cudakernels::{REDUCE, REDUCE2};
 use hpt_traits::{
-    CommonBounds, EvalReduce, FloatReduce, NormalEvalReduce, NormalReduce, TensorInfo,
+    Accumulation, CommonBounds, EvalReduce, FloatReduce, NormalEvalReduce, NormalReduce, TensorInfo,
 };
 use hpt_types::cuda_types::scalar::Scalar;
 use hpt_types::dtype::CudaType;
@@ -19,6 +21,23 @@ use hpt_types::into_scalar::Cast;
 use hpt_types::traits::SimdSelect;
 use hpt_types::type_promote::{Eval, FloatOutBinary, FloatOutUnary, NormalOut};
 
+/// the cuda kernels only accumulate natively, the other policies return an error
+#[track_caller]
+fn check_accumulation<O: TypeCommon>(
+    op: &'static str,
+    accumulation: Accumulation,
+) -> std::result::Result<(), TensorError> {
+    match resolve_accumulation::<O>(accumulation) {
+        Accumulation::Native => Ok(()),
+        accumulation => Err(TensorError::Kernel(KernelError::UnsupportedAccumulation {
+            op,
+            accumulation: format!("{:?}", accumulation),
+            dtype: O::STR,
+            location: std::panic::Location::caller(),
+        })),
+    }
+}
+
 impl<T: CommonBounds + DeviceRepr + CudaType + Cast<f64>, const DEVICE_ID: usize> NormalReduce<T>
     for _Tensor<T, Cuda, DEVICE_ID>
 {
@@ -45,6 +64,17 @@ impl<T: CommonBounds + DeviceRepr + CudaType + Cast<f64>, const DEVICE_ID: usize
         )
     }
 
+    #[track_caller]
+    fn sum_with<S: Into<Axis>>(
+        &self,
+        axes: S,
+        keep_dims: bool,
+        accumulation: Accumulation,
+    ) -> std::result::Result<Self::Output, TensorError> {
+        check_accumulation::<T>("sum", accumulation)?;
+        self.sum(axes, keep_dims)
+    }
+
     fn sum_<S: Into<Axis>, O>(
         &self,
         axes: S,
@@ -414,4 +444,37 @@ where
     {
         unimplemented!()
     }
+
+    #[track_caller]
+    fn mean_with<S: Into<Axis>>(
+        &self,
+        axes: S,
+        keep_dims: bool,
+        accumulation: Accumulation,
+    ) -> std::result::Result<Self::Output, TensorError> {
+        check_accumulation::<FloatBinaryType<T>>("mean", accumulation)?;
+        self.mean(axes, keep_dims)
+    }
+
+    #[track_caller]
+    fn reducel2_with<S: Into<Axis>>(
+        &self,
+        axes: S,
+        keep_dims: bool,
+        accumulation: Accumulation,
+    ) -> std::result::Result<Self::Output, TensorError> {
+        check_accumulation::<FloatBinaryType<T>>("reducel2", accumulation)?;
+        self.reducel2(axes, keep_dims)
+    }
+
+    #[track_caller]
+    fn logsumexp_with<S: Into<Axis>>(
+        &self,
+        axes: S,
+        keep_dims: bool,
+        accumulation: Accumulation,
+    ) -> std::result::Result<Self::Output, TensorError> {
+        check_accumulation::<FloatBinaryType<T>>("logsumexp", accumulation)?;
+        self.logsumexp(axes, keep_dims)
+    }
 }
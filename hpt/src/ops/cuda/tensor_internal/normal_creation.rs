@@ -5,7 +5,7 @@ use crate::{
         cuda::cuda_utils::{compute_kernel_launch_config, load_ptx_and_get_data},
     },
     tensor_base::_Tensor,
-    BoolVector, Cuda, ALIGN,
+    BoolVector, Cpu, Cuda, Tensor, ALIGN,
 };
 use cudarc::driver::{DeviceRepr, LaunchAsync, LaunchConfig};
 use hpt_allocator::CUDA_CACHE;
@@ -115,6 +115,15 @@ impl<T: CommonBounds + DeviceRepr + CudaType, const DEVICE: usize> TensorCreator
         Self::full(val, self.shape())
     }
 
+    fn from_fn<S, F>(shape: S, f: F) -> std::result::Result<Self, TensorError>
+    where
+        S: Into<Shape>,
+        F: Fn(&[i64]) -> T + Sync + Send,
+    {
+        let cpu = Tensor::<T, Cpu>::from_fn(shape, f)?;
+        Ok(cpu.to_cuda::<DEVICE>()?.inner.as_ref().clone())
+    }
+
     fn arange<U>(start: U, end: U) -> std::result::Result<Self, TensorError>
     where
         usize: Cast<T>,
@@ -2,19 +2,20 @@ use std::{
     borrow::{Borrow, BorrowMut},
     fmt::{Debug, Display},
     rc::Rc,
-    sync::{atomic::Ordering, Arc},
+    sync::Arc,
 };
 
 use crate::{
     backend::{BackendTy, Buffer, Cpu},
+    lazy::record::data_ptr,
     tensor_base::_Tensor,
-    DISPLAY_LR_ELEMENTS, DISPLAY_PRECISION,
+    PRINT_OPTIONS,
 };
 use hpt_common::{
     error::base::TensorError, layout::layout::Layout, shape::shape::Shape, utils::pointer::Pointer,
 };
 use hpt_dataloader::{CPUTensorCreator, DataLoader};
-use hpt_display::display;
+use hpt_display::{display, DisplayWith, PrintOptions};
 use hpt_iterator::TensorIterator;
 use hpt_traits::tensor::{CommonBounds, TensorAlloc, TensorCreator, TensorInfo, TensorLike};
 use hpt_types::into_scalar::Cast;
@@ -76,7 +77,7 @@ macro_rules! impl_tensor_info {
             B: BackendTy + Buffer,
         {
             fn ptr(&self) -> Pointer<T> {
-                self.inner.as_ref().data.clone()
+                data_ptr(self.inner.as_ref())
             }
 
             fn size(&self) -> usize {
@@ -124,14 +125,33 @@ impl<T: CommonBounds, const DEVICE: usize> TensorAlloc for Tensor<T, Cpu, DEVICE
     }
 }
 
+impl<T, const DEVICE: usize> Tensor<T, Cpu, DEVICE>
+where
+    T: CommonBounds + Cast<f64>,
+{
+    /// Display the tensor with `options` instead of the global print options set by `set_print_options`.
+    ///
+    /// ## Example:
+    /// ```rust
+    /// use hpt::{PrintOptions, Tensor};
+    /// let a = Tensor::<f32>::new(&[1.0, 2.5]);
+    /// let options = PrintOptions {
+    ///     precision: 2,
+    ///     ..Default::default()
+    /// };
+    /// assert_eq!(format!("{:#}", a.display_with(&options)), "[1. 2.50]");
+    /// ```
+    pub fn display_with<'a>(&'a self, options: &'a PrintOptions) -> DisplayWith<'a, &'a Self, T> {
+        DisplayWith::new(self, options)
+    }
+}
+
 impl<T, const DEVICE: usize> Display for Tensor<T, Cpu, DEVICE>
 where
     T: CommonBounds + Cast<f64>,
 {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let precision = DISPLAY_PRECISION.load(Ordering::Relaxed);
-        let lr_element_size = DISPLAY_LR_ELEMENTS.load(Ordering::Relaxed);
-        display(self, f, lr_element_size, precision, false)
+        display(self, f, &PRINT_OPTIONS.read().unwrap(), false, false)
     }
 }
 
@@ -140,9 +160,7 @@ where
     T: CommonBounds + Cast<f64>,
 {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let precision = DISPLAY_PRECISION.load(Ordering::Relaxed);
-        let lr_element_size = DISPLAY_LR_ELEMENTS.load(Ordering::Relaxed);
-        display(self, f, lr_element_size, precision, false)
+        display(self, f, &PRINT_OPTIONS.read().unwrap(), true, false)
     }
 }
 
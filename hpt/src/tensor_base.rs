@@ -1,10 +1,13 @@
 use crate::backend::{Backend, BackendTy, Buffer, Cpu};
 use hpt_allocator::traits::Allocator;
-use hpt_allocator::CACHE;
 #[cfg(feature = "cuda")]
 use hpt_allocator::CUDA_CACHE;
-use hpt_common::{layout::layout::Layout, utils::pointer::Pointer};
-use std::sync::Arc;
+use hpt_allocator::{ForeignDeleter, CACHE};
+use hpt_common::error::{base::TensorError, memory::MemoryError};
+use hpt_common::{
+    layout::layout::Layout, shape::shape::Shape, strides::strides::Strides, utils::pointer::Pointer,
+};
+use std::{panic::Location, sync::Arc};
 
 /// This struct is the base of All Tensors.
 ///
@@ -27,6 +30,66 @@ where
     pub(crate) _backend: Backend<B>,
 }
 
+impl<T, const DEVICE_ID: usize> _Tensor<T, Cpu, DEVICE_ID> {
+    /// create a tensor viewing memory not owned by hpt
+    ///
+    /// `deleter` is called once the last tensor referencing the storage is dropped.
+    ///
+    /// # Safety
+    ///
+    /// `data` must be valid for all the elements reachable with `shape` and `strides` until `deleter` is called.
+    #[track_caller]
+    pub(crate) unsafe fn from_foreign(
+        data: *mut T,
+        shape: Shape,
+        strides: Strides,
+        deleter: ForeignDeleter,
+    ) -> Result<Self, TensorError> {
+        // number of elements reachable from `data`, only used for bound check
+        let extent = if shape.iter().any(|&x| x == 0) {
+            0
+        } else {
+            shape
+                .iter()
+                .zip(strides.iter())
+                .map(|(&dim, &stride)| (dim - 1) * stride.abs())
+                .sum::<i64>()
+                + 1
+        };
+        // foreign memory is never cached, the mem layout is only kept for consistency
+        let mem_layout = std::alloc::Layout::from_size_align(
+            extent as usize * std::mem::size_of::<T>(),
+            std::mem::align_of::<T>(),
+        )
+        .map_err(|e| {
+            TensorError::Memory(MemoryError::AllocationFailed {
+                device: "cpu".to_string(),
+                id: DEVICE_ID,
+                size: extent as usize,
+                source: Some(Box::new(e)),
+                location: Location::caller(),
+            })
+        })?;
+        let (key, released) = {
+            let mut cache = CACHE.lock().expect("CACHE is poisoned");
+            let key = cache.insert_foreign(DEVICE_ID, deleter);
+            (key, cache.take_released_foreign())
+        };
+        released.into_iter().for_each(|deleter| deleter());
+
+        Ok(_Tensor {
+            #[cfg(feature = "bound_check")]
+            data: Pointer::new(data, extent),
+            #[cfg(not(feature = "bound_check"))]
+            data: Pointer::new(data),
+            parent: None,
+            layout: Layout::new(shape, strides),
+            mem_layout: Arc::new(mem_layout),
+            _backend: Backend::<Cpu>::new(key as u64, DEVICE_ID),
+        })
+    }
+}
+
 impl<T, B, const DEVICE_ID: usize> Drop for _Tensor<T, B, DEVICE_ID>
 where
     B: BackendTy + Buffer,
@@ -34,15 +97,18 @@ where
     fn drop(&mut self) {
         match B::ID {
             0 => {
-                if let Ok(mut cpu_cache) = CACHE.lock() {
+                let released = if let Ok(mut cpu_cache) = CACHE.lock() {
                     cpu_cache.deallocate(
                         self._backend._backend.get_ptr() as *mut u8,
                         &self.mem_layout,
                         DEVICE_ID,
                     );
+                    cpu_cache.take_released_foreign()
                 } else {
                     panic!("CUDA_CACHE is poisoned");
-                }
+                };
+                // foreign deleters may need to lock the cache, so they must be called after the lock is released
+                released.into_iter().for_each(|deleter| deleter());
             }
             #[cfg(feature = "cuda")]
             1 => {
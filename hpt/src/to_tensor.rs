@@ -1,4 +1,5 @@
 use crate::backend::Backend;
+use crate::dyn_tensor::{Dtype, TypeNum};
 use crate::tensor::{DiffTensor, Tensor};
 use crate::{backend::Cpu, tensor_base::_Tensor};
 use half::bf16;
@@ -38,6 +39,54 @@ macro_rules! from_scalar {
     };
 }
 
+macro_rules! map_type_num {
+    (bool) => {
+        Dtype::Bool
+    };
+    (i8) => {
+        Dtype::I8
+    };
+    (u8) => {
+        Dtype::U8
+    };
+    (i16) => {
+        Dtype::I16
+    };
+    (u16) => {
+        Dtype::U16
+    };
+    (i32) => {
+        Dtype::I32
+    };
+    (u32) => {
+        Dtype::U32
+    };
+    (i64) => {
+        Dtype::I64
+    };
+    (u64) => {
+        Dtype::U64
+    };
+    (bf16) => {
+        Dtype::BF16
+    };
+    (f16) => {
+        Dtype::F16
+    };
+    (f32) => {
+        Dtype::F32
+    };
+    (f64) => {
+        Dtype::F64
+    };
+    (Complex32) => {
+        Dtype::C32
+    };
+    (Complex64) => {
+        Dtype::C64
+    };
+}
+
 macro_rules! impl_type_num {
     (num, $($t:ident),*) => {
         $(
@@ -416,6 +465,9 @@ macro_rules! repeate_generic {
 }
 
 from_scalar!(bool, i8, u8, i16, u16, i32, u32, i64, u64, f16, bf16, f32, f64, Complex32, Complex64);
+impl_type_num!(
+    num, bool, i8, u8, i16, u16, i32, u32, i64, u64, bf16, f16, f32, f64, Complex32, Complex64
+);
 impl_type_num!(
     vec, bool, i8, u8, i16, u16, i32, u32, i64, u64, f16, f32, f64, Complex32, Complex64
 ); // prettier-ignore